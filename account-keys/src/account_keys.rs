@@ -9,10 +9,17 @@
 //! keys (A,B) directly with senders, users generate and share "subaddresses"
 //! (C_i, D_i) that are derived from the private keys (a,b) and an index i.
 //! We refer to (C_0, D_0)* as the "default subaddress" for account (a,b).
+//!
+//! Like BIP-32 non-hardened derivation, subaddress public keys only depend on
+//! the view private key `a` and the spend public key `B`, so a
+//! [ViewAccountKey] can derive and recognize any subaddress without the spend
+//! private key. The one exception is the fog authority signature of a fog
+//! subaddress, which is made with the subaddress view private key `a * d_i`
+//! and has to be supplied by whoever holds `b`.
 
 #![allow(non_snake_case)]
 
-use crate::domain_separators::SUBADDRESS_DOMAIN_TAG;
+use crate::{domain_separators::SUBADDRESS_DOMAIN_TAG, error::Error};
 use alloc::{
     string::{String, ToString},
     vec::Vec,
@@ -25,7 +32,7 @@ use core::{
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
 use mc_crypto_digestible::Digestible;
 use mc_crypto_hashes::{Blake2b512, Digest};
-use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic, RistrettoSignature};
 use mc_fog_sig_authority::{Signer as AuthoritySigner, Verifier as AuthorityVerifier};
 use mc_util_from_random::FromRandom;
 use prost::Message;
@@ -387,26 +394,32 @@ impl AccountKey {
             RistrettoPublic::from(&subaddress_spend_private)
         };
 
-        let fog_authority_sig = if !self.fog_report_url.is_empty() {
-            let sig = self
-                .subaddress_view_private(index)
-                .sign_authority(&self.fog_authority_spki)
-                .expect("Could not sign authority bytes with view-key private address");
-            let sig_bytes: &[u8] = sig.as_ref();
-            sig_bytes.to_vec()
-        } else {
-            Vec::default()
-        };
-
         PublicAddress {
             view_public_key,
             spend_public_key,
             fog_report_url: self.fog_report_url.clone(),
             fog_report_id: self.fog_report_id.clone(),
-            fog_authority_sig,
+            fog_authority_sig: self.subaddress_fog_authority_sig(index).unwrap_or_default(),
         }
     }
 
+    /// The signature over the fog authority's subjectPublicKeyInfo for the
+    /// i^th subaddress, or None if the account has no fog service.
+    ///
+    /// This can be handed to a [ViewAccountKey] holder, so that they can
+    /// produce the subaddress with fog info.
+    pub fn subaddress_fog_authority_sig(&self, index: u64) -> Option<Vec<u8>> {
+        if self.fog_report_url.is_empty() {
+            return None;
+        }
+        let sig = self
+            .subaddress_view_private(index)
+            .sign_authority(&self.fog_authority_spki)
+            .expect("Could not sign authority bytes with view-key private address");
+        let sig_bytes: &[u8] = sig.as_ref();
+        Some(sig_bytes.to_vec())
+    }
+
     /// The private spend key for the default subaddress.
     pub fn default_subaddress_spend_private(&self) -> RistrettoPrivate {
         self.subaddress_spend_private(DEFAULT_SUBADDRESS_INDEX)
@@ -475,7 +488,11 @@ impl AccountKey {
     }
 }
 
-/// View AccountKey, containing the view private key and the spend public key.
+/// View AccountKey, containing the view private key and the spend public key,
+/// and optionally some fog-related info.
+///
+/// This is enough to derive the public address of any subaddress and to
+/// recognize outputs sent to it, but not to spend them.
 #[derive(Clone, Message, Zeroize)]
 #[zeroize(drop)]
 pub struct ViewAccountKey {
@@ -486,6 +503,18 @@ pub struct ViewAccountKey {
     /// Public key `B` used for generating Public Addresses.
     #[prost(message, required, tag = "2")]
     spend_public_key: RistrettoPublic,
+
+    /// Fog Report server url (if user has Fog service), empty string otherwise
+    #[prost(string, tag = "3")]
+    fog_report_url: String,
+
+    /// Fog Report Key (if user has Fog service), empty otherwise
+    #[prost(string, tag = "4")]
+    fog_report_id: String,
+
+    /// Fog Authority Key Fingerprint (if user has Fog service), empty otherwise
+    #[prost(bytes, tag = "5")]
+    fog_authority_spki: Vec<u8>,
 }
 
 // Note: Hash, Ord is implemented in terms of default_subaddress() because
@@ -521,6 +550,9 @@ impl From<&AccountKey> for ViewAccountKey {
         ViewAccountKey {
             view_private_key: *account_key.view_private_key(),
             spend_public_key: account_key.spend_private_key().into(),
+            fog_report_url: account_key.fog_report_url.clone(),
+            fog_report_id: account_key.fog_report_id.clone(),
+            fog_authority_spki: account_key.fog_authority_spki.clone(),
         }
     }
 }
@@ -536,6 +568,36 @@ impl ViewAccountKey {
         Self {
             view_private_key,
             spend_public_key,
+            fog_report_url: Default::default(),
+            fog_report_id: Default::default(),
+            fog_authority_spki: Default::default(),
+        }
+    }
+
+    /// A user's ViewAccountKey, with a fog service.
+    ///
+    /// # Arguments
+    /// * `view_private_key` - The user's private view key `a`.
+    /// * `spend_public_key` - The user's public spend key `B`.
+    /// * `fog_report_url` - Url of fog report service
+    /// * `fog_report_id` - The id labelling the report to use, from among the
+    ///   several reports which might be served by the fog report server.
+    /// * `fog_authority_spki` - The DER-encoded subjectPublicKeyInfo of the fog
+    ///   authority, which subaddress fog authority signatures are checked
+    ///   against.
+    pub fn new_with_fog(
+        view_private_key: RistrettoPrivate,
+        spend_public_key: RistrettoPublic,
+        fog_report_url: impl ToString,
+        fog_report_id: String,
+        fog_authority_spki: impl AsRef<[u8]>,
+    ) -> Self {
+        Self {
+            view_private_key,
+            spend_public_key,
+            fog_report_url: fog_report_url.to_string(),
+            fog_report_id,
+            fog_authority_spki: fog_authority_spki.as_ref().to_vec(),
         }
     }
 
@@ -549,6 +611,33 @@ impl ViewAccountKey {
         &self.spend_public_key
     }
 
+    /// Access the fog url (if it exists).
+    pub fn fog_report_url(&self) -> Option<&str> {
+        if self.fog_report_url.is_empty() {
+            None
+        } else {
+            Some(&self.fog_report_url)
+        }
+    }
+
+    /// Access the fog authority subject public key info.
+    pub fn fog_authority_spki(&self) -> Option<&[u8]> {
+        if self.fog_authority_spki.is_empty() {
+            None
+        } else {
+            Some(&self.fog_authority_spki)
+        }
+    }
+
+    /// Access the fog report key (if it exists).
+    pub fn fog_report_id(&self) -> Option<&str> {
+        if self.fog_report_id.is_empty() {
+            None
+        } else {
+            Some(&self.fog_report_id)
+        }
+    }
+
     /// Create a view account key with random keys
     pub fn random<T: RngCore + CryptoRng>(rng: &mut T) -> Self {
         Self::new(
@@ -575,7 +664,10 @@ impl ViewAccountKey {
         self.subaddress(GIFT_CODE_SUBADDRESS_INDEX)
     }
 
-    /// Get the account's i^th subaddress.
+    /// Get the account's i^th subaddress, without fog info.
+    ///
+    /// Use [ViewAccountKey::subaddress_with_fog] for a subaddress with fog
+    /// info.
     pub fn subaddress(&self, index: u64) -> PublicAddress {
         let view_public_key = self.subaddress_view_public(index);
         let spend_public_key = self.subaddress_spend_public(index);
@@ -589,6 +681,34 @@ impl ViewAccountKey {
         }
     }
 
+    /// Get the account's i^th subaddress, with fog info.
+    ///
+    /// The fog authority signature can't be made without the spend private
+    /// key, so it must come from
+    /// [AccountKey::subaddress_fog_authority_sig]. It is checked against
+    /// the subaddress and the fog authority before being used.
+    pub fn subaddress_with_fog(
+        &self,
+        index: u64,
+        fog_authority_sig: impl AsRef<[u8]>,
+    ) -> Result<PublicAddress, Error> {
+        if self.fog_report_url.is_empty() {
+            return Err(Error::MissingFogInfo);
+        }
+
+        let mut subaddress = self.subaddress(index);
+        let sig = RistrettoSignature::try_from(fog_authority_sig.as_ref())
+            .map_err(|_| Error::SignatureLength)?;
+        subaddress
+            .verify_authority(&self.fog_authority_spki, &sig)
+            .map_err(|_| Error::InvalidAuthoritySignature)?;
+
+        subaddress.fog_report_url = self.fog_report_url.clone();
+        subaddress.fog_report_id = self.fog_report_id.clone();
+        subaddress.fog_authority_sig = fog_authority_sig.as_ref().to_vec();
+        Ok(subaddress)
+    }
+
     /// The public spend key for the default subaddress.
     pub fn default_subaddress_spend_public(&self) -> RistrettoPublic {
         self.subaddress_spend_public(DEFAULT_SUBADDRESS_INDEX)
@@ -655,6 +775,7 @@ mod account_key_tests {
     use mc_crypto_keys::RistrettoSignature;
     use mc_test_vectors_account_keys::{
         DefaultSubaddrKeysFromAcctPrivKeys, SubaddrKeysFromAcctPrivKeys,
+        SubaddrKeysFromViewAcctKeys,
     };
     use mc_util_test_vector::TestVector;
     use mc_util_test_with_data::test_with_data;
@@ -772,6 +893,22 @@ mod account_key_tests {
         );
     }
 
+    #[test_with_data(SubaddrKeysFromViewAcctKeys::from_jsonl("../test-vectors/vectors"))]
+    fn subaddr_keys_from_view_acct_keys(case: SubaddrKeysFromViewAcctKeys) {
+        let view_private_key = RistrettoPrivate::try_from(&case.view_private_key).unwrap();
+        let spend_public_key = RistrettoPublic::try_from(&case.spend_public_key).unwrap();
+        let view_account_key = ViewAccountKey::new(view_private_key, spend_public_key);
+        let public_address = view_account_key.subaddress(case.subaddress_index);
+        assert_eq!(
+            public_address.view_public_key().to_bytes(),
+            case.subaddress_view_public_key
+        );
+        assert_eq!(
+            public_address.spend_public_key().to_bytes(),
+            case.subaddress_spend_public_key
+        );
+    }
+
     #[test]
     // Subaddress fog authority signature should verify
     fn test_fog_authority_signature() {
@@ -829,4 +966,47 @@ mod account_key_tests {
             view_account_key.subaddress(500)
         );
     }
+
+    #[test]
+    // A View Account Key with fog info should produce the same fog subaddresses
+    // as the Account Key, given the fog authority signatures
+    fn test_view_account_keys_subaddresses_with_fog() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let mut fog_authority_spki = [0u8; 32];
+        rng.fill_bytes(&mut fog_authority_spki);
+        let account_key = AccountKey::new_with_fog(
+            &RistrettoPrivate::from_random(&mut rng),
+            &RistrettoPrivate::from_random(&mut rng),
+            "fog://example.com",
+            String::from("1"),
+            fog_authority_spki,
+        );
+        let view_account_key = ViewAccountKey::from(&account_key);
+
+        let index = rng.next_u64();
+        let sig = account_key.subaddress_fog_authority_sig(index).unwrap();
+        let subaddress = view_account_key.subaddress_with_fog(index, &sig).unwrap();
+        assert_eq!(subaddress, account_key.subaddress(index));
+        verify_signature(&subaddress, &fog_authority_spki);
+
+        // A signature for another subaddress is rejected
+        assert_eq!(
+            view_account_key.subaddress_with_fog(index.wrapping_add(1), &sig),
+            Err(Error::InvalidAuthoritySignature)
+        );
+
+        // Without fog info, there is no fog subaddress
+        let view_account_key = ViewAccountKey::new(
+            *view_account_key.view_private_key(),
+            *view_account_key.spend_public_key(),
+        );
+        assert_eq!(
+            view_account_key.subaddress_with_fog(index, &sig),
+            Err(Error::MissingFogInfo)
+        );
+        assert_eq!(
+            AccountKey::random(&mut rng).subaddress_fog_authority_sig(index),
+            None
+        );
+    }
 }
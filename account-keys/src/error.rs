@@ -21,4 +21,8 @@ pub enum Error {
     AuthoritySubjectLength,
     /// A Slip-0010 key could not be generated
     Slip0010Keygen,
+    /// The account has no fog service
+    MissingFogInfo,
+    /// The fog authority signature does not match the subaddress
+    InvalidAuthoritySignature,
 }
@@ -0,0 +1,119 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from printable::Invoice
+
+use crate::{external, printable, ConversionError};
+use mc_account_keys::PublicAddress;
+use mc_transaction_core::{Amount, Invoice, InvoicePrefix, TokenId};
+
+/// Convert mc_transaction_core::InvoicePrefix --> printable::InvoicePrefix.
+impl From<&InvoicePrefix> for printable::InvoicePrefix {
+    fn from(src: &InvoicePrefix) -> Self {
+        let mut prefix = printable::InvoicePrefix::new();
+        prefix.set_public_address(external::PublicAddress::from(&src.public_address));
+        for amount in src.amounts.iter() {
+            let mut invoice_amount = printable::InvoiceAmount::new();
+            invoice_amount.set_value(amount.value);
+            invoice_amount.set_token_id(*amount.token_id);
+            prefix.mut_amounts().push(invoice_amount);
+        }
+        prefix.set_expiry_block(src.expiry_block);
+        prefix.set_expiry_timestamp(src.expiry_timestamp);
+        prefix.set_payment_request_id(src.payment_request_id);
+        prefix.set_memo(src.memo.clone());
+        prefix.set_callback_url(src.callback_url.clone());
+        prefix
+    }
+}
+
+/// Convert printable::InvoicePrefix --> mc_transaction_core::InvoicePrefix.
+impl TryFrom<&printable::InvoicePrefix> for InvoicePrefix {
+    type Error = ConversionError;
+
+    fn try_from(src: &printable::InvoicePrefix) -> Result<Self, Self::Error> {
+        if !src.has_public_address() {
+            return Err(ConversionError::ObjectMissing);
+        }
+        let public_address = PublicAddress::try_from(src.get_public_address())?;
+        let amounts = src
+            .get_amounts()
+            .iter()
+            .map(|amount| Amount::new(amount.get_value(), TokenId::from(amount.get_token_id())))
+            .collect();
+
+        Ok(Self {
+            public_address,
+            amounts,
+            expiry_block: src.get_expiry_block(),
+            expiry_timestamp: src.get_expiry_timestamp(),
+            payment_request_id: src.get_payment_request_id(),
+            memo: src.get_memo().to_owned(),
+            callback_url: src.get_callback_url().to_owned(),
+        })
+    }
+}
+
+/// Convert mc_transaction_core::Invoice --> printable::Invoice.
+impl From<&Invoice> for printable::Invoice {
+    fn from(src: &Invoice) -> Self {
+        let mut invoice = printable::Invoice::new();
+        invoice.set_prefix((&src.prefix).into());
+        invoice.set_signature(src.signature.clone());
+        invoice
+    }
+}
+
+/// Convert printable::Invoice --> mc_transaction_core::Invoice.
+///
+/// This does not check the signature, see [Invoice::verify_signature].
+impl TryFrom<&printable::Invoice> for Invoice {
+    type Error = ConversionError;
+
+    fn try_from(src: &printable::Invoice) -> Result<Self, Self::Error> {
+        if !src.has_prefix() {
+            return Err(ConversionError::ObjectMissing);
+        }
+        Ok(Self {
+            prefix: InvoicePrefix::try_from(src.get_prefix())?,
+            signature: src.get_signature().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_account_keys::AccountKey;
+    use rand::rngs::StdRng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    // Converting an invoice to protobuf and back gives the same, still valid,
+    // invoice
+    fn test_invoice_roundtrip() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let account_key = AccountKey::random_with_fog(&mut rng);
+        let prefix = InvoicePrefix {
+            public_address: Default::default(),
+            amounts: vec![Amount::new(12345, TokenId::from(0))],
+            expiry_block: 100,
+            expiry_timestamp: 0,
+            payment_request_id: 7,
+            memo: "Coffee".to_owned(),
+            callback_url: String::new(),
+        };
+        let source = Invoice::sign(prefix, &account_key, 3);
+
+        let external = printable::Invoice::from(&source);
+        let recovered = Invoice::try_from(&external).unwrap();
+        assert_eq!(source, recovered);
+        recovered.verify_signature().unwrap();
+
+        // A missing prefix is an error
+        let external = printable::Invoice::new();
+        assert_eq!(
+            Invoice::try_from(&external),
+            Err(ConversionError::ObjectMissing)
+        );
+    }
+}
@@ -46,6 +46,7 @@ mod verification_signature;
 mod watcher;
 
 // printable
+mod invoice;
 mod tx_out_gift_code;
 
 // error
@@ -78,11 +78,15 @@ mod display_tests {
         external,
         printable::{PaymentRequest, PrintableWrapper, TransferPayload},
     };
+    use mc_account_keys::AccountKey;
     use mc_test_vectors_b58_encodings::{
         B58EncodePublicAddressWithFog, B58EncodePublicAddressWithoutFog,
     };
+    use mc_transaction_core::{Amount, Invoice, InvoicePrefix, TokenId};
     use mc_util_test_vector::TestVector;
     use mc_util_test_with_data::test_with_data;
+    use rand::rngs::StdRng;
+    use rand_core::SeedableRng;
 
     fn sample_public_address() -> external::PublicAddress {
         let mut public_address = external::PublicAddress::new();
@@ -194,6 +198,36 @@ mod display_tests {
         assert_eq!(wrapper, decoded);
     }
 
+    #[test]
+    fn test_invoice_roundtrip() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let account_key = AccountKey::random_with_fog(&mut rng);
+        let prefix = InvoicePrefix {
+            public_address: Default::default(),
+            amounts: vec![
+                Amount::new(10, TokenId::from(0)),
+                Amount::new(3, TokenId::from(1)),
+            ],
+            expiry_block: 1000,
+            expiry_timestamp: 0,
+            payment_request_id: 1234,
+            memo: "Please pay me!".to_string(),
+            callback_url: "https://example.com/invoices/1234".to_string(),
+        };
+        let invoice = Invoice::sign(prefix, &account_key, 0);
+
+        let mut wrapper = PrintableWrapper::new();
+        wrapper.set_invoice((&invoice).into());
+        let encoded = wrapper.b58_encode().unwrap();
+        let decoded = PrintableWrapper::b58_decode(encoded).unwrap();
+        assert_eq!(wrapper, decoded);
+
+        // The decoded invoice is still correctly signed
+        let decoded_invoice = Invoice::try_from(decoded.get_invoice()).unwrap();
+        assert_eq!(decoded_invoice, invoice);
+        decoded_invoice.verify_signature().unwrap();
+    }
+
     #[test]
     fn test_transfer_payload_roundtrip() {
         let mut transfer_payload = TransferPayload::new();
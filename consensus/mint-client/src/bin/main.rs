@@ -4,15 +4,18 @@
 
 use clap::Parser;
 use grpcio::{ChannelBuilder, EnvBuilder};
-use mc_common::logger::{create_app_logger, o};
+use mc_common::logger::{create_app_logger, log, o, Logger};
 use mc_consensus_api::{
     consensus_client_grpc::ConsensusClientApiClient, consensus_common_grpc::BlockchainApiClient,
     empty::Empty,
 };
 use mc_consensus_enclave_api::GovernorsSigner;
-use mc_consensus_mint_client::{printers, Commands, Config, TxFile};
+use mc_consensus_mint_client::{
+    printers, Commands, Config, MintingState, MintingStateSourceParams, TxFile,
+};
 use mc_crypto_keys::{Ed25519Pair, Signer};
 use mc_crypto_multisig::MultiSig;
+use mc_ledger_db::{Ledger, LedgerDB};
 use mc_transaction_core::{
     constants::MAX_TOMBSTONE_BLOCKS,
     mint::{MintConfigTx, MintTx},
@@ -244,6 +247,55 @@ fn main() {
             }
         }
 
+        Commands::ListMintConfigs { source, token_id } => {
+            let (minting_state, _ledger_db) = load_minting_state(source, &logger);
+
+            println!(
+                "Minting state at block {} (block version {}):",
+                minting_state.current_block_index, minting_state.block_version
+            );
+            for (state_token_id, state) in &minting_state.tokens {
+                if token_id.map_or(true, |token_id| token_id == *state_token_id) {
+                    printers::print_token_minting_state(*state_token_id, state, 1);
+                }
+            }
+        }
+
+        Commands::CheckMintTx { source, tx_file } => {
+            let tx = MintTx::try_from(tx_file).expect("failed loading MintTx");
+            let (minting_state, ledger_db) = load_minting_state(source, &logger);
+
+            // Consensus nodes do not expose the MintTx nonces they have seen, so we can
+            // only check for replays when we have a local ledger.
+            let nonce_block_index = match &ledger_db {
+                Some(ledger_db) => ledger_db
+                    .check_mint_tx_nonce(&tx.prefix.nonce)
+                    .expect("failed checking MintTx nonce"),
+                None => {
+                    log::warn!(
+                        logger,
+                        "Not checking whether the MintTx nonce was already used, this requires a local ledger"
+                    );
+                    None
+                }
+            };
+
+            printers::print_mint_tx(&tx, 0);
+            match minting_state.predict_mint_tx(&tx, nonce_block_index) {
+                Ok(active_mint_config) => {
+                    println!(
+                        "MintTx would be accepted at block {}, minting using:",
+                        minting_state.current_block_index
+                    );
+                    printers::print_mint_config(&active_mint_config.mint_config, 1);
+                }
+                Err(err) => {
+                    println!("{}", err);
+                    exit(1);
+                }
+            }
+        }
+
         Commands::Dump { tx_file } => match tx_file {
             TxFile::MintConfigTx(tx) => {
                 printers::print_mint_config_tx(&tx, 0);
@@ -308,3 +360,37 @@ fn main() {
         }
     }
 }
+
+/// Load the minting state from either a local ledger or a consensus node. The
+/// ledger is returned as well, when one was used.
+fn load_minting_state(
+    source: MintingStateSourceParams,
+    logger: &Logger,
+) -> (MintingState, Option<LedgerDB>) {
+    match (source.ledger_db, source.node) {
+        (Some(ledger_db_path), _) => {
+            let ledger_db = LedgerDB::open(&ledger_db_path).expect("failed opening ledger db");
+            let minting_state =
+                MintingState::from_ledger(&ledger_db).expect("failed loading minting state");
+            (minting_state, Some(ledger_db))
+        }
+        (None, Some(node)) => {
+            let env = Arc::new(EnvBuilder::new().name_prefix("mint-client-grpc").build());
+            let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&node, logger);
+            let client_api = ConsensusClientApiClient::new(ch.clone());
+            let blockchain_api = BlockchainApiClient::new(ch);
+
+            let node_config = client_api
+                .get_node_config(&Empty::new())
+                .expect("get node config");
+            let last_block_info = blockchain_api
+                .get_last_block_info(&Empty::new())
+                .expect("get last block info");
+            let minting_state =
+                MintingState::from_node_config(&node_config, last_block_info.index + 1)
+                    .expect("failed loading minting state");
+            (minting_state, None)
+        }
+        (None, None) => panic!("either a ledger db or a node must be provided"),
+    }
+}
@@ -3,7 +3,7 @@
 //! Command line configuration for the consensus mint client.
 
 use crate::TxFile;
-use clap::{Args, Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand};
 use hex::FromHex;
 use mc_account_keys::PublicAddress;
 use mc_api::printable::PrintableWrapper;
@@ -225,6 +225,19 @@ impl MintTxParams {
         Ok(MintTx { prefix, signature })
     }
 }
+
+#[derive(Args)]
+#[clap(group(ArgGroup::new("minting_state_source").required(true).args(&["ledger_db", "node"])))]
+pub struct MintingStateSourceParams {
+    /// Path to a local ledger database to read the minting state from.
+    #[clap(long, env = "MC_LEDGER_DB")]
+    pub ledger_db: Option<PathBuf>,
+
+    /// URI of consensus node to read the minting state from.
+    #[clap(long, env = "MC_CONSENSUS_URI")]
+    pub node: Option<ConsensusClientUri>,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Generate and submit a MintConfigTx transaction.
@@ -338,6 +351,28 @@ pub enum Commands {
         output_json: Option<PathBuf>,
     },
 
+    /// List the active mint configurations of each token, together with their
+    /// remaining capacity and the nonces used.
+    ListMintConfigs {
+        #[clap(flatten)]
+        source: MintingStateSourceParams,
+
+        /// Only list the configurations of this token id.
+        #[clap(long, env = "MC_MINTING_TOKEN_ID")]
+        token_id: Option<TokenId>,
+    },
+
+    /// Predict whether a MintTx produced by this tool would be accepted by
+    /// consensus, without submitting it.
+    CheckMintTx {
+        #[clap(flatten)]
+        source: MintingStateSourceParams,
+
+        /// The file containing the MintTx to check.
+        #[clap(long, parse(try_from_str = load_tx_file_from_path), env = "MC_MINTING_TX_FILE")]
+        tx_file: TxFile,
+    },
+
     /// Load a previously-serialized file produced by this tool and print its
     /// contents in a human-friendly way.
     Dump {
@@ -3,7 +3,9 @@
 mod config;
 mod tx_file;
 
+pub mod minting_state;
 pub mod printers;
 
-pub use config::{Commands, Config};
+pub use config::{Commands, Config, MintingStateSourceParams};
+pub use minting_state::{MintingState, MintingStateError};
 pub use tx_file::TxFile;
@@ -0,0 +1,488 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A snapshot of the minting state of the network, as seen by a local ledger or
+//! by a consensus node. This is used for inspecting the active mint
+//! configurations and for predicting whether a MintTx would be accepted before
+//! submitting it.
+
+use displaydoc::Display;
+use mc_api::ConversionError;
+use mc_blockchain_types::{BlockIndex, BlockVersion, BlockVersionError};
+use mc_consensus_api::consensus_config::ConsensusNodeConfig;
+use mc_ledger_db::{ActiveMintConfig, ActiveMintConfigs, Error as LedgerError, Ledger};
+use mc_transaction_core::{
+    mint::{validate_mint_tx, MintTx, MintValidationError},
+    TokenId,
+};
+use std::collections::BTreeMap;
+
+/// A MintTx that was included in the ledger and counted against the currently
+/// active mint configurations of its token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintTxRecord {
+    /// The block the MintTx was included in.
+    pub block_index: BlockIndex,
+
+    /// The index (inside the MintConfigTx) of the configuration that was used
+    /// to mint, if it could be determined.
+    pub config_index: Option<usize>,
+
+    /// The MintTx nonce.
+    pub nonce: Vec<u8>,
+
+    /// The amount minted.
+    pub amount: u64,
+}
+
+/// The minting state of a single token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMintingState {
+    /// The currently active mint configurations.
+    pub active_mint_configs: ActiveMintConfigs,
+
+    /// The block the active MintConfigTx was included in. Only available when
+    /// the state was loaded from a ledger.
+    pub mint_config_tx_block_index: Option<BlockIndex>,
+
+    /// The MintTxs that minted using the active configurations. Only available
+    /// when the state was loaded from a ledger.
+    pub mint_txs: Option<Vec<MintTxRecord>>,
+}
+
+impl TokenMintingState {
+    /// The amount that can still be minted using the configuration at the
+    /// given index, taking the shared total mint limit into account.
+    pub fn remaining_capacity(&self, config_index: usize) -> Option<u64> {
+        let config = self.active_mint_configs.configs.get(config_index)?;
+        let remaining = config
+            .mint_config
+            .mint_limit
+            .saturating_sub(config.total_minted);
+        Some(remaining.min(self.total_remaining_capacity()))
+    }
+
+    /// The amount that can still be minted across all configurations.
+    pub fn total_remaining_capacity(&self) -> u64 {
+        self.active_mint_configs
+            .total_mint_limit()
+            .saturating_sub(self.active_mint_configs.total_minted())
+    }
+}
+
+/// The minting state of all tokens that have active mint configurations.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintingState {
+    /// The index of the next block to be appended, which is the block a MintTx
+    /// submitted now would be validated against.
+    pub current_block_index: BlockIndex,
+
+    /// The block version MintTxs are validated against.
+    pub block_version: BlockVersion,
+
+    /// Token id -> minting state.
+    pub tokens: BTreeMap<TokenId, TokenMintingState>,
+}
+
+impl MintingState {
+    /// Load the minting state from a ledger. In addition to the active mint
+    /// configurations, this looks up the MintTxs that were counted against
+    /// them.
+    pub fn from_ledger(ledger: &impl Ledger) -> Result<Self, MintingStateError> {
+        let num_blocks = ledger.num_blocks()?;
+        let block_version = BlockVersion::try_from(ledger.get_latest_block()?.version)?;
+
+        let mut tokens = BTreeMap::new();
+        for (token_id, active_mint_configs) in ledger.get_active_mint_configs_map()? {
+            let mint_config_tx_block_index = ledger
+                .check_mint_config_tx_nonce(&active_mint_configs.mint_config_tx.prefix.nonce)?;
+            tokens.insert(
+                token_id,
+                TokenMintingState {
+                    active_mint_configs,
+                    mint_config_tx_block_index,
+                    mint_txs: Some(Vec::new()),
+                },
+            );
+        }
+
+        // MintTxs are processed before MintConfigTxs when a block is appended, so
+        // MintTxs included in the same block as the active MintConfigTx were
+        // counted against the previous configuration.
+        let first_block_index = tokens
+            .values()
+            .filter_map(|state| state.mint_config_tx_block_index)
+            .min()
+            .map(|block_index| block_index + 1)
+            .unwrap_or(num_blocks);
+
+        // Replay the MintTxs against a fresh copy of the configurations so that we
+        // can tell which configuration each of them used.
+        let mut replayed_configs = tokens
+            .iter()
+            .map(|(token_id, state)| {
+                (
+                    *token_id,
+                    ActiveMintConfigs::from(&state.active_mint_configs.mint_config_tx),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        for block_index in first_block_index..num_blocks {
+            let block_contents = ledger.get_block_contents(block_index)?;
+            for mint_tx in block_contents.mint_txs {
+                let token_id = TokenId::from(mint_tx.prefix.token_id);
+                let state = match tokens.get_mut(&token_id) {
+                    Some(state) => state,
+                    None => continue,
+                };
+                if state
+                    .mint_config_tx_block_index
+                    .map_or(true, |activated_at| block_index <= activated_at)
+                {
+                    continue;
+                }
+
+                let replayed = replayed_configs
+                    .get_mut(&token_id)
+                    .expect("replayed configs are created for every token");
+                let config_index = replayed
+                    .get_active_mint_config_for_mint_tx(&mint_tx)
+                    .ok()
+                    .and_then(|active_mint_config| {
+                        replayed
+                            .configs
+                            .iter()
+                            .position(|c| c.mint_config == active_mint_config.mint_config)
+                    });
+                if let Some(index) = config_index {
+                    replayed.configs[index].total_minted += mint_tx.prefix.amount;
+                }
+
+                state
+                    .mint_txs
+                    .get_or_insert_with(Vec::new)
+                    .push(MintTxRecord {
+                        block_index,
+                        config_index,
+                        nonce: mint_tx.prefix.nonce,
+                        amount: mint_tx.prefix.amount,
+                    });
+            }
+        }
+
+        Ok(Self {
+            current_block_index: num_blocks,
+            block_version,
+            tokens,
+        })
+    }
+
+    /// Load the minting state from a consensus node configuration. Nodes do not
+    /// expose which MintTxs were counted against the active configurations.
+    ///
+    /// # Arguments
+    /// * `node_config` - The configuration returned by the node.
+    /// * `num_blocks` - The number of blocks in the node's ledger.
+    pub fn from_node_config(
+        node_config: &ConsensusNodeConfig,
+        num_blocks: u64,
+    ) -> Result<Self, MintingStateError> {
+        let block_version = BlockVersion::try_from(node_config.get_block_version())?;
+
+        let tokens = node_config
+            .get_token_config_map()
+            .values()
+            .filter(|token_config| token_config.has_active_mint_configs())
+            .map(|token_config| {
+                let active_mint_configs =
+                    ActiveMintConfigs::try_from(token_config.get_active_mint_configs())?;
+                Ok::<_, MintingStateError>((
+                    TokenId::from(token_config.get_token_id()),
+                    TokenMintingState {
+                        active_mint_configs,
+                        mint_config_tx_block_index: None,
+                        mint_txs: None,
+                    },
+                ))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        Ok(Self {
+            current_block_index: num_blocks,
+            block_version,
+            tokens,
+        })
+    }
+
+    /// Predict whether a MintTx would be accepted, performing the same checks
+    /// consensus performs. Returns the active mint configuration that would be
+    /// used to mint.
+    ///
+    /// # Arguments
+    /// * `mint_tx` - The MintTx to check.
+    /// * `nonce_block_index` - The block the MintTx nonce was previously used
+    ///   in, if any.
+    pub fn predict_mint_tx(
+        &self,
+        mint_tx: &MintTx,
+        nonce_block_index: Option<BlockIndex>,
+    ) -> Result<ActiveMintConfig, MintingStateError> {
+        // Ensure that the nonce has not been seen before.
+        if nonce_block_index.is_some() {
+            return Err(MintValidationError::NonceAlreadyUsed.into());
+        }
+
+        // Try and get an active minting configuration that can validate the signature
+        // of this transaction.
+        let token_id = TokenId::from(mint_tx.prefix.token_id);
+        let state = self
+            .tokens
+            .get(&token_id)
+            .ok_or(MintValidationError::NoMatchingMintConfig)?;
+        let active_mint_config = state
+            .active_mint_configs
+            .get_active_mint_config_for_mint_tx(mint_tx)
+            .map_err(|err| match err {
+                LedgerError::NotFound => MintValidationError::NoMatchingMintConfig.into(),
+                LedgerError::MintLimitExceeded(_, _, _) => {
+                    MintValidationError::AmountExceedsMintLimit.into()
+                }
+                err => MintingStateError::from(err),
+            })?;
+
+        // Perform the actual validation.
+        validate_mint_tx(
+            mint_tx,
+            self.current_block_index,
+            self.block_version,
+            &active_mint_config.mint_config,
+        )?;
+
+        Ok(active_mint_config)
+    }
+}
+
+/// Error type for minting state operations.
+#[derive(Debug, Display)]
+pub enum MintingStateError {
+    /// Ledger error: {0}
+    Ledger(LedgerError),
+
+    /// Conversion error: {0}
+    Conversion(ConversionError),
+
+    /// Block version error: {0}
+    BlockVersion(BlockVersionError),
+
+    /// MintTx would be rejected: {0}
+    MintValidation(MintValidationError),
+}
+
+impl From<LedgerError> for MintingStateError {
+    fn from(err: LedgerError) -> Self {
+        Self::Ledger(err)
+    }
+}
+
+impl From<ConversionError> for MintingStateError {
+    fn from(err: ConversionError) -> Self {
+        Self::Conversion(err)
+    }
+}
+
+impl From<BlockVersionError> for MintingStateError {
+    fn from(err: BlockVersionError) -> Self {
+        Self::BlockVersion(err)
+    }
+}
+
+impl From<MintValidationError> for MintingStateError {
+    fn from(err: MintValidationError) -> Self {
+        Self::MintValidation(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_account_keys::AccountKey;
+    use mc_blockchain_types::{Block, BlockContents};
+    use mc_crypto_keys::Ed25519Pair;
+    use mc_transaction_core_test_utils::{
+        create_ledger, create_mint_config_tx_and_signers, create_mint_tx, create_test_tx_out,
+        initialize_ledger, mint_config_tx_to_validated as to_validated,
+    };
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::get_seeded_rng;
+
+    const BLOCK_VERSION: BlockVersion = BlockVersion::MAX;
+
+    fn append_block(ledger: &mut impl Ledger, block_contents: BlockContents) {
+        let parent_block = ledger.get_latest_block().unwrap();
+        let block = Block::new_with_parent(
+            BLOCK_VERSION,
+            &parent_block,
+            &Default::default(),
+            &block_contents,
+        );
+        ledger.append_block(&block, &block_contents, None).unwrap();
+    }
+
+    #[test]
+    fn from_ledger_tracks_mint_txs_against_active_configs() {
+        let mut rng = get_seeded_rng();
+        let token_id = TokenId::from(1);
+
+        let mut ledger = create_ledger();
+        let sender = AccountKey::random(&mut rng);
+        initialize_ledger(BLOCK_VERSION, &mut ledger, 3, &sender, &mut rng);
+
+        let (mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id, &mut rng);
+        append_block(
+            &mut ledger,
+            BlockContents {
+                validated_mint_config_txs: vec![to_validated(&mint_config_tx)],
+                ..Default::default()
+            },
+        );
+
+        // One MintTx signed by the first configuration and one signed by the second.
+        let mint_tx1 = create_mint_tx(
+            token_id,
+            &[Ed25519Pair::from(signers[0].private_key())],
+            10,
+            &mut rng,
+        );
+        let mint_tx2 = create_mint_tx(
+            token_id,
+            &[Ed25519Pair::from(signers[1].private_key())],
+            20,
+            &mut rng,
+        );
+        append_block(
+            &mut ledger,
+            BlockContents {
+                mint_txs: vec![mint_tx1.clone(), mint_tx2.clone()],
+                outputs: (0..2)
+                    .map(|_| create_test_tx_out(BLOCK_VERSION, &mut rng))
+                    .collect(),
+                ..Default::default()
+            },
+        );
+
+        let minting_state = MintingState::from_ledger(&ledger).unwrap();
+        assert_eq!(minting_state.current_block_index, 5);
+        assert_eq!(minting_state.block_version, BLOCK_VERSION);
+        assert_eq!(minting_state.tokens.len(), 1);
+
+        let token_state = &minting_state.tokens[&token_id];
+        assert_eq!(token_state.mint_config_tx_block_index, Some(3));
+        assert_eq!(
+            token_state.mint_txs,
+            Some(vec![
+                MintTxRecord {
+                    block_index: 4,
+                    config_index: Some(0),
+                    nonce: mint_tx1.prefix.nonce,
+                    amount: 10,
+                },
+                MintTxRecord {
+                    block_index: 4,
+                    config_index: Some(1),
+                    nonce: mint_tx2.prefix.nonce,
+                    amount: 20,
+                },
+            ])
+        );
+
+        let configs = &token_state.active_mint_configs.configs;
+        assert_eq!(configs[0].total_minted, 10);
+        assert_eq!(configs[1].total_minted, 20);
+        assert_eq!(
+            token_state.total_remaining_capacity(),
+            mint_config_tx.prefix.total_mint_limit - 30
+        );
+        assert_eq!(
+            token_state.remaining_capacity(0),
+            Some(configs[0].mint_config.mint_limit - 10)
+        );
+        assert_eq!(token_state.remaining_capacity(3), None);
+    }
+
+    #[test]
+    fn predict_mint_tx_mirrors_consensus_checks() {
+        let mut rng = get_seeded_rng();
+        let token_id = TokenId::from(1);
+
+        let mut ledger = create_ledger();
+        let sender = AccountKey::random(&mut rng);
+        initialize_ledger(BLOCK_VERSION, &mut ledger, 3, &sender, &mut rng);
+
+        let (mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id, &mut rng);
+        append_block(
+            &mut ledger,
+            BlockContents {
+                validated_mint_config_txs: vec![to_validated(&mint_config_tx)],
+                ..Default::default()
+            },
+        );
+
+        let minting_state = MintingState::from_ledger(&ledger).unwrap();
+        let signer = || Ed25519Pair::from(signers[0].private_key());
+
+        // A valid MintTx is accepted by the first configuration.
+        let mint_tx = create_mint_tx(token_id, &[signer()], 1, &mut rng);
+        let active_mint_config = minting_state.predict_mint_tx(&mint_tx, None).unwrap();
+        assert_eq!(
+            active_mint_config.mint_config,
+            mint_config_tx.prefix.configs[0]
+        );
+
+        // A used nonce is rejected.
+        assert!(matches!(
+            minting_state.predict_mint_tx(&mint_tx, Some(4)),
+            Err(MintingStateError::MintValidation(
+                MintValidationError::NonceAlreadyUsed
+            ))
+        ));
+
+        // Exceeding the configuration's mint limit is rejected.
+        let mint_limit = mint_config_tx.prefix.configs[0].mint_limit;
+        let mint_tx = create_mint_tx(token_id, &[signer()], mint_limit + 1, &mut rng);
+        assert!(matches!(
+            minting_state.predict_mint_tx(&mint_tx, None),
+            Err(MintingStateError::MintValidation(
+                MintValidationError::AmountExceedsMintLimit
+            ))
+        ));
+
+        // A token without active configurations is rejected.
+        let mint_tx = create_mint_tx(TokenId::from(2), &[signer()], 1, &mut rng);
+        assert!(matches!(
+            minting_state.predict_mint_tx(&mint_tx, None),
+            Err(MintingStateError::MintValidation(
+                MintValidationError::NoMatchingMintConfig
+            ))
+        ));
+
+        // An unknown signer is rejected.
+        let mint_tx = create_mint_tx(token_id, &[Ed25519Pair::from_random(&mut rng)], 1, &mut rng);
+        assert!(matches!(
+            minting_state.predict_mint_tx(&mint_tx, None),
+            Err(MintingStateError::MintValidation(
+                MintValidationError::NoMatchingMintConfig
+            ))
+        ));
+
+        // A tombstone block in the past is rejected.
+        let mut future_state = minting_state.clone();
+        future_state.current_block_index = 100;
+        let mint_tx = create_mint_tx(token_id, &[signer()], 1, &mut rng);
+        assert!(matches!(
+            future_state.predict_mint_tx(&mint_tx, None),
+            Err(MintingStateError::MintValidation(
+                MintValidationError::TombstoneBlockExceeded
+            ))
+        ));
+    }
+}
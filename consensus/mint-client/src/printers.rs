@@ -2,12 +2,15 @@
 
 //! Utility functions for printing objects in a human-friendly way.
 
+use crate::minting_state::{MintTxRecord, TokenMintingState};
 use mc_account_keys::PublicAddress;
 use mc_api::printable::PrintableWrapper;
 use mc_crypto_keys::{DistinguishedEncoding, Ed25519Public, Ed25519Signature};
 use mc_crypto_multisig::{MultiSig, SignerSet};
-use mc_transaction_core::mint::{
-    MintConfig, MintConfigTx, MintConfigTxPrefix, MintTx, MintTxPrefix,
+use mc_ledger_db::ActiveMintConfig;
+use mc_transaction_core::{
+    mint::{MintConfig, MintConfigTx, MintConfigTxPrefix, MintTx, MintTxPrefix},
+    TokenId,
 };
 use pem::Pem;
 
@@ -81,6 +84,95 @@ pub fn print_mint_tx_prefix(prefix: &MintTxPrefix, indent: usize) {
     println!("{}Tombstone block: {}", indent_str, prefix.tombstone_block);
 }
 
+pub fn print_token_minting_state(token_id: TokenId, state: &TokenMintingState, indent: usize) {
+    let mut indent_str = INDENT_STR.repeat(indent);
+    println!("{}Token id {}:", indent_str, token_id);
+
+    indent_str.push_str(INDENT_STR);
+    let active_mint_configs = &state.active_mint_configs;
+    println!(
+        "{}MintConfigTx nonce: {}",
+        indent_str,
+        hex::encode(&active_mint_configs.mint_config_tx.prefix.nonce)
+    );
+    if let Some(block_index) = state.mint_config_tx_block_index {
+        println!("{}Activated in block: {}", indent_str, block_index);
+    }
+    println!(
+        "{}Total mint limit: {}",
+        indent_str,
+        active_mint_configs.total_mint_limit()
+    );
+    println!(
+        "{}Total minted: {}",
+        indent_str,
+        active_mint_configs.total_minted()
+    );
+    println!(
+        "{}Remaining capacity: {}",
+        indent_str,
+        state.total_remaining_capacity()
+    );
+
+    println!(
+        "{}Active configs ({} config(s)):",
+        indent_str,
+        active_mint_configs.configs.len()
+    );
+    for (config_index, config) in active_mint_configs.configs.iter().enumerate() {
+        print_active_mint_config(
+            config_index,
+            config,
+            state.remaining_capacity(config_index).unwrap_or_default(),
+            indent + 2,
+        );
+    }
+
+    match &state.mint_txs {
+        Some(mint_txs) => {
+            println!("{}MintTxs ({} tx(s)):", indent_str, mint_txs.len());
+            for record in mint_txs {
+                print_mint_tx_record(record, indent + 2);
+            }
+        }
+        None => println!("{}MintTxs: not available from this source", indent_str),
+    }
+}
+
+pub fn print_active_mint_config(
+    config_index: usize,
+    active_mint_config: &ActiveMintConfig,
+    remaining_capacity: u64,
+    indent: usize,
+) {
+    let mut indent_str = INDENT_STR.repeat(indent);
+    println!("{}Config #{}:", indent_str, config_index);
+
+    indent_str.push_str(INDENT_STR);
+    println!(
+        "{}Total minted: {}",
+        indent_str, active_mint_config.total_minted
+    );
+    println!("{}Remaining capacity: {}", indent_str, remaining_capacity);
+    print_mint_config(&active_mint_config.mint_config, indent + 1);
+}
+
+pub fn print_mint_tx_record(record: &MintTxRecord, indent: usize) {
+    let indent_str = INDENT_STR.repeat(indent);
+    let config = record
+        .config_index
+        .map(|config_index| format!("config #{}", config_index))
+        .unwrap_or_else(|| "unknown config".to_string());
+    println!(
+        "{}Block {}: minted {} using {}, nonce {}",
+        indent_str,
+        record.block_index,
+        record.amount,
+        config,
+        hex::encode(&record.nonce)
+    );
+}
+
 pub fn print_signer_set(signer_set: &SignerSet<Ed25519Public>, indent: usize) {
     let mut indent_str = INDENT_STR.repeat(indent);
     println!(
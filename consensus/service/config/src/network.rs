@@ -18,7 +18,8 @@ pub struct NetworkConfig {
     /// List of peers we connect to.
     pub broadcast_peers: Vec<PeerUri>,
 
-    /// List of URLs to use for transaction data.
+    /// List of URLs to use for transaction data (http(s)://, file:// or
+    /// s3://).
     pub tx_source_urls: Vec<String>,
 
     /// Optional list of peers we are aware of.
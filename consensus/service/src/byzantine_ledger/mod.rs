@@ -23,7 +23,7 @@ use mc_consensus_enclave::ConsensusEnclave;
 use mc_consensus_scp::{scp_log::LoggingScpNode, Node, QuorumSet, ScpNode};
 use mc_crypto_keys::Ed25519Pair;
 use mc_ledger_db::Ledger;
-use mc_ledger_sync::{LedgerSyncService, ReqwestTransactionsFetcher};
+use mc_ledger_sync::{LedgerSyncService, ObjectStoreTransactionsFetcher};
 use mc_peers::{
     Broadcast, ConsensusConnection, ConsensusMsg, ConsensusValue, VerifiedConsensusMsg,
 };
@@ -214,7 +214,7 @@ impl ByzantineLedger {
             let ledger_sync_service = LedgerSyncService::new(
                 ledger.clone(),
                 peer_manager.clone(),
-                ReqwestTransactionsFetcher::new(tx_source_urls, logger.clone()).unwrap(), /* Unwrap? */
+                ObjectStoreTransactionsFetcher::new(tx_source_urls, logger.clone()).unwrap(), /* Unwrap? */
                 logger.clone(),
             );
 
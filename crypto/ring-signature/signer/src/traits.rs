@@ -133,6 +133,8 @@ pub enum Error {
     RingSignature(RingSignatureError),
     /// No path to spend key (logic error)
     NoPathToSpendKey,
+    /// Signing request rejected: {0}
+    Rejected(String),
 }
 
 impl From<KeyError> for Error {
@@ -21,8 +21,8 @@ pub mod proptest_fixtures;
 
 pub use amount::{Commitment, CompressedCommitment};
 pub use ring_signature::{
-    generators, CryptoRngCore, CurveScalar, Error, GeneratorCache, KeyImage, PedersenGens,
-    ReducedTxOut, RingMLSAG, Scalar,
+    generators, hash_to_point, CryptoRngCore, CurveScalar, Error, GeneratorCache, KeyImage,
+    PedersenGens, ReducedTxOut, RingMLSAG, RistrettoPoint, Scalar, UnfinishedRingMLSAG,
 };
 
 /// Get the shared secret for a transaction output.
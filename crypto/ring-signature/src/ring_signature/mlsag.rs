@@ -32,7 +32,7 @@ pub trait CryptoRngCore: RngCore + CryptoRng {}
 impl<T> CryptoRngCore for T where T: RngCore + CryptoRng {}
 
 /// A reduced representation of a TxOut, appropriate for making MLSAG
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ReducedTxOut {
     /// The tx_out.public_key field
     pub public_key: CompressedRistrettoPublic,
@@ -162,9 +162,6 @@ impl RingMLSAG {
         // Ring must decompress.
         let decompressed_ring = decompress_ring(ring)?;
 
-        // Challenges `c_0, ... c_{ring_size - 1}`.
-        let mut c: Vec<Scalar> = vec![Scalar::zero(); ring_size];
-
         // Responses `r_{0,0}, r_{0,1}, ... , r_{ring_size-1,0}, r_{ring_size-1,1}`.
         let mut r: Vec<Scalar> = vec![Scalar::zero(); 2 * ring_size];
         for i in 0..ring_size {
@@ -178,47 +175,23 @@ impl RingMLSAG {
         let alpha_0 = Zeroizing::new(Scalar::random(&mut rng));
         let alpha_1 = Zeroizing::new(Scalar::random(&mut rng));
 
-        for n in 0..ring_size {
-            // Iterate around the ring, starting at real_index.
-            let i = (real_index + n) % ring_size;
-            let (P_i, input_commitment) = &decompressed_ring[i];
-
-            let (L0, R0, L1) = if i == real_index {
-                // c_{i+1} = Hn( m | key_image | alpha_0 * G | alpha_0 * Hp(P_i) | alpha_1 * G )
-                //         = Hn( m | key_image |      L0     |         R0        |      L1     )
-                //
-                // where P_i is the i^th onetime public key.
-                // There is no R1 term because no key image is needed for the commitment to
-                // zero.
-
-                let L0 = *alpha_0 * G;
-                let R0 = *alpha_0 * hash_to_point(P_i);
-                let L1 = *alpha_1 * G;
-                (L0, R0, L1)
-            } else {
-                // c_{i+1} = Hn( m | key_image | r_{i,0} * G + c_i * P_i | r_{i,0} * Hp(P_i) +
-                // c_i * I | r_{i,1} * G + c_i * Z_i )         = Hn( m |
-                // key_image |           L0            |               R0            |
-                // L1          )
-                //
-                // where:
-                // * P_i is the i^th onetime public key.
-                // * I is the key image of the real input's private key,
-                // * Z_i is the i^th "commitment to zero" = output_commitment -
-                //   input_commitment.
-                //
-                // There is no R1 term because no key image is needed for the commitment to
-                // zero.
-
-                let L0 = r[2 * i] * G + c[i] * P_i.as_ref();
-                let R0 = r[2 * i] * hash_to_point(P_i) + c[i] * I;
-                let L1 =
-                    r[2 * i + 1] * G + c[i] * (output_commitment.point - input_commitment.point);
-                (L0, R0, L1)
-            };
-
-            c[(i + 1) % ring_size] = challenge(message, &key_image, &L0, &R0, &L1);
-        }
+        // c_{i+1} = Hn( m | key_image | alpha_0 * G | alpha_0 * Hp(P_i) | alpha_1 * G )
+        //         = Hn( m | key_image |      L0     |         R0        |      L1     )
+        //
+        // where i is the real index and P_i is the real input's onetime public key.
+        // There is no R1 term because no key image is needed for the commitment to
+        // zero.
+        let (P_real, _) = &decompressed_ring[real_index];
+        let c = close_ring(
+            message,
+            &decompressed_ring,
+            real_index,
+            &key_image,
+            &I,
+            &output_commitment,
+            (*alpha_0 * G, *alpha_0 * hash_to_point(P_real), *alpha_1 * G),
+            &r,
+        );
 
         // "Close the loop" by computing responses for the real index.
 
@@ -245,6 +218,97 @@ impl RingMLSAG {
         })
     }
 
+    /// Sign a ring, except for the response for the real input's onetime
+    /// private key.
+    ///
+    /// This is for signers where no single party holds the onetime private
+    /// key, e.g. threshold signing. The parties agree on a nonce `alpha_0`,
+    /// known to none of them, and the key image, and supply `alpha_0 * G` and
+    /// `alpha_0 * Hp(P)` where `P` is the real input's onetime public key.
+    /// Everything else is computed here. The parties then each contribute a
+    /// share of the response `alpha_0 - c * x`, where `c` is
+    /// [UnfinishedRingMLSAG::challenge], and the signature is completed with
+    /// [UnfinishedRingMLSAG::finish].
+    ///
+    /// # Arguments
+    /// * `message` - Message to be signed.
+    /// * `ring` - A ring of reduced TxOuts
+    /// * `real_index` - The index in the ring of the real input.
+    /// * `key_image` - The real input's key image.
+    /// * `nonce` - `alpha_0 * G`
+    /// * `key_image_nonce` - `alpha_0 * Hp(P)`
+    /// * `value` - Value of the real input.
+    /// * `blinding` - Blinding of the real input.
+    /// * `output_blinding` - The output amount's blinding factor.
+    /// * `generator` - The pedersen generator to use for this commitment and
+    ///   signature
+    /// * `rng` - Randomness.
+    pub fn sign_unfinished(
+        message: &[u8],
+        ring: &[ReducedTxOut],
+        real_index: usize,
+        key_image: &KeyImage,
+        nonce: &RistrettoPoint,
+        key_image_nonce: &RistrettoPoint,
+        value: u64,
+        blinding: &Scalar,
+        output_blinding: &Scalar,
+        generator: &PedersenGens,
+        mut rng: &mut dyn CryptoRngCore,
+    ) -> Result<UnfinishedRingMLSAG, Error> {
+        let ring_size = ring.len();
+
+        if real_index >= ring_size {
+            return Err(Error::IndexOutOfBounds);
+        }
+
+        let G = B_BLINDING;
+        let I: RistrettoPoint = key_image.point.decompress().ok_or(Error::InvalidKeyImage)?;
+        let output_commitment = Commitment::new(value, *output_blinding, generator);
+        let decompressed_ring = decompress_ring(ring)?;
+
+        let z: Scalar = output_blinding - blinding;
+        let (_, input_commitment) = decompressed_ring[real_index];
+        if output_commitment.point - input_commitment.point != z * G {
+            return Err(Error::ValueNotConserved);
+        }
+
+        let mut r: Vec<Scalar> = vec![Scalar::zero(); 2 * ring_size];
+        for i in 0..ring_size {
+            if i == real_index {
+                continue;
+            }
+            r[2 * i] = Scalar::random(&mut rng);
+            r[2 * i + 1] = Scalar::random(&mut rng);
+        }
+
+        let alpha_1 = Zeroizing::new(Scalar::random(&mut rng));
+        let L1 = *alpha_1 * G;
+        let c = close_ring(
+            message,
+            &decompressed_ring,
+            real_index,
+            key_image,
+            &I,
+            &output_commitment,
+            (*nonce, *key_image_nonce, L1),
+            &r,
+        );
+
+        // The commitment to zero is signed here, since its secret is not split.
+        r[2 * real_index + 1] = *alpha_1 - c[real_index] * z;
+
+        Ok(UnfinishedRingMLSAG {
+            signature: RingMLSAG {
+                c_zero: CurveScalar::from(c[0]),
+                responses: r.into_iter().map(CurveScalar::from).collect(),
+                key_image: *key_image,
+            },
+            real_index,
+            commitment_nonce: CompressedRistrettoPublic::from(L1),
+        })
+    }
+
     /// Verify MLSAG signature.
     ///
     /// # Arguments
@@ -335,6 +399,148 @@ impl RingMLSAG {
     }
 }
 
+/// An MLSAG which is complete except for the response for the real input's
+/// onetime private key. See [RingMLSAG::sign_unfinished].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UnfinishedRingMLSAG {
+    /// The signature, with a zero response for the real input's onetime
+    /// private key.
+    pub signature: RingMLSAG,
+
+    /// The index in the ring of the real input.
+    pub real_index: usize,
+
+    /// `alpha_1 * G`, the nonce for the real input's commitment to zero.
+    pub commitment_nonce: CompressedRistrettoPublic,
+}
+
+impl UnfinishedRingMLSAG {
+    /// Recompute the challenge `c` for the real input's onetime private key
+    /// from the message, ring and nonces, and check that it is consistent
+    /// with the rest of the signature.
+    ///
+    /// Parties contributing to the response should call this with their own
+    /// view of the message, ring and nonces, rather than trusting whoever
+    /// assembled the signature.
+    ///
+    /// # Arguments
+    /// * `message` - Message to be signed.
+    /// * `ring` - A ring of reduced TxOuts
+    /// * `output_commitment` - Output amount commitment.
+    /// * `nonce` - `alpha_0 * G`
+    /// * `key_image_nonce` - `alpha_0 * Hp(P)`
+    pub fn challenge(
+        &self,
+        message: &[u8],
+        ring: &[ReducedTxOut],
+        output_commitment: &CompressedCommitment,
+        nonce: &RistrettoPoint,
+        key_image_nonce: &RistrettoPoint,
+    ) -> Result<Scalar, Error> {
+        let ring_size = ring.len();
+        if self.real_index >= ring_size {
+            return Err(Error::IndexOutOfBounds);
+        }
+        if self.signature.responses.len() != 2 * ring_size {
+            return Err(Error::LengthMismatch(
+                2 * ring_size,
+                self.signature.responses.len(),
+            ));
+        }
+
+        let I: RistrettoPoint = self
+            .signature
+            .key_image
+            .point
+            .decompress()
+            .ok_or(Error::InvalidKeyImage)?;
+        let output_commitment: Commitment = Commitment::try_from(output_commitment)?;
+        let decompressed_ring = decompress_ring(ring)?;
+        let L1 = RistrettoPublic::try_from(&self.commitment_nonce)
+            .map_err(|_e| Error::InvalidCurvePoint)?;
+        let r: Vec<Scalar> = self
+            .signature
+            .responses
+            .iter()
+            .map(|response| response.scalar)
+            .collect();
+
+        let c = close_ring(
+            message,
+            &decompressed_ring,
+            self.real_index,
+            &self.signature.key_image,
+            &I,
+            &output_commitment,
+            (*nonce, *key_image_nonce, *L1.as_ref()),
+            &r,
+        );
+
+        if c[0] != self.signature.c_zero.scalar {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(c[self.real_index])
+    }
+
+    /// Complete the signature with the response `alpha_0 - c * x` for the real
+    /// input's onetime private key `x`.
+    pub fn finish(mut self, response: Scalar) -> RingMLSAG {
+        self.signature.responses[2 * self.real_index] = CurveScalar::from(response);
+        self.signature
+    }
+}
+
+// Compute the challenges `c_0, ... c_{ring_size - 1}` by iterating around the
+// ring, starting at the real input with the given nonces `(L0, R0, L1)`, and
+// using the responses `r` for every other ring member.
+fn close_ring(
+    message: &[u8],
+    decompressed_ring: &[(RistrettoPublic, Commitment)],
+    real_index: usize,
+    key_image: &KeyImage,
+    I: &RistrettoPoint,
+    output_commitment: &Commitment,
+    real_nonces: (RistrettoPoint, RistrettoPoint, RistrettoPoint),
+    r: &[Scalar],
+) -> Vec<Scalar> {
+    let G = B_BLINDING;
+    let ring_size = decompressed_ring.len();
+    let mut c: Vec<Scalar> = vec![Scalar::zero(); ring_size];
+
+    for n in 0..ring_size {
+        // Iterate around the ring, starting at real_index.
+        let i = (real_index + n) % ring_size;
+        let (P_i, input_commitment) = &decompressed_ring[i];
+
+        let (L0, R0, L1) = if i == real_index {
+            real_nonces
+        } else {
+            // c_{i+1} = Hn( m | key_image | r_{i,0} * G + c_i * P_i | r_{i,0} * Hp(P_i) +
+            // c_i * I | r_{i,1} * G + c_i * Z_i )         = Hn( m |
+            // key_image |           L0            |               R0            |
+            // L1          )
+            //
+            // where:
+            // * P_i is the i^th onetime public key.
+            // * I is the key image of the real input's private key,
+            // * Z_i is the i^th "commitment to zero" = output_commitment -
+            //   input_commitment.
+            //
+            // There is no R1 term because no key image is needed for the commitment to
+            // zero.
+
+            let L0 = r[2 * i] * G + c[i] * P_i.as_ref();
+            let R0 = r[2 * i] * hash_to_point(P_i) + c[i] * I;
+            let L1 = r[2 * i + 1] * G + c[i] * (output_commitment.point - input_commitment.point);
+            (L0, R0, L1)
+        };
+
+        c[(i + 1) % ring_size] = challenge(message, key_image, &L0, &R0, &L1);
+    }
+
+    c
+}
+
 // Compute the "challenge" H( message | key_image | L0 | R0 | L1 ).
 fn challenge(
     message: &[u8],
@@ -868,5 +1074,56 @@ mod mlsag_tests {
             assert_eq!(signature, recovered_signature);
         }
 
+        #[test]
+        // A signature completed externally with `alpha_0 - c * x` should verify.
+        fn test_sign_unfinished_then_finish_verifies(
+            num_mixins in 1..17usize,
+            seed in any::<[u8; 32]>(),
+        ) {
+            let mut rng: RngType = SeedableRng::from_seed(seed);
+            let pseudo_output_blinding = Scalar::random(&mut rng);
+            let params = RingMLSAGParameters::random(num_mixins, pseudo_output_blinding, &mut rng);
+
+            let key_image = KeyImage::from(&params.onetime_private_key);
+            let onetime_public_key = RistrettoPublic::from(&params.onetime_private_key);
+            let alpha_0 = Scalar::random(&mut rng);
+            let nonce = alpha_0 * B_BLINDING;
+            let key_image_nonce = alpha_0 * hash_to_point(&onetime_public_key);
+
+            let unfinished = RingMLSAG::sign_unfinished(
+                &params.message,
+                &params.ring,
+                params.real_index,
+                &key_image,
+                &nonce,
+                &key_image_nonce,
+                params.value,
+                &params.blinding,
+                &params.pseudo_output_blinding,
+                &params.generator,
+                &mut rng,
+            )
+            .unwrap();
+
+            let output_commitment = CompressedCommitment::new(params.value, params.pseudo_output_blinding, &params.generator);
+
+            // The challenge can't be recomputed for a different message.
+            let mut wrong_message = params.message;
+            wrong_message[0] ^= 1;
+            assert_eq!(
+                unfinished.challenge(&wrong_message, &params.ring, &output_commitment, &nonce, &key_image_nonce),
+                Err(Error::InvalidSignature)
+            );
+
+            let c = unfinished
+                .challenge(&params.message, &params.ring, &output_commitment, &nonce, &key_image_nonce)
+                .unwrap();
+            let signature = unfinished.finish(alpha_0 - c * params.onetime_private_key.as_ref());
+
+            assert!(signature
+                .verify(&params.message, &params.ring, &output_commitment)
+                .is_ok());
+        }
+
     } // end proptest!
 }
@@ -17,7 +17,7 @@ pub use self::{
     error::Error,
     generator_cache::GeneratorCache,
     key_image::KeyImage,
-    mlsag::{CryptoRngCore, ReducedTxOut, RingMLSAG},
+    mlsag::{CryptoRngCore, ReducedTxOut, RingMLSAG, UnfinishedRingMLSAG},
 };
 
 use crate::domain_separators::HASH_TO_POINT_DOMAIN_TAG;
@@ -0,0 +1,436 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Distributed generation of a shared spend private key.
+//!
+//! This is Pedersen's DKG with Feldman commitments, where each party also
+//! proves knowledge of its constant term so that no party can choose its
+//! contribution as a function of the others'.
+//!
+//! 1. The parties agree on a session id, which must be unique to this run of
+//!    key generation, so that proofs from another run cannot be replayed.
+//!    Each party `i` calls [DkgParticipant::new], which picks a random
+//!    polynomial `f_i` of degree `threshold - 1`, and broadcasts the resulting
+//!    [DkgCommitment] to every other party.
+//! 2. Each party sends the [DkgShare]s from [DkgParticipant::shares] to their
+//!    recipients, over private authenticated channels.
+//! 3. Once a party has every commitment and every share addressed to it, it
+//!    calls [DkgParticipant::finish] to check them and obtain its [KeyShare].
+//!
+//! Party `j`'s share of the spend private key is `b_j = sum_i f_i(j)`, and the
+//! spend private key `b = sum_i f_i(0)` is never computed.
+
+use crate::{
+    key_share::{decompress, public_point},
+    Error, KeyShare, ThresholdAccount,
+};
+use mc_crypto_hashes::{Blake2b512, Digest};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+use mc_crypto_ring_signature::{CurveScalar, RistrettoPoint, Scalar};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zeroize::Zeroizing;
+
+/// Domain separator for the proof of knowledge of a party's constant term
+const DKG_PROOF_DOMAIN_TAG: &str = "mc_threshold_mlsag_dkg_proof";
+
+/// A party's broadcast commitment to its secret polynomial
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DkgCommitment {
+    /// The party's index
+    pub index: u32,
+
+    /// `a_k * G` for each coefficient `a_k` of the party's polynomial
+    pub coefficient_commitments: Vec<CompressedRistrettoPublic>,
+
+    /// The nonce `k * G` of a Schnorr proof of knowledge of `a_0`
+    pub proof_nonce: CompressedRistrettoPublic,
+
+    /// The response `k + c * a_0` of the proof of knowledge
+    pub proof_response: CurveScalar,
+}
+
+/// A party's secret share for another party. It must only be sent to its
+/// recipient.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DkgShare {
+    /// The sending party
+    pub from: u32,
+
+    /// The receiving party
+    pub to: u32,
+
+    /// `f_from(to)`
+    pub share: CurveScalar,
+}
+
+/// A party's state during distributed key generation
+pub struct DkgParticipant {
+    session_id: Vec<u8>,
+    index: u32,
+    threshold: u32,
+    num_parties: u32,
+    coefficients: Vec<Zeroizing<Scalar>>,
+    commitment: DkgCommitment,
+}
+
+impl DkgParticipant {
+    /// Start key generation as party `index` of `num_parties`, any
+    /// `threshold` of which will be able to sign. Party indices start at 1.
+    /// Every party must use the same `session_id`, unique to this run of key
+    /// generation.
+    ///
+    /// Returns the party's state, and the commitment to broadcast.
+    pub fn new<R: CryptoRng + RngCore>(
+        session_id: &[u8],
+        index: u32,
+        threshold: u32,
+        num_parties: u32,
+        rng: &mut R,
+    ) -> Result<(Self, DkgCommitment), Error> {
+        if threshold == 0 || threshold > num_parties {
+            return Err(Error::InvalidParameters(format!(
+                "threshold {} for {} parties",
+                threshold, num_parties
+            )));
+        }
+        if index == 0 || index > num_parties {
+            return Err(Error::InvalidParameters(format!(
+                "party index {} for {} parties",
+                index, num_parties
+            )));
+        }
+
+        let coefficients: Vec<Zeroizing<Scalar>> = (0..threshold)
+            .map(|_| Zeroizing::new(Scalar::random(rng)))
+            .collect();
+        let coefficient_commitments: Vec<CompressedRistrettoPublic> = coefficients
+            .iter()
+            .map(|coefficient| public_point(coefficient).into())
+            .collect();
+
+        let k = Zeroizing::new(Scalar::random(rng));
+        let proof_nonce = CompressedRistrettoPublic::from(public_point(&k));
+        let c = proof_challenge(
+            session_id,
+            index,
+            threshold,
+            num_parties,
+            &coefficient_commitments[0],
+            &proof_nonce,
+        );
+        let proof_response = CurveScalar::from(*k + c * *coefficients[0]);
+
+        let commitment = DkgCommitment {
+            index,
+            coefficient_commitments,
+            proof_nonce,
+            proof_response,
+        };
+        Ok((
+            Self {
+                session_id: session_id.to_vec(),
+                index,
+                threshold,
+                num_parties,
+                coefficients,
+                commitment: commitment.clone(),
+            },
+            commitment,
+        ))
+    }
+
+    /// This party's index
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The secret shares to send to each of the other parties
+    pub fn shares(&self) -> Vec<DkgShare> {
+        (1..=self.num_parties)
+            .filter(|to| *to != self.index)
+            .map(|to| DkgShare {
+                from: self.index,
+                to,
+                share: CurveScalar::from(*self.evaluate(to)),
+            })
+            .collect()
+    }
+
+    /// Check every party's commitment and the shares sent to this party, and
+    /// compute this party's key share.
+    ///
+    /// Arguments:
+    /// * `commitments`: The commitments broadcast by every party, including
+    ///   this one
+    /// * `shares`: The shares sent to this party by every other party
+    /// * `view_private_key`: The account's view private key. This is not split,
+    ///   and should be generated by one party and sent to the others privately.
+    pub fn finish(
+        self,
+        commitments: &[DkgCommitment],
+        shares: &[DkgShare],
+        view_private_key: &RistrettoPrivate,
+    ) -> Result<KeyShare, Error> {
+        // Exactly one valid commitment from each party, and ours unchanged
+        let mut commitment_points: BTreeMap<u32, Vec<RistrettoPoint>> = BTreeMap::new();
+        for commitment in commitments {
+            if commitment.index == self.index && *commitment != self.commitment {
+                return Err(Error::InvalidCommitment(commitment.index));
+            }
+            let points = self.check_commitment(commitment)?;
+            if commitment_points.insert(commitment.index, points).is_some() {
+                return Err(Error::UnexpectedMessage(commitment.index));
+            }
+        }
+        if let Some(missing) = (1..=self.num_parties).find(|i| !commitment_points.contains_key(i)) {
+            return Err(Error::MissingMessage(missing));
+        }
+
+        // Exactly one share from each other party, consistent with its
+        // commitment
+        let mut share_total = Zeroizing::new(*self.evaluate(self.index));
+        let mut received = vec![self.index];
+        for share in shares {
+            if share.to != self.index || received.contains(&share.from) {
+                return Err(Error::UnexpectedMessage(share.from));
+            }
+            let points = commitment_points
+                .get(&share.from)
+                .ok_or(Error::UnexpectedMessage(share.from))?;
+            if public_point(&share.share.scalar) != evaluate_commitments(points, self.index) {
+                return Err(Error::InvalidShare(share.from));
+            }
+            *share_total += share.share.scalar;
+            received.push(share.from);
+        }
+        if let Some(missing) = (1..=self.num_parties).find(|i| !received.contains(i)) {
+            return Err(Error::MissingMessage(missing));
+        }
+
+        let spend_public_key: RistrettoPoint =
+            commitment_points.values().map(|points| points[0]).sum();
+        let verification_shares = (1..=self.num_parties)
+            .map(|j| {
+                let share: RistrettoPoint = commitment_points
+                    .values()
+                    .map(|points| evaluate_commitments(points, j))
+                    .sum();
+                (j, RistrettoPublic::from(share))
+            })
+            .collect();
+
+        Ok(KeyShare {
+            index: self.index,
+            spend_private_share: RistrettoPrivate::from(*share_total),
+            account: ThresholdAccount {
+                threshold: self.threshold,
+                spend_public_key: RistrettoPublic::from(spend_public_key),
+                verification_shares,
+                view_private_key: *view_private_key,
+            },
+        })
+    }
+
+    // Evaluate this party's polynomial at x
+    fn evaluate(&self, x: u32) -> Zeroizing<Scalar> {
+        let x = Scalar::from(x as u64);
+        let mut result = Zeroizing::new(Scalar::zero());
+        for coefficient in self.coefficients.iter().rev() {
+            *result = *result * x + **coefficient;
+        }
+        result
+    }
+
+    // Check a commitment's length and proof of knowledge, and decompress it
+    fn check_commitment(&self, commitment: &DkgCommitment) -> Result<Vec<RistrettoPoint>, Error> {
+        let invalid = || Error::InvalidCommitment(commitment.index);
+        if commitment.index == 0 || commitment.index > self.num_parties {
+            return Err(Error::UnexpectedMessage(commitment.index));
+        }
+        if commitment.coefficient_commitments.len() != self.threshold as usize {
+            return Err(invalid());
+        }
+        let points = commitment
+            .coefficient_commitments
+            .iter()
+            .map(|point| decompress(point).map_err(|_| invalid()))
+            .collect::<Result<Vec<RistrettoPoint>, Error>>()?;
+        let proof_nonce = decompress(&commitment.proof_nonce).map_err(|_| invalid())?;
+
+        // s * G == R + c * A_0
+        let c = proof_challenge(
+            &self.session_id,
+            commitment.index,
+            self.threshold,
+            self.num_parties,
+            &commitment.coefficient_commitments[0],
+            &commitment.proof_nonce,
+        );
+        if public_point(&commitment.proof_response.scalar) != proof_nonce + c * points[0] {
+            return Err(invalid());
+        }
+        Ok(points)
+    }
+}
+
+// sum_k A_k * x^k
+fn evaluate_commitments(points: &[RistrettoPoint], x: u32) -> RistrettoPoint {
+    let x = Scalar::from(x as u64);
+    points
+        .iter()
+        .rev()
+        .fold(RistrettoPoint::default(), |result, point| {
+            result * x + point
+        })
+}
+
+// The challenge of a party's proof of knowledge of its constant term
+fn proof_challenge(
+    session_id: &[u8],
+    index: u32,
+    threshold: u32,
+    num_parties: u32,
+    constant_commitment: &CompressedRistrettoPublic,
+    proof_nonce: &CompressedRistrettoPublic,
+) -> Scalar {
+    let mut hasher = Blake2b512::new();
+    hasher.update(&DKG_PROOF_DOMAIN_TAG);
+    hasher.update((session_id.len() as u64).to_le_bytes());
+    hasher.update(session_id);
+    hasher.update(index.to_le_bytes());
+    hasher.update(threshold.to_le_bytes());
+    hasher.update(num_parties.to_le_bytes());
+    hasher.update(constant_commitment.as_bytes());
+    hasher.update(proof_nonce.as_bytes());
+    Scalar::from_hash(hasher)
+}
+
+/// Run key generation for all parties in one process, e.g. for tests or for
+/// an offline ceremony where the shares are then exported to their holders.
+pub fn generate_key_shares<R: CryptoRng + RngCore>(
+    session_id: &[u8],
+    threshold: u32,
+    num_parties: u32,
+    view_private_key: &RistrettoPrivate,
+    rng: &mut R,
+) -> Result<Vec<KeyShare>, Error> {
+    let mut participants = Vec::new();
+    let mut commitments = Vec::new();
+    for index in 1..=num_parties {
+        let (participant, commitment) =
+            DkgParticipant::new(session_id, index, threshold, num_parties, rng)?;
+        participants.push(participant);
+        commitments.push(commitment);
+    }
+    let shares: Vec<DkgShare> = participants
+        .iter()
+        .flat_map(|participant| participant.shares())
+        .collect();
+    participants
+        .into_iter()
+        .map(|participant| {
+            let index = participant.index();
+            let shares: Vec<DkgShare> = shares
+                .iter()
+                .filter(|share| share.to == index)
+                .cloned()
+                .collect();
+            participant.finish(&commitments, &shares, view_private_key)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lagrange_coefficient;
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::get_seeded_rng;
+
+    const SESSION_ID: &[u8] = b"dkg test session";
+
+    #[test]
+    fn shares_reconstruct_the_spend_key() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+
+        let spend_public_key = shares[0].account.spend_public_key;
+        for share in &shares {
+            assert_eq!(share.account.spend_public_key, spend_public_key);
+            assert_eq!(
+                share.account.verification_shares[&share.index],
+                RistrettoPublic::from(&share.spend_private_share)
+            );
+        }
+
+        // Any two shares reconstruct the same spend private key
+        for signers in [[1u32, 2], [1, 3], [2, 3]] {
+            let spend_private_key: Scalar = signers
+                .iter()
+                .map(|index| {
+                    let share: &Scalar = shares[*index as usize - 1].spend_private_share.as_ref();
+                    lagrange_coefficient(*index, &signers) * share
+                })
+                .sum();
+            assert_eq!(public_point(&spend_private_key), *spend_public_key.as_ref());
+        }
+    }
+
+    #[test]
+    fn bad_shares_are_detected() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let (first, first_commitment) = DkgParticipant::new(SESSION_ID, 1, 2, 2, &mut rng).unwrap();
+        let (second, second_commitment) =
+            DkgParticipant::new(SESSION_ID, 2, 2, 2, &mut rng).unwrap();
+        let commitments = [first_commitment, second_commitment];
+
+        let mut shares = second.shares();
+        shares[0].share = CurveScalar::from(shares[0].share.scalar + Scalar::one());
+        assert_eq!(
+            first
+                .finish(&commitments, &shares, &view_private_key)
+                .unwrap_err(),
+            Error::InvalidShare(2)
+        );
+    }
+
+    #[test]
+    fn bad_proofs_are_detected() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let (first, first_commitment) = DkgParticipant::new(SESSION_ID, 1, 2, 2, &mut rng).unwrap();
+        let (second, mut second_commitment) =
+            DkgParticipant::new(SESSION_ID, 2, 2, 2, &mut rng).unwrap();
+
+        // Party 2 replaces its constant term, without knowing its discrete log
+        second_commitment.coefficient_commitments[0] =
+            CompressedRistrettoPublic::from_random(&mut rng);
+        let commitments = [first_commitment, second_commitment];
+        assert_eq!(
+            first
+                .finish(&commitments, &second.shares(), &view_private_key)
+                .unwrap_err(),
+            Error::InvalidCommitment(2)
+        );
+    }
+
+    #[test]
+    fn proofs_from_another_session_are_rejected() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let (first, first_commitment) = DkgParticipant::new(SESSION_ID, 1, 2, 2, &mut rng).unwrap();
+        let (second, second_commitment) =
+            DkgParticipant::new(b"another session", 2, 2, 2, &mut rng).unwrap();
+
+        let commitments = [first_commitment, second_commitment];
+        assert_eq!(
+            first
+                .finish(&commitments, &second.shares(), &view_private_key)
+                .unwrap_err(),
+            Error::InvalidCommitment(2)
+        );
+    }
+}
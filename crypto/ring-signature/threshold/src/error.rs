@@ -0,0 +1,55 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Errors which can occur during threshold key generation and signing
+
+use displaydoc::Display;
+use mc_crypto_keys::KeyError;
+use mc_crypto_ring_signature::Error as RingSignatureError;
+use serde::{Deserialize, Serialize};
+
+/// An error in threshold key generation or signing
+#[derive(Clone, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum Error {
+    /// Invalid parameters: {0}
+    InvalidParameters(String),
+    /// Party {0} sent an invalid key generation commitment
+    InvalidCommitment(u32),
+    /// Party {0} sent a key share which does not match its commitment
+    InvalidShare(u32),
+    /// Missing message from party {0}
+    MissingMessage(u32),
+    /// Unexpected or duplicate message from party {0}
+    UnexpectedMessage(u32),
+    /// Party {0} revealed a nonce which does not match its commitment
+    NonceMismatch(u32),
+    /// Party {0} sent an invalid partial response
+    InvalidResponse(u32),
+    /// Unknown signing session
+    UnknownSession,
+    /// The signing session already exists
+    DuplicateSession,
+    /// This party is not one of the signers
+    NotASigner,
+    /// The real input is not owned by this account
+    InputNotOwned,
+    /// The key image does not match the parties' partial key images
+    KeyImageMismatch,
+    /// Invalid curve point
+    InvalidPoint,
+    /// Keys: {0}
+    Keys(KeyError),
+    /// Ring signature: {0}
+    RingSignature(RingSignatureError),
+}
+
+impl From<KeyError> for Error {
+    fn from(src: KeyError) -> Self {
+        Self::Keys(src)
+    }
+}
+
+impl From<RingSignatureError> for Error {
+    fn from(src: RingSignatureError) -> Self {
+        Self::RingSignature(src)
+    }
+}
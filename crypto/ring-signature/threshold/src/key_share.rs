@@ -0,0 +1,148 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Key shares produced by distributed key generation
+
+use crate::Error;
+use mc_account_keys::{AccountKey, PublicAddress, ViewAccountKey};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+use mc_crypto_ring_signature::{onetime_keys::recover_onetime_private_key, RistrettoPoint, Scalar};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The public parts of a threshold account, plus its view private key, which
+/// every party holds.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ThresholdAccount {
+    /// The number of parties needed to sign
+    pub threshold: u32,
+
+    /// The account's spend public key `B`
+    pub spend_public_key: RistrettoPublic,
+
+    /// Each party's public verification share `b_j * G`, by party index
+    pub verification_shares: BTreeMap<u32, RistrettoPublic>,
+
+    /// The account's view private key `a`
+    pub view_private_key: RistrettoPrivate,
+}
+
+impl ThresholdAccount {
+    /// The view account key, which can derive the account's subaddresses and
+    /// recognize its outputs
+    pub fn view_account_key(&self) -> ViewAccountKey {
+        ViewAccountKey::new(self.view_private_key, self.spend_public_key)
+    }
+
+    /// The public address of a subaddress of the account
+    pub fn subaddress(&self, index: u64) -> PublicAddress {
+        self.view_account_key().subaddress(index)
+    }
+
+    /// The public part `Hs(a * R) + Hs(a || i)` of the onetime private key
+    /// `x = Hs(a * R) + Hs(a || i) + b` of an output sent to subaddress `i`,
+    /// with tx public key `R`.
+    ///
+    /// Adding it to a Shamir share of `b` gives a Shamir share of `x`.
+    pub(crate) fn onetime_key_offset(
+        &self,
+        tx_public_key: &RistrettoPublic,
+        subaddress_index: u64,
+    ) -> Scalar {
+        // With a zero spend private key, the subaddress spend private key is
+        // just `Hs(a || i)`.
+        let offset_account = AccountKey::new(
+            &RistrettoPrivate::from(Scalar::zero()),
+            &self.view_private_key,
+        );
+        let onetime_offset = recover_onetime_private_key(
+            tx_public_key,
+            &self.view_private_key,
+            &offset_account.subaddress_spend_private(subaddress_index),
+        );
+        let offset: &Scalar = onetime_offset.as_ref();
+        *offset
+    }
+
+    /// A party's public share `x_j * G` of an output's onetime private key
+    pub(crate) fn onetime_public_share(
+        &self,
+        index: u32,
+        onetime_key_offset: &Scalar,
+    ) -> Result<RistrettoPoint, Error> {
+        let verification_share = self
+            .verification_shares
+            .get(&index)
+            .ok_or(Error::UnexpectedMessage(index))?;
+        let share_point: &RistrettoPoint = verification_share.as_ref();
+        Ok(public_point(onetime_key_offset) + share_point)
+    }
+
+    /// Check that a set of signers is large enough and only contains known
+    /// parties, each once
+    pub(crate) fn check_signers(&self, signers: &[u32]) -> Result<(), Error> {
+        if signers.len() < self.threshold as usize {
+            return Err(Error::InvalidParameters(format!(
+                "{} signers, but the threshold is {}",
+                signers.len(),
+                self.threshold
+            )));
+        }
+        for (position, index) in signers.iter().enumerate() {
+            if !self.verification_shares.contains_key(index) || signers[..position].contains(index)
+            {
+                return Err(Error::UnexpectedMessage(*index));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One party's share of a threshold account
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeyShare {
+    /// This party's index, starting at 1
+    pub index: u32,
+
+    /// This party's Shamir share `b_j` of the spend private key
+    pub spend_private_share: RistrettoPrivate,
+
+    /// The account this is a share of
+    pub account: ThresholdAccount,
+}
+
+impl KeyShare {
+    /// This party's share `x_j` of the onetime private key of an output, given
+    /// the output's onetime key offset
+    pub(crate) fn onetime_private_share(&self, onetime_key_offset: &Scalar) -> Scalar {
+        let b_j: &Scalar = self.spend_private_share.as_ref();
+        onetime_key_offset + b_j
+    }
+}
+
+/// The Lagrange coefficient at zero for `index`, among the given signers.
+///
+/// The secret shared by a set of Shamir shares `s_j` is the sum of
+/// `lagrange_coefficient(j, signers) * s_j` over the signers.
+pub fn lagrange_coefficient(index: u32, signers: &[u32]) -> Scalar {
+    let x_j = Scalar::from(index as u64);
+    let mut numerator = Scalar::one();
+    let mut denominator = Scalar::one();
+    for other in signers.iter().filter(|other| **other != index) {
+        let x_m = Scalar::from(*other as u64);
+        numerator *= x_m;
+        denominator *= x_m - x_j;
+    }
+    numerator * denominator.invert()
+}
+
+// x * G
+pub(crate) fn public_point(x: &Scalar) -> RistrettoPoint {
+    *RistrettoPublic::from(&RistrettoPrivate::from(*x)).as_ref()
+}
+
+// Decompress a point sent by another party
+pub(crate) fn decompress(point: &CompressedRistrettoPublic) -> Result<RistrettoPoint, Error> {
+    RistrettoPublic::try_from(point)
+        .map(|point| *point.as_ref())
+        .map_err(|_| Error::InvalidPoint)
+}
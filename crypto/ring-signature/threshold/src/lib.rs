@@ -0,0 +1,36 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Threshold (t-of-n) MLSAG signing for shared-custody accounts.
+//!
+//! The account's spend private key is never assembled. Instead:
+//! * The parties run a distributed key generation ([dkg]) which gives each of
+//!   them a Shamir share of the spend private key, and everyone the spend
+//!   public key. The view private key is not split, since every party needs it
+//!   to recognize the account's outputs.
+//! * To spend an input, any `threshold` of the parties run a three round
+//!   protocol (commit to nonces, reveal nonces, respond to the challenge) with
+//!   a coordinator which holds the transaction's amount secrets. The result is
+//!   a standard [RingMLSAG](mc_crypto_ring_signature::RingMLSAG), with the same
+//!   key image a single-key signer would produce.
+//!
+//! [ThresholdRingSigner] is the coordinator, and implements
+//! [RingSigner](mc_crypto_ring_signature_signer::RingSigner) so it can be used
+//! with the transaction builder. The parties are reached through the
+//! [SigningParticipant] trait, so they may live in other processes.
+
+#![deny(missing_docs)]
+
+pub mod dkg;
+
+mod error;
+mod key_share;
+mod participant;
+mod signer;
+
+pub use error::Error;
+pub use key_share::{lagrange_coefficient, KeyShare, ThresholdAccount};
+pub use participant::{
+    LocalParticipant, NonceCommitment, NonceReveal, PartialResponse, SessionId, SigningPackage,
+    SigningParticipant, SigningRequest,
+};
+pub use signer::ThresholdRingSigner;
@@ -0,0 +1,451 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! The parties' side of threshold signing.
+//!
+//! For each input, the coordinator runs three rounds with the signing parties:
+//! 1. [SigningParticipant::commit]: each party checks the request, picks a
+//!    nonce `alpha_j`, and returns a hash of `alpha_j * G` and
+//!    `alpha_j * Hp(P)`, along with its partial key image `x_j * Hp(P)`.
+//! 2. [SigningParticipant::reveal]: once every party has committed, each
+//!    party reveals its nonce points.
+//! 3. [SigningParticipant::respond]: each party checks the revealed nonces
+//!    against the commitments, recomputes the MLSAG challenge `c` from its own
+//!    view of the request, and returns `alpha_j - c * lambda_j * x_j`.
+//!
+//! Committing before revealing stops a party from choosing its nonce as a
+//! function of the others'. Each session can only be responded to once, and a
+//! party forgets the session once it has responded, or when the coordinator
+//! calls [SigningParticipant::abort].
+
+use crate::{
+    key_share::{decompress, public_point},
+    lagrange_coefficient, Error, KeyShare,
+};
+use mc_crypto_hashes::{Blake2b512, Digest};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
+use mc_crypto_ring_signature::{
+    hash_to_point, CompressedCommitment, CurveScalar, KeyImage, ReducedTxOut, RistrettoPoint,
+    Scalar, UnfinishedRingMLSAG,
+};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+use zeroize::Zeroizing;
+
+/// Domain separator for nonce commitments
+const NONCE_COMMITMENT_DOMAIN_TAG: &str = "mc_threshold_mlsag_nonce_commitment";
+
+/// Identifies a signing session, i.e. the signing of one input
+pub type SessionId = [u8; 32];
+
+/// A request to take part in signing an input
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SigningRequest {
+    /// The session, chosen at random by the coordinator
+    pub session_id: SessionId,
+
+    /// The indices of the parties taking part
+    pub signers: Vec<u32>,
+
+    /// The digest of transaction context to sign
+    pub message: Vec<u8>,
+
+    /// The ring being signed
+    pub ring: Vec<ReducedTxOut>,
+
+    /// The index of the real input in the ring
+    pub real_index: usize,
+
+    /// The subaddress of the account which owns the real input
+    pub subaddress_index: u64,
+}
+
+/// A party's first round message
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NonceCommitment {
+    /// The session
+    pub session_id: SessionId,
+
+    /// The party's index
+    pub index: u32,
+
+    /// A hash of the party's nonce points
+    pub nonce_hash: [u8; 32],
+
+    /// The party's partial key image `x_j * Hp(P)`
+    pub partial_key_image: CompressedRistrettoPublic,
+}
+
+/// A party's second round message
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NonceReveal {
+    /// The session
+    pub session_id: SessionId,
+
+    /// The party's index
+    pub index: u32,
+
+    /// `alpha_j * G`
+    pub nonce: CompressedRistrettoPublic,
+
+    /// `alpha_j * Hp(P)`
+    pub key_image_nonce: CompressedRistrettoPublic,
+}
+
+/// What the parties need in the third round
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SigningPackage {
+    /// The session
+    pub session_id: SessionId,
+
+    /// Every party's revealed nonces
+    pub reveals: Vec<NonceReveal>,
+
+    /// The commitment to the pseudo-output amount
+    pub output_commitment: CompressedCommitment,
+
+    /// The signature, missing the response for the real input's onetime key
+    pub unfinished: UnfinishedRingMLSAG,
+}
+
+/// A party's third round message
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PartialResponse {
+    /// The session
+    pub session_id: SessionId,
+
+    /// The party's index
+    pub index: u32,
+
+    /// `alpha_j - c * lambda_j * x_j`
+    pub response: CurveScalar,
+}
+
+/// A party taking part in threshold signing. Implementations may forward to a
+/// party in another process.
+pub trait SigningParticipant {
+    /// The party's index
+    fn index(&self) -> u32;
+
+    /// Round 1: check the request and commit to a nonce
+    fn commit(&self, request: &SigningRequest) -> Result<NonceCommitment, Error>;
+
+    /// Round 2: reveal the nonce, given every signer's commitment
+    fn reveal(
+        &self,
+        session_id: &SessionId,
+        commitments: &[NonceCommitment],
+    ) -> Result<NonceReveal, Error>;
+
+    /// Round 3: respond to the challenge
+    fn respond(&self, package: &SigningPackage) -> Result<PartialResponse, Error>;
+
+    /// Forget a session which will not be completed
+    fn abort(&self, session_id: &SessionId);
+}
+
+impl<P: SigningParticipant> SigningParticipant for &P {
+    fn index(&self) -> u32 {
+        (*self).index()
+    }
+
+    fn commit(&self, request: &SigningRequest) -> Result<NonceCommitment, Error> {
+        (*self).commit(request)
+    }
+
+    fn reveal(
+        &self,
+        session_id: &SessionId,
+        commitments: &[NonceCommitment],
+    ) -> Result<NonceReveal, Error> {
+        (*self).reveal(session_id, commitments)
+    }
+
+    fn respond(&self, package: &SigningPackage) -> Result<PartialResponse, Error> {
+        (*self).respond(package)
+    }
+
+    fn abort(&self, session_id: &SessionId) {
+        (*self).abort(session_id)
+    }
+}
+
+/// A party's state for one session
+struct Session {
+    request: SigningRequest,
+    /// This party's share `x_j` of the real input's onetime private key
+    onetime_private_share: Zeroizing<Scalar>,
+    alpha: Zeroizing<Scalar>,
+    reveal: NonceReveal,
+    commitment: NonceCommitment,
+    /// Every signer's commitment, once this party has revealed
+    commitments: Option<Vec<NonceCommitment>>,
+}
+
+/// A party which holds its key share in this process
+pub struct LocalParticipant<R: CryptoRng + RngCore> {
+    key_share: KeyShare,
+    rng: Mutex<R>,
+    sessions: Mutex<HashMap<SessionId, Session>>,
+}
+
+impl<R: CryptoRng + RngCore> LocalParticipant<R> {
+    /// Create a participant holding the given key share
+    pub fn new(key_share: KeyShare, rng: R) -> Self {
+        Self {
+            key_share,
+            rng: Mutex::new(rng),
+            sessions: Default::default(),
+        }
+    }
+
+    /// The key share this party holds
+    pub fn key_share(&self) -> &KeyShare {
+        &self.key_share
+    }
+
+    /// The number of sessions this party has committed to but not yet
+    /// responded to or aborted
+    pub fn num_sessions(&self) -> usize {
+        self.sessions.lock().expect("mutex poisoned").len()
+    }
+}
+
+impl<R: CryptoRng + RngCore> SigningParticipant for LocalParticipant<R> {
+    fn index(&self) -> u32 {
+        self.key_share.index
+    }
+
+    fn commit(&self, request: &SigningRequest) -> Result<NonceCommitment, Error> {
+        let account = &self.key_share.account;
+        account.check_signers(&request.signers)?;
+        if !request.signers.contains(&self.key_share.index) {
+            return Err(Error::NotASigner);
+        }
+
+        let real_input = request
+            .ring
+            .get(request.real_index)
+            .ok_or(mc_crypto_ring_signature::Error::IndexOutOfBounds)?;
+        let tx_public_key = RistrettoPublic::try_from(&real_input.public_key)?;
+        let onetime_public_key = RistrettoPublic::try_from(&real_input.target_key)?;
+
+        // The onetime public key must be Hs(a * R) * G + D_i
+        let offset = account.onetime_key_offset(&tx_public_key, request.subaddress_index);
+        let expected_public_key: RistrettoPoint =
+            public_point(&offset) + account.spend_public_key.as_ref();
+        if expected_public_key != *onetime_public_key.as_ref() {
+            return Err(Error::InputNotOwned);
+        }
+
+        let onetime_private_share = Zeroizing::new(self.key_share.onetime_private_share(&offset));
+        let hp = hash_to_point(&onetime_public_key);
+        let alpha = Zeroizing::new(Scalar::random(
+            &mut *self.rng.lock().expect("mutex poisoned"),
+        ));
+
+        let reveal = NonceReveal {
+            session_id: request.session_id,
+            index: self.key_share.index,
+            nonce: public_point(&alpha).into(),
+            key_image_nonce: (*alpha * hp).into(),
+        };
+        let commitment = NonceCommitment {
+            session_id: request.session_id,
+            index: self.key_share.index,
+            nonce_hash: nonce_hash(&reveal),
+            partial_key_image: (*onetime_private_share * hp).into(),
+        };
+
+        let mut sessions = self.sessions.lock().expect("mutex poisoned");
+        if sessions.contains_key(&request.session_id) {
+            return Err(Error::DuplicateSession);
+        }
+        sessions.insert(
+            request.session_id,
+            Session {
+                request: request.clone(),
+                onetime_private_share,
+                alpha,
+                reveal,
+                commitment: commitment.clone(),
+                commitments: None,
+            },
+        );
+        Ok(commitment)
+    }
+
+    fn reveal(
+        &self,
+        session_id: &SessionId,
+        commitments: &[NonceCommitment],
+    ) -> Result<NonceReveal, Error> {
+        let mut sessions = self.sessions.lock().expect("mutex poisoned");
+        let session = sessions.get_mut(session_id).ok_or(Error::UnknownSession)?;
+        if session.commitments.is_some() {
+            return Err(Error::UnexpectedMessage(self.key_share.index));
+        }
+
+        // A session whose commitments are invalid can never complete, so it is
+        // dropped rather than left waiting for an abort.
+        let result = order_by_signers(
+            &session.request.signers,
+            commitments,
+            |commitment| commitment.index,
+            |commitment| commitment.session_id == *session_id,
+        )
+        .and_then(|commitments| {
+            if commitments.contains(&session.commitment) {
+                Ok(commitments)
+            } else {
+                Err(Error::UnexpectedMessage(self.key_share.index))
+            }
+        });
+        match result {
+            Ok(commitments) => {
+                session.commitments = Some(commitments);
+                Ok(session.reveal.clone())
+            }
+            Err(err) => {
+                sessions.remove(session_id);
+                Err(err)
+            }
+        }
+    }
+
+    fn respond(&self, package: &SigningPackage) -> Result<PartialResponse, Error> {
+        // The session is removed whatever happens, so the nonce is never used
+        // for two different challenges.
+        let session = self
+            .sessions
+            .lock()
+            .expect("mutex poisoned")
+            .remove(&package.session_id)
+            .ok_or(Error::UnknownSession)?;
+        let commitments = session.commitments.as_ref().ok_or(Error::UnknownSession)?;
+        let request = &session.request;
+
+        let (nonce, key_image_nonce) = aggregate_nonces(commitments, &package.reveals)?;
+        let key_image = aggregate_key_image(&request.signers, commitments)?;
+        if package.unfinished.signature.key_image != key_image {
+            return Err(Error::KeyImageMismatch);
+        }
+        if package.unfinished.real_index != request.real_index {
+            return Err(Error::InvalidParameters("real index mismatch".to_owned()));
+        }
+
+        let c = package.unfinished.challenge(
+            &request.message,
+            &request.ring,
+            &package.output_commitment,
+            &nonce,
+            &key_image_nonce,
+        )?;
+        let lambda = lagrange_coefficient(self.key_share.index, &request.signers);
+        let response = *session.alpha - c * lambda * *session.onetime_private_share;
+
+        Ok(PartialResponse {
+            session_id: package.session_id,
+            index: self.key_share.index,
+            response: response.into(),
+        })
+    }
+
+    fn abort(&self, session_id: &SessionId) {
+        self.sessions
+            .lock()
+            .expect("mutex poisoned")
+            .remove(session_id);
+    }
+}
+
+// The hash committing to a party's nonce points
+pub(crate) fn nonce_hash(reveal: &NonceReveal) -> [u8; 32] {
+    let mut hasher = Blake2b512::new();
+    hasher.update(&NONCE_COMMITMENT_DOMAIN_TAG);
+    hasher.update(&reveal.session_id);
+    hasher.update(reveal.index.to_le_bytes());
+    hasher.update(reveal.nonce.as_bytes());
+    hasher.update(reveal.key_image_nonce.as_bytes());
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&hasher.finalize()[..32]);
+    result
+}
+
+// Put one message per signer in the order of the signers, checking that there
+// are no extra or missing messages
+pub(crate) fn order_by_signers<T: Clone>(
+    signers: &[u32],
+    messages: &[T],
+    index: impl Fn(&T) -> u32,
+    is_valid: impl Fn(&T) -> bool,
+) -> Result<Vec<T>, Error> {
+    for (position, message) in messages.iter().enumerate() {
+        let message_index = index(message);
+        if !is_valid(message)
+            || !signers.contains(&message_index)
+            || messages[..position]
+                .iter()
+                .any(|other| index(other) == message_index)
+        {
+            return Err(Error::UnexpectedMessage(message_index));
+        }
+    }
+    signers
+        .iter()
+        .map(|signer| {
+            messages
+                .iter()
+                .find(|message| index(message) == *signer)
+                .cloned()
+                .ok_or(Error::MissingMessage(*signer))
+        })
+        .collect()
+}
+
+// Check each revealed nonce against its commitment, and sum them
+pub(crate) fn aggregate_nonces(
+    commitments: &[NonceCommitment],
+    reveals: &[NonceReveal],
+) -> Result<(RistrettoPoint, RistrettoPoint), Error> {
+    let signers: Vec<u32> = commitments
+        .iter()
+        .map(|commitment| commitment.index)
+        .collect();
+    let session_id = commitments
+        .first()
+        .map(|commitment| commitment.session_id)
+        .ok_or(Error::UnknownSession)?;
+    let reveals = order_by_signers(
+        &signers,
+        reveals,
+        |reveal| reveal.index,
+        |reveal| reveal.session_id == session_id,
+    )?;
+
+    let mut nonce = RistrettoPoint::default();
+    let mut key_image_nonce = RistrettoPoint::default();
+    for (commitment, reveal) in commitments.iter().zip(reveals.iter()) {
+        if nonce_hash(reveal) != commitment.nonce_hash {
+            return Err(Error::NonceMismatch(reveal.index));
+        }
+        nonce += decompress(&reveal.nonce)?;
+        key_image_nonce += decompress(&reveal.key_image_nonce)?;
+    }
+    Ok((nonce, key_image_nonce))
+}
+
+// Combine the partial key images `x_j * Hp(P)` into `x * Hp(P)`
+pub(crate) fn aggregate_key_image(
+    signers: &[u32],
+    commitments: &[NonceCommitment],
+) -> Result<KeyImage, Error> {
+    let mut key_image = RistrettoPoint::default();
+    for commitment in commitments {
+        let lambda = lagrange_coefficient(commitment.index, signers);
+        key_image += lambda * decompress(&commitment.partial_key_image)?;
+    }
+    Ok(KeyImage {
+        point: key_image.compress(),
+    })
+}
@@ -0,0 +1,466 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! The coordinator of threshold signing
+
+use crate::{
+    key_share::public_point,
+    lagrange_coefficient,
+    participant::{aggregate_key_image, aggregate_nonces, order_by_signers},
+    Error, NonceCommitment, NonceReveal, PartialResponse, SessionId, SigningPackage,
+    SigningParticipant, SigningRequest, ThresholdAccount,
+};
+use mc_crypto_keys::RistrettoPublic;
+use mc_crypto_ring_signature::{
+    generators, CompressedCommitment, CryptoRngCore, RingMLSAG, Scalar,
+};
+use mc_crypto_ring_signature_signer::{
+    Error as SignerError, NoKeysRingSigner, OneTimeKeyDeriveData, RingSigner, SignableInputRing,
+};
+use rand_core::RngCore;
+
+/// A [RingSigner] which signs with a threshold account, by running the signing
+/// protocol with enough of the account's parties.
+///
+/// The coordinator learns the amount secrets of the transaction, but nothing
+/// about the spend private key.
+pub struct ThresholdRingSigner<P: SigningParticipant> {
+    account: ThresholdAccount,
+    participants: Vec<P>,
+}
+
+impl<P: SigningParticipant> ThresholdRingSigner<P> {
+    /// Create a signer for the given account, which signs with every one of
+    /// the given parties
+    pub fn new(account: ThresholdAccount, participants: Vec<P>) -> Self {
+        Self {
+            account,
+            participants,
+        }
+    }
+
+    /// The account this signs for
+    pub fn account(&self) -> &ThresholdAccount {
+        &self.account
+    }
+
+    // Run the signing protocol for an input owned by a subaddress, making sure
+    // no party is left holding the session if it fails
+    fn sign_with_participants(
+        &self,
+        message: &[u8],
+        ring: &SignableInputRing,
+        subaddress_index: u64,
+        output_blinding: Scalar,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<RingMLSAG, Error> {
+        let mut session_id = SessionId::default();
+        rng.fill_bytes(&mut session_id);
+        let result = self.run_session(
+            session_id,
+            message,
+            ring,
+            subaddress_index,
+            output_blinding,
+            rng,
+        );
+        if result.is_err() {
+            for participant in &self.participants {
+                participant.abort(&session_id);
+            }
+        }
+        result
+    }
+
+    fn run_session(
+        &self,
+        session_id: SessionId,
+        message: &[u8],
+        ring: &SignableInputRing,
+        subaddress_index: u64,
+        output_blinding: Scalar,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<RingMLSAG, Error> {
+        let signers: Vec<u32> = self
+            .participants
+            .iter()
+            .map(|participant| participant.index())
+            .collect();
+        self.account.check_signers(&signers)?;
+
+        let real_input = ring
+            .members
+            .get(ring.real_input_index)
+            .ok_or(mc_crypto_ring_signature::Error::IndexOutOfBounds)?;
+        let tx_public_key = RistrettoPublic::try_from(&real_input.public_key)?;
+        let offset = self
+            .account
+            .onetime_key_offset(&tx_public_key, subaddress_index);
+
+        let request = SigningRequest {
+            session_id,
+            signers: signers.clone(),
+            message: message.to_vec(),
+            ring: ring.members.clone(),
+            real_index: ring.real_input_index,
+            subaddress_index,
+        };
+
+        // Round 1
+        let commitments = self
+            .participants
+            .iter()
+            .map(|participant| participant.commit(&request))
+            .collect::<Result<Vec<NonceCommitment>, Error>>()?;
+        let commitments = order_by_signers(
+            &signers,
+            &commitments,
+            |commitment| commitment.index,
+            |commitment| commitment.session_id == session_id,
+        )?;
+
+        // Round 2
+        let reveals = self
+            .participants
+            .iter()
+            .map(|participant| participant.reveal(&session_id, &commitments))
+            .collect::<Result<Vec<NonceReveal>, Error>>()?;
+        let (nonce, key_image_nonce) = aggregate_nonces(&commitments, &reveals)?;
+        let key_image = aggregate_key_image(&signers, &commitments)?;
+
+        let amount = &ring.input_secret.amount;
+        let generator = generators(*amount.token_id);
+        let unfinished = RingMLSAG::sign_unfinished(
+            message,
+            &ring.members,
+            ring.real_input_index,
+            &key_image,
+            &nonce,
+            &key_image_nonce,
+            amount.value,
+            &ring.input_secret.blinding,
+            &output_blinding,
+            &generator,
+            rng,
+        )?;
+        let output_commitment =
+            CompressedCommitment::new(amount.value, output_blinding, &generator);
+        let c = unfinished.challenge(
+            message,
+            &ring.members,
+            &output_commitment,
+            &nonce,
+            &key_image_nonce,
+        )?;
+
+        // Round 3
+        let package = SigningPackage {
+            session_id,
+            reveals,
+            output_commitment,
+            unfinished,
+        };
+        let responses = self
+            .participants
+            .iter()
+            .map(|participant| participant.respond(&package))
+            .collect::<Result<Vec<PartialResponse>, Error>>()?;
+        let responses = order_by_signers(
+            &signers,
+            &responses,
+            |response| response.index,
+            |response| response.session_id == session_id,
+        )?;
+
+        // Check each partial response, so that a misbehaving party can be
+        // identified: alpha_j * G = r_j * G + c * lambda_j * x_j * G
+        let mut response_sum = Scalar::zero();
+        for (response, reveal) in responses.iter().zip(package.reveals.iter()) {
+            let lambda = lagrange_coefficient(response.index, &signers);
+            let public_share = self.account.onetime_public_share(response.index, &offset)?;
+            let expected_nonce =
+                public_point(&response.response.scalar) + c * lambda * public_share;
+            if expected_nonce.compress().as_bytes() != reveal.nonce.as_bytes() {
+                return Err(Error::InvalidResponse(response.index));
+            }
+            response_sum += response.response.scalar;
+        }
+
+        let signature = package.unfinished.finish(response_sum);
+        signature.verify(message, &ring.members, &output_commitment)?;
+        Ok(signature)
+    }
+}
+
+impl<P: SigningParticipant> RingSigner for ThresholdRingSigner<P> {
+    fn sign(
+        &self,
+        message: &[u8],
+        ring: &SignableInputRing,
+        output_blinding: Scalar,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<RingMLSAG, SignerError> {
+        let subaddress_index = match ring.input_secret.onetime_key_derive_data {
+            // The onetime private key is already known, so there is nothing to
+            // share out.
+            OneTimeKeyDeriveData::OneTimeKey(_) => {
+                return NoKeysRingSigner {}.sign(message, ring, output_blinding, rng)
+            }
+            OneTimeKeyDeriveData::SubaddressIndex(subaddress_index) => subaddress_index,
+        };
+
+        self.sign_with_participants(message, ring, subaddress_index, output_blinding, rng)
+            .map_err(|err| match err {
+                Error::InputNotOwned => SignerError::TrueInputNotOwned,
+                Error::Keys(err) => SignerError::Keys(err),
+                Error::RingSignature(err) => SignerError::RingSignature(err),
+                err => SignerError::ConnectionFailed(format!("threshold signing failed: {}", err)),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dkg::generate_key_shares, KeyShare, LocalParticipant};
+    use mc_account_keys::AccountKey;
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
+    use mc_crypto_ring_signature::{
+        onetime_keys::{create_tx_out_public_key, create_tx_out_target_key},
+        Commitment, ReducedTxOut,
+    };
+    use mc_crypto_ring_signature_signer::{InputSecret, LocalRingSigner};
+    use mc_transaction_types::{Amount, TokenId};
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::{get_seeded_rng, RngType, SeedableRng};
+
+    const SUBADDRESS_INDEX: u64 = 5;
+
+    const SESSION_ID: &[u8] = b"signer test session";
+
+    // Build a ring whose real input is owned by the given account
+    fn owned_ring(
+        account: &ThresholdAccount,
+        amount: Amount,
+        rng: &mut RngType,
+    ) -> SignableInputRing {
+        let mut members: Vec<ReducedTxOut> = (0..11)
+            .map(|_| ReducedTxOut {
+                public_key: CompressedRistrettoPublic::from_random(rng),
+                target_key: CompressedRistrettoPublic::from_random(rng),
+                commitment: CompressedCommitment::from(&Commitment::new(
+                    rng.next_u64(),
+                    Scalar::random(rng),
+                    &generators(*amount.token_id),
+                )),
+            })
+            .collect();
+
+        let recipient = account.subaddress(SUBADDRESS_INDEX);
+        let tx_private_key = RistrettoPrivate::from_random(rng);
+        let blinding = Scalar::random(rng);
+        let real_input_index = 2;
+        members[real_input_index] = ReducedTxOut {
+            public_key: (&create_tx_out_public_key(&tx_private_key, recipient.spend_public_key()))
+                .into(),
+            target_key: (&create_tx_out_target_key(&tx_private_key, &recipient)).into(),
+            commitment: CompressedCommitment::from(&Commitment::new(
+                amount.value,
+                blinding,
+                &generators(*amount.token_id),
+            )),
+        };
+
+        SignableInputRing {
+            members,
+            real_input_index,
+            input_secret: InputSecret {
+                onetime_key_derive_data: OneTimeKeyDeriveData::SubaddressIndex(SUBADDRESS_INDEX),
+                amount,
+                blinding,
+            },
+        }
+    }
+
+    // Reassemble the spend private key from enough shares
+    fn reconstruct(shares: &[&KeyShare]) -> AccountKey {
+        let signers: Vec<u32> = shares.iter().map(|share| share.index).collect();
+        let spend_private_key: Scalar = shares
+            .iter()
+            .map(|share| {
+                let b_j: &Scalar = share.spend_private_share.as_ref();
+                lagrange_coefficient(share.index, &signers) * b_j
+            })
+            .sum();
+        AccountKey::new(
+            &RistrettoPrivate::from(spend_private_key),
+            &shares[0].account.view_private_key,
+        )
+    }
+
+    fn participants(shares: &[KeyShare], indices: &[u32]) -> Vec<LocalParticipant<RngType>> {
+        indices
+            .iter()
+            .map(|index| {
+                let share = shares.iter().find(|share| share.index == *index).unwrap();
+                LocalParticipant::new(share.clone(), RngType::from_seed([*index as u8; 32]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn threshold_signature_verifies() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+        let account = shares[0].account.clone();
+
+        let amount = Amount::new(1234, TokenId::from(2));
+        let ring = owned_ring(&account, amount, &mut rng);
+        let message = [3u8; 32];
+        let output_blinding = Scalar::random(&mut rng);
+        let output_commitment =
+            CompressedCommitment::new(amount.value, output_blinding, &generators(*amount.token_id));
+
+        // The key image is the same one a single-key signer would produce
+        let local_signer = LocalRingSigner::from(&reconstruct(&[&shares[0], &shares[1]]));
+        let local_signature = local_signer
+            .sign(&message, &ring, output_blinding, &mut rng)
+            .unwrap();
+
+        for indices in [[1, 3], [2, 1], [3, 2]] {
+            let signer = ThresholdRingSigner::new(account.clone(), participants(&shares, &indices));
+            let signature = signer
+                .sign(&message, &ring, output_blinding, &mut rng)
+                .unwrap();
+            signature
+                .verify(&message, &ring.members, &output_commitment)
+                .unwrap();
+            assert_eq!(signature.key_image, local_signature.key_image);
+        }
+    }
+
+    #[test]
+    fn sessions_are_forgotten_after_signing() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+        let other_shares =
+            generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+        let account = shares[0].account.clone();
+        let ring = owned_ring(&account, Amount::new(10, TokenId::from(0)), &mut rng);
+
+        let parties = participants(&shares, &[1, 2]);
+        let signer = ThresholdRingSigner::new(account.clone(), parties.iter().collect());
+        signer
+            .sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng)
+            .unwrap();
+        assert!(parties.iter().all(|party| party.num_sessions() == 0));
+
+        // Party 2 holds a share of another account, so it refuses to commit
+        // after party 1 already has
+        let mut parties = participants(&shares, &[1]);
+        parties.extend(participants(&other_shares, &[2]));
+        let signer = ThresholdRingSigner::new(account, parties.iter().collect());
+        assert!(signer
+            .sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng)
+            .is_err());
+        assert!(parties.iter().all(|party| party.num_sessions() == 0));
+    }
+
+    #[test]
+    fn too_few_signers_is_an_error() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+        let account = shares[0].account.clone();
+
+        let ring = owned_ring(&account, Amount::new(10, TokenId::from(0)), &mut rng);
+        let signer = ThresholdRingSigner::new(account, participants(&shares, &[2]));
+        assert!(matches!(
+            signer.sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng),
+            Err(SignerError::ConnectionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn input_owned_by_another_account_is_refused() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+        let other_shares =
+            generate_key_shares(SESSION_ID, 2, 3, &view_private_key, &mut rng).unwrap();
+
+        let ring = owned_ring(
+            &other_shares[0].account,
+            Amount::new(10, TokenId::from(0)),
+            &mut rng,
+        );
+        let signer =
+            ThresholdRingSigner::new(shares[0].account.clone(), participants(&shares, &[1, 2]));
+        assert_eq!(
+            signer.sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng),
+            Err(SignerError::TrueInputNotOwned)
+        );
+    }
+
+    #[test]
+    fn sessions_cannot_be_replayed() {
+        let mut rng = get_seeded_rng();
+        let view_private_key = RistrettoPrivate::from_random(&mut rng);
+        let shares = generate_key_shares(SESSION_ID, 2, 2, &view_private_key, &mut rng).unwrap();
+        let account = shares[0].account.clone();
+        let parties = participants(&shares, &[1, 2]);
+
+        let ring = owned_ring(&account, Amount::new(77, TokenId::from(0)), &mut rng);
+        let message = [9u8; 32];
+        let output_blinding = Scalar::random(&mut rng);
+        let request = SigningRequest {
+            session_id: [1u8; 32],
+            signers: vec![1, 2],
+            message: message.to_vec(),
+            ring: ring.members.clone(),
+            real_index: ring.real_input_index,
+            subaddress_index: SUBADDRESS_INDEX,
+        };
+
+        let commitments: Vec<NonceCommitment> = parties
+            .iter()
+            .map(|party| party.commit(&request).unwrap())
+            .collect();
+        assert_eq!(parties[0].commit(&request), Err(Error::DuplicateSession));
+        let reveals: Vec<NonceReveal> = parties
+            .iter()
+            .map(|party| party.reveal(&request.session_id, &commitments).unwrap())
+            .collect();
+
+        let (nonce, key_image_nonce) = aggregate_nonces(&commitments, &reveals).unwrap();
+        let key_image = aggregate_key_image(&request.signers, &commitments).unwrap();
+        let unfinished = RingMLSAG::sign_unfinished(
+            &message,
+            &ring.members,
+            ring.real_input_index,
+            &key_image,
+            &nonce,
+            &key_image_nonce,
+            ring.input_secret.amount.value,
+            &ring.input_secret.blinding,
+            &output_blinding,
+            &generators(0),
+            &mut rng,
+        )
+        .unwrap();
+        let package = SigningPackage {
+            session_id: request.session_id,
+            reveals,
+            output_commitment: CompressedCommitment::new(
+                ring.input_secret.amount.value,
+                output_blinding,
+                &generators(0),
+            ),
+            unfinished,
+        };
+
+        parties[0].respond(&package).unwrap();
+        assert_eq!(parties[0].respond(&package), Err(Error::UnknownSession));
+    }
+}
@@ -20,7 +20,10 @@ pub mod report_parse;
 
 pub mod conversions;
 
-use grpcio::{CallOption, Metadata, Result as GrpcResult};
+use futures::{executor::block_on, TryStreamExt};
+use grpcio::{
+    CallOption, Error as GrpcError, Metadata, Result as GrpcResult, RpcStatus, RpcStatusCode,
+};
 use mc_fog_uri::{IngestPeerUri, UriParseError};
 use std::{collections::BTreeSet, str::FromStr};
 
@@ -44,7 +47,7 @@ impl ingest_common::IngestSummary {
 // Implement the EnclaveGrpcChannel trait on attested service types.
 // If we don't do this in this crate, then newtype wrappers must be used,
 // because of orphan rules
-use mc_fog_enclave_connection::EnclaveGrpcChannel;
+use mc_fog_enclave_connection::{EnclaveGrpcChannel, EnclaveGrpcStreamingChannel};
 
 impl EnclaveGrpcChannel for view_grpc::FogViewApiClient {
     fn auth(
@@ -96,3 +99,38 @@ impl EnclaveGrpcChannel for ledger_grpc::FogMerkleProofApiClient {
         <Self>::get_outputs_async_opt(self, msg, call_option)?.receive_sync()
     }
 }
+
+impl EnclaveGrpcChannel for ledger_grpc::FogBatchedLedgerApiClient {
+    fn auth(
+        &mut self,
+        msg: &attest::AuthMessage,
+        call_option: CallOption,
+    ) -> GrpcResult<(Metadata, attest::AuthMessage, Metadata)> {
+        <Self>::auth_async_opt(self, msg, call_option)?.receive_sync()
+    }
+    fn enclave_request(
+        &mut self,
+        _msg: &attest::Message,
+        _call_option: CallOption,
+    ) -> GrpcResult<(Metadata, attest::Message, Metadata)> {
+        Err(GrpcError::RpcFailure(RpcStatus::with_message(
+            RpcStatusCode::UNIMPLEMENTED,
+            "batched queries are streamed, use enclave_streaming_request".to_owned(),
+        )))
+    }
+}
+
+impl EnclaveGrpcStreamingChannel for ledger_grpc::FogBatchedLedgerApiClient {
+    fn enclave_streaming_request(
+        &mut self,
+        msg: &attest::Message,
+        call_option: CallOption,
+    ) -> GrpcResult<(Metadata, Vec<attest::Message>)> {
+        let mut receiver = <Self>::batched_query_opt(self, msg, call_option)?;
+        block_on(async {
+            let headers = receiver.headers().await?.clone();
+            let messages = receiver.try_collect().await?;
+            Ok((headers, messages))
+        })
+    }
+}
@@ -34,6 +34,12 @@ fn fog_view_query_request_round_trip() {
             get_txos: (0..num_txos as usize)
                 .map(|_| <[u8; 32]>::sample(&mut rng).to_vec())
                 .collect(),
+            probe_txos: (0..num_txos as usize / 2)
+                .map(|_| <[u8; 32]>::sample(&mut rng).to_vec())
+                .collect(),
+            accounts: (0..num_txos as usize / 8)
+                .map(|_| mc_fog_types::view::AccountQueryRequest::sample(&mut rng))
+                .collect(),
         };
         round_trip_message::<mc_fog_types::view::QueryRequest, mc_fog_api::view::QueryRequest>(
             &test_val,
@@ -52,6 +58,11 @@ fn fog_view_query_request_protobuf_round_trip() {
                 .get_txos
                 .push(<[u8; 32]>::sample(&mut rng).to_vec());
         }
+        for _ in 0..10 {
+            test_val
+                .probe_txos
+                .push(<[u8; 32]>::sample(&mut rng).to_vec());
+        }
         round_trip_protobuf_object::<
             mc_fog_api::view::QueryRequest,
             mc_fog_types::view::QueryRequest,
@@ -74,6 +85,12 @@ fn fog_view_query_request_aad_round_trip() {
         let test_val = mc_fog_types::view::QueryRequestAAD {
             start_from_user_event_id: rng.next_u64() as i64,
             start_from_block_index: rng.next_u64(),
+            accounts: (0..10)
+                .map(|account_id| mc_fog_types::view::AccountQueryRequestAAD {
+                    account_id,
+                    start_from_user_event_id: rng.next_u64() as i64,
+                })
+                .collect(),
         };
         round_trip_message::<mc_fog_types::view::QueryRequestAAD, mc_fog_api::view::QueryRequestAAD>(
             &test_val,
@@ -135,6 +152,8 @@ fn fog_view_query_response_round_trip() {
                 .collect(),
             last_known_block_count: rng.next_u32() as u64,
             last_known_block_cumulative_txo_count: rng.next_u32() as u64,
+            tx_out_probe_results: Default::default(),
+            account_responses: Default::default(),
         };
         round_trip_message::<mc_fog_types::view::QueryResponse, mc_fog_api::view::QueryResponse>(
             &test_val,
@@ -158,6 +177,8 @@ fn fog_view_query_response_round_trip() {
                 .collect(),
             last_known_block_count: rng.next_u32() as u64,
             last_known_block_cumulative_txo_count: rng.next_u32() as u64,
+            tx_out_probe_results: Default::default(),
+            account_responses: Default::default(),
         };
         round_trip_message::<mc_fog_types::view::QueryResponse, mc_fog_api::view::QueryResponse>(
             &test_val,
@@ -188,6 +209,12 @@ fn fog_view_query_response_round_trip() {
                 .collect(),
             last_known_block_count: rng.next_u32() as u64,
             last_known_block_cumulative_txo_count: rng.next_u32() as u64,
+            tx_out_probe_results: (0..40)
+                .map(|_| mc_fog_types::view::TxOutProbeResult::sample(&mut rng))
+                .collect(),
+            account_responses: (0..5)
+                .map(|_| mc_fog_types::view::AccountQueryResponse::sample(&mut rng))
+                .collect(),
         };
         round_trip_message::<mc_fog_types::view::QueryResponse, mc_fog_api::view::QueryResponse>(
             &test_val,
@@ -279,6 +306,42 @@ fn check_key_images_response_round_trip() {
     });
 }
 
+/// Test that many random instances of prosty BatchedLedgerResponse round trip
+/// with protobufy BatchedLedgerResponse
+#[test]
+fn batched_ledger_response_round_trip() {
+    {
+        let test_val = mc_fog_types::ledger::BatchedLedgerResponse::default();
+        round_trip_message::<
+            mc_fog_types::ledger::BatchedLedgerResponse,
+            mc_fog_api::ledger::BatchedLedgerResponse,
+        >(&test_val);
+    }
+
+    run_with_several_seeds(|mut rng| {
+        let mut test_val = mc_fog_types::ledger::BatchedLedgerResponse {
+            chunk_index: rng.next_u32(),
+            num_chunks: rng.next_u32(),
+            num_blocks: rng.next_u32() as u64,
+            global_txo_count: rng.next_u32() as u64,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            test_val
+                .key_image_results
+                .push(mc_fog_types::ledger::KeyImageResult::sample(&mut rng));
+            test_val
+                .output_results
+                .push(mc_fog_types::ledger::OutputResult::sample(&mut rng));
+        }
+
+        round_trip_message::<
+            mc_fog_types::ledger::BatchedLedgerResponse,
+            mc_fog_api::ledger::BatchedLedgerResponse,
+        >(&test_val);
+    });
+}
+
 /// Test that .proto enum values match what is in
 /// src/fog/recovery_db_iface/src/types.rs
 #[test]
@@ -438,6 +501,51 @@ impl Sample for mc_fog_types::view::TxOutSearchResult {
     }
 }
 
+impl Sample for mc_fog_types::view::TxOutProbeResult {
+    fn sample<T: RngCore + CryptoRng>(rng: &mut T) -> Self {
+        Self {
+            search_key: <[u8; 32]>::sample(rng).to_vec(),
+            result_code: 1,
+            block_index: rng.next_u64(),
+        }
+    }
+}
+
+impl Sample for mc_fog_types::view::AccountQueryRequest {
+    fn sample<T: RngCore + CryptoRng>(rng: &mut T) -> Self {
+        Self {
+            account_id: rng.next_u64(),
+            get_txos: (0..10).map(|_| <[u8; 32]>::sample(rng).to_vec()).collect(),
+        }
+    }
+}
+
+impl Sample for mc_fog_types::view::AccountQueryResponse {
+    fn sample<T: RngCore + CryptoRng>(rng: &mut T) -> Self {
+        Self {
+            account_id: rng.next_u64(),
+            next_start_from_user_event_id: rng.next_u64() as i64,
+            missed_block_ranges: (0..3)
+                .map(|_| {
+                    mc_fog_types::common::BlockRange::new(
+                        rng.next_u32() as u64,
+                        rng.next_u32() as u64,
+                    )
+                })
+                .collect(),
+            rng_records: (0..5)
+                .map(|_| mc_fog_types::view::RngRecord::sample(rng))
+                .collect(),
+            decommissioned_ingest_invocations: (0..2)
+                .map(|_| mc_fog_types::view::DecommissionedIngestInvocation::sample(rng))
+                .collect(),
+            tx_out_search_results: (0..10)
+                .map(|_| mc_fog_types::view::TxOutSearchResult::sample(rng))
+                .collect(),
+        }
+    }
+}
+
 impl Sample for MaskedAmount {
     fn sample<T: RngCore + CryptoRng>(rng: &mut T) -> Self {
         let amount = Amount {
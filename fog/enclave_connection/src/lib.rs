@@ -49,6 +49,23 @@ pub trait EnclaveGrpcChannel: Send + Sync {
     ) -> GrpcResult<(Metadata, Message, Metadata)>;
 }
 
+/// Abstracts the enclave_request aspect of a grpc channel used for attested
+/// connections, for services which reply with a stream of encrypted messages
+/// rather than a single one.
+///
+/// This call:
+/// - Takes a message type appropriate to the service
+/// - Takes a CallOption object containing credentials info and cookies
+/// - Returns a metadata object containing grpc headers, as well as every
+///   message in the stream, in order.
+pub trait EnclaveGrpcStreamingChannel: EnclaveGrpcChannel {
+    fn enclave_streaming_request(
+        &mut self,
+        ciphertext: &Message,
+        call_option: CallOption,
+    ) -> GrpcResult<(Metadata, Vec<Message>)>;
+}
+
 /// A generic object representing an attested connection to a remote enclave
 pub struct EnclaveConnection<U: ConnectionUri, G: EnclaveGrpcChannel> {
     /// The URI we are connecting to, and which provides the ResponderId
@@ -182,27 +199,7 @@ impl<U: ConnectionUri, G: EnclaveGrpcChannel> EnclaveConnection<U, G> {
         plaintext_request: &RequestMessage,
         aad: &[u8],
     ) -> Result<ResponseMessage, Error> {
-        if !self.is_attested() {
-            let _verification_report = self.attest()?;
-        }
-
-        // Build encrypted request, scope attest_cipher borrow
-        let msg = {
-            let attest_cipher = self
-                .attest_cipher
-                .as_mut()
-                .expect("no enclave_connection even though attest succeeded");
-
-            let mut msg = Message::new();
-            msg.set_channel_id(Vec::from(attest_cipher.binding()));
-            msg.set_aad(aad.to_vec());
-
-            let plaintext_bytes = mc_util_serial::encode(plaintext_request);
-
-            let request_ciphertext = attest_cipher.encrypt(aad, &plaintext_bytes)?;
-            msg.set_data(request_ciphertext);
-            msg
-        };
+        let msg = self.encrypt_request(plaintext_request, aad)?;
 
         // make an attested call to EnclaveGrpcChannel::enclave_request,
         // and handle cookies
@@ -225,17 +222,49 @@ impl<U: ConnectionUri, G: EnclaveGrpcChannel> EnclaveConnection<U, G> {
             Ok(message)
         })?;
 
-        // Decrypt request, scope attest_cipher borrow
-        {
-            let attest_cipher = self
-                .attest_cipher
-                .as_mut()
-                .expect("no enclave_connection even though attest succeeded");
-
-            let plaintext_bytes = attest_cipher.decrypt(message.get_aad(), message.get_data())?;
-            let plaintext_response: ResponseMessage = mc_util_serial::decode(&plaintext_bytes)?;
-            Ok(plaintext_response)
+        self.decrypt_response(&message)
+    }
+
+    /// Attest if needed, then encrypt a request for the enclave
+    fn encrypt_request<RequestMessage: mc_util_serial::Message>(
+        &mut self,
+        plaintext_request: &RequestMessage,
+        aad: &[u8],
+    ) -> Result<Message, Error> {
+        if !self.is_attested() {
+            let _verification_report = self.attest()?;
         }
+
+        // Build encrypted request, scope attest_cipher borrow
+        let attest_cipher = self
+            .attest_cipher
+            .as_mut()
+            .expect("no enclave_connection even though attest succeeded");
+
+        let mut msg = Message::new();
+        msg.set_channel_id(Vec::from(attest_cipher.binding()));
+        msg.set_aad(aad.to_vec());
+
+        let plaintext_bytes = mc_util_serial::encode(plaintext_request);
+
+        let request_ciphertext = attest_cipher.encrypt(aad, &plaintext_bytes)?;
+        msg.set_data(request_ciphertext);
+        Ok(msg)
+    }
+
+    /// Decrypt and deserialize a response from the enclave
+    fn decrypt_response<ResponseMessage: mc_util_serial::Message + Default>(
+        &mut self,
+        message: &Message,
+    ) -> Result<ResponseMessage, Error> {
+        let attest_cipher = self
+            .attest_cipher
+            .as_mut()
+            .expect("no enclave_connection even though attest succeeded");
+
+        let plaintext_bytes = attest_cipher.decrypt(message.get_aad(), message.get_data())?;
+        let plaintext_response: ResponseMessage = mc_util_serial::decode(&plaintext_bytes)?;
+        Ok(plaintext_response)
     }
 
     /// Same as encrypted_enclave_request, but convert result to an
@@ -262,6 +291,73 @@ impl<U: ConnectionUri, G: EnclaveGrpcChannel> EnclaveConnection<U, G> {
     }
 }
 
+impl<U: ConnectionUri, G: EnclaveGrpcStreamingChannel> EnclaveConnection<U, G> {
+    /// Make an attested request to an enclave which replies with a stream of
+    /// encrypted messages. Each message in the stream is decrypted and
+    /// deserialized in order, and the responses are returned once the stream
+    /// has completed.
+    pub fn encrypted_enclave_streaming_request<
+        RequestMessage: mc_util_serial::Message,
+        ResponseMessage: mc_util_serial::Message + Default,
+    >(
+        &mut self,
+        plaintext_request: &RequestMessage,
+        aad: &[u8],
+    ) -> Result<Vec<ResponseMessage>, Error> {
+        let msg = self.encrypt_request(plaintext_request, aad)?;
+
+        // make an attested call to
+        // EnclaveGrpcStreamingChannel::enclave_streaming_request, and handle
+        // cookies
+        let messages = self.attested_call(|this| {
+            let call_opt = this.call_option();
+            let (header, messages) = this.grpc.enclave_streaming_request(&msg, call_opt)?;
+
+            // Update cookies from server-sent metadata
+            if let Err(e) = this
+                .cookies
+                .update_from_server_metadata(Some(&header), None)
+            {
+                log::warn!(
+                    this.logger,
+                    "Could not update cookies from gRPC metadata: {}",
+                    e
+                )
+            }
+
+            Ok(messages)
+        })?;
+
+        messages
+            .iter()
+            .map(|message| self.decrypt_response(message))
+            .collect()
+    }
+
+    /// Same as encrypted_enclave_streaming_request, but convert result to an
+    /// OperationResult for use with the retry crate
+    pub fn retriable_encrypted_enclave_streaming_request<
+        RequestMessage: mc_util_serial::Message,
+        ResponseMessage: mc_util_serial::Message + Default,
+    >(
+        &mut self,
+        plaintext_request: &RequestMessage,
+        aad: &[u8],
+    ) -> OperationResult<Vec<ResponseMessage>, Error> {
+        match self.encrypted_enclave_streaming_request(plaintext_request, aad) {
+            Ok(value) => OperationResult::Ok(value),
+            Err(err) => {
+                if err.should_retry() {
+                    log::debug!(self.logger, "retriable enclave connection error: {}", err);
+                    OperationResult::Retry(err)
+                } else {
+                    OperationResult::Err(err)
+                }
+            }
+        }
+    }
+}
+
 // boilerplate
 
 impl<U: ConnectionUri, G: EnclaveGrpcChannel> Display for EnclaveConnection<U, G> {
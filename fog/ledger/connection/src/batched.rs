@@ -0,0 +1,162 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use super::Error;
+use grpcio::{ChannelBuilder, Environment};
+use mc_attest_verifier::Verifier;
+use mc_common::logger::{o, Logger};
+use mc_fog_api::ledger_grpc::FogBatchedLedgerApiClient;
+use mc_fog_enclave_connection::EnclaveConnection;
+use mc_fog_types::ledger::{BatchedLedgerRequest, BatchedLedgerResponse, KeyImageQuery};
+use mc_fog_uri::FogLedgerUri;
+use mc_transaction_core::ring_signature::KeyImage;
+use mc_util_grpc::{ConnectionUriGrpcioChannel, GrpcRetryConfig};
+use std::sync::Arc;
+
+/// An attested connection to the Fog Batched Ledger service, which checks key
+/// images and fetches merkle proofs in a single round trip.
+pub struct FogBatchedLedgerGrpcClient {
+    /// The attested connection
+    conn: EnclaveConnection<FogLedgerUri, FogBatchedLedgerApiClient>,
+    /// Grpc retry config
+    grpc_retry_config: GrpcRetryConfig,
+    /// Uri to connect to
+    uri: FogLedgerUri,
+}
+
+impl FogBatchedLedgerGrpcClient {
+    /// Create a new client object
+    ///
+    /// Arguments:
+    /// * uri: The uri to connect to
+    /// * grpc_retry_config: The retry policy to use for connection errors
+    /// * verifier: The attestation verifier
+    /// * env: The grpc environment to use (thread pool)
+    /// * logger: for logging
+    pub fn new(
+        uri: FogLedgerUri,
+        grpc_retry_config: GrpcRetryConfig,
+        verifier: Verifier,
+        env: Arc<Environment>,
+        logger: Logger,
+    ) -> Self {
+        let logger = logger.new(o!("mc.ledger.cxn" => uri.to_string()));
+
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&uri, &logger);
+
+        let grpc_client = FogBatchedLedgerApiClient::new(ch);
+
+        Self {
+            conn: EnclaveConnection::new(uri.clone(), grpc_client, verifier, logger),
+            grpc_retry_config,
+            uri,
+        }
+    }
+
+    /// Make a private request to check the validity of several key images,
+    /// and get membership proofs for several TxOuts, at once.
+    ///
+    /// The chunks streamed back by the server are reassembled into a single
+    /// response, whose `chunk_index` is 0 and `num_chunks` is 1.
+    pub fn batched_query(
+        &mut self,
+        key_images: &[KeyImage],
+        output_indices: Vec<u64>,
+        merkle_root_block: u64,
+    ) -> Result<BatchedLedgerResponse, Error> {
+        let request = BatchedLedgerRequest {
+            key_image_queries: key_images
+                .iter()
+                .map(|key_image| KeyImageQuery {
+                    key_image: *key_image,
+                    start_block: 0,
+                })
+                .collect(),
+            output_indices,
+            merkle_root_block,
+        };
+
+        let retry_config = self.grpc_retry_config;
+
+        let chunks: Vec<BatchedLedgerResponse> = retry_config
+            .retry(|| {
+                self.conn
+                    .retriable_encrypted_enclave_streaming_request(&request, &[])
+            })
+            .map_err(|err| Error::Connection(self.uri.clone(), err))?;
+
+        merge_chunks(chunks)
+    }
+}
+
+/// Reassemble the chunks of a batched response, checking that none are
+/// missing.
+fn merge_chunks(mut chunks: Vec<BatchedLedgerResponse>) -> Result<BatchedLedgerResponse, Error> {
+    chunks.sort_by_key(|chunk| chunk.chunk_index);
+
+    let num_chunks = chunks.first().map(|chunk| chunk.num_chunks).unwrap_or(1);
+    let complete = chunks.len() == num_chunks as usize
+        && chunks.iter().enumerate().all(|(idx, chunk)| {
+            chunk.chunk_index as usize == idx && chunk.num_chunks == num_chunks
+        });
+    if !complete {
+        return Err(Error::IncompleteBatchedResponse(num_chunks, chunks.len()));
+    }
+
+    let mut chunks = chunks.into_iter();
+    let mut result = chunks
+        .next()
+        .expect("checked that there is at least one chunk");
+    for chunk in chunks {
+        result.key_image_results.extend(chunk.key_image_results);
+        result.output_results.extend(chunk.output_results);
+    }
+    result.num_chunks = 1;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_types::ledger::{KeyImageResult, OutputResult};
+
+    fn chunk(chunk_index: u32, num_chunks: u32, num_key_images: u64) -> BatchedLedgerResponse {
+        BatchedLedgerResponse {
+            chunk_index,
+            num_chunks,
+            num_blocks: 10,
+            key_image_results: (0..num_key_images)
+                .map(|idx| KeyImageResult {
+                    key_image: KeyImage::from(idx),
+                    ..Default::default()
+                })
+                .collect(),
+            output_results: vec![OutputResult {
+                index: chunk_index as u64,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_chunks_reassembles_in_order() {
+        let merged = merge_chunks(vec![chunk(1, 3, 1), chunk(0, 3, 2), chunk(2, 3, 0)]).unwrap();
+        assert_eq!(merged.num_chunks, 1);
+        assert_eq!(merged.num_blocks, 10);
+        assert_eq!(merged.key_image_results.len(), 3);
+        assert_eq!(
+            merged
+                .output_results
+                .iter()
+                .map(|result| result.index)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn merge_chunks_rejects_missing_chunks() {
+        assert!(merge_chunks(vec![chunk(0, 3, 1), chunk(2, 3, 1)]).is_err());
+        assert!(merge_chunks(vec![]).is_err());
+    }
+}
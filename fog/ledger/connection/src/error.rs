@@ -22,6 +22,8 @@ pub enum Error {
     Conversion(ConversionError),
     /// grpcio error ({0}): {1}
     Grpc(FogLedgerUri, RetryError<grpcio::Error>),
+    /// Batched response was incomplete: expected {0} chunks, got {1}
+    IncompleteBatchedResponse(u32, usize),
 }
 
 impl From<ProtobufError> for Error {
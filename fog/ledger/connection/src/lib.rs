@@ -3,6 +3,9 @@
 //! Rust objects wrapping the grpc interface
 
 #![deny(missing_docs)]
+mod batched;
+pub use batched::FogBatchedLedgerGrpcClient;
+
 mod block;
 pub use block::FogBlockGrpcClient;
 
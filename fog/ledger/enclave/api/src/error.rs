@@ -40,6 +40,13 @@ pub enum Error {
 
     /// Prost decode error
     ProstDecode,
+
+    /// No batched query is pending for this client session, or it is not in
+    /// the expected state
+    NoPendingBatchedQuery,
+
+    /// A batched query is already pending for this client session
+    BatchedQueryPending,
 }
 
 /// An error when something goes wrong with adding a record
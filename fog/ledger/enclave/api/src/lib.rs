@@ -19,7 +19,8 @@ use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession
 use mc_common::ResponderId;
 use mc_crypto_keys::X25519Public;
 pub use mc_fog_types::ledger::{
-    CheckKeyImagesResponse, GetOutputsResponse, KeyImageResult, KeyImageResultCode, OutputResult,
+    BatchedLedgerResponse, CheckKeyImagesResponse, GetOutputsResponse, KeyImageResult,
+    KeyImageResultCode, OutputResult,
 };
 use mc_sgx_report_cache_api::ReportableEnclave;
 use serde::{Deserialize, Serialize};
@@ -27,6 +28,19 @@ use serde::{Deserialize, Serialize};
 /// A generic result type for enclave calls
 pub type Result<T> = StdResult<T, Error>;
 
+/// The maximum number of key image results in a single chunk of a batched
+/// query response.
+pub const BATCHED_QUERY_KEY_IMAGES_PER_CHUNK: usize = 1000;
+
+/// The maximum number of outputs (with their membership proofs) in a single
+/// chunk of a batched query response.
+pub const BATCHED_QUERY_OUTPUTS_PER_CHUNK: usize = 100;
+
+/// The maximum number of batched queries whose response the enclave keeps
+/// while it is being streamed. Starting a query for a new client session
+/// beyond this evicts the oldest pending query.
+pub const MAX_PENDING_BATCHED_QUERIES: usize = 256;
+
 /// An intermediate struct for holding data required to get outputs for the
 /// client. This is returned by `client_get_outputs` and allows untrusted to
 /// gather data that will be encrypted for the client in `outputs_for_client`.
@@ -98,6 +112,39 @@ pub trait LedgerEnclave: ReportableEnclave {
 
     /// Add a key image data to the oram Using thrm -rf targete key image
     fn add_key_image_data(&self, records: Vec<KeyImageData>) -> Result<()>;
+
+    /// Start a batched query from a client: check the requested key images,
+    /// keeping the results in the enclave until the response was streamed to
+    /// the same client session, and extract the output context to be handed
+    /// back to untrusted so that it could collect the outputs and proofs.
+    ///
+    /// Fails if the previous batched query of the client session was neither
+    /// finished nor cancelled.
+    fn batched_query(
+        &self,
+        msg: EnclaveMessage<ClientSession>,
+        untrusted_keyimagequery_response: UntrustedKeyImageQueryResponse,
+    ) -> Result<OutputContext>;
+
+    /// Provide the outputs and proofs requested in the pending batched query
+    /// of a client session, and return the number of chunks in its response.
+    fn batched_query_data(
+        &self,
+        response: GetOutputsResponse,
+        client: ClientSession,
+    ) -> Result<u32>;
+
+    /// Encrypt the next chunk of the response to the pending batched query of
+    /// a client session, or return None once every chunk was produced. The
+    /// pending query is released after its last chunk.
+    fn batched_query_next_chunk(
+        &self,
+        client: ClientSession,
+    ) -> Result<Option<EnclaveMessage<ClientSession>>>;
+
+    /// Release the pending batched query of a client session, if any, without
+    /// producing the rest of its response.
+    fn batched_query_cancel(&self, client: ClientSession) -> Result<()>;
 }
 
 /// Helper trait which reduces boiler-plate in untrusted side
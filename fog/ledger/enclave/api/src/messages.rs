@@ -101,4 +101,27 @@ pub enum EnclaveCall {
     ///
     ///  Add key image data to the ORAM.
     AddKeyImageData(Vec<KeyImageData>),
+
+    /// The [LedgerEnclave::batched_query()] method.
+    ///
+    /// Start a new batched key image and output query from a client.
+    BatchedQuery(
+        EnclaveMessage<ClientSession>,
+        UntrustedKeyImageQueryResponse,
+    ),
+
+    /// The [LedgerEnclave::batched_query_data()] method.
+    ///
+    /// Provide the outputs and proofs requested in a pending batched query.
+    BatchedQueryData(GetOutputsResponse, ClientSession),
+
+    /// The [LedgerEnclave::batched_query_next_chunk()] method.
+    ///
+    /// Encrypt the next chunk of a batched query response for a client.
+    BatchedQueryNextChunk(ClientSession),
+
+    /// The [LedgerEnclave::batched_query_cancel()] method.
+    ///
+    /// Release a pending batched query.
+    BatchedQueryCancel(ClientSession),
 }
@@ -8,7 +8,7 @@ use aligned_cmov::{
     typenum::{U1024, U16, U32, U4096, U64},
     A8Bytes, CMov,
 };
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 use mc_common::logger::{log, Logger};
 use mc_crypto_rand::McRng;
 use mc_fog_ledger_enclave_api::AddRecordsError;
@@ -194,4 +194,19 @@ impl<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> KeyImageStore<OS
 
         result
     }
+
+    /// Look up a batch of key images, returning the results in the order
+    /// given.
+    ///
+    /// This costs one ORAM read per key image, exactly like calling
+    /// [Self::find_record] for each of them: the oblivious map has no batched
+    /// access, so batching only saves client round trips. Every key image is
+    /// looked up, even when the same key image appears several times, so that
+    /// the access pattern only depends on the size of the batch.
+    pub fn find_records(&mut self, key_images: &[KeyImage]) -> Vec<KeyImageResult> {
+        key_images
+            .iter()
+            .map(|key_image| self.find_record(key_image))
+            .collect()
+    }
 }
@@ -13,7 +13,7 @@
 extern crate alloc;
 
 mod key_image_store;
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use key_image_store::{KeyImageStore, StorageDataSize, StorageMetaSize};
 use mc_attest_core::{IasNonce, Quote, QuoteNonce, Report, TargetInfo, VerificationReport};
 use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
@@ -25,9 +25,12 @@ use mc_crypto_ake_enclave::{AkeEnclaveState, NullIdentity};
 use mc_crypto_keys::X25519Public;
 use mc_fog_ledger_enclave_api::{
     Error, KeyImageData, LedgerEnclave, OutputContext, Result, UntrustedKeyImageQueryResponse,
+    BATCHED_QUERY_KEY_IMAGES_PER_CHUNK, BATCHED_QUERY_OUTPUTS_PER_CHUNK,
+    MAX_PENDING_BATCHED_QUERIES,
 };
 use mc_fog_types::ledger::{
-    CheckKeyImagesRequest, CheckKeyImagesResponse, GetOutputsRequest, GetOutputsResponse,
+    BatchedLedgerRequest, BatchedLedgerResponse, CheckKeyImagesRequest, CheckKeyImagesResponse,
+    GetOutputsRequest, GetOutputsResponse, KeyImageResult, OutputResult,
 };
 use mc_oblivious_traits::ORAMStorageCreator;
 use mc_sgx_compat::sync::Mutex;
@@ -41,6 +44,10 @@ where
     /// The encrypted storage
     key_image_store: Mutex<Option<KeyImageStore<OSC>>>,
 
+    /// Batched queries which were started, but whose response was not fully
+    /// streamed to the client yet
+    pending_batched_queries: Mutex<PendingBatchedQueries>,
+
     /// The enclave state
     ake: AkeEnclaveState<NullIdentity>,
 
@@ -57,12 +64,192 @@ where
     pub fn new(logger: Logger) -> Self {
         Self {
             key_image_store: Mutex::new(None),
+            pending_batched_queries: Mutex::new(Default::default()),
             ake: Default::default(),
             logger,
         }
     }
 }
 
+/// The part of a batched query which was evaluated inside the enclave, kept
+/// until its response has been streamed to the client.
+struct PendingBatchedQuery {
+    /// Orders pending queries by when they were started, for eviction
+    sequence: u64,
+
+    /// The data provided by untrusted when the query was started
+    untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+
+    /// The results of the key image checks
+    key_image_results: Vec<KeyImageResult>,
+
+    /// The outputs and proofs, once untrusted has provided them
+    output_results: Option<Vec<OutputResult>>,
+
+    /// The index of the next chunk to encrypt for the client
+    next_chunk: u32,
+}
+
+/// The batched queries which are in progress, at most one per client session.
+///
+/// The enclave has no trusted clock, so pending queries cannot expire on
+/// their own. Instead, a pending query is removed once its last chunk was
+/// produced, when untrusted cancels it (for instance because streaming to the
+/// client failed or timed out), or when its client session is closed. To bound
+/// the memory used by queries that are abandoned without any of those
+/// happening, the oldest pending query is evicted when a new query would
+/// exceed [MAX_PENDING_BATCHED_QUERIES].
+#[derive(Default)]
+struct PendingBatchedQueries {
+    /// The pending queries, by client session
+    queries: BTreeMap<ClientSession, PendingBatchedQuery>,
+
+    /// The sequence number given to the next query
+    next_sequence: u64,
+}
+
+impl PendingBatchedQueries {
+    /// Start a query for a client session, evicting the oldest query if there
+    /// are too many.
+    ///
+    /// A client session has at most one pending query, so this fails if the
+    /// previous query of the session was neither finished nor cancelled.
+    fn insert(
+        &mut self,
+        client: ClientSession,
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+        key_image_results: Vec<KeyImageResult>,
+    ) -> Result<()> {
+        if self.queries.contains_key(&client) {
+            return Err(Error::BatchedQueryPending);
+        }
+        if self.queries.len() >= MAX_PENDING_BATCHED_QUERIES {
+            let oldest = self
+                .queries
+                .iter()
+                .min_by_key(|(_, query)| query.sequence)
+                .map(|(client, _)| client.clone());
+            if let Some(oldest) = oldest {
+                self.queries.remove(&oldest);
+            }
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.queries.insert(
+            client,
+            PendingBatchedQuery {
+                sequence,
+                untrusted_key_image_query_response,
+                key_image_results,
+                output_results: None,
+                next_chunk: 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Provide the outputs of a pending query, and return how many chunks its
+    /// response has.
+    fn set_output_results(
+        &mut self,
+        client: &ClientSession,
+        output_results: Vec<OutputResult>,
+    ) -> Result<u32> {
+        let query = self
+            .queries
+            .get_mut(client)
+            .filter(|query| query.output_results.is_none())
+            .ok_or(Error::NoPendingBatchedQuery)?;
+        let num_chunks =
+            num_batched_response_chunks(query.key_image_results.len(), output_results.len());
+        query.output_results = Some(output_results);
+        Ok(num_chunks)
+    }
+
+    /// Build the next chunk of the response to a pending query, or return None
+    /// if all chunks were already produced. The query is removed once its last
+    /// chunk has been produced.
+    fn next_chunk(&mut self, client: &ClientSession) -> Result<Option<BatchedLedgerResponse>> {
+        let query = match self.queries.get_mut(client) {
+            Some(query) => query,
+            None => return Ok(None),
+        };
+        let output_results = query
+            .output_results
+            .as_ref()
+            .ok_or(Error::NoPendingBatchedQuery)?;
+
+        let chunk = batched_response_chunk(
+            &query.untrusted_key_image_query_response,
+            &query.key_image_results,
+            output_results,
+            query.next_chunk,
+        );
+        query.next_chunk += 1;
+        if query.next_chunk >= chunk.num_chunks {
+            self.queries.remove(client);
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Remove the pending query of a client session, if any
+    fn remove(&mut self, client: &ClientSession) {
+        self.queries.remove(client);
+    }
+}
+
+/// The number of chunks in the response to a batched query.
+///
+/// Key image results come first, followed by output results, and no chunk
+/// mixes the two. There is always at least one chunk, so that clients learn
+/// the ledger metadata even when the query was empty.
+fn num_batched_response_chunks(num_key_images: usize, num_outputs: usize) -> u32 {
+    let num_key_image_chunks = (num_key_images + BATCHED_QUERY_KEY_IMAGES_PER_CHUNK - 1)
+        / BATCHED_QUERY_KEY_IMAGES_PER_CHUNK;
+    let num_output_chunks =
+        (num_outputs + BATCHED_QUERY_OUTPUTS_PER_CHUNK - 1) / BATCHED_QUERY_OUTPUTS_PER_CHUNK;
+    let num_chunks = num_key_image_chunks + num_output_chunks;
+    core::cmp::max(num_chunks, 1) as u32
+}
+
+/// Build the chunk with the given index of the response to a batched query.
+/// See [num_batched_response_chunks] for how results are split into chunks.
+fn batched_response_chunk(
+    untrusted: &UntrustedKeyImageQueryResponse,
+    key_image_results: &[KeyImageResult],
+    output_results: &[OutputResult],
+    chunk_index: u32,
+) -> BatchedLedgerResponse {
+    let mut chunk = BatchedLedgerResponse {
+        chunk_index,
+        num_chunks: num_batched_response_chunks(key_image_results.len(), output_results.len()),
+        num_blocks: untrusted.highest_processed_block_count,
+        global_txo_count: untrusted.last_known_block_cumulative_txo_count,
+        latest_block_version: untrusted.latest_block_version,
+        max_block_version: untrusted.max_block_version,
+        key_image_results: Default::default(),
+        output_results: Default::default(),
+    };
+
+    let mut key_image_chunks = key_image_results.chunks(BATCHED_QUERY_KEY_IMAGES_PER_CHUNK);
+    let num_key_image_chunks = key_image_chunks.len();
+    let chunk_index = chunk_index as usize;
+    if chunk_index < num_key_image_chunks {
+        chunk.key_image_results = key_image_chunks
+            .nth(chunk_index)
+            .unwrap_or_default()
+            .to_vec();
+    } else {
+        chunk.output_results = output_results
+            .chunks(BATCHED_QUERY_OUTPUTS_PER_CHUNK)
+            .nth(chunk_index - num_key_image_chunks)
+            .unwrap_or_default()
+            .to_vec();
+    }
+    chunk
+}
+
 /// Implementation of the reportable enclave for sgxledger enclave
 impl<OSC> ReportableEnclave for SgxLedgerEnclave<OSC>
 where
@@ -108,6 +295,7 @@ where
     }
 
     fn client_close(&self, channel_id: ClientSession) -> Result<()> {
+        self.pending_batched_queries.lock()?.remove(&channel_id);
         Ok(self.ake.client_close(channel_id)?)
     }
 
@@ -164,11 +352,9 @@ where
             let mut lk = self.key_image_store.lock()?;
             let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
 
-            resp.results = req
-                .queries
-                .iter() //  get the key images used to find the key image data using the oram
-                .map(|key| store.find_record(&key.key_image))
-                .collect();
+            //  get the key images used to find the key image data using the oram
+            let key_images: Vec<_> = req.queries.iter().map(|key| key.key_image).collect();
+            resp.results = store.find_records(&key_images);
         }
 
         let response_plaintext_bytes = mc_util_serial::encode(&resp);
@@ -191,6 +377,71 @@ where
 
         Ok(())
     }
+
+    fn batched_query(
+        &self,
+        msg: EnclaveMessage<ClientSession>,
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+    ) -> Result<OutputContext> {
+        let channel_id = msg.channel_id.clone();
+        let user_plaintext = self.ake.client_decrypt(msg)?;
+
+        let req: BatchedLedgerRequest = mc_util_serial::decode(&user_plaintext).map_err(|e| {
+            log::error!(self.logger, "Could not decode user request: {}", e);
+            Error::ProstDecode
+        })?;
+
+        let key_image_results = {
+            let mut lk = self.key_image_store.lock()?;
+            let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
+
+            let key_images: Vec<_> = req
+                .key_image_queries
+                .iter()
+                .map(|query| query.key_image)
+                .collect();
+            store.find_records(&key_images)
+        };
+
+        self.pending_batched_queries.lock()?.insert(
+            channel_id,
+            untrusted_key_image_query_response,
+            key_image_results,
+        )?;
+
+        Ok(OutputContext {
+            indexes: req.output_indices,
+            merkle_root_block: req.merkle_root_block,
+        })
+    }
+
+    fn batched_query_data(
+        &self,
+        response: GetOutputsResponse,
+        client: ClientSession,
+    ) -> Result<u32> {
+        self.pending_batched_queries
+            .lock()?
+            .set_output_results(&client, response.results)
+    }
+
+    fn batched_query_next_chunk(
+        &self,
+        client: ClientSession,
+    ) -> Result<Option<EnclaveMessage<ClientSession>>> {
+        let chunk = match self.pending_batched_queries.lock()?.next_chunk(&client)? {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        };
+
+        let chunk_bytes = mc_util_serial::encode(&chunk);
+        Ok(Some(self.ake.client_encrypt(&client, &[], &chunk_bytes)?))
+    }
+
+    fn batched_query_cancel(&self, client: ClientSession) -> Result<()> {
+        self.pending_batched_queries.lock()?.remove(&client);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +450,7 @@ mod tests {
     use key_image_store::KeyImageStore;
     use mc_common::logger::create_root_logger;
     use mc_fog_ledger_enclave_api::KeyImageData;
+    use mc_fog_types::ledger::KeyImageResultCode;
     use mc_oblivious_traits::HeapORAMStorageCreator;
     use mc_transaction_core::ring_signature::KeyImage;
     // Test that we were able to add key image record to the oram
@@ -298,4 +550,230 @@ mod tests {
             mc_fog_types::ledger::KeyImageResultCode::NotSpent as u32
         );
     }
+
+    fn key_image_result(i: u64) -> KeyImageResult {
+        KeyImageResult {
+            key_image: KeyImage::from(i),
+            spent_at: i,
+            timestamp: i,
+            timestamp_result_code: 1,
+            key_image_result_code: KeyImageResultCode::Spent as u32,
+        }
+    }
+
+    fn output_result(i: u64) -> OutputResult {
+        OutputResult {
+            index: i,
+            result_code: 1,
+            output: Default::default(),
+            proof: Default::default(),
+        }
+    }
+
+    /// Produce every chunk of a pending query
+    fn drain_chunks(
+        pending: &mut PendingBatchedQueries,
+        client: &ClientSession,
+    ) -> Vec<BatchedLedgerResponse> {
+        let mut chunks = Vec::new();
+        while let Some(chunk) = pending.next_chunk(client).unwrap() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    #[test]
+    fn test_find_records() {
+        let logger = create_root_logger();
+        let mut key_image_store = KeyImageStore::<HeapORAMStorageCreator>::new(1024, logger);
+        key_image_store
+            .add_record(&KeyImage::from(1), 10, 100)
+            .unwrap();
+        key_image_store
+            .add_record(&KeyImage::from(2), 20, 200)
+            .unwrap();
+
+        // Duplicates and misses are answered in place, in order.
+        let key_images = [
+            KeyImage::from(2),
+            KeyImage::from(3),
+            KeyImage::from(1),
+            KeyImage::from(2),
+        ];
+        let results = key_image_store.find_records(&key_images);
+        assert_eq!(results.len(), key_images.len());
+        for (key_image, result) in key_images.iter().zip(results.iter()) {
+            assert_eq!(&result.key_image, key_image);
+            assert_eq!(result, &key_image_store.find_record(key_image));
+        }
+        assert_eq!(results[0].spent_at, 20);
+        assert_eq!(results[0].timestamp, 200);
+        assert_eq!(
+            results[1].key_image_result_code,
+            KeyImageResultCode::NotSpent as u32
+        );
+        assert_eq!(results[1].spent_at, u64::MAX);
+        assert_eq!(results[2].spent_at, 10);
+        assert_eq!(
+            results[3].key_image_result_code,
+            KeyImageResultCode::Spent as u32
+        );
+
+        assert!(key_image_store.find_records(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_batched_response_empty() {
+        let untrusted = UntrustedKeyImageQueryResponse {
+            highest_processed_block_count: 10,
+            last_known_block_cumulative_txo_count: 20,
+            latest_block_version: 2,
+            max_block_version: 3,
+        };
+        let client = ClientSession::from(vec![1u8]);
+
+        let mut pending = PendingBatchedQueries::default();
+        pending.insert(client.clone(), untrusted, vec![]).unwrap();
+        assert_eq!(pending.set_output_results(&client, vec![]).unwrap(), 1);
+
+        let chunks = drain_chunks(&mut pending, &client);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].chunk_index, 0);
+        assert_eq!(chunks[0].num_chunks, 1);
+        assert_eq!(chunks[0].num_blocks, 10);
+        assert_eq!(chunks[0].global_txo_count, 20);
+        assert_eq!(chunks[0].latest_block_version, 2);
+        assert_eq!(chunks[0].max_block_version, 3);
+
+        // The query is gone once its last chunk was produced.
+        assert!(pending.queries.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_batched_response() {
+        let untrusted = UntrustedKeyImageQueryResponse::default();
+        let num_key_images = BATCHED_QUERY_KEY_IMAGES_PER_CHUNK as u64 + 1;
+        let num_outputs = 2 * BATCHED_QUERY_OUTPUTS_PER_CHUNK as u64;
+        let client = ClientSession::from(vec![1u8]);
+
+        let mut pending = PendingBatchedQueries::default();
+        pending
+            .insert(
+                client.clone(),
+                untrusted,
+                (0..num_key_images).map(key_image_result).collect(),
+            )
+            .unwrap();
+
+        // Chunks cannot be produced before the outputs are provided.
+        assert_eq!(
+            pending.next_chunk(&client),
+            Err(Error::NoPendingBatchedQuery)
+        );
+        assert_eq!(
+            pending
+                .set_output_results(&client, (0..num_outputs).map(output_result).collect())
+                .unwrap(),
+            4
+        );
+
+        // Two chunks of key images, followed by two chunks of outputs.
+        let chunks = drain_chunks(&mut pending, &client);
+        assert_eq!(chunks.len(), 4);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.chunk_index, i as u32);
+            assert_eq!(chunk.num_chunks, 4);
+        }
+        assert_eq!(
+            chunks[0].key_image_results.len(),
+            BATCHED_QUERY_KEY_IMAGES_PER_CHUNK
+        );
+        assert_eq!(chunks[1].key_image_results.len(), 1);
+        assert!(chunks[1].output_results.is_empty());
+        assert!(chunks[2].key_image_results.is_empty());
+        assert_eq!(
+            chunks[2].output_results.len(),
+            BATCHED_QUERY_OUTPUTS_PER_CHUNK
+        );
+
+        // Concatenating the chunks gives back the results, in order.
+        let key_image_results: Vec<_> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.key_image_results.clone())
+            .collect();
+        assert_eq!(
+            key_image_results,
+            (0..num_key_images)
+                .map(key_image_result)
+                .collect::<Vec<_>>()
+        );
+        let output_indices: Vec<_> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.output_results.iter().map(|result| result.index))
+            .collect();
+        assert_eq!(output_indices, (0..num_outputs).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_pending_batched_queries_are_bounded() {
+        let untrusted = UntrustedKeyImageQueryResponse::default();
+        let client = |i: usize| ClientSession::from(i.to_le_bytes().to_vec());
+
+        let mut pending = PendingBatchedQueries::default();
+        for i in 0..MAX_PENDING_BATCHED_QUERIES {
+            pending
+                .insert(client(i), untrusted.clone(), vec![])
+                .unwrap();
+        }
+        assert_eq!(pending.queries.len(), MAX_PENDING_BATCHED_QUERIES);
+
+        // A new session evicts the oldest pending query.
+        pending
+            .insert(client(MAX_PENDING_BATCHED_QUERIES), untrusted, vec![])
+            .unwrap();
+        assert_eq!(pending.queries.len(), MAX_PENDING_BATCHED_QUERIES);
+        assert!(!pending.queries.contains_key(&client(0)));
+        assert!(pending.queries.contains_key(&client(1)));
+        assert_eq!(
+            pending.set_output_results(&client(0), vec![]),
+            Err(Error::NoPendingBatchedQuery)
+        );
+
+        // Cancelled queries are removed.
+        pending.remove(&client(1));
+        assert!(!pending.queries.contains_key(&client(1)));
+    }
+
+    #[test]
+    fn test_pending_batched_query_is_not_replaced() {
+        let untrusted = UntrustedKeyImageQueryResponse::default();
+        let client = ClientSession::from(vec![1u8]);
+
+        let mut pending = PendingBatchedQueries::default();
+        pending
+            .insert(client.clone(), untrusted.clone(), vec![key_image_result(1)])
+            .unwrap();
+
+        // A second query for the same session is rejected, and the first one is
+        // left as it was.
+        assert_eq!(
+            pending.insert(client.clone(), untrusted.clone(), vec![key_image_result(2)]),
+            Err(Error::BatchedQueryPending)
+        );
+        pending.set_output_results(&client, vec![]).unwrap();
+        let chunks = drain_chunks(&mut pending, &client);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].key_image_results, vec![key_image_result(1)]);
+
+        // Once the first query is finished, the session can start another one.
+        pending
+            .insert(client.clone(), untrusted.clone(), vec![key_image_result(2)])
+            .unwrap();
+
+        // The same goes for a cancelled query.
+        pending.remove(&client);
+        pending
+            .insert(client, untrusted, vec![key_image_result(3)])
+            .unwrap();
+    }
 }
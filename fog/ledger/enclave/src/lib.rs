@@ -187,6 +187,40 @@ impl LedgerEnclave for LedgerSgxEnclave {
         let outbuf = self.enclave_call(&inbuf)?;
         mc_util_serial::deserialize(&outbuf[..])?
     }
+
+    fn batched_query(
+        &self,
+        msg: EnclaveMessage<ClientSession>,
+        untrusted_keyimagequery_response: UntrustedKeyImageQueryResponse,
+    ) -> Result<OutputContext> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::BatchedQuery(
+            msg,
+            untrusted_keyimagequery_response,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn batched_query_data(&self, resp: GetOutputsResponse, client: ClientSession) -> Result<u32> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::BatchedQueryData(resp, client))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn batched_query_next_chunk(
+        &self,
+        client: ClientSession,
+    ) -> Result<Option<EnclaveMessage<ClientSession>>> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::BatchedQueryNextChunk(client))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn batched_query_cancel(&self, client: ClientSession) -> Result<()> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::BatchedQueryCancel(client))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
 }
 
 extern "C" {
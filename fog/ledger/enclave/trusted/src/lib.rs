@@ -61,6 +61,17 @@ pub fn ecall_dispatcher(inbuf: &[u8]) -> Result<Vec<u8>, sgx_status_t> {
         }
         // Add Key Image Data
         EnclaveCall::AddKeyImageData(records) => serialize(&ENCLAVE.add_key_image_data(records)),
+        // Batched key image and output queries
+        EnclaveCall::BatchedQuery(req, untrusted_keyimagequery_response) => {
+            serialize(&ENCLAVE.batched_query(req, untrusted_keyimagequery_response))
+        }
+        EnclaveCall::BatchedQueryData(resp, client) => {
+            serialize(&ENCLAVE.batched_query_data(resp, client))
+        }
+        EnclaveCall::BatchedQueryNextChunk(client) => {
+            serialize(&ENCLAVE.batched_query_next_chunk(client))
+        }
+        EnclaveCall::BatchedQueryCancel(client) => serialize(&ENCLAVE.batched_query_cancel(client)),
     }
     .or(Err(sgx_status_t::SGX_ERROR_UNEXPECTED))
 }
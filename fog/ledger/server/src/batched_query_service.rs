@@ -0,0 +1,217 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{server::DbPollSharedState, MerkleProofService};
+use futures::{FutureExt, SinkExt, TryFutureExt};
+use grpcio::{RpcContext, RpcStatus, ServerStreamingSink, UnarySink, WriteFlags};
+use mc_attest_api::attest::{AuthMessage, Message};
+use mc_attest_enclave_api::ClientSession;
+use mc_common::logger::{log, Logger};
+use mc_fog_api::ledger_grpc::FogBatchedLedgerApi;
+use mc_fog_ledger_enclave::LedgerEnclaveProxy;
+use mc_fog_ledger_enclave_api::Error as EnclaveError;
+use mc_ledger_db::Ledger;
+use mc_util_grpc::{
+    rpc_internal_error, rpc_invalid_arg_error, rpc_logger, rpc_permissions_error,
+    rpc_precondition_error, send_result, Authenticator,
+};
+use mc_util_metrics::SVC_COUNTERS;
+use std::sync::{Arc, Mutex};
+
+/// Answers key image checks and merkle proof requests from a single attested
+/// request, streaming the encrypted response back in chunks.
+///
+/// Each chunk is only encrypted by the enclave once the previous one was
+/// handed to grpc, so the response is never held in full outside the enclave.
+#[derive(Clone)]
+pub struct BatchedQueryService<L: Ledger + Clone, E: LedgerEnclaveProxy> {
+    enclave: E,
+    /// Used to collect the outputs and proofs requested in a batched query
+    merkle_proof_service: MerkleProofService<L, E>,
+    authenticator: Arc<dyn Authenticator + Send + Sync>,
+    logger: Logger,
+    /// Shared state from db polling thread.
+    db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
+}
+
+impl<L: Ledger + Clone, E: LedgerEnclaveProxy> BatchedQueryService<L, E> {
+    pub fn new(
+        ledger: L,
+        enclave: E,
+        db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
+        authenticator: Arc<dyn Authenticator + Send + Sync>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            merkle_proof_service: MerkleProofService::new(
+                ledger,
+                enclave.clone(),
+                authenticator.clone(),
+                logger.clone(),
+            ),
+            enclave,
+            authenticator,
+            logger,
+            db_poll_shared_state,
+        }
+    }
+
+    /// Unwrap and forward to enclave, and collect the requested outputs.
+    ///
+    /// Returns the client session whose response is ready to be streamed.
+    fn batched_query_auth(&mut self, request: Message) -> Result<ClientSession, RpcStatus> {
+        mc_common::trace_time!(self.logger, "Batched query");
+
+        let untrusted_query_response = self
+            .db_poll_shared_state
+            .lock()
+            .expect("mutex poisoned")
+            .untrusted_key_image_query_response();
+
+        let client = ClientSession::from(request.channel_id.clone());
+
+        let output_context = self
+            .enclave
+            .batched_query(request.into(), untrusted_query_response)
+            .map_err(|err| enclave_err_to_rpc_status("batched_query", err, &self.logger))?;
+
+        let result = self
+            .merkle_proof_service
+            .get_outputs_impl(output_context)
+            .and_then(|output_data| {
+                self.enclave
+                    .batched_query_data(output_data, client.clone())
+                    .map_err(|err| {
+                        enclave_err_to_rpc_status("batched_query_data", err, &self.logger)
+                    })
+            });
+        match result {
+            Ok(num_chunks) => {
+                log::trace!(self.logger, "Streaming {} chunks", num_chunks);
+                Ok(client)
+            }
+            Err(rpc_status) => {
+                cancel_batched_query(&self.enclave, client, &self.logger);
+                Err(rpc_status)
+            }
+        }
+    }
+}
+
+/// Release the enclave's pending query for a client session
+fn cancel_batched_query(enclave: &impl LedgerEnclaveProxy, client: ClientSession, logger: &Logger) {
+    if let Err(err) = enclave.batched_query_cancel(client) {
+        log::error!(logger, "Failed to cancel batched query: {}", err);
+    }
+}
+
+// Helper function that is common
+fn enclave_err_to_rpc_status(context: &str, src: EnclaveError, logger: &Logger) -> RpcStatus {
+    // Treat prost-decode error as an invalid arg,
+    // treat attest error as permission denied,
+    // treat a query overlapping a pending one as a failed precondition,
+    // everything else is an internal error
+    match src {
+        EnclaveError::ProstDecode => rpc_invalid_arg_error(context, "Prost decode failed", logger),
+        EnclaveError::BatchedQueryPending => rpc_precondition_error(context, src, logger),
+        EnclaveError::Attest(err) => rpc_permissions_error(context, err, logger),
+        other => rpc_internal_error(context, format!("{}", &other), logger),
+    }
+}
+
+impl<L: Ledger + Clone, E: LedgerEnclaveProxy> FogBatchedLedgerApi for BatchedQueryService<L, E> {
+    fn batched_query(
+        &mut self,
+        ctx: RpcContext,
+        request: Message,
+        mut sink: ServerStreamingSink<Message>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            let result = match self.authenticator.authenticate_rpc(&ctx) {
+                Ok(_) => self.batched_query_auth(request),
+                Err(err) => err.into(),
+            };
+
+            let logger = logger.clone();
+            match result {
+                Ok(client) => {
+                    // Pull chunks out of the enclave one at a time, as grpc is ready to
+                    // send them. If the client goes away or the call's deadline passes,
+                    // sending fails and the pending query is cancelled.
+                    let enclave = self.enclave.clone();
+                    let service_logger = self.logger.clone();
+                    let send_chunks = async move {
+                        loop {
+                            match enclave.batched_query_next_chunk(client.clone()) {
+                                Ok(Some(chunk)) => {
+                                    if let Err(err) =
+                                        sink.send((chunk.into(), WriteFlags::default())).await
+                                    {
+                                        cancel_batched_query(&enclave, client, &service_logger);
+                                        return Err(err);
+                                    }
+                                }
+                                Ok(None) => return sink.close().await,
+                                Err(err) => {
+                                    cancel_batched_query(&enclave, client, &service_logger);
+                                    let rpc_status = enclave_err_to_rpc_status(
+                                        "batched_query_next_chunk",
+                                        err,
+                                        &service_logger,
+                                    );
+                                    return sink.fail(rpc_status).await;
+                                }
+                            }
+                        }
+                    }
+                    .map_err(move |err| log::error!(logger, "failed to reply: {:?}", err))
+                    .map(|_| ());
+                    ctx.spawn(send_chunks);
+                }
+                Err(rpc_status) => {
+                    let fail = sink
+                        .fail(rpc_status)
+                        .map_err(move |err| log::error!(logger, "failed to reply: {:?}", err))
+                        .map(|_| ());
+                    ctx.spawn(fail);
+                }
+            }
+        })
+    }
+
+    fn auth(&mut self, ctx: RpcContext, request: AuthMessage, sink: UnarySink<AuthMessage>) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                return send_result(ctx, sink, err.into(), logger);
+            }
+
+            // TODO: Use the prost message directly, once available
+            match self.enclave.client_accept(request.into()) {
+                Ok((response, _session_id)) => {
+                    send_result(ctx, sink, Ok(response.into()), logger);
+                }
+                Err(client_error) => {
+                    // This is debug because there's no requirement on the remote party to trigger
+                    // it.
+                    log::info!(
+                        logger,
+                        "LedgerEnclave::client_accept failed: {}",
+                        client_error
+                    );
+                    // TODO: increment failed inbound peering counter.
+                    send_result(
+                        ctx,
+                        sink,
+                        Err(rpc_permissions_error(
+                            "client_auth",
+                            "Permission denied",
+                            logger,
+                        )),
+                        logger,
+                    );
+                }
+            }
+        });
+    }
+}
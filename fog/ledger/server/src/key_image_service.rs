@@ -5,11 +5,10 @@ use mc_attest_api::{
     attest,
     attest::{AuthMessage, Message},
 };
-use mc_blockchain_types::MAX_BLOCK_VERSION;
 use mc_common::logger::{log, Logger};
 use mc_fog_api::ledger_grpc::FogKeyImageApi;
 use mc_fog_ledger_enclave::LedgerEnclaveProxy;
-use mc_fog_ledger_enclave_api::{Error as EnclaveError, UntrustedKeyImageQueryResponse};
+use mc_fog_ledger_enclave_api::Error as EnclaveError;
 use mc_ledger_db::Ledger;
 use mc_util_grpc::{
     rpc_internal_error, rpc_invalid_arg_error, rpc_logger, rpc_permissions_error, send_result,
@@ -73,25 +72,11 @@ impl<L: Ledger + Clone, E: LedgerEnclaveProxy> KeyImageService<L, E> {
     ) -> Result<attest::Message, RpcStatus> {
         log::trace!(self.logger, "Getting encrypted request");
 
-        let (
-            highest_processed_block_count,
-            last_known_block_cumulative_txo_count,
-            latest_block_version,
-        ) = {
-            let shared_state = self.db_poll_shared_state.lock().expect("mutex poisoned");
-            (
-                shared_state.highest_processed_block_count,
-                shared_state.last_known_block_cumulative_txo_count,
-                shared_state.latest_block_version,
-            )
-        };
-
-        let untrusted_query_response = UntrustedKeyImageQueryResponse {
-            highest_processed_block_count,
-            last_known_block_cumulative_txo_count,
-            latest_block_version,
-            max_block_version: latest_block_version.max(*MAX_BLOCK_VERSION),
-        };
+        let untrusted_query_response = self
+            .db_poll_shared_state
+            .lock()
+            .expect("mutex poisoned")
+            .untrusted_key_image_query_response();
 
         let result_blob = self
             .enclave
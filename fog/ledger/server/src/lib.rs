@@ -1,5 +1,6 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
+mod batched_query_service;
 mod block_service;
 mod config;
 mod counters;
@@ -9,6 +10,7 @@ mod merkle_proof_service;
 mod server;
 mod untrusted_tx_out_service;
 
+pub use batched_query_service::BatchedQueryService;
 pub use block_service::BlockService;
 pub use config::LedgerServerConfig;
 pub use key_image_service::KeyImageService;
@@ -92,7 +92,7 @@ impl<L: Ledger + Clone, E: LedgerEnclaveProxy> MerkleProofService<L, E> {
         Ok(result.into())
     }
 
-    fn get_outputs_impl(
+    pub(crate) fn get_outputs_impl(
         &mut self,
         output_context: OutputContext,
     ) -> Result<GetOutputsResponse, RpcStatus> {
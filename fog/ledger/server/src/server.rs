@@ -1,19 +1,21 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use crate::{
-    config::LedgerServerConfig, counters, db_fetcher::DbFetcher, BlockService, KeyImageService,
-    MerkleProofService, UntrustedTxOutService,
+    config::LedgerServerConfig, counters, db_fetcher::DbFetcher, BatchedQueryService, BlockService,
+    KeyImageService, MerkleProofService, UntrustedTxOutService,
 };
 use displaydoc::Display;
 use futures::executor::block_on;
 use grpcio::Error as GrpcError;
 use mc_attest_net::RaClient;
+use mc_blockchain_types::MAX_BLOCK_VERSION;
 use mc_common::{
     logger::{log, Logger},
     time::TimeProvider,
 };
 use mc_fog_api::ledger_grpc;
 use mc_fog_ledger_enclave::{Error as EnclaveError, LedgerEnclaveProxy};
+use mc_fog_ledger_enclave_api::UntrustedKeyImageQueryResponse;
 use mc_ledger_db::LedgerDB;
 use mc_sgx_report_cache_untrusted::{Error as ReportCacheError, ReportCacheThread};
 use mc_util_encodings::Error as EncodingError;
@@ -70,6 +72,7 @@ pub struct LedgerServer<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'stat
     server: Option<grpcio::Server>,
     key_image_service: KeyImageService<LedgerDB, E>,
     merkle_proof_service: MerkleProofService<LedgerDB, E>,
+    batched_query_service: BatchedQueryService<LedgerDB, E>,
     block_service: BlockService<LedgerDB>,
     untrusted_tx_out_service: UntrustedTxOutService<LedgerDB>,
     enclave: E,
@@ -106,7 +109,7 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             ledger.clone(),
             watcher.clone(),
             enclave.clone(),
-            shared_state,
+            shared_state.clone(),
             client_authenticator.clone(),
             logger.clone(),
         );
@@ -116,6 +119,13 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             client_authenticator.clone(),
             logger.clone(),
         );
+        let batched_query_service = BatchedQueryService::new(
+            ledger.clone(),
+            enclave.clone(),
+            shared_state,
+            client_authenticator.clone(),
+            logger.clone(),
+        );
         let block_service = BlockService::new(
             ledger.clone(),
             watcher.clone(),
@@ -134,6 +144,7 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             server: None,
             key_image_service,
             merkle_proof_service,
+            batched_query_service,
             block_service,
             untrusted_tx_out_service,
             enclave,
@@ -176,6 +187,8 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
                 ledger_grpc::create_fog_key_image_api(self.key_image_service.clone());
             let merkle_proof_service =
                 ledger_grpc::create_fog_merkle_proof_api(self.merkle_proof_service.clone());
+            let batched_query_service =
+                ledger_grpc::create_fog_batched_ledger_api(self.batched_query_service.clone());
             let block_service = ledger_grpc::create_fog_block_api(self.block_service.clone());
             let untrusted_tx_out_service =
                 ledger_grpc::create_fog_untrusted_tx_out_api(self.untrusted_tx_out_service.clone());
@@ -196,6 +209,7 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             let server_builder = grpcio::ServerBuilder::new(env)
                 .register_service(key_image_service)
                 .register_service(merkle_proof_service)
+                .register_service(batched_query_service)
                 .register_service(block_service)
                 .register_service(untrusted_tx_out_service)
                 .register_service(health_service)
@@ -251,3 +265,16 @@ pub struct DbPollSharedState {
     /// The latest value of `block_version` in the blockchain
     pub latest_block_version: u32,
 }
+
+impl DbPollSharedState {
+    /// The data which the enclave needs from untrusted to answer key image
+    /// queries, as of the last db poll.
+    pub fn untrusted_key_image_query_response(&self) -> UntrustedKeyImageQueryResponse {
+        UntrustedKeyImageQueryResponse {
+            highest_processed_block_count: self.highest_processed_block_count,
+            last_known_block_cumulative_txo_count: self.last_known_block_cumulative_txo_count,
+            latest_block_version: self.latest_block_version,
+            max_block_version: self.latest_block_version.max(*MAX_BLOCK_VERSION),
+        }
+    }
+}
@@ -16,8 +16,8 @@ use mc_common::{
 use mc_crypto_keys::{CompressedRistrettoPublic, Ed25519Pair, RistrettoPrivate};
 use mc_fog_api::ledger::TxOutResultCode;
 use mc_fog_ledger_connection::{
-    FogKeyImageGrpcClient, FogMerkleProofGrpcClient, FogUntrustedLedgerGrpcClient,
-    KeyImageResultExtension, OutputResultExtension,
+    FogBatchedLedgerGrpcClient, FogKeyImageGrpcClient, FogMerkleProofGrpcClient,
+    FogUntrustedLedgerGrpcClient, KeyImageResultExtension, OutputResultExtension,
 };
 use mc_fog_ledger_enclave::LedgerSgxEnclave;
 use mc_fog_ledger_server::{LedgerServer, LedgerServerConfig};
@@ -439,6 +439,197 @@ fn fog_ledger_key_images_test(logger: Logger) {
     }
 }
 
+// Test that a batched query streamed back by a fog ledger server checks key
+// images and gets valid merkle proofs, through the real ledger enclave
+#[test_with_logger]
+fn fog_ledger_batched_query_test(logger: Logger) {
+    let base_port = 3270;
+
+    let mut rng = RngType::from_seed([0u8; 32]);
+
+    for block_version in BlockVersion::iterator() {
+        let alice = AccountKey::random_with_fog(&mut rng);
+        let bob = AccountKey::random_with_fog(&mut rng);
+
+        let recipients = vec![alice.default_subaddress(), bob.default_subaddress()];
+
+        let keys: Vec<KeyImage> = (0..20).map(|x| KeyImage::from(x as u64)).collect();
+
+        // Make LedgerDB
+        let ledger_dir = TempDir::new("fog-ledger").expect("Could not get test_ledger tempdir");
+        let db_full_path = ledger_dir.path();
+        let mut ledger = generate_ledger_db(db_full_path);
+
+        let (mut watcher, watcher_dir) = setup_watcher_db(logger.clone());
+
+        // Populate ledger with some data
+        // Origin block cannot have key images
+        add_block_to_ledger_db(
+            block_version,
+            &mut ledger,
+            &recipients,
+            &[],
+            &mut rng,
+            &mut watcher,
+        );
+        add_block_to_ledger_db(
+            block_version,
+            &mut ledger,
+            &recipients,
+            &keys[0..2],
+            &mut rng,
+            &mut watcher,
+        );
+        let num_blocks = add_block_to_ledger_db(
+            block_version,
+            &mut ledger,
+            &recipients,
+            &keys[3..6],
+            &mut rng,
+            &mut watcher,
+        );
+
+        {
+            // Make LedgerServer
+            let client_uri = FogLedgerUri::from_str(&format!(
+                "insecure-fog-ledger://127.0.0.1:{}",
+                base_port + 7
+            ))
+            .unwrap();
+            let config = LedgerServerConfig {
+                ledger_db: db_full_path.to_path_buf(),
+                watcher_db: watcher_dir,
+                admin_listen_uri: Default::default(),
+                client_listen_uri: client_uri.clone(),
+                client_responder_id: ResponderId::from_str(&client_uri.addr()).unwrap(),
+                ias_spid: Default::default(),
+                ias_api_key: Default::default(),
+                client_auth_token_secret: None,
+                client_auth_token_max_lifetime: Default::default(),
+                omap_capacity: OMAP_CAPACITY,
+            };
+
+            let enclave = LedgerSgxEnclave::new(
+                get_enclave_path(mc_fog_ledger_enclave::ENCLAVE_FILE),
+                &config.client_responder_id,
+                OMAP_CAPACITY,
+                logger.clone(),
+            );
+
+            let ra_client =
+                AttestClient::new(&config.ias_api_key).expect("Could not create IAS client");
+
+            let grpc_env = Arc::new(grpcio::EnvBuilder::new().build());
+
+            let mut ledger_server = LedgerServer::new(
+                config,
+                enclave,
+                ledger.clone(),
+                watcher,
+                ra_client,
+                SystemTimeProvider::default(),
+                logger.clone(),
+            );
+
+            ledger_server
+                .start()
+                .expect("Failed starting ledger server");
+
+            // Make ledger enclave client
+            let mut mr_signer_verifier =
+                MrSignerVerifier::from(mc_fog_ledger_enclave_measurement::sigstruct());
+            mr_signer_verifier.allow_hardening_advisory("INTEL-SA-00334");
+
+            let mut verifier = Verifier::default();
+            verifier.mr_signer(mr_signer_verifier).debug(DEBUG_ENCLAVE);
+
+            let mut client = FogBatchedLedgerGrpcClient::new(
+                client_uri,
+                GRPC_RETRY_CONFIG,
+                verifier,
+                grpc_env,
+                logger.clone(),
+            );
+
+            let query_keys = [keys[0], keys[4], keys[19]];
+            let query_indices = vec![0u64, 1u64, 2u64, 3u64, 4u64, 5u64, 9u64];
+
+            let mut response = client
+                .batched_query(&query_keys, query_indices.clone(), num_blocks - 1)
+                .expect("batched_query failed");
+
+            let mut n = 1;
+            // adding a delay to give fog ledger time to fully initialize
+            while response.num_blocks != num_blocks {
+                sleep(Duration::from_secs(10));
+                response = client
+                    .batched_query(&query_keys, query_indices.clone(), num_blocks - 1)
+                    .expect("batched_query failed");
+
+                // panic on the 20th time
+                n += 1;
+                if n > 20 {
+                    panic!("Fog ledger not  fully initialized");
+                }
+            }
+
+            // The chunks were reassembled into a single response
+            assert_eq!(response.chunk_index, 0);
+            assert_eq!(response.num_chunks, 1);
+            assert_eq!(response.global_txo_count, ledger.num_txos().unwrap());
+
+            // Check on key images
+            assert_eq!(response.key_image_results.len(), 3);
+            assert_eq!(response.key_image_results[0].key_image, keys[0]);
+            assert_eq!(response.key_image_results[0].status(), Ok(Some(1)));
+            assert_eq!(response.key_image_results[1].key_image, keys[4]);
+            assert_eq!(response.key_image_results[1].status(), Ok(Some(2)));
+            assert_eq!(response.key_image_results[2].key_image, keys[19]);
+            assert_eq!(response.key_image_results[2].status(), Ok(None));
+
+            // Validate merkle proofs against the root of the requested block
+            let merkle_root = {
+                let temp = ledger.get_tx_out_proof_of_memberships(&[0u64]).unwrap();
+                let merkle_proof = &temp[0];
+                mc_transaction_core::membership_proofs::compute_implied_merkle_root(merkle_proof)
+                    .unwrap()
+            };
+            assert_eq!(response.output_results.len(), query_indices.len());
+            for (res, index) in response.output_results.iter().zip(query_indices.iter()) {
+                assert_eq!(res.index, *index);
+                if *index >= ledger.num_txos().unwrap() {
+                    assert!(res.status().as_ref().unwrap().is_none());
+                    continue;
+                }
+                let (tx_out, proof) = res.status().unwrap().unwrap();
+                let result = mc_transaction_core::membership_proofs::is_membership_proof_valid(
+                    &tx_out,
+                    &proof,
+                    merkle_root.hash.as_ref(),
+                )
+                .expect("membership proof structure failed!");
+                assert!(result, "membership proof was invalid! idx = {}", res.index);
+            }
+
+            // The pending query of the session was released once streamed, so
+            // the same session can make another batched query.
+            let response = client
+                .batched_query(&[keys[1]], vec![], num_blocks - 1)
+                .expect("batched_query failed");
+            assert_eq!(response.key_image_results.len(), 1);
+            assert_eq!(response.key_image_results[0].status(), Ok(Some(1)));
+            assert!(response.output_results.is_empty());
+        }
+
+        // grpcio detaches all its threads and does not join them :(
+        // we opened a PR here: https://github.com/tikv/grpc-rs/pull/455
+        // in the meantime we can just sleep after grpcio env and all related
+        // objects have been destroyed, and hope that those 6 threads see the
+        // shutdown requests within 1 second.
+        sleep(Duration::from_millis(1000));
+    }
+}
+
 // Test that a fog ledger connection is able to check key images by hitting
 // a fog ledger server
 #[test_with_logger]
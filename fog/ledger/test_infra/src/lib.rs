@@ -82,6 +82,33 @@ impl LedgerEnclave for MockEnclave {
     ) -> Result<(), mc_fog_ledger_enclave::Error> {
         unimplemented!()
     }
+
+    fn batched_query(
+        &self,
+        _msg: EnclaveMessage<ClientSession>,
+        _untrusted_keyimagequery_response: UntrustedKeyImageQueryResponse,
+    ) -> EnclaveResult<OutputContext> {
+        unimplemented!()
+    }
+
+    fn batched_query_data(
+        &self,
+        _response: GetOutputsResponse,
+        _client: ClientSession,
+    ) -> EnclaveResult<u32> {
+        unimplemented!()
+    }
+
+    fn batched_query_next_chunk(
+        &self,
+        _client: ClientSession,
+    ) -> EnclaveResult<Option<EnclaveMessage<ClientSession>>> {
+        unimplemented!()
+    }
+
+    fn batched_query_cancel(&self, _client: ClientSession) -> EnclaveResult<()> {
+        unimplemented!()
+    }
 }
 
 #[derive(Clone, Default)]
@@ -0,0 +1,307 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Structured alerts raised by Fog Overseer, and the sinks they are sent to.
+
+use crate::{error::OverseerError, health::OverseerStatus};
+use mc_common::logger::{log, Logger};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+/// The kind of condition an alert is about.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// An unretired key's pubkey expiry is close to the next block it needs to
+    /// scan.
+    KeyExpiringSoon,
+    /// A key's last scanned block has not advanced for a while.
+    KeyStalled,
+    /// No responsive node is active with a key that still needs scanning.
+    KeyOutage,
+    /// The number of missed blocks in the recovery db increased.
+    MissedBlocksIncreased,
+    /// More than one node in the cluster is active.
+    MultipleActiveNodes,
+    /// Automatic failover did not succeed.
+    FailoverFailed,
+    /// Overseer reported a key as lost.
+    LostKeyReported,
+    /// Overseer set new keys on a node and activated it.
+    KeyRotated,
+}
+
+/// How urgently an alert needs attention.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    /// Overseer took an action that operators should know about.
+    Info,
+    /// Something is degraded and may need attention.
+    Warning,
+    /// Something is broken and needs attention.
+    Critical,
+}
+
+/// A structured alert.
+#[derive(Clone, Debug, Serialize)]
+pub struct Alert {
+    /// What the alert is about.
+    pub kind: AlertKind,
+
+    /// How urgent the alert is.
+    pub severity: AlertSeverity,
+
+    /// The key, node or other object the alert is about. Alerts of the same
+    /// kind about the same subject are deduplicated.
+    pub subject: String,
+
+    /// A human readable description.
+    pub message: String,
+
+    /// When the alert was raised, in seconds since the unix epoch.
+    pub timestamp: u64,
+}
+
+/// Something that alerts can be sent to.
+pub trait AlertSink: Send {
+    /// Send an alert.
+    fn send(&self, alert: &Alert) -> Result<(), OverseerError>;
+}
+
+/// Writes alerts to the log, where they are picked up by Sentry.
+pub struct LogAlertSink {
+    logger: Logger,
+}
+
+impl LogAlertSink {
+    /// Create a new log sink.
+    pub fn new(logger: Logger) -> Self {
+        Self { logger }
+    }
+}
+
+impl AlertSink for LogAlertSink {
+    fn send(&self, alert: &Alert) -> Result<(), OverseerError> {
+        match alert.severity {
+            AlertSeverity::Info => log::info!(
+                self.logger,
+                "Overseer alert {:?} ({}): {}",
+                alert.kind,
+                alert.subject,
+                alert.message
+            ),
+            AlertSeverity::Warning => log::warn!(
+                self.logger,
+                "Overseer alert {:?} ({}): {}",
+                alert.kind,
+                alert.subject,
+                alert.message
+            ),
+            AlertSeverity::Critical => log::error!(
+                self.logger,
+                "Overseer alert {:?} ({}): {}",
+                alert.kind,
+                alert.subject,
+                alert.message
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// POSTs alerts as JSON to a webhook.
+pub struct WebhookAlertSink {
+    url: Url,
+    client: Client,
+}
+
+impl WebhookAlertSink {
+    /// How long to wait for the webhook to respond.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Create a new webhook sink.
+    ///
+    /// This must not be called from within an async runtime.
+    pub fn new(url: Url) -> Result<Self, OverseerError> {
+        let client = Client::builder()
+            .timeout(Self::TIMEOUT)
+            .build()
+            .map_err(|err| OverseerError::AlertWebhook(err.to_string()))?;
+        Ok(Self { url, client })
+    }
+}
+
+impl AlertSink for WebhookAlertSink {
+    fn send(&self, alert: &Alert) -> Result<(), OverseerError> {
+        self.client
+            .post(self.url.clone())
+            .json(alert)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| {
+                OverseerError::AlertWebhook(format!("Posting to '{}' failed: {}", self.url, err))
+            })?;
+        Ok(())
+    }
+}
+
+/// Raises alerts: deduplicates them, sends them to every sink, and records
+/// them in the shared overseer status.
+pub struct Alerter {
+    sinks: Vec<Box<dyn AlertSink>>,
+
+    /// Alerts of the same kind about the same subject are not raised again
+    /// within this interval.
+    repeat_interval: Duration,
+
+    /// When each (kind, subject) pair was last raised.
+    last_raised: Mutex<HashMap<(AlertKind, String), Instant>>,
+
+    /// Where raised alerts are recorded.
+    status: Arc<Mutex<OverseerStatus>>,
+
+    logger: Logger,
+}
+
+impl Alerter {
+    /// Create a new alerter.
+    pub fn new(
+        sinks: Vec<Box<dyn AlertSink>>,
+        repeat_interval: Duration,
+        status: Arc<Mutex<OverseerStatus>>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            sinks,
+            repeat_interval,
+            last_raised: Default::default(),
+            status,
+            logger,
+        }
+    }
+
+    /// Raise an alert, unless the same alert was raised recently.
+    ///
+    /// Returns true if the alert was raised.
+    pub fn raise(
+        &self,
+        kind: AlertKind,
+        severity: AlertSeverity,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> bool {
+        let subject = subject.into();
+        {
+            let mut last_raised = self.last_raised.lock().expect("mutex poisoned");
+            let now = Instant::now();
+            let key = (kind, subject.clone());
+            if let Some(last) = last_raised.get(&key) {
+                if now.duration_since(*last) < self.repeat_interval {
+                    return false;
+                }
+            }
+            last_raised.insert(key, now);
+        }
+
+        let alert = Alert {
+            kind,
+            severity,
+            subject,
+            message: message.into(),
+            timestamp: now_unix_seconds(),
+        };
+        for sink in &self.sinks {
+            if let Err(err) = sink.send(&alert) {
+                log::error!(self.logger, "Could not send overseer alert: {}", err);
+            }
+        }
+        self.status
+            .lock()
+            .expect("mutex poisoned")
+            .record_alert(alert);
+        true
+    }
+
+    /// Forget that an alert was raised, so that it is raised again as soon as
+    /// the condition recurs. This should be called when the condition clears.
+    pub fn clear(&self, kind: AlertKind, subject: &str) {
+        self.last_raised
+            .lock()
+            .expect("mutex poisoned")
+            .remove(&(kind, subject.to_owned()));
+    }
+}
+
+/// The current time, in seconds since the unix epoch
+pub fn now_unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_common::logger::test_with_logger;
+
+    struct RecordingSink(Arc<Mutex<Vec<Alert>>>);
+
+    impl AlertSink for RecordingSink {
+        fn send(&self, alert: &Alert) -> Result<(), OverseerError> {
+            self.0.lock().unwrap().push(alert.clone());
+            Ok(())
+        }
+    }
+
+    #[test_with_logger]
+    fn repeated_alerts_are_suppressed(logger: Logger) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let status = Arc::new(Mutex::new(OverseerStatus::default()));
+        let alerter = Alerter::new(
+            vec![Box::new(RecordingSink(sent.clone()))],
+            Duration::from_secs(3600),
+            status.clone(),
+            logger,
+        );
+
+        assert!(alerter.raise(
+            AlertKind::KeyStalled,
+            AlertSeverity::Warning,
+            "a",
+            "stalled"
+        ));
+        assert!(!alerter.raise(
+            AlertKind::KeyStalled,
+            AlertSeverity::Warning,
+            "a",
+            "stalled"
+        ));
+        // A different subject or kind is not suppressed
+        assert!(alerter.raise(
+            AlertKind::KeyStalled,
+            AlertSeverity::Warning,
+            "b",
+            "stalled"
+        ));
+        assert!(alerter.raise(AlertKind::KeyOutage, AlertSeverity::Critical, "a", "outage"));
+
+        // Once cleared, the alert is raised again
+        alerter.clear(AlertKind::KeyStalled, "a");
+        assert!(alerter.raise(
+            AlertKind::KeyStalled,
+            AlertSeverity::Warning,
+            "a",
+            "stalled"
+        ));
+
+        assert_eq!(sent.lock().unwrap().len(), 4);
+        assert_eq!(status.lock().unwrap().alerts.len(), 4);
+    }
+}
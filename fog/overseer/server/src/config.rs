@@ -6,7 +6,10 @@
 use clap::Parser;
 use mc_fog_sql_recovery_db::SqlRecoveryDbConnectionConfig;
 use mc_fog_uri::FogIngestUri;
+use mc_util_parse::parse_duration_in_seconds;
 use serde::Serialize;
+use std::time::Duration;
+use url::Url;
 
 /// Parser configuration options for an Overseer Server
 #[derive(Clone, Serialize, Parser)]
@@ -32,6 +35,68 @@ pub struct OverseerConfig {
     /// Postgres config
     #[clap(flatten)]
     pub postgres_config: SqlRecoveryDbConnectionConfig,
+
+    /// Ingress key health monitoring and alerting config
+    #[clap(flatten)]
+    pub key_health_config: KeyHealthConfig,
+}
+
+/// Configuration for how Fog Overseer judges the health of the outstanding
+/// ingress keys, how it alerts on problems, and whether it acts on them.
+#[derive(Clone, Debug, Serialize, Parser)]
+pub struct KeyHealthConfig {
+    /// If set, alerts are POSTed as JSON to this URL, in addition to being
+    /// logged.
+    #[clap(long, env = "MC_OVERSEER_ALERT_WEBHOOK_URL")]
+    pub alert_webhook_url: Option<Url>,
+
+    /// Seconds to wait before raising the same alert about the same subject
+    /// again.
+    #[clap(long, default_value = "3600", parse(try_from_str = parse_duration_in_seconds), env = "MC_OVERSEER_ALERT_REPEAT_INTERVAL")]
+    pub alert_repeat_interval: Duration,
+
+    /// Alert when an unretired key's published pubkey expiry is fewer than
+    /// this many blocks ahead of the next block it needs to scan. This
+    /// happens when the active node stops publishing reports.
+    ///
+    /// This should be less than the ingest nodes' pubkey expiry window.
+    #[clap(
+        long,
+        default_value = "20",
+        env = "MC_OVERSEER_KEY_EXPIRY_ALERT_BLOCKS"
+    )]
+    pub key_expiry_alert_blocks: u64,
+
+    /// Alert when a key's last scanned block has not advanced for this many
+    /// seconds.
+    ///
+    /// Blocks are only produced when there are transactions, so on quiet
+    /// networks this should be set generously.
+    #[clap(long, default_value = "600", parse(try_from_str = parse_duration_in_seconds), env = "MC_OVERSEER_STALLED_KEY_ALERT_AFTER")]
+    pub stalled_key_alert_after: Duration,
+
+    /// If set, a key which has had an outage for this many seconds is
+    /// automatically reported as lost, and new keys are set on an idle node
+    /// which is then activated.
+    ///
+    /// A key has an outage when no responsive node is active with it and its
+    /// last scanned block is not advancing. Reporting a key as lost is
+    /// irreversible and makes users download the blocks it did not scan, so
+    /// this is off by default.
+    #[clap(long, parse(try_from_str = parse_duration_in_seconds), env = "MC_OVERSEER_AUTO_REPORT_LOST_KEY_AFTER")]
+    pub auto_report_lost_key_after: Option<Duration>,
+}
+
+impl Default for KeyHealthConfig {
+    fn default() -> Self {
+        Self {
+            alert_webhook_url: None,
+            alert_repeat_interval: Duration::from_secs(3600),
+            key_expiry_alert_blocks: 20,
+            stalled_key_alert_after: Duration::from_secs(600),
+            auto_report_lost_key_after: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -55,5 +120,42 @@ mod tests {
 
         assert_eq!(config.ingest_cluster_uris[0].port(), 3226);
         assert_eq!(config.ingest_cluster_uris[1].port(), 3227);
+
+        assert!(config.key_health_config.alert_webhook_url.is_none());
+        assert!(config
+            .key_health_config
+            .auto_report_lost_key_after
+            .is_none());
+    }
+
+    #[test]
+    fn key_health_config_example() {
+        let config = OverseerConfig::try_parse_from(&[
+            "/usr/bin/fog_overseer_server",
+            "--ingest-cluster-uris",
+            "insecure-fog-ingest://0.0.0.0:3226/",
+            "--alert-webhook-url",
+            "https://alerts.example.com/hooks/fog",
+            "--stalled-key-alert-after",
+            "120",
+            "--auto-report-lost-key-after",
+            "900",
+        ])
+        .expect("Could not parse command line arguments.");
+
+        let key_health_config = config.key_health_config;
+        assert_eq!(
+            key_health_config.alert_webhook_url.unwrap().as_str(),
+            "https://alerts.example.com/hooks/fog"
+        );
+        assert_eq!(
+            key_health_config.stalled_key_alert_after,
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            key_health_config.auto_report_lost_key_after,
+            Some(Duration::from_secs(900))
+        );
+        assert_eq!(key_health_config.key_expiry_alert_blocks, 20);
     }
 }
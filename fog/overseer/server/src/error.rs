@@ -30,6 +30,9 @@ pub enum OverseerError {
 
     /// There are multiple active Fog Ingest nodes at once: {0}
     MultipleActiveNodes(String),
+
+    /// Sending an alert to the webhook failed: {0}
+    AlertWebhook(String),
 }
 
 impl From<SqlRecoveryDbError> for OverseerError {
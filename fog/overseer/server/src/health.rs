@@ -0,0 +1,415 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Health scoring for the ingress keys that Fog Ingest still needs to scan
+//! with, and the status that Fog Overseer shares with its HTTP API.
+
+use crate::{alerts::Alert, config::KeyHealthConfig};
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_recovery_db_iface::IngressPublicKeyRecord;
+use mc_fog_types::common::BlockRange;
+use mc_fog_uri::FogIngestUri;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// A problem found with an outstanding ingress key.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyHealthIssue {
+    /// The key is not retired, and its pubkey expiry is close to the next
+    /// block it needs to scan.
+    ExpiringSoon,
+    /// The key's last scanned block has not advanced for a while.
+    Stalled,
+    /// No responsive node is active with this key.
+    NoActiveNode,
+}
+
+impl KeyHealthIssue {
+    /// How many points this issue takes off a key's health score.
+    fn penalty(&self) -> u8 {
+        match self {
+            Self::ExpiringSoon => 30,
+            Self::Stalled => 30,
+            Self::NoActiveNode => 40,
+        }
+    }
+}
+
+/// The health of an ingress key that Fog Ingest still needs to scan with.
+#[derive(Clone, Debug, Serialize)]
+pub struct KeyHealth {
+    /// The ingress public key, in hex.
+    pub key: String,
+
+    /// The first block this key is used to scan.
+    pub start_block: u64,
+
+    /// The largest pubkey expiry published for this key.
+    pub pubkey_expiry: u64,
+
+    /// Whether the key is retired.
+    pub retired: bool,
+
+    /// The last block scanned with this key, if any.
+    pub last_scanned_block: Option<u64>,
+
+    /// How many blocks are left between the next block this key needs to
+    /// scan and its pubkey expiry.
+    pub blocks_until_expiry: u64,
+
+    /// The node which is active with this key, if there is one.
+    pub active_node: Option<FogIngestUri>,
+
+    /// Seconds since overseer first saw the key's current last scanned block.
+    pub seconds_since_progress: u64,
+
+    /// Seconds for which the key has had an outage, meaning no responsive
+    /// node is active with it and its last scanned block is not advancing.
+    pub outage_seconds: Option<u64>,
+
+    /// A score from 0 (unusable) to 100 (healthy).
+    pub score: u8,
+
+    /// The problems found with the key.
+    pub issues: Vec<KeyHealthIssue>,
+}
+
+/// What overseer remembers about a key between polls.
+struct KeyProgress {
+    /// The last scanned block seen on the previous poll.
+    last_scanned_block: Option<u64>,
+
+    /// When the last scanned block last changed (or was first seen).
+    last_progress: Instant,
+
+    /// When the key's current outage started, if it has one.
+    outage_started: Option<Instant>,
+}
+
+/// Tracks the outstanding ingress keys across polls, to score their health.
+pub struct KeyHealthTracker {
+    config: KeyHealthConfig,
+
+    progress: HashMap<CompressedRistrettoPublic, KeyProgress>,
+
+    /// The total number of missed blocks seen on the previous poll.
+    missed_block_count: Option<u64>,
+}
+
+impl KeyHealthTracker {
+    /// Create a new tracker.
+    pub fn new(config: KeyHealthConfig) -> Self {
+        Self {
+            config,
+            progress: Default::default(),
+            missed_block_count: None,
+        }
+    }
+
+    /// Score the outstanding keys.
+    ///
+    /// Arguments:
+    /// * records: The outstanding (not lost and not expired) ingress key
+    ///   records from the recovery db.
+    /// * active_keys: The ingress keys of the responsive nodes which are
+    ///   active, and the node each one is active on.
+    /// * now: The current time.
+    pub fn evaluate(
+        &mut self,
+        records: &[IngressPublicKeyRecord],
+        active_keys: &HashMap<CompressedRistrettoPublic, FogIngestUri>,
+        now: Instant,
+    ) -> Vec<KeyHealth> {
+        // Forget keys which are no longer outstanding.
+        self.progress
+            .retain(|key, _| records.iter().any(|record| record.key == *key));
+
+        records
+            .iter()
+            .map(|record| {
+                let progress = self.progress.entry(record.key).or_insert(KeyProgress {
+                    last_scanned_block: record.last_scanned_block,
+                    last_progress: now,
+                    outage_started: None,
+                });
+                let progressed = progress.last_scanned_block != record.last_scanned_block;
+                if progressed {
+                    progress.last_scanned_block = record.last_scanned_block;
+                    progress.last_progress = now;
+                }
+
+                let active_node = active_keys.get(&record.key).cloned();
+                if active_node.is_some() || progressed {
+                    progress.outage_started = None;
+                } else if progress.outage_started.is_none() {
+                    progress.outage_started = Some(now);
+                }
+
+                let next_block = record
+                    .last_scanned_block
+                    .map(|block| block + 1)
+                    .unwrap_or(record.status.start_block);
+                let blocks_until_expiry = record.status.pubkey_expiry.saturating_sub(next_block);
+                let since_progress = now.saturating_duration_since(progress.last_progress);
+                let outage = progress
+                    .outage_started
+                    .map(|started| now.saturating_duration_since(started));
+
+                let mut issues = Vec::new();
+                if !record.status.retired
+                    && blocks_until_expiry < self.config.key_expiry_alert_blocks
+                {
+                    issues.push(KeyHealthIssue::ExpiringSoon);
+                }
+                if since_progress >= self.config.stalled_key_alert_after {
+                    issues.push(KeyHealthIssue::Stalled);
+                }
+                if active_node.is_none() {
+                    issues.push(KeyHealthIssue::NoActiveNode);
+                }
+                let score = issues
+                    .iter()
+                    .fold(100u8, |score, issue| score.saturating_sub(issue.penalty()));
+
+                KeyHealth {
+                    key: record.key.to_string(),
+                    start_block: record.status.start_block,
+                    pubkey_expiry: record.status.pubkey_expiry,
+                    retired: record.status.retired,
+                    last_scanned_block: record.last_scanned_block,
+                    blocks_until_expiry,
+                    active_node,
+                    seconds_since_progress: since_progress.as_secs(),
+                    outage_seconds: outage.as_ref().map(Duration::as_secs),
+                    score,
+                    issues,
+                }
+            })
+            .collect()
+    }
+
+    /// Record the missed block ranges from the recovery db.
+    ///
+    /// Returns the previous and current number of missed blocks if the number
+    /// increased since the previous call.
+    pub fn observe_missed_blocks(&mut self, ranges: &[BlockRange]) -> Option<(u64, u64)> {
+        let count = ranges
+            .iter()
+            .map(|range| range.end_block.saturating_sub(range.start_block))
+            .sum();
+        let previous = self.missed_block_count.replace(count);
+        previous
+            .filter(|previous| *previous < count)
+            .map(|previous| (previous, count))
+    }
+
+    /// The keys whose outage is long enough that they should be reported as
+    /// lost, if automatic lost key reporting is configured.
+    pub fn keys_to_report_lost(&self, now: Instant) -> Vec<CompressedRistrettoPublic> {
+        let threshold = match self.config.auto_report_lost_key_after {
+            Some(threshold) => threshold,
+            None => return Vec::new(),
+        };
+        self.progress
+            .iter()
+            .filter(|(_, progress)| {
+                progress.outage_started.map_or(false, |started| {
+                    now.saturating_duration_since(started) >= threshold
+                })
+            })
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+/// Something Fog Overseer did to the Fog Ingest cluster or the recovery db.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverseerActionKind {
+    /// Activated an idle node.
+    ActivateNode,
+    /// Set new keys on an idle node.
+    SetNewKeys,
+    /// Reported an ingress key as lost.
+    ReportLostKey,
+}
+
+/// A record of an action Fog Overseer took, and why.
+#[derive(Clone, Debug, Serialize)]
+pub struct OverseerAction {
+    /// What was done.
+    pub kind: OverseerActionKind,
+
+    /// The node acted on, if any.
+    pub node: Option<FogIngestUri>,
+
+    /// The ingress key acted on, in hex, if any.
+    pub key: Option<String>,
+
+    /// Why overseer did it.
+    pub reason: String,
+
+    /// When it was done, in seconds since the unix epoch.
+    pub timestamp: u64,
+}
+
+/// The latest health evaluation and the recent alerts and actions, shared by
+/// the overseer worker with the overseer service.
+#[derive(Debug, Default)]
+pub struct OverseerStatus {
+    /// The health of each outstanding ingress key, as of the latest poll.
+    pub key_health: Vec<KeyHealth>,
+
+    /// The total number of missed blocks, as of the latest poll.
+    pub missed_block_count: Option<u64>,
+
+    /// The most recent alerts, oldest first.
+    pub alerts: VecDeque<Alert>,
+
+    /// The most recent actions, oldest first.
+    pub actions: VecDeque<OverseerAction>,
+}
+
+impl OverseerStatus {
+    /// How many alerts and actions are kept.
+    pub const HISTORY_LENGTH: usize = 100;
+
+    /// Record an alert, forgetting the oldest one if needed.
+    pub fn record_alert(&mut self, alert: Alert) {
+        if self.alerts.len() == Self::HISTORY_LENGTH {
+            self.alerts.pop_front();
+        }
+        self.alerts.push_back(alert);
+    }
+
+    /// Record an action, forgetting the oldest one if needed.
+    pub fn record_action(&mut self, action: OverseerAction) {
+        if self.actions.len() == Self::HISTORY_LENGTH {
+            self.actions.pop_front();
+        }
+        self.actions.push_back(action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_recovery_db_iface::IngressPublicKeyStatus;
+    use std::str::FromStr;
+
+    fn record(key_byte: u8, last_scanned_block: Option<u64>) -> IngressPublicKeyRecord {
+        IngressPublicKeyRecord {
+            key: CompressedRistrettoPublic::from(&[key_byte; 32]),
+            status: IngressPublicKeyStatus {
+                start_block: 10,
+                pubkey_expiry: 100,
+                retired: false,
+                lost: false,
+            },
+            last_scanned_block,
+        }
+    }
+
+    fn config() -> KeyHealthConfig {
+        KeyHealthConfig {
+            key_expiry_alert_blocks: 20,
+            stalled_key_alert_after: Duration::from_secs(60),
+            auto_report_lost_key_after: Some(Duration::from_secs(300)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn healthy_key() {
+        let mut tracker = KeyHealthTracker::new(config());
+        let node = FogIngestUri::from_str("insecure-fog-ingest://localhost:3226/").unwrap();
+        let record = record(1, Some(50));
+        let active_keys = HashMap::from([(record.key, node.clone())]);
+
+        let health = tracker.evaluate(&[record], &active_keys, Instant::now());
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].blocks_until_expiry, 49);
+        assert_eq!(health[0].active_node, Some(node));
+        assert_eq!(health[0].outage_seconds, None);
+        assert_eq!(health[0].score, 100);
+        assert!(health[0].issues.is_empty());
+    }
+
+    #[test]
+    fn expiring_and_stalled_key() {
+        let mut tracker = KeyHealthTracker::new(config());
+        let node = FogIngestUri::from_str("insecure-fog-ingest://localhost:3226/").unwrap();
+        let record = record(1, Some(90));
+        let active_keys = HashMap::from([(record.key, node)]);
+
+        let start = Instant::now();
+        tracker.evaluate(&[record.clone()], &active_keys, start);
+        let health = tracker.evaluate(&[record], &active_keys, start + Duration::from_secs(61));
+        assert_eq!(
+            health[0].issues,
+            vec![KeyHealthIssue::ExpiringSoon, KeyHealthIssue::Stalled]
+        );
+        assert_eq!(health[0].score, 40);
+        assert!(tracker
+            .keys_to_report_lost(start + Duration::from_secs(1000))
+            .is_empty());
+    }
+
+    #[test]
+    fn outage_is_reset_by_progress() {
+        let mut tracker = KeyHealthTracker::new(config());
+        let start = Instant::now();
+
+        tracker.evaluate(&[record(1, Some(50))], &HashMap::new(), start);
+        let health = tracker.evaluate(
+            &[record(1, Some(50))],
+            &HashMap::new(),
+            start + Duration::from_secs(200),
+        );
+        assert_eq!(health[0].outage_seconds, Some(200));
+        assert!(tracker
+            .keys_to_report_lost(start + Duration::from_secs(200))
+            .is_empty());
+        assert_eq!(
+            tracker.keys_to_report_lost(start + Duration::from_secs(300)),
+            vec![record(1, None).key]
+        );
+
+        // An unresponsive node is still scanning with the key
+        let health = tracker.evaluate(
+            &[record(1, Some(51))],
+            &HashMap::new(),
+            start + Duration::from_secs(300),
+        );
+        assert_eq!(health[0].outage_seconds, None);
+        assert!(tracker
+            .keys_to_report_lost(start + Duration::from_secs(300))
+            .is_empty());
+
+        // Keys which are no longer outstanding are forgotten
+        tracker.evaluate(&[], &HashMap::new(), start + Duration::from_secs(1000));
+        assert!(tracker
+            .keys_to_report_lost(start + Duration::from_secs(1000))
+            .is_empty());
+    }
+
+    #[test]
+    fn missed_block_growth() {
+        let mut tracker = KeyHealthTracker::new(config());
+        assert_eq!(
+            tracker.observe_missed_blocks(&[BlockRange::new(10, 20)]),
+            None
+        );
+        assert_eq!(
+            tracker.observe_missed_blocks(&[BlockRange::new(10, 20)]),
+            None
+        );
+        assert_eq!(
+            tracker.observe_missed_blocks(&[BlockRange::new(10, 20), BlockRange::new(30, 35)]),
+            Some((10, 15))
+        );
+    }
+}
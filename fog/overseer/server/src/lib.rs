@@ -5,7 +5,9 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 #![deny(missing_docs)]
 
+pub mod alerts;
 pub mod config;
+pub mod health;
 pub mod metrics;
 pub mod responses;
 pub mod server;
@@ -2,6 +2,10 @@
 
 //! Contains responses that are returned by Fog Overseer.
 
+use crate::{
+    alerts::Alert,
+    health::{KeyHealth, OverseerAction},
+};
 use mc_fog_types::ingest_common::IngestSummary;
 use mc_fog_uri::FogIngestUri;
 use serde::Serialize;
@@ -15,3 +19,32 @@ pub struct GetIngestSummariesResponse {
     /// The ingest summaries.
     pub ingest_summaries: HashMap<FogIngestUri, Result<IngestSummary, String>>,
 }
+
+/// The health of the ingress keys that Fog Ingest still needs to scan with,
+/// as of Fog Overseer's latest poll.
+#[derive(Serialize)]
+pub struct GetKeyHealthResponse {
+    /// The health of each outstanding key.
+    pub key_health: Vec<KeyHealth>,
+
+    /// The total number of missed blocks in the recovery db.
+    pub missed_block_count: Option<u64>,
+
+    /// How long a key's outage has to last before Fog Overseer reports it as
+    /// lost, in seconds, or None if it never does.
+    pub auto_report_lost_key_after_seconds: Option<u64>,
+}
+
+/// The most recent alerts raised by Fog Overseer, oldest first.
+#[derive(Serialize)]
+pub struct GetAlertsResponse {
+    /// The alerts.
+    pub alerts: Vec<Alert>,
+}
+
+/// The most recent actions taken by Fog Overseer, oldest first.
+#[derive(Serialize)]
+pub struct GetActionsResponse {
+    /// The actions.
+    pub actions: Vec<OverseerAction>,
+}
@@ -6,7 +6,11 @@
 //! HTTP Client -> *Overseer Rocket Server* -> OverseerService -> OverseerWorker
 
 use crate::{
-    error::OverseerError, responses::GetIngestSummariesResponse, service::OverseerService,
+    error::OverseerError,
+    responses::{
+        GetActionsResponse, GetAlertsResponse, GetIngestSummariesResponse, GetKeyHealthResponse,
+    },
+    service::OverseerService,
 };
 use mc_fog_recovery_db_iface::RecoveryDb;
 use mc_fog_sql_recovery_db::SqlRecoveryDb;
@@ -34,6 +38,30 @@ fn get_ingest_summaries(
     state.overseer_service.get_ingest_summaries().map(Json)
 }
 
+/// The health of the outstanding ingress keys.
+#[get("/key_health")]
+fn get_key_health(
+    state: &rocket::State<OverseerState<SqlRecoveryDb>>,
+) -> Result<Json<GetKeyHealthResponse>, String> {
+    state.overseer_service.get_key_health().map(Json)
+}
+
+/// The most recent alerts.
+#[get("/alerts")]
+fn get_alerts(
+    state: &rocket::State<OverseerState<SqlRecoveryDb>>,
+) -> Result<Json<GetAlertsResponse>, String> {
+    state.overseer_service.get_alerts().map(Json)
+}
+
+/// The most recent actions taken on the cluster.
+#[get("/actions")]
+fn get_actions(
+    state: &rocket::State<OverseerState<SqlRecoveryDb>>,
+) -> Result<Json<GetActionsResponse>, String> {
+    state.overseer_service.get_actions().map(Json)
+}
+
 /// Produces metrics for Prometheus.
 ///
 /// Meant to be called only by the Prometheus pull mechanism.
@@ -66,7 +94,10 @@ pub fn initialize_rocket_server<T: rocket::figment::Provider>(
             disable,
             get_status,
             get_metrics,
-            get_ingest_summaries
+            get_ingest_summaries,
+            get_key_health,
+            get_alerts,
+            get_actions
         ],
     )
 }
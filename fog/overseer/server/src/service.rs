@@ -7,7 +7,15 @@
 //!
 //! HTTP Client -> Overseer Rocket Server -> *OverseerService* -> OverseerWorker
 
-use crate::{error::OverseerError, responses::GetIngestSummariesResponse, worker::OverseerWorker};
+use crate::{
+    config::KeyHealthConfig,
+    error::OverseerError,
+    health::OverseerStatus,
+    responses::{
+        GetActionsResponse, GetAlertsResponse, GetIngestSummariesResponse, GetKeyHealthResponse,
+    },
+    worker::OverseerWorker,
+};
 use mc_common::logger::{log, Logger};
 use mc_fog_ingest_client::FogIngestGrpcClient;
 use mc_fog_recovery_db_iface::RecoveryDb;
@@ -18,7 +26,7 @@ use std::{
     collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     time::Duration,
 };
@@ -33,6 +41,8 @@ where
     overseer_worker: Option<OverseerWorker>,
     recovery_db: DB,
     is_enabled: Arc<AtomicBool>,
+    key_health_config: KeyHealthConfig,
+    status: Arc<Mutex<OverseerStatus>>,
 }
 
 impl<DB: RecoveryDb + Clone + Send + Sync + 'static> OverseerService<DB>
@@ -43,7 +53,12 @@ where
     const GRPC_RETRY_SECONDS: Duration = Duration::from_millis(10000);
 
     /// Insantiate the service with the given URIs and DB.
-    pub fn new(ingest_cluster_uris: Vec<FogIngestUri>, recovery_db: DB, logger: Logger) -> Self {
+    pub fn new(
+        ingest_cluster_uris: Vec<FogIngestUri>,
+        recovery_db: DB,
+        key_health_config: KeyHealthConfig,
+        logger: Logger,
+    ) -> Self {
         let grpcio_env = Arc::new(grpcio::EnvBuilder::new().build());
         let ingest_clients: Vec<FogIngestGrpcClient> = ingest_cluster_uris
             .iter()
@@ -62,6 +77,8 @@ where
             overseer_worker: None,
             recovery_db,
             is_enabled: Arc::new(AtomicBool::new(false)),
+            key_health_config,
+            status: Default::default(),
         }
     }
 
@@ -82,6 +99,8 @@ where
         self.overseer_worker = Some(OverseerWorker::new(
             self.ingest_clients.clone(),
             self.recovery_db.clone(),
+            self.key_health_config.clone(),
+            self.status.clone(),
             self.logger.clone(),
             self.is_enabled.clone(),
         ));
@@ -183,6 +202,36 @@ where
 
         Ok(GetIngestSummariesResponse { ingest_summaries })
     }
+
+    /// Get the health of the outstanding ingress keys, as of the worker's
+    /// latest poll.
+    pub fn get_key_health(&self) -> Result<GetKeyHealthResponse, String> {
+        let status = self.status.lock().map_err(|err| err.to_string())?;
+        Ok(GetKeyHealthResponse {
+            key_health: status.key_health.clone(),
+            missed_block_count: status.missed_block_count,
+            auto_report_lost_key_after_seconds: self
+                .key_health_config
+                .auto_report_lost_key_after
+                .map(|duration| duration.as_secs()),
+        })
+    }
+
+    /// Get the most recent alerts.
+    pub fn get_alerts(&self) -> Result<GetAlertsResponse, String> {
+        let status = self.status.lock().map_err(|err| err.to_string())?;
+        Ok(GetAlertsResponse {
+            alerts: status.alerts.iter().cloned().collect(),
+        })
+    }
+
+    /// Get the most recent actions the worker took.
+    pub fn get_actions(&self) -> Result<GetActionsResponse, String> {
+        let status = self.status.lock().map_err(|err| err.to_string())?;
+        Ok(GetActionsResponse {
+            actions: status.actions.iter().cloned().collect(),
+        })
+    }
 }
 
 impl<DB: RecoveryDb + Clone + Send + Sync + 'static> Drop for OverseerService<DB>
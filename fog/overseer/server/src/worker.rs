@@ -7,7 +7,18 @@
 //!
 //! HTTP Client -> Overseer Rocket Server -> OverseerService -> *OverseerWorker*
 
-use crate::{error::OverseerError, metrics};
+use crate::{
+    alerts::{
+        now_unix_seconds, AlertKind, AlertSeverity, AlertSink, Alerter, LogAlertSink,
+        WebhookAlertSink,
+    },
+    config::KeyHealthConfig,
+    error::OverseerError,
+    health::{
+        KeyHealthIssue, KeyHealthTracker, OverseerAction, OverseerActionKind, OverseerStatus,
+    },
+    metrics,
+};
 use mc_api::external;
 use mc_common::logger::{log, Logger};
 use mc_crypto_keys::CompressedRistrettoPublic;
@@ -17,13 +28,13 @@ use mc_fog_recovery_db_iface::{IngressPublicKeyRecord, IngressPublicKeyRecordFil
 use mc_fog_uri::FogIngestUri;
 use retry::{delay::Fixed, retry_with_index, OperationResult};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     thread::{sleep, Builder as ThreadBuilder, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// Wraps a thread that is responsible for overseeing the active Fog Ingest
@@ -33,6 +44,10 @@ use std::{
 /// there is no active key, then it promotes an idle node to active, and in the
 /// case where none of the idle nodes contain the previously active ingress key,
 /// it reports that key as lost.
+///
+/// It also scores the health of the outstanding ingress keys, raises alerts
+/// when they are unhealthy, and, if configured, reports a key as lost and
+/// rotates to new keys when the key's outage lasts too long.
 pub struct OverseerWorker {
     /// Join handle used to wait for the thread to terminate.
     join_handle: Option<JoinHandle<()>>,
@@ -45,6 +60,8 @@ impl OverseerWorker {
     pub fn new<DB: RecoveryDb + Clone + Send + Sync + 'static>(
         ingest_clients: Arc<Vec<FogIngestGrpcClient>>,
         recovery_db: DB,
+        key_health_config: KeyHealthConfig,
+        status: Arc<Mutex<OverseerStatus>>,
         logger: Logger,
         is_enabled: Arc<AtomicBool>,
     ) -> Self
@@ -58,12 +75,36 @@ impl OverseerWorker {
             ThreadBuilder::new()
                 .name("OverseerWorker".to_string())
                 .spawn(move || {
+                    // The webhook client has to be built outside of the async runtime that
+                    // the rocket server runs in.
+                    let mut sinks: Vec<Box<dyn AlertSink>> =
+                        vec![Box::new(LogAlertSink::new(logger.clone()))];
+                    if let Some(url) = key_health_config.alert_webhook_url.clone() {
+                        match WebhookAlertSink::new(url) {
+                            Ok(sink) => sinks.push(Box::new(sink)),
+                            Err(err) => log::error!(
+                                logger,
+                                "Alerts will only be logged, could not create webhook: {}",
+                                err
+                            ),
+                        }
+                    }
+                    let alerter = Alerter::new(
+                        sinks,
+                        key_health_config.alert_repeat_interval,
+                        status.clone(),
+                        logger.clone(),
+                    );
+
                     OverseerWorkerThread::start(
                         ingest_clients,
                         recovery_db,
                         thread_is_enabled,
                         thread_stop_requested,
                         HashSet::new(),
+                        KeyHealthTracker::new(key_health_config),
+                        alerter,
+                        status,
                         logger,
                     )
                 })
@@ -114,6 +155,15 @@ struct OverseerWorkerThread<DB: RecoveryDb> {
     /// This helps us debug when a node starts responding again.
     unresponsive_node_urls: HashSet<FogIngestUri>,
 
+    /// Scores the health of the outstanding ingress keys across polls.
+    key_health_tracker: KeyHealthTracker,
+
+    /// Raises alerts about unhealthy keys and the actions overseer takes.
+    alerter: Alerter,
+
+    /// The latest key health and recent actions, shared with the service.
+    status: Arc<Mutex<OverseerStatus>>,
+
     logger: Logger,
 }
 
@@ -143,6 +193,9 @@ where
         is_enabled: Arc<AtomicBool>,
         stop_requested: Arc<AtomicBool>,
         unresponsive_node_urls: HashSet<FogIngestUri>,
+        key_health_tracker: KeyHealthTracker,
+        alerter: Alerter,
+        status: Arc<Mutex<OverseerStatus>>,
         logger: Logger,
     ) {
         let thread = Self {
@@ -151,6 +204,9 @@ where
             is_enabled,
             stop_requested,
             unresponsive_node_urls,
+            key_health_tracker,
+            alerter,
+            status,
             logger,
         };
         thread.run();
@@ -166,21 +222,36 @@ where
                 break;
             }
 
-            if !self.is_enabled.load(Ordering::SeqCst) {
+            let (ingest_summary_node_mappings, unresponsive_node_error) =
+                self.retrieve_ingest_summary_node_mappings();
+
+            // Key health is monitored even when overseer is disabled, but no
+            // action is taken.
+            let is_enabled = self.is_enabled.load(Ordering::SeqCst);
+            match self.check_key_health(&ingest_summary_node_mappings, is_enabled) {
+                Ok(true) => {
+                    log::info!(
+                        self.logger,
+                        "Rotated away from a lost key. Returning to beginning of overseer logic."
+                    );
+                    continue;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    log::error!(self.logger, "Checking ingress key health failed: {}", err);
+                }
+            }
+
+            if !is_enabled {
                 log::trace!(self.logger, "Overseer worker is currently disabled.");
                 continue;
             }
 
-            let ingest_summary_node_mappings: Vec<IngestSummaryNodeMapping> = match self
-                .retrieve_ingest_summary_node_mappings()
-            {
-                Ok(ingest_summary_node_mappings) => ingest_summary_node_mappings,
-                Err(err) => {
-                    log::error!(self.logger, "Encountered an error while retrieving ingest summaries: {}. Returning to beginning of overseer logic.", err);
-                    metrics::increment_unresponsive_node_count(&self.logger);
-                    continue;
-                }
-            };
+            if let Some(err) = unresponsive_node_error {
+                log::error!(self.logger, "Encountered an error while retrieving ingest summaries: {}. Returning to beginning of overseer logic.", err);
+                metrics::increment_unresponsive_node_count(&self.logger);
+                continue;
+            }
 
             let ingest_summaries: Vec<IngestSummary> = ingest_summary_node_mappings
                 .iter()
@@ -209,7 +280,12 @@ where
                             log::info!(self.logger, "Automatic failover completed successfully.")
                         }
                         Err(err) => {
-                            log::error!(self.logger, "Automatic failover failed: {}", err)
+                            self.alerter.raise(
+                                AlertKind::FailoverFailed,
+                                AlertSeverity::Critical,
+                                "cluster",
+                                format!("Automatic failover failed: {}", err),
+                            );
                         }
                     };
                 }
@@ -236,57 +312,194 @@ where
                     let error_message =
                         format!("Active ingress keys: {:?}", active_node_ingress_pubkeys);
                     let error = OverseerError::MultipleActiveNodes(error_message);
-                    log::error!(self.logger, "{}", error);
+                    self.alerter.raise(
+                        AlertKind::MultipleActiveNodes,
+                        AlertSeverity::Critical,
+                        "cluster",
+                        error.to_string(),
+                    );
                 }
             }
         }
     }
 
     /// Returns the latest round of ingest summaries for each
-    /// FogIngestGrpcClient that communicates with a node that is online.
+    /// FogIngestGrpcClient that communicates with a node that is online, and
+    /// an error if any node did not respond.
     fn retrieve_ingest_summary_node_mappings(
         &mut self,
-    ) -> Result<Vec<IngestSummaryNodeMapping>, OverseerError> {
+    ) -> (Vec<IngestSummaryNodeMapping>, Option<OverseerError>) {
         let logger = &self.logger;
         let unresponsive_node_urls = &mut self.unresponsive_node_urls;
-        self.ingest_clients
-            .iter()
-            .enumerate()
-            .map(|(node_index, ingest_client)| {
-                let uri = ingest_client.get_uri();
-                match ingest_client.get_status() {
-                    Ok(ingest_summary) => {
-                        log::trace!(
-                            logger,
-                            "Ingest summary retrieved from '{}': {:?}",
-                            uri,
-                            ingest_summary
-                        );
-                        if unresponsive_node_urls.remove(uri) {
-                            log::info!(
+        let mut mappings = Vec::new();
+        let mut first_error = None;
+        for (node_index, ingest_client) in self.ingest_clients.iter().enumerate() {
+            let uri = ingest_client.get_uri();
+            match ingest_client.get_status() {
+                Ok(ingest_summary) => {
+                    log::trace!(
+                        logger,
+                        "Ingest summary retrieved from '{}': {:?}",
+                        uri,
+                        ingest_summary
+                    );
+                    if unresponsive_node_urls.remove(uri) {
+                        log::info!(
                             logger,
                             "Node {} was previously unresponsive, but just successfully responded!",
                             uri,
                         );
-                        }
-                        Ok(IngestSummaryNodeMapping {
-                            node_index,
-                            ingest_summary,
-                        })
                     }
+                    mappings.push(IngestSummaryNodeMapping {
+                        node_index,
+                        ingest_summary,
+                    });
+                }
 
-                    Err(err) => {
-                        let error_message = format!(
-                            "Unable to retrieve ingest summary for node ({}): {}",
-                            uri, err
-                        );
-                        log::trace!(logger, "{}", error_message);
-                        unresponsive_node_urls.insert(uri.clone());
-                        Err(OverseerError::UnresponsiveNodeError(error_message))
-                    }
+                Err(err) => {
+                    let error_message = format!(
+                        "Unable to retrieve ingest summary for node ({}): {}",
+                        uri, err
+                    );
+                    log::trace!(logger, "{}", error_message);
+                    unresponsive_node_urls.insert(uri.clone());
+                    first_error.get_or_insert(OverseerError::UnresponsiveNodeError(error_message));
                 }
-            })
-            .collect()
+            }
+        }
+        (mappings, first_error)
+    }
+
+    /// Scores the health of the outstanding keys, raises alerts about them and
+    /// publishes the result to the shared status.
+    ///
+    /// If overseer is enabled and automatic lost key reporting is configured,
+    /// keys whose outage has lasted long enough are reported as lost, and new
+    /// keys are set on an idle node which is then activated.
+    ///
+    /// Returns true if a key was rotated away from.
+    fn check_key_health(
+        &mut self,
+        ingest_summary_node_mappings: &[IngestSummaryNodeMapping],
+        is_enabled: bool,
+    ) -> Result<bool, OverseerError> {
+        let now = Instant::now();
+        let records = self.get_outstanding_key_records()?;
+
+        let active_keys: HashMap<CompressedRistrettoPublic, FogIngestUri> =
+            ingest_summary_node_mappings
+                .iter()
+                .filter(|mapping| mapping.ingest_summary.mode == IngestControllerMode::Active)
+                .filter_map(|mapping| {
+                    let key = CompressedRistrettoPublic::try_from(
+                        mapping.ingest_summary.get_ingress_pubkey(),
+                    )
+                    .ok()?;
+                    let uri = self.ingest_clients[mapping.node_index].get_uri().clone();
+                    Some((key, uri))
+                })
+                .collect();
+
+        let key_health = self
+            .key_health_tracker
+            .evaluate(&records, &active_keys, now);
+        for health in &key_health {
+            for issue in [
+                KeyHealthIssue::ExpiringSoon,
+                KeyHealthIssue::Stalled,
+                KeyHealthIssue::NoActiveNode,
+            ] {
+                let (kind, severity, message) = match issue {
+                    KeyHealthIssue::ExpiringSoon => (
+                        AlertKind::KeyExpiringSoon,
+                        AlertSeverity::Warning,
+                        format!(
+                            "Pubkey expiry {} is only {} blocks after the next block to scan",
+                            health.pubkey_expiry, health.blocks_until_expiry
+                        ),
+                    ),
+                    KeyHealthIssue::Stalled => (
+                        AlertKind::KeyStalled,
+                        AlertSeverity::Warning,
+                        format!(
+                            "Last scanned block {:?} has not advanced for {} seconds",
+                            health.last_scanned_block, health.seconds_since_progress
+                        ),
+                    ),
+                    KeyHealthIssue::NoActiveNode => (
+                        AlertKind::KeyOutage,
+                        AlertSeverity::Critical,
+                        format!(
+                            "No responsive node is active with this key, outage for {:?} seconds",
+                            health.outage_seconds
+                        ),
+                    ),
+                };
+                if health.issues.contains(&issue) {
+                    self.alerter.raise(kind, severity, &health.key, message);
+                } else {
+                    self.alerter.clear(kind, &health.key);
+                }
+            }
+        }
+
+        let missed_block_ranges = self.recovery_db.get_missed_block_ranges()?;
+        if let Some((previous, current)) = self
+            .key_health_tracker
+            .observe_missed_blocks(&missed_block_ranges)
+        {
+            self.alerter.raise(
+                AlertKind::MissedBlocksIncreased,
+                AlertSeverity::Warning,
+                format!("{}", current),
+                format!(
+                    "Missed blocks increased from {} to {}, users will have to download them",
+                    previous, current
+                ),
+            );
+        }
+
+        {
+            let mut status = self.status.lock().expect("mutex poisoned");
+            status.key_health = key_health;
+            status.missed_block_count = Some(
+                missed_block_ranges
+                    .iter()
+                    .map(|range| range.end_block.saturating_sub(range.start_block))
+                    .sum(),
+            );
+        }
+
+        if !is_enabled {
+            return Ok(false);
+        }
+        let lost_keys = self.key_health_tracker.keys_to_report_lost(now);
+        if lost_keys.is_empty() {
+            return Ok(false);
+        }
+        for lost_key in lost_keys {
+            log::warn!(
+                self.logger,
+                "Outage of key {} crossed the configured threshold, reporting it as lost",
+                lost_key
+            );
+            self.report_lost_ingress_key(lost_key)?;
+        }
+        // Only rotate if no node is active, since a node that is active with
+        // another key is already scanning.
+        if active_keys.is_empty() {
+            let activated_node_index = self.set_new_key_on_a_node()?;
+            self.activate_a_node(activated_node_index)?;
+            self.alerter.raise(
+                AlertKind::KeyRotated,
+                AlertSeverity::Info,
+                self.ingest_clients[activated_node_index]
+                    .get_uri()
+                    .to_string(),
+                "Set new keys on this node and activated it, after reporting a key as lost",
+            );
+        }
+        Ok(true)
     }
 
     /// Performs automatic failover, which means that we try to activate nodes
@@ -349,6 +562,16 @@ where
     fn get_inactive_outstanding_keys(
         &self,
     ) -> Result<Vec<CompressedRistrettoPublic>, OverseerError> {
+        Ok(self
+            .get_outstanding_key_records()?
+            .iter()
+            .map(|record| record.key)
+            .collect())
+    }
+
+    /// Gets the records of the keys that Fog Ingest is still obligated to be
+    /// scanning blocks with.
+    fn get_outstanding_key_records(&self) -> Result<Vec<IngressPublicKeyRecord>, OverseerError> {
         // An outanding key is one that Fog Ingest is still obligated to be
         // scanning blocks with on behalf of users.
         let outstanding_keys_filters = IngressPublicKeyRecordFilters {
@@ -370,16 +593,10 @@ where
         // keys that we've grabbed from the RecoveryDb.
         //
         // TODO: Add a config that allows us to set this start block.
-        let ingress_public_key_records: Vec<IngressPublicKeyRecord> =
-            self.recovery_db.get_ingress_key_records(
-                /* start_block_at_least= */ 0,
-                &outstanding_keys_filters,
-            )?;
-
-        Ok(ingress_public_key_records
-            .iter()
-            .map(|record| record.key)
-            .collect())
+        Ok(self.recovery_db.get_ingress_key_records(
+            /* start_block_at_least= */ 0,
+            &outstanding_keys_filters,
+        )?)
     }
 
     /// Performs the following logic when one inactive outstanding key is found:
@@ -417,6 +634,12 @@ where
                             "Successfully activated node {}.",
                             node.get_uri()
                         );
+                        self.record_action(
+                            OverseerActionKind::ActivateNode,
+                            Some(node.get_uri()),
+                            Some(&inactive_outstanding_key),
+                            "No node was active, and this idle node has the outstanding key",
+                        );
                         return Ok(());
                     }
                     Err(err) => {
@@ -464,6 +687,18 @@ where
                         "The following key was successfully reported as lost: {}",
                         inactive_outstanding_key
                     );
+                    self.record_action(
+                        OverseerActionKind::ReportLostKey,
+                        None,
+                        Some(&inactive_outstanding_key),
+                        "No responsive node can scan with this key",
+                    );
+                    self.alerter.raise(
+                        AlertKind::LostKeyReported,
+                        AlertSeverity::Critical,
+                        inactive_outstanding_key.to_string(),
+                        "Reported this key as lost, users will have to download the blocks it did not scan",
+                    );
                     OperationResult::Ok(())
                 }
                 Err(err) => {
@@ -491,6 +726,12 @@ where
                                 "New keys successfully set on the ingest node {}.",
                                 ingest_client.get_uri()
                             );
+                            self.record_action(
+                                OverseerActionKind::SetNewKeys,
+                                Some(ingest_client.get_uri()),
+                                None,
+                                "No idle node has an outstanding key",
+                            );
                             OperationResult::Ok(())
                         }
                         // TODO: We'll need to alert Ops to take manual action at this point.
@@ -527,6 +768,12 @@ where
                             "Node {} successfully activated.",
                             ingest_client.get_uri(),
                         );
+                        self.record_action(
+                            OverseerActionKind::ActivateNode,
+                            Some(ingest_client.get_uri()),
+                            None,
+                            "No node was active, and new keys were set on this node",
+                        );
                         OperationResult::Ok(())
                     }
                     // TODO: Alert Ops to take manual action at this point.
@@ -547,4 +794,24 @@ where
 
         Ok(result?)
     }
+
+    /// Records an action in the shared status.
+    fn record_action(
+        &self,
+        kind: OverseerActionKind,
+        node: Option<&FogIngestUri>,
+        key: Option<&CompressedRistrettoPublic>,
+        reason: &str,
+    ) {
+        self.status
+            .lock()
+            .expect("mutex poisoned")
+            .record_action(OverseerAction {
+                kind,
+                node: node.cloned(),
+                key: key.map(|key| key.to_string()),
+                reason: reason.to_owned(),
+                timestamp: now_unix_seconds(),
+            });
+    }
 }
@@ -0,0 +1,39 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+mod utils;
+
+use mc_common::logger::{test_with_logger, Logger};
+use mc_fog_ingest_server_test_utils::IngestServerTestHelper;
+use serde_json::Value;
+use utils::TestHelperExt;
+
+const BASE_PORT: u16 = 8900;
+
+// When the Fog Ingest cluster has one active node, its key should be reported
+// as healthy, and Fog Overseer shouldn't take or report any action.
+#[test_with_logger]
+fn one_active_node_key_is_healthy(logger: Logger) {
+    let mut helper = IngestServerTestHelper::new(BASE_PORT, logger.clone());
+    helper.add_origin_block();
+    let nodes = helper.make_nodes(3);
+
+    nodes[0].activate().expect("first node failed to activate");
+    let active_ingress_key = nodes[0].get_ingress_key();
+
+    // Initialize an OverseerService with an associated server.
+    let client = helper.enable_overseer_for_nodes(&nodes);
+
+    let response = client.get("/key_health").dispatch();
+    let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let key_health = body["key_health"].as_array().unwrap();
+    assert_eq!(key_health.len(), 1, "unexpected key health: {}", body);
+    assert_eq!(key_health[0]["key"], active_ingress_key.to_string());
+    assert_eq!(key_health[0]["score"], 100);
+    assert!(key_health[0]["issues"].as_array().unwrap().is_empty());
+    assert!(!key_health[0]["active_node"].is_null());
+    assert_eq!(body["auto_report_lost_key_after_seconds"], Value::Null);
+
+    let response = client.get("/actions").dispatch();
+    let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert!(body["actions"].as_array().unwrap().is_empty());
+}
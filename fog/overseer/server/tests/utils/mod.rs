@@ -4,6 +4,7 @@
 
 use mc_fog_ingest_server_test_utils::{IngestServerTestHelper, TestIngestNode};
 use mc_fog_overseer_server::{
+    config::KeyHealthConfig,
     server::{initialize_rocket_server, OverseerState},
     service::OverseerService,
 };
@@ -25,8 +26,12 @@ pub trait TestHelperExt {
 
 impl TestHelperExt for IngestServerTestHelper {
     fn enable_overseer(&self, ingest_uris: Vec<FogIngestUri>) -> Client {
-        let mut overseer_service =
-            OverseerService::new(ingest_uris, self.recovery_db.clone(), self.logger.clone());
+        let mut overseer_service = OverseerService::new(
+            ingest_uris,
+            self.recovery_db.clone(),
+            KeyHealthConfig::default(),
+            self.logger.clone(),
+        );
         overseer_service
             .start()
             .expect("OverseerService failed to start");
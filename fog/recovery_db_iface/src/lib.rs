@@ -19,7 +19,7 @@ pub use mc_blockchain_types::Block;
 pub use mc_fog_types::{common::BlockRange, ETxOutRecord};
 pub use types::{
     AddBlockDataStatus, FogUserEvent, IngestInvocationId, IngestableRange, IngressPublicKeyRecord,
-    IngressPublicKeyStatus, ReportData,
+    IngressPublicKeyStatus, ReportData, ReportIdWindow,
 };
 
 /// Contains fields that are used as filters in  queries for ingress keys.
@@ -349,4 +349,25 @@ pub trait ReportDb {
 
     /// Remove report data associated with a given report id.
     fn remove_report(&self, report_id: &str) -> Result<(), Self::Error>;
+
+    /// Get the serving windows configured for report ids.
+    ///
+    /// Report ids without a window are always served. Windows are kept after
+    /// the report itself is removed, so that a retired report id is not served
+    /// again if ingest publishes it later.
+    ///
+    /// Returns:
+    /// * Pairs of the form report-id, window
+    fn get_report_id_windows(&self) -> Result<Vec<(String, ReportIdWindow)>, Self::Error>;
+
+    /// Set the serving window for a report id, replacing any existing window.
+    ///
+    /// Arguments:
+    /// * report_id - the report id the window applies to
+    /// * window - the window during which the report id is served
+    fn set_report_id_window(
+        &self,
+        report_id: &str,
+        window: &ReportIdWindow,
+    ) -> Result<(), Self::Error>;
 }
@@ -225,6 +225,24 @@ pub struct ReportData {
     pub pubkey_expiry: u64,
 }
 
+/// The window of time, in seconds since the unix epoch, during which a report
+/// id is served. A bound of `None` means the window is open on that side.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReportIdWindow {
+    /// The first second at which the report id is served
+    pub not_before: Option<u64>,
+    /// The first second at which the report id is no longer served
+    pub not_after: Option<u64>,
+}
+
+impl ReportIdWindow {
+    /// Check if the window contains the given timestamp
+    pub fn contains(&self, now: u64) -> bool {
+        self.not_before.map_or(true, |not_before| not_before <= now)
+            && self.not_after.map_or(true, |not_after| now < not_after)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,205 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Implementation of the ReportAdminApi
+
+use crate::report_ids::{
+    now_unix_seconds, ReportIdStatus, ReportIdWindow, ReportIdWindows, SharedReportIdWindows,
+};
+use grpcio::{RpcContext, RpcStatus, UnarySink};
+use mc_common::logger::{self, Logger};
+use mc_fog_api::{
+    report_admin::{
+        AddReportIdRequest, GetReportIdsResponse, ReportIdStatus as ProtobufReportIdStatus,
+        ReportIdWindow as ProtobufReportIdWindow, RetireReportIdRequest,
+    },
+    report_admin_grpc::ReportAdminApi,
+    Empty,
+};
+use mc_fog_recovery_db_iface::ReportDb;
+use mc_util_grpc::{rpc_database_err, rpc_invalid_arg_error, rpc_logger, send_result};
+use mc_util_metrics::SVC_COUNTERS;
+use protobuf::RepeatedField;
+
+/// Implements the report admin grpc api
+#[derive(Clone)]
+pub struct AdminService<R: ReportDb + Clone + Send + Sync> {
+    /// Access to the Report db is needed to list the report ids it contains,
+    /// and to store their serving windows
+    report_db: R,
+
+    /// The serving windows used by the report service, refreshed whenever
+    /// they are changed
+    windows: SharedReportIdWindows,
+
+    /// Slog logger object
+    logger: Logger,
+}
+
+impl<R: ReportDb + Clone + Send + Sync> AdminService<R> {
+    /// Creates a new report admin service
+    pub fn new(report_db: R, windows: SharedReportIdWindows, logger: Logger) -> Self {
+        Self {
+            report_db,
+            windows,
+            logger,
+        }
+    }
+
+    /// Logic of proto api
+    fn get_report_ids_impl(&self, logger: &Logger) -> Result<GetReportIdsResponse, RpcStatus> {
+        let windows = self
+            .windows
+            .get(&self.report_db)
+            .map_err(|err| rpc_database_err(err, logger))?;
+        self.report_ids_response(&windows, logger)
+    }
+
+    /// Build the response listing the status of every report id
+    fn report_ids_response(
+        &self,
+        windows: &ReportIdWindows,
+        logger: &Logger,
+    ) -> Result<GetReportIdsResponse, RpcStatus> {
+        let db_reports = self
+            .report_db
+            .get_all_reports()
+            .map_err(|err| rpc_database_err(err, logger))?;
+
+        let mut response = GetReportIdsResponse::new();
+        response.set_report_ids(RepeatedField::from_vec(
+            windows
+                .statuses(&db_reports, now_unix_seconds())
+                .into_iter()
+                .map(ProtobufReportIdStatus::from)
+                .collect(),
+        ));
+        Ok(response)
+    }
+
+    /// Logic of proto api
+    fn add_report_id_impl(
+        &self,
+        request: AddReportIdRequest,
+        logger: &Logger,
+    ) -> Result<GetReportIdsResponse, RpcStatus> {
+        let window = ReportIdWindow::from(request.get_window());
+        if let (Some(not_before), Some(not_after)) = (window.not_before, window.not_after) {
+            if not_after <= not_before {
+                return Err(rpc_invalid_arg_error(
+                    "add_report_id",
+                    "window.not_after must be greater than window.not_before",
+                    logger,
+                ));
+            }
+        }
+
+        self.set_window(request.get_report_id(), &window, logger)
+    }
+
+    /// Logic of proto api
+    fn retire_report_id_impl(
+        &self,
+        request: RetireReportIdRequest,
+        logger: &Logger,
+    ) -> Result<GetReportIdsResponse, RpcStatus> {
+        let window = ReportIdWindows::load(&self.report_db)
+            .map_err(|err| rpc_database_err(err, logger))?
+            .retire(request.get_report_id(), now_unix_seconds());
+        self.set_window(request.get_report_id(), &window, logger)
+    }
+
+    /// Store the window of a report id, and refresh the windows served by the
+    /// report service
+    fn set_window(
+        &self,
+        report_id: &str,
+        window: &ReportIdWindow,
+        logger: &Logger,
+    ) -> Result<GetReportIdsResponse, RpcStatus> {
+        self.report_db
+            .set_report_id_window(report_id, window)
+            .map_err(|err| rpc_database_err(err, logger))?;
+        let windows = self
+            .windows
+            .refresh(&self.report_db)
+            .map_err(|err| rpc_database_err(err, logger))?;
+        self.report_ids_response(&windows, logger)
+    }
+}
+
+impl From<&ProtobufReportIdWindow> for ReportIdWindow {
+    fn from(src: &ProtobufReportIdWindow) -> Self {
+        Self {
+            not_before: Some(src.get_not_before()).filter(|val| *val != 0),
+            not_after: Some(src.get_not_after()).filter(|val| *val != 0),
+        }
+    }
+}
+
+impl From<&ReportIdWindow> for ProtobufReportIdWindow {
+    fn from(src: &ReportIdWindow) -> Self {
+        let mut window = ProtobufReportIdWindow::new();
+        window.set_not_before(src.not_before.unwrap_or_default());
+        window.set_not_after(src.not_after.unwrap_or_default());
+        window
+    }
+}
+
+impl From<ReportIdStatus> for ProtobufReportIdStatus {
+    fn from(src: ReportIdStatus) -> Self {
+        let mut status = ProtobufReportIdStatus::new();
+        status.set_report_id(src.report_id);
+        status.set_in_database(src.pubkey_expiry.is_some());
+        status.set_pubkey_expiry(src.pubkey_expiry.unwrap_or_default());
+        status.set_managed(src.window.is_some());
+        if let Some(window) = src.window.as_ref() {
+            status.set_window(window.into());
+        }
+        status.set_served(src.served);
+        status
+    }
+}
+
+// Implement grpc trait
+impl<R: ReportDb + Clone + Send + Sync> ReportAdminApi for AdminService<R> {
+    fn get_report_ids(
+        &mut self,
+        ctx: RpcContext,
+        _request: Empty,
+        sink: UnarySink<GetReportIdsResponse>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            send_result(ctx, sink, self.get_report_ids_impl(logger), logger)
+        })
+    }
+
+    fn add_report_id(
+        &mut self,
+        ctx: RpcContext,
+        request: AddReportIdRequest,
+        sink: UnarySink<GetReportIdsResponse>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            send_result(ctx, sink, self.add_report_id_impl(request, logger), logger)
+        })
+    }
+
+    fn retire_report_id(
+        &mut self,
+        ctx: RpcContext,
+        request: RetireReportIdRequest,
+        sink: UnarySink<GetReportIdsResponse>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            send_result(
+                ctx,
+                sink,
+                self.retire_report_id_impl(request, logger),
+                logger,
+            )
+        })
+    }
+}
@@ -3,7 +3,7 @@
 //! Main Method for the Fog Report Server
 
 use mc_common::{logger, sentry};
-use mc_fog_report_server::{Config, Materials, Server};
+use mc_fog_report_server::{Config, Materials, MaterialsReloader, Server};
 use mc_fog_sql_recovery_db::SqlRecoveryDb;
 use mc_util_cli::ParserWithBuildInfo;
 use mc_util_grpc::AdminServer;
@@ -18,6 +18,9 @@ fn main() {
     let config = Config::parse();
 
     let materials = Materials::try_from(&config).expect("Could not read cryptographic materials");
+    let materials_reloader =
+        MaterialsReloader::new(&config.signing_chain, &config.signing_key, logger.clone())
+            .expect("Could not register SIGHUP handler for cryptographic materials");
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL environment variable missing");
     let db = SqlRecoveryDb::new_from_url(
@@ -32,7 +35,15 @@ fn main() {
         )
     });
 
-    let mut server = Server::new(db, &config.client_listen_uri, materials, logger.clone());
+    let mut server = Server::new(
+        db,
+        &config.client_listen_uri,
+        config.report_admin_listen_uri.as_ref(),
+        materials,
+        Some(materials_reloader),
+        config.db_poll_interval,
+        logger.clone(),
+    );
     server.start();
 
     let config_json = serde_json::to_string(&config).expect("failed to serialize config to JSON");
@@ -8,10 +8,11 @@ use displaydoc::Display;
 use mc_crypto_keys::{DistinguishedEncoding, Ed25519Pair, Ed25519Private, Ed25519Public, KeyError};
 use mc_crypto_x509_utils::{ChainError, X509CertificateChain, X509CertificateIter};
 use mc_fog_sql_recovery_db::SqlRecoveryDbConnectionConfig;
+use mc_util_parse::parse_duration_in_seconds;
 use mc_util_uri::{AdminUri, FogUri};
 use pem::PemError;
 use serde::Serialize;
-use std::{fs, io::Error as IoError, path::PathBuf, result::Result as StdResult};
+use std::{fs, io::Error as IoError, path::PathBuf, result::Result as StdResult, time::Duration};
 use x509_signature::X509Certificate;
 
 /// Configuration options for the report server
@@ -38,6 +39,16 @@ pub struct Config {
     #[clap(long, parse(from_os_str), env = "MC_SIGNING_KEY")]
     pub signing_key: PathBuf,
 
+    /// Internal gRPC listening URI for the report admin API, used to manage
+    /// which report ids are served. If omitted, the admin API is not served.
+    #[clap(long, env = "MC_REPORT_ADMIN_LISTEN_URI")]
+    pub report_admin_listen_uri: Option<AdminUri>,
+
+    /// How long, in seconds, reports read from the database are reused before
+    /// querying the database again. 0 means query it on every request.
+    #[clap(long, default_value = "1", parse(try_from_str = parse_duration_in_seconds), env = "MC_DB_POLL_INTERVAL")]
+    pub db_poll_interval: Duration,
+
     /// Postgres config
     #[clap(flatten)]
     pub postgres_config: SqlRecoveryDbConnectionConfig,
@@ -112,8 +123,7 @@ impl Materials {
     /// Construct from a PEM chain and a keypair
     pub fn from_pem_keypair(pem_chain: String, signing_keypair: Ed25519Pair) -> Result<Self> {
         // Convert the PEM chain into the DER chain we want to use
-        let chain = pem::parse_many(pem_chain)
-            .expect("Could not parse PEM chain")
+        let chain = pem::parse_many(pem_chain)?
             .into_iter()
             .map(|pem| pem.contents)
             .collect();
@@ -4,11 +4,16 @@
 
 #![deny(missing_docs)]
 
+mod admin_service;
 mod config;
+mod materials_reloader;
+mod report_ids;
 mod server;
 mod service;
 
 pub use crate::{
     config::{Config, Error, Materials},
+    materials_reloader::MaterialsReloader,
+    report_ids::{ReportIdStatus, ReportIdWindow, ReportIdWindows},
     server::Server,
 };
@@ -0,0 +1,88 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Reloads the report signing chain and key from disk when a SIGHUP is
+//! received, in the same way as `mc_util_grpc::ServerCertReloader` does for
+//! TLS certificates.
+
+use crate::config::{Error, Materials};
+use mc_common::logger::{log, Logger};
+use signal_hook::{consts::SIGHUP, flag};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Watches the signing chain and key files, and reloads them on SIGHUP.
+pub struct MaterialsReloader {
+    /// The path to the X509 certificate chain in PEM format.
+    signing_chain: PathBuf,
+
+    /// The path to the signing key.
+    signing_key: PathBuf,
+
+    /// Signal that we need to re-load the chain and key files.
+    load_needed: Arc<AtomicBool>,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl MaterialsReloader {
+    /// Create a new MaterialsReloader that watches `signing_chain` and
+    /// `signing_key`.
+    ///
+    /// The materials are not loaded until SIGHUP is received, the caller is
+    /// expected to have loaded the initial materials already.
+    pub fn new(
+        signing_chain: &impl AsRef<Path>,
+        signing_key: &impl AsRef<Path>,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let load_needed = Arc::new(AtomicBool::new(false));
+
+        flag::register(SIGHUP, load_needed.clone())?;
+
+        Ok(Self {
+            signing_chain: signing_chain.as_ref().to_path_buf(),
+            signing_key: signing_key.as_ref().to_path_buf(),
+            load_needed,
+            logger,
+        })
+    }
+
+    /// Reload the materials, if a SIGHUP was received since the last call.
+    ///
+    /// Returns None if no reload was requested, or if the files on disk could
+    /// not be loaded, in which case the previous materials should remain in
+    /// use.
+    pub fn poll(&self) -> Option<Materials> {
+        if !self.load_needed.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+
+        log::info!(self.logger, "Reloading report signing materials");
+
+        let result = fs::read_to_string(&self.signing_chain)
+            .map_err(Error::from)
+            .and_then(|pem_chain| {
+                let pem_privkey = fs::read_to_string(&self.signing_key)?;
+                Materials::from_pems(pem_chain, pem_privkey)
+            });
+
+        match result {
+            Ok(materials) => Some(materials),
+            Err(err) => {
+                log::error!(
+                    self.logger,
+                    "Could not reload report signing materials, keeping the previous ones: {}",
+                    err
+                );
+                None
+            }
+        }
+    }
+}
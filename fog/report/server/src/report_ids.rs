@@ -0,0 +1,237 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Management of which report ids are served to clients.
+//!
+//! Ingest publishes reports to the database under a report id (almost always
+//! the empty string). Operators may want to stage a new report id that only
+//! starts being served at a given time, or stop serving a report id without
+//! waiting for ingest to remove it. The serving windows are stored in the
+//! recovery database, so that they survive restarts and are shared by every
+//! report server using that database.
+
+pub use mc_fog_recovery_db_iface::ReportIdWindow;
+
+use mc_fog_recovery_db_iface::{ReportData, ReportDb};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The state of a single report id, as reported by the admin API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportIdStatus {
+    /// The report id
+    pub report_id: String,
+    /// The pubkey expiry of the report in the database, if there is one
+    pub pubkey_expiry: Option<u64>,
+    /// The serving window configured for this report id, if any
+    pub window: Option<ReportIdWindow>,
+    /// Whether this report id is currently served to clients
+    pub served: bool,
+}
+
+/// The serving windows of report ids, as read from the database.
+///
+/// Report ids without a window are always served.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReportIdWindows {
+    windows: BTreeMap<String, ReportIdWindow>,
+}
+
+impl ReportIdWindows {
+    /// Load the serving windows from the database
+    pub fn load<R: ReportDb>(report_db: &R) -> Result<Self, R::Error> {
+        Ok(Self {
+            windows: report_db.get_report_id_windows()?.into_iter().collect(),
+        })
+    }
+
+    /// Add a report id, replacing its window if it was already managed
+    pub fn add(&mut self, report_id: &str, window: ReportIdWindow) {
+        self.windows.insert(report_id.to_owned(), window);
+    }
+
+    /// Retire a report id as of `now`, and return its new window. If the report
+    /// id was scheduled to start being served in the future, it will never be
+    /// served.
+    pub fn retire(&mut self, report_id: &str, now: u64) -> ReportIdWindow {
+        let window = self.windows.entry(report_id.to_owned()).or_default();
+        window.not_after = Some(window.not_after.map_or(now, |not_after| not_after.min(now)));
+        if window
+            .not_before
+            .map_or(false, |not_before| not_before > now)
+        {
+            window.not_before = Some(now);
+        }
+        *window
+    }
+
+    /// Check whether a report id should be served at time `now`
+    pub fn is_served(&self, report_id: &str, now: u64) -> bool {
+        self.windows
+            .get(report_id)
+            .map_or(true, |window| window.contains(now))
+    }
+
+    /// Filter a list of reports from the database down to the ones which
+    /// should be served at time `now`.
+    pub fn filter(&self, reports: &[(String, ReportData)], now: u64) -> Vec<(String, ReportData)> {
+        reports
+            .iter()
+            .filter(|(report_id, _)| self.is_served(report_id, now))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the status of every report id which is either in the given list of
+    /// reports from the database, or has a configured window.
+    pub fn statuses(&self, reports: &[(String, ReportData)], now: u64) -> Vec<ReportIdStatus> {
+        let mut result = BTreeMap::<String, ReportIdStatus>::new();
+        for (report_id, window) in self.windows.iter() {
+            result.insert(
+                report_id.clone(),
+                ReportIdStatus {
+                    report_id: report_id.clone(),
+                    pubkey_expiry: None,
+                    window: Some(*window),
+                    served: false,
+                },
+            );
+        }
+        for (report_id, report_data) in reports {
+            let status = result
+                .entry(report_id.clone())
+                .or_insert_with(|| ReportIdStatus {
+                    report_id: report_id.clone(),
+                    pubkey_expiry: None,
+                    window: None,
+                    served: false,
+                });
+            status.pubkey_expiry = Some(report_data.pubkey_expiry);
+            status.served = status.window.map_or(true, |window| window.contains(now));
+        }
+        result.into_values().collect()
+    }
+}
+
+/// The serving windows, shared between the report and admin services.
+///
+/// The windows are read from the database on first use, and are then only
+/// read again when they are changed through the admin API.
+#[derive(Clone, Debug, Default)]
+pub struct SharedReportIdWindows {
+    windows: Arc<Mutex<Option<ReportIdWindows>>>,
+}
+
+impl SharedReportIdWindows {
+    /// Get the current windows, loading them from the database if needed
+    pub fn get<R: ReportDb>(&self, report_db: &R) -> Result<ReportIdWindows, R::Error> {
+        let mut windows = self.windows.lock().expect("mutex poisoned");
+        if windows.is_none() {
+            *windows = Some(ReportIdWindows::load(report_db)?);
+        }
+        Ok(windows.clone().unwrap_or_default())
+    }
+
+    /// Reload the windows from the database, after they were changed
+    pub fn refresh<R: ReportDb>(&self, report_db: &R) -> Result<ReportIdWindows, R::Error> {
+        let windows = ReportIdWindows::load(report_db)?;
+        *self.windows.lock().expect("mutex poisoned") = Some(windows.clone());
+        Ok(windows)
+    }
+}
+
+/// The current time, in seconds since the unix epoch
+pub fn now_unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_attest_core::VerificationReport;
+
+    fn report_data(pubkey_expiry: u64) -> ReportData {
+        ReportData {
+            ingest_invocation_id: None,
+            report: VerificationReport::default(),
+            pubkey_expiry,
+        }
+    }
+
+    #[test]
+    fn unmanaged_report_ids_are_served() {
+        let windows = ReportIdWindows::default();
+        let reports = vec![("".to_owned(), report_data(10))];
+
+        assert!(windows.is_served("", 100));
+        assert_eq!(windows.filter(&reports, 100), reports);
+
+        let statuses = windows.statuses(&reports, 100);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].pubkey_expiry, Some(10));
+        assert_eq!(statuses[0].window, None);
+        assert!(statuses[0].served);
+    }
+
+    #[test]
+    fn windows_are_respected() {
+        let mut windows = ReportIdWindows::default();
+        windows.add(
+            "next",
+            ReportIdWindow {
+                not_before: Some(100),
+                not_after: Some(200),
+            },
+        );
+        let reports = vec![
+            ("".to_owned(), report_data(10)),
+            ("next".to_owned(), report_data(20)),
+        ];
+
+        assert_eq!(windows.filter(&reports, 99).len(), 1);
+        assert_eq!(windows.filter(&reports, 100).len(), 2);
+        assert_eq!(windows.filter(&reports, 199).len(), 2);
+        assert_eq!(windows.filter(&reports, 200).len(), 1);
+    }
+
+    #[test]
+    fn retire_stops_serving() {
+        let mut windows = ReportIdWindows::default();
+        windows.add(
+            "later",
+            ReportIdWindow {
+                not_before: Some(500),
+                not_after: None,
+            },
+        );
+        assert_eq!(
+            windows.retire("", 100),
+            ReportIdWindow {
+                not_before: None,
+                not_after: Some(100),
+            }
+        );
+        assert_eq!(
+            windows.retire("later", 100),
+            ReportIdWindow {
+                not_before: Some(100),
+                not_after: Some(100),
+            }
+        );
+
+        assert!(!windows.is_served("", 100));
+        assert!(!windows.is_served("later", 600));
+
+        // A report id that was retired but is no longer in the database is still
+        // listed, since it is managed.
+        let statuses = windows.statuses(&[], 100);
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.iter().all(|status| !status.served));
+        assert!(statuses.iter().all(|status| status.pubkey_expiry.is_none()));
+    }
+}
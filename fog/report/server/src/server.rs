@@ -2,28 +2,46 @@
 
 //! Server for ingest reports.
 
-use crate::{config::Materials, service::Service};
+use crate::{
+    admin_service::AdminService, config::Materials, materials_reloader::MaterialsReloader,
+    report_ids::SharedReportIdWindows, service::Service,
+};
 use futures::executor::block_on;
 use grpcio::{Server as GrpcioServer, ServerBuilder};
 use mc_common::logger::{log, Logger};
-use mc_fog_api::report_grpc;
+use mc_fog_api::{report_admin_grpc, report_grpc};
 use mc_fog_recovery_db_iface::ReportDb;
 use mc_util_grpc::{ConnectionUriGrpcioServer, HealthService};
-use mc_util_uri::{ConnectionUri, FogUri};
-use std::sync::Arc;
+use mc_util_uri::{AdminUri, ConnectionUri, FogUri};
+use std::{sync::Arc, time::Duration};
 
-/// The application server structure, contains the gRPC server and logger.
+/// The application server structure, contains the gRPC servers and logger.
 pub struct Server {
     server: GrpcioServer,
+    admin_server: Option<GrpcioServer>,
     logger: Logger,
 }
 
 impl Server {
     /// Construct a new server object.
+    ///
+    /// Arguments:
+    /// * db - The database reports are read from
+    /// * client_listen_uri - The uri clients request reports on
+    /// * report_admin_listen_uri - The uri the report admin api is served on,
+    ///   if any
+    /// * materials - The initial signing materials
+    /// * materials_reloader - Reloads the signing materials on SIGHUP, if any
+    /// * db_poll_interval - How long reports read from the database are reused
+    ///   before querying it again
+    /// * logger - Logger
     pub fn new(
         db: impl ReportDb + Clone + Send + Sync + 'static,
         client_listen_uri: &FogUri,
+        report_admin_listen_uri: Option<&AdminUri>,
         materials: Materials,
+        materials_reloader: Option<MaterialsReloader>,
+        db_poll_interval: Duration,
         logger: Logger,
     ) -> Self {
         let env = Arc::new(
@@ -32,8 +50,18 @@ impl Server {
                 .build(),
         );
 
-        let report_service =
-            report_grpc::create_report_api(Service::new(db, materials, logger.clone()));
+        // Report id windows are shared so that changes made through the admin API
+        // take effect immediately.
+        let windows = SharedReportIdWindows::default();
+
+        let report_service = report_grpc::create_report_api(Service::new(
+            db.clone(),
+            materials,
+            materials_reloader.map(Arc::new),
+            windows.clone(),
+            db_poll_interval,
+            logger.clone(),
+        ));
         log::debug!(logger, "Constructed Report GRPC Service");
 
         // Health check service
@@ -52,7 +80,31 @@ impl Server {
 
         let server = server_builder.build().unwrap();
 
-        Self { server, logger }
+        let admin_server = report_admin_listen_uri.map(|report_admin_listen_uri| {
+            let admin_service = report_admin_grpc::create_report_admin_api(AdminService::new(
+                db,
+                windows,
+                logger.clone(),
+            ));
+            log::debug!(logger, "Constructed Report Admin GRPC Service");
+
+            log::info!(
+                logger,
+                "Starting Report admin server on {}",
+                report_admin_listen_uri.addr(),
+            );
+            ServerBuilder::new(env.clone())
+                .register_service(admin_service)
+                .bind_using_uri(report_admin_listen_uri, logger.clone())
+                .build()
+                .unwrap()
+        });
+
+        Self {
+            server,
+            admin_server,
+            logger,
+        }
     }
 
     /// Start the server.
@@ -61,11 +113,20 @@ impl Server {
         for (host, port) in self.server.bind_addrs() {
             log::info!(self.logger, "API listening on {}:{}", host, port);
         }
+        if let Some(admin_server) = self.admin_server.as_mut() {
+            admin_server.start();
+            for (host, port) in admin_server.bind_addrs() {
+                log::info!(self.logger, "Admin API listening on {}:{}", host, port);
+            }
+        }
     }
 
     /// Stop the server.
     pub fn stop(&mut self) {
         block_on(self.server.shutdown()).expect("Could not stop grpc server");
+        if let Some(admin_server) = self.admin_server.as_mut() {
+            block_on(admin_server.shutdown()).expect("Could not stop admin grpc server");
+        }
     }
 }
 
@@ -2,7 +2,11 @@
 
 //! Implementation of the ReportService
 
-use crate::config::Materials;
+use crate::{
+    config::Materials,
+    materials_reloader::MaterialsReloader,
+    report_ids::{now_unix_seconds, SharedReportIdWindows},
+};
 use displaydoc::Display;
 use grpcio::{RpcContext, RpcStatus, UnarySink};
 use mc_common::logger::{self, log, Logger};
@@ -10,13 +14,17 @@ use mc_fog_api::{
     report::{ReportRequest as ProtobufReportRequest, ReportResponse as ProtobufReportResponse},
     report_grpc::ReportApi,
 };
-use mc_fog_recovery_db_iface::{RecoveryDbError, ReportDb};
+use mc_fog_recovery_db_iface::{RecoveryDbError, ReportData, ReportDb};
 use mc_fog_report_types::{Report, ReportResponse};
 use mc_fog_sig_report::Signer as ReportSigner;
 use mc_util_grpc::{rpc_database_err, rpc_internal_error, rpc_logger, send_result};
 use mc_util_metrics::SVC_COUNTERS;
 use prost::DecodeError;
 use signature::{Error as SignatureError, Signature};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 #[derive(Clone)]
 pub struct Service<R: ReportDb + Clone + Send + Sync> {
@@ -24,13 +32,52 @@ pub struct Service<R: ReportDb + Clone + Send + Sync> {
     /// clients.
     report_db: R,
 
-    /// Cryptographic materials used in response construction
-    materials: Materials,
+    /// The signing materials and the most recent signed response
+    cache: Arc<Mutex<ReportCache>>,
+
+    /// The serving windows configured through the admin API
+    windows: SharedReportIdWindows,
+
+    /// Reloads the signing materials from disk on SIGHUP, if configured
+    materials_reloader: Option<Arc<MaterialsReloader>>,
+
+    /// How long reports read from the database may be reused before the
+    /// database is queried again
+    db_poll_interval: Duration,
 
     /// Slog logger object
     logger: Logger,
 }
 
+/// The reports most recently read from the database, and the signed response
+/// built from them.
+///
+/// The response is only re-signed when the reports that should be served, or
+/// the signing materials, change.
+///
+/// The lock around the cache is only held to read or replace its contents, so
+/// database queries and signing never block other requests.
+struct ReportCache {
+    /// Cryptographic materials used in response construction
+    materials: Arc<Materials>,
+
+    /// The reports read from the database
+    db_reports: Option<DbReports>,
+
+    /// The reports that were signed, and the resulting response
+    signed: Option<(Vec<(String, ReportData)>, ReportResponse)>,
+}
+
+/// A snapshot of the reports in the database
+#[derive(Clone)]
+struct DbReports {
+    /// When the snapshot was taken
+    loaded_at: Instant,
+
+    /// The reports in the database
+    reports: Vec<(String, ReportData)>,
+}
+
 /// An internal error type used to marshal DB and signature errors
 /// to RPC errors suitable for this service.
 #[derive(Clone, Debug, Display, Eq, PartialEq)]
@@ -69,44 +116,113 @@ impl<E: RecoveryDbError> Error<E> {
 impl<R: ReportDb + Clone + Send + Sync> Service<R> {
     /// Creates a new report service node (but does not create sockets and start
     /// it etc.)
-    pub fn new(report_db: R, materials: Materials, logger: Logger) -> Self {
+    pub fn new(
+        report_db: R,
+        materials: Materials,
+        materials_reloader: Option<Arc<MaterialsReloader>>,
+        windows: SharedReportIdWindows,
+        db_poll_interval: Duration,
+        logger: Logger,
+    ) -> Self {
         Self {
             report_db,
-            materials,
+            cache: Arc::new(Mutex::new(ReportCache {
+                materials: Arc::new(materials),
+                db_reports: None,
+                signed: None,
+            })),
+            windows,
+            materials_reloader,
+            db_poll_interval,
             logger,
         }
     }
 
-    /// Loads report data from the database, signs it, and puts the results into
-    /// constructs a new response structure.
+    /// Loads report data from the database (unless it was loaded recently),
+    /// and returns a signed response containing the reports which should
+    /// currently be served.
+    ///
+    /// The previously signed response is reused if neither the served reports
+    /// nor the signing materials changed.
     fn build_response(&self) -> Result<ReportResponse, Error<R::Error>> {
         mc_common::trace_time!(self.logger, "Building prost response from report DB");
-        let reports = self
-            .report_db
-            .get_all_reports()
+
+        if let Some(materials) = self
+            .materials_reloader
+            .as_ref()
+            .and_then(|reloader| reloader.poll())
+        {
+            log::info!(self.logger, "Report signing materials reloaded");
+            let mut cache = self.cache.lock().expect("mutex poisoned");
+            cache.materials = Arc::new(materials);
+            cache.signed = None;
+        }
+
+        let db_reports = self
+            .cache
+            .lock()
+            .expect("mutex poisoned")
+            .db_reports
+            .clone()
+            .filter(|db_reports| db_reports.loaded_at.elapsed() < self.db_poll_interval);
+        let db_reports = match db_reports {
+            Some(db_reports) => db_reports,
+            None => {
+                let db_reports = DbReports {
+                    loaded_at: Instant::now(),
+                    reports: self.report_db.get_all_reports().map_err(Error::Db)?,
+                };
+                self.cache.lock().expect("mutex poisoned").db_reports = Some(db_reports.clone());
+                db_reports
+            }
+        };
+
+        let served_reports = self
+            .windows
+            .get(&self.report_db)
             .map_err(Error::Db)?
-            .into_iter()
+            .filter(&db_reports.reports, now_unix_seconds());
+
+        let materials = {
+            let cache = self.cache.lock().expect("mutex poisoned");
+            if let Some((signed_reports, response)) = cache.signed.as_ref() {
+                if signed_reports == &served_reports {
+                    return Ok(response.clone());
+                }
+            }
+            cache.materials.clone()
+        };
+
+        let reports = served_reports
+            .iter()
             .map(|(fog_report_id, report_data)| {
                 Ok(Report {
-                    fog_report_id,
-                    report: report_data.report,
+                    fog_report_id: fog_report_id.clone(),
+                    report: report_data.report.clone(),
                     pubkey_expiry: report_data.pubkey_expiry,
                 })
             })
             .collect::<Result<Vec<Report>, DecodeError>>()?;
         log::trace!(self.logger, "Got reports from DB, signing: {:?}", reports);
-        let signature = self
-            .materials
+        let signature = materials
             .signing_keypair
             .sign_reports(&reports[..])?
             .as_bytes()
             .into();
         log::trace!(self.logger, "Reports list signature: {:?}", signature);
-        Ok(ReportResponse {
+        let response = ReportResponse {
             reports,
-            chain: self.materials.chain.clone(),
+            chain: materials.chain.clone(),
             signature,
-        })
+        };
+
+        // Only cache the response if the materials were not reloaded while we
+        // were signing.
+        let mut cache = self.cache.lock().expect("mutex poisoned");
+        if Arc::ptr_eq(&cache.materials, &materials) {
+            cache.signed = Some((served_reports, response.clone()));
+        }
+        Ok(response)
     }
 }
 
@@ -6,16 +6,20 @@ use grpcio::ChannelBuilder;
 use mc_attest_core::VerificationReport;
 use mc_common::logger::{test_with_logger, Logger};
 use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
-use mc_fog_api::{report::ReportRequest as ProtobufReportRequest, report_grpc};
+use mc_fog_api::{
+    report::ReportRequest as ProtobufReportRequest,
+    report_admin::{AddReportIdRequest, ReportIdWindow, RetireReportIdRequest},
+    report_admin_grpc, report_grpc, Empty,
+};
 use mc_fog_recovery_db_iface::{RecoveryDb, ReportData, ReportDb};
 use mc_fog_report_server::{Materials, Server};
 use mc_fog_sql_recovery_db::test_utils::SqlRecoveryDbTestContext;
 use mc_fog_test_infra::db_tests::random_kex_rng_pubkey;
 use mc_util_from_random::FromRandom;
 use mc_util_grpc::ConnectionUriGrpcioChannel;
-use mc_util_uri::FogUri;
+use mc_util_uri::{AdminUri, FogUri};
 use rand::{rngs::StdRng, SeedableRng};
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 #[test_with_logger]
 fn report_server_grpc_tests(logger: Logger) {
@@ -31,7 +35,15 @@ fn report_server_grpc_tests(logger: Logger) {
         .expect("Could not parse x509 test vectors key");
 
     let client_uri = FogUri::from_str("insecure-fog://0.0.0.0:3400").unwrap();
-    let mut server = Server::new(db, &client_uri, materials, logger.clone());
+    let mut server = Server::new(
+        db,
+        &client_uri,
+        None,
+        materials,
+        None,
+        Duration::from_secs(0),
+        logger.clone(),
+    );
     server.start();
 
     let env = Arc::new(grpcio::EnvBuilder::new().build());
@@ -155,3 +167,145 @@ fn report_server_grpc_tests(logger: Logger) {
     );
     assert_eq!(resp.reports[0].get_pubkey_expiry(), report2.pubkey_expiry);
 }
+
+#[test_with_logger]
+fn report_admin_grpc_tests(logger: Logger) {
+    let mut rng: StdRng = SeedableRng::from_seed([124u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+
+    let db = db_test_context.get_db_instance();
+    let ingress_key = CompressedRistrettoPublic::from(&RistrettoPublic::from_random(&mut rng));
+    db.new_ingress_key(&ingress_key, 1).unwrap();
+    let invoc_id = db
+        .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(&mut rng), 123)
+        .unwrap();
+
+    let report = ReportData {
+        ingest_invocation_id: Some(invoc_id),
+        report: VerificationReport {
+            sig: Default::default(),
+            chain: vec![b"asdf".to_vec(), b"jkl;".to_vec()],
+            http_body: "body".to_string(),
+        },
+        pubkey_expiry: 102030,
+    };
+    db.set_report(&ingress_key, "", &report).unwrap();
+    db.set_report(&ingress_key, "next", &report).unwrap();
+
+    let (pem_chain, signing_keypair) = mc_crypto_x509_test_vectors::ok_rsa_chain_25519_leaf();
+    let materials = Materials::from_pem_keypair(pem_chain, signing_keypair)
+        .expect("Could not parse x509 test vectors key");
+
+    let client_uri = FogUri::from_str("insecure-fog://0.0.0.0:3401").unwrap();
+    let admin_uri = AdminUri::from_str("insecure-mca://0.0.0.0:3402").unwrap();
+    let mut server = Server::new(
+        db.clone(),
+        &client_uri,
+        Some(&admin_uri),
+        materials,
+        None,
+        Duration::from_secs(0),
+        logger.clone(),
+    );
+    server.start();
+
+    let env = Arc::new(grpcio::EnvBuilder::new().build());
+
+    let report_client = {
+        let ch = ChannelBuilder::default_channel_builder(env.clone())
+            .connect_to_uri(&client_uri, &logger);
+        report_grpc::ReportApiClient::new(ch)
+    };
+    let admin_client = {
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&admin_uri, &logger);
+        report_admin_grpc::ReportAdminApiClient::new(ch)
+    };
+
+    // Both report ids are unmanaged, and therefore served.
+    let resp = admin_client.get_report_ids(&Empty::new()).unwrap();
+    assert_eq!(resp.report_ids.len(), 2);
+    assert!(resp
+        .report_ids
+        .iter()
+        .all(|status| status.in_database && !status.managed && status.served));
+
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 2);
+
+    // Schedule "next" to only be served far in the future.
+    let mut window = ReportIdWindow::new();
+    window.set_not_before(u64::MAX - 1);
+    let mut req = AddReportIdRequest::new();
+    req.set_report_id("next".to_string());
+    req.set_window(window);
+    let resp = admin_client.add_report_id(&req).unwrap();
+    let next_status = resp
+        .report_ids
+        .iter()
+        .find(|status| status.report_id == "next")
+        .unwrap();
+    assert!(next_status.managed);
+    assert!(!next_status.served);
+
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 1);
+    assert_eq!(resp.reports[0].get_fog_report_id(), "");
+
+    // Invalid windows are rejected.
+    let mut window = ReportIdWindow::new();
+    window.set_not_before(10);
+    window.set_not_after(5);
+    let mut req = AddReportIdRequest::new();
+    req.set_report_id("next".to_string());
+    req.set_window(window);
+    assert!(admin_client.add_report_id(&req).is_err());
+
+    // Retiring the default report id stops it from being served, even though it
+    // is still in the database.
+    let mut req = RetireReportIdRequest::new();
+    req.set_report_id("".to_string());
+    let resp = admin_client.retire_report_id(&req).unwrap();
+    assert!(resp
+        .report_ids
+        .iter()
+        .all(|status| status.in_database && status.managed && !status.served));
+
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 0);
+
+    // The serving windows are stored in the database, so a restarted server
+    // keeps honoring them.
+    server.stop();
+
+    let (pem_chain, signing_keypair) = mc_crypto_x509_test_vectors::ok_rsa_chain_25519_leaf();
+    let materials = Materials::from_pem_keypair(pem_chain, signing_keypair)
+        .expect("Could not parse x509 test vectors key");
+
+    let client_uri = FogUri::from_str("insecure-fog://0.0.0.0:3403").unwrap();
+    let mut server = Server::new(
+        db,
+        &client_uri,
+        None,
+        materials,
+        None,
+        Duration::from_secs(0),
+        logger.clone(),
+    );
+    server.start();
+
+    let report_client = {
+        let env = Arc::new(grpcio::EnvBuilder::new().build());
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&client_uri, &logger);
+        report_grpc::ReportApiClient::new(ch)
+    };
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 0);
+}
@@ -101,6 +101,36 @@ impl MemoHandler {
                     Err(MemoHandlerError::UnknownSender)
                 }
             }
+            MemoType::AuthenticatedRefund(memo) => {
+                if let Some(addr) = self.contacts.get(&memo.sender_address_hash()) {
+                    if bool::from(memo.validate(
+                        addr,
+                        &account_key.default_subaddress_view_private(),
+                        &tx_out.public_key,
+                    )) {
+                        Ok(Some(memo_type))
+                    } else {
+                        Err(MemoHandlerError::FailedHmacValidation)
+                    }
+                } else {
+                    Err(MemoHandlerError::UnknownSender)
+                }
+            }
+            MemoType::AuthenticatedInvoiceSettlement(memo) => {
+                if let Some(addr) = self.contacts.get(&memo.sender_address_hash()) {
+                    if bool::from(memo.validate(
+                        addr,
+                        &account_key.default_subaddress_view_private(),
+                        &tx_out.public_key,
+                    )) {
+                        Ok(Some(memo_type))
+                    } else {
+                        Err(MemoHandlerError::FailedHmacValidation)
+                    }
+                } else {
+                    Err(MemoHandlerError::UnknownSender)
+                }
+            }
             MemoType::Destination(_) => {
                 if subaddress_matches_tx_out(account_key, CHANGE_SUBADDRESS_INDEX, tx_out)? {
                     Ok(Some(memo_type))
@@ -154,3 +184,125 @@ impl From<MemoDecodingError> for MemoHandlerError {
         Self::MemoDecode(src)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_common::logger::test_with_logger;
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
+    use mc_transaction_core::{
+        encrypted_fog_hint::EncryptedFogHint, tokens::Mob, Amount, BlockVersion, MemoPayload, Token,
+    };
+    use mc_transaction_std::{
+        AuthenticatedInvoiceSettlementMemo, AuthenticatedRefundMemo, SenderMemoCredential,
+    };
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn tx_out_with_memo(
+        recipient: &PublicAddress,
+        memo_fn: impl FnOnce(&RistrettoPublic) -> MemoPayload,
+        rng: &mut StdRng,
+    ) -> TxOut {
+        TxOut::new_with_memo(
+            BlockVersion::MAX,
+            Amount::new(10, Mob::ID),
+            recipient,
+            &RistrettoPrivate::from_random(rng),
+            EncryptedFogHint::fake_onetime_hint(rng),
+            |memo_context| Ok(memo_fn(memo_context.tx_public_key)),
+        )
+        .unwrap()
+    }
+
+    // Sends tx_outs with memos made by memo_fn from alice to bob, and checks
+    // that bob's memo handler only accepts them when they are authenticated
+    // by alice.
+    fn check_authenticated_memo(
+        memo_fn: impl Fn(&SenderMemoCredential, &PublicAddress, &RistrettoPublic) -> MemoPayload,
+        logger: Logger,
+    ) {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+        let mallory = AccountKey::random(&mut rng);
+        let bob_addr = bob.default_subaddress();
+
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                memo_fn(
+                    &SenderMemoCredential::from(&alice),
+                    &bob_addr,
+                    tx_public_key,
+                )
+            },
+            &mut rng,
+        );
+
+        let mut handler = MemoHandler::new(vec![alice.default_subaddress()], logger.clone());
+        handler.handle_memo(&tx_out, &bob);
+        assert!(matches!(handler.get_last_memo(), Ok(Some(_))));
+
+        let mut handler = MemoHandler::new(vec![], logger.clone());
+        handler.handle_memo(&tx_out, &bob);
+        assert!(matches!(
+            handler.get_last_memo(),
+            Err(MemoHandlerError::UnknownSender)
+        ));
+
+        // Mallory claims to be alice, but can't produce alice's hmac.
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                let cred = SenderMemoCredential {
+                    address_hash: ShortAddressHash::from(&alice.default_subaddress()),
+                    subaddress_spend_private_key: mallory.default_subaddress_spend_private(),
+                };
+                memo_fn(&cred, &bob_addr, tx_public_key)
+            },
+            &mut rng,
+        );
+        let mut handler = MemoHandler::new(vec![alice.default_subaddress()], logger);
+        handler.handle_memo(&tx_out, &bob);
+        assert!(matches!(
+            handler.get_last_memo(),
+            Err(MemoHandlerError::FailedHmacValidation)
+        ));
+    }
+
+    #[test_with_logger]
+    fn test_authenticated_refund_memo(logger: Logger) {
+        let refunded = CompressedRistrettoPublic::from(RistrettoPublic::from_random(
+            &mut StdRng::from_seed([2u8; 32]),
+        ));
+        check_authenticated_memo(
+            |cred, recipient, tx_public_key| {
+                AuthenticatedRefundMemo::new(
+                    cred,
+                    recipient.view_public_key(),
+                    &tx_public_key.into(),
+                    &refunded,
+                )
+                .into()
+            },
+            logger,
+        );
+    }
+
+    #[test_with_logger]
+    fn test_authenticated_invoice_settlement_memo(logger: Logger) {
+        check_authenticated_memo(
+            |cred, recipient, tx_public_key| {
+                AuthenticatedInvoiceSettlementMemo::new(
+                    cred,
+                    recipient.view_public_key(),
+                    &tx_public_key.into(),
+                    [7u8; 16],
+                )
+                .into()
+            },
+            logger,
+        );
+    }
+}
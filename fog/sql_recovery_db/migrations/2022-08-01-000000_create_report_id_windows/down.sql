@@ -0,0 +1,3 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+DROP TABLE report_id_windows;
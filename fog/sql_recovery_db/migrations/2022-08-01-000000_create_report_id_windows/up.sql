@@ -0,0 +1,18 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+-- Serving windows for report ids, managed through the report server admin API.
+-- A report id without a row here is always served.
+CREATE TABLE report_id_windows (
+    -- The fog_report_id this window applies to. This is not a foreign key into
+    -- reports, since a retired report id should stay retired even if its report
+    -- is removed and later published again.
+    fog_report_id VARCHAR(64) PRIMARY KEY,
+
+    -- The first unix timestamp (in seconds) at which the report id is served,
+    -- or NULL if there is no lower bound
+    not_before BIGINT,
+
+    -- The first unix timestamp (in seconds) at which the report id is no longer
+    -- served, or NULL if there is no upper bound
+    not_after BIGINT
+);
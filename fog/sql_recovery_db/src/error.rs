@@ -41,6 +41,12 @@ pub enum Error {
     /// Overlapping missed block range: {0:?} overlaps with {0:?}
     OverlappingMissedBlocksRange(BlockRange, BlockRange),
 
+    /// Report id window bound does not fit in the database: {0}
+    ReportIdWindowOutOfRange(u64),
+
+    /// ReportIdWindows schema violation: {0}
+    ReportIdWindowsSchemaViolation(String),
+
     /**
      * The data in the database could not be decoded as a
      * VerificationReport: {0:?}
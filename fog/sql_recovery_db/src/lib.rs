@@ -36,7 +36,7 @@ use mc_fog_kex_rng::KexRngPubkey;
 use mc_fog_recovery_db_iface::{
     AddBlockDataStatus, FogUserEvent, IngestInvocationId, IngressPublicKeyRecord,
     IngressPublicKeyRecordFilters, IngressPublicKeyStatus, RecoveryDb, RecoveryDbError, ReportData,
-    ReportDb,
+    ReportDb, ReportIdWindow,
 };
 use mc_fog_types::{
     common::BlockRange,
@@ -1150,6 +1150,64 @@ impl SqlRecoveryDb {
         .execute(&conn)?;
         Ok(())
     }
+
+    /// Get the serving windows configured for report ids.
+    fn get_report_id_windows_retriable(&self) -> Result<Vec<(String, ReportIdWindow)>, Error> {
+        let conn = self.pool.get()?;
+
+        use schema::report_id_windows::dsl;
+        let windows = dsl::report_id_windows
+            .select((dsl::fog_report_id, dsl::not_before, dsl::not_after))
+            .order_by(dsl::fog_report_id)
+            .load::<(String, Option<i64>, Option<i64>)>(&conn)?;
+
+        let to_u64 = |val: i64| {
+            u64::try_from(val).map_err(|_| {
+                Error::ReportIdWindowsSchemaViolation(format!("negative window bound {}", val))
+            })
+        };
+        windows
+            .into_iter()
+            .map(|(report_id, not_before, not_after)| {
+                Ok((
+                    report_id,
+                    ReportIdWindow {
+                        not_before: not_before.map(to_u64).transpose()?,
+                        not_after: not_after.map(to_u64).transpose()?,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Set the serving window for a report id.
+    fn set_report_id_window_retriable(
+        &self,
+        report_id: &str,
+        window: &ReportIdWindow,
+    ) -> Result<(), Error> {
+        let conn = self.pool.get()?;
+
+        let to_i64 =
+            |val: u64| i64::try_from(val).map_err(|_| Error::ReportIdWindowOutOfRange(val));
+        let new_window = models::NewReportIdWindow {
+            fog_report_id: report_id,
+            not_before: window.not_before.map(to_i64).transpose()?,
+            not_after: window.not_after.map(to_i64).transpose()?,
+        };
+
+        use schema::report_id_windows::dsl;
+        diesel::insert_into(dsl::report_id_windows)
+            .values(&new_window)
+            .on_conflict(dsl::fog_report_id)
+            .do_update()
+            .set((
+                dsl::not_before.eq(new_window.not_before),
+                dsl::not_after.eq(new_window.not_after),
+            ))
+            .execute(&conn)?;
+        Ok(())
+    }
 }
 
 /// See trait `fog_recovery_db_iface::RecoveryDb` for documentation.
@@ -1429,6 +1487,22 @@ impl ReportDb for SqlRecoveryDb {
             self.remove_report_retriable(report_id)
         })
     }
+
+    fn get_report_id_windows(&self) -> Result<Vec<(String, ReportIdWindow)>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_report_id_windows_retriable()
+        })
+    }
+
+    fn set_report_id_window(
+        &self,
+        report_id: &str,
+        window: &ReportIdWindow,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_id_window_retriable(report_id, window)
+        })
+    }
 }
 
 // Helper for using the retry crate's retry function
@@ -2453,6 +2527,57 @@ mod tests {
         );
     }
 
+    #[test_with_logger]
+    fn test_report_id_windows(logger: Logger) {
+        let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
+        let db = db_test_context.get_db_instance();
+
+        // We start with no windows.
+        assert_eq!(db.get_report_id_windows().unwrap(), vec![]);
+
+        let window1 = ReportIdWindow {
+            not_before: Some(100),
+            not_after: None,
+        };
+        let window2 = ReportIdWindow {
+            not_before: None,
+            not_after: Some(200),
+        };
+        db.set_report_id_window("next", &window1).unwrap();
+        db.set_report_id_window("", &window2).unwrap();
+
+        assert_eq!(
+            db.get_report_id_windows().unwrap(),
+            vec![("".into(), window2), ("next".into(), window1)]
+        );
+
+        // Replace an existing window.
+        let window3 = ReportIdWindow {
+            not_before: Some(100),
+            not_after: Some(150),
+        };
+        db.set_report_id_window("next", &window3).unwrap();
+
+        assert_eq!(
+            db.get_report_id_windows().unwrap(),
+            vec![("".into(), window2), ("next".into(), window3)]
+        );
+
+        // Bounds that do not fit in the database are rejected.
+        let window4 = ReportIdWindow {
+            not_before: Some(100),
+            not_after: Some(u64::MAX),
+        };
+        assert!(matches!(
+            db.set_report_id_window("next", &window4),
+            Err(Error::ReportIdWindowOutOfRange(val)) if val == u64::MAX
+        ));
+        assert_eq!(
+            db.get_report_id_windows().unwrap(),
+            vec![("".into(), window2), ("next".into(), window3)]
+        );
+    }
+
     #[test_with_logger]
     fn test_get_ingress_key_records(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
@@ -120,3 +120,11 @@ pub struct NewReport<'a> {
     pub report: &'a [u8],
     pub pubkey_expiry: i64,
 }
+
+#[derive(Debug, Insertable)]
+#[table_name = "report_id_windows"]
+pub struct NewReportIdWindow<'a> {
+    pub fog_report_id: &'a str,
+    pub not_before: Option<i64>,
+    pub not_after: Option<i64>,
+}
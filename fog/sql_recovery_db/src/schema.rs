@@ -45,6 +45,17 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::*;
+
+    report_id_windows (fog_report_id) {
+        fog_report_id -> Varchar,
+        not_before -> Nullable<Int8>,
+        not_after -> Nullable<Int8>,
+    }
+}
+
 table! {
     use diesel::sql_types::*;
     use crate::sql_types::*;
@@ -81,6 +92,7 @@ allow_tables_to_appear_in_same_query!(
     ingest_invocations,
     ingested_blocks,
     ingress_keys,
+    report_id_windows,
     reports,
     user_events,
 );
@@ -9,6 +9,7 @@ use grpcio::{RpcStatus, RpcStatusCode};
 use mc_fog_test_client::{
     config::TestClientConfig,
     error::TestClientError,
+    scenario::Scenario,
     test_client::{TestClient, TestClientPolicy},
 };
 use mc_util_cli::ParserWithBuildInfo;
@@ -47,6 +48,17 @@ fn main() {
 
     let account_keys = config.load_accounts(&logger);
 
+    let scenario = config.scenario.as_ref().map(|path| {
+        let scenario = Scenario::load(path).expect("Could not load scenario");
+        log::info!(
+            logger,
+            "Loaded scenario '{}' with {} steps",
+            scenario.name,
+            scenario.steps.len()
+        );
+        scenario
+    });
+
     // Start an admin server to publish prometheus metrics, if admin_listen_uri is
     // given
     let admin_server = config.admin_listen_uri.as_ref().map(|admin_listen_uri| {
@@ -97,11 +109,25 @@ fn main() {
             );
         }
 
-        test_client.run_continuously(config.transfer_period);
+        match scenario.as_ref() {
+            Some(scenario) => {
+                test_client.run_scenario_continuously(scenario, config.transfer_period)
+            }
+            None => test_client.run_continuously(config.transfer_period),
+        }
     } else {
-        log::info!(logger, "Running {} test transfers", config.num_transactions);
+        let result = match scenario.as_ref() {
+            Some(scenario) => {
+                log::info!(logger, "Running scenario '{}'", scenario.name);
+                test_client.run_scenario(scenario)
+            }
+            None => {
+                log::info!(logger, "Running {} test transfers", config.num_transactions);
+                test_client.run_test(config.num_transactions)
+            }
+        };
 
-        match test_client.run_test(config.num_transactions) {
+        match result {
             Ok(()) => log::info!(logger, "All tests passed"),
             Err(TestClientError::TxTimeout) => panic!(
                 "Transactions could not clear in {:?} seconds",
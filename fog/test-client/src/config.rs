@@ -134,6 +134,16 @@ pub struct TestClientConfig {
         default_value = "0"
     )]
     pub token_ids: Vec<TokenId>,
+
+    /// A TOML scenario file describing the test transfers to make, and the
+    /// balances and memos to check after each of them.
+    ///
+    /// If set, the scenario is run instead of the built-in test transfers,
+    /// and num_transactions, transfer_amount and token_ids are ignored. When
+    /// running continuously, the whole scenario is run once per
+    /// transfer_period.
+    #[clap(long, env = "MC_SCENARIO")]
+    pub scenario: Option<PathBuf>,
 }
 
 impl TestClientConfig {
@@ -20,6 +20,9 @@ pub enum TestClientError {
     TxTimeout,
     /// A bad balance was observed: expected {0:?}, found {1:?}
     BadBalance(HashMap<TokenId, u64>, HashMap<TokenId, u64>),
+    /// A bad balance was observed after a scenario step: expected {0:?},
+    /// found {1:?}
+    BadScenarioBalance(HashMap<TokenId, Option<u64>>, HashMap<TokenId, u64>),
     /// A double spend was not rejected by consensus as expected
     DoubleSpend,
     /// An unexpected memo was received
@@ -6,4 +6,5 @@
 pub mod config;
 pub mod counters;
 pub mod error;
+pub mod scenario;
 pub mod test_client;
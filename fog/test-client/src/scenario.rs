@@ -0,0 +1,386 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Test scenarios, which describe a sequence of test transfers and the checks
+//! to make after each of them.
+//!
+//! A scenario is loaded from a TOML file, for example:
+//!
+//! ```toml
+//! name = "two tokens"
+//! accounts = 3
+//!
+//! [[steps]]
+//! kind = "transfer"
+//! from = 0
+//! to = 1
+//! token_id = 0
+//! amount = 1000000000
+//! double_spend = true
+//!
+//! [[steps]]
+//! kind = "transfer"
+//! from = 1
+//! to = 2
+//! token_id = 1
+//! amount = 20
+//! memo = "none"
+//!
+//! [[steps.expect]]
+//! account = 2
+//! token_id = 1
+//! change = 20
+//!
+//! [[steps]]
+//! kind = "swap"
+//! from = 2
+//! to = 0
+//! offered_token_id = 0
+//! requested_token_id = 1
+//! amount = 20
+//! ```
+//!
+//! Account numbers are indices into the accounts loaded from the key
+//! directory. Expected balances are changes relative to the balances at the
+//! start of the run, so that a scenario can be run repeatedly.
+
+use displaydoc::Display;
+use mc_fog_sample_paykit::TokenId;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// A test scenario
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// A name for the scenario, used in logs
+    pub name: String,
+    /// The number of accounts from the key directory used by the scenario
+    pub accounts: usize,
+    /// The steps of the scenario, which are run in order
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// A single step of a test scenario
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ScenarioStep {
+    /// Send a payment from one account to another, and check that both
+    /// accounts see the expected balance change within the deadlines.
+    Transfer {
+        /// The sending account
+        from: usize,
+        /// The receiving account
+        to: usize,
+        /// The token id to send
+        token_id: TokenId,
+        /// The amount to send, not including the fee
+        amount: u64,
+        /// The memos to check for once the transfer has landed
+        #[serde(default)]
+        memo: MemoCheck,
+        /// Whether to attempt to double spend the transaction afterwards
+        #[serde(default)]
+        double_spend: bool,
+        /// Balances to check once the step is complete
+        #[serde(default)]
+        expect: Vec<ExpectedBalance>,
+    },
+    /// The receiving account offers `amount` (plus a fee) of the offered token
+    /// in exchange for `amount` of the requested token, using a signed
+    /// contingent input, and the sending account fills the offer.
+    Swap {
+        /// The account which fills the offer and submits the transaction
+        from: usize,
+        /// The account which builds the offer
+        to: usize,
+        /// The token id offered by `to`
+        offered_token_id: TokenId,
+        /// The token id requested by `to`
+        requested_token_id: TokenId,
+        /// The amount of each token to exchange
+        amount: u64,
+        /// Balances to check once the step is complete
+        #[serde(default)]
+        expect: Vec<ExpectedBalance>,
+    },
+}
+
+impl ScenarioStep {
+    /// The accounts used by this step
+    pub fn accounts(&self) -> Vec<usize> {
+        let (from, to, expect) = match self {
+            Self::Transfer {
+                from, to, expect, ..
+            } => (from, to, expect),
+            Self::Swap {
+                from, to, expect, ..
+            } => (from, to, expect),
+        };
+        let mut result = vec![*from, *to];
+        result.extend(expect.iter().map(|expected| expected.account));
+        result
+    }
+
+    /// The balances to check once the step is complete
+    pub fn expected_balances(&self) -> &[ExpectedBalance] {
+        match self {
+            Self::Transfer { expect, .. } | Self::Swap { expect, .. } => expect,
+        }
+    }
+}
+
+/// The memos to check for after a transfer
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoCheck {
+    /// The recipient should get an authenticated sender memo from the sender,
+    /// and the sender a destination memo for the recipient, with the right
+    /// amounts (recoverable transaction history).
+    ///
+    /// These checks are skipped if the test client is run with `--no-memos`.
+    Rth,
+    /// Memos are not checked
+    None,
+}
+
+impl Default for MemoCheck {
+    fn default() -> Self {
+        Self::Rth
+    }
+}
+
+/// A balance change that an account is expected to have after a step
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedBalance {
+    /// The account to check
+    pub account: usize,
+    /// The token id to check
+    pub token_id: TokenId,
+    /// The expected change in balance, relative to the balance the account
+    /// had when the current run of the scenario started
+    pub change: i64,
+}
+
+impl ExpectedBalance {
+    /// The expected balance, given the balance at the start of the run, or
+    /// None if the change would make the balance negative or overflow.
+    pub fn balance(&self, start_balance: u64) -> Option<u64> {
+        u64::try_from(start_balance as i128 + self.change as i128).ok()
+    }
+}
+
+impl Scenario {
+    /// Load and validate a scenario from a TOML file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let scenario: Self = toml::from_str(&fs::read_to_string(path)?)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// The accounts used by any step of the scenario
+    pub fn used_accounts(&self) -> BTreeSet<usize> {
+        self.steps.iter().flat_map(|step| step.accounts()).collect()
+    }
+
+    /// The accounts whose balances are checked by any step of the scenario
+    pub fn checked_accounts(&self) -> BTreeSet<usize> {
+        self.steps
+            .iter()
+            .flat_map(|step| step.expected_balances())
+            .map(|expected| expected.account)
+            .collect()
+    }
+
+    /// Check that the scenario is self-consistent
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        if self.accounts < 2 {
+            return Err(ScenarioError::Invalid(
+                "a scenario needs at least two accounts".to_owned(),
+            ));
+        }
+        if self.steps.is_empty() {
+            return Err(ScenarioError::Invalid(
+                "a scenario needs at least one step".to_owned(),
+            ));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if let Some(account) = step
+                .accounts()
+                .into_iter()
+                .find(|account| *account >= self.accounts)
+            {
+                return Err(ScenarioError::Invalid(format!(
+                    "step {} uses account {}, but the scenario only has {} accounts",
+                    index, account, self.accounts
+                )));
+            }
+            match step {
+                ScenarioStep::Transfer { from, to, .. } | ScenarioStep::Swap { from, to, .. }
+                    if from == to =>
+                {
+                    return Err(ScenarioError::Invalid(format!(
+                        "step {} sends from account {} to itself",
+                        index, from
+                    )));
+                }
+                ScenarioStep::Swap {
+                    offered_token_id,
+                    requested_token_id,
+                    ..
+                } if offered_token_id == requested_token_id => {
+                    return Err(ScenarioError::Invalid(format!(
+                        "step {} swaps token id {} for itself",
+                        index, offered_token_id
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error loading a scenario
+#[derive(Debug, Display)]
+pub enum ScenarioError {
+    /// Could not read scenario file: {0}
+    Io(std::io::Error),
+    /// Could not parse scenario file: {0}
+    Toml(toml::de::Error),
+    /// Invalid scenario: {0}
+    Invalid(String),
+}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(src: std::io::Error) -> Self {
+        Self::Io(src)
+    }
+}
+
+impl From<toml::de::Error> for ScenarioError {
+    fn from(src: toml::de::Error) -> Self {
+        Self::Toml(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+name = "two tokens"
+accounts = 3
+
+[[steps]]
+kind = "transfer"
+from = 0
+to = 1
+token_id = 0
+amount = 1000000000
+double_spend = true
+
+[[steps]]
+kind = "transfer"
+from = 1
+to = 2
+token_id = 1
+amount = 20
+memo = "none"
+
+[[steps.expect]]
+account = 2
+token_id = 1
+change = 20
+
+[[steps.expect]]
+account = 0
+token_id = 1
+change = 0
+
+[[steps]]
+kind = "swap"
+from = 2
+to = 0
+offered_token_id = 0
+requested_token_id = 1
+amount = 20
+"#;
+
+    #[test]
+    fn parse_example() {
+        let scenario: Scenario = toml::from_str(EXAMPLE).unwrap();
+        scenario.validate().unwrap();
+
+        assert_eq!(scenario.accounts, 3);
+        assert_eq!(scenario.steps.len(), 3);
+        assert_eq!(
+            scenario.steps[0],
+            ScenarioStep::Transfer {
+                from: 0,
+                to: 1,
+                token_id: TokenId::from(0),
+                amount: 1000000000,
+                memo: MemoCheck::Rth,
+                double_spend: true,
+                expect: vec![],
+            }
+        );
+        assert_eq!(
+            scenario.steps[1].expected_balances(),
+            &[
+                ExpectedBalance {
+                    account: 2,
+                    token_id: TokenId::from(1),
+                    change: 20,
+                },
+                ExpectedBalance {
+                    account: 0,
+                    token_id: TokenId::from(1),
+                    change: 0,
+                }
+            ]
+        );
+        assert!(matches!(scenario.steps[2], ScenarioStep::Swap { .. }));
+        assert_eq!(scenario.used_accounts(), BTreeSet::from([0, 1, 2]));
+        assert_eq!(scenario.checked_accounts(), BTreeSet::from([0, 2]));
+    }
+
+    #[test]
+    fn expected_balance_is_relative() {
+        let expected = ExpectedBalance {
+            account: 1,
+            token_id: TokenId::from(1),
+            change: -20,
+        };
+        assert_eq!(expected.balance(1020), Some(1000));
+        assert_eq!(expected.balance(20), Some(0));
+        assert_eq!(expected.balance(10), None);
+
+        let expected = ExpectedBalance {
+            change: 20,
+            ..expected
+        };
+        assert_eq!(expected.balance(1000), Some(1020));
+        assert_eq!(expected.balance(u64::MAX), None);
+    }
+
+    #[test]
+    fn validate_rejects_bad_accounts() {
+        let mut scenario: Scenario = toml::from_str(EXAMPLE).unwrap();
+        scenario.accounts = 2;
+        assert!(matches!(
+            scenario.validate(),
+            Err(ScenarioError::Invalid(_))
+        ));
+
+        let mut scenario: Scenario = toml::from_str(EXAMPLE).unwrap();
+        if let ScenarioStep::Transfer { to, .. } = &mut scenario.steps[0] {
+            *to = 0;
+        }
+        assert!(matches!(
+            scenario.validate(),
+            Err(ScenarioError::Invalid(_))
+        ));
+    }
+}
@@ -5,7 +5,11 @@
 //! - Fog canary (sends transactions in prod to alert if it fails, and collect
 //!   timings)
 
-use crate::{counters, error::TestClientError};
+use crate::{
+    counters,
+    error::TestClientError,
+    scenario::{MemoCheck, Scenario, ScenarioStep},
+};
 
 use hex_fmt::HexList;
 use maplit::hashmap;
@@ -28,7 +32,7 @@ use more_asserts::assert_gt;
 use once_cell::sync::OnceCell;
 use serde::Serialize;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ops::Sub,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -195,6 +199,14 @@ impl TestClient {
         // Need at least 2 clients to send transactions to each other.
         assert_gt!(client_count, 1);
 
+        for i in 0..self.account_keys.len() {
+            clients.push(self.build_client(i));
+        }
+        clients
+    }
+
+    /// Build the client for the account key with the given index
+    fn build_client(&self, i: usize) -> Arc<Mutex<Client>> {
         // Build an address book for each client (for memos)
         let address_book: Vec<_> = self
             .account_keys
@@ -202,35 +214,33 @@ impl TestClient {
             .map(|x| x.default_subaddress())
             .collect();
 
-        for (i, account_key) in self.account_keys.iter().enumerate() {
-            log::debug!(
-                self.logger,
-                "Now building client for account_key {} {:?}",
-                i,
-                account_key
-            );
-            let uri = &self.consensus_uris[i % self.consensus_uris.len()];
-            let client = ClientBuilder::new(
-                uri.clone(),
-                self.fog_view.clone(),
-                self.fog_ledger.clone(),
-                account_key.clone(),
-                self.logger.clone(),
-            )
-            .grpc_retry_config(self.grpc_retry_config)
-            .ring_size(RING_SIZE)
-            .address_book(address_book.clone())
-            .consensus_sig(self.consensus_sig.clone())
-            .fog_ingest_sig(self.fog_ingest_sig.clone())
-            .fog_ledger_sig(self.fog_ledger_sig.clone())
-            .fog_view_sig(self.fog_view_sig.clone())
-            .build();
-            clients.push(Arc::new(Mutex::new(client)));
-        }
-        clients
+        let account_key = &self.account_keys[i];
+        log::debug!(
+            self.logger,
+            "Now building client for account_key {} {:?}",
+            i,
+            account_key
+        );
+        let uri = &self.consensus_uris[i % self.consensus_uris.len()];
+        let client = ClientBuilder::new(
+            uri.clone(),
+            self.fog_view.clone(),
+            self.fog_ledger.clone(),
+            account_key.clone(),
+            self.logger.clone(),
+        )
+        .grpc_retry_config(self.grpc_retry_config)
+        .ring_size(RING_SIZE)
+        .address_book(address_book)
+        .consensus_sig(self.consensus_sig.clone())
+        .fog_ingest_sig(self.fog_ingest_sig.clone())
+        .fog_ledger_sig(self.fog_ledger_sig.clone())
+        .fog_view_sig(self.fog_view_sig.clone())
+        .build();
+        Arc::new(Mutex::new(client))
     }
 
-    /// Conduct a transfer between two clients, according to the policy
+    /// Conduct a transfer between two clients
     /// Returns the transaction and the block count of the node it was submitted
     /// to.
     ///
@@ -244,13 +254,14 @@ impl TestClient {
         source_client: &mut Client,
         target_client: &mut Client,
         token_id: TokenId,
+        amount: u64,
     ) -> Result<TransferData, TestClientError> {
         self.tx_info.clear();
         let target_address = target_client.get_account_key().default_subaddress();
         log::debug!(
             self.logger,
             "Attempting to transfer {} ({})",
-            self.policy.transfer_amount,
+            amount,
             source_client.consensus_service_address()
         );
 
@@ -284,7 +295,7 @@ impl TestClient {
             let start = Instant::now();
             let transaction = source_client
                 .build_transaction(
-                    Amount::new(self.policy.transfer_amount, token_id),
+                    Amount::new(amount, token_id),
                     &target_address,
                     &mut rng,
                     fee,
@@ -487,6 +498,9 @@ impl TestClient {
     ///
     /// Arguments:
     /// * token_id: The token id to use for the test transfer
+    /// * amount: The amount to transfer
+    /// * check_memos: Whether to check the RTH memos seen by both clients, if
+    ///   the policy says to test them
     /// * source_client: The client to send from
     /// * source_client_index: The index of this client in the list of clients
     ///   (for debugging info)
@@ -496,6 +510,8 @@ impl TestClient {
     fn test_transfer(
         &self,
         token_id: TokenId,
+        amount: u64,
+        check_memos: bool,
         source_client: Arc<Mutex<Client>>,
         source_client_index: usize,
         target_client: Arc<Mutex<Client>>,
@@ -549,8 +565,12 @@ impl TestClient {
         )?;
 
         let transfer_start = std::time::SystemTime::now();
-        let transfer_data =
-            self.transfer(&mut source_client_lk, &mut target_client_lk, token_id)?;
+        let transfer_data = self.transfer(
+            &mut source_client_lk,
+            &mut target_client_lk,
+            token_id,
+            amount,
+        )?;
 
         let mut span = block_span_builder(&tracer, "test_iteration", transfer_data.block_count)
             .with_start_time(transfer_start)
@@ -565,9 +585,9 @@ impl TestClient {
         let mut receive_tx_worker = ReceiveTxWorker::new(
             target_client,
             hashmap! { token_id => tgt_balance },
-            hashmap! { token_id => tgt_balance + self.policy.transfer_amount },
+            hashmap! { token_id => tgt_balance + amount },
             self.policy.clone(),
-            false, // dont skip rth memo tests if policy says to do them
+            !check_memos,
             Some(src_address_hash),
             self.tx_info.clone(),
             self.health_tracker.clone(),
@@ -591,14 +611,14 @@ impl TestClient {
             self.ensure_expected_balance_after_block(
                 &mut source_client_lk,
                 transaction_appeared,
-                hashmap! { token_id => src_balance - self.policy.transfer_amount - transfer_data.fee.value },
+                hashmap! { token_id => src_balance - amount - transfer_data.fee.value },
             )
         })?;
 
         // Wait for receive tx worker to successfully get the transaction
         receive_tx_worker.join()?;
 
-        if self.policy.test_rth_memos {
+        if self.policy.test_rth_memos && check_memos {
             let block_version =
                 BlockVersion::try_from(source_client_lk.get_latest_block_version())?;
             if block_version.e_memo_feature_is_supported() {
@@ -607,10 +627,8 @@ impl TestClient {
                 match source_client_lk.get_last_memo() {
                     Ok(Some(memo)) => match memo {
                         MemoType::Destination(memo) => {
-                            if memo.get_total_outlay()
-                                != self.policy.transfer_amount + transfer_data.fee.value
-                            {
-                                log::error!(self.logger, "Destination memo had wrong total outlay, found {}, expected {}. Tx Info: {}", memo.get_total_outlay(), self.policy.transfer_amount + transfer_data.fee.value, self.tx_info);
+                            if memo.get_total_outlay() != amount + transfer_data.fee.value {
+                                log::error!(self.logger, "Destination memo had wrong total outlay, found {}, expected {}. Tx Info: {}", memo.get_total_outlay(), amount + transfer_data.fee.value, self.tx_info);
                                 return Err(TestClientError::UnexpectedMemo);
                             }
                             if memo.get_fee() != transfer_data.fee.value {
@@ -681,15 +699,16 @@ impl TestClient {
         target_client: &mut Client,
         token_id1: TokenId,
         token_id2: TokenId,
+        amount: u64,
     ) -> Result<TransferData, TestClientError> {
         self.tx_info.clear();
         let target_address = target_client.get_account_key().default_subaddress();
         log::debug!(
             self.logger,
             "Attempting to swap ({} + fee) of {} and ({}) of {} ({})",
-            self.policy.transfer_amount,
+            amount,
             token_id1,
-            self.policy.transfer_amount,
+            amount,
             token_id2,
             source_client.consensus_service_address()
         );
@@ -727,11 +746,11 @@ impl TestClient {
 
         // Build swap proposal
         // Note: We are adding fee-value here to avoid "SCI Unprofitable" errors,
-        // when transfer_amount is very small
+        // when amount is very small
         let signed_input = target_client
             .build_swap_proposal(
-                Amount::new(self.policy.transfer_amount + fee_value, token_id1),
-                Amount::new(self.policy.transfer_amount, token_id2),
+                Amount::new(amount + fee_value, token_id1),
+                Amount::new(amount, token_id2),
                 &mut rng,
             )
             .map_err(TestClientError::BuildSwapProposal)?;
@@ -760,6 +779,7 @@ impl TestClient {
     /// Arguments:
     /// * token_id1: The first token id to swap
     /// * token_id2: The second token id to swap
+    /// * amount: The amount of each token id to swap
     /// * source_client: The client to send from
     /// * source_client_index: The index of this client in the list of clients
     ///   (for debugging info)
@@ -770,6 +790,7 @@ impl TestClient {
         &self,
         token_id1: TokenId,
         token_id2: TokenId,
+        amount: u64,
         source_client: Arc<Mutex<Client>>,
         _source_client_index: usize,
         target_client: Arc<Mutex<Client>>,
@@ -802,6 +823,7 @@ impl TestClient {
             &mut target_client_lk,
             token_id1,
             token_id2,
+            amount,
         )?;
 
         let mut span = block_span_builder(&tracer, "test_iteration", transfer_data.block_count)
@@ -814,9 +836,8 @@ impl TestClient {
 
         let expected_tgt_balances = {
             let mut result = tgt_balances.clone();
-            *result.entry(token_id1).or_default() -=
-                self.policy.transfer_amount + transfer_data.fee.value;
-            *result.entry(token_id2).or_default() += self.policy.transfer_amount;
+            *result.entry(token_id1).or_default() -= amount + transfer_data.fee.value;
+            *result.entry(token_id2).or_default() += amount;
             result
         };
 
@@ -845,8 +866,8 @@ impl TestClient {
 
         let expected_src_balance = {
             let mut result = src_balances;
-            *result.entry(token_id1).or_default() += self.policy.transfer_amount;
-            *result.entry(token_id2).or_default() -= self.policy.transfer_amount;
+            *result.entry(token_id1).or_default() += amount;
+            *result.entry(token_id2).or_default() -= amount;
             result
         };
 
@@ -896,6 +917,8 @@ impl TestClient {
 
                 let transaction = self.test_transfer(
                     *token_id,
+                    self.policy.transfer_amount,
+                    true,
                     source_client.clone(),
                     source_index,
                     target_client,
@@ -942,6 +965,7 @@ impl TestClient {
                 self.test_atomic_swap(
                     token_id1,
                     token_id2,
+                    self.policy.transfer_amount,
                     source_client,
                     source_index,
                     target_client,
@@ -997,6 +1021,8 @@ impl TestClient {
             let transfer_start = Instant::now();
             match self.test_transfer(
                 self.policy.token_ids[0],
+                self.policy.transfer_amount,
+                true,
                 source_client,
                 source_index,
                 target_client,
@@ -1008,80 +1034,326 @@ impl TestClient {
                 }
                 Err(err) => {
                     log::error!(self.logger, "Transfer failed: {}", err);
-                    counters::TX_FAILURE_COUNT.inc();
-                    self.health_tracker.announce_failure();
-                    match err {
-                        TestClientError::ZeroBalance => {
-                            counters::ZERO_BALANCE_COUNT.inc();
-                        }
-                        TestClientError::TxExpired => {
-                            counters::TX_EXPIRED_COUNT.inc();
-                        }
-                        TestClientError::SubmittedTxTimeout => {
-                            counters::CONFIRM_TX_TIMEOUT_COUNT.inc();
-                        }
-                        TestClientError::TxTimeout => {
-                            counters::RECEIVE_TX_TIMEOUT_COUNT.inc();
-                        }
-                        TestClientError::BadBalance(_, _) => {
-                            counters::BAD_BALANCE_COUNT.inc();
-                        }
-                        TestClientError::DoubleSpend => {
-                            counters::TX_DOUBLE_SPEND_COUNT.inc();
-                        }
-                        TestClientError::UnexpectedMemo => {
-                            counters::TX_UNEXPECTED_MEMO_COUNT.inc();
-                        }
-                        TestClientError::InvalidMemo => {
-                            counters::TX_INVALID_MEMO_COUNT.inc();
-                        }
-                        TestClientError::CheckBalance(_) => {
-                            counters::CHECK_BALANCE_ERROR_COUNT.inc();
-                        }
-                        TestClientError::GetFee(_) => {
-                            counters::GET_FEE_ERROR_COUNT.inc();
-                        }
-                        TestClientError::TokenNotConfigured(_) => {
-                            counters::TOKEN_NOT_CONFIGURED_ERROR_COUNT.inc();
-                        }
-                        TestClientError::BuildTx(_) => {
-                            counters::BUILD_TX_ERROR_COUNT.inc();
-                        }
-                        TestClientError::SubmitTx(_) => {
-                            counters::SUBMIT_TX_ERROR_COUNT.inc();
-                        }
-                        TestClientError::ConfirmTx(_) => {
-                            counters::CONFIRM_TX_ERROR_COUNT.inc();
-                        }
-                        TestClientError::BlockVersion(_) => {
-                            counters::BUILD_TX_ERROR_COUNT.inc();
-                        }
-                        TestClientError::BuildSwapProposal(_) => {
-                            counters::BUILD_SWAP_PROPOSAL_ERROR_COUNT.inc();
-                        }
-                    }
+                    self.record_failure(&err);
                 }
             }
-            let transfer_duration = transfer_start.elapsed();
-            let sleep_duration = match period.checked_sub(transfer_duration) {
-                Some(duration) => duration,
-                None => {
-                    let excess_transaction_time = transfer_duration.sub(period);
-                    log::warn!(
-                        self.logger,
-                        "Transfer took {} seconds. This is {} seconds more than the allotted transfer time.",
-                        transfer_duration.as_secs(),
-                        excess_transaction_time.as_secs()
-                    );
-                    Duration::ZERO
-                }
-            };
+            let sleep_duration = self.remaining_period(transfer_start.elapsed(), period);
+
+            ti += 1;
+            self.health_tracker.set_counter(ti);
+            std::thread::sleep(sleep_duration);
+        }
+    }
+
+    /// Run a scenario once, failing fast on an error
+    ///
+    /// Arguments:
+    /// * scenario: The scenario to run. It should use at most as many accounts
+    ///   as the test client has account keys.
+    pub fn run_scenario(&self, scenario: &Scenario) -> Result<(), TestClientError> {
+        let clients = self.build_scenario_clients(scenario);
+
+        let start_time = Instant::now();
+        let start_balances = self.scenario_start_balances(scenario, &clients)?;
+        for (step_index, step) in scenario.steps.iter().enumerate() {
+            self.run_scenario_step(scenario, step_index, step, &clients, &start_balances)?;
+        }
+        log::debug!(
+            self.logger,
+            "Scenario '{}' took {}s",
+            scenario.name,
+            start_time.elapsed().as_secs()
+        );
+        Ok(())
+    }
+
+    /// Run a scenario continuously, handling errors by incrementing prometheus
+    /// counters.
+    ///
+    /// Each step counts as one transfer for the purpose of the counters. If a
+    /// step fails, the rest of that run of the scenario is skipped, since the
+    /// balances it expects are no longer meaningful. The balances that the
+    /// expected changes are relative to are read again at the start of each
+    /// run.
+    ///
+    /// Arguments:
+    /// * scenario: The scenario to run
+    /// * period: The amount of time we allot for one run of the whole scenario.
+    ///   See `run_continuously` for details.
+    pub fn run_scenario_continuously(&self, scenario: &Scenario, period: Duration) {
+        let clients = self.build_scenario_clients(scenario);
+
+        let mut ti = 0usize;
+        loop {
+            log::debug!(self.logger, "Scenario '{}' run: {:?}", scenario.name, ti);
+
+            let run_start = Instant::now();
+            if let Err(err) = self.run_scenario_counted(scenario, &clients) {
+                self.record_failure(&err);
+            }
+            let sleep_duration = self.remaining_period(run_start.elapsed(), period);
 
             ti += 1;
             self.health_tracker.set_counter(ti);
             std::thread::sleep(sleep_duration);
         }
     }
+
+    /// Run a scenario once, incrementing the success counter after each
+    /// successful step, and stopping at the first error
+    fn run_scenario_counted(
+        &self,
+        scenario: &Scenario,
+        clients: &BTreeMap<usize, Arc<Mutex<Client>>>,
+    ) -> Result<(), TestClientError> {
+        let start_balances = self
+            .scenario_start_balances(scenario, clients)
+            .map_err(|err| {
+                log::error!(
+                    self.logger,
+                    "Scenario '{}' could not read starting balances: {}",
+                    scenario.name,
+                    err
+                );
+                err
+            })?;
+        for (step_index, step) in scenario.steps.iter().enumerate() {
+            self.run_scenario_step(scenario, step_index, step, clients, &start_balances)
+                .map_err(|err| {
+                    log::error!(
+                        self.logger,
+                        "Scenario '{}' step {} failed: {}",
+                        scenario.name,
+                        step_index,
+                        err
+                    );
+                    err
+                })?;
+            counters::TX_SUCCESS_COUNT.inc();
+        }
+        Ok(())
+    }
+
+    /// Build the clients for the accounts used by a scenario, checking that
+    /// there are enough account keys
+    fn build_scenario_clients(&self, scenario: &Scenario) -> BTreeMap<usize, Arc<Mutex<Client>>> {
+        assert!(
+            self.account_keys.len() >= scenario.accounts,
+            "Scenario '{}' needs {} accounts but only {} account keys were loaded",
+            scenario.name,
+            scenario.accounts,
+            self.account_keys.len()
+        );
+        let used_accounts = scenario.used_accounts();
+        log::debug!(self.logger, "Creating {} clients", used_accounts.len());
+        used_accounts
+            .into_iter()
+            .map(|account| (account, self.build_client(account)))
+            .collect()
+    }
+
+    /// Read the balances of the accounts whose balances are checked by a
+    /// scenario, at the start of a run
+    fn scenario_start_balances(
+        &self,
+        scenario: &Scenario,
+        clients: &BTreeMap<usize, Arc<Mutex<Client>>>,
+    ) -> Result<BTreeMap<usize, HashMap<TokenId, u64>>, TestClientError> {
+        scenario
+            .checked_accounts()
+            .into_iter()
+            .map(|account| {
+                let mut client = clients[&account].lock().expect("mutex poisoned");
+                let (balances, _block_count) = client
+                    .check_balance()
+                    .map_err(TestClientError::CheckBalance)?;
+                Ok((account, balances))
+            })
+            .collect()
+    }
+
+    /// Run a single step of a scenario, then check the balances it expects
+    fn run_scenario_step(
+        &self,
+        scenario: &Scenario,
+        step_index: usize,
+        step: &ScenarioStep,
+        clients: &BTreeMap<usize, Arc<Mutex<Client>>>,
+        start_balances: &BTreeMap<usize, HashMap<TokenId, u64>>,
+    ) -> Result<(), TestClientError> {
+        log::info!(
+            self.logger,
+            "Scenario '{}' step {}: {:?}",
+            scenario.name,
+            step_index,
+            step
+        );
+        match step {
+            ScenarioStep::Transfer {
+                from,
+                to,
+                token_id,
+                amount,
+                memo,
+                double_spend,
+                ..
+            } => {
+                let transaction = self.test_transfer(
+                    *token_id,
+                    *amount,
+                    *memo == MemoCheck::Rth,
+                    clients[from].clone(),
+                    *from,
+                    clients[to].clone(),
+                    *to,
+                )?;
+                if *double_spend {
+                    let mut source_client_lk = clients[from].lock().expect("mutex poisoned");
+                    self.attempt_double_spend(&mut source_client_lk, &transaction)?;
+                }
+            }
+            ScenarioStep::Swap {
+                from,
+                to,
+                offered_token_id,
+                requested_token_id,
+                amount,
+                ..
+            } => {
+                self.test_atomic_swap(
+                    *offered_token_id,
+                    *requested_token_id,
+                    *amount,
+                    clients[from].clone(),
+                    *from,
+                    clients[to].clone(),
+                    *to,
+                )?;
+            }
+        }
+
+        for (account, expected_balances) in group_expected_balances(step, start_balances) {
+            let mut client = clients[&account].lock().expect("mutex poisoned");
+            let (balances, _block_count) = client
+                .check_balance()
+                .map_err(TestClientError::CheckBalance)?;
+            if !scenario_balance_match(&expected_balances, &balances) {
+                log::error!(
+                    self.logger,
+                    "Scenario '{}' step {}: client {} expected balance {:?}, found {:?}",
+                    scenario.name,
+                    step_index,
+                    account,
+                    expected_balances,
+                    balances
+                );
+                return Err(TestClientError::BadScenarioBalance(
+                    expected_balances,
+                    balances,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Increment the prometheus counters corresponding to a failed transfer,
+    /// and announce the failure to the health tracker
+    fn record_failure(&self, err: &TestClientError) {
+        counters::TX_FAILURE_COUNT.inc();
+        self.health_tracker.announce_failure();
+        match err {
+            TestClientError::ZeroBalance => {
+                counters::ZERO_BALANCE_COUNT.inc();
+            }
+            TestClientError::TxExpired => {
+                counters::TX_EXPIRED_COUNT.inc();
+            }
+            TestClientError::SubmittedTxTimeout => {
+                counters::CONFIRM_TX_TIMEOUT_COUNT.inc();
+            }
+            TestClientError::TxTimeout => {
+                counters::RECEIVE_TX_TIMEOUT_COUNT.inc();
+            }
+            TestClientError::BadBalance(_, _) | TestClientError::BadScenarioBalance(_, _) => {
+                counters::BAD_BALANCE_COUNT.inc();
+            }
+            TestClientError::DoubleSpend => {
+                counters::TX_DOUBLE_SPEND_COUNT.inc();
+            }
+            TestClientError::UnexpectedMemo => {
+                counters::TX_UNEXPECTED_MEMO_COUNT.inc();
+            }
+            TestClientError::InvalidMemo => {
+                counters::TX_INVALID_MEMO_COUNT.inc();
+            }
+            TestClientError::CheckBalance(_) => {
+                counters::CHECK_BALANCE_ERROR_COUNT.inc();
+            }
+            TestClientError::GetFee(_) => {
+                counters::GET_FEE_ERROR_COUNT.inc();
+            }
+            TestClientError::TokenNotConfigured(_) => {
+                counters::TOKEN_NOT_CONFIGURED_ERROR_COUNT.inc();
+            }
+            TestClientError::BuildTx(_) => {
+                counters::BUILD_TX_ERROR_COUNT.inc();
+            }
+            TestClientError::SubmitTx(_) => {
+                counters::SUBMIT_TX_ERROR_COUNT.inc();
+            }
+            TestClientError::ConfirmTx(_) => {
+                counters::CONFIRM_TX_ERROR_COUNT.inc();
+            }
+            TestClientError::BlockVersion(_) => {
+                counters::BUILD_TX_ERROR_COUNT.inc();
+            }
+            TestClientError::BuildSwapProposal(_) => {
+                counters::BUILD_SWAP_PROPOSAL_ERROR_COUNT.inc();
+            }
+        }
+    }
+
+    /// The time left in a period after some work took `elapsed`, warning if
+    /// the work overran the period
+    fn remaining_period(&self, elapsed: Duration, period: Duration) -> Duration {
+        match period.checked_sub(elapsed) {
+            Some(duration) => duration,
+            None => {
+                let excess_transaction_time = elapsed.sub(period);
+                log::warn!(
+                    self.logger,
+                    "Transfer took {} seconds. This is {} seconds more than the allotted transfer time.",
+                    elapsed.as_secs(),
+                    excess_transaction_time.as_secs()
+                );
+                Duration::ZERO
+            }
+        }
+    }
+}
+
+// Group the balances expected after a scenario step by account, given the
+// balances at the start of the run.
+//
+// A change which would make the balance negative is expected to be None, which
+// no balance matches.
+fn group_expected_balances(
+    step: &ScenarioStep,
+    start_balances: &BTreeMap<usize, HashMap<TokenId, u64>>,
+) -> BTreeMap<usize, HashMap<TokenId, Option<u64>>> {
+    let mut result = BTreeMap::<usize, HashMap<TokenId, Option<u64>>>::new();
+    for expected in step.expected_balances() {
+        let start_balance = start_balances
+            .get(&expected.account)
+            .and_then(|balances| balances.get(&expected.token_id))
+            .cloned()
+            .unwrap_or(0);
+        result
+            .entry(expected.account)
+            .or_default()
+            .insert(expected.token_id, expected.balance(start_balance));
+    }
+    result
 }
 
 /// Helper struct: A thread to check balance continuously on the target client
@@ -1295,6 +1567,17 @@ fn balance_match(expected: &HashMap<TokenId, u64>, found: &HashMap<TokenId, u64>
         .all(|(token_id, value)| *value == found.get(token_id).cloned().unwrap_or(0))
 }
 
+// Like balance_match, for balances expected after a scenario step, where an
+// expected balance of None never matches.
+fn scenario_balance_match(
+    expected: &HashMap<TokenId, Option<u64>>,
+    found: &HashMap<TokenId, u64>,
+) -> bool {
+    expected
+        .iter()
+        .all(|(token_id, value)| *value == Some(found.get(token_id).cloned().unwrap_or(0)))
+}
+
 /// An object which tracks info about a Tx as it evolves, for logging context
 /// in case of errors.
 /// This is thread-safe so that we can share it with the receive worker
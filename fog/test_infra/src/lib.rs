@@ -217,4 +217,8 @@ pub fn test_polling_recovery<C: FogViewConnection>(users: &mut UserPool, view: &
         let result = users.compute_delta(&zero_checkpoint);
         assert_eq!(result, expected_result);
     }
+    // Check that rescanning from scratch finds the same number of transactions
+    users.check_rescan(view);
+    // Check that polling for all the users at once finds the same transactions
+    users.check_poll_accounts(view);
 }
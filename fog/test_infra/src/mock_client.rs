@@ -3,8 +3,14 @@
 //! Mock Fog client
 
 use mc_fog_recovery_db_iface::{FogUserEvent, RecoveryDb};
-use mc_fog_types::view::QueryResponse;
-use mc_fog_view_protocol::FogViewConnection;
+use mc_fog_types::{
+    common::BlockRange,
+    view::{
+        check_query_account_ids, AccountQueryResponse, DecommissionedIngestInvocation,
+        QueryResponse, RngRecord, TxOutProbeResult, TxOutSearchResultCode,
+    },
+};
+use mc_fog_view_protocol::{AccountQuery, FogViewConnection};
 
 /// A structure that wraps recovery db reader and implements FogViewConnection,
 /// bypassing view node grpc and view enclave entirely.
@@ -20,6 +26,29 @@ impl<R: RecoveryDb> PassThroughViewClient<R> {
     pub fn new(db: R) -> Self {
         Self { db }
     }
+
+    /// Find the index of the block a search key's TxOut appeared in, given
+    /// that it appeared in or after start_block, and before end_block.
+    ///
+    /// The recovery db can only tell whether a TxOut appeared in or after some
+    /// block, so this binary searches for the last block for which it did.
+    fn find_block_index(
+        &self,
+        search_key: &[u8],
+        mut start_block: u64,
+        mut end_block: u64,
+    ) -> Result<u64, R::Error> {
+        while start_block + 1 < end_block {
+            let mid = start_block + (end_block - start_block) / 2;
+            let results = self.db.get_tx_outs(mid, &[search_key.to_vec()])?;
+            if results[0].result_code == TxOutSearchResultCode::Found as u32 {
+                start_block = mid;
+            } else {
+                end_block = mid;
+            }
+        }
+        Ok(start_block)
+    }
 }
 
 impl<R: RecoveryDb> FogViewConnection for PassThroughViewClient<R> {
@@ -47,23 +76,8 @@ impl<R: RecoveryDb> FogViewConnection for PassThroughViewClient<R> {
             0
         };
 
-        // Prepare the untrusted part of the response - duplicated from the view enclave
-        // code.
-        let mut missed_block_ranges = Vec::new();
-        let mut rng_records = Vec::new();
-        let mut decommissioned_ingest_invocations = Vec::new();
-
-        for event in user_events.into_iter() {
-            match event {
-                FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
-
-                FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
-                    decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
-                }
-
-                FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
-            }
-        }
+        let (missed_block_ranges, rng_records, decommissioned_ingest_invocations) =
+            split_user_events(user_events);
 
         let mut resp = QueryResponse {
             highest_processed_block_count: highest_known_block_count,
@@ -75,10 +89,105 @@ impl<R: RecoveryDb> FogViewConnection for PassThroughViewClient<R> {
             tx_out_search_results: Default::default(),
             last_known_block_count: highest_known_block_count,
             last_known_block_cumulative_txo_count: cumulative_txo_count,
+            tx_out_probe_results: Default::default(),
+            account_responses: Default::default(),
         };
 
         resp.tx_out_search_results = self.db.get_tx_outs(start_from_block_index, &search_keys)?;
 
         Ok(resp)
     }
+    fn probe(
+        &mut self,
+        start_from_block_index: u64,
+        probe_keys: Vec<Vec<u8>>,
+    ) -> Result<QueryResponse, Self::Error> {
+        let mut resp = self.request(i64::MAX, start_from_block_index, probe_keys)?;
+        let last_known_block_count = resp.last_known_block_count;
+        resp.tx_out_probe_results = resp
+            .tx_out_search_results
+            .drain(..)
+            .map(|result| {
+                let block_index = if result.result_code == TxOutSearchResultCode::Found as u32 {
+                    self.find_block_index(
+                        &result.search_key,
+                        start_from_block_index,
+                        last_known_block_count,
+                    )?
+                } else {
+                    0
+                };
+                Ok(TxOutProbeResult {
+                    search_key: result.search_key,
+                    result_code: result.result_code,
+                    block_index,
+                })
+            })
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(resp)
+    }
+    fn request_accounts(
+        &mut self,
+        start_from_block_index: u64,
+        accounts: Vec<AccountQuery>,
+    ) -> Result<QueryResponse, Self::Error> {
+        // Mirror the checks of the view server, so that tests catch requests it
+        // would reject.
+        if let Err(err) = check_query_account_ids(accounts.iter().map(|account| account.account_id))
+        {
+            panic!("view server would reject this request: {}", err);
+        }
+
+        let mut resp = self.request(i64::MAX, start_from_block_index, Default::default())?;
+        for account in accounts {
+            let (user_events, next_start_from_user_event_id) = self
+                .db
+                .search_user_events(account.start_from_user_event_id)?;
+            let (missed_block_ranges, rng_records, decommissioned_ingest_invocations) =
+                split_user_events(user_events);
+            resp.account_responses.push(AccountQueryResponse {
+                account_id: account.account_id,
+                next_start_from_user_event_id,
+                missed_block_ranges,
+                rng_records,
+                decommissioned_ingest_invocations,
+                tx_out_search_results: self
+                    .db
+                    .get_tx_outs(start_from_block_index, &account.search_keys)?,
+            });
+        }
+        Ok(resp)
+    }
+}
+
+/// Prepare the untrusted part of the response - duplicated from the view
+/// enclave code.
+fn split_user_events(
+    user_events: Vec<FogUserEvent>,
+) -> (
+    Vec<BlockRange>,
+    Vec<RngRecord>,
+    Vec<DecommissionedIngestInvocation>,
+) {
+    let mut missed_block_ranges = Vec::new();
+    let mut rng_records = Vec::new();
+    let mut decommissioned_ingest_invocations = Vec::new();
+
+    for event in user_events.into_iter() {
+        match event {
+            FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
+
+            FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
+                decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
+            }
+
+            FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
+        }
+    }
+
+    (
+        missed_block_ranges,
+        rng_records,
+        decommissioned_ingest_invocations,
+    )
 }
@@ -7,7 +7,7 @@
 use mc_common::logger::global_log;
 use mc_crypto_keys::RistrettoPublic;
 use mc_fog_types::{
-    view::{FogTxOut, FogTxOutMetadata, TxOutRecord},
+    view::{FogTxOut, FogTxOutMetadata, TxOutRecord, MAX_ACCOUNTS_PER_QUERY},
     BlockCount,
 };
 use mc_fog_view_protocol::{FogViewConnection, UserPrivate, UserRngSet};
@@ -234,4 +234,89 @@ impl UserPool {
 
         final_num_blocks_values
     }
+
+    /// Make each of the users rescan their rngs from scratch, and check that
+    /// the number of rng outputs with results matches the number of Txos that
+    /// they found by polling.
+    pub fn check_rescan<C: FogViewConnection>(&self, view_node: &mut C) {
+        for (upriv, udata) in self.users.iter() {
+            let mut rngs = UserRngSet::default();
+
+            let resp = view_node
+                .request(0, 0, Default::default())
+                .expect("Could not get rng records");
+            for rng_record in resp.rng_records.iter() {
+                rngs.ingest_rng_record(upriv, rng_record)
+                    .expect("Could not ingest rng record");
+            }
+
+            let result = view_node.rescan(&rngs).expect("Rescan failed");
+            assert_eq!(
+                result.output_counts.values().sum::<u64>(),
+                udata.get_txos().len() as u64
+            );
+
+            // Every Txo is in the block range of one of the rngs with results
+            assert_eq!(
+                result.block_ranges.len(),
+                result
+                    .output_counts
+                    .values()
+                    .filter(|count| **count > 0)
+                    .count()
+            );
+            for txo in udata.get_txos() {
+                assert!(result
+                    .block_ranges
+                    .values()
+                    .any(|range| range.contains(txo.block_index)));
+            }
+        }
+    }
+
+    /// Make all of the users poll from scratch together, with a single batched
+    /// poll, and check that each of them finds the same Txos that they found
+    /// by polling on their own.
+    ///
+    /// The users are repeated, each time with a fresh rng set, so that the
+    /// poll covers more accounts than fit in a single request.
+    pub fn check_poll_accounts<C: FogViewConnection>(&self, view_node: &mut C) {
+        if self.users.is_empty() {
+            return;
+        }
+        let num_accounts = core::cmp::max(self.users.len(), MAX_ACCOUNTS_PER_QUERY + 1);
+        let users: Vec<_> = self.users.iter().cycle().take(num_accounts).collect();
+
+        let mut rng_sets: Vec<UserRngSet> = users.iter().map(|_| Default::default()).collect();
+        let mut accounts: Vec<(&UserPrivate, &mut UserRngSet)> = users
+            .iter()
+            .map(|(upriv, _)| upriv)
+            .zip(rng_sets.iter_mut())
+            .collect();
+
+        let (results, conn_error) = view_node.poll_accounts(&mut accounts);
+        if let Some(err) = conn_error {
+            panic!("Unexpected connection error when polling accounts: {}", err);
+        }
+
+        assert_eq!(results.len(), users.len());
+        for ((_, udata), result) in users.into_iter().zip(results) {
+            assert!(
+                result.errors.is_empty(),
+                "Unexpected errors when polling accounts: {:?}",
+                result.errors
+            );
+            let expected: HashSet<_> = udata
+                .get_txos()
+                .iter()
+                .map(|txo| txo.tx_out_global_index)
+                .collect();
+            let found: HashSet<_> = result
+                .txos
+                .iter()
+                .map(|txo| txo.tx_out_global_index)
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
 }
@@ -192,6 +192,64 @@ pub struct KeyImageResult {
     pub key_image_result_code: u32,
 }
 
+/// A batch of key image queries and output requests. This is the contents of
+/// the encrypted payload sent by the client to the batched ledger API.
+#[derive(Message, Eq, PartialEq)]
+pub struct BatchedLedgerRequest {
+    /// Key image queries
+    #[prost(message, repeated, tag = "1")]
+    pub key_image_queries: Vec<KeyImageQuery>,
+
+    /// Indices for outputs requested
+    #[prost(fixed64, repeated, tag = "2")]
+    pub output_indices: Vec<u64>,
+
+    /// Block to use as the merkle root of the returned proofs
+    #[prost(fixed64, tag = "3")]
+    pub merkle_root_block: u64,
+}
+
+/// One chunk of the response to a batched ledger request. This is the contents
+/// of each encrypted payload streamed to the client.
+///
+/// Key image results come before output results, and the chunks must be
+/// concatenated in order to get the full response.
+#[derive(Clone, Message, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BatchedLedgerResponse {
+    /// The index of this chunk
+    #[prost(uint32, tag = "1")]
+    pub chunk_index: u32,
+
+    /// The total number of chunks in the response
+    #[prost(uint32, tag = "2")]
+    pub num_chunks: u32,
+
+    /// Number of blocks in the ledger
+    #[prost(uint64, tag = "3")]
+    pub num_blocks: u64,
+
+    /// Number of txos in the ledger
+    #[prost(uint64, tag = "4")]
+    pub global_txo_count: u64,
+
+    /// The latest block_version of a block in the block chain
+    #[prost(uint32, tag = "5")]
+    pub latest_block_version: u32,
+
+    /// The max of latest_block_version and the MAX_BLOCK_VERSION value
+    /// in mc-transaction-core (in this deploy of fog ledger).
+    #[prost(uint32, tag = "6")]
+    pub max_block_version: u32,
+
+    /// Results of key image checks in this chunk
+    #[prost(message, repeated, tag = "7")]
+    pub key_image_results: Vec<KeyImageResult>,
+
+    /// Outputs and proofs in this chunk
+    #[prost(message, repeated, tag = "8")]
+    pub output_results: Vec<OutputResult>,
+}
+
 /// An enum corresponding to the KeyImageResultCode proto enum
 #[derive(PartialEq, Eq, Debug, Display)]
 #[repr(u32)]
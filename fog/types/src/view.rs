@@ -1,7 +1,7 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use crate::common::BlockRange;
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use crc::Crc;
 use displaydoc::Display;
 use mc_crypto_keys::{CompressedRistrettoPublic, KeyError, RistrettoPrivate, RistrettoPublic};
@@ -33,6 +33,74 @@ pub struct QueryRequestAAD {
     // TODO this is currently unused
     #[prost(uint64, tag = "2")]
     pub start_from_block_index: u64,
+
+    /// The user event cursors of each account, when a single session makes a
+    /// query on behalf of several accounts.
+    #[prost(message, repeated, tag = "3")]
+    pub accounts: Vec<AccountQueryRequestAAD>,
+}
+
+impl QueryRequestAAD {
+    /// Check that the accounts of this request are not too many, and that
+    /// their ids are unique.
+    pub fn check_accounts(&self) -> Result<(), QueryAccountsError> {
+        check_query_account_ids(self.accounts.iter().map(|account| account.account_id))
+    }
+}
+
+/// The untrusted part of the query of a single account, in a query made on
+/// behalf of several accounts.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct AccountQueryRequestAAD {
+    /// A tag chosen by the client, unique within the request, which identifies
+    /// this account in the QueryRequest and in the QueryResponse.
+    #[prost(uint64, tag = "1")]
+    pub account_id: u64,
+
+    /// The first id of a user event to return for this account.
+    #[prost(int64, tag = "2")]
+    pub start_from_user_event_id: i64,
+}
+
+/// The maximum number of search keys the view enclave serves in a single
+/// query, counting the get_txos, the probe_txos, and the get_txos of every
+/// account.
+pub const MAX_SEARCH_KEYS_PER_QUERY: usize = 16384;
+
+/// The maximum number of accounts a single query can be made on behalf of.
+/// Each account carries its own user event cursor, which can cost the view
+/// server a recovery db query of its own.
+pub const MAX_ACCOUNTS_PER_QUERY: usize = 64;
+
+/// An error in the accounts of a query made on behalf of several accounts
+#[derive(Clone, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum QueryAccountsError {
+    /// Too many accounts in query: {0} > {1}
+    TooManyAccounts(usize, usize),
+    /// Account id {0} appears more than once in the query
+    DuplicateAccount(u64),
+}
+
+/// Check that there are at most MAX_ACCOUNTS_PER_QUERY account ids, and that
+/// they are unique.
+pub fn check_query_account_ids(
+    account_ids: impl ExactSizeIterator<Item = u64>,
+) -> Result<(), QueryAccountsError> {
+    let num_accounts = account_ids.len();
+    if num_accounts > MAX_ACCOUNTS_PER_QUERY {
+        return Err(QueryAccountsError::TooManyAccounts(
+            num_accounts,
+            MAX_ACCOUNTS_PER_QUERY,
+        ));
+    }
+
+    let mut seen = BTreeSet::new();
+    for account_id in account_ids {
+        if !seen.insert(account_id) {
+            return Err(QueryAccountsError::DuplicateAccount(account_id));
+        }
+    }
+    Ok(())
 }
 
 /// The QueryRequest structure, which should be passed as the encrypted data
@@ -43,6 +111,49 @@ pub struct QueryRequest {
     /// These should all be values that came from KexRng's
     #[prost(bytes, repeated, tag = "1")]
     pub get_txos: Vec<Vec<u8>>,
+
+    /// The search keys to probe for TxOut's, without fetching them.
+    /// This is used to find how many outputs of an Rng have been used,
+    /// when restoring an account.
+    #[prost(bytes, repeated, tag = "2")]
+    pub probe_txos: Vec<Vec<u8>>,
+
+    /// The search keys of each account, in a query made on behalf of several
+    /// accounts. Every account must also appear in the QueryRequestAAD.
+    #[prost(message, repeated, tag = "3")]
+    pub accounts: Vec<AccountQueryRequest>,
+}
+
+impl QueryRequest {
+    /// The total number of search keys in this request.
+    pub fn num_search_keys(&self) -> usize {
+        self.get_txos.len()
+            + self.probe_txos.len()
+            + self
+                .accounts
+                .iter()
+                .map(|account| account.get_txos.len())
+                .sum::<usize>()
+    }
+
+    /// Check that the accounts of this request are not too many, and that
+    /// their ids are unique.
+    pub fn check_accounts(&self) -> Result<(), QueryAccountsError> {
+        check_query_account_ids(self.accounts.iter().map(|account| account.account_id))
+    }
+}
+
+/// The encrypted part of the query of a single account, in a query made on
+/// behalf of several accounts.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct AccountQueryRequest {
+    /// The tag of this account, matching an AccountQueryRequestAAD
+    #[prost(uint64, tag = "1")]
+    pub account_id: u64,
+
+    /// The search keys to query for TxOut's on behalf of this account
+    #[prost(bytes, repeated, tag = "2")]
+    pub get_txos: Vec<Vec<u8>>,
 }
 
 /// The QueryResponse structure, returned by the enclave in response to an
@@ -93,6 +204,45 @@ pub struct QueryResponse {
     /// clients sample for mixins.
     #[prost(uint64, tag = "9")]
     pub last_known_block_cumulative_txo_count: u64,
+    /// The results of each tx out probe query
+    #[prost(message, repeated, tag = "10")]
+    pub tx_out_probe_results: Vec<TxOutProbeResult>,
+    /// The results of each account in a query made on behalf of several
+    /// accounts, in the order of the accounts of the request
+    #[prost(message, repeated, tag = "11")]
+    pub account_responses: Vec<AccountQueryResponse>,
+}
+
+/// The results of the query of a single account, in a query made on behalf of
+/// several accounts. The block counts and timestamps of the enclosing
+/// QueryResponse apply to every account.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct AccountQueryResponse {
+    /// The tag of this account, as chosen by the client
+    #[prost(uint64, tag = "1")]
+    pub account_id: u64,
+
+    /// The next value this account should use for start_from_user_event_id
+    #[prost(int64, tag = "2")]
+    pub next_start_from_user_event_id: i64,
+
+    /// Any block ranges corresponding to missed block events since the
+    /// start_from_user_event_id of this account.
+    #[prost(message, repeated, tag = "3")]
+    pub missed_block_ranges: Vec<BlockRange>,
+
+    /// Any RNG records since the start_from_user_event_id of this account.
+    #[prost(message, repeated, tag = "4")]
+    pub rng_records: Vec<RngRecord>,
+
+    /// Any records of decommissioned ingest invocations since the
+    /// start_from_user_event_id of this account.
+    #[prost(message, repeated, tag = "5")]
+    pub decommissioned_ingest_invocations: Vec<DecommissionedIngestInvocation>,
+
+    /// The results of each tx out search query of this account
+    #[prost(message, repeated, tag = "6")]
+    pub tx_out_search_results: Vec<TxOutSearchResult>,
 }
 
 /// A record that can be used by the user to produce an Rng shared with fog
@@ -182,6 +332,22 @@ pub struct TxOutSearchResult {
     pub ciphertext: Vec<u8>,
 }
 
+/// A struct representing the result of a fog view Txo probe, which reports
+/// whether a search key has a result, and in which block, without returning it
+#[derive(Clone, Eq, Hash, PartialEq, Message, Serialize, Deserialize)]
+pub struct TxOutProbeResult {
+    /// The search key that yielded this result
+    #[prost(bytes, tag = "1")]
+    pub search_key: Vec<u8>,
+    /// This is a TxOutSearchResultCode
+    #[prost(fixed32, tag = "2")]
+    pub result_code: u32,
+    /// The index of the block the TxOut appeared in, if it was found, and 0
+    /// otherwise
+    #[prost(fixed64, tag = "3")]
+    pub block_index: u64,
+}
+
 /// TxOutRecord is what information the fog service preserves for a user about
 /// their TxOut. These are created by the ingest server and then encrypted. The
 /// encrypted blobs are eventually returned to the user, who must deserialize
@@ -448,6 +614,7 @@ pub struct FogTxOutMetadata {
 #[cfg(test)]
 mod view_tests {
     use super::*;
+    use alloc::vec;
     use mc_test_vectors_tx_out_records::{CorrectTxOutRecordData, IncorrectTxOutRecordData};
     use mc_util_test_vector::TestVector;
     use mc_util_test_with_data::test_with_data;
@@ -492,4 +659,54 @@ mod view_tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn check_query_account_ids_works() {
+        let aad = |account_ids: &[u64]| QueryRequestAAD {
+            accounts: account_ids
+                .iter()
+                .map(|account_id| AccountQueryRequestAAD {
+                    account_id: *account_id,
+                    start_from_user_event_id: 0,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(aad(&[]).check_accounts(), Ok(()));
+        assert_eq!(aad(&[3, 1, 2]).check_accounts(), Ok(()));
+        assert_eq!(
+            aad(&[3, 1, 3]).check_accounts(),
+            Err(QueryAccountsError::DuplicateAccount(3))
+        );
+
+        let max_ids: Vec<u64> = (0..MAX_ACCOUNTS_PER_QUERY as u64).collect();
+        assert_eq!(aad(&max_ids).check_accounts(), Ok(()));
+        let too_many_ids: Vec<u64> = (0..=MAX_ACCOUNTS_PER_QUERY as u64).collect();
+        assert_eq!(
+            aad(&too_many_ids).check_accounts(),
+            Err(QueryAccountsError::TooManyAccounts(
+                MAX_ACCOUNTS_PER_QUERY + 1,
+                MAX_ACCOUNTS_PER_QUERY
+            ))
+        );
+
+        let request = QueryRequest {
+            accounts: vec![
+                AccountQueryRequest {
+                    account_id: 7,
+                    get_txos: Default::default(),
+                },
+                AccountQueryRequest {
+                    account_id: 7,
+                    get_txos: Default::default(),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            request.check_accounts(),
+            Err(QueryAccountsError::DuplicateAccount(7))
+        );
+    }
 }
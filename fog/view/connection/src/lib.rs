@@ -13,9 +13,11 @@ use mc_common::{
 };
 use mc_fog_api::view_grpc;
 use mc_fog_enclave_connection::{EnclaveConnection, Error as EnclaveConnectionError};
-use mc_fog_types::view::{QueryRequest, QueryRequestAAD, QueryResponse};
+use mc_fog_types::view::{
+    AccountQueryRequest, AccountQueryRequestAAD, QueryRequest, QueryRequestAAD, QueryResponse,
+};
 use mc_fog_uri::FogViewUri;
-use mc_fog_view_protocol::FogViewConnection;
+use mc_fog_view_protocol::{AccountQuery, FogViewConnection};
 use mc_util_grpc::{ConnectionUriGrpcioChannel, GrpcRetryConfig};
 use mc_util_telemetry::{tracer, Tracer};
 use retry::Error as RetryError;
@@ -86,29 +88,125 @@ impl FogViewConnection for FogViewGrpcClient {
 
             let req = QueryRequest {
                 get_txos: search_keys,
+                probe_txos: Default::default(),
+                accounts: Default::default(),
             };
 
             let req_aad = QueryRequestAAD {
                 start_from_user_event_id,
                 start_from_block_index,
+                accounts: Default::default(),
             };
 
-            let aad_bytes = mc_util_serial::encode(&req_aad);
+            self.query(&req, &req_aad)
+        })
+    }
 
-            let retry_config = self.grpc_retry_config;
-            retry_config
-                .retry(|| {
-                    self.conn
-                        .retriable_encrypted_enclave_request(&req, &aad_bytes)
-                })
-                .map_err(|error| Error {
-                    uri: self.uri.clone(),
-                    error,
+    fn probe(
+        &mut self,
+        start_from_block_index: u64,
+        probe_keys: Vec<Vec<u8>>,
+    ) -> Result<QueryResponse, Self::Error> {
+        tracer!().in_span("fog_view_grpc_probe", |_cx_| {
+            trace_time!(self.logger, "FogViewGrpcClient::probe");
+
+            log::trace!(
+                self.logger,
+                "probe: start_from_block_index={} num_probe_keys={}",
+                start_from_block_index,
+                probe_keys.len()
+            );
+
+            let req = QueryRequest {
+                get_txos: Default::default(),
+                probe_txos: probe_keys,
+                accounts: Default::default(),
+            };
+
+            // We don't care about any events, we just want to probe for TXOs.
+            let req_aad = QueryRequestAAD {
+                start_from_user_event_id: i64::MAX,
+                start_from_block_index,
+                accounts: Default::default(),
+            };
+
+            self.query(&req, &req_aad)
+        })
+    }
+
+    fn request_accounts(
+        &mut self,
+        start_from_block_index: u64,
+        accounts: Vec<AccountQuery>,
+    ) -> Result<QueryResponse, Self::Error> {
+        tracer!().in_span("fog_view_grpc_request_accounts", |_cx_| {
+            trace_time!(self.logger, "FogViewGrpcClient::request_accounts");
+
+            log::trace!(
+                self.logger,
+                "request_accounts: start_from_block_index={} num_accounts={}",
+                start_from_block_index,
+                accounts.len()
+            );
+
+            // The user event cursors go in the AAD, so that the server can look
+            // up the events, while the search keys are encrypted.
+            let (aad_accounts, req_accounts) = accounts
+                .into_iter()
+                .map(|account| {
+                    (
+                        AccountQueryRequestAAD {
+                            account_id: account.account_id,
+                            start_from_user_event_id: account.start_from_user_event_id,
+                        },
+                        AccountQueryRequest {
+                            account_id: account.account_id,
+                            get_txos: account.search_keys,
+                        },
+                    )
                 })
+                .unzip();
+
+            let req = QueryRequest {
+                get_txos: Default::default(),
+                probe_txos: Default::default(),
+                accounts: req_accounts,
+            };
+
+            // The events of each account are in its own response.
+            let req_aad = QueryRequestAAD {
+                start_from_user_event_id: i64::MAX,
+                start_from_block_index,
+                accounts: aad_accounts,
+            };
+
+            self.query(&req, &req_aad)
         })
     }
 }
 
+impl FogViewGrpcClient {
+    /// Make an attested query to the fog view enclave, with retries
+    fn query(
+        &mut self,
+        req: &QueryRequest,
+        req_aad: &QueryRequestAAD,
+    ) -> Result<QueryResponse, Error> {
+        let aad_bytes = mc_util_serial::encode(req_aad);
+
+        let retry_config = self.grpc_retry_config;
+        retry_config
+            .retry(|| {
+                self.conn
+                    .retriable_encrypted_enclave_request(req, &aad_bytes)
+            })
+            .map_err(|error| Error {
+                uri: self.uri.clone(),
+                error,
+            })
+    }
+}
+
 /// An error that can occur when making a fog view request
 #[derive(Debug)]
 pub struct Error {
@@ -19,7 +19,7 @@ use mc_attest_enclave_api::{
 use mc_common::ResponderId;
 use mc_crypto_keys::X25519Public;
 use mc_fog_recovery_db_iface::FogUserEvent;
-use mc_fog_types::ETxOutRecord;
+use mc_fog_types::{view::QueryAccountsError, ETxOutRecord};
 use mc_sgx_compat::sync::PoisonError;
 use mc_sgx_report_cache_api::ReportableEnclave;
 use mc_sgx_types::{sgx_enclave_id_t, sgx_status_t};
@@ -48,6 +48,24 @@ pub struct UntrustedQueryResponse {
 
     /// The cumulative txo count of the last known block.
     pub last_known_block_cumulative_txo_count: u64,
+
+    /// User events for each account of a query made on behalf of several
+    /// accounts.
+    pub accounts: Vec<UntrustedAccountQueryResponse>,
+}
+
+/// Untrusted data that is part of the response to a single account, in a view
+/// enclave query made on behalf of several accounts.
+#[derive(Serialize, Deserialize)]
+pub struct UntrustedAccountQueryResponse {
+    /// The tag of the account, as chosen by the client.
+    pub account_id: u64,
+
+    /// User events since the start_from_user_event_id of the account.
+    pub user_events: Vec<FogUserEvent>,
+
+    /// The next value the account should use for start_from_user_event_id.
+    pub next_start_from_user_event_id: i64,
 }
 
 /// Represents a serialized request for the view enclave to service
@@ -81,8 +99,9 @@ pub enum ViewEnclaveRequest {
     /// An encrypted fog_types::view::QueryRequest
     /// Respond with fog_types::view::QueryResponse
     Query(EnclaveMessage<ClientSession>, UntrustedQueryResponse),
-    /// Request from untrusted to add encrypted tx out records to ORAM
-    AddRecords(Vec<ETxOutRecord>),
+    /// Request from untrusted to add the encrypted tx out records of a block
+    /// to ORAM
+    AddRecords(u64, Vec<ETxOutRecord>),
 }
 
 /// The parameters needed to initialize the view enclave
@@ -132,8 +151,8 @@ pub trait ViewEnclaveApi: ReportableEnclave {
     /// SERVER-FACING
 
     /// Add encrypted tx out records from the fog recovery db to the view
-    /// enclave's ORAM
-    fn add_records(&self, records: Vec<ETxOutRecord>) -> Result<()>;
+    /// enclave's ORAM, along with the index of the block they appeared in
+    fn add_records(&self, block_index: u64, records: Vec<ETxOutRecord>) -> Result<()>;
 }
 
 /// Helper trait which reduces boiler-plate in untrusted side
@@ -187,6 +206,18 @@ pub enum Error {
     Poison,
     /// Enclave not initialized
     EnclaveNotInitialized,
+    /// Too many search keys in query: {0} > {1}
+    TooManySearchKeys(usize, usize),
+    /// Account {0} of the query is missing from the query AAD
+    UnknownAccount(u64),
+    /// Invalid query accounts: {0}
+    QueryAccounts(QueryAccountsError),
+}
+
+impl From<QueryAccountsError> for Error {
+    fn from(src: QueryAccountsError) -> Self {
+        Self::QueryAccounts(src)
+    }
 }
 
 impl From<SgxError> for Error {
@@ -8,13 +8,13 @@ use alloc::vec;
 
 use aligned_cmov::{
     subtle::{Choice, ConstantTimeEq},
-    typenum::{Unsigned, U1024, U16, U240, U4096, U64},
+    typenum::{Unsigned, U1024, U16, U240, U4096, U64, U8},
     A8Bytes, CMov,
 };
 use alloc::boxed::Box;
 use mc_common::logger::Logger;
 use mc_crypto_rand::McRng;
-use mc_fog_types::view::{TxOutSearchResult, TxOutSearchResultCode};
+use mc_fog_types::view::{TxOutProbeResult, TxOutSearchResult, TxOutSearchResultCode};
 use mc_fog_view_enclave_api::AddRecordsError;
 use mc_oblivious_map::CuckooHashTableCreator;
 use mc_oblivious_ram::PathORAM4096Z4Creator;
@@ -28,6 +28,9 @@ use mc_oblivious_traits::{
 // We must choose an oblivious map algorithm that can support that
 type KeySize = U16;
 type ValueSize = U240;
+// The block index of each record is kept in a second oblivious map, since the
+// ciphertexts leave too little room in ValueSize to hold it as well
+type BlockIndexSize = U8;
 // BlockSize is a tuning parameter for OMap which must become the ValueSize of
 // the selected ORAM
 type BlockSize = U1024;
@@ -59,10 +62,16 @@ type ObliviousMapCreator<OSC> = CuckooHashTableCreator<BlockSize, McRng, Oblivio
 /// - When the lookup misses, we try to obliviously return a buffer of the
 ///   normal size. We do this by remembering the ciphertext size byte of the
 ///   last stored ciphertext.
+/// - The block index of each record is stored little-endian in a second omap,
+///   under the same search key.
 pub struct ETxOutStore<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> {
     /// Oblivious map to hold ETxOutRecords
     omap: Box<<ObliviousMapCreator<OSC> as OMapCreator<KeySize, ValueSize, McRng>>::Output>,
 
+    /// Oblivious map to hold the block index of each ETxOutRecord
+    block_index_omap:
+        Box<<ObliviousMapCreator<OSC> as OMapCreator<KeySize, BlockIndexSize, McRng>>::Output>,
+
     /// The size byte from the payload for the last ciphertext we stored in omap
     last_ciphertext_size_byte: u8,
 
@@ -81,6 +90,13 @@ impl<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> ETxOutStore<OSC>
             >>::create(
                 desired_capacity, STASH_SIZE, McRng::default
             )),
+            block_index_omap: Box::new(<ObliviousMapCreator<OSC> as OMapCreator<
+                KeySize,
+                BlockIndexSize,
+                McRng,
+            >>::create(
+                desired_capacity, STASH_SIZE, McRng::default
+            )),
             last_ciphertext_size_byte: 0,
             logger,
         }
@@ -90,6 +106,7 @@ impl<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> ETxOutStore<OSC>
         &mut self,
         search_key: &[u8],
         ciphertext: &[u8],
+        block_index: u64,
     ) -> Result<(), AddRecordsError> {
         if search_key.len() != KeySize::USIZE {
             return Err(AddRecordsError::KeyWrongSize);
@@ -110,41 +127,58 @@ impl<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> ETxOutStore<OSC>
         (&mut value[1..data_end]).clone_from_slice(ciphertext);
         self.last_ciphertext_size_byte = value[0];
 
+        let mut block_index_value = A8Bytes::<BlockIndexSize>::default();
+        block_index_value.clone_from_slice(&block_index.to_le_bytes());
+
         // Note: Passing true means we allow overwrite, which seems fine since
         // the value is not changing
         let omap_result_code = self.omap.vartime_write(&key, &value, Choice::from(1));
-        if omap_result_code == OMAP_INVALID_KEY {
-            return Err(AddRecordsError::KeyRejected);
-        } else if omap_result_code == OMAP_OVERFLOW {
-            return Err(AddRecordsError::MapOverflow(
-                self.omap.len(),
-                self.omap.capacity(),
-            ));
-        } else if omap_result_code == OMAP_FOUND {
-            // log::debug!(
-            //    self.logger,
-            //    "An omap key was added twice, overwriting previous value"
-            // );
-        } else if omap_result_code != OMAP_NOT_FOUND {
-            panic!(
-                "omap_result_code had an unexpected value: {}",
-                omap_result_code
-            );
-        }
-        Ok(())
+        check_write_result(omap_result_code, self.omap.len(), self.omap.capacity())?;
+        let omap_result_code =
+            self.block_index_omap
+                .vartime_write(&key, &block_index_value, Choice::from(1));
+        check_write_result(
+            omap_result_code,
+            self.block_index_omap.len(),
+            self.block_index_omap.capacity(),
+        )
     }
 
     pub fn find_record(&mut self, search_key: &[u8]) -> TxOutSearchResult {
+        self.lookup(search_key).0
+    }
+
+    /// Check whether a record exists for a search key, without returning it,
+    /// but returning the index of the block it appeared in.
+    ///
+    /// This performs exactly the same ORAM accesses as find_record, so that a
+    /// probe cannot be distinguished from a lookup by its access pattern.
+    pub fn probe_record(&mut self, search_key: &[u8]) -> TxOutProbeResult {
+        let (result, block_index) = self.lookup(search_key);
+        TxOutProbeResult {
+            search_key: result.search_key,
+            result_code: result.result_code,
+            block_index,
+        }
+    }
+
+    /// Look up the record for a search key, along with its block index, which
+    /// is zero if the record is not found.
+    ///
+    /// Both omaps are read for every lookup, whether it serves a query or a
+    /// probe.
+    fn lookup(&mut self, search_key: &[u8]) -> (TxOutSearchResult, u64) {
         let mut result = TxOutSearchResult {
             search_key: search_key.to_vec(),
             result_code: TxOutSearchResultCode::InternalError as u32,
             ciphertext: vec![0u8; ValueSize::USIZE - 1 - self.last_ciphertext_size_byte as usize],
         };
+        let mut block_index = 0u64;
 
         // Early return for bad search key
         if search_key.len() != KeySize::USIZE {
             result.result_code = TxOutSearchResultCode::BadSearchKey as u32;
-            return result;
+            return (result, block_index);
         }
 
         let mut key = A8Bytes::<KeySize>::default();
@@ -189,6 +223,83 @@ impl<OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>> ETxOutStore<OSC>
         let data_end = ValueSize::USIZE - value[0] as usize;
         result.ciphertext.copy_from_slice(&value[1..data_end]);
 
-        result
+        // Read the block index, and branchlessly keep it only if it was found
+        {
+            let mut block_index_value = A8Bytes::<BlockIndexSize>::default();
+            let oram_result_code = self.block_index_omap.read(&key, &mut block_index_value);
+            let mut block_index_bytes = [0u8; 8];
+            block_index_bytes.copy_from_slice(&block_index_value);
+            block_index.cmov(
+                oram_result_code.ct_eq(&OMAP_FOUND),
+                &u64::from_le_bytes(block_index_bytes),
+            );
+        }
+
+        (result, block_index)
+    }
+}
+
+/// Map the result code of an omap write to the result of adding a record
+fn check_write_result(
+    omap_result_code: u32,
+    len: u64,
+    capacity: u64,
+) -> Result<(), AddRecordsError> {
+    if omap_result_code == OMAP_INVALID_KEY {
+        return Err(AddRecordsError::KeyRejected);
+    } else if omap_result_code == OMAP_OVERFLOW {
+        return Err(AddRecordsError::MapOverflow(len, capacity));
+    } else if omap_result_code == OMAP_FOUND {
+        // log::debug!(
+        //    self.logger,
+        //    "An omap key was added twice, overwriting previous value"
+        // );
+    } else if omap_result_code != OMAP_NOT_FOUND {
+        panic!(
+            "omap_result_code had an unexpected value: {}",
+            omap_result_code
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_common::logger::create_root_logger;
+    use mc_oblivious_traits::HeapORAMStorageCreator;
+
+    #[test]
+    fn test_probe_record_block_index() {
+        let mut store = ETxOutStore::<HeapORAMStorageCreator>::new(1024, create_root_logger());
+
+        let search_key = |i: u8| vec![i; KeySize::USIZE];
+        store.add_record(&search_key(1), &[1u8; 237], 10).unwrap();
+        store.add_record(&search_key(2), &[2u8; 237], 20).unwrap();
+
+        let result = store.probe_record(&search_key(1));
+        assert_eq!(result.search_key, search_key(1));
+        assert_eq!(result.result_code, TxOutSearchResultCode::Found as u32);
+        assert_eq!(result.block_index, 10);
+
+        let result = store.probe_record(&search_key(2));
+        assert_eq!(result.result_code, TxOutSearchResultCode::Found as u32);
+        assert_eq!(result.block_index, 20);
+
+        let result = store.probe_record(&search_key(3));
+        assert_eq!(result.result_code, TxOutSearchResultCode::NotFound as u32);
+        assert_eq!(result.block_index, 0);
+
+        let result = store.probe_record(&[1u8; 3]);
+        assert_eq!(
+            result.result_code,
+            TxOutSearchResultCode::BadSearchKey as u32
+        );
+        assert_eq!(result.block_index, 0);
+
+        // Lookups still return the records themselves.
+        let result = store.find_record(&search_key(2));
+        assert_eq!(result.result_code, TxOutSearchResultCode::Found as u32);
+        assert_eq!(result.ciphertext, vec![2u8; 237]);
     }
 }
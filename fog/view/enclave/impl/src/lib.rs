@@ -9,7 +9,7 @@ extern crate alloc;
 mod e_tx_out_store;
 use e_tx_out_store::{ETxOutStore, StorageDataSize, StorageMetaSize};
 
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use mc_attest_core::{IasNonce, Quote, QuoteNonce, Report, TargetInfo, VerificationReport};
 use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
 use mc_common::logger::{log, Logger};
@@ -17,11 +17,16 @@ use mc_crypto_ake_enclave::{AkeEnclaveState, NullIdentity};
 use mc_crypto_keys::X25519Public;
 use mc_fog_recovery_db_iface::FogUserEvent;
 use mc_fog_types::{
-    view::{QueryRequest, QueryResponse},
+    common::BlockRange,
+    view::{
+        AccountQueryResponse, DecommissionedIngestInvocation, QueryRequest, QueryResponse,
+        RngRecord, MAX_SEARCH_KEYS_PER_QUERY,
+    },
     ETxOutRecord,
 };
 use mc_fog_view_enclave_api::{
-    Error, Result, UntrustedQueryResponse, ViewEnclaveApi, ViewEnclaveInitParams,
+    Error, Result, UntrustedAccountQueryResponse, UntrustedQueryResponse, ViewEnclaveApi,
+    ViewEnclaveInitParams,
 };
 use mc_oblivious_traits::ORAMStorageCreator;
 use mc_sgx_compat::sync::Mutex;
@@ -124,22 +129,54 @@ where
             Error::ProstDecode
         })?;
 
-        // Prepare the untrusted part of the response.
-        let mut missed_block_ranges = Vec::new();
-        let mut rng_records = Vec::new();
-        let mut decommissioned_ingest_invocations = Vec::new();
-
-        for event in untrusted_query_response.user_events.into_iter() {
-            match event {
-                FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
+        let num_search_keys = req.num_search_keys();
+        if num_search_keys > MAX_SEARCH_KEYS_PER_QUERY {
+            log::debug!(
+                self.logger,
+                "Rejecting query with {} search keys",
+                num_search_keys
+            );
+            return Err(Error::TooManySearchKeys(
+                num_search_keys,
+                MAX_SEARCH_KEYS_PER_QUERY,
+            ));
+        }
+        if let Err(err) = req.check_accounts() {
+            log::debug!(self.logger, "Rejecting query: {}", err);
+            return Err(err.into());
+        }
 
-                FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
-                    decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
-                }
+        // Prepare the untrusted part of the response.
+        let (missed_block_ranges, rng_records, decommissioned_ingest_invocations) =
+            split_user_events(untrusted_query_response.user_events);
 
-                FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
-            }
-        }
+        // Prepare the untrusted part of the response of each account, in the
+        // order of the accounts in the request.
+        let untrusted_accounts: BTreeMap<u64, UntrustedAccountQueryResponse> =
+            untrusted_query_response
+                .accounts
+                .into_iter()
+                .map(|untrusted_account| (untrusted_account.account_id, untrusted_account))
+                .collect();
+        let mut account_responses = req
+            .accounts
+            .iter()
+            .map(|account| {
+                let untrusted_account = untrusted_accounts
+                    .get(&account.account_id)
+                    .ok_or(Error::UnknownAccount(account.account_id))?;
+                let (missed_block_ranges, rng_records, decommissioned_ingest_invocations) =
+                    split_user_events(untrusted_account.user_events.clone());
+                Ok(AccountQueryResponse {
+                    account_id: account.account_id,
+                    next_start_from_user_event_id: untrusted_account.next_start_from_user_event_id,
+                    missed_block_ranges,
+                    rng_records,
+                    decommissioned_ingest_invocations,
+                    tx_out_search_results: Default::default(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let mut resp = QueryResponse {
             highest_processed_block_count: untrusted_query_response.highest_processed_block_count,
@@ -153,9 +190,11 @@ where
             last_known_block_count: untrusted_query_response.last_known_block_count,
             last_known_block_cumulative_txo_count: untrusted_query_response
                 .last_known_block_cumulative_txo_count,
+            tx_out_probe_results: Default::default(),
+            account_responses: Default::default(),
         };
 
-        // Do the txos part, scope lock of e_tx_out_store
+        // Do the txos and probes part, scope lock of e_tx_out_store
         {
             let mut lk = self.e_tx_out_store.lock()?;
             let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
@@ -165,7 +204,23 @@ where
                 .iter()
                 .map(|key| store.find_record(&key[..]))
                 .collect();
+
+            resp.tx_out_probe_results = req
+                .probe_txos
+                .iter()
+                .map(|key| store.probe_record(&key[..]))
+                .collect();
+
+            for (account, account_response) in req.accounts.iter().zip(account_responses.iter_mut())
+            {
+                account_response.tx_out_search_results = account
+                    .get_txos
+                    .iter()
+                    .map(|key| store.find_record(&key[..]))
+                    .collect();
+            }
         }
+        resp.account_responses = account_responses;
 
         let response_plaintext_bytes = mc_util_serial::encode(&resp);
 
@@ -176,12 +231,44 @@ where
         Ok(response.data)
     }
 
-    fn add_records(&self, records: Vec<ETxOutRecord>) -> Result<()> {
+    fn add_records(&self, block_index: u64, records: Vec<ETxOutRecord>) -> Result<()> {
         let mut lk = self.e_tx_out_store.lock()?;
         let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
         for rec in records {
-            store.add_record(&rec.search_key, &rec.payload)?;
+            store.add_record(&rec.search_key, &rec.payload, block_index)?;
         }
         Ok(())
     }
 }
+
+/// Split user events by type, into missed block ranges, rng records and
+/// decommissioned ingest invocations.
+fn split_user_events(
+    user_events: Vec<FogUserEvent>,
+) -> (
+    Vec<BlockRange>,
+    Vec<RngRecord>,
+    Vec<DecommissionedIngestInvocation>,
+) {
+    let mut missed_block_ranges = Vec::new();
+    let mut rng_records = Vec::new();
+    let mut decommissioned_ingest_invocations = Vec::new();
+
+    for event in user_events.into_iter() {
+        match event {
+            FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
+
+            FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
+                decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
+            }
+
+            FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
+        }
+    }
+
+    (
+        missed_block_ranges,
+        rng_records,
+        decommissioned_ingest_invocations,
+    )
+}
@@ -42,5 +42,5 @@ fn basic(logger: Logger) {
         ],
     };
 
-    enclave.add_records(vec![rec]).unwrap();
+    enclave.add_records(0, vec![rec]).unwrap();
 }
@@ -174,8 +174,9 @@ impl ViewEnclaveApi for SgxViewEnclave {
         mc_util_serial::deserialize(&outbuf[..])?
     }
 
-    fn add_records(&self, records: Vec<ETxOutRecord>) -> Result<()> {
-        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::AddRecords(records))?;
+    fn add_records(&self, block_index: u64, records: Vec<ETxOutRecord>) -> Result<()> {
+        let inbuf =
+            mc_util_serial::serialize(&ViewEnclaveRequest::AddRecords(block_index, records))?;
         let outbuf = self.enclave_call(&inbuf)?;
         mc_util_serial::deserialize(&outbuf[..])?
     }
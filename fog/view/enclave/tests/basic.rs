@@ -46,5 +46,5 @@ fn add_one_tx_record(logger: Logger) {
         ],
     };
 
-    enclave.add_records(vec![rec]).unwrap();
+    enclave.add_records(0, vec![rec]).unwrap();
 }
@@ -5,7 +5,9 @@
 extern crate alloc;
 
 mod polling;
-pub use polling::{FogViewConnection, TxOutPollingError};
+pub use polling::{
+    AccountPollResult, AccountQuery, FogViewConnection, RngRescanResult, TxOutPollingError,
+};
 
 mod user_private;
 pub use user_private::UserPrivate;
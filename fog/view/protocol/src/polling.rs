@@ -21,12 +21,15 @@ use crate::{
 use alloc::vec::Vec;
 use core::fmt::{Debug, Display};
 use displaydoc::Display;
-use mc_common::HashSet;
+use mc_common::{HashMap, HashSet};
 use mc_crypto_keys::KeyError;
-use mc_fog_kex_rng::BufferedRng;
+use mc_fog_kex_rng::{BufferedRng, VersionedKexRng};
 use mc_fog_types::{
     common::BlockRange,
-    view::{QueryResponse, TxOutRecord},
+    view::{
+        QueryResponse, TxOutRecord, TxOutSearchResultCode, MAX_ACCOUNTS_PER_QUERY,
+        MAX_SEARCH_KEYS_PER_QUERY,
+    },
     BlockCount,
 };
 
@@ -50,6 +53,177 @@ pub trait FogViewConnection {
         search_keys: Vec<Vec<u8>>,
     ) -> Result<QueryResponse, Self::Error>;
 
+    /// Asks the view server which of the given search keys have results, and
+    /// in which blocks, without fetching the results. The answers are in the
+    /// `tx_out_probe_results` of the response.
+    /// - start_from_block_index: Limit ETxOutRecord search for only tx outs
+    ///   that appeared in or after start_from_block_index
+    /// - probe_keys: ETxOutRecord search keys
+    fn probe(
+        &mut self,
+        start_from_block_index: u64,
+        probe_keys: Vec<Vec<u8>>,
+    ) -> Result<QueryResponse, Self::Error>;
+
+    /// Queries the view server on behalf of several accounts at once, in a
+    /// single request. The results of each account are in the
+    /// `account_responses` of the response.
+    /// - start_from_block_index: Limit ETxOutRecord search for only tx outs
+    ///   that appeared in or after start_from_block_index
+    /// - accounts: The user event cursor and search keys of each account
+    fn request_accounts(
+        &mut self,
+        start_from_block_index: u64,
+        accounts: Vec<AccountQuery>,
+    ) -> Result<QueryResponse, Self::Error>;
+
+    /// Find how many outputs of each of the user's rngs have results, counting
+    /// from each rng's current index, without downloading any TxOuts.
+    ///
+    /// Fog ingest uses the outputs of an rng in order, so the outputs which
+    /// have results are always a prefix of the rng's sequence. This lets us
+    /// find the count with an exponential search followed by a binary search,
+    /// probing one output of every unresolved rng per round trip. Rngs with no
+    /// results at all are resolved after the first round trip.
+    ///
+    /// This is meant for restoring an account which has a lot of history: once
+    /// the counts are known, the TxOuts can be fetched with `request` in as
+    /// few round trips as the request size allows, and rngs with nothing to
+    /// fetch can be skipped. The block range of each rng's results also tells
+    /// which blocks have nothing to fetch for it.
+    fn rescan(
+        &mut self,
+        user_rng_set: &UserRngSet,
+    ) -> Result<RngRescanResult, TxOutPollingError<Self::Error>> {
+        // For each rng, every output before `found` has a result, and the
+        // output at `not_found` (if known) does not. The first output is always
+        // probed first, and `found` only grows, so `first_block` and
+        // `last_block` are the blocks of the first and last outputs with results.
+        struct SearchState {
+            found: u64,
+            not_found: Option<u64>,
+            first_block: u64,
+            last_block: u64,
+        }
+
+        impl SearchState {
+            fn next_probe(&self) -> Option<u64> {
+                match self.not_found {
+                    None => Some(core::cmp::max(2 * self.found, 1) - 1),
+                    Some(not_found) if not_found > self.found => {
+                        Some(self.found + (not_found - self.found) / 2)
+                    }
+                    Some(_) => None,
+                }
+            }
+        }
+
+        let mut states: HashMap<Vec<u8>, SearchState> = user_rng_set
+            .get_rngs()
+            .keys()
+            .map(|nonce| {
+                (
+                    nonce.clone(),
+                    SearchState {
+                        found: 0,
+                        not_found: None,
+                        first_block: 0,
+                        last_block: 0,
+                    },
+                )
+            })
+            .collect();
+
+        let mut highest_processed_block_count = u64::MAX;
+
+        loop {
+            // Collect the next probe of every unresolved rng
+            let probes: Vec<(Vec<u8>, u64, Vec<u8>)> = states
+                .iter()
+                .filter_map(|(nonce, state)| {
+                    let offset = state.next_probe()?;
+                    let rng = user_rng_set.get_rngs().get(nonce)?;
+                    let search_key = rng.clone().nth(offset as usize)?;
+                    Some((nonce.clone(), offset, search_key))
+                })
+                .collect();
+
+            if probes.is_empty() {
+                break;
+            }
+
+            let resp = self
+                .probe(
+                    user_rng_set.get_highest_processed_block_count().into(),
+                    probes.iter().map(|(_, _, key)| key.clone()).collect(),
+                )
+                .map_err(TxOutPollingError::Conn)?;
+
+            // As in poll, we can only vouch for the smallest block count that any
+            // of the servers we talked to had processed.
+            highest_processed_block_count = core::cmp::min(
+                highest_processed_block_count,
+                resp.highest_processed_block_count,
+            );
+
+            let results: HashMap<Vec<u8>, (u32, u64)> = resp
+                .tx_out_probe_results
+                .into_iter()
+                .map(|result| (result.search_key, (result.result_code, result.block_index)))
+                .collect();
+
+            for (nonce, offset, search_key) in probes {
+                let (result_code, block_index) = results
+                    .get(&search_key)
+                    .copied()
+                    .ok_or_else(|| TxOutRecoveryError::SearchKeyNotFound(search_key.clone()))?;
+                let state = states.get_mut(&nonce).expect("probe for unknown rng");
+                match TxOutSearchResultCode::try_from(result_code) {
+                    Ok(TxOutSearchResultCode::Found) => {
+                        if offset == 0 {
+                            state.first_block = block_index;
+                        }
+                        state.found = offset + 1;
+                        state.last_block = block_index;
+                    }
+                    Ok(TxOutSearchResultCode::NotFound) => state.not_found = Some(offset),
+                    Ok(code) => {
+                        return Err(TxOutRecoveryError::TxOutSearchFailure(code, search_key).into())
+                    }
+                    Err(()) => {
+                        return Err(TxOutRecoveryError::UnexpectedTxOutSearchResultCode(
+                            result_code,
+                            search_key,
+                        )
+                        .into())
+                    }
+                }
+            }
+        }
+
+        Ok(RngRescanResult {
+            output_counts: states
+                .iter()
+                .map(|(nonce, state)| (nonce.clone(), state.found))
+                .collect(),
+            block_ranges: states
+                .into_iter()
+                .filter(|(_, state)| state.found > 0)
+                .map(|(nonce, state)| {
+                    (
+                        nonce,
+                        BlockRange::new(state.first_block, state.last_block + 1),
+                    )
+                })
+                .collect(),
+            highest_processed_block_count: if highest_processed_block_count == u64::MAX {
+                user_rng_set.get_highest_processed_block_count().into()
+            } else {
+                highest_processed_block_count
+            },
+        })
+    }
+
     /// Take a view endpoint and use the above two functions to poll for updates
     /// Returns any new TxOuts
     /// Abstracts all the rngs etc.
@@ -219,6 +393,328 @@ pub trait FogViewConnection {
         }
         (results, missed_block_ranges, errs)
     }
+
+    /// Poll for updates on behalf of several accounts, sharing requests
+    /// between them. This is the batched equivalent of `poll`, for services
+    /// which track many accounts through a single attested connection.
+    ///
+    /// Each account is tagged in the requests with its index in `accounts`,
+    /// and the results are returned in the same order. Requests are split so
+    /// that none of them has more than MAX_ACCOUNTS_PER_QUERY accounts or
+    /// MAX_SEARCH_KEYS_PER_QUERY search keys.
+    ///
+    /// Polling stops at the first connection error, which is returned along
+    /// with everything found up to that point.
+    fn poll_accounts(
+        &mut self,
+        accounts: &mut [(&UserPrivate, &mut UserRngSet)],
+    ) -> (Vec<AccountPollResult<Self::Error>>, Option<Self::Error>) {
+        // The polling state of a single account
+        struct AccountState {
+            // Whether there may be more TxOuts to find for this account
+            live: bool,
+            // The rngs which did not yield all of the outputs requested from
+            // them in the previous round
+            dead_rng_set: HashSet<Vec<u8>>,
+            // The smallest block count reported by the server in any of the
+            // responses this account was part of
+            new_highest_processed_block_count: u64,
+        }
+
+        // The search keys of a single account, for one round of requests
+        struct PendingQuery {
+            index: usize,
+            request_multiplier: u64,
+            old_rngs: HashMap<Vec<u8>, VersionedKexRng>,
+            search_keys: Vec<Vec<u8>>,
+        }
+
+        let mut results: Vec<AccountPollResult<Self::Error>> =
+            accounts.iter().map(|_| Default::default()).collect();
+        if accounts.is_empty() {
+            return (results, None);
+        }
+
+        let start_from_block_index = accounts
+            .iter()
+            .map(|(_, user_rng_set)| u64::from(user_rng_set.get_highest_processed_block_count()))
+            .min()
+            .unwrap_or_default();
+
+        // Update seeds, get block count. Each account remembers the block count
+        // of the response it was part of.
+        let mut highest_processed_block_counts = vec![0u64; accounts.len()];
+        for chunk_start in (0..accounts.len()).step_by(MAX_ACCOUNTS_PER_QUERY) {
+            let chunk =
+                chunk_start..core::cmp::min(chunk_start + MAX_ACCOUNTS_PER_QUERY, accounts.len());
+            let queries = chunk
+                .clone()
+                .map(|index| AccountQuery {
+                    account_id: index as u64,
+                    start_from_user_event_id: accounts[index].1.get_next_start_from_user_event_id(),
+                    search_keys: Default::default(),
+                })
+                .collect();
+            let resp = match self.request_accounts(start_from_block_index, queries) {
+                Ok(resp) => resp,
+                Err(err) => return (results, Some(err)),
+            };
+            for count in &mut highest_processed_block_counts[chunk.clone()] {
+                *count = resp.highest_processed_block_count;
+            }
+            for account_response in resp.account_responses {
+                let index = account_response.account_id as usize;
+                if !chunk.contains(&index) {
+                    continue;
+                }
+                let (upriv, user_rng_set) = &mut accounts[index];
+                let result = &mut results[index];
+
+                for rng_record in account_response.rng_records.iter() {
+                    if let Err(err) = user_rng_set.ingest_rng_record(upriv, rng_record) {
+                        result.errors.push(TxOutPollingError::from(err));
+                    }
+                }
+
+                // Missed block ranges are reported once, so we can add
+                // directly without fear of repeating ranges.
+                result
+                    .missed_block_ranges
+                    .extend(account_response.missed_block_ranges);
+
+                user_rng_set.set_next_start_from_user_event_id(
+                    account_response.next_start_from_user_event_id,
+                );
+            }
+        }
+
+        // Optimization: Accounts whose num_blocks is not older than the view
+        // server's don't have any new tx's.
+        let mut states: Vec<AccountState> = accounts
+            .iter()
+            .zip(highest_processed_block_counts)
+            .map(
+                |((_, user_rng_set), highest_processed_block_count)| AccountState {
+                    live: user_rng_set.get_highest_processed_block_count()
+                        < BlockCount::from(highest_processed_block_count),
+                    dead_rng_set: Default::default(),
+                    new_highest_processed_block_count: highest_processed_block_count,
+                },
+            )
+            .collect();
+        let polled_accounts: Vec<bool> = states.iter().map(|state| state.live).collect();
+
+        // Get new tx's, as in poll
+        let mut request_multiplier = 2u64; // This value doubles each round
+        loop {
+            // Inspect the rngs of every live account to collect the search keys we
+            // request
+            let mut pending_queries = Vec::new();
+            for (index, (state, (_, user_rng_set))) in
+                states.iter_mut().zip(accounts.iter()).enumerate()
+            {
+                let num_live_rngs = user_rng_set
+                    .get_rngs()
+                    .len()
+                    .saturating_sub(state.dead_rng_set.len());
+                if num_live_rngs == 0 {
+                    state.live = false;
+                }
+                if !state.live {
+                    continue;
+                }
+
+                // Make sure that the search keys of one account fit in a single
+                // request
+                let account_request_multiplier = core::cmp::min(
+                    request_multiplier,
+                    core::cmp::max(MAX_SEARCH_KEYS_PER_QUERY / num_live_rngs, 1) as u64,
+                );
+                let search_keys: Vec<Vec<u8>> = user_rng_set
+                    .get_rngs()
+                    .iter()
+                    .filter(|(nonce, _)| !state.dead_rng_set.contains(&nonce[..]))
+                    .flat_map(|(_, rng)| {
+                        rng.clone()
+                            .take(account_request_multiplier as usize)
+                            .collect::<Vec<Vec<u8>>>()
+                    })
+                    .collect();
+
+                pending_queries.push(PendingQuery {
+                    index,
+                    request_multiplier: account_request_multiplier,
+                    old_rngs: user_rng_set.get_rngs().clone(),
+                    search_keys,
+                });
+            }
+
+            // Escape if there are no more live accounts
+            if pending_queries.is_empty() {
+                break;
+            }
+
+            // Split the queries into requests which respect the enclave's limits
+            let mut batches: Vec<Vec<PendingQuery>> = Vec::new();
+            let mut batch_num_search_keys = 0;
+            for pending_query in pending_queries {
+                let num_search_keys = pending_query.search_keys.len();
+                if batches.is_empty()
+                    || batch_num_search_keys + num_search_keys > MAX_SEARCH_KEYS_PER_QUERY
+                    || batches.last().map_or(0, Vec::len) >= MAX_ACCOUNTS_PER_QUERY
+                {
+                    batches.push(Vec::new());
+                    batch_num_search_keys = 0;
+                }
+                batches
+                    .last_mut()
+                    .expect("batches is never empty")
+                    .push(pending_query);
+                batch_num_search_keys += num_search_keys;
+            }
+
+            for batch in batches {
+                let queries = batch
+                    .iter()
+                    .map(|pending_query| AccountQuery {
+                        account_id: pending_query.index as u64,
+                        // We don't care about any events, we just want to search for TXOs.
+                        start_from_user_event_id: i64::MAX,
+                        search_keys: pending_query.search_keys.clone(),
+                    })
+                    .collect();
+
+                // Make the request to the view node.
+                let resp = match self.request_accounts(start_from_block_index, queries) {
+                    Ok(resp) => resp,
+                    Err(err) => return (results, Some(err)),
+                };
+
+                for account_response in resp.account_responses {
+                    let index = account_response.account_id as usize;
+                    if !batch
+                        .iter()
+                        .any(|pending_query| pending_query.index == index)
+                    {
+                        continue;
+                    }
+                    let (upriv, user_rng_set) = &mut accounts[index];
+                    let result = &mut results[index];
+
+                    let (this_round_txos, this_round_errs) = user_rng_set
+                        .ingest_tx_out_search_results(
+                            upriv,
+                            &account_response.tx_out_search_results,
+                        );
+                    result.txos.extend(this_round_txos);
+                    result
+                        .errors
+                        .extend(this_round_errs.into_iter().map(TxOutPollingError::from));
+                    result
+                        .missed_block_ranges
+                        .extend(account_response.missed_block_ranges);
+                }
+
+                for pending_query in batch {
+                    let state = &mut states[pending_query.index];
+                    let user_rng_set = &accounts[pending_query.index].1;
+
+                    // As in poll, we can only vouch for the smallest block count
+                    // of any of the responses.
+                    state.new_highest_processed_block_count = core::cmp::min(
+                        state.new_highest_processed_block_count,
+                        resp.highest_processed_block_count,
+                    );
+
+                    // Calculate dead rngs, as in poll. This includes all the rngs
+                    // of an account missing from the response.
+                    state.dead_rng_set = user_rng_set
+                        .get_rngs()
+                        .iter()
+                        .filter(|(key, rng)| {
+                            pending_query
+                                .old_rngs
+                                .get(&key[..])
+                                .map(|old_rng| {
+                                    old_rng.index() + pending_query.request_multiplier > rng.index()
+                                })
+                                .unwrap_or(true)
+                        })
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                }
+            }
+
+            // Ask for twice as many values from each rng next round, so that we only need
+            // log n round trips, up to the same limit as poll.
+            request_multiplier = core::cmp::min(request_multiplier * 2, 1000);
+        }
+
+        // As in poll, don't update the num_blocks value in reverse.
+        for ((polled, state), (_, user_rng_set)) in polled_accounts
+            .into_iter()
+            .zip(states.iter())
+            .zip(accounts.iter_mut())
+        {
+            if polled
+                && state.new_highest_processed_block_count
+                    > user_rng_set.get_highest_processed_block_count().into()
+            {
+                user_rng_set
+                    .set_highest_processed_block_count(state.new_highest_processed_block_count);
+            }
+        }
+
+        (results, None)
+    }
+}
+
+/// The user event cursor and search keys of a single account, in a request
+/// made on behalf of several accounts
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccountQuery {
+    /// A tag identifying the account in the response, which must be unique
+    /// within the request
+    pub account_id: u64,
+    /// Limit user events search for this account to only event ids higher than
+    /// this
+    pub start_from_user_event_id: i64,
+    /// ETxOutRecord search keys of this account
+    pub search_keys: Vec<Vec<u8>>,
+}
+
+/// The result of polling for a single account with `poll_accounts`
+#[derive(Debug)]
+pub struct AccountPollResult<ConnError: Debug + Display> {
+    /// Any new TxOuts
+    pub txos: Vec<TxOutRecord>,
+    /// Block ranges for which Fog Ingest did not process the account's TxOuts
+    pub missed_block_ranges: Vec<BlockRange>,
+    /// Any errors encountered while processing the account's results
+    pub errors: Vec<TxOutPollingError<ConnError>>,
+}
+
+impl<ConnError: Debug + Display> Default for AccountPollResult<ConnError> {
+    fn default() -> Self {
+        Self {
+            txos: Default::default(),
+            missed_block_ranges: Default::default(),
+            errors: Default::default(),
+        }
+    }
+}
+
+/// The result of rescanning a UserRngSet
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RngRescanResult {
+    /// For each rng (by nonce), the number of outputs starting from its
+    /// current index that have results
+    pub output_counts: HashMap<Vec<u8>, u64>,
+    /// For each rng (by nonce) which has results, the range of blocks holding
+    /// them. The other blocks have no results for that rng.
+    pub block_ranges: HashMap<Vec<u8>, BlockRange>,
+    /// The highest processed block count which the counts are valid up to
+    pub highest_processed_block_count: u64,
 }
 
 /// TxOutPollingError type
@@ -3,12 +3,15 @@
 use crate::server::DbPollSharedState;
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
 use mc_attest_api::attest;
-use mc_common::logger::{log, Logger};
+use mc_common::{
+    logger::{log, Logger},
+    HashMap,
+};
 use mc_fog_api::view_grpc::FogViewApi;
 use mc_fog_recovery_db_iface::RecoveryDb;
 use mc_fog_types::view::QueryRequestAAD;
 use mc_fog_view_enclave::{Error as ViewEnclaveError, ViewEnclaveProxy};
-use mc_fog_view_enclave_api::UntrustedQueryResponse;
+use mc_fog_view_enclave_api::{UntrustedAccountQueryResponse, UntrustedQueryResponse};
 use mc_util_grpc::{
     rpc_internal_error, rpc_invalid_arg_error, rpc_logger, rpc_permissions_error, send_result,
     Authenticator,
@@ -69,6 +72,12 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
                     )
                 })?;
 
+            // Bound the number of accounts, and thus of database queries, before
+            // touching the database.
+            query_request_aad
+                .check_accounts()
+                .map_err(|err| rpc_invalid_arg_error("query", err, &self.logger))?;
+
             let (user_events, next_start_from_user_event_id) =
                 tracer.in_span("search_user_events", |_cx| {
                     self.db
@@ -76,6 +85,43 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
                         .map_err(|e| rpc_internal_error("search_user_events", e, &self.logger))
                 })?;
 
+            // Search the user events of each account. Accounts which share a
+            // cursor share a single database query.
+            let accounts = tracer.in_span("search_account_user_events", |_cx| {
+                let mut user_events_by_cursor = HashMap::default();
+                query_request_aad
+                    .accounts
+                    .iter()
+                    .map(|account| {
+                        let start_from_user_event_id = account.start_from_user_event_id;
+                        let (user_events, next_start_from_user_event_id) =
+                            match user_events_by_cursor.get(&start_from_user_event_id) {
+                                Some(result) => result.clone(),
+                                None => {
+                                    let result = self
+                                        .db
+                                        .search_user_events(start_from_user_event_id)
+                                        .map_err(|e| {
+                                            rpc_internal_error(
+                                                "search_user_events",
+                                                e,
+                                                &self.logger,
+                                            )
+                                        })?;
+                                    user_events_by_cursor
+                                        .insert(start_from_user_event_id, result.clone());
+                                    result
+                                }
+                            };
+                        Ok(UntrustedAccountQueryResponse {
+                            account_id: account.account_id,
+                            user_events,
+                            next_start_from_user_event_id,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, RpcStatus>>()
+            })?;
+
             let (
                 highest_processed_block_count,
                 highest_processed_block_signature_timestamp,
@@ -98,6 +144,7 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
                 highest_processed_block_signature_timestamp,
                 last_known_block_count,
                 last_known_block_cumulative_txo_count,
+                accounts,
             };
 
             let result_blob = tracer.in_span("enclave_query", |_cx| {
@@ -114,13 +161,18 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
 
     // Helper function that is common
     fn enclave_err_to_rpc_status(&self, context: &str, src: ViewEnclaveError) -> RpcStatus {
-        // Treat prost-decode error as an invalid arg,
+        // Treat prost-decode and malformed query errors as an invalid arg,
         // treat attest error as permission denied,
         // everything else is an internal error
         match src {
             ViewEnclaveError::ProstDecode => {
                 rpc_invalid_arg_error(context, "Prost decode failed", &self.logger)
             }
+            ViewEnclaveError::TooManySearchKeys(_, _)
+            | ViewEnclaveError::UnknownAccount(_)
+            | ViewEnclaveError::QueryAccounts(_) => {
+                rpc_invalid_arg_error(context, src, &self.logger)
+            }
             ViewEnclaveError::AttestEnclave(err) => {
                 rpc_permissions_error(context, err, &self.logger)
             }
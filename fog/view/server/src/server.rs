@@ -555,7 +555,7 @@ where
                 num_records
             );
             let _metrics_timer = counters::ENCLAVE_ADD_RECORDS_TIME.start_timer();
-            self.enclave.add_records(records)
+            self.enclave.add_records(block_index, records)
         };
 
         match add_records_result {
@@ -0,0 +1,631 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Compaction of an existing local archive: verifies every block that was
+//! previously written, restores missing or corrupt blocks from valid merged
+//! blocks, reports the gaps and corrupt objects that remain, and produces any
+//! merged blocks that are missing for the configured bucket sizes (e.g. after
+//! a crash, or after the bucket sizes were changed).
+
+use crate::BlockHandler;
+use mc_api::{block_num_to_s3block_path, blockchain, merged_block_num_to_s3block_path};
+use mc_blockchain_types::{BlockData, BlockID, BlockIndex};
+use mc_common::{
+    logger::{log, Logger},
+    HashMap,
+};
+use protobuf::Message;
+use std::{
+    collections::VecDeque,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// The outcome of compacting an archive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompactionReport {
+    /// The number of blocks the archive should contain, based on the highest
+    /// block index found in blocks and merged blocks.
+    pub num_blocks: u64,
+
+    /// Blocks that are missing from the archive.
+    pub missing_blocks: Vec<BlockIndex>,
+
+    /// Blocks that failed to parse or verify, and why.
+    pub corrupt_blocks: Vec<(BlockIndex, String)>,
+
+    /// Blocks that were missing or corrupt, and were restored from a valid
+    /// merged block.
+    pub restored_blocks: Vec<BlockIndex>,
+
+    /// Merged blocks (bucket size, first block index) that were written.
+    pub written_merged_blocks: Vec<(u64, BlockIndex)>,
+
+    /// Merged blocks (bucket size, first block index) that existed but failed
+    /// to verify or did not match the individual blocks, and why. These are
+    /// rewritten when possible.
+    pub corrupt_merged_blocks: Vec<(u64, BlockIndex, String)>,
+
+    /// Merged blocks (bucket size, first block index) that could not be
+    /// written because some of their blocks are missing or corrupt.
+    pub skipped_merged_blocks: Vec<(u64, BlockIndex)>,
+}
+
+impl CompactionReport {
+    /// Whether the archive has any missing or corrupt blocks that could not be
+    /// restored.
+    pub fn has_errors(&self) -> bool {
+        !self.missing_blocks.is_empty()
+            || !self.corrupt_blocks.is_empty()
+            || !self.skipped_merged_blocks.is_empty()
+    }
+}
+
+/// Reasons an object could not be read from the archive.
+enum ObjectError {
+    Missing,
+    Corrupt(String),
+}
+
+/// Verify all blocks stored in a local archive, restore missing or corrupt
+/// blocks from merged blocks and write any missing merged blocks.
+///
+/// Blocks are read once, in order. Verified blocks are kept in memory until
+/// every merged block they belong to has been checked, so at most as many
+/// blocks as the largest bucket size are held at a time.
+///
+/// # Arguments
+/// * `path` - The archive directory.
+/// * `bucket_sizes` - Merged blocks bucket sizes that should be present. Sizes
+///   of 0 or 1 are ignored.
+/// * `block_handler` - Used for writing blocks and merged blocks into the
+///   archive.
+/// * `logger` - Logger.
+pub fn compact_local_archive(
+    path: &Path,
+    bucket_sizes: &[u64],
+    block_handler: &mut dyn BlockHandler,
+    logger: &Logger,
+) -> CompactionReport {
+    let mut bucket_sizes = bucket_sizes
+        .iter()
+        .copied()
+        .filter(|size| *size > 1)
+        .collect::<Vec<_>>();
+    bucket_sizes.sort_unstable();
+    bucket_sizes.dedup();
+
+    let mut report = CompactionReport {
+        num_blocks: find_highest_block_index(path, &bucket_sizes).map_or(0, |index| index + 1),
+        ..Default::default()
+    };
+    log::info!(
+        logger,
+        "Verifying {} blocks in {:?}",
+        report.num_blocks,
+        path
+    );
+
+    let mut compactor = Compactor {
+        path,
+        bucket_sizes: &bucket_sizes,
+        block_handler,
+        logger,
+        report: &mut report,
+        previous_block_id: None,
+        buffered_blocks: VecDeque::new(),
+        first_buffered_block_index: 0,
+        merged_blocks: HashMap::default(),
+    };
+    for block_index in 0..compactor.report.num_blocks {
+        compactor.verify_block(block_index);
+    }
+
+    report
+}
+
+/// State of an in-progress compaction.
+struct Compactor<'a> {
+    path: &'a Path,
+    bucket_sizes: &'a [u64],
+    block_handler: &'a mut dyn BlockHandler,
+    logger: &'a Logger,
+    report: &'a mut CompactionReport,
+
+    /// The id of the previous block, if it is valid.
+    previous_block_id: Option<BlockID>,
+
+    /// Blocks that belong to merged blocks that were not checked yet, starting
+    /// at `first_buffered_block_index`. Blocks that are missing or corrupt
+    /// are `None`.
+    buffered_blocks: VecDeque<Option<BlockData>>,
+
+    /// The index of the first block in `buffered_blocks`.
+    first_buffered_block_index: BlockIndex,
+
+    /// Merged blocks that were read while restoring blocks, keyed by bucket
+    /// size and first block index. Merged blocks that are missing or failed
+    /// verification are `None`.
+    merged_blocks: HashMap<(u64, BlockIndex), Option<Vec<BlockData>>>,
+}
+
+impl<'a> Compactor<'a> {
+    /// Verify the next block, including its chaining to the previous block,
+    /// and check the merged blocks that end with it.
+    fn verify_block(&mut self, block_index: BlockIndex) {
+        // When the previous block is unavailable we cannot check the chaining, but
+        // the block itself is still valid.
+        let parent_id = match block_index {
+            0 => Some(BlockID::default()),
+            _ => self.previous_block_id.take(),
+        };
+
+        let result = read_block(self.path, block_index).and_then(|block_data| {
+            if parent_id
+                .as_ref()
+                .map_or(true, |parent_id| *parent_id == block_data.block().parent_id)
+            {
+                Ok(block_data)
+            } else {
+                Err(ObjectError::Corrupt("parent id mismatch".to_string()))
+            }
+        });
+
+        let block_data = match result {
+            Ok(block_data) => Some(block_data),
+            Err(err) => {
+                let block_data = self.restore_block(block_index, parent_id.as_ref());
+                match (&block_data, err) {
+                    (Some(_), _) => {}
+                    (None, ObjectError::Missing) => {
+                        log::error!(self.logger, "Block #{} is missing", block_index);
+                        self.report.missing_blocks.push(block_index);
+                    }
+                    (None, ObjectError::Corrupt(reason)) => {
+                        log::error!(self.logger, "Block #{} is corrupt: {}", block_index, reason);
+                        self.report.corrupt_blocks.push((block_index, reason));
+                    }
+                }
+                block_data
+            }
+        };
+        self.previous_block_id = block_data
+            .as_ref()
+            .map(|block_data| block_data.block().id.clone());
+        self.buffered_blocks.push_back(block_data);
+
+        for bucket_size in self.bucket_sizes {
+            if (block_index + 1) % bucket_size == 0 {
+                self.compact_bucket(*bucket_size, block_index + 1 - bucket_size);
+            }
+        }
+
+        // Drop the blocks that are not part of a merged block that is still pending.
+        let next_block_index = block_index + 1;
+        let keep_from = self
+            .bucket_sizes
+            .iter()
+            .map(|bucket_size| next_block_index / bucket_size * bucket_size)
+            .min()
+            .unwrap_or(next_block_index);
+        while self.first_buffered_block_index < keep_from {
+            self.buffered_blocks.pop_front();
+            self.first_buffered_block_index += 1;
+        }
+    }
+
+    /// Look for a valid copy of a block inside the merged blocks that contain
+    /// it, and write it back into the archive.
+    fn restore_block(
+        &mut self,
+        block_index: BlockIndex,
+        parent_id: Option<&BlockID>,
+    ) -> Option<BlockData> {
+        for bucket_size in self.bucket_sizes {
+            let first_block_index = block_index / bucket_size * bucket_size;
+            if first_block_index + bucket_size > self.report.num_blocks {
+                continue;
+            }
+
+            let merged_blocks = self.get_merged_blocks(*bucket_size, first_block_index);
+            let block_data = merged_blocks
+                .as_ref()
+                .map(|blocks_data| &blocks_data[(block_index - first_block_index) as usize])
+                .filter(|block_data| {
+                    parent_id.map_or(true, |parent_id| *parent_id == block_data.block().parent_id)
+                })
+                .cloned();
+
+            if let Some(block_data) = block_data {
+                log::info!(
+                    self.logger,
+                    "Restoring block #{} from merged block {}@{}",
+                    block_index,
+                    bucket_size,
+                    first_block_index
+                );
+                self.block_handler.write_single_block(&block_data);
+                self.report.restored_blocks.push(block_index);
+                return Some(block_data);
+            }
+        }
+        None
+    }
+
+    /// Check the merged block that ends with the most recently verified block
+    /// against the individual blocks, and write it if it is missing or does
+    /// not match.
+    fn compact_bucket(&mut self, bucket_size: u64, first_block_index: BlockIndex) {
+        let merged_blocks = match self.merged_blocks.remove(&(bucket_size, first_block_index)) {
+            Some(merged_blocks) => merged_blocks,
+            None => self.read_merged_blocks(bucket_size, first_block_index),
+        };
+
+        let start = (first_block_index - self.first_buffered_block_index) as usize;
+        let blocks_data = self
+            .buffered_blocks
+            .range(start..start + bucket_size as usize)
+            .cloned()
+            .collect::<Option<Vec<_>>>();
+
+        // We can only produce the merged block if all of its blocks are valid.
+        let blocks_data = match blocks_data {
+            Some(blocks_data) => blocks_data,
+            None => {
+                self.report
+                    .skipped_merged_blocks
+                    .push((bucket_size, first_block_index));
+                return;
+            }
+        };
+
+        if let Some(merged_blocks) = merged_blocks {
+            let ids = merged_blocks
+                .iter()
+                .map(|block_data| &block_data.block().id);
+            if ids.eq(blocks_data.iter().map(|block_data| &block_data.block().id)) {
+                return;
+            }
+            log::error!(
+                self.logger,
+                "Merged block {}@{} does not match the individual blocks",
+                bucket_size,
+                first_block_index
+            );
+            self.report.corrupt_merged_blocks.push((
+                bucket_size,
+                first_block_index,
+                "block ids mismatch".to_string(),
+            ));
+        }
+
+        self.block_handler.write_multiple_blocks(&blocks_data);
+        self.report
+            .written_merged_blocks
+            .push((bucket_size, first_block_index));
+    }
+
+    /// Get a merged block, reading it from the archive the first time.
+    fn get_merged_blocks(
+        &mut self,
+        bucket_size: u64,
+        first_block_index: BlockIndex,
+    ) -> &Option<Vec<BlockData>> {
+        if !self
+            .merged_blocks
+            .contains_key(&(bucket_size, first_block_index))
+        {
+            let merged_blocks = self.read_merged_blocks(bucket_size, first_block_index);
+            self.merged_blocks
+                .insert((bucket_size, first_block_index), merged_blocks);
+        }
+        &self.merged_blocks[&(bucket_size, first_block_index)]
+    }
+
+    /// Read and verify a merged block, reporting it if it is corrupt.
+    fn read_merged_blocks(
+        &mut self,
+        bucket_size: u64,
+        first_block_index: BlockIndex,
+    ) -> Option<Vec<BlockData>> {
+        match read_merged_blocks(self.path, bucket_size, first_block_index) {
+            Ok(blocks_data) => Some(blocks_data),
+            Err(ObjectError::Missing) => None,
+            Err(ObjectError::Corrupt(reason)) => {
+                log::error!(
+                    self.logger,
+                    "Merged block {}@{} is corrupt: {}",
+                    bucket_size,
+                    first_block_index,
+                    reason
+                );
+                self.report
+                    .corrupt_merged_blocks
+                    .push((bucket_size, first_block_index, reason));
+                None
+            }
+        }
+    }
+}
+
+/// Find the highest block index stored in the archive, either as an
+/// individual block or as part of a merged block of one of the given bucket
+/// sizes.
+fn find_highest_block_index(path: &Path, bucket_sizes: &[u64]) -> Option<BlockIndex> {
+    fn walk(dir: &Path, depth: usize) -> Option<BlockIndex> {
+        let entries = fs::read_dir(dir).ok()?;
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                if depth == 0 && name.starts_with("merged-") {
+                    return None;
+                }
+                let entry_path = entry.path();
+                if entry_path.is_dir() {
+                    walk(&entry_path, depth + 1)
+                } else {
+                    let stem = name.strip_suffix(".pb")?;
+                    BlockIndex::from_str_radix(stem, 16).ok()
+                }
+            })
+            .max()
+    }
+
+    let highest_merged_block_index = bucket_sizes.iter().filter_map(|bucket_size| {
+        let first_block_index = walk(&path.join(format!("merged-{}", bucket_size)), 1)?;
+        Some(first_block_index + bucket_size - 1)
+    });
+    walk(path, 0)
+        .into_iter()
+        .chain(highest_merged_block_index)
+        .max()
+}
+
+fn read_object<M: Message>(path: PathBuf) -> Result<M, ObjectError> {
+    let bytes = fs::read(&path).map_err(|err| {
+        if err.kind() == ErrorKind::NotFound {
+            ObjectError::Missing
+        } else {
+            ObjectError::Corrupt(format!("failed reading {:?}: {}", path, err))
+        }
+    })?;
+    M::parse_from_bytes(&bytes)
+        .map_err(|err| ObjectError::Corrupt(format!("protobuf parse failed: {}", err)))
+}
+
+/// Read a single block. The conversion into a `BlockData` verifies the
+/// signature (when present), the contents hash and the block id.
+fn read_block(path: &Path, block_index: BlockIndex) -> Result<BlockData, ObjectError> {
+    let archive_block: blockchain::ArchiveBlock =
+        read_object(path.join(block_num_to_s3block_path(block_index)))?;
+    let block_data =
+        BlockData::try_from(&archive_block).map_err(|err| ObjectError::Corrupt(err.to_string()))?;
+    if block_data.block().index != block_index {
+        return Err(ObjectError::Corrupt(format!(
+            "contains block #{}",
+            block_data.block().index
+        )));
+    }
+    Ok(block_data)
+}
+
+/// Read a merged block. The conversion into `BlockData`s verifies every block
+/// like [read_block] does, and the chaining between them. On top of that, the
+/// merged block must hold exactly the blocks of its bucket.
+fn read_merged_blocks(
+    path: &Path,
+    bucket_size: u64,
+    first_block_index: BlockIndex,
+) -> Result<Vec<BlockData>, ObjectError> {
+    let archive_blocks: blockchain::ArchiveBlocks = read_object(path.join(
+        merged_block_num_to_s3block_path(bucket_size, first_block_index),
+    ))?;
+    let blocks_data = Vec::<BlockData>::try_from(&archive_blocks)
+        .map_err(|err| ObjectError::Corrupt(err.to_string()))?;
+
+    if blocks_data.len() as u64 != bucket_size {
+        return Err(ObjectError::Corrupt(format!(
+            "contains {} blocks",
+            blocks_data.len()
+        )));
+    }
+    for (block_index, block_data) in (first_block_index..).zip(blocks_data.iter()) {
+        if block_data.block().index != block_index {
+            return Err(ObjectError::Corrupt(format!(
+                "contains block #{} instead of #{}",
+                block_data.block().index,
+                block_index
+            )));
+        }
+    }
+    if first_block_index == 0 && blocks_data[0].block().parent_id != BlockID::default() {
+        return Err(ObjectError::Corrupt(
+            "origin block has a parent id".to_string(),
+        ));
+    }
+    Ok(blocks_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalBlockWriter;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_ledger_db::test_utils::get_test_ledger_blocks;
+    use tempdir::TempDir;
+
+    fn write_blocks(dir: &Path, n_blocks: usize, logger: &Logger) -> Vec<BlockData> {
+        let blocks_data = get_test_ledger_blocks(n_blocks)
+            .into_iter()
+            .map(|(block, contents)| BlockData::new(block, contents, None, None))
+            .collect::<Vec<_>>();
+        let mut writer = LocalBlockWriter::new(dir.to_path_buf(), logger.clone());
+        for block_data in &blocks_data {
+            writer.write_single_block(block_data);
+        }
+        blocks_data
+    }
+
+    #[test_with_logger]
+    fn writes_missing_merged_blocks(logger: Logger) {
+        let dir = TempDir::new("compaction").unwrap();
+        let blocks_data = write_blocks(dir.path(), 7, &logger);
+
+        let mut writer = LocalBlockWriter::new(dir.path().to_path_buf(), logger.clone());
+        let report = compact_local_archive(dir.path(), &[0, 1, 3], &mut writer, &logger);
+        assert_eq!(
+            report,
+            CompactionReport {
+                num_blocks: 7,
+                written_merged_blocks: vec![(3, 0), (3, 3)],
+                ..Default::default()
+            }
+        );
+        assert!(!report.has_errors());
+        assert_eq!(
+            read_merged_blocks(dir.path(), 3, 3).ok().unwrap(),
+            blocks_data[3..6].to_vec()
+        );
+
+        // Running again finds nothing to do.
+        let report = compact_local_archive(dir.path(), &[3], &mut writer, &logger);
+        assert!(report.written_merged_blocks.is_empty());
+        assert!(report.corrupt_merged_blocks.is_empty());
+    }
+
+    #[test_with_logger]
+    fn restores_blocks_from_merged_blocks(logger: Logger) {
+        let dir = TempDir::new("compaction").unwrap();
+        let blocks_data = write_blocks(dir.path(), 8, &logger);
+
+        let mut writer = LocalBlockWriter::new(dir.path().to_path_buf(), logger.clone());
+        let report = compact_local_archive(dir.path(), &[4], &mut writer, &logger);
+        assert_eq!(report.written_merged_blocks, vec![(4, 0), (4, 4)]);
+
+        // Remove block #1 and the last block, corrupt block #5 and replace block #6
+        // with a copy of block #2.
+        fs::remove_file(dir.path().join(block_num_to_s3block_path(1))).unwrap();
+        fs::remove_file(dir.path().join(block_num_to_s3block_path(7))).unwrap();
+        fs::write(dir.path().join(block_num_to_s3block_path(5)), b"garbage").unwrap();
+        fs::copy(
+            dir.path().join(block_num_to_s3block_path(2)),
+            dir.path().join(block_num_to_s3block_path(6)),
+        )
+        .unwrap();
+
+        let report = compact_local_archive(dir.path(), &[4], &mut writer, &logger);
+        assert_eq!(
+            report,
+            CompactionReport {
+                num_blocks: 8,
+                restored_blocks: vec![1, 5, 6, 7],
+                ..Default::default()
+            }
+        );
+        assert!(!report.has_errors());
+        for block_data in &blocks_data {
+            let block_index = block_data.block().index;
+            assert_eq!(
+                &read_block(dir.path(), block_index).ok().unwrap(),
+                block_data
+            );
+        }
+    }
+
+    #[test_with_logger]
+    fn verifies_merged_blocks_before_restoring(logger: Logger) {
+        let dir = TempDir::new("compaction").unwrap();
+        let blocks_data = write_blocks(dir.path(), 4, &logger);
+        fs::remove_file(dir.path().join(block_num_to_s3block_path(2))).unwrap();
+
+        // Every block in this merged block is valid, but blocks #2 and #3 are swapped.
+        let merged_block = blockchain::ArchiveBlocks::from(&blocks_data[..]);
+        let mut blocks = merged_block.get_blocks().to_vec();
+        blocks.swap(2, 3);
+        let mut merged_block = blockchain::ArchiveBlocks::new();
+        merged_block.set_blocks(blocks.into());
+        fs::create_dir_all(
+            dir.path()
+                .join(merged_block_num_to_s3block_path(4, 0))
+                .parent()
+                .unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(merged_block_num_to_s3block_path(4, 0)),
+            merged_block.write_to_bytes().unwrap(),
+        )
+        .unwrap();
+
+        let mut writer = LocalBlockWriter::new(dir.path().to_path_buf(), logger.clone());
+        let report = compact_local_archive(dir.path(), &[4], &mut writer, &logger);
+        assert_eq!(report.missing_blocks, vec![2]);
+        assert!(report.restored_blocks.is_empty());
+        assert_eq!(
+            report
+                .corrupt_merged_blocks
+                .iter()
+                .map(|(bucket_size, block_index, _)| (*bucket_size, *block_index))
+                .collect::<Vec<_>>(),
+            vec![(4, 0)]
+        );
+        assert_eq!(report.skipped_merged_blocks, vec![(4, 0)]);
+        assert!(!dir.path().join(block_num_to_s3block_path(2)).exists());
+    }
+
+    #[test_with_logger]
+    fn reports_gaps_and_corrupt_blocks(logger: Logger) {
+        let dir = TempDir::new("compaction").unwrap();
+        let blocks_data = write_blocks(dir.path(), 8, &logger);
+
+        // Remove block #1, corrupt block #4 and replace block #6 with a copy of block
+        // #7.
+        fs::remove_file(dir.path().join(block_num_to_s3block_path(1))).unwrap();
+        fs::write(dir.path().join(block_num_to_s3block_path(4)), b"garbage").unwrap();
+        fs::copy(
+            dir.path().join(block_num_to_s3block_path(7)),
+            dir.path().join(block_num_to_s3block_path(6)),
+        )
+        .unwrap();
+
+        // A stale merged block whose contents do not match.
+        let stale_merged_block = blockchain::ArchiveBlocks::from(&blocks_data[2..4]);
+        fs::create_dir_all(
+            dir.path()
+                .join(merged_block_num_to_s3block_path(2, 0))
+                .parent()
+                .unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(merged_block_num_to_s3block_path(2, 0)),
+            stale_merged_block.write_to_bytes().unwrap(),
+        )
+        .unwrap();
+
+        let mut writer = LocalBlockWriter::new(dir.path().to_path_buf(), logger.clone());
+        let report = compact_local_archive(dir.path(), &[2], &mut writer, &logger);
+        assert!(report.has_errors());
+        assert_eq!(report.num_blocks, 8);
+        assert_eq!(report.missing_blocks, vec![1]);
+        assert_eq!(
+            report
+                .corrupt_blocks
+                .iter()
+                .map(|(block_index, _)| *block_index)
+                .collect::<Vec<_>>(),
+            vec![4, 6]
+        );
+        assert_eq!(
+            report
+                .corrupt_merged_blocks
+                .iter()
+                .map(|(bucket_size, block_index, _)| (*bucket_size, *block_index))
+                .collect::<Vec<_>>(),
+            vec![(2, 0)]
+        );
+        assert_eq!(report.written_merged_blocks, vec![(2, 2)]);
+        assert_eq!(report.skipped_merged_blocks, vec![(2, 0), (2, 4), (2, 6)]);
+    }
+}
@@ -4,9 +4,13 @@
 //! A helper utility for collecting blocks from a local ledger file and storing
 //! them as Protobuf-serialized files on S3.
 
+pub mod compaction;
 pub mod uri;
 
-use crate::uri::{Destination, Uri};
+use crate::{
+    compaction::compact_local_archive,
+    uri::{Destination, Uri},
+};
 use clap::{ArgEnum, Parser};
 use mc_api::{block_num_to_s3block_path, blockchain, merged_block_num_to_s3block_path};
 use mc_blockchain_types::{BlockData, BlockIndex};
@@ -51,8 +55,13 @@ pub enum StartFrom {
 )]
 pub struct Config {
     /// Path to local LMDB db file.
-    #[clap(long, parse(from_os_str), env = "MC_LEDGER_PATH")]
-    pub ledger_path: PathBuf,
+    #[clap(
+        long,
+        parse(from_os_str),
+        required_unless_present = "compact",
+        env = "MC_LEDGER_PATH"
+    )]
+    pub ledger_path: Option<PathBuf>,
 
     /// Destination to upload to.
     #[clap(long = "dest", env = "MC_DEST")]
@@ -74,6 +83,12 @@ pub struct Config {
         env = "MC_MERGE_BUCKETS"
     )]
     merge_buckets: Vec<u64>,
+
+    /// Instead of distributing blocks, verify the blocks already present at a
+    /// local destination, report gaps and corrupt objects, and write any
+    /// missing merged blocks.
+    #[clap(long, env = "MC_COMPACT")]
+    pub compact: bool,
 }
 
 /// State file contents.
@@ -308,6 +323,21 @@ fn main() {
         .expect("tokio runtime");
     let _enter_guard = runtime.enter();
 
+    if config.compact {
+        let path = match config.destination.destination {
+            Destination::Local { path } => path,
+            Destination::S3 { .. } => panic!("Compaction is only supported for local destinations"),
+        };
+        let mut block_handler = LocalBlockWriter::new(path.clone(), logger.clone());
+        let report =
+            compact_local_archive(&path, &config.merge_buckets, &mut block_handler, &logger);
+        log::info!(logger, "Compaction finished: {:?}", report);
+        if report.has_errors() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Get path to our state file.
     let state_file_path = config.state_file.clone().unwrap_or_else(|| {
         let mut home_dir = dirs::home_dir().unwrap_or_else(|| panic!("Unable to get home directory, please specify state file explicitly with --state-file"));
@@ -318,8 +348,9 @@ fn main() {
     log::info!(logger, "State file is {:?}", state_file_path);
 
     // Open ledger
-    log::info!(logger, "Opening ledger db {:?}", config.ledger_path);
-    let ledger_db = LedgerDB::open(&config.ledger_path).expect("Could not read ledger DB");
+    let ledger_path = config.ledger_path.expect("Missing ledger path");
+    log::info!(logger, "Opening ledger db {:?}", ledger_path);
+    let ledger_db = LedgerDB::open(&ledger_path).expect("Could not read ledger DB");
 
     // Figure out the first block to sync from.
     let first_desired_block = match config.start_from {
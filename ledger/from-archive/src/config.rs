@@ -24,6 +24,9 @@ pub struct LedgerFromArchiveConfig {
     /// URLs to use to pull blocks.
     ///
     /// For example: https://s3-us-west-1.amazonaws.com/mobilecoin.chain/node1.master.mobilecoin.com/
+    ///
+    /// Local (file:///path/to/blocks/) and S3 (s3://bucket/prefix/) snapshots
+    /// are also supported.
     #[clap(
         long = "tx-source-url",
         required = true,
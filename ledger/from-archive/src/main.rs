@@ -9,7 +9,7 @@ use clap::Parser;
 use config::LedgerFromArchiveConfig;
 use mc_common::logger::{create_app_logger, log, o};
 use mc_ledger_db::{Ledger, LedgerDB};
-use mc_ledger_sync::ReqwestTransactionsFetcher;
+use mc_ledger_sync::ObjectStoreTransactionsFetcher;
 use std::fs;
 
 fn main() {
@@ -19,8 +19,8 @@ fn main() {
     let config = LedgerFromArchiveConfig::parse();
 
     let transactions_fetcher =
-        ReqwestTransactionsFetcher::new(config.tx_source_urls.clone(), logger.clone())
-            .expect("Failed creating ReqwestTransactionsFetcher");
+        ObjectStoreTransactionsFetcher::new(config.tx_source_urls.clone(), logger.clone())
+            .expect("Failed creating ObjectStoreTransactionsFetcher");
 
     log::info!(logger, "Creating local ledger at {:?}", config.ledger_db);
     // Open LedgerDB
@@ -2,7 +2,7 @@
 
 mod ledger_sync;
 mod network_state;
-mod reqwest_transactions_fetcher;
+mod object_store_transactions_fetcher;
 mod transactions_fetcher_trait;
 
 pub use ledger_sync::{
@@ -10,8 +10,8 @@ pub use ledger_sync::{
     MockLedgerSync,
 };
 pub use network_state::{NetworkState, PollingNetworkState, SCPNetworkState};
-pub use reqwest_transactions_fetcher::{
-    ReqwestTransactionsFetcher, ReqwestTransactionsFetcherError,
+pub use object_store_transactions_fetcher::{
+    ObjectStore, ObjectStoreTransactionsFetcher, ObjectStoreTransactionsFetcherError, S3ObjectStore,
 };
 pub use transactions_fetcher_trait::{TransactionFetcherError, TransactionsFetcher};
 
@@ -0,0 +1,917 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Implementation of the `TransactionsFetcher` trait that reads transactions
+//! data from object stores laid out the way `mc-ledger-distribution` writes
+//! it. The store is picked based on the URL scheme:
+//! - `http://` and `https://` fetch objects using the `reqwest` library, e.g.
+//!   from a public S3 bucket.
+//! - `file:///path/to/blocks` reads objects from a local directory.
+//! - `s3://bucket/optional/prefix` reads objects from an S3-compatible bucket
+//!   using the S3 API.
+//!
+//! S3 URLs accept a `region` query parameter, and an `endpoint` query parameter
+//! for S3-compatible services that are not AWS (e.g. MinIO):
+//! `s3://bucket/prefix?region=us-east-1&endpoint=http://localhost:9000`.
+//! Credentials are taken from the standard AWS environment variables and
+//! configuration files.
+//!
+//! Every block goes through the `ArchiveBlock` -> `BlockData` conversion, which
+//! verifies the block signature (when present), the contents hash and the
+//! `BlockID`. This makes it safe to sync from a snapshot of unknown
+//! provenance, such as a mounted drive on an air-gapped machine.
+
+use crate::transactions_fetcher_trait::{TransactionFetcherError, TransactionsFetcher};
+use displaydoc::Display;
+use mc_api::{block_num_to_s3block_path, blockchain, merged_block_num_to_s3block_path};
+use mc_blockchain_types::{Block, BlockData, BlockIndex};
+use mc_common::{
+    logger::{log, Logger},
+    lru::LruCache,
+    ResponderId,
+};
+use protobuf::Message;
+use reqwest::StatusCode;
+use rusoto_core::{region::ParseRegionError, Region, RusotoError};
+use rusoto_s3::{GetObjectError, GetObjectRequest, S3Client, S3};
+use std::{
+    fs,
+    io::{ErrorKind, Read},
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// Default merged blocks bucket sizes. Merged blocks are objects that contain
+/// multiple consecutive blocks that have been bundled together in order to
+/// reduce the amount of requests needed to get the block data.
+/// Notes:
+/// - This should match the defaults in `mc-ledger-distribution`.
+/// - This must be sorted in descending order.
+pub const DEFAULT_MERGED_BLOCKS_BUCKET_SIZES: &[u64] = &[10000, 1000, 100];
+
+/// Maximum number of pre-fetched blocks to keep in cache.
+pub const MAX_PREFETCHED_BLOCKS: usize = 10000;
+
+#[derive(Debug, Display)]
+pub enum ObjectStoreTransactionsFetcherError {
+    /// Url parse error on {0}: {1}
+    UrlParse(String, url::ParseError),
+
+    /// Unsupported scheme in {0}, expected http(s)://, file:// or s3://
+    UnsupportedScheme(String),
+
+    /// Missing path in {0}
+    MissingPath(String),
+
+    /// Invalid S3 region in {0}: {1}
+    InvalidS3Region(String, ParseRegionError),
+
+    /// Failed creating async runtime: {0:?}
+    Runtime(std::io::Error),
+
+    /// IO error on {0}: {1:?}
+    IO(String, std::io::Error),
+
+    /// reqwest error on {0}: {1:?}
+    Reqwest(String, reqwest::Error),
+
+    /// S3 error on {0}: {1}
+    S3(String, String),
+
+    /// Object not found: {0}
+    NotFound(String),
+
+    /// Access denied to {0}
+    Forbidden(String),
+
+    /// Received an invalid block from {0}: {1}
+    InvalidBlockReceived(String, String),
+
+    /// No sources configured
+    NoSourcesConfigured,
+}
+
+impl TransactionFetcherError for ObjectStoreTransactionsFetcherError {}
+
+/// An S3 bucket (or a prefix inside of one).
+#[derive(Clone)]
+pub struct S3ObjectStore {
+    /// The bucket name.
+    bucket: String,
+
+    /// The key prefix blocks are stored under, without leading or trailing
+    /// slashes.
+    prefix: String,
+
+    /// S3 client.
+    client: S3Client,
+
+    /// Runtime used for driving the S3 client.
+    runtime: Arc<Runtime>,
+}
+
+impl S3ObjectStore {
+    fn get_object(&self, key: &str) -> Result<Vec<u8>, ObjectStoreTransactionsFetcherError> {
+        let key = if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix, key)
+        };
+        let location = format!("s3://{}/{}", self.bucket, key);
+
+        let request = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key,
+            ..Default::default()
+        };
+        let output = self
+            .runtime
+            .block_on(self.client.get_object(request))
+            .map_err(|err| match err {
+                RusotoError::Service(GetObjectError::NoSuchKey(_)) => {
+                    ObjectStoreTransactionsFetcherError::NotFound(location.clone())
+                }
+                RusotoError::Unknown(ref response) if response.status.as_u16() == 404 => {
+                    ObjectStoreTransactionsFetcherError::NotFound(location.clone())
+                }
+                RusotoError::Unknown(ref response) if response.status.as_u16() == 403 => {
+                    ObjectStoreTransactionsFetcherError::Forbidden(location.clone())
+                }
+                err => ObjectStoreTransactionsFetcherError::S3(location.clone(), err.to_string()),
+            })?;
+
+        let body = output.body.ok_or_else(|| {
+            ObjectStoreTransactionsFetcherError::S3(location.clone(), "missing body".to_string())
+        })?;
+        let mut bytes = Vec::new();
+        body.into_blocking_read()
+            .read_to_end(&mut bytes)
+            .map_err(|err| ObjectStoreTransactionsFetcherError::IO(location, err))?;
+        Ok(bytes)
+    }
+}
+
+/// A place blocks can be read from, addressed by object keys such as
+/// `00/00/00/00/00/00/00/0000000000000001.pb`.
+#[derive(Clone)]
+pub enum ObjectStore {
+    /// An http(s) server.
+    Http {
+        /// The URL objects keys are relative to. Always ends with a '/'.
+        base_url: Url,
+
+        /// Client used for HTTP(s) requests.
+        client: reqwest::blocking::Client,
+    },
+
+    /// A local directory.
+    Local {
+        /// The directory blocks are stored in.
+        path: PathBuf,
+    },
+
+    /// An S3-compatible bucket.
+    S3(S3ObjectStore),
+}
+
+impl ObjectStore {
+    /// Create an object store from an `http(s)://`, `file://` or `s3://` URL.
+    pub fn from_url(url: &Url) -> Result<Self, ObjectStoreTransactionsFetcherError> {
+        Self::from_url_with_client(url, reqwest::blocking::Client::new())
+    }
+
+    /// Create an object store from an `http(s)://`, `file://` or `s3://` URL,
+    /// using the given client for `http(s)://` URLs.
+    pub fn from_url_with_client(
+        url: &Url,
+        client: reqwest::blocking::Client,
+    ) -> Result<Self, ObjectStoreTransactionsFetcherError> {
+        let path = url[url::Position::BeforeHost..url::Position::AfterPath].trim_matches('/');
+
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http {
+                base_url: with_trailing_slash(url.clone()),
+                client,
+            }),
+
+            // Only local, absolute paths can be converted. Percent-escapes are
+            // decoded.
+            "file" => url
+                .to_file_path()
+                .map(|path| Self::Local { path })
+                .map_err(|_| ObjectStoreTransactionsFetcherError::MissingPath(url.to_string())),
+
+            "s3" if !path.is_empty() => {
+                let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+
+                let query_param = |name: &str| {
+                    url.query_pairs().find_map(|(k, v)| {
+                        if k == name && !v.is_empty() {
+                            Some(v.to_string())
+                        } else {
+                            None
+                        }
+                    })
+                };
+                let region = match (query_param("region"), query_param("endpoint")) {
+                    (region, Some(endpoint)) => Region::Custom {
+                        name: region.unwrap_or_else(|| Region::default().name().to_string()),
+                        endpoint,
+                    },
+                    (Some(region), None) => Region::from_str(&region).map_err(|err| {
+                        ObjectStoreTransactionsFetcherError::InvalidS3Region(url.to_string(), err)
+                    })?,
+                    (None, None) => Region::default(),
+                };
+
+                let runtime =
+                    Runtime::new().map_err(ObjectStoreTransactionsFetcherError::Runtime)?;
+                Ok(Self::S3(S3ObjectStore {
+                    bucket: bucket.to_string(),
+                    prefix: prefix.trim_matches('/').to_string(),
+                    client: S3Client::new(region),
+                    runtime: Arc::new(runtime),
+                }))
+            }
+
+            "s3" => Err(ObjectStoreTransactionsFetcherError::MissingPath(
+                url.to_string(),
+            )),
+
+            _ => Err(ObjectStoreTransactionsFetcherError::UnsupportedScheme(
+                url.to_string(),
+            )),
+        }
+    }
+
+    /// Get the contents of the object stored at the given (relative) key.
+    pub fn get_object(&self, key: &str) -> Result<Vec<u8>, ObjectStoreTransactionsFetcherError> {
+        match self {
+            Self::Http { base_url, client } => {
+                let url = base_url.join(key).map_err(|err| {
+                    ObjectStoreTransactionsFetcherError::UrlParse(key.to_string(), err)
+                })?;
+                let response = client.get(url.as_str()).send().map_err(|err| {
+                    ObjectStoreTransactionsFetcherError::Reqwest(url.to_string(), err)
+                })?;
+
+                match response.status() {
+                    StatusCode::NOT_FOUND => {
+                        return Err(ObjectStoreTransactionsFetcherError::NotFound(
+                            url.to_string(),
+                        ))
+                    }
+                    StatusCode::FORBIDDEN => {
+                        return Err(ObjectStoreTransactionsFetcherError::Forbidden(
+                            url.to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+
+                let mut response = response.error_for_status().map_err(|err| {
+                    ObjectStoreTransactionsFetcherError::Reqwest(url.to_string(), err)
+                })?;
+                let mut bytes = Vec::new();
+                response.copy_to(&mut bytes).map_err(|err| {
+                    ObjectStoreTransactionsFetcherError::Reqwest(url.to_string(), err)
+                })?;
+                Ok(bytes)
+            }
+            Self::Local { path } => {
+                let path = path.join(key);
+                fs::read(&path).map_err(|err| {
+                    let path = path.display().to_string();
+                    if err.kind() == ErrorKind::NotFound {
+                        ObjectStoreTransactionsFetcherError::NotFound(path)
+                    } else {
+                        ObjectStoreTransactionsFetcherError::IO(path, err)
+                    }
+                })
+            }
+            Self::S3(store) => store.get_object(key),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ObjectStoreTransactionsFetcher {
+    /// List of URLs to try and fetch objects from, each ending with a '/'.
+    pub source_urls: Vec<Url>,
+
+    /// The stores backing `source_urls`, in the same order.
+    stores: Vec<ObjectStore>,
+
+    /// Logger.
+    logger: Logger,
+
+    /// The most recently used store index (in `stores`).
+    store_index_counter: Arc<AtomicU64>,
+
+    /// Cache mapping a `BlockIndex` to `BlockData`, filled by merged blocks
+    /// when possible.
+    blocks_cache: Arc<Mutex<LruCache<BlockIndex, BlockData>>>,
+
+    /// Merged blocks bucket sizes to attempt fetching.
+    merged_blocks_bucket_sizes: Vec<u64>,
+}
+
+impl ObjectStoreTransactionsFetcher {
+    /// Create a fetcher from a list of `http(s)://`, `file://` and `s3://`
+    /// URLs.
+    pub fn new(
+        source_urls: Vec<String>,
+        logger: Logger,
+    ) -> Result<Self, ObjectStoreTransactionsFetcherError> {
+        Self::new_with_client(source_urls, reqwest::blocking::Client::new(), logger)
+    }
+
+    /// Create a fetcher from a list of `http(s)://`, `file://` and `s3://`
+    /// URLs, using the given client for `http(s)://` URLs.
+    pub fn new_with_client(
+        source_urls: Vec<String>,
+        client: reqwest::blocking::Client,
+        logger: Logger,
+    ) -> Result<Self, ObjectStoreTransactionsFetcherError> {
+        let source_urls = source_urls
+            .into_iter()
+            .map(|url| {
+                Url::parse(&url)
+                    .map(with_trailing_slash)
+                    .map_err(|err| ObjectStoreTransactionsFetcherError::UrlParse(url, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let stores = source_urls
+            .iter()
+            .map(|url| ObjectStore::from_url_with_client(url, client.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            source_urls,
+            stores,
+            logger,
+            store_index_counter: Arc::new(AtomicU64::new(0)),
+            blocks_cache: Arc::new(Mutex::new(LruCache::new(MAX_PREFETCHED_BLOCKS))),
+            merged_blocks_bucket_sizes: DEFAULT_MERGED_BLOCKS_BUCKET_SIZES.to_vec(),
+        })
+    }
+
+    pub fn set_merged_blocks_bucket_sizes(&mut self, bucket_sizes: &[u64]) {
+        self.merged_blocks_bucket_sizes = bucket_sizes.to_vec();
+    }
+
+    pub fn get_origin_block_and_transactions(
+        &self,
+    ) -> Result<BlockData, ObjectStoreTransactionsFetcherError> {
+        self.get_block_data_by_index(0, None)
+    }
+
+    pub fn get_block_data_by_index(
+        &self,
+        block_index: BlockIndex,
+        expected_block: Option<&Block>,
+    ) -> Result<BlockData, ObjectStoreTransactionsFetcherError> {
+        // Try and see if we can get this block from our cache.
+        if let Some(block_data) = self.get_cached_block_data(block_index, expected_block) {
+            return Ok(block_data);
+        }
+
+        // Get the store to fetch from.
+        if self.stores.is_empty() {
+            return Err(ObjectStoreTransactionsFetcherError::NoSourcesConfigured);
+        }
+        let store_index = self.store_index_counter.fetch_add(1, Ordering::SeqCst) as usize;
+        let store = &self.stores[store_index % self.stores.len()];
+
+        // Try and fetch a merged block if we stand a chance of finding one.
+        for bucket in self.merged_blocks_bucket_sizes.iter() {
+            if block_index % bucket != 0 {
+                continue;
+            }
+
+            let key = path_to_key(merged_block_num_to_s3block_path(*bucket, block_index));
+            let result = self
+                .fetch_protobuf_object::<blockchain::ArchiveBlocks>(store, &key)
+                .and_then(|archive_blocks| {
+                    Vec::<BlockData>::try_from(&archive_blocks).map_err(|err| {
+                        ObjectStoreTransactionsFetcherError::InvalidBlockReceived(
+                            key.clone(),
+                            err.to_string(),
+                        )
+                    })
+                });
+            match result {
+                Ok(blocks_data) => {
+                    log::debug!(
+                        self.logger,
+                        "Got a merged block for #{} (bucket size {}): {} entries",
+                        block_index,
+                        bucket,
+                        blocks_data.len(),
+                    );
+
+                    {
+                        let mut blocks_cache = self.blocks_cache.lock().expect("mutex poisoned");
+                        for block_data in blocks_data.into_iter() {
+                            blocks_cache.put(block_data.block().index, block_data);
+                        }
+                    }
+
+                    // Supposedly we have the block we asked for in the cache now.
+                    if let Some(block_data) =
+                        self.get_cached_block_data(block_index, expected_block)
+                    {
+                        return Ok(block_data);
+                    }
+                }
+                // Merged blocks are optional, so not finding one is expected. S3
+                // answers 403 rather than 404 for missing objects when the bucket
+                // cannot be listed, so access being denied is expected too.
+                Err(ObjectStoreTransactionsFetcherError::NotFound(_))
+                | Err(ObjectStoreTransactionsFetcherError::Forbidden(_)) => {}
+                Err(err) => {
+                    log::warn!(self.logger, "Failed fetching merged block {}: {}", key, err);
+                }
+            }
+        }
+
+        let key = path_to_key(block_num_to_s3block_path(block_index));
+        log::debug!(self.logger, "Attempting to fetch block {}", key);
+        let archive_block = self.fetch_protobuf_object::<blockchain::ArchiveBlock>(store, &key)?;
+        let block_data = BlockData::try_from(&archive_block).map_err(|err| {
+            ObjectStoreTransactionsFetcherError::InvalidBlockReceived(key.clone(), err.to_string())
+        })?;
+
+        if block_data.block().index != block_index {
+            return Err(ObjectStoreTransactionsFetcherError::InvalidBlockReceived(
+                key,
+                format!("expected block #{}", block_index),
+            ));
+        }
+
+        // If the caller is expecting a specific block, check that we received data for
+        // the block they asked for
+        if let Some(expected_block) = expected_block {
+            if expected_block != block_data.block() {
+                return Err(ObjectStoreTransactionsFetcherError::InvalidBlockReceived(
+                    key,
+                    "block data mismatch".to_string(),
+                ));
+            }
+        }
+
+        Ok(block_data)
+    }
+
+    fn fetch_protobuf_object<M: Message>(
+        &self,
+        store: &ObjectStore,
+        key: &str,
+    ) -> Result<M, ObjectStoreTransactionsFetcherError> {
+        let bytes = store.get_object(key)?;
+        M::parse_from_bytes(&bytes).map_err(|err| {
+            ObjectStoreTransactionsFetcherError::InvalidBlockReceived(
+                key.to_string(),
+                format!("protobuf parse failed: {:?}", err),
+            )
+        })
+    }
+
+    fn get_cached_block_data(
+        &self,
+        block_index: BlockIndex,
+        expected_block: Option<&Block>,
+    ) -> Option<BlockData> {
+        let mut blocks_cache = self.blocks_cache.lock().expect("mutex poisoned");
+
+        // Blocks are taken out of the cache since LedgerSyncService does not fetch
+        // the same block twice once it got a valid copy of it.
+        let block_data = blocks_cache.pop(&block_index)?;
+        match expected_block {
+            Some(expected_block) if block_data.block() != expected_block => {
+                log::warn!(
+                    self.logger,
+                    "Got cached block {:?} but actually requested {:?}",
+                    block_data.block(),
+                    expected_block
+                );
+                None
+            }
+            _ if block_data.block().index != block_index => None,
+            _ => Some(block_data),
+        }
+    }
+}
+
+impl TransactionsFetcher for ObjectStoreTransactionsFetcher {
+    type Error = ObjectStoreTransactionsFetcherError;
+
+    fn get_block_data(
+        &self,
+        _safe_responder_ids: &[ResponderId],
+        block: &Block,
+    ) -> Result<BlockData, Self::Error> {
+        self.get_block_data_by_index(block.index, Some(block))
+    }
+}
+
+/// Source URLs are directories, so they need to end with a '/' for relative
+/// keys to be joined onto them. Query parameters are left untouched.
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
+/// Object store keys always use forward slashes.
+fn path_to_key(path: PathBuf) -> String {
+    path.iter()
+        .map(|part| part.to_str().expect("block paths are valid utf8"))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_ledger_db::test_utils::get_test_ledger_blocks;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        path::Path,
+        thread,
+    };
+    use tempdir::TempDir;
+
+    fn blocks_data(n_blocks: usize) -> Vec<BlockData> {
+        get_test_ledger_blocks(n_blocks)
+            .into_iter()
+            .map(|(block, contents)| BlockData::new(block, contents, None, None))
+            .collect()
+    }
+
+    fn write_object(root: &Path, path: PathBuf, object: &impl Message) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, object.write_to_bytes().unwrap()).unwrap();
+    }
+
+    fn write_blocks(root: &Path, blocks_data: &[BlockData]) {
+        for block_data in blocks_data {
+            write_object(
+                root,
+                block_num_to_s3block_path(block_data.block().index),
+                &blockchain::ArchiveBlock::from(block_data),
+            );
+        }
+    }
+
+    /// Serve `root` as the contents of `bucket` the way S3-compatible services
+    /// (e.g. MinIO) do for path-style GetObject requests, i.e.
+    /// `GET /<bucket>/<key>`. This is enough for both the S3 API and plain
+    /// http(s) fetching. Missing objects are answered with `missing_status`,
+    /// since buckets that cannot be listed answer 403 rather than 404.
+    fn serve_bucket(root: &Path, bucket: &'static str, missing_status: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let root = root.to_path_buf();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let root = root.clone();
+                thread::spawn(move || {
+                    serve_connection(stream.unwrap(), &root, bucket, missing_status)
+                });
+            }
+        });
+        addr
+    }
+
+    fn serve_connection(mut stream: TcpStream, root: &Path, bucket: &str, missing_status: &str) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                return;
+            }
+            // Skip the headers, GET requests have no body.
+            loop {
+                let mut header = String::new();
+                if reader.read_line(&mut header).unwrap_or(0) == 0 {
+                    return;
+                }
+                if header.trim_end().is_empty() {
+                    break;
+                }
+            }
+
+            let path = request_line.split(' ').nth(1).unwrap_or_default();
+            let path = path.split('?').next().unwrap_or_default();
+            let object = path
+                .strip_prefix(&format!("/{}/", bucket))
+                .and_then(|key| fs::read(root.join(key)).ok());
+
+            let (status, content_type, body) = match object {
+                Some(bytes) => ("200 OK", "application/octet-stream", bytes),
+                None => (
+                    missing_status,
+                    "application/xml",
+                    b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                      <Error><Code>NoSuchKey</Code>\
+                      <Message>The specified key does not exist.</Message></Error>"
+                        .to_vec(),
+                ),
+            };
+            let header = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                status,
+                content_type,
+                body.len()
+            );
+            if stream.write_all(header.as_bytes()).is_err() || stream.write_all(&body).is_err() {
+                return;
+            }
+        }
+    }
+
+    #[test_with_logger]
+    fn fetches_blocks_from_local_directory(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(5);
+        write_blocks(dir.path(), &blocks_data);
+
+        let url = format!("file://{}", dir.path().display());
+        let fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+
+        for block_data in &blocks_data {
+            let block = block_data.block();
+            assert_eq!(
+                &fetcher.get_block_data(&[], block).unwrap(),
+                block_data,
+                "block #{}",
+                block.index
+            );
+        }
+
+        // Blocks that were never written are reported as missing.
+        let mut missing_block = blocks_data[4].block().clone();
+        missing_block.index = 5;
+        assert!(matches!(
+            fetcher.get_block_data(&[], &missing_block),
+            Err(ObjectStoreTransactionsFetcherError::NotFound(_))
+        ));
+    }
+
+    #[test_with_logger]
+    fn fetches_merged_blocks_from_local_directory(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(4);
+
+        // Only write the merged block, so the individual blocks can only be served from
+        // the cache.
+        write_object(
+            dir.path(),
+            merged_block_num_to_s3block_path(4, 0),
+            &blockchain::ArchiveBlocks::from(&blocks_data[..]),
+        );
+
+        let url = format!("file://{}/", dir.path().display());
+        let mut fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+        fetcher.set_merged_blocks_bucket_sizes(&[4]);
+
+        for block_data in &blocks_data {
+            assert_eq!(
+                &fetcher.get_block_data(&[], block_data.block()).unwrap(),
+                block_data
+            );
+        }
+    }
+
+    #[test_with_logger]
+    fn fetches_blocks_from_s3_compatible_endpoint(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(6);
+
+        // Blocks 0..4 are available as a merged block, the rest individually.
+        write_object(
+            &dir.path().join("some/prefix"),
+            merged_block_num_to_s3block_path(4, 0),
+            &blockchain::ArchiveBlocks::from(&blocks_data[..4]),
+        );
+        write_blocks(&dir.path().join("some/prefix"), &blocks_data[4..]);
+
+        let addr = serve_bucket(dir.path(), "blocks", "404 Not Found");
+
+        // The local endpoint does not check credentials, but the S3 client refuses to
+        // send unsigned requests.
+        std::env::set_var("AWS_ACCESS_KEY_ID", "minioadmin");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "minioadmin");
+
+        let url = format!(
+            "s3://blocks/some/prefix?region=us-east-1&endpoint=http://{}",
+            addr
+        );
+        let mut fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+        fetcher.set_merged_blocks_bucket_sizes(&[4]);
+
+        for block_data in &blocks_data {
+            assert_eq!(
+                &fetcher.get_block_data(&[], block_data.block()).unwrap(),
+                block_data
+            );
+        }
+
+        assert!(matches!(
+            fetcher.get_block_data_by_index(6, None),
+            Err(ObjectStoreTransactionsFetcherError::NotFound(_))
+        ));
+    }
+
+    #[test_with_logger]
+    fn fetches_blocks_over_http(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(3);
+        write_blocks(&dir.path().join("node1"), &blocks_data);
+
+        let addr = serve_bucket(dir.path(), "blocks", "404 Not Found");
+
+        let url = format!("http://{}/blocks/node1", addr);
+        let fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+        assert_eq!(
+            fetcher.source_urls,
+            vec![Url::parse(&format!("http://{}/blocks/node1/", addr)).unwrap()]
+        );
+
+        assert_eq!(
+            fetcher.get_origin_block_and_transactions().unwrap(),
+            blocks_data[0]
+        );
+        for block_data in &blocks_data[1..] {
+            assert_eq!(
+                &fetcher.get_block_data(&[], block_data.block()).unwrap(),
+                block_data
+            );
+        }
+
+        assert!(matches!(
+            fetcher.get_block_data_by_index(3, None),
+            Err(ObjectStoreTransactionsFetcherError::NotFound(_))
+        ));
+    }
+
+    #[test_with_logger]
+    fn access_denied_is_only_ignored_for_merged_blocks(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(4);
+        write_blocks(&dir.path().join("node1"), &blocks_data);
+
+        let addr = serve_bucket(dir.path(), "blocks", "403 Forbidden");
+
+        let url = format!("http://{}/blocks/node1", addr);
+        let mut fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+        fetcher.set_merged_blocks_bucket_sizes(&[4, 2]);
+
+        // Merged blocks are denied, so blocks are fetched individually.
+        for block_data in &blocks_data {
+            assert_eq!(
+                &fetcher.get_block_data(&[], block_data.block()).unwrap(),
+                block_data
+            );
+        }
+
+        // A single block being denied is an error, not a missing block.
+        assert!(matches!(
+            fetcher.get_block_data_by_index(4, None),
+            Err(ObjectStoreTransactionsFetcherError::Forbidden(_))
+        ));
+    }
+
+    #[test_with_logger]
+    fn rejects_tampered_blocks(logger: Logger) {
+        let dir = TempDir::new("object_store_fetcher").unwrap();
+        let blocks_data = blocks_data(3);
+        write_blocks(dir.path(), &blocks_data);
+
+        // Replace block #1 with a copy that has the contents of block #2, which no
+        // longer match the block's contents hash.
+        let tampered = BlockData::new(
+            blocks_data[1].block().clone(),
+            blocks_data[2].contents().clone(),
+            None,
+            None,
+        );
+        write_object(
+            dir.path(),
+            block_num_to_s3block_path(1),
+            &blockchain::ArchiveBlock::from(&tampered),
+        );
+
+        // Serve block #2 under the path of block #0.
+        write_object(
+            dir.path(),
+            block_num_to_s3block_path(0),
+            &blockchain::ArchiveBlock::from(&blocks_data[2]),
+        );
+
+        let url = format!("file://{}", dir.path().display());
+        let fetcher = ObjectStoreTransactionsFetcher::new(vec![url], logger).unwrap();
+
+        for block_index in 0..2 {
+            assert!(matches!(
+                fetcher.get_block_data(&[], blocks_data[block_index].block()),
+                Err(ObjectStoreTransactionsFetcherError::InvalidBlockReceived(
+                    _,
+                    _
+                ))
+            ));
+        }
+        assert_eq!(
+            fetcher.get_block_data(&[], blocks_data[2].block()).unwrap(),
+            blocks_data[2]
+        );
+    }
+
+    #[test_with_logger]
+    fn parses_object_store_urls(logger: Logger) {
+        let url = Url::parse("file:///tmp/blocks/").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url).unwrap(),
+            ObjectStore::Local { path } if path == Path::new("/tmp/blocks")
+        ));
+
+        // Percent-escapes are decoded.
+        let url = Url::parse("file:///tmp/my%20blocks/").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url).unwrap(),
+            ObjectStore::Local { path } if path == Path::new("/tmp/my blocks")
+        ));
+
+        // Remote hosts are not local paths.
+        let url = Url::parse("file://example.com/tmp/blocks/").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url),
+            Err(ObjectStoreTransactionsFetcherError::MissingPath(_))
+        ));
+
+        let url =
+            Url::parse("s3://bucket/some/prefix/?region=us-west-2&endpoint=http://localhost:9000")
+                .unwrap();
+        match ObjectStore::from_url(&url).unwrap() {
+            ObjectStore::S3(store) => {
+                assert_eq!(store.bucket, "bucket");
+                assert_eq!(store.prefix, "some/prefix");
+            }
+            _ => panic!("expected an S3 store"),
+        }
+
+        let url = Url::parse("s3://bucket?region=not-a-region").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url),
+            Err(ObjectStoreTransactionsFetcherError::InvalidS3Region(_, _))
+        ));
+
+        let url = Url::parse("https://example.com/blocks").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url).unwrap(),
+            ObjectStore::Http { base_url, .. } if base_url.as_str() == "https://example.com/blocks/"
+        ));
+
+        let url = Url::parse("ftp://example.com/blocks/").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url),
+            Err(ObjectStoreTransactionsFetcherError::UnsupportedScheme(_))
+        ));
+
+        let url = Url::parse("s3://").unwrap();
+        assert!(matches!(
+            ObjectStore::from_url(&url),
+            Err(ObjectStoreTransactionsFetcherError::MissingPath(_))
+        ));
+
+        // Query parameters are kept when normalizing source URLs.
+        let fetcher = ObjectStoreTransactionsFetcher::new(
+            vec!["s3://bucket/prefix?region=us-west-2".to_string()],
+            logger.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            fetcher.source_urls[0].as_str(),
+            "s3://bucket/prefix/?region=us-west-2"
+        );
+
+        // A fetcher without sources (e.g. an offline node) can be created, but
+        // cannot fetch anything.
+        let fetcher = ObjectStoreTransactionsFetcher::new(vec![], logger).unwrap();
+        assert!(matches!(
+            fetcher.get_origin_block_and_transactions(),
+            Err(ObjectStoreTransactionsFetcherError::NoSourcesConfigured)
+        ));
+    }
+}
@@ -147,7 +147,7 @@ fn main() {
     let transactions_fetcher =
         mc_ledger_sync::ConnectionManagerTransactionsFetcher::new(conn_manager.clone(), logger.clone());
     */
-    let transactions_fetcher = mc_ledger_sync::ReqwestTransactionsFetcher::new(
+    let transactions_fetcher = mc_ledger_sync::ObjectStoreTransactionsFetcher::new(
         vec![
             String::from(
                 "https://s3-us-west-1.amazonaws.com/mobilecoin.chain/node2.test.mobilecoin.com/",
@@ -161,7 +161,7 @@ fn main() {
         ],
         logger.clone(),
     )
-    .expect("failed creating ReqwestTransactionsFetcher");
+    .expect("failed creating ObjectStoreTransactionsFetcher");
 
     let mut sync_service = LedgerSyncService::new(
         ledger.clone(),
@@ -0,0 +1,5 @@
+ALTER TABLE counters DROP COLUMN num_unexpected_errors_matching_withdrawals_to_burns;
+ALTER TABLE counters DROP COLUMN num_unexpected_errors_matching_burns_to_withdrawals;
+ALTER TABLE counters DROP COLUMN num_mismatching_burns_and_withdrawals;
+DROP TABLE audited_burns;
+DROP TABLE burn_tx_outs;
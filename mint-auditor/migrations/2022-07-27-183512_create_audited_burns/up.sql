@@ -0,0 +1,36 @@
+CREATE TABLE burn_tx_outs (
+    id INTEGER PRIMARY KEY,
+    -- The block index at which this burn tx out appeared.
+    block_index BIGINT NOT NULL,
+    -- The token id being burned.
+    token_id BIGINT NOT NULL,
+    -- The amount that was burned.
+    amount BIGINT NOT NULL,
+    -- The TxOut public key, as hex-encoded bytes.
+    public_key_hex VARCHAR(64) NOT NULL UNIQUE,
+    -- The memo data of the BurnRedemptionMemo, as hex-encoded bytes (if the TxOut has one).
+    burn_redemption_memo_hex VARCHAR(128),
+    -- The protobuf-serialized TxOut.
+    protobuf BLOB NOT NULL
+);
+CREATE INDEX idx__burn_tx_outs__block_index ON burn_tx_outs(block_index);
+
+CREATE TABLE audited_burns (
+    id INTEGER PRIMARY KEY,
+    burn_tx_out_id INTEGER NOT NULL,
+    gnosis_safe_withdrawal_id INTEGER NOT NULL,
+    -- Constraints
+    FOREIGN KEY (burn_tx_out_id) REFERENCES burn_tx_outs(id),
+    FOREIGN KEY (gnosis_safe_withdrawal_id) REFERENCES gnosis_safe_withdrawals(id)
+);
+CREATE INDEX idx__audited_burns__burn_tx_out_id ON audited_burns(burn_tx_out_id);
+CREATE INDEX idx__audited_burns__gnosis_safe_withdrawal_id ON audited_burns(gnosis_safe_withdrawal_id);
+
+-- Number of mismatched burns and Gnosis withdrawals.
+ALTER TABLE counters ADD COLUMN num_mismatching_burns_and_withdrawals BIGINT NOT NULL DEFAULT 0;
+
+-- Number of unexpected errors attempting to match burns to withdrawals.
+ALTER TABLE counters ADD COLUMN num_unexpected_errors_matching_burns_to_withdrawals BIGINT NOT NULL DEFAULT 0;
+
+-- Number of unexpected errors attempting to match withdrawals to burns.
+ALTER TABLE counters ADD COLUMN num_unexpected_errors_matching_withdrawals_to_burns BIGINT NOT NULL DEFAULT 0;
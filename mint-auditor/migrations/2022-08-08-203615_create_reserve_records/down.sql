@@ -0,0 +1,70 @@
+DROP TABLE reserve_sync_cursors;
+
+CREATE TABLE gnosis_safe_txs (
+    eth_tx_hash VARCHAR(66) NOT NULL UNIQUE PRIMARY KEY,
+    raw_tx_json TEXT NOT NULL
+);
+INSERT INTO gnosis_safe_txs (eth_tx_hash, raw_tx_json)
+    SELECT tx_id, raw_tx_json FROM reserve_txs GROUP BY tx_id;
+
+CREATE TABLE gnosis_safe_deposits (
+    id INTEGER PRIMARY KEY,
+    eth_tx_hash VARCHAR(66) NOT NULL UNIQUE,
+    eth_block_number BIGINT NOT NULL,
+    safe_addr VARCHAR(42) NOT NULL,
+    token_addr VARCHAR(42) NOT NULL,
+    amount BIGINT NOT NULL,
+    expected_mc_mint_tx_nonce_hex VARCHAR(128) NOT NULL,
+    FOREIGN KEY (eth_tx_hash) REFERENCES gnosis_safe_txs(eth_tx_hash)
+);
+INSERT INTO gnosis_safe_deposits (id, eth_tx_hash, eth_block_number, safe_addr, token_addr, amount, expected_mc_mint_tx_nonce_hex)
+    SELECT id, tx_id, position, reserve_id, asset, amount, expected_mc_mint_tx_nonce_hex FROM reserve_deposits;
+CREATE INDEX idx__gnosis_safe_deposits__eth_block_number ON gnosis_safe_deposits(eth_block_number);
+CREATE INDEX idx__gnosis_safe_deposits__expected_mc_mint_tx_nonce_hex ON gnosis_safe_deposits(expected_mc_mint_tx_nonce_hex);
+
+CREATE TABLE gnosis_safe_withdrawals (
+    id INTEGER PRIMARY KEY,
+    eth_tx_hash VARCHAR(66) NOT NULL UNIQUE,
+    eth_block_number BIGINT NOT NULL,
+    safe_addr VARCHAR(42) NOT NULL,
+    token_addr VARCHAR(42) NOT NULL,
+    amount BIGINT NOT NULL,
+    mc_tx_out_public_key_hex VARCHAR(64) NOT NULL,
+    FOREIGN KEY (eth_tx_hash) REFERENCES gnosis_safe_txs(eth_tx_hash)
+);
+INSERT INTO gnosis_safe_withdrawals (id, eth_tx_hash, eth_block_number, safe_addr, token_addr, amount, mc_tx_out_public_key_hex)
+    SELECT id, tx_id, position, reserve_id, asset, amount, mc_tx_out_public_key_hex FROM reserve_withdrawals;
+CREATE INDEX idx__gnosis_safe_withdrawals__eth_block_number ON gnosis_safe_withdrawals(eth_block_number);
+CREATE INDEX idx__gnosis_safe_withdrawals__mc_tx_out_public_key_hex ON gnosis_safe_withdrawals(mc_tx_out_public_key_hex);
+
+CREATE TABLE new_audited_mints (
+    id INTEGER PRIMARY KEY,
+    mint_tx_id INTEGER NOT NULL,
+    gnosis_safe_deposit_id INTEGER NOT NULL,
+    FOREIGN KEY (mint_tx_id) REFERENCES mint_txs(id),
+    FOREIGN KEY (gnosis_safe_deposit_id) REFERENCES gnosis_safe_deposits(id)
+);
+INSERT INTO new_audited_mints (id, mint_tx_id, gnosis_safe_deposit_id)
+    SELECT id, mint_tx_id, reserve_deposit_id FROM audited_mints;
+DROP TABLE audited_mints;
+ALTER TABLE new_audited_mints RENAME TO audited_mints;
+CREATE INDEX idx__audited_mints__mint_tx_id ON audited_mints(mint_tx_id);
+CREATE INDEX idx__audited_mints__gnosis_safe_deposit_id ON audited_mints(gnosis_safe_deposit_id);
+
+CREATE TABLE new_audited_burns (
+    id INTEGER PRIMARY KEY,
+    burn_tx_out_id INTEGER NOT NULL,
+    gnosis_safe_withdrawal_id INTEGER NOT NULL,
+    FOREIGN KEY (burn_tx_out_id) REFERENCES burn_tx_outs(id),
+    FOREIGN KEY (gnosis_safe_withdrawal_id) REFERENCES gnosis_safe_withdrawals(id)
+);
+INSERT INTO new_audited_burns (id, burn_tx_out_id, gnosis_safe_withdrawal_id)
+    SELECT id, burn_tx_out_id, reserve_withdrawal_id FROM audited_burns;
+DROP TABLE audited_burns;
+ALTER TABLE new_audited_burns RENAME TO audited_burns;
+CREATE INDEX idx__audited_burns__burn_tx_out_id ON audited_burns(burn_tx_out_id);
+CREATE INDEX idx__audited_burns__gnosis_safe_withdrawal_id ON audited_burns(gnosis_safe_withdrawal_id);
+
+DROP TABLE reserve_withdrawals;
+DROP TABLE reserve_deposits;
+DROP TABLE reserve_txs;
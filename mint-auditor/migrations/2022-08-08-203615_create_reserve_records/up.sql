@@ -0,0 +1,103 @@
+-- Reserve records are no longer specific to Gnosis safes. A reserve is identified by a source-neutral `reserve_id`
+-- (the lowercase address of a Gnosis safe or Ethereum wallet, or the account id of an imported file), and each
+-- deposit/withdrawal records the source transaction id, its position in the reserve's history (e.g. an Ethereum
+-- block number) and the asset that was moved (e.g. the lowercase address of an ERC-20 token contract).
+
+-- Processed reserve transactions
+CREATE TABLE reserve_txs (
+    reserve_id VARCHAR(128) NOT NULL,
+    tx_id VARCHAR(128) NOT NULL,
+    raw_tx_json TEXT NOT NULL,
+    -- Constraints
+    PRIMARY KEY (reserve_id, tx_id)
+);
+
+-- Transactions that did not contain any deposits or withdrawals are not carried over, since we do not know which
+-- reserve they belong to. They will be fetched again and found to be empty again.
+INSERT INTO reserve_txs (reserve_id, tx_id, raw_tx_json)
+    SELECT DISTINCT events.reserve_id, gnosis_safe_txs.eth_tx_hash, gnosis_safe_txs.raw_tx_json
+    FROM gnosis_safe_txs
+    INNER JOIN (
+        SELECT eth_tx_hash, lower(safe_addr) AS reserve_id FROM gnosis_safe_deposits
+        UNION
+        SELECT eth_tx_hash, lower(safe_addr) AS reserve_id FROM gnosis_safe_withdrawals
+    ) AS events ON events.eth_tx_hash = gnosis_safe_txs.eth_tx_hash;
+
+-- Deposits into a reserve.
+CREATE TABLE reserve_deposits (
+    id INTEGER PRIMARY KEY,
+    reserve_id VARCHAR(128) NOT NULL,
+    tx_id VARCHAR(128) NOT NULL,
+    position BIGINT NOT NULL,
+    asset VARCHAR(128) NOT NULL,
+    amount BIGINT NOT NULL,
+    -- This is the expected nonce of the matching MintTx we want to see on the MobileCoin blockchain.
+    expected_mc_mint_tx_nonce_hex VARCHAR(128) NOT NULL,
+    -- Constraints
+    UNIQUE (reserve_id, tx_id),
+    FOREIGN KEY (reserve_id, tx_id) REFERENCES reserve_txs(reserve_id, tx_id)
+);
+INSERT INTO reserve_deposits (id, reserve_id, tx_id, position, asset, amount, expected_mc_mint_tx_nonce_hex)
+    SELECT id, lower(safe_addr), eth_tx_hash, eth_block_number, lower(token_addr), amount, expected_mc_mint_tx_nonce_hex
+    FROM gnosis_safe_deposits;
+CREATE INDEX idx__reserve_deposits__expected_mc_mint_tx_nonce_hex ON reserve_deposits(expected_mc_mint_tx_nonce_hex);
+
+-- Withdrawals from a reserve.
+CREATE TABLE reserve_withdrawals (
+    id INTEGER PRIMARY KEY,
+    reserve_id VARCHAR(128) NOT NULL,
+    tx_id VARCHAR(128) NOT NULL,
+    position BIGINT NOT NULL,
+    asset VARCHAR(128) NOT NULL,
+    amount BIGINT NOT NULL,
+    mc_tx_out_public_key_hex VARCHAR(64) NOT NULL,
+    -- Constraints
+    UNIQUE (reserve_id, tx_id),
+    FOREIGN KEY (reserve_id, tx_id) REFERENCES reserve_txs(reserve_id, tx_id)
+);
+INSERT INTO reserve_withdrawals (id, reserve_id, tx_id, position, asset, amount, mc_tx_out_public_key_hex)
+    SELECT id, lower(safe_addr), eth_tx_hash, eth_block_number, lower(token_addr), amount, mc_tx_out_public_key_hex
+    FROM gnosis_safe_withdrawals;
+CREATE INDEX idx__reserve_withdrawals__mc_tx_out_public_key_hex ON reserve_withdrawals(mc_tx_out_public_key_hex);
+
+-- Point the audit tables at the new reserve tables.
+CREATE TABLE new_audited_mints (
+    id INTEGER PRIMARY KEY,
+    mint_tx_id INTEGER NOT NULL,
+    reserve_deposit_id INTEGER NOT NULL,
+    -- Constraints
+    FOREIGN KEY (mint_tx_id) REFERENCES mint_txs(id),
+    FOREIGN KEY (reserve_deposit_id) REFERENCES reserve_deposits(id)
+);
+INSERT INTO new_audited_mints (id, mint_tx_id, reserve_deposit_id)
+    SELECT id, mint_tx_id, gnosis_safe_deposit_id FROM audited_mints;
+DROP TABLE audited_mints;
+ALTER TABLE new_audited_mints RENAME TO audited_mints;
+CREATE INDEX idx__audited_mints__mint_tx_id ON audited_mints(mint_tx_id);
+CREATE INDEX idx__audited_mints__reserve_deposit_id ON audited_mints(reserve_deposit_id);
+
+CREATE TABLE new_audited_burns (
+    id INTEGER PRIMARY KEY,
+    burn_tx_out_id INTEGER NOT NULL,
+    reserve_withdrawal_id INTEGER NOT NULL,
+    -- Constraints
+    FOREIGN KEY (burn_tx_out_id) REFERENCES burn_tx_outs(id),
+    FOREIGN KEY (reserve_withdrawal_id) REFERENCES reserve_withdrawals(id)
+);
+INSERT INTO new_audited_burns (id, burn_tx_out_id, reserve_withdrawal_id)
+    SELECT id, burn_tx_out_id, gnosis_safe_withdrawal_id FROM audited_burns;
+DROP TABLE audited_burns;
+ALTER TABLE new_audited_burns RENAME TO audited_burns;
+CREATE INDEX idx__audited_burns__burn_tx_out_id ON audited_burns(burn_tx_out_id);
+CREATE INDEX idx__audited_burns__reserve_withdrawal_id ON audited_burns(reserve_withdrawal_id);
+
+DROP TABLE gnosis_safe_withdrawals;
+DROP TABLE gnosis_safe_deposits;
+DROP TABLE gnosis_safe_txs;
+
+-- Where each reserve source should resume fetching from. The meaning of the cursor is specific to the source (e.g. an
+-- Ethereum block number, or a number of records already imported from a file).
+CREATE TABLE reserve_sync_cursors (
+    reserve_id VARCHAR(128) NOT NULL PRIMARY KEY,
+    cursor BIGINT NOT NULL
+);
@@ -0,0 +1,19 @@
+-- For each audited block, the reserve deposits and withdrawals the auditor had synced by the
+-- time the block was audited. Proof-of-reserves reports for a block only consider reserve
+-- records up to these ids, so that regenerating a report yields the same result.
+CREATE TABLE block_reserve_cutoffs (
+    block_index BIGINT NOT NULL PRIMARY KEY,
+    -- The highest reserve_deposits id that existed when the block was audited (0 if none).
+    last_reserve_deposit_id INTEGER NOT NULL,
+    -- The highest reserve_withdrawals id that existed when the block was audited (0 if none).
+    last_reserve_withdrawal_id INTEGER NOT NULL,
+    FOREIGN KEY (block_index) REFERENCES block_audit_data(block_index)
+);
+
+-- Blocks audited before this migration can only be attributed what is synced now.
+INSERT INTO block_reserve_cutoffs (block_index, last_reserve_deposit_id, last_reserve_withdrawal_id)
+    SELECT
+        block_index,
+        (SELECT COALESCE(MAX(id), 0) FROM reserve_deposits),
+        (SELECT COALESCE(MAX(id), 0) FROM reserve_withdrawals)
+    FROM block_audit_data;
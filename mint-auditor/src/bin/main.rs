@@ -5,12 +5,16 @@
 use clap::{Parser, Subcommand};
 use grpcio::{EnvBuilder, ServerBuilder};
 use mc_common::logger::{log, o, Logger};
+use mc_crypto_keys::{DistinguishedEncoding, Ed25519Pair, Ed25519Private, Ed25519Public};
 use mc_ledger_db::{Ledger, LedgerDB};
 use mc_mint_auditor::{
     db::{
-        transaction, AuditedMint, BlockAuditData, BlockBalance, Conn, MintAuditorDb, SyncBlockData,
+        transaction, AuditedBurn, AuditedMint, BlockAuditData, BlockBalance, Conn, MintAuditorDb,
+        SyncBlockData,
     },
-    gnosis::{GnosisSafeConfig, GnosisSyncThread},
+    gnosis::GnosisSafeConfig,
+    report::{ProofOfReservesReport, RecordInclusionProof, SignedReport},
+    reserve::{ReserveConfig, ReserveSyncThread},
     Error, MintAuditorService,
 };
 use mc_mint_auditor_api::MintAuditorUri;
@@ -18,7 +22,14 @@ use mc_util_grpc::{AdminServer, BuildInfoService, ConnectionUriGrpcioServer, Hea
 use mc_util_parse::parse_duration_in_seconds;
 use mc_util_uri::AdminUri;
 use serde_json::json;
-use std::{cmp::Ordering, path::PathBuf, sync::Arc, thread::sleep, time::Duration};
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::sleep,
+    time::Duration,
+};
 
 /// Maximum number of concurrent connections in the database pool.
 const DB_POOL_SIZE: u32 = 10;
@@ -52,8 +63,16 @@ pub enum Command {
 
         /// Gnosis safe configuration file (json/toml).
         /// When provided, the configured gnosis safe(s) will be audited.
+        /// Superseded by --reserve-config, which can also describe Gnosis
+        /// safes.
         #[clap(long, env = "MC_GNOSIS_SAFE_CONFIG", parse(try_from_str = parse_gnosis_safe_config))]
         gnosis_safe_config: Option<GnosisSafeConfig>,
+
+        /// Reserve configuration file (json/toml).
+        /// When provided, the configured reserve(s) will be audited, in
+        /// addition to any safe in --gnosis-safe-config.
+        #[clap(long, env = "MC_RESERVE_CONFIG", parse(try_from_str = parse_reserve_config))]
+        reserve_config: Option<ReserveConfig>,
     },
 
     /// Get the audit data for a specific block, optionally in JSON format
@@ -71,6 +90,51 @@ pub enum Command {
         #[clap(long, env = "MC_JSON")]
         json: bool,
     },
+
+    /// Generate a signed proof-of-reserves report (JSON) for a specific block.
+    GenerateReport {
+        /// Path to ledger db, used to look up the block hash.
+        #[clap(long, parse(from_os_str), env = "MC_LEDGER_DB")]
+        ledger_db: PathBuf,
+
+        /// Path to mint auditor db.
+        #[clap(long, parse(from_os_str), env = "MC_MINT_AUDITOR_DB")]
+        mint_auditor_db: PathBuf,
+
+        /// Block index (optional, defaults to last synced block).
+        #[clap(long = "block", env = "MC_BLOCK_INDEX")]
+        block_index: Option<u64>,
+
+        /// Ed25519 private key used to sign the report (PEM).
+        #[clap(long, parse(from_os_str), env = "MC_SIGNING_KEY")]
+        signing_key: PathBuf,
+
+        /// File to write the signed report to (optional, defaults to stdout).
+        #[clap(long, parse(from_os_str), env = "MC_OUTPUT")]
+        output: Option<PathBuf>,
+
+        /// File to write the inclusion proofs of all records in the report to
+        /// (optional).
+        #[clap(long, parse(from_os_str), env = "MC_PROOFS_OUTPUT")]
+        proofs_output: Option<PathBuf>,
+    },
+
+    /// Verify the signature of a report generated by `generate-report`, and
+    /// optionally that records are included in it.
+    VerifyReport {
+        /// The signed report (JSON).
+        #[clap(long, parse(from_os_str), env = "MC_REPORT")]
+        report: PathBuf,
+
+        /// Ed25519 public key of the expected signer (PEM).
+        #[clap(long, parse(from_os_str), env = "MC_SIGNER_PUBLIC_KEY")]
+        signer_public_key: PathBuf,
+
+        /// Inclusion proofs (JSON) to verify against the report (optional).
+        /// Either a single proof or a list of them.
+        #[clap(long, parse(from_os_str), env = "MC_PROOFS")]
+        proofs: Option<PathBuf>,
+    },
 }
 
 /// Configuration for the mint auditor.
@@ -98,6 +162,7 @@ fn main() {
             listen_uri,
             admin_listen_uri,
             gnosis_safe_config,
+            reserve_config,
         } => {
             cmd_scan_ledger(
                 ledger_db,
@@ -105,7 +170,7 @@ fn main() {
                 poll_interval,
                 listen_uri,
                 admin_listen_uri,
-                gnosis_safe_config,
+                merge_reserve_configs(gnosis_safe_config, reserve_config),
                 logger,
             );
         }
@@ -117,6 +182,33 @@ fn main() {
         } => {
             cmd_get_block_audit_data(mint_auditor_db, block_index, json, logger);
         }
+
+        Command::GenerateReport {
+            ledger_db,
+            mint_auditor_db,
+            block_index,
+            signing_key,
+            output,
+            proofs_output,
+        } => {
+            cmd_generate_report(
+                ledger_db,
+                mint_auditor_db,
+                block_index,
+                signing_key,
+                output,
+                proofs_output,
+                logger,
+            );
+        }
+
+        Command::VerifyReport {
+            report,
+            signer_public_key,
+            proofs,
+        } => {
+            cmd_verify_report(report, signer_public_key, proofs);
+        }
     }
 }
 
@@ -127,7 +219,7 @@ fn cmd_scan_ledger(
     poll_interval: Duration,
     listen_uri: Option<MintAuditorUri>,
     admin_listen_uri: Option<AdminUri>,
-    gnosis_safe_config: Option<GnosisSafeConfig>,
+    reserve_config: Option<ReserveConfig>,
     logger: Logger,
 ) {
     let ledger_db = LedgerDB::open(&ledger_db_path).expect("Could not open ledger DB");
@@ -179,18 +271,18 @@ fn cmd_scan_ledger(
         .expect("Failed starting admin grpc server")
     });
 
-    let _gnosis_safe_fetcher_threads = gnosis_safe_config.as_ref().map(|gnosis_safe_config| {
-        gnosis_safe_config
-            .safes
+    let _reserve_sync_threads = reserve_config.as_ref().map(|reserve_config| {
+        reserve_config
+            .reserves
             .iter()
-            .map(|safe_config| {
-                GnosisSyncThread::start(
-                    safe_config,
+            .map(|reserve| {
+                ReserveSyncThread::start(
+                    reserve,
                     mint_auditor_db.clone(),
                     poll_interval,
                     logger.clone(),
                 )
-                .expect("Failed starting gnosis safe fetcher thread")
+                .expect("Failed starting reserve sync thread")
             })
             .collect::<Vec<_>>()
     });
@@ -198,7 +290,7 @@ fn cmd_scan_ledger(
     loop {
         sync_loop(
             &mint_auditor_db,
-            gnosis_safe_config.as_ref(),
+            reserve_config.as_ref(),
             &ledger_db,
             &logger,
         )
@@ -256,11 +348,104 @@ fn cmd_get_block_audit_data(
     .expect("db transaction failed");
 }
 
+/// Implementation of the GenerateReport CLI command.
+fn cmd_generate_report(
+    ledger_db_path: PathBuf,
+    mint_auditor_db_path: PathBuf,
+    block_index: Option<u64>,
+    signing_key_path: PathBuf,
+    output: Option<PathBuf>,
+    proofs_output: Option<PathBuf>,
+    logger: Logger,
+) {
+    let signer = Ed25519Pair::from(
+        load_pem(&signing_key_path)
+            .and_then(|der| Ok(Ed25519Private::try_from_der(&der)?))
+            .expect("Could not load signing key"),
+    );
+
+    let ledger_db = LedgerDB::open(&ledger_db_path).expect("Could not open ledger DB");
+    let mint_auditor_db = MintAuditorDb::new_from_path(
+        &mint_auditor_db_path.into_os_string().into_string().unwrap(),
+        DB_POOL_SIZE,
+        logger.clone(),
+    )
+    .expect("Could not open mint auditor DB");
+
+    let conn = mint_auditor_db
+        .get_conn()
+        .expect("Could not get db connection");
+
+    let (report, proofs) = transaction(&conn, |conn| {
+        let last_synced_block_index = BlockAuditData::last_synced_block_index(conn)?;
+        let block_index = block_index
+            .or(last_synced_block_index)
+            .ok_or_else(|| Error::Other("Failed figuring out the last block index".into()))?;
+
+        // Only blocks the auditor has processed can be reported on.
+        BlockAuditData::get(conn, block_index)?;
+        let block = ledger_db.get_block(block_index)?;
+
+        ProofOfReservesReport::generate(block_index, &block.id, conn)
+    })
+    .expect("Failed generating report");
+
+    log::info!(
+        logger,
+        "Generated report for block {} with {} records",
+        report.block_index,
+        report.num_records
+    );
+
+    let signed_report = report.sign(&signer).expect("Failed signing report");
+    write_json(&signed_report, output.as_deref()).expect("Failed writing report");
+
+    if let Some(proofs_output) = proofs_output {
+        write_json(&proofs, Some(&proofs_output)).expect("Failed writing inclusion proofs");
+    }
+}
+
+/// Implementation of the VerifyReport CLI command.
+fn cmd_verify_report(
+    report_path: PathBuf,
+    signer_public_key_path: PathBuf,
+    proofs_path: Option<PathBuf>,
+) {
+    let signer = load_pem(&signer_public_key_path)
+        .and_then(|der| Ok(Ed25519Public::try_from_der(&der)?))
+        .expect("Could not load signer public key");
+
+    let signed_report: SignedReport = read_json(&report_path).expect("Could not load report");
+    signed_report
+        .verify(&signer)
+        .expect("Report signature verification failed");
+    println!(
+        "Report for block {} (block id {}) is signed by {}",
+        signed_report.report.block_index,
+        signed_report.report.block_id_hex,
+        signed_report.signer_public_key_hex
+    );
+
+    if let Some(proofs_path) = proofs_path {
+        // Accept either a single proof (e.g. handed to a user to prove their
+        // record is included) or the list written by generate-report.
+        let proofs = read_json::<Vec<RecordInclusionProof>>(&proofs_path)
+            .or_else(|_| read_json::<RecordInclusionProof>(&proofs_path).map(|proof| vec![proof]))
+            .expect("Could not load inclusion proofs");
+        for proof in &proofs {
+            proof
+                .verify(&signed_report.report)
+                .expect("Inclusion proof verification failed");
+        }
+        println!("{} record(s) are included in the report", proofs.len());
+    }
+}
+
 /// Synchronizes the mint auditor database with the ledger database.
 /// Will run until all blocks in the ledger database have been synced.
 fn sync_loop(
     mint_auditor_db: &MintAuditorDb,
-    gnosis_safe_config: Option<&GnosisSafeConfig>,
+    reserve_config: Option<&ReserveConfig>,
     ledger_db: &LedgerDB,
     logger: &Logger,
 ) -> Result<(), Error> {
@@ -296,9 +481,9 @@ fn sync_loop(
                         block_data.contents(),
                     )?;
 
-                    // If we were configured to audit Gnosis safes, attempt to do that with
+                    // If we were configured to audit reserves, attempt to do that with
                     // information we found in the block.
-                    if let Some(config) = gnosis_safe_config {
+                    if let Some(config) = reserve_config {
                         audit_block_data(&sync_block_data, config, &conn, logger)?;
                     }
 
@@ -311,10 +496,10 @@ fn sync_loop(
     Ok(())
 }
 
-/// Perform gnosis auditing of any data found in the block.
+/// Perform reserve auditing of any data found in the block.
 fn audit_block_data(
     sync_block_data: &SyncBlockData,
-    config: &GnosisSafeConfig,
+    config: &ReserveConfig,
     conn: &Conn,
     logger: &Logger,
 ) -> Result<(), Error> {
@@ -323,18 +508,18 @@ fn audit_block_data(
             Ok(deposit) => {
                 log::info!(
                     logger,
-                    "MintTx nonce={} matched Gnosis deposit eth_tx_hash={}",
+                    "MintTx nonce={} matched reserve deposit tx_id={}",
                     mint_tx.nonce_hex(),
-                    deposit.eth_tx_hash(),
+                    deposit.tx_id(),
                 )
             }
             Err(Error::NotFound) => {
-                log::debug!(logger, "MintTx with nonce={} does not currently have matching Gnosis deposit, this could be fine if the safe data is not fully synced.", mint_tx.nonce_hex());
+                log::debug!(logger, "MintTx with nonce={} does not currently have matching reserve deposit, this could be fine if the reserve data is not fully synced.", mint_tx.nonce_hex());
             }
             Err(err) => {
                 log::error!(
                     logger,
-                    "MintTx nonce={} failed matching Gnosis deposit: {}",
+                    "MintTx nonce={} failed matching reserve deposit: {}",
                     mint_tx.nonce_hex(),
                     err
                 );
@@ -342,6 +527,66 @@ fn audit_block_data(
         };
     }
 
+    for burn_tx_out in &sync_block_data.burn_tx_outs {
+        match AuditedBurn::try_match_burn_with_withdrawal(burn_tx_out, config, conn) {
+            Ok(withdrawal) => {
+                log::info!(
+                    logger,
+                    "BurnTxOut public_key={} matched reserve withdrawal tx_id={}",
+                    burn_tx_out.public_key_hex(),
+                    withdrawal.tx_id(),
+                )
+            }
+            Err(Error::NotFound) => {
+                log::debug!(logger, "BurnTxOut with public_key={} does not currently have matching reserve withdrawal, this could be fine if the reserve data is not fully synced.", burn_tx_out.public_key_hex());
+            }
+            Err(err) => {
+                log::error!(
+                    logger,
+                    "BurnTxOut public_key={} failed matching reserve withdrawal: {}",
+                    burn_tx_out.public_key_hex(),
+                    err
+                );
+            }
+        };
+    }
+
+    AuditedBurn::update_unaudited_prometheus_gauges(conn)?;
+
+    Ok(())
+}
+
+/// Read a PEM file and return its DER contents.
+fn load_pem(path: &Path) -> Result<Vec<u8>, Error> {
+    let parsed_pem = pem::parse(fs::read(path)?).map_err(|err| {
+        Error::Other(format!(
+            "Failed parsing PEM file {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    Ok(parsed_pem.contents)
+}
+
+/// Read a JSON file.
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    serde_json::from_slice(&fs::read(path)?).map_err(|err| {
+        Error::Other(format!(
+            "Failed parsing JSON file {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// Write pretty-printed JSON to a file, or to stdout when no path is given.
+fn write_json<T: serde::Serialize>(value: &T, path: Option<&Path>) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|err| Error::Other(format!("failed serializing json: {}", err)))?;
+    match path {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{}", json),
+    }
     Ok(())
 }
 
@@ -349,3 +594,26 @@ fn audit_block_data(
 fn parse_gnosis_safe_config(path: &str) -> Result<GnosisSafeConfig, Error> {
     Ok(GnosisSafeConfig::load_from_path(path)?)
 }
+
+/// Load a reserve config file.
+fn parse_reserve_config(path: &str) -> Result<ReserveConfig, Error> {
+    Ok(ReserveConfig::load_from_path(path)?)
+}
+
+/// Combine the reserves of a reserve config file with the safes of a legacy
+/// gnosis safe config file.
+fn merge_reserve_configs(
+    gnosis_safe_config: Option<GnosisSafeConfig>,
+    reserve_config: Option<ReserveConfig>,
+) -> Option<ReserveConfig> {
+    match (gnosis_safe_config, reserve_config) {
+        (None, reserve_config) => reserve_config,
+        (Some(gnosis_safe_config), reserve_config) => {
+            let mut config = reserve_config.unwrap_or_default();
+            config
+                .reserves
+                .extend(ReserveConfig::from(gnosis_safe_config).reserves);
+            Some(config)
+        }
+    }
+}
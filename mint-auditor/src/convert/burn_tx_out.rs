@@ -0,0 +1,53 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from mc_mint_auditor_api::BurnTxOut.
+
+use crate::{db::BurnTxOut as DbBurnTxOut, Error};
+use mc_mint_auditor_api::BurnTxOut as ProtoBurnTxOut;
+
+/// Convert DbBurnTxOut --> ProtoBurnTxOut
+impl TryFrom<&DbBurnTxOut> for ProtoBurnTxOut {
+    type Error = Error;
+
+    fn try_from(src: &DbBurnTxOut) -> Result<Self, Error> {
+        let mut dst = Self::new();
+        dst.set_id(src.id().unwrap_or_default());
+        dst.set_block_index(src.block_index());
+        dst.set_token_id(*src.token_id());
+        dst.set_amount(src.amount());
+        dst.set_public_key((&src.public_key()?).into());
+        if let Some(memo_hex) = src.burn_redemption_memo_hex() {
+            dst.set_burn_redemption_memo(hex::decode(memo_hex)?);
+        }
+        dst.set_tx_out((&src.decode()?).into());
+        Ok(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::create_burn_tx_out;
+    use mc_transaction_core::TokenId;
+
+    #[test]
+    fn test_convert_burn_tx_out() {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let tx_out = create_burn_tx_out(TokenId::from(3), 123, Some([5; 64]), &mut rng);
+        let source = DbBurnTxOut::from_core_tx_out(10, &tx_out).unwrap();
+
+        let proto = ProtoBurnTxOut::try_from(&source).unwrap();
+        assert_eq!(proto.get_block_index(), 10);
+        assert_eq!(proto.get_token_id(), 3);
+        assert_eq!(proto.get_amount(), 123);
+        assert_eq!(
+            proto.get_public_key().get_data(),
+            tx_out.public_key.as_bytes()
+        );
+        assert_eq!(proto.get_burn_redemption_memo(), &[5; 64]);
+        assert_eq!(
+            mc_transaction_core::tx::TxOut::try_from(proto.get_tx_out()).unwrap(),
+            tx_out
+        );
+    }
+}
@@ -22,6 +22,13 @@ impl From<&Counters> for mc_mint_auditor_api::Counters {
         dst.set_num_unexpected_errors_matching_mints_to_deposits(
             src.num_unexpected_errors_matching_mints_to_deposits(),
         );
+        dst.set_num_mismatching_burns_and_withdrawals(src.num_mismatching_burns_and_withdrawals());
+        dst.set_num_unexpected_errors_matching_burns_to_withdrawals(
+            src.num_unexpected_errors_matching_burns_to_withdrawals(),
+        );
+        dst.set_num_unexpected_errors_matching_withdrawals_to_burns(
+            src.num_unexpected_errors_matching_withdrawals_to_burns(),
+        );
         dst
     }
 }
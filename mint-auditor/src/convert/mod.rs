@@ -2,7 +2,8 @@
 
 //! Convertion trait implementations betweens Prost and Protobuf.
 
+mod burn_tx_out;
 mod counters;
-mod gnosis_safe_deposit;
-mod gnosis_safe_tx;
-mod gnosis_safe_withdrawal;
+mod reserve_deposit;
+mod reserve_tx;
+mod reserve_withdrawal;
@@ -0,0 +1,89 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from mc_mint_auditor_api::ReserveDeposit.
+
+use crate::{db::ReserveDeposit as DbReserveDeposit, Error};
+use mc_mint_auditor_api::ReserveDeposit as ProtoReserveDeposit;
+use mc_transaction_core::mint::constants::NONCE_LENGTH;
+
+/// Convert DbReserveDeposit --> ProtoReserveDeposit
+impl From<&DbReserveDeposit> for ProtoReserveDeposit {
+    fn from(src: &DbReserveDeposit) -> Self {
+        let mut dst = Self::new();
+        dst.set_id(src.id().unwrap_or_default());
+        dst.set_tx_id(src.tx_id().to_string());
+        dst.set_position(src.position());
+        dst.set_reserve_id(src.reserve_id().to_string());
+        dst.set_asset(src.asset().to_string());
+        dst.set_amount(src.amount());
+        dst.set_expected_mc_mint_tx_nonce_hex(src.expected_mc_mint_tx_nonce_hex().to_string());
+        dst
+    }
+}
+
+/// Convert ProtoReserveDeposit --> DbReserveDeposit
+impl TryFrom<&ProtoReserveDeposit> for DbReserveDeposit {
+    type Error = Error;
+
+    fn try_from(src: &ProtoReserveDeposit) -> Result<Self, Self::Error> {
+        let nonce_bytes = hex::decode(src.get_expected_mc_mint_tx_nonce_hex())?;
+        let nonce = <[u8; NONCE_LENGTH]>::try_from(&nonce_bytes[..])
+            .map_err(|_| Error::InvalidLength(NONCE_LENGTH, nonce_bytes.len()))?;
+
+        Ok(Self::new(
+            match src.get_id() {
+                0 => None,
+                id => Some(id),
+            },
+            src.get_reserve_id().to_string(),
+            src.get_tx_id().to_string(),
+            src.get_position(),
+            src.get_asset().to_string(),
+            src.get_amount(),
+            nonce,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gnosis::EthTxHash, MintTxNonce};
+    use std::str::FromStr;
+
+    #[test]
+    // DbReserveDeposit --> ProtoReserveDeposit --> DbReserveDeposit should
+    // be the identity function.
+    fn test_convert_reserve_deposit() {
+        let tx_hash = EthTxHash::from_str(
+            "0x0e781edb7739aa88ad2ffb6a69aab46ff9e32dbd0f0c87e4006a176838b075d2",
+        )
+        .unwrap();
+        let source = DbReserveDeposit::new(
+            Some(10),
+            "0xb0dfaaa92e4f3667758f2a864d50f94e8ac7a56b".to_string(),
+            tx_hash.to_string(),
+            123456,
+            "0xb0dfaaa92e4f3667758f2a864d50f94e8ac7a56b".to_string(),
+            333,
+            MintTxNonce::EthereumGnosisDeposit(tx_hash).to_bytes(),
+        );
+
+        // Converting should be the identity function.
+        {
+            let external = ProtoReserveDeposit::from(&source);
+            let recovered = DbReserveDeposit::try_from(&external).unwrap();
+            assert_eq!(source, recovered);
+        }
+
+        // A truncated nonce is rejected.
+        {
+            let mut external = ProtoReserveDeposit::from(&source);
+            external.set_expected_mc_mint_tx_nonce_hex("0102".to_string());
+            assert!(matches!(
+                DbReserveDeposit::try_from(&external),
+                Err(Error::InvalidLength(NONCE_LENGTH, 2))
+            ));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from mc_mint_auditor_api::ReserveTx.
+
+use crate::db::ReserveTx as DbReserveTx;
+use mc_mint_auditor_api::ReserveTx as ProtoReserveTx;
+
+/// Convert DbReserveTx --> ProtoReserveTx
+impl From<&DbReserveTx> for ProtoReserveTx {
+    fn from(src: &DbReserveTx) -> Self {
+        let mut dst = Self::new();
+        dst.set_reserve_id(src.reserve_id.clone());
+        dst.set_tx_id(src.tx_id.clone());
+        dst.set_raw_tx_json(src.raw_tx_json.clone());
+        dst
+    }
+}
+
+/// Convert ProtoReserveTx --> DbReserveTx
+impl From<&ProtoReserveTx> for DbReserveTx {
+    fn from(src: &ProtoReserveTx) -> Self {
+        Self {
+            reserve_id: src.get_reserve_id().to_string(),
+            tx_id: src.get_tx_id().to_string(),
+            raw_tx_json: src.get_raw_tx_json().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // DbReserveTx --> ProtoReserveTx --> DbReserveTx should be the identity
+    // function.
+    fn test_convert_reserve_tx() {
+        let source = DbReserveTx {
+            reserve_id: "0xb0dfaaa92e4f3667758f2a864d50f94e8ac7a56b".to_string(),
+            tx_id: "0x0e781edb7739aa88ad2ffb6a69aab46ff9e32dbd0f0c87e4006a176838b075d2".to_string(),
+            raw_tx_json: "{\"test\": 10}".to_string(),
+        };
+
+        // Converting should be the identity function.
+        {
+            let external = ProtoReserveTx::from(&source);
+            let recovered = DbReserveTx::from(&external);
+            assert_eq!(source, recovered);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from mc_mint_auditor_api::ReserveWithdrawal.
+
+use crate::{db::ReserveWithdrawal as DbReserveWithdrawal, Error};
+use hex::ToHex;
+use mc_mint_auditor_api::ReserveWithdrawal as ProtoReserveWithdrawal;
+
+/// Convert DbReserveWithdrawal --> ProtoReserveWithdrawal
+impl TryFrom<&DbReserveWithdrawal> for ProtoReserveWithdrawal {
+    type Error = Error;
+
+    fn try_from(src: &DbReserveWithdrawal) -> Result<Self, Error> {
+        let mut dst = Self::new();
+        dst.set_id(src.id().unwrap_or_default());
+        dst.set_tx_id(src.tx_id().to_string());
+        dst.set_position(src.position());
+        dst.set_reserve_id(src.reserve_id().to_string());
+        dst.set_asset(src.asset().to_string());
+        dst.set_amount(src.amount());
+        dst.set_mc_tx_out_pub_key((&src.mc_tx_out_public_key()?).into());
+        Ok(dst)
+    }
+}
+
+/// Convert ProtoReserveWithdrawal --> DbReserveWithdrawal
+impl TryFrom<&ProtoReserveWithdrawal> for DbReserveWithdrawal {
+    type Error = Error;
+
+    fn try_from(src: &ProtoReserveWithdrawal) -> Result<Self, Error> {
+        Ok(Self::new(
+            if src.get_id() == 0 {
+                None
+            } else {
+                Some(src.get_id())
+            },
+            src.get_reserve_id().to_string(),
+            src.get_tx_id().to_string(),
+            src.get_position(),
+            src.get_asset().to_string(),
+            src.get_amount(),
+            src.get_mc_tx_out_pub_key().get_data().encode_hex(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::{ETH_TOKEN_CONTRACT_ADDR, SAFE_ADDR};
+
+    #[test]
+    // DbReserveWithdrawal --> ProtoReserveWithdrawal -->
+    // DbReserveWithdrawal should be the identity function.
+    fn test_convert_reserve_withdrawal() {
+        let source = DbReserveWithdrawal::new(
+            Some(10),
+            SAFE_ADDR.to_lowercase(),
+            "0x0e781edb7739aa88ad2ffb6a69aab46ff9e32dbd0f0c87e4006a176838b075d2".to_string(),
+            123456,
+            ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+            333,
+            "1111111111111111111111111111111111111111111111111111111111111111".to_string(),
+        );
+
+        // Converting should be the identity function.
+        {
+            let external = ProtoReserveWithdrawal::try_from(&source).unwrap();
+            let recovered = DbReserveWithdrawal::try_from(&external).unwrap();
+            assert_eq!(source, recovered);
+        }
+    }
+}
@@ -32,6 +32,21 @@ lazy_static::lazy_static! {
     /// Number of unexpected errors attempting to match mints to deposits.
     pub static ref NUM_UNEXPECTED_ERRORS_MATCHING_MINTS_TO_DEPOSITS: IntGauge = OP_COUNTERS.gauge("num_unexpected_errors_matching_mints_to_deposits");
 
-    /// Number of times we failed to fetch gnosis transactions.
-    pub static ref NUM_FAILED_GNOSIS_GET_ALL_TRANSACTION_DATA: IntCounter = OP_COUNTERS.counter("num_failed_gnosis_get_all_transaction_data");
+    /// Number of mismatched BurnTxOuts and Gnosis withdrawals.
+    pub static ref NUM_MISMATCHING_BURNS_AND_WITHDRAWALS: IntGauge = OP_COUNTERS.gauge("num_mismatching_burns_and_withdrawals");
+
+    /// Number of unexpected errors attempting to match burns to withdrawals.
+    pub static ref NUM_UNEXPECTED_ERRORS_MATCHING_BURNS_TO_WITHDRAWALS: IntGauge = OP_COUNTERS.gauge("num_unexpected_errors_matching_burns_to_withdrawals");
+
+    /// Number of unexpected errors attempting to match withdrawals to burns.
+    pub static ref NUM_UNEXPECTED_ERRORS_MATCHING_WITHDRAWALS_TO_BURNS: IntGauge = OP_COUNTERS.gauge("num_unexpected_errors_matching_withdrawals_to_burns");
+
+    /// Number of BurnTxOuts that have not been matched with a reserve withdrawal.
+    pub static ref NUM_UNAUDITED_BURN_TX_OUTS: IntGauge = OP_COUNTERS.gauge("num_unaudited_burn_tx_outs");
+
+    /// Number of reserve withdrawals that have not been matched with a BurnTxOut.
+    pub static ref NUM_UNAUDITED_RESERVE_WITHDRAWALS: IntGauge = OP_COUNTERS.gauge("num_unaudited_reserve_withdrawals");
+
+    /// Number of times we failed to fetch transactions from a reserve source.
+    pub static ref NUM_FAILED_RESERVE_SOURCE_FETCHES: IntCounter = OP_COUNTERS.counter("num_failed_reserve_source_fetches");
 }
@@ -15,8 +15,9 @@ pub mod schema;
 pub use self::{
     conn::{Conn, ConnectionOptions},
     models::{
-        AuditedMint, BlockAuditData, BlockBalance, Counters, GnosisSafeDeposit, GnosisSafeTx,
-        GnosisSafeWithdrawal, MintConfig, MintConfigTx, MintTx,
+        AuditedBurn, AuditedMint, BlockAuditData, BlockBalance, BlockReserveCutoff, BurnTxOut,
+        Counters, MintConfig, MintConfigTx, MintTx, ReserveDeposit, ReserveSyncCursor, ReserveTx,
+        ReserveWithdrawal,
     },
     transaction::{transaction, TransactionRetriableError},
 };
@@ -33,7 +34,7 @@ use mc_common::{
     logger::{log, Logger},
     HashMap,
 };
-use mc_transaction_core::{tx::TxOut, TokenId};
+use mc_transaction_core::TokenId;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::time::Duration;
 
@@ -58,7 +59,7 @@ pub struct SyncBlockData {
     pub mint_txs: Vec<MintTx>,
 
     /// Burn TxOuts in the block.
-    pub burn_tx_outs: Vec<TxOut>,
+    pub burn_tx_outs: Vec<BurnTxOut>,
 }
 
 /// Mint Auditor Database.
@@ -236,11 +237,18 @@ impl MintAuditorDb {
                 }
             }
 
+            // Store the burns.
+            let burn_tx_outs = burn_tx_outs
+                .iter()
+                .map(|tx_out| BurnTxOut::insert_from_core_tx_out(block_index, tx_out, conn))
+                .collect::<Result<Vec<_>, _>>()?;
+
             Counters::inc_num_blocks_synced(conn)?;
 
             let block_audit = BlockAuditData::new(block_index);
             log::trace!(self.logger, "Storing block audit data: {:?}", block_audit);
             block_audit.set(conn)?;
+            BlockReserveCutoff::record(block_index, conn)?;
 
             BlockBalance::set_balances_for_block(conn, block_index, &balance_map)?;
 
@@ -507,9 +515,18 @@ mod tests {
                 block_audit: BlockAuditData::new(block.index),
                 balance_map: HashMap::from_iter([(token_id1, 41), (token_id2, 2)]),
                 mint_txs: MintTx::get_mint_txs_by_block_index(block.index, &conn).unwrap(),
-                burn_tx_outs: vec![tx_out1, tx_out2],
+                burn_tx_outs: BurnTxOut::get_burn_tx_outs_by_block_index(block.index, &conn)
+                    .unwrap(),
             }
         );
+        assert_eq!(
+            sync_block_data
+                .burn_tx_outs
+                .iter()
+                .map(|burn_tx_out| burn_tx_out.decode().unwrap())
+                .collect::<Vec<_>>(),
+            vec![tx_out1, tx_out2]
+        );
 
         // Sync a block that mixes burning and minting.
         let mint_tx1 = create_mint_tx(token_id1, &signers1, 1000, &mut rng);
@@ -565,9 +582,18 @@ mod tests {
                     (token_id3, 20000)
                 ]),
                 mint_txs: MintTx::get_mint_txs_by_block_index(block.index, &conn).unwrap(),
-                burn_tx_outs: vec![tx_out1, tx_out2],
+                burn_tx_outs: BurnTxOut::get_burn_tx_outs_by_block_index(block.index, &conn)
+                    .unwrap(),
             }
         );
+        assert_eq!(
+            sync_block_data
+                .burn_tx_outs
+                .iter()
+                .map(|burn_tx_out| burn_tx_out.decode().unwrap())
+                .collect::<Vec<_>>(),
+            vec![tx_out1, tx_out2]
+        );
 
         // Sanity check counters.
         let counters = Counters::get(&conn).unwrap();
@@ -816,9 +842,18 @@ mod tests {
                 block_audit: BlockAuditData::new(block.index),
                 balance_map: HashMap::from_iter([(token_id1, 0), (token_id2, 0)]),
                 mint_txs: MintTx::get_mint_txs_by_block_index(block.index, &conn).unwrap(),
-                burn_tx_outs: vec![tx_out1, tx_out2],
+                burn_tx_outs: BurnTxOut::get_burn_tx_outs_by_block_index(block.index, &conn)
+                    .unwrap(),
             }
         );
+        assert_eq!(
+            sync_block_data
+                .burn_tx_outs
+                .iter()
+                .map(|burn_tx_out| burn_tx_out.decode().unwrap())
+                .collect::<Vec<_>>(),
+            vec![tx_out1, tx_out2]
+        );
 
         // Over-burn has been recorded.
         assert_eq!(
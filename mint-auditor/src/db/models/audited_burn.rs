@@ -0,0 +1,480 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{
+    counters as prom_counters,
+    db::{
+        schema::{audited_burns, burn_tx_outs, reserve_withdrawals},
+        transaction, BurnTxOut, Conn, Counters, ReserveWithdrawal,
+    },
+    reserve::{AuditedReserveConfig, ReserveConfig},
+    Error,
+};
+use diesel::prelude::*;
+use mc_blockchain_types::BlockIndex;
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `audited_burns` table.
+/// This stores audit data linking BurnTxOuts with matching
+/// ReserveWithdrawals.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize)]
+pub struct AuditedBurn {
+    /// Id (required to keep Diesel happy).
+    pub id: Option<i32>,
+
+    /// Id pointing to the BurnTxOut table.
+    pub burn_tx_out_id: i32,
+
+    /// Id pointing to the ReserveWithdrawal table.
+    pub reserve_withdrawal_id: i32,
+}
+
+impl AuditedBurn {
+    /// Attempt to find a matching [BurnTxOut] for a given
+    /// [ReserveWithdrawal], and if successful return the [BurnTxOut] and
+    /// record the match in the database. Note that each [BurnTxOut] can be
+    /// matched to at most one [ReserveWithdrawal], so calling this
+    /// repeatedly over the same withdrawal will fail.
+    pub fn try_match_withdrawal_with_burn(
+        withdrawal: &ReserveWithdrawal,
+        config: &AuditedReserveConfig,
+        conn: &Conn,
+    ) -> Result<BurnTxOut, Error> {
+        // Wrapped in a closure to allow using the ? operator without returning from the
+        // function.
+        let result = || -> Result<BurnTxOut, Error> {
+            // We only operate on objects that were saved to the database.
+            let withdrawal_id = withdrawal.id().ok_or(Error::ObjectNotSaved)?;
+
+            transaction(conn, |conn| {
+                // Currently we only support 1:1 mapping between withdrawals and burns, so
+                // ensure that there isn't already a match for this withdrawal.
+                let existing_match: Option<(String, String)> = audited_burns::table
+                    .inner_join(burn_tx_outs::table)
+                    .inner_join(reserve_withdrawals::table)
+                    .select((burn_tx_outs::public_key_hex, reserve_withdrawals::tx_id))
+                    .filter(audited_burns::reserve_withdrawal_id.eq(withdrawal_id))
+                    .first(conn)
+                    .optional()?;
+                if let Some((public_key_hex, tx_id)) = existing_match {
+                    return Err(Error::AlreadyExists(format!(
+                        "ReserveWithdrawal tx_id={} already matched with burn tx out public_key={}",
+                        tx_id, public_key_hex,
+                    )));
+                }
+
+                // See if we can find a BurnTxOut that matches the public key recorded in the
+                // withdrawal and has not been associated with a withdrawal.
+                let burn_tx_out = BurnTxOut::find_unaudited_burn_tx_out_by_public_key(
+                    withdrawal.mc_tx_out_public_key_hex(),
+                    conn,
+                )?
+                .ok_or(Error::NotFound)?;
+
+                // Check that the burn and withdrawal details match.
+                Self::verify_burn_tx_out_matches_withdrawal(&burn_tx_out, withdrawal, config)?;
+
+                // Associate the withdrawal with the burn.
+                Self::associate_withdrawal_with_burn(
+                    withdrawal_id,
+                    burn_tx_out.id().expect(
+                        "got a BurnTxOut without id but database auto-populates that field",
+                    ),
+                    conn,
+                )?;
+
+                Ok(burn_tx_out)
+            })
+        }();
+
+        // Count certain errors. This needs to happen outside of the transaction because
+        // errors result in the transaction getting rolled back.
+        match result {
+            Ok(_) | Err(Error::NotFound) => {}
+
+            Err(Error::BurnAndWithdrawalMismatch(_)) => {
+                Counters::inc_num_mismatching_burns_and_withdrawals(conn)?;
+            }
+
+            Err(_) => {
+                Counters::inc_num_unexpected_errors_matching_withdrawals_to_burns(conn)?;
+            }
+        }
+
+        result
+    }
+
+    /// Attempt to find a matching [ReserveWithdrawal] for a given
+    /// [BurnTxOut], and if successful return the [ReserveWithdrawal] and
+    /// record the match in the database. Note that each
+    /// [ReserveWithdrawal] can be matched to at most one [BurnTxOut], so
+    /// calling this repeatedly over the same [BurnTxOut] will fail.
+    pub fn try_match_burn_with_withdrawal(
+        burn_tx_out: &BurnTxOut,
+        config: &ReserveConfig,
+        conn: &Conn,
+    ) -> Result<ReserveWithdrawal, Error> {
+        // Wrapped in a closure to allow using the ? operator without returning from the
+        // function.
+        let result = || -> Result<ReserveWithdrawal, Error> {
+            // We only operate on objects that were saved to the database.
+            let burn_tx_out_id = burn_tx_out.id().ok_or(Error::ObjectNotSaved)?;
+
+            transaction(conn, |conn| -> Result<ReserveWithdrawal, Error> {
+                // Currently we only support 1:1 mapping between withdrawals and burns, so
+                // ensure that there isn't already a match for this burn.
+                let existing_match = audited_burns::table
+                    .filter(audited_burns::burn_tx_out_id.eq(burn_tx_out_id))
+                    .first::<AuditedBurn>(conn)
+                    .optional()?;
+                if let Some(existing_match) = existing_match {
+                    return Err(Error::AlreadyExists(format!(
+                        "BurnTxOut id={} already matched with reserve_withdrawal_id={}",
+                        existing_match.burn_tx_out_id, existing_match.reserve_withdrawal_id,
+                    )));
+                }
+
+                // See if we can find a ReserveWithdrawal that carries the public key of the
+                // burn and has not been associated with a burn.
+                let withdrawal = ReserveWithdrawal::find_unaudited_withdrawal_by_public_key(
+                    burn_tx_out.public_key_hex(),
+                    conn,
+                )?
+                .ok_or(Error::NotFound)?;
+
+                // See if the withdrawal we found is for a reserve we are auditing.
+                let audited_reserve_config = config
+                    .get_reserve(withdrawal.reserve_id())
+                    .ok_or_else(|| Error::ReserveNotAudited(withdrawal.reserve_id().to_string()))?;
+
+                // See if they match.
+                Self::verify_burn_tx_out_matches_withdrawal(
+                    burn_tx_out,
+                    &withdrawal,
+                    audited_reserve_config,
+                )?;
+
+                // Associate the burn with the withdrawal.
+                Self::associate_withdrawal_with_burn(
+                    withdrawal.id().expect(
+                        "got a ReserveWithdrawal without id but database auto-populates that field",
+                    ),
+                    burn_tx_out_id,
+                    conn,
+                )?;
+
+                Ok(withdrawal)
+            })
+        }();
+
+        // Count certain errors. This needs to happen outside of the transaction because
+        // errors result in the transaction getting rolled back.
+        match result {
+            Ok(_) | Err(Error::NotFound) => {}
+
+            Err(Error::BurnAndWithdrawalMismatch(_)) => {
+                Counters::inc_num_mismatching_burns_and_withdrawals(conn)?;
+            }
+
+            Err(_) => {
+                Counters::inc_num_unexpected_errors_matching_burns_to_withdrawals(conn)?;
+            }
+        }
+
+        result
+    }
+
+    /// Update the Prometheus gauges tracking the number of burns and
+    /// withdrawals that have not been matched yet.
+    pub fn update_unaudited_prometheus_gauges(conn: &Conn) -> Result<(), Error> {
+        let num_unaudited_burn_tx_outs = BurnTxOut::find_unaudited_burn_tx_outs(conn)?.len();
+        let num_unaudited_withdrawals = ReserveWithdrawal::find_unaudited_withdrawals(conn)?.len();
+
+        prom_counters::NUM_UNAUDITED_BURN_TX_OUTS.set(num_unaudited_burn_tx_outs as i64);
+        prom_counters::NUM_UNAUDITED_RESERVE_WITHDRAWALS.set(num_unaudited_withdrawals as i64);
+
+        Ok(())
+    }
+
+    /// Get all [BurnTxOut]s that appeared at or before a given block index and
+    /// were matched with a [ReserveWithdrawal], together with the
+    /// withdrawal.
+    pub fn get_audited_burns_up_to_block_index(
+        block_index: BlockIndex,
+        conn: &Conn,
+    ) -> Result<Vec<(BurnTxOut, ReserveWithdrawal)>, Error> {
+        Ok(audited_burns::table
+            .inner_join(burn_tx_outs::table)
+            .inner_join(reserve_withdrawals::table)
+            .filter(burn_tx_outs::block_index.le(block_index as i64))
+            .order_by(audited_burns::id)
+            .select((burn_tx_outs::all_columns, reserve_withdrawals::all_columns))
+            .load(conn)?)
+    }
+
+    /// Verify that the details of a BurnTxOut match the details of a
+    /// ReserveWithdrawal (public key/amount/token).
+    fn verify_burn_tx_out_matches_withdrawal(
+        burn_tx_out: &BurnTxOut,
+        withdrawal: &ReserveWithdrawal,
+        config: &AuditedReserveConfig,
+    ) -> Result<(), Error> {
+        // The withdrawal reserve needs to match the audited reserve configuration.
+        // This shouldn't happen and indicates misuse of this function.
+        if withdrawal.reserve_id() != config.id() {
+            return Err(Error::Other(format!(
+                "Reserve withdrawal reserve id {} does not match audited reserve id {}",
+                withdrawal.reserve_id(),
+                config.id()
+            )));
+        }
+
+        // Public keys should match.
+        if burn_tx_out.public_key_hex() != withdrawal.mc_tx_out_public_key_hex() {
+            return Err(Error::BurnAndWithdrawalMismatch(format!(
+                "BurnTxOut public key {} does not match withdrawal public key {}",
+                burn_tx_out.public_key_hex(),
+                withdrawal.mc_tx_out_public_key_hex()
+            )));
+        }
+
+        // Check to see if the amount matches the withdrawal.
+        if burn_tx_out.amount() != withdrawal.amount() {
+            return Err(Error::BurnAndWithdrawalMismatch(format!(
+                "BurnTxOut amount={} does not match ReserveWithdrawal amount={} (public_key={})",
+                burn_tx_out.amount(),
+                withdrawal.amount(),
+                burn_tx_out.public_key_hex(),
+            )));
+        }
+
+        // Check and see if the tokens match.
+        let audited_token_id = config
+            .token_id_for_asset(withdrawal.asset())
+            .ok_or_else(|| {
+                Error::ReserveAssetNotAudited(
+                    withdrawal.asset().to_string(),
+                    withdrawal.reserve_id().to_string(),
+                    withdrawal.tx_id().to_string(),
+                )
+            })?;
+
+        if audited_token_id != burn_tx_out.token_id() {
+            return Err(Error::BurnAndWithdrawalMismatch(format!(
+                "BurnTxOut token_id={} does not match audited token_id={} (public_key={})",
+                burn_tx_out.token_id(),
+                audited_token_id,
+                burn_tx_out.public_key_hex(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    // This is pub(crate) since its used in tests.
+    pub(crate) fn associate_withdrawal_with_burn(
+        reserve_withdrawal_id: i32,
+        burn_tx_out_id: i32,
+        conn: &Conn,
+    ) -> Result<(), Error> {
+        let audited_burn = Self {
+            id: None,
+            burn_tx_out_id,
+            reserve_withdrawal_id,
+        };
+        let _ = diesel::insert_into(audited_burns::table)
+            .values(&audited_burn)
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::{
+        create_burn_tx_out, create_reserve_withdrawal, insert_reserve_withdrawal,
+        test_gnosis_config, test_reserve_config, TestDbContext,
+    };
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::TokenId;
+
+    fn assert_audited_burns_table_is_empty(conn: &Conn) {
+        let num_rows: i64 = audited_burns::table
+            .select(diesel::dsl::count(audited_burns::id))
+            .first(conn)
+            .unwrap();
+        assert_eq!(num_rows, 0);
+    }
+
+    #[test_with_logger]
+    fn test_try_match_withdrawal_with_burn_happy_flow(logger: Logger) {
+        let config = &AuditedReserveConfig::GnosisSafe(test_gnosis_config().safes[0].clone());
+        let token_id = test_gnosis_config().safes[0].tokens[0].token_id;
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        let tx_out1 = create_burn_tx_out(token_id, 100, Some([1; 64]), &mut rng);
+        let tx_out2 = create_burn_tx_out(token_id, 200, None, &mut rng);
+
+        // Create gnosis withdrawals.
+        let mut withdrawal1 = create_reserve_withdrawal(&tx_out1, 100, &mut rng);
+        let mut withdrawal2 = create_reserve_withdrawal(&tx_out2, 200, &mut rng);
+
+        insert_reserve_withdrawal(&mut withdrawal1, &conn);
+        insert_reserve_withdrawal(&mut withdrawal2, &conn);
+
+        // Initially the database has no burns.
+        assert!(matches!(
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal1, config, &conn),
+            Err(Error::NotFound)
+        ));
+        assert!(matches!(
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal2, config, &conn),
+            Err(Error::NotFound)
+        ));
+        assert_audited_burns_table_is_empty(&conn);
+        assert_eq!(
+            ReserveWithdrawal::find_unaudited_withdrawals(&conn).unwrap(),
+            vec![withdrawal1.clone(), withdrawal2.clone()]
+        );
+
+        // Insert the first burn to the database, we should get a match now.
+        let burn_tx_out1 = BurnTxOut::insert_from_core_tx_out(0, &tx_out1, &conn).unwrap();
+        assert_eq!(
+            burn_tx_out1,
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal1, config, &conn).unwrap()
+        );
+        assert!(matches!(
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal2, config, &conn),
+            Err(Error::NotFound)
+        ));
+
+        // Matching again should fail.
+        assert!(matches!(
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal1, config, &conn),
+            Err(Error::AlreadyExists(_))
+        ));
+
+        // Insert the second burn, it should match the second withdrawal.
+        let burn_tx_out2 = BurnTxOut::insert_from_core_tx_out(1, &tx_out2, &conn).unwrap();
+        assert_eq!(
+            burn_tx_out2,
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal2, config, &conn).unwrap()
+        );
+
+        // Nothing is left unaudited.
+        assert_eq!(
+            BurnTxOut::find_unaudited_burn_tx_outs(&conn).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            ReserveWithdrawal::find_unaudited_withdrawals(&conn).unwrap(),
+            vec![]
+        );
+
+        let counters = Counters::get(&conn).unwrap();
+        assert_eq!(counters.num_mismatching_burns_and_withdrawals(), 0);
+        assert_eq!(
+            counters.num_unexpected_errors_matching_withdrawals_to_burns(),
+            1
+        );
+    }
+
+    #[test_with_logger]
+    fn test_try_match_burn_with_withdrawal_happy_flow(logger: Logger) {
+        let config = test_reserve_config();
+        let token_id = test_gnosis_config().safes[0].tokens[0].token_id;
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        let tx_out1 = create_burn_tx_out(token_id, 100, None, &mut rng);
+        let tx_out2 = create_burn_tx_out(token_id, 200, None, &mut rng);
+
+        let burn_tx_out1 = BurnTxOut::insert_from_core_tx_out(0, &tx_out1, &conn).unwrap();
+        let burn_tx_out2 = BurnTxOut::insert_from_core_tx_out(0, &tx_out2, &conn).unwrap();
+
+        // Initially the database has no withdrawals.
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out1, &config, &conn),
+            Err(Error::NotFound)
+        ));
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out2, &config, &conn),
+            Err(Error::NotFound)
+        ));
+        assert_audited_burns_table_is_empty(&conn);
+
+        // Insert the withdrawal for the second burn, we should get a match now.
+        let mut withdrawal2 = create_reserve_withdrawal(&tx_out2, 200, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal2, &conn);
+
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out1, &config, &conn),
+            Err(Error::NotFound)
+        ));
+        assert_eq!(
+            withdrawal2,
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out2, &config, &conn).unwrap()
+        );
+
+        // Matching again should fail.
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out2, &config, &conn),
+            Err(Error::AlreadyExists(_))
+        ));
+
+        assert_eq!(
+            BurnTxOut::find_unaudited_burn_tx_outs(&conn).unwrap(),
+            vec![burn_tx_out1]
+        );
+    }
+
+    #[test_with_logger]
+    fn test_try_match_burn_with_withdrawal_mismatches(logger: Logger) {
+        let config = test_reserve_config();
+        let token_id = test_gnosis_config().safes[0].tokens[0].token_id;
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        // A withdrawal whose amount does not match the burn.
+        let tx_out1 = create_burn_tx_out(token_id, 100, None, &mut rng);
+        let burn_tx_out1 = BurnTxOut::insert_from_core_tx_out(0, &tx_out1, &conn).unwrap();
+        let mut withdrawal1 = create_reserve_withdrawal(&tx_out1, 101, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal1, &conn);
+
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out1, &config, &conn),
+            Err(Error::BurnAndWithdrawalMismatch(_))
+        ));
+        assert!(matches!(
+            AuditedBurn::try_match_withdrawal_with_burn(&withdrawal1, &config.reserves[0], &conn),
+            Err(Error::BurnAndWithdrawalMismatch(_))
+        ));
+
+        // A burn of a token that does not match the withdrawn token.
+        let tx_out2 = create_burn_tx_out(TokenId::from(2), 100, None, &mut rng);
+        let burn_tx_out2 = BurnTxOut::insert_from_core_tx_out(0, &tx_out2, &conn).unwrap();
+        let mut withdrawal2 = create_reserve_withdrawal(&tx_out2, 100, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal2, &conn);
+
+        assert!(matches!(
+            AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out2, &config, &conn),
+            Err(Error::BurnAndWithdrawalMismatch(_))
+        ));
+
+        assert_audited_burns_table_is_empty(&conn);
+        assert_eq!(
+            Counters::get(&conn)
+                .unwrap()
+                .num_mismatching_burns_and_withdrawals(),
+            3
+        );
+    }
+}
@@ -2,17 +2,18 @@
 
 use crate::{
     db::{
-        schema::{audited_mints, gnosis_safe_deposits, mint_txs},
-        transaction, Conn, Counters, GnosisSafeDeposit, MintTx,
+        schema::{audited_mints, mint_txs, reserve_deposits},
+        transaction, Conn, Counters, MintTx, ReserveDeposit,
     },
-    gnosis::{AuditedSafeConfig, GnosisSafeConfig},
+    reserve::{AuditedReserveConfig, ReserveConfig},
     Error,
 };
 use diesel::prelude::*;
+use mc_blockchain_types::BlockIndex;
 use serde::{Deserialize, Serialize};
 
 /// Diesel model for the `audited_mints` table.
-/// This stores audit data linking MintTxs with matching GnosisSafeDeposits.
+/// This stores audit data linking MintTxs with matching ReserveDeposits.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize)]
 pub struct AuditedMint {
     /// Id (required to keep Diesel happy).
@@ -21,19 +22,19 @@ pub struct AuditedMint {
     /// Id pointing to the MintTx table.
     pub mint_tx_id: i32,
 
-    /// Id pointing to the GnosisSafeDeposit table.
-    pub gnosis_safe_deposit_id: i32,
+    /// Id pointing to the ReserveDeposit table.
+    pub reserve_deposit_id: i32,
 }
 
 impl AuditedMint {
-    /// Attempt to find a matching [MintTx] for a given [GnosisSafeDeposit], and
+    /// Attempt to find a matching [MintTx] for a given [ReserveDeposit], and
     /// if successful return the [MintTx] and record the match in the
     /// database. Note that each [MintTx] can be matched to at most one
-    /// [GnosisSafeDeposit], so calling this repeatedly over the same
+    /// [ReserveDeposit], so calling this repeatedly over the same
     /// deposit will fail.
     pub fn try_match_deposit_with_mint(
-        deposit: &GnosisSafeDeposit,
-        config: &AuditedSafeConfig,
+        deposit: &ReserveDeposit,
+        config: &AuditedReserveConfig,
         conn: &Conn,
     ) -> Result<MintTx, Error> {
         // Wrapped in a closure to allow using the ? operator without returning from the
@@ -42,13 +43,13 @@ impl AuditedMint {
             // We only operate on objects that were saved to the database.
             let deposit_id = deposit.id().ok_or(Error::ObjectNotSaved)?;
 
-            // The deposit safe needs to match the audited safe configuration.
+            // The deposit reserve needs to match the audited reserve configuration.
             // This shouldn't happen and indicates misuse of this function.
-            if deposit.safe_addr() != &config.safe_addr {
+            if deposit.reserve_id() != config.id() {
                 return Err(Error::Other(format!(
-                    "Gnosis safe deposit addr {} does not match audited safe addr {}",
-                    deposit.safe_addr(),
-                    config.safe_addr
+                    "Reserve deposit reserve id {} does not match audited reserve id {}",
+                    deposit.reserve_id(),
+                    config.id()
                 )));
             }
 
@@ -57,16 +58,16 @@ impl AuditedMint {
                 // that there isn't already a match for this deposit.
                 let existing_match: Option<(String, String)> = audited_mints::table
                     .inner_join(mint_txs::table)
-                    .inner_join(gnosis_safe_deposits::table)
-                    .select((mint_txs::nonce_hex, gnosis_safe_deposits::eth_tx_hash))
-                    .filter(audited_mints::gnosis_safe_deposit_id.eq(deposit_id))
+                    .inner_join(reserve_deposits::table)
+                    .select((mint_txs::nonce_hex, reserve_deposits::tx_id))
+                    .filter(audited_mints::reserve_deposit_id.eq(deposit_id))
                     .first(conn)
                     .optional()?;
-                if let Some((nonce_hex, eth_tx_hash)) = existing_match {
+                if let Some((nonce_hex, tx_id)) = existing_match {
                     Counters::inc_num_unexpected_errors_matching_deposits_to_mints(conn)?;
                     return Err(Error::AlreadyExists(format!(
-                        "GnosisSafeDeposit eth_tx_hash={} already matched with mint_tx nonce={}",
-                        eth_tx_hash, nonce_hex,
+                        "ReserveDeposit tx_id={} already matched with mint_tx nonce={}",
+                        tx_id, nonce_hex,
                     )));
                 }
 
@@ -103,7 +104,7 @@ impl AuditedMint {
                 Counters::inc_num_mismatching_mints_and_deposits(conn)?;
             }
 
-            Err(Error::EthereumTokenNotAudited(_, _, _)) => {
+            Err(Error::ReserveAssetNotAudited(_, _, _)) => {
                 Counters::inc_num_unknown_ethereum_token_deposits(conn)?;
             }
 
@@ -115,23 +116,23 @@ impl AuditedMint {
         result
     }
 
-    /// Attempt to find a matching [GnosisSafeDeposit] for a given [MintTx], and
-    /// if successful return the [GnosisSafeDeposit] and record the match in the
-    /// database. Note that each [GnosisSafeDeposit] can be matched to at
+    /// Attempt to find a matching [ReserveDeposit] for a given [MintTx], and
+    /// if successful return the [ReserveDeposit] and record the match in the
+    /// database. Note that each [ReserveDeposit] can be matched to at
     /// most one [MintTx], so calling this repeatedly over the same [MintTx]
     /// will fail.
     pub fn try_match_mint_with_deposit(
         mint_tx: &MintTx,
-        config: &GnosisSafeConfig,
+        config: &ReserveConfig,
         conn: &Conn,
-    ) -> Result<GnosisSafeDeposit, Error> {
+    ) -> Result<ReserveDeposit, Error> {
         // Wrapped in a closure to allow using the ? operator without returning from the
         // function.
-        let result = || -> Result<GnosisSafeDeposit, Error> {
+        let result = || -> Result<ReserveDeposit, Error> {
             // We only operate on objects that were saved to the database.
             let mint_tx_id = mint_tx.id().ok_or(Error::ObjectNotSaved)?;
 
-            transaction(conn, |conn| -> Result<GnosisSafeDeposit, Error> {
+            transaction(conn, |conn| -> Result<ReserveDeposit, Error> {
                 // Currently we only support 1:1 mapping between deposits and mints, so ensure
                 // that there isn't already a match for this mint.
                 let existing_match = audited_mints::table
@@ -140,29 +141,29 @@ impl AuditedMint {
                     .optional()?;
                 if let Some(existing_match) = existing_match {
                     return Err(Error::AlreadyExists(format!(
-                        "MintTx id={} already matched with gnosis_safe_deposit_id={}",
-                        existing_match.mint_tx_id, existing_match.gnosis_safe_deposit_id,
+                        "MintTx id={} already matched with reserve_deposit_id={}",
+                        existing_match.mint_tx_id, existing_match.reserve_deposit_id,
                     )));
                 }
 
-                // See if we can find a GnosisSafeDeposit that matches the nonce and has not
+                // See if we can find a ReserveDeposit that matches the nonce and has not
                 // been associated with a mint.
                 let deposit =
-                    GnosisSafeDeposit::find_unaudited_deposit_by_nonce(mint_tx.nonce_hex(), conn)?
+                    ReserveDeposit::find_unaudited_deposit_by_nonce(mint_tx.nonce_hex(), conn)?
                         .ok_or(Error::NotFound)?;
 
-                // See if the deposit we found is for a safe we are auditing.
-                let audited_safe_config = config
-                    .get_audited_safe_config_by_safe_addr(deposit.safe_addr())
-                    .ok_or_else(|| Error::GnosisSafeNotAudited(deposit.safe_addr().clone()))?;
+                // See if the deposit we found is for a reserve we are auditing.
+                let audited_reserve_config = config
+                    .get_reserve(deposit.reserve_id())
+                    .ok_or_else(|| Error::ReserveNotAudited(deposit.reserve_id().to_string()))?;
 
                 // See if they match.
-                Self::verify_mint_tx_matches_deposit(mint_tx, &deposit, &audited_safe_config)?;
+                Self::verify_mint_tx_matches_deposit(mint_tx, &deposit, audited_reserve_config)?;
 
                 // Associate the mint with the deposit.
                 Self::associate_deposit_with_mint(
                     deposit.id().expect(
-                        "got a GnosisSafeDeposit without id but database auto-populates that field",
+                        "got a ReserveDeposit without id but database auto-populates that field",
                     ),
                     mint_tx_id,
                     conn,
@@ -177,7 +178,7 @@ impl AuditedMint {
         match result {
             Ok(_) => {}
 
-            Err(Error::GnosisSafeNotAudited(_)) => {
+            Err(Error::ReserveNotAudited(_)) => {
                 Counters::inc_num_mints_to_unknown_safe(conn)?;
             }
 
@@ -185,7 +186,7 @@ impl AuditedMint {
                 Counters::inc_num_mismatching_mints_and_deposits(conn)?;
             }
 
-            Err(Error::EthereumTokenNotAudited(_, _, _)) => {
+            Err(Error::ReserveAssetNotAudited(_, _, _)) => {
                 Counters::inc_num_unknown_ethereum_token_deposits(conn)?;
             }
 
@@ -197,20 +198,35 @@ impl AuditedMint {
         result
     }
 
+    /// Get all [MintTx]s that appeared at or before a given block index and
+    /// were matched with a [ReserveDeposit], together with the deposit.
+    pub fn get_audited_mints_up_to_block_index(
+        block_index: BlockIndex,
+        conn: &Conn,
+    ) -> Result<Vec<(MintTx, ReserveDeposit)>, Error> {
+        Ok(audited_mints::table
+            .inner_join(mint_txs::table)
+            .inner_join(reserve_deposits::table)
+            .filter(mint_txs::block_index.le(block_index as i64))
+            .order_by(audited_mints::id)
+            .select((mint_txs::all_columns, reserve_deposits::all_columns))
+            .load(conn)?)
+    }
+
     /// Verify that the details of a MintTx match the details of a
-    /// GnosisSafeDeposit (amount/nonce/token).
+    /// ReserveDeposit (amount/nonce/token).
     fn verify_mint_tx_matches_deposit(
         mint_tx: &MintTx,
-        deposit: &GnosisSafeDeposit,
-        config: &AuditedSafeConfig,
+        deposit: &ReserveDeposit,
+        config: &AuditedReserveConfig,
     ) -> Result<(), Error> {
-        // The deposit safe needs to match the audited safe configuration.
+        // The deposit reserve needs to match the audited reserve configuration.
         // This shouldn't happen and indicates misuse of this function.
-        if deposit.safe_addr() != &config.safe_addr {
+        if deposit.reserve_id() != config.id() {
             return Err(Error::Other(format!(
-                "Gnosis safe deposit addr {} does not match audited safe addr {}",
-                deposit.safe_addr(),
-                config.safe_addr
+                "Reserve deposit reserve id {} does not match audited reserve id {}",
+                deposit.reserve_id(),
+                config.id()
             )));
         }
 
@@ -226,7 +242,7 @@ impl AuditedMint {
         // Check to see if the amount matches the deposit.
         if mint_tx.amount() != deposit.amount() {
             return Err(Error::DepositAndMintMismatch(format!(
-                "MintTx amount={} does not match GnosisSafeDeposit amount={} (nonce={})",
+                "MintTx amount={} does not match ReserveDeposit amount={} (nonce={})",
                 mint_tx.amount(),
                 deposit.amount(),
                 deposit.expected_mc_mint_tx_nonce_hex(),
@@ -234,21 +250,19 @@ impl AuditedMint {
         }
 
         // Check and see if the tokens match.
-        let audited_token = config
-            .get_token_by_eth_contract_addr(deposit.token_addr())
-            .ok_or_else(|| {
-                Error::EthereumTokenNotAudited(
-                    deposit.token_addr().clone(),
-                    deposit.safe_addr().clone(),
-                    *deposit.eth_tx_hash(),
-                )
-            })?;
-
-        if audited_token.token_id != mint_tx.token_id() {
+        let audited_token_id = config.token_id_for_asset(deposit.asset()).ok_or_else(|| {
+            Error::ReserveAssetNotAudited(
+                deposit.asset().to_string(),
+                deposit.reserve_id().to_string(),
+                deposit.tx_id().to_string(),
+            )
+        })?;
+
+        if audited_token_id != mint_tx.token_id() {
             return Err(Error::DepositAndMintMismatch(format!(
                 "MintTx token_id={} does not match audited token_id={} (nonce={})",
                 mint_tx.token_id(),
-                audited_token.token_id,
+                audited_token_id,
                 deposit.expected_mc_mint_tx_nonce_hex(),
             )));
         }
@@ -258,14 +272,14 @@ impl AuditedMint {
 
     // This is pub(crate) since its used in tests.
     pub(crate) fn associate_deposit_with_mint(
-        gnosis_safe_deposit_id: i32,
+        reserve_deposit_id: i32,
         mint_tx_id: i32,
         conn: &Conn,
     ) -> Result<(), Error> {
         let audited_mint = Self {
             id: None,
             mint_tx_id,
-            gnosis_safe_deposit_id,
+            reserve_deposit_id,
         };
         let _ = diesel::insert_into(audited_mints::table)
             .values(&audited_mint)
@@ -282,8 +296,8 @@ mod tests {
         db::{
             models::AuditedMint,
             test_utils::{
-                create_gnosis_safe_deposit, insert_gnosis_deposit, insert_mint_tx_from_deposit,
-                test_gnosis_config, TestDbContext,
+                create_reserve_deposit, insert_mint_tx_from_deposit, insert_reserve_deposit,
+                test_gnosis_config, test_reserve_config, TestDbContext,
             },
         },
         gnosis::EthAddr,
@@ -303,18 +317,18 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_deposit_with_mint_happy_flow(logger: Logger) {
-        let config = &test_gnosis_config().safes[0];
+        let config = &AuditedReserveConfig::GnosisSafe(test_gnosis_config().safes[0].clone());
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
         // Create gnosis deposits.
-        let mut deposit1 = create_gnosis_safe_deposit(100, &mut rng);
-        let mut deposit2 = create_gnosis_safe_deposit(200, &mut rng);
+        let mut deposit1 = create_reserve_deposit(100, &mut rng);
+        let mut deposit2 = create_reserve_deposit(200, &mut rng);
 
-        insert_gnosis_deposit(&mut deposit1, &conn);
-        insert_gnosis_deposit(&mut deposit2, &conn);
+        insert_reserve_deposit(&mut deposit1, &conn);
+        insert_reserve_deposit(&mut deposit2, &conn);
 
         // Initially the database is empty.
         assert!(matches!(
@@ -366,16 +380,16 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_deposit_with_mint_amount_mismatch(logger: Logger) {
-        let config = &test_gnosis_config().safes[0];
+        let config = &AuditedReserveConfig::GnosisSafe(test_gnosis_config().safes[0].clone());
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
-        let token_id1 = config.tokens[0].token_id;
+        let token_id1 = test_gnosis_config().safes[0].tokens[0].token_id;
         let conn = mint_auditor_db.get_conn().unwrap();
 
         // Create gnosis deposit.
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
 
         // Create MintTxs with a mismatching amount.
         let (_mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id1, &mut rng);
@@ -405,13 +419,13 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_deposit_with_mint_unsaved_object(logger: Logger) {
-        let config = &test_gnosis_config().safes[0];
+        let config = &AuditedReserveConfig::GnosisSafe(test_gnosis_config().safes[0].clone());
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let deposit = create_gnosis_safe_deposit(100, &mut rng);
+        let deposit = create_reserve_deposit(100, &mut rng);
 
         assert!(matches!(
             AuditedMint::try_match_deposit_with_mint(&deposit, config, &conn),
@@ -437,11 +451,12 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
         insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.safe_addr = EthAddr::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let config = AuditedReserveConfig::GnosisSafe(config);
         assert!(matches!(
             AuditedMint::try_match_deposit_with_mint(&deposit, &config, &conn),
             Err(Error::Other(_))
@@ -466,11 +481,12 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
         insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.tokens[0].token_id = TokenId::from(123);
+        let config = AuditedReserveConfig::GnosisSafe(config);
 
         assert!(matches!(
             AuditedMint::try_match_deposit_with_mint(&deposit, &config, &conn),
@@ -497,16 +513,17 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
         insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.tokens[0].eth_token_contract_addr =
             EthAddr::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let config = AuditedReserveConfig::GnosisSafe(config);
 
         assert!(matches!(
             AuditedMint::try_match_deposit_with_mint(&deposit, &config, &conn),
-            Err(Error::EthereumTokenNotAudited(_, _, _))
+            Err(Error::ReserveAssetNotAudited(_, _, _))
         ));
 
         // Check that nothing was written to the `audited_mints` table
@@ -522,15 +539,15 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_mint_with_deposit_happy_flow(logger: Logger) {
-        let config = test_gnosis_config();
+        let config = test_reserve_config();
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
         // Create gnosis deposits (that are not yet in the database).
-        let mut deposit1 = create_gnosis_safe_deposit(100, &mut rng);
-        let mut deposit2 = create_gnosis_safe_deposit(200, &mut rng);
+        let mut deposit1 = create_reserve_deposit(100, &mut rng);
+        let mut deposit2 = create_reserve_deposit(200, &mut rng);
 
         // Create MintTxs.
         let sql_mint_tx1 = insert_mint_tx_from_deposit(&deposit1, &conn, &mut rng);
@@ -548,7 +565,7 @@ mod tests {
         assert_audited_mints_table_is_empty(&conn);
 
         // Insert the first deposit to the database, we should get a match now.
-        insert_gnosis_deposit(&mut deposit1, &conn);
+        insert_reserve_deposit(&mut deposit1, &conn);
 
         assert_eq!(
             deposit1,
@@ -560,7 +577,7 @@ mod tests {
         ));
 
         // Insert the second deposit to the database, we should get a match on both.
-        insert_gnosis_deposit(&mut deposit2, &conn);
+        insert_reserve_deposit(&mut deposit2, &conn);
 
         assert!(matches!(
             AuditedMint::try_match_mint_with_deposit(&sql_mint_tx1, &config, &conn),
@@ -588,16 +605,16 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_mint_with_deposit_amount_mismatch(logger: Logger) {
-        let config = test_gnosis_config();
+        let config = test_reserve_config();
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
-        let token_id1 = config.safes[0].tokens[0].token_id;
+        let token_id1 = test_gnosis_config().safes[0].tokens[0].token_id;
         let conn = mint_auditor_db.get_conn().unwrap();
 
         // Create gnosis deposit.
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
 
         // Create  MintTxs with a mismatching amount.
         let (_mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id1, &mut rng);
@@ -627,11 +644,11 @@ mod tests {
 
     #[test_with_logger]
     fn test_try_match_mint_with_deposit_unsaved_object(logger: Logger) {
-        let config = test_gnosis_config();
+        let config = test_reserve_config();
         let mut rng = mc_util_test_helper::get_seeded_rng();
         let test_db_context = TestDbContext::default();
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
-        let token_id1 = config.safes[0].tokens[0].token_id;
+        let token_id1 = test_gnosis_config().safes[0].tokens[0].token_id;
         let conn = mint_auditor_db.get_conn().unwrap();
 
         let (_mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id1, &mut rng);
@@ -655,16 +672,17 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
 
         let mint_tx = insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.safes[0].safe_addr =
             EthAddr::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let config = ReserveConfig::from(config);
         assert!(matches!(
             AuditedMint::try_match_mint_with_deposit(&mint_tx, &config, &conn),
-            Err(Error::GnosisSafeNotAudited(_))
+            Err(Error::ReserveNotAudited(_))
         ));
 
         // Check that nothing was written to the `audited_mints` table
@@ -679,11 +697,12 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
         let sql_mint_tx = insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.safes[0].tokens[0].token_id = TokenId::from(123);
+        let config = ReserveConfig::from(config);
 
         assert!(matches!(
             AuditedMint::try_match_mint_with_deposit(&sql_mint_tx, &config, &conn),
@@ -710,16 +729,17 @@ mod tests {
         let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        let mut deposit = create_gnosis_safe_deposit(100, &mut rng);
-        insert_gnosis_deposit(&mut deposit, &conn);
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
         let sql_mint_tx = insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
 
         config.safes[0].tokens[0].eth_token_contract_addr =
             EthAddr::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let config = ReserveConfig::from(config);
 
         assert!(matches!(
             AuditedMint::try_match_mint_with_deposit(&sql_mint_tx, &config, &conn),
-            Err(Error::EthereumTokenNotAudited(_, _, _))
+            Err(Error::ReserveAssetNotAudited(_, _, _))
         ));
 
         // Check that nothing was written to the `audited_mints` table
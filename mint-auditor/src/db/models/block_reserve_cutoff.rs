@@ -0,0 +1,131 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{
+    db::{
+        schema::{block_reserve_cutoffs, reserve_deposits, reserve_withdrawals},
+        Conn,
+    },
+    Error,
+};
+use diesel::{dsl::max, prelude::*};
+use mc_blockchain_types::BlockIndex;
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `block_reserve_cutoffs` table.
+/// This stores, for a specific block index, the last reserve deposit and
+/// withdrawal the auditor had synced when the block was audited.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize)]
+pub struct BlockReserveCutoff {
+    /// Block index.
+    block_index: i64,
+
+    /// The highest [crate::db::ReserveDeposit] id at the time the block was
+    /// audited (0 if there were none).
+    last_reserve_deposit_id: i32,
+
+    /// The highest [crate::db::ReserveWithdrawal] id at the time the block
+    /// was audited (0 if there were none).
+    last_reserve_withdrawal_id: i32,
+}
+
+impl BlockReserveCutoff {
+    /// Get block index.
+    pub fn block_index(&self) -> BlockIndex {
+        self.block_index as BlockIndex
+    }
+
+    /// Get the highest reserve deposit id at the time the block was audited.
+    pub fn last_reserve_deposit_id(&self) -> i32 {
+        self.last_reserve_deposit_id
+    }
+
+    /// Get the highest reserve withdrawal id at the time the block was
+    /// audited.
+    pub fn last_reserve_withdrawal_id(&self) -> i32 {
+        self.last_reserve_withdrawal_id
+    }
+
+    /// Get the cutoff for a given block index.
+    pub fn get(block_index: BlockIndex, conn: &Conn) -> Result<Self, Error> {
+        Ok(block_reserve_cutoffs::table
+            .filter(block_reserve_cutoffs::block_index.eq(block_index as i64))
+            .get_result(conn)?)
+    }
+
+    /// Record the reserve deposits and withdrawals that are currently synced
+    /// as the cutoff for a given block index.
+    pub fn record(block_index: BlockIndex, conn: &Conn) -> Result<Self, Error> {
+        let last_reserve_deposit_id = reserve_deposits::table
+            .select(max(reserve_deposits::id))
+            .first::<Option<i32>>(conn)?
+            .unwrap_or(0);
+        let last_reserve_withdrawal_id = reserve_withdrawals::table
+            .select(max(reserve_withdrawals::id))
+            .first::<Option<i32>>(conn)?
+            .unwrap_or(0);
+
+        let obj = Self {
+            block_index: block_index as i64,
+            last_reserve_deposit_id,
+            last_reserve_withdrawal_id,
+        };
+
+        diesel::replace_into(block_reserve_cutoffs::table)
+            .values(&obj)
+            .execute(conn)?;
+
+        Ok(obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        test_utils::{
+            create_burn_tx_out, create_reserve_deposit, create_reserve_withdrawal,
+            insert_reserve_deposit, insert_reserve_withdrawal, TestDbContext,
+        },
+        BlockAuditData,
+    };
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::TokenId;
+
+    #[test_with_logger]
+    fn record_and_get_work(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        // Nothing synced yet.
+        BlockAuditData::new(0).set(&conn).unwrap();
+        let cutoff = BlockReserveCutoff::record(0, &conn).unwrap();
+        assert_eq!(cutoff.block_index(), 0);
+        assert_eq!(cutoff.last_reserve_deposit_id(), 0);
+        assert_eq!(cutoff.last_reserve_withdrawal_id(), 0);
+
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
+        let tx_out = create_burn_tx_out(TokenId::from(1), 100, None, &mut rng);
+        let mut withdrawal = create_reserve_withdrawal(&tx_out, 100, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal, &conn);
+
+        BlockAuditData::new(1).set(&conn).unwrap();
+        BlockReserveCutoff::record(1, &conn).unwrap();
+
+        // Records synced later do not affect earlier cutoffs.
+        let mut later_deposit = create_reserve_deposit(200, &mut rng);
+        insert_reserve_deposit(&mut later_deposit, &conn);
+
+        assert_eq!(BlockReserveCutoff::get(0, &conn).unwrap(), cutoff);
+        let cutoff = BlockReserveCutoff::get(1, &conn).unwrap();
+        assert_eq!(cutoff.last_reserve_deposit_id(), deposit.id().unwrap());
+        assert_eq!(
+            cutoff.last_reserve_withdrawal_id(),
+            withdrawal.id().unwrap()
+        );
+
+        assert!(BlockReserveCutoff::get(2, &conn).is_err());
+    }
+}
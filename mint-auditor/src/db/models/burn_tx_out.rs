@@ -0,0 +1,256 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Model file for the burn_tx_outs table.
+
+use crate::{
+    db::{
+        last_insert_rowid,
+        schema::{audited_burns, burn_tx_outs},
+        Conn,
+    },
+    Error,
+};
+use diesel::{
+    dsl::{exists, not},
+    prelude::*,
+};
+use hex::ToHex;
+use mc_account_keys::burn_address_view_private;
+use mc_blockchain_types::BlockIndex;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_transaction_core::{tx::TxOut, TokenId};
+use mc_transaction_std::MemoType;
+use mc_util_serial::{decode, encode};
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `burn_tx_outs` table.
+/// This stores data about a single TxOut sent to the burn address.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize,
+)]
+pub struct BurnTxOut {
+    /// Auto incrementing primary key.
+    id: Option<i32>,
+
+    /// The block index at which this burn tx out appeared.
+    block_index: i64,
+
+    /// The token id being burned.
+    token_id: i64,
+
+    /// The amount being burned.
+    amount: i64,
+
+    /// The TxOut public key, as hex-encoded bytes.
+    public_key_hex: String,
+
+    /// The memo data of the BurnRedemptionMemo, as hex-encoded bytes (if the
+    /// TxOut has one).
+    burn_redemption_memo_hex: Option<String>,
+
+    /// The protobuf-serialized TxOut.
+    protobuf: Vec<u8>,
+}
+
+impl BurnTxOut {
+    /// Get id.
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Get block index.
+    pub fn block_index(&self) -> u64 {
+        self.block_index as u64
+    }
+
+    /// Get token id.
+    pub fn token_id(&self) -> TokenId {
+        TokenId::from(self.token_id as u64)
+    }
+
+    /// Get amount.
+    pub fn amount(&self) -> u64 {
+        self.amount as u64
+    }
+
+    /// Get the TxOut public key (hex-encoded).
+    pub fn public_key_hex(&self) -> &str {
+        &self.public_key_hex
+    }
+
+    /// Get the TxOut public key.
+    pub fn public_key(&self) -> Result<CompressedRistrettoPublic, Error> {
+        let key_bytes = hex::decode(&self.public_key_hex)?;
+        Ok(CompressedRistrettoPublic::try_from(&key_bytes[..])?)
+    }
+
+    /// Get the memo data of the BurnRedemptionMemo (hex-encoded), if the TxOut
+    /// has one.
+    pub fn burn_redemption_memo_hex(&self) -> Option<&str> {
+        self.burn_redemption_memo_hex.as_deref()
+    }
+
+    /// Get the original TxOut.
+    pub fn decode(&self) -> Result<TxOut, Error> {
+        Ok(decode(&self.protobuf)?)
+    }
+
+    /// Create an instance of this object from a
+    /// [mc_transaction_core::tx::TxOut] sent to the burn address.
+    /// The amount and memo are recovered using the burn address view key.
+    pub fn from_core_tx_out(block_index: BlockIndex, tx_out: &TxOut) -> Result<Self, Error> {
+        let (amount, shared_secret) = tx_out
+            .view_key_match(&burn_address_view_private())
+            .map_err(|err| Error::Other(format!("TxOut is not a burn: {}", err)))?;
+
+        // Burns are not required to carry a BurnRedemptionMemo, and memos of other
+        // types are not an error either.
+        let burn_redemption_memo_hex =
+            match MemoType::try_from(&tx_out.decrypt_memo(&shared_secret)) {
+                Ok(MemoType::BurnRedemption(memo)) => Some(memo.memo_data().encode_hex()),
+                _ => None,
+            };
+
+        Ok(Self {
+            id: None,
+            block_index: block_index as i64,
+            token_id: *amount.token_id as i64,
+            amount: amount.value as i64,
+            public_key_hex: tx_out.public_key.encode_hex(),
+            burn_redemption_memo_hex,
+            protobuf: encode(tx_out),
+        })
+    }
+
+    /// Insert a new BurnTxOut into the database.
+    pub fn insert(&mut self, conn: &Conn) -> Result<(), Error> {
+        if let Some(id) = self.id {
+            return Err(Error::AlreadyExists(format!(
+                "BurnTxOut already has an id ({})",
+                id
+            )));
+        }
+        diesel::insert_into(burn_tx_outs::table)
+            .values(self.clone())
+            .execute(conn)?;
+
+        self.id = Some(diesel::select(last_insert_rowid).get_result::<i32>(conn)?);
+
+        Ok(())
+    }
+
+    /// Helper for inserting from a [mc_transaction_core::tx::TxOut] sent to
+    /// the burn address.
+    pub fn insert_from_core_tx_out(
+        block_index: BlockIndex,
+        tx_out: &TxOut,
+        conn: &Conn,
+    ) -> Result<Self, Error> {
+        let mut burn_tx_out = Self::from_core_tx_out(block_index, tx_out)?;
+        burn_tx_out.insert(conn)?;
+        Ok(burn_tx_out)
+    }
+
+    /// Attempt to find all [BurnTxOut]s that do not have a matching entry in
+    /// the `audited_burns` table.
+    pub fn find_unaudited_burn_tx_outs(conn: &Conn) -> Result<Vec<Self>, Error> {
+        Ok(burn_tx_outs::table
+            .filter(not(exists(
+                audited_burns::table
+                    .select(audited_burns::burn_tx_out_id)
+                    .filter(
+                        audited_burns::burn_tx_out_id
+                            .nullable()
+                            .eq(burn_tx_outs::id),
+                    ),
+            )))
+            .order_by(burn_tx_outs::id)
+            .load(conn)?)
+    }
+
+    /// Attempt to find a [BurnTxOut] that has a given public key and no
+    /// matching entry in the `audited_burns` table.
+    pub fn find_unaudited_burn_tx_out_by_public_key(
+        public_key_hex: &str,
+        conn: &Conn,
+    ) -> Result<Option<Self>, Error> {
+        Ok(burn_tx_outs::table
+            .filter(burn_tx_outs::public_key_hex.eq(public_key_hex))
+            .filter(not(exists(
+                audited_burns::table
+                    .select(audited_burns::burn_tx_out_id)
+                    .filter(
+                        audited_burns::burn_tx_out_id
+                            .nullable()
+                            .eq(burn_tx_outs::id),
+                    ),
+            )))
+            .first(conn)
+            .optional()?)
+    }
+
+    /// Get [BurnTxOut]s for a given block index.
+    pub fn get_burn_tx_outs_by_block_index(
+        block_index: BlockIndex,
+        conn: &Conn,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(burn_tx_outs::table
+            .filter(burn_tx_outs::block_index.eq(block_index as i64))
+            .order_by(burn_tx_outs::id)
+            .load(conn)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::{create_burn_tx_out, TestDbContext};
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::BlockVersion;
+    use mc_transaction_core_test_utils::create_test_tx_out;
+
+    #[test_with_logger]
+    fn test_insert_from_core_tx_out(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+        let token_id = TokenId::from(1);
+
+        let tx_out1 = create_burn_tx_out(token_id, 100, Some([7; 64]), &mut rng);
+        let tx_out2 = create_burn_tx_out(token_id, 200, None, &mut rng);
+
+        let burn_tx_out1 = BurnTxOut::insert_from_core_tx_out(5, &tx_out1, &conn).unwrap();
+        let burn_tx_out2 = BurnTxOut::insert_from_core_tx_out(5, &tx_out2, &conn).unwrap();
+
+        assert_eq!(burn_tx_out1.block_index(), 5);
+        assert_eq!(burn_tx_out1.token_id(), token_id);
+        assert_eq!(burn_tx_out1.amount(), 100);
+        assert_eq!(burn_tx_out1.public_key().unwrap(), tx_out1.public_key);
+        assert_eq!(
+            burn_tx_out1.burn_redemption_memo_hex(),
+            Some(hex::encode([7; 64]).as_str())
+        );
+        assert_eq!(burn_tx_out1.decode().unwrap(), tx_out1);
+
+        assert_eq!(burn_tx_out2.amount(), 200);
+        assert_eq!(burn_tx_out2.burn_redemption_memo_hex(), None);
+
+        assert_eq!(
+            BurnTxOut::get_burn_tx_outs_by_block_index(5, &conn).unwrap(),
+            vec![burn_tx_out1.clone(), burn_tx_out2]
+        );
+        assert_eq!(
+            BurnTxOut::find_unaudited_burn_tx_out_by_public_key(
+                &tx_out1.public_key.encode_hex::<String>(),
+                &conn
+            )
+            .unwrap(),
+            Some(burn_tx_out1)
+        );
+
+        // TxOuts that were not sent to the burn address are rejected.
+        let tx_out3 = create_test_tx_out(BlockVersion::MAX, &mut rng);
+        assert!(BurnTxOut::from_core_tx_out(5, &tx_out3).is_err());
+    }
+}
@@ -44,6 +44,15 @@ pub struct Counters {
 
     // Number of unexpected errors attempting to match mints to deposits.
     num_unexpected_errors_matching_mints_to_deposits: i64,
+
+    /// Number of mismatching BurnTxOuts and Gnosis withdrawals.
+    num_mismatching_burns_and_withdrawals: i64,
+
+    /// Number of unexpected errors attempting to match burns to withdrawals.
+    num_unexpected_errors_matching_burns_to_withdrawals: i64,
+
+    /// Number of unexpected errors attempting to match withdrawals to burns.
+    num_unexpected_errors_matching_withdrawals_to_burns: i64,
 }
 
 // A helper macro for DRYing up get/inc methods for each counter.
@@ -91,6 +100,9 @@ impl_get_and_inc! {
     num_mints_to_unknown_safe inc_num_mints_to_unknown_safe,
     num_unexpected_errors_matching_deposits_to_mints inc_num_unexpected_errors_matching_deposits_to_mints,
     num_unexpected_errors_matching_mints_to_deposits inc_num_unexpected_errors_matching_mints_to_deposits,
+    num_mismatching_burns_and_withdrawals inc_num_mismatching_burns_and_withdrawals,
+    num_unexpected_errors_matching_burns_to_withdrawals inc_num_unexpected_errors_matching_burns_to_withdrawals,
+    num_unexpected_errors_matching_withdrawals_to_burns inc_num_unexpected_errors_matching_withdrawals_to_burns,
 }
 
 impl Counters {
@@ -130,6 +142,12 @@ impl Counters {
             .set(self.num_unexpected_errors_matching_deposits_to_mints);
         prom_counters::NUM_UNEXPECTED_ERRORS_MATCHING_MINTS_TO_DEPOSITS
             .set(self.num_unexpected_errors_matching_mints_to_deposits);
+        prom_counters::NUM_MISMATCHING_BURNS_AND_WITHDRAWALS
+            .set(self.num_mismatching_burns_and_withdrawals);
+        prom_counters::NUM_UNEXPECTED_ERRORS_MATCHING_BURNS_TO_WITHDRAWALS
+            .set(self.num_unexpected_errors_matching_burns_to_withdrawals);
+        prom_counters::NUM_UNEXPECTED_ERRORS_MATCHING_WITHDRAWALS_TO_BURNS
+            .set(self.num_unexpected_errors_matching_withdrawals_to_burns);
     }
 }
 
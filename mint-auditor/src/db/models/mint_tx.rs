@@ -203,7 +203,7 @@ mod tests {
     use super::*;
     use crate::db::{
         models::AuditedMint,
-        test_utils::{create_gnosis_safe_deposit, insert_gnosis_deposit, TestDbContext},
+        test_utils::{create_reserve_deposit, insert_reserve_deposit, TestDbContext},
     };
     use mc_common::logger::{test_with_logger, Logger};
     use mc_transaction_core::TokenId;
@@ -237,8 +237,8 @@ mod tests {
         let conn = mint_auditor_db.get_conn().unwrap();
 
         // Create gnosis deposits.
-        let mut deposit1 = create_gnosis_safe_deposit(100, &mut rng);
-        let mut deposit2 = create_gnosis_safe_deposit(200, &mut rng);
+        let mut deposit1 = create_reserve_deposit(100, &mut rng);
+        let mut deposit2 = create_reserve_deposit(200, &mut rng);
 
         // Create two MintTxs.
         let (_mint_config_tx1, signers1) = create_mint_config_tx_and_signers(token_id1, &mut rng);
@@ -299,7 +299,7 @@ mod tests {
 
         // Insert a row to the `audited_mints` table marking the first MintTx as
         // audited. We should no longer be able to find it.
-        insert_gnosis_deposit(&mut deposit1, &conn);
+        insert_reserve_deposit(&mut deposit1, &conn);
         AuditedMint::associate_deposit_with_mint(
             deposit1.id().unwrap(),
             sql_mint_tx1.id().unwrap(),
@@ -322,7 +322,7 @@ mod tests {
         );
 
         // Mark the second mint as audited. We should no longer be able to find it.
-        insert_gnosis_deposit(&mut deposit2, &conn);
+        insert_reserve_deposit(&mut deposit2, &conn);
         AuditedMint::associate_deposit_with_mint(
             deposit2.id().unwrap(),
             sql_mint_tx2.id().unwrap(),
@@ -1,27 +1,24 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
+mod audited_burn;
 mod audited_mint;
 mod block_audit_data;
 mod block_balance;
+mod block_reserve_cutoff;
+mod burn_tx_out;
 mod counters;
-mod gnosis_safe_deposit;
-mod gnosis_safe_tx;
-mod gnosis_safe_withdrawal;
 mod mint_config;
 mod mint_config_tx;
 mod mint_tx;
-mod sql_types;
+mod reserve_deposit;
+mod reserve_sync_cursor;
+mod reserve_tx;
+mod reserve_withdrawal;
 
 pub use self::{
-    audited_mint::AuditedMint,
-    block_audit_data::BlockAuditData,
-    block_balance::BlockBalance,
-    counters::Counters,
-    gnosis_safe_deposit::GnosisSafeDeposit,
-    gnosis_safe_tx::GnosisSafeTx,
-    gnosis_safe_withdrawal::GnosisSafeWithdrawal,
-    mint_config::MintConfig,
-    mint_config_tx::MintConfigTx,
-    mint_tx::MintTx,
-    sql_types::{SqlEthAddr, SqlEthTxHash},
+    audited_burn::AuditedBurn, audited_mint::AuditedMint, block_audit_data::BlockAuditData,
+    block_balance::BlockBalance, block_reserve_cutoff::BlockReserveCutoff, burn_tx_out::BurnTxOut,
+    counters::Counters, mint_config::MintConfig, mint_config_tx::MintConfigTx, mint_tx::MintTx,
+    reserve_deposit::ReserveDeposit, reserve_sync_cursor::ReserveSyncCursor, reserve_tx::ReserveTx,
+    reserve_withdrawal::ReserveWithdrawal,
 };
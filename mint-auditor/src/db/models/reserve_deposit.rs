@@ -3,40 +3,40 @@
 use crate::{
     db::{
         last_insert_rowid,
-        models::{SqlEthAddr, SqlEthTxHash},
-        schema::{audited_mints, gnosis_safe_deposits},
-        Conn,
+        schema::{audited_mints, mint_txs, reserve_deposits},
+        BlockReserveCutoff, Conn,
     },
     error::Error,
-    gnosis::{EthAddr, EthTxHash},
-    MintTxNonce,
 };
 use diesel::{
     dsl::{exists, not},
     prelude::*,
 };
+use mc_transaction_core::mint::constants::NONCE_LENGTH;
 use serde::{Deserialize, Serialize};
 
-/// Diesel model for the `gnosis_safe_deposits` table.
-/// This table stores deposits into the monitored gnosis safe.
+/// Diesel model for the `reserve_deposits` table.
+/// This table stores deposits into the audited reserves.
 #[derive(
     Clone, Debug, Default, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize,
 )]
-pub struct GnosisSafeDeposit {
+pub struct ReserveDeposit {
     /// Auto incrementing primary key.
     id: Option<i32>,
 
-    /// Ethereum transaction hash.
-    eth_tx_hash: SqlEthTxHash,
+    /// The reserve receiving the deposit.
+    reserve_id: String,
 
-    /// Ethereum block number.
-    eth_block_number: i64,
+    /// Id of the reserve transaction that contains the deposit.
+    tx_id: String,
 
-    /// Gnosis safe address receiving the deposit.
-    safe_addr: SqlEthAddr,
+    /// Position of the transaction in the reserve's history (e.g. an Ethereum
+    /// block number).
+    position: i64,
 
-    /// Token contract address that is being deposited.
-    token_addr: SqlEthAddr,
+    /// The asset that is being deposited (e.g. an Ethereum token contract
+    /// address).
+    asset: String,
 
     /// Amount deposited.
     amount: i64,
@@ -46,26 +46,25 @@ pub struct GnosisSafeDeposit {
     expected_mc_mint_tx_nonce_hex: String,
 }
 
-impl GnosisSafeDeposit {
-    /// Construct a new [GnosisSafeDeposit] object.
+impl ReserveDeposit {
+    /// Construct a new [ReserveDeposit] object.
     pub fn new(
         id: Option<i32>,
-        eth_tx_hash: EthTxHash,
-        eth_block_number: u64,
-        safe_addr: EthAddr,
-        token_addr: EthAddr,
+        reserve_id: String,
+        tx_id: String,
+        position: u64,
+        asset: String,
         amount: u64,
+        expected_mc_mint_tx_nonce: [u8; NONCE_LENGTH],
     ) -> Self {
-        let expected_mc_mint_tx_nonce = MintTxNonce::EthereumGnosisDeposit(eth_tx_hash);
-        let expected_mc_mint_tx_nonce_hex = hex::encode(expected_mc_mint_tx_nonce.to_bytes());
         Self {
             id,
-            eth_tx_hash: eth_tx_hash.into(),
-            eth_block_number: eth_block_number as i64,
-            safe_addr: safe_addr.into(),
-            token_addr: token_addr.into(),
+            reserve_id,
+            tx_id,
+            position: position as i64,
+            asset,
             amount: amount as i64,
-            expected_mc_mint_tx_nonce_hex,
+            expected_mc_mint_tx_nonce_hex: hex::encode(expected_mc_mint_tx_nonce),
         }
     }
 
@@ -74,24 +73,24 @@ impl GnosisSafeDeposit {
         self.id
     }
 
-    /// Get Ethereum transaction hash.
-    pub fn eth_tx_hash(&self) -> &EthTxHash {
-        &self.eth_tx_hash
+    /// Get the reserve id.
+    pub fn reserve_id(&self) -> &str {
+        &self.reserve_id
     }
 
-    /// Get ethereum block number.
-    pub fn eth_block_number(&self) -> u64 {
-        self.eth_block_number as u64
+    /// Get the reserve transaction id.
+    pub fn tx_id(&self) -> &str {
+        &self.tx_id
     }
 
-    /// Get safe address.
-    pub fn safe_addr(&self) -> &EthAddr {
-        &self.safe_addr
+    /// Get the position of the transaction in the reserve's history.
+    pub fn position(&self) -> u64 {
+        self.position as u64
     }
 
-    /// Get token address.
-    pub fn token_addr(&self) -> &EthAddr {
-        &self.token_addr
+    /// Get the asset deposited.
+    pub fn asset(&self) -> &str {
+        &self.asset
     }
 
     /// Get amount deposited.
@@ -109,12 +108,12 @@ impl GnosisSafeDeposit {
     pub fn insert(&mut self, conn: &Conn) -> Result<(), Error> {
         if let Some(id) = self.id {
             return Err(Error::AlreadyExists(format!(
-                "GnosisSafeDeposit already has an id ({})",
+                "ReserveDeposit already has an id ({})",
                 id
             )));
         }
 
-        diesel::insert_into(gnosis_safe_deposits::table)
+        diesel::insert_into(reserve_deposits::table)
             .values(self.clone())
             .execute(conn)?;
 
@@ -123,37 +122,66 @@ impl GnosisSafeDeposit {
         Ok(())
     }
 
-    /// Attempt to find all [GnosisSafeDeposit]s that do not have a matching
+    /// Attempt to find all [ReserveDeposit]s that do not have a matching
     /// entry in the `audited_mints` table.
     pub fn find_unaudited_deposits(conn: &Conn) -> Result<Vec<Self>, Error> {
-        Ok(gnosis_safe_deposits::table
+        Ok(reserve_deposits::table
             .filter(not(exists(
                 audited_mints::table
-                    .select(audited_mints::gnosis_safe_deposit_id)
+                    .select(audited_mints::reserve_deposit_id)
                     .filter(
-                        audited_mints::gnosis_safe_deposit_id
+                        audited_mints::reserve_deposit_id
                             .nullable()
-                            .eq(gnosis_safe_deposits::id),
+                            .eq(reserve_deposits::id),
                     ),
             )))
             .load(conn)?)
     }
 
-    /// Attempt to find a [GnosisSafeDeposit] that has a given nonce and no
+    /// Attempt to find all [ReserveDeposit]s that had been synced when the
+    /// block of a given [BlockReserveCutoff] was audited, and that are not
+    /// matched with a [crate::db::MintTx] at or before that block.
+    pub fn find_unaudited_deposits_at_block(
+        cutoff: &BlockReserveCutoff,
+        conn: &Conn,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(reserve_deposits::table
+            .filter(reserve_deposits::id.le(cutoff.last_reserve_deposit_id()))
+            .filter(not(exists(
+                audited_mints::table
+                    .select(audited_mints::reserve_deposit_id)
+                    .filter(
+                        audited_mints::reserve_deposit_id
+                            .nullable()
+                            .eq(reserve_deposits::id),
+                    )
+                    .filter(
+                        audited_mints::mint_tx_id.nullable().eq_any(
+                            mint_txs::table
+                                .select(mint_txs::id)
+                                .filter(mint_txs::block_index.le(cutoff.block_index() as i64)),
+                        ),
+                    ),
+            )))
+            .order_by(reserve_deposits::id)
+            .load(conn)?)
+    }
+
+    /// Attempt to find a [ReserveDeposit] that has a given nonce and no
     /// matching entry in the `audited_mints` table.
     pub fn find_unaudited_deposit_by_nonce(
         nonce_hex: &str,
         conn: &Conn,
     ) -> Result<Option<Self>, Error> {
-        Ok(gnosis_safe_deposits::table
-            .filter(gnosis_safe_deposits::expected_mc_mint_tx_nonce_hex.eq(nonce_hex))
+        Ok(reserve_deposits::table
+            .filter(reserve_deposits::expected_mc_mint_tx_nonce_hex.eq(nonce_hex))
             .filter(not(exists(
                 audited_mints::table
-                    .select(audited_mints::gnosis_safe_deposit_id)
+                    .select(audited_mints::reserve_deposit_id)
                     .filter(
-                        audited_mints::gnosis_safe_deposit_id
+                        audited_mints::reserve_deposit_id
                             .nullable()
-                            .eq(gnosis_safe_deposits::id),
+                            .eq(reserve_deposits::id),
                     ),
             )))
             .first(conn)
@@ -166,7 +194,7 @@ mod tests {
     use super::*;
     use crate::db::{
         models::{AuditedMint, MintTx},
-        test_utils::{create_gnosis_safe_deposit, insert_gnosis_deposit, TestDbContext},
+        test_utils::{create_reserve_deposit, insert_reserve_deposit, TestDbContext},
     };
     use mc_common::logger::{test_with_logger, Logger};
     use mc_transaction_core::TokenId;
@@ -180,9 +208,9 @@ mod tests {
         let token_id1 = TokenId::from(1);
         let conn = mint_auditor_db.get_conn().unwrap();
 
-        // Create gnosis deposits.
-        let mut deposit1 = create_gnosis_safe_deposit(100, &mut rng);
-        let mut deposit2 = create_gnosis_safe_deposit(200, &mut rng);
+        // Create reserve deposits.
+        let mut deposit1 = create_reserve_deposit(100, &mut rng);
+        let mut deposit2 = create_reserve_deposit(200, &mut rng);
 
         let nonce1 = deposit1.expected_mc_mint_tx_nonce_hex().to_string();
         let nonce2 = deposit2.expected_mc_mint_tx_nonce_hex().to_string();
@@ -200,44 +228,44 @@ mod tests {
 
         // Since they haven't been inserted yet, they should not be found.
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
                 .unwrap()
                 .is_none()
         );
 
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
                 .unwrap()
                 .is_none()
         );
 
         // Insert the first deposit, it should now be found.
-        insert_gnosis_deposit(&mut deposit1, &conn);
+        insert_reserve_deposit(&mut deposit1, &conn);
 
         assert_eq!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
                 .unwrap()
                 .unwrap(),
             deposit1
         );
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
                 .unwrap()
                 .is_none()
         );
 
         // Insert the second deposit, they should both be found.
-        insert_gnosis_deposit(&mut deposit2, &conn);
+        insert_reserve_deposit(&mut deposit2, &conn);
 
         assert_eq!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
                 .unwrap()
                 .unwrap(),
             deposit1,
         );
 
         assert_eq!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
                 .unwrap()
                 .unwrap(),
             deposit2,
@@ -248,7 +276,7 @@ mod tests {
         let audited_mint = AuditedMint {
             id: None,
             mint_tx_id: sql_mint_tx1.id().unwrap(),
-            gnosis_safe_deposit_id: deposit1.id().unwrap(),
+            reserve_deposit_id: deposit1.id().unwrap(),
         };
         diesel::insert_into(audited_mints::table)
             .values(audited_mint)
@@ -256,13 +284,13 @@ mod tests {
             .unwrap();
 
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
                 .unwrap()
                 .is_none()
         );
 
         assert_eq!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
                 .unwrap()
                 .unwrap(),
             deposit2,
@@ -272,7 +300,7 @@ mod tests {
         let audited_mint = AuditedMint {
             id: None,
             mint_tx_id: sql_mint_tx2.id().unwrap(),
-            gnosis_safe_deposit_id: deposit2.id().unwrap(),
+            reserve_deposit_id: deposit2.id().unwrap(),
         };
         diesel::insert_into(audited_mints::table)
             .values(audited_mint)
@@ -280,13 +308,13 @@ mod tests {
             .unwrap();
 
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce1, &conn)
                 .unwrap()
                 .is_none()
         );
 
         assert!(
-            GnosisSafeDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
+            ReserveDeposit::find_unaudited_deposit_by_nonce(&nonce2, &conn)
                 .unwrap()
                 .is_none()
         );
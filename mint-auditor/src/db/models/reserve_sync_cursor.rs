@@ -0,0 +1,48 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{
+    db::{schema::reserve_sync_cursors, Conn},
+    error::Error,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `reserve_sync_cursors` table.
+/// This table stores, for each audited reserve, where its source should resume
+/// fetching transactions from.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize,
+)]
+pub struct ReserveSyncCursor {
+    /// The reserve id.
+    pub reserve_id: String,
+
+    /// The cursor, in a format specific to the reserve source.
+    pub cursor: i64,
+}
+
+impl ReserveSyncCursor {
+    /// Get the stored cursor of a reserve, if there is one.
+    pub fn get(reserve_id: &str, conn: &Conn) -> Result<Option<u64>, Error> {
+        Ok(reserve_sync_cursors::table
+            .filter(reserve_sync_cursors::reserve_id.eq(reserve_id))
+            .select(reserve_sync_cursors::cursor)
+            .first::<i64>(conn)
+            .optional()?
+            .map(|cursor| cursor as u64))
+    }
+
+    /// Store the cursor of a reserve, replacing any previous value.
+    pub fn set(reserve_id: &str, cursor: u64, conn: &Conn) -> Result<(), Error> {
+        let obj = Self {
+            reserve_id: reserve_id.to_string(),
+            cursor: cursor as i64,
+        };
+
+        diesel::replace_into(reserve_sync_cursors::table)
+            .values(obj)
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{
+    db::{schema::reserve_txs, Conn},
+    error::Error,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `reserve_txs` table.
+/// This table stores the transactions fetched from the audited reserves.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize,
+)]
+pub struct ReserveTx {
+    /// The reserve this transaction was fetched for.
+    pub reserve_id: String,
+
+    /// Id of the transaction, unique within the reserve (e.g. an Ethereum
+    /// transaction hash).
+    pub tx_id: String,
+
+    /// The JSON representation of the transaction, in a format specific to
+    /// the reserve source it was fetched from.
+    pub raw_tx_json: String,
+}
+
+impl ReserveTx {
+    /// Construct a new [ReserveTx] object.
+    pub fn new(reserve_id: String, tx_id: String, raw_tx_json: String) -> Self {
+        Self {
+            reserve_id,
+            tx_id,
+            raw_tx_json,
+        }
+    }
+
+    /// Insert a reserve transaction into the database.
+    pub fn insert(&self, conn: &Conn) -> Result<(), Error> {
+        diesel::insert_into(reserve_txs::table)
+            .values(self)
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
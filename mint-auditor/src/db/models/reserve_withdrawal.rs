@@ -0,0 +1,196 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use crate::{
+    db::{
+        last_insert_rowid,
+        schema::{audited_burns, burn_tx_outs, reserve_withdrawals},
+        BlockReserveCutoff, Conn,
+    },
+    error::Error,
+};
+use diesel::{
+    dsl::{exists, not},
+    prelude::*,
+};
+use mc_crypto_keys::CompressedRistrettoPublic;
+use serde::{Deserialize, Serialize};
+
+/// Diesel model for the `reserve_withdrawals` table.
+/// This table stores withdrawals out of the audited reserves.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, Insertable, PartialEq, Queryable, Serialize,
+)]
+pub struct ReserveWithdrawal {
+    /// Id (required to keep Diesel happy).
+    id: Option<i32>,
+
+    /// The reserve being withdrawn from.
+    reserve_id: String,
+
+    /// Id of the reserve transaction that contains the withdrawal.
+    tx_id: String,
+
+    /// Position of the transaction in the reserve's history (e.g. an Ethereum
+    /// block number).
+    position: i64,
+
+    /// The asset that is being withdrawn (e.g. an Ethereum token contract
+    /// address).
+    asset: String,
+
+    /// Amount withdrawan.
+    amount: i64,
+
+    /// Associated mobilecoin transaction public key (hex-encoded).
+    mc_tx_out_public_key_hex: String,
+}
+
+impl ReserveWithdrawal {
+    /// Construct a new [ReserveWithdrawal] object.
+    pub fn new(
+        id: Option<i32>,
+        reserve_id: String,
+        tx_id: String,
+        position: u64,
+        asset: String,
+        amount: u64,
+        mc_tx_out_public_key_hex: String,
+    ) -> Self {
+        Self {
+            id,
+            reserve_id,
+            tx_id,
+            position: position as i64,
+            asset,
+            amount: amount as i64,
+            mc_tx_out_public_key_hex,
+        }
+    }
+
+    /// Get id.
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Get the reserve id.
+    pub fn reserve_id(&self) -> &str {
+        &self.reserve_id
+    }
+
+    /// Get the reserve transaction id.
+    pub fn tx_id(&self) -> &str {
+        &self.tx_id
+    }
+
+    /// Get the position of the transaction in the reserve's history.
+    pub fn position(&self) -> u64 {
+        self.position as u64
+    }
+
+    /// Get the asset withdrawn.
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
+
+    /// Get amount withdrawan.
+    pub fn amount(&self) -> u64 {
+        self.amount as u64
+    }
+
+    /// Get associated mobilecoin transaction public key (hex-encoded).
+    pub fn mc_tx_out_public_key_hex(&self) -> &str {
+        &self.mc_tx_out_public_key_hex
+    }
+
+    /// Get the associated mobilecoin transaction public key
+    pub fn mc_tx_out_public_key(&self) -> Result<CompressedRistrettoPublic, Error> {
+        let key_bytes = hex::decode(&self.mc_tx_out_public_key_hex)?;
+        Ok(CompressedRistrettoPublic::try_from(&key_bytes[..])?)
+    }
+
+    /// Insert a withdrawal into the database.
+    pub fn insert(&mut self, conn: &Conn) -> Result<(), Error> {
+        if let Some(id) = self.id {
+            return Err(Error::AlreadyExists(format!(
+                "ReserveWithdrawal already has an id ({})",
+                id
+            )));
+        }
+
+        diesel::insert_into(reserve_withdrawals::table)
+            .values(self.clone())
+            .execute(conn)?;
+
+        self.id = Some(diesel::select(last_insert_rowid).get_result::<i32>(conn)?);
+
+        Ok(())
+    }
+
+    /// Attempt to find all [ReserveWithdrawal]s that do not have a matching
+    /// entry in the `audited_burns` table.
+    pub fn find_unaudited_withdrawals(conn: &Conn) -> Result<Vec<Self>, Error> {
+        Ok(reserve_withdrawals::table
+            .filter(not(exists(
+                audited_burns::table
+                    .select(audited_burns::reserve_withdrawal_id)
+                    .filter(
+                        audited_burns::reserve_withdrawal_id
+                            .nullable()
+                            .eq(reserve_withdrawals::id),
+                    ),
+            )))
+            .order_by(reserve_withdrawals::id)
+            .load(conn)?)
+    }
+
+    /// Attempt to find all [ReserveWithdrawal]s that had been synced when the
+    /// block of a given [BlockReserveCutoff] was audited, and that are not
+    /// matched with a [crate::db::BurnTxOut] at or before that block.
+    pub fn find_unaudited_withdrawals_at_block(
+        cutoff: &BlockReserveCutoff,
+        conn: &Conn,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(reserve_withdrawals::table
+            .filter(reserve_withdrawals::id.le(cutoff.last_reserve_withdrawal_id()))
+            .filter(not(exists(
+                audited_burns::table
+                    .select(audited_burns::reserve_withdrawal_id)
+                    .filter(
+                        audited_burns::reserve_withdrawal_id
+                            .nullable()
+                            .eq(reserve_withdrawals::id),
+                    )
+                    .filter(
+                        audited_burns::burn_tx_out_id.nullable().eq_any(
+                            burn_tx_outs::table
+                                .select(burn_tx_outs::id)
+                                .filter(burn_tx_outs::block_index.le(cutoff.block_index() as i64)),
+                        ),
+                    ),
+            )))
+            .order_by(reserve_withdrawals::id)
+            .load(conn)?)
+    }
+
+    /// Attempt to find a [ReserveWithdrawal] that carries a given
+    /// MobileCoin TxOut public key and has no matching entry in the
+    /// `audited_burns` table.
+    pub fn find_unaudited_withdrawal_by_public_key(
+        mc_tx_out_public_key_hex: &str,
+        conn: &Conn,
+    ) -> Result<Option<Self>, Error> {
+        Ok(reserve_withdrawals::table
+            .filter(reserve_withdrawals::mc_tx_out_public_key_hex.eq(mc_tx_out_public_key_hex))
+            .filter(not(exists(
+                audited_burns::table
+                    .select(audited_burns::reserve_withdrawal_id)
+                    .filter(
+                        audited_burns::reserve_withdrawal_id
+                            .nullable()
+                            .eq(reserve_withdrawals::id),
+                    ),
+            )))
+            .first(conn)
+            .optional()?)
+    }
+}
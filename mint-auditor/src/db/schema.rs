@@ -2,11 +2,19 @@
 // Run `diesel migration run` to update (inside the mint-auditor directory)
 #![allow(missing_docs)]
 
+table! {
+    audited_burns (id) {
+        id -> Nullable<Integer>,
+        burn_tx_out_id -> Integer,
+        reserve_withdrawal_id -> Integer,
+    }
+}
+
 table! {
     audited_mints (id) {
         id -> Nullable<Integer>,
         mint_tx_id -> Integer,
-        gnosis_safe_deposit_id -> Integer,
+        reserve_deposit_id -> Integer,
     }
 }
 
@@ -27,47 +35,39 @@ table! {
 }
 
 table! {
-    counters (id) {
-        id -> Integer,
-        num_blocks_synced -> BigInt,
-        num_burns_exceeding_balance -> BigInt,
-        num_mint_txs_without_matching_mint_config -> BigInt,
-        num_mismatching_mints_and_deposits -> BigInt,
-        num_unknown_ethereum_token_deposits -> BigInt,
-        num_mints_to_unknown_safe -> BigInt,
-        num_unexpected_errors_matching_deposits_to_mints -> BigInt,
-        num_unexpected_errors_matching_mints_to_deposits -> BigInt,
+    block_reserve_cutoffs (block_index) {
+        block_index -> BigInt,
+        last_reserve_deposit_id -> Integer,
+        last_reserve_withdrawal_id -> Integer,
     }
 }
 
 table! {
-    gnosis_safe_deposits (id) {
+    burn_tx_outs (id) {
         id -> Nullable<Integer>,
-        eth_tx_hash -> Text,
-        eth_block_number -> BigInt,
-        safe_addr -> Text,
-        token_addr -> Text,
+        block_index -> BigInt,
+        token_id -> BigInt,
         amount -> BigInt,
-        expected_mc_mint_tx_nonce_hex -> Text,
-    }
-}
-
-table! {
-    gnosis_safe_txs (eth_tx_hash) {
-        eth_tx_hash -> Text,
-        raw_tx_json -> Text,
+        public_key_hex -> Text,
+        burn_redemption_memo_hex -> Nullable<Text>,
+        protobuf -> Binary,
     }
 }
 
 table! {
-    gnosis_safe_withdrawals (id) {
-        id -> Nullable<Integer>,
-        eth_tx_hash -> Text,
-        eth_block_number -> BigInt,
-        safe_addr -> Text,
-        token_addr -> Text,
-        amount -> BigInt,
-        mc_tx_out_public_key_hex -> Text,
+    counters (id) {
+        id -> Integer,
+        num_blocks_synced -> BigInt,
+        num_burns_exceeding_balance -> BigInt,
+        num_mint_txs_without_matching_mint_config -> BigInt,
+        num_mismatching_mints_and_deposits -> BigInt,
+        num_unknown_ethereum_token_deposits -> BigInt,
+        num_mints_to_unknown_safe -> BigInt,
+        num_unexpected_errors_matching_deposits_to_mints -> BigInt,
+        num_unexpected_errors_matching_mints_to_deposits -> BigInt,
+        num_mismatching_burns_and_withdrawals -> BigInt,
+        num_unexpected_errors_matching_burns_to_withdrawals -> BigInt,
+        num_unexpected_errors_matching_withdrawals_to_burns -> BigInt,
     }
 }
 
@@ -106,22 +106,65 @@ table! {
     }
 }
 
-joinable!(audited_mints -> gnosis_safe_deposits (gnosis_safe_deposit_id));
+table! {
+    reserve_deposits (id) {
+        id -> Nullable<Integer>,
+        reserve_id -> Text,
+        tx_id -> Text,
+        position -> BigInt,
+        asset -> Text,
+        amount -> BigInt,
+        expected_mc_mint_tx_nonce_hex -> Text,
+    }
+}
+
+table! {
+    reserve_sync_cursors (reserve_id) {
+        reserve_id -> Text,
+        cursor -> BigInt,
+    }
+}
+
+table! {
+    reserve_txs (reserve_id, tx_id) {
+        reserve_id -> Text,
+        tx_id -> Text,
+        raw_tx_json -> Text,
+    }
+}
+
+table! {
+    reserve_withdrawals (id) {
+        id -> Nullable<Integer>,
+        reserve_id -> Text,
+        tx_id -> Text,
+        position -> BigInt,
+        asset -> Text,
+        amount -> BigInt,
+        mc_tx_out_public_key_hex -> Text,
+    }
+}
+
+joinable!(audited_burns -> burn_tx_outs (burn_tx_out_id));
+joinable!(audited_burns -> reserve_withdrawals (reserve_withdrawal_id));
 joinable!(audited_mints -> mint_txs (mint_tx_id));
-joinable!(gnosis_safe_deposits -> gnosis_safe_txs (eth_tx_hash));
-joinable!(gnosis_safe_withdrawals -> gnosis_safe_txs (eth_tx_hash));
+joinable!(audited_mints -> reserve_deposits (reserve_deposit_id));
 joinable!(mint_configs -> mint_config_txs (mint_config_tx_id));
 joinable!(mint_txs -> mint_configs (mint_config_id));
 
 allow_tables_to_appear_in_same_query!(
+    audited_burns,
     audited_mints,
     block_audit_data,
     block_balance,
+    block_reserve_cutoffs,
+    burn_tx_outs,
     counters,
-    gnosis_safe_deposits,
-    gnosis_safe_txs,
-    gnosis_safe_withdrawals,
     mint_config_txs,
     mint_configs,
     mint_txs,
+    reserve_deposits,
+    reserve_sync_cursors,
+    reserve_txs,
+    reserve_withdrawals,
 );
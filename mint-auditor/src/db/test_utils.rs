@@ -1,17 +1,19 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use crate::{
-    db::{Conn, GnosisSafeDeposit, GnosisSafeTx, MintAuditorDb, MintTx},
-    gnosis::{
-        api_data_types::RawGnosisTransaction, AuditedSafeConfig, AuditedToken, EthAddr, EthTxHash,
-        GnosisSafeConfig,
-    },
+    db::{Conn, MintAuditorDb, MintTx, ReserveDeposit, ReserveTx, ReserveWithdrawal},
+    gnosis::{AuditedSafeConfig, AuditedToken, EthAddr, EthTxHash, GnosisSafeConfig},
+    reserve::ReserveConfig,
+    MintTxNonce,
 };
+use hex::ToHex;
+use mc_account_keys::burn_address;
 use mc_common::logger::Logger;
-use mc_transaction_core::TokenId;
+use mc_crypto_keys::RistrettoPrivate;
+use mc_transaction_core::{tx::TxOut, Amount, BlockVersion, TokenId};
 use mc_transaction_core_test_utils::{create_mint_config_tx_and_signers, create_mint_tx};
+use mc_transaction_std::{BurnRedemptionMemo, MemoPayload};
 use mc_util_from_random::{CryptoRng, FromRandom, RngCore};
-use serde_json::json;
 use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use url::Url;
@@ -37,6 +39,10 @@ pub fn test_gnosis_config() -> GnosisSafeConfig {
     }
 }
 
+pub fn test_reserve_config() -> ReserveConfig {
+    ReserveConfig::from(test_gnosis_config())
+}
+
 pub struct TestDbContext {
     // Kept here to avoid the temp directory being deleted.
     _temp_dir: TempDir,
@@ -66,33 +72,36 @@ impl TestDbContext {
     }
 }
 
-/// Insert a mock GnosisSafeTx that has a specific tx hash
-pub fn insert_gnosis_deposit(deposit: &mut GnosisSafeDeposit, conn: &Conn) {
-    let raw_tx = RawGnosisTransaction::from(json!({
-        "txHash": deposit.eth_tx_hash(),
-    }));
-    GnosisSafeTx::insert(&raw_tx, conn).unwrap();
+/// Insert a mock ReserveTx that has a specific tx id, followed by the deposit
+/// itself.
+pub fn insert_reserve_deposit(deposit: &mut ReserveDeposit, conn: &Conn) {
+    ReserveTx::new(
+        deposit.reserve_id().to_string(),
+        deposit.tx_id().to_string(),
+        "{}".to_string(),
+    )
+    .insert(conn)
+    .unwrap();
     deposit.insert(conn).unwrap();
 }
 
-/// Create a GnosisSafeDeposit used for testing.
-pub fn create_gnosis_safe_deposit(
-    amount: u64,
-    rng: &mut (impl CryptoRng + RngCore),
-) -> GnosisSafeDeposit {
-    GnosisSafeDeposit::new(
+/// Create a ReserveDeposit into the test Gnosis safe used for testing.
+pub fn create_reserve_deposit(amount: u64, rng: &mut (impl CryptoRng + RngCore)) -> ReserveDeposit {
+    let tx_hash = EthTxHash::from_random(rng);
+    ReserveDeposit::new(
         None,
-        EthTxHash::from_random(rng),
+        SAFE_ADDR.to_lowercase(),
+        tx_hash.to_string(),
         1,
-        EthAddr::from_str(SAFE_ADDR).unwrap(),
-        EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+        ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
         amount,
+        MintTxNonce::EthereumGnosisDeposit(tx_hash).to_bytes(),
     )
 }
 
-/// Create a MintTx that matches a GnosisSafeDeposit.
+/// Create a MintTx that matches a ReserveDeposit.
 pub fn insert_mint_tx_from_deposit(
-    deposit: &GnosisSafeDeposit,
+    deposit: &ReserveDeposit,
     conn: &Conn,
     rng: &mut (impl CryptoRng + RngCore),
 ) -> MintTx {
@@ -104,3 +113,64 @@ pub fn insert_mint_tx_from_deposit(
     mint_tx.prefix.nonce = hex::decode(&deposit.expected_mc_mint_tx_nonce_hex()).unwrap();
     MintTx::insert_from_core_mint_tx(0, None, &mint_tx, conn).unwrap()
 }
+
+/// Insert a mock ReserveTx that has a specific tx id, followed by the
+/// withdrawal itself.
+pub fn insert_reserve_withdrawal(withdrawal: &mut ReserveWithdrawal, conn: &Conn) {
+    ReserveTx::new(
+        withdrawal.reserve_id().to_string(),
+        withdrawal.tx_id().to_string(),
+        "{}".to_string(),
+    )
+    .insert(conn)
+    .unwrap();
+    withdrawal.insert(conn).unwrap();
+}
+
+/// Create a TxOut sent to the burn address, optionally carrying a
+/// BurnRedemptionMemo.
+pub fn create_burn_tx_out(
+    token_id: TokenId,
+    amount: u64,
+    memo_data: Option<[u8; BurnRedemptionMemo::MEMO_DATA_LEN]>,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> TxOut {
+    let amount = Amount::new(amount, token_id);
+    let tx_private_key = RistrettoPrivate::from_random(rng);
+    match memo_data {
+        Some(memo_data) => TxOut::new_with_memo(
+            BlockVersion::MAX,
+            amount,
+            &burn_address(),
+            &tx_private_key,
+            Default::default(),
+            |_| Ok(MemoPayload::from(BurnRedemptionMemo::new(memo_data))),
+        ),
+        None => TxOut::new(
+            BlockVersion::MAX,
+            amount,
+            &burn_address(),
+            &tx_private_key,
+            Default::default(),
+        ),
+    }
+    .unwrap()
+}
+
+/// Create a ReserveWithdrawal out of the test Gnosis safe that matches a burn
+/// TxOut.
+pub fn create_reserve_withdrawal(
+    tx_out: &TxOut,
+    amount: u64,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> ReserveWithdrawal {
+    ReserveWithdrawal::new(
+        None,
+        SAFE_ADDR.to_lowercase(),
+        EthTxHash::from_random(rng).to_string(),
+        1,
+        ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+        amount,
+        tx_out.public_key.encode_hex(),
+    )
+}
@@ -2,10 +2,7 @@
 
 //! Mint auditor error data type.
 
-use crate::{
-    db::TransactionRetriableError,
-    gnosis::{Error as GnosisError, EthAddr, EthTxHash},
-};
+use crate::{db::TransactionRetriableError, gnosis::Error as GnosisError};
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use diesel_migrations::RunMigrationsError;
 use displaydoc::Display;
@@ -32,11 +29,14 @@ pub enum Error {
     /// Deposit and mint mismatch: {0}
     DepositAndMintMismatch(String),
 
-    /// Ethereum token {0} not audited in safe {1} (tx hash: {2})
-    EthereumTokenNotAudited(EthAddr, EthAddr, EthTxHash),
+    /// Burn and withdrawal mismatch: {0}
+    BurnAndWithdrawalMismatch(String),
 
-    /// Gnosis safe {0} not audited
-    GnosisSafeNotAudited(EthAddr),
+    /// Asset {0} not audited in reserve {1} (tx id: {2})
+    ReserveAssetNotAudited(String, String, String),
+
+    /// Reserve {0} not audited
+    ReserveNotAudited(String),
 
     /// IO: {0}
     Io(IoError),
@@ -77,6 +77,9 @@ pub enum Error {
     /// Invalid nonce identifier: {0:?}
     InvalidNonceIdentifier(Vec<u8>),
 
+    /// Report verification failed: {0}
+    ReportVerification(String),
+
     /// Other: {0}
     Other(String),
 }
@@ -6,8 +6,7 @@ mod config;
 mod error;
 mod eth_data_types;
 mod fetcher;
-mod sync;
-mod sync_thread;
+mod source;
 
 pub mod api_data_types;
 
@@ -15,6 +14,5 @@ pub use self::{
     config::{AuditedSafeConfig, AuditedToken, GnosisSafeConfig},
     error::Error,
     eth_data_types::{EthAddr, EthTxHash},
-    sync::GnosisSync,
-    sync_thread::GnosisSyncThread,
+    source::GnosisSafeSource,
 };
@@ -0,0 +1,333 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A [ReserveSource] for Gnosis safes, backed by a Gnosis
+//! safe-transaction-service.
+
+use super::{
+    api_data_types::{EthereumTransaction, MultiSigTransaction, RawGnosisTransaction, Transaction},
+    fetcher::GnosisSafeFetcher,
+    AuditedSafeConfig, Error,
+};
+use crate::{
+    db::{ReserveDeposit, ReserveTx, ReserveWithdrawal},
+    reserve::{ReserveCursor, ReserveEvents, ReserveSource, ReserveTransactions},
+    MintTxNonce,
+};
+use mc_common::logger::{log, Logger};
+
+/// A [ReserveSource] for Gnosis safes, backed by a Gnosis
+/// safe-transaction-service.
+pub struct GnosisSafeSource {
+    fetcher: GnosisSafeFetcher,
+    audited_safe: AuditedSafeConfig,
+    reserve_id: String,
+    logger: Logger,
+}
+
+impl GnosisSafeSource {
+    /// Instantiate a new [GnosisSafeSource] fetching transactions from the
+    /// safe-transaction-service API endpoint of the audited safe.
+    pub fn new(audited_safe: AuditedSafeConfig, logger: Logger) -> Result<Self, Error> {
+        Ok(Self {
+            fetcher: GnosisSafeFetcher::new(audited_safe.api_url.clone(), logger.clone())?,
+            reserve_id: audited_safe.safe_addr.0.to_lowercase(),
+            audited_safe,
+            logger,
+        })
+    }
+
+    /// Parse deposits out of an Ethereum transaction.
+    fn parse_eth_transaction(&self, tx: &EthereumTransaction) -> Vec<ReserveDeposit> {
+        log::trace!(self.logger, "Processing Ethereum transaction: {:?}", tx);
+
+        let mut deposits = Vec::new();
+        for transfer in &tx.transfers {
+            // See if this is a deposit to the safe.
+            if transfer.to == self.audited_safe.safe_addr {
+                log::info!(
+                    self.logger,
+                    "Processing gnosis safe deposit: {:?}",
+                    transfer
+                );
+
+                // Empty token address means ETH
+                let token_addr = transfer.token_addr.clone().unwrap_or_default();
+
+                deposits.push(ReserveDeposit::new(
+                    None,
+                    self.reserve_id.clone(),
+                    transfer.tx_hash.to_string(),
+                    tx.eth_block_number,
+                    token_addr.0.to_lowercase(),
+                    u64::from(transfer.value),
+                    MintTxNonce::EthereumGnosisDeposit(transfer.tx_hash).to_bytes(),
+                ));
+            }
+            // We don't know what this is.
+            else {
+                log::crit!(
+                    self.logger,
+                    "Unknown transfer {:?} in eth tx {}",
+                    transfer,
+                    tx.tx_hash,
+                );
+            }
+        }
+
+        deposits
+    }
+
+    /// Parse a withdrawal out of a MultiSig transaction.
+    fn parse_multi_sig_transaction(
+        &self,
+        multi_sig_tx: &MultiSigTransaction,
+    ) -> Option<ReserveWithdrawal> {
+        // See if this is a withdrawal from the safe we are tracking. In theory we
+        // should never receive a response for a different safe since the Gnosis API
+        // filters by safe address.
+        if multi_sig_tx.safe != self.audited_safe.safe_addr {
+            log::warn!(
+                self.logger,
+                "Received MultiSig transaction for a different safe than {}: {:?}",
+                self.audited_safe.safe_addr,
+                multi_sig_tx
+            );
+            return None;
+        }
+
+        match self.parse_withdrawal_with_pub_key_multi_sig_tx(multi_sig_tx) {
+            Ok(withdrawal) => {
+                log::info!(
+                    self.logger,
+                    "Processing withdrawal from multi-sig tx: {:?}",
+                    withdrawal
+                );
+                Some(withdrawal)
+            }
+
+            Err(err) => {
+                log::warn!(
+                    self.logger,
+                    "Failed parsing a withdrawal from multisig tx {}: {}",
+                    multi_sig_tx.tx_hash,
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// See if this is a multi-sig withdrawal that uses the auxiliary contract
+    /// for recording the tx out public key, and if so parse it into a
+    /// [ReserveWithdrawal] object.
+    fn parse_withdrawal_with_pub_key_multi_sig_tx(
+        &self,
+        multi_sig_tx: &MultiSigTransaction,
+    ) -> Result<ReserveWithdrawal, Error> {
+        // Get the decoded data - this is the part that contains details about the
+        // individual transfers included in the multi-transfer.
+        let data = multi_sig_tx
+            .data_decoded
+            .as_ref()
+            .ok_or_else(|| Error::ApiResultParse("data_decoded is empty".into()))?;
+
+        if data.method != "multiSend" {
+            return Err(Error::ApiResultParse(format!(
+                "multi-sig tx method mismatch: got {}, expected multiSend",
+                data.method
+            )));
+        }
+
+        // The decoded data is expected to contain a single "transactions" parameter,
+        // which should be an array of the individual transfers.
+        if data.parameters.len() != 1 {
+            return Err(Error::ApiResultParse(format!(
+                "invalid number of parameters: got {}, expected 1",
+                data.parameters.len()
+            )));
+        }
+
+        let parameter = &data.parameters[0];
+        let value_decoded = parameter.value_decoded.as_ref().ok_or_else(|| {
+            Error::ApiResultParse("decoded data parameter is missing value_decoded".into())
+        })?;
+
+        // Each value contains a single transfer. We expect to have two transfers:
+        // 1) A transfer moving the token being withdrawn from the safe
+        // 2) A "dummy" transfer into the auxiliary contract, used to record the
+        // matching MobileCoin tx out public key of the matching burn.
+        if value_decoded.len() != 2 {
+            return Err(Error::ApiResultParse(format!(
+                "Invalid number of values in multiSend transfer: got {}, expected 2",
+                value_decoded.len()
+            )));
+        }
+
+        // The first value is the transfer of the actual token held in the safe. It
+        // should match a token we are auditing.
+        let transfer_data = &value_decoded[0];
+        let audited_token = self
+            .audited_safe
+            .get_token_by_eth_contract_addr(&transfer_data.to)
+            .ok_or_else(|| {
+                Error::ApiResultParse(format!(
+                    "Encountered multiSend transaction to an unknown token: {}",
+                    transfer_data.to
+                ))
+            })?;
+
+        // The first value (transfer of token held in safe) should contain two
+        // parameters - the ethereum address receiving the withdrawal and the
+        // amount being moved out of the safe.
+        let transfer_data_decoded = transfer_data.data_decoded.as_ref().ok_or_else(|| {
+            Error::ApiResultParse("multiSend transfer first value has no decoded data".into())
+        })?;
+        if transfer_data_decoded.method != "transfer" {
+            return Err(Error::ApiResultParse(format!(
+                "Invalid first value method: got {}, expected transfer",
+                transfer_data_decoded.method
+            )));
+        }
+
+        let value_str = transfer_data_decoded
+            .parameters
+            .iter()
+            .find_map(|param| {
+                if param.name == "value" {
+                    Some(&param.value)
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| {
+                Error::ApiResultParse("first value is missing the \"value\" parameter".into())
+            })?;
+        let transfer_value = value_str.parse::<u64>().map_err(|err| {
+            Error::ApiResultParse(format!(
+                "invalid first value parameter: \"value\" {} cannot be be converted to u64: {}",
+                value_str, err,
+            ))
+        })?;
+
+        // The second value (dummy transfer to auxiliary contract) should contain the
+        // MobileCoin tx out public key in the data. There is no decoded version
+        // of the data since the Gnosis API does not know how to decode custom
+        // contracts.
+        let aux_contract_value = &value_decoded[1];
+        if aux_contract_value.to != audited_token.aux_burn_contract_addr {
+            return Err(Error::ApiResultParse(format!(
+                "aux contract destination mismatch: got {}, expected {}",
+                aux_contract_value.to, audited_token.aux_burn_contract_addr
+            )));
+        }
+
+        if !aux_contract_value.data.starts_with("0x") {
+            return Err(Error::ApiResultParse(format!(
+                "aux contract data doesn't start with 0x: got {}",
+                aux_contract_value.data,
+            )));
+        }
+
+        let aux_data_bytes = hex::decode(&aux_contract_value.data[2..]).map_err(|err| {
+            Error::ApiResultParse(format!(
+                "aux contract data {} cannot be hex-decoded: {}",
+                aux_contract_value.data, err,
+            ))
+        })?;
+
+        if !aux_data_bytes.starts_with(&audited_token.aux_burn_function_sig) {
+            return Err(Error::ApiResultParse(format!(
+                "aux contract data {} does not start with the expected function signature ({})",
+                aux_contract_value.data,
+                hex::encode(audited_token.aux_burn_function_sig),
+            )));
+        }
+
+        // The tx out pub key is the last 32 bytes. Ensure we have enough bytes in the
+        // data for that.
+        let min_length = audited_token.aux_burn_function_sig.len() + 32;
+        if aux_data_bytes.len() < min_length {
+            return Err(Error::ApiResultParse(format!(
+                "aux contract data {} does not contain enough bytes. got {}, expected at least {}",
+                aux_contract_value.data,
+                aux_contract_value.data.len(),
+                min_length,
+            )));
+        }
+
+        let tx_out_pub_key = &aux_data_bytes[aux_data_bytes.len() - 32..];
+
+        // Parsed everything we need.
+        Ok(ReserveWithdrawal::new(
+            None,
+            self.reserve_id.clone(),
+            multi_sig_tx.tx_hash.to_string(),
+            multi_sig_tx.eth_block_number,
+            transfer_data.to.0.to_lowercase(),
+            transfer_value,
+            hex::encode(tx_out_pub_key),
+        ))
+    }
+}
+
+impl ReserveSource for GnosisSafeSource {
+    fn fetch_since(
+        &mut self,
+        _cursor: Option<ReserveCursor>,
+    ) -> Result<ReserveTransactions, Error> {
+        // TODO: This is inefficient since it repeatedly fetches all transactions, even
+        // once we reached ones we have seen before. The gnosis safe API returns
+        // transactions from the newest to the oldest, and right now there is no way to
+        // change that (see https://github.com/safe-global/safe-transaction-service/issues/847).
+        // For this to be more efficient we need to first fetch everything once, until
+        // we are certain we have synced all transactions. After that, we could
+        // add an optimization that stops fetching once we've reached a transaction
+        // we've seen before. This requires keeping track of whether we have
+        // managed to complete a full fetch. Since right now we are not
+        // expecting a large amount of transactions, this optimization is postponed to a
+        // future PR.
+        let transactions = self
+            .fetcher
+            .get_all_transaction_data(&self.audited_safe.safe_addr)?
+            .into_iter()
+            .map(|raw_tx| {
+                Ok(ReserveTx::new(
+                    self.reserve_id.clone(),
+                    raw_tx.tx_hash()?.to_string(),
+                    raw_tx.to_json_string(),
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ReserveTransactions {
+            transactions,
+            next_cursor: None,
+        })
+    }
+
+    fn parse_transaction(&self, tx: &ReserveTx) -> Result<ReserveEvents, Error> {
+        Ok(
+            match RawGnosisTransaction::from_json(&tx.raw_tx_json)?.decode()? {
+                Transaction::Ethereum(eth_tx) => ReserveEvents {
+                    deposits: self.parse_eth_transaction(&eth_tx),
+                    ..Default::default()
+                },
+                Transaction::MultiSig(multi_sig_tx) => ReserveEvents {
+                    withdrawals: self
+                        .parse_multi_sig_transaction(&multi_sig_tx)
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+                Transaction::Module(value) => {
+                    log::warn!(
+                        self.logger,
+                        "Got unexpected \"Module\" transaction: {:?}",
+                        value
+                    );
+                    ReserveEvents::default()
+                }
+            },
+        )
+    }
+}
@@ -8,6 +8,8 @@
 pub mod counters;
 pub mod db;
 pub mod gnosis;
+pub mod report;
+pub mod reserve;
 
 mod convert;
 mod error;
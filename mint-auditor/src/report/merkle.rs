@@ -0,0 +1,206 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A minimal binary Merkle tree, used to commit to the records included in a
+//! proof-of-reserves report.
+//!
+//! Leaves and interior nodes are hashed with distinct prefixes so that a leaf
+//! can never be confused with an interior node. When a level has an odd number
+//! of nodes, the last node is promoted to the next level unchanged. The root
+//! of an empty tree is a distinct constant, which can't be the root of a
+//! non-empty tree.
+
+use crate::Error;
+use mc_crypto_hashes::{Blake2b256, Digest};
+use serde::{Deserialize, Serialize};
+
+/// A 32 bytes Merkle tree hash.
+pub type MerkleHash = [u8; 32];
+
+/// Prefix used when hashing leaves.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Prefix used when hashing interior nodes.
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hash the contents of a leaf.
+pub fn hash_leaf(data: &[u8]) -> MerkleHash {
+    let mut hasher = Blake2b256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// The root of an empty tree: the interior node prefix followed by a zero
+/// leaf count. Interior nodes always hash two 32 bytes children, so this can't
+/// collide with any of them.
+fn empty_root() -> MerkleHash {
+    let mut hasher = Blake2b256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(0u64.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Hash two child nodes into their parent.
+fn hash_node(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = Blake2b256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// A Merkle tree over a list of leaf hashes.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// All levels of the tree, starting with the leaves and ending with the
+    /// root.
+    levels: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over the given leaf hashes (see [hash_leaf]).
+    pub fn new(leaves: Vec<MerkleHash>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().expect("levels is never empty").len() > 1 {
+            let next_level = levels
+                .last()
+                .expect("levels is never empty")
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two nodes"),
+                })
+                .collect();
+            levels.push(next_level);
+        }
+        Self { levels }
+    }
+
+    /// Get the number of leaves in the tree.
+    pub fn num_leaves(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Get the root of the tree. The root of an empty tree is a constant that
+    /// doesn't match any leaf hash.
+    pub fn root(&self) -> MerkleHash {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_else(empty_root)
+    }
+
+    /// Get a proof that the leaf at the given index is included in the tree.
+    pub fn proof(&self, leaf_index: usize) -> Option<MerkleProof> {
+        if leaf_index >= self.num_leaves() {
+            return None;
+        }
+
+        let mut steps = Vec::new();
+        let mut index = leaf_index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_index = index ^ 1;
+            if let Some(sibling) = level.get(sibling_index) {
+                steps.push(MerkleProofStep {
+                    sibling_hex: hex::encode(sibling),
+                    sibling_is_left: sibling_index < index,
+                });
+            }
+            index /= 2;
+        }
+
+        Some(MerkleProof { steps })
+    }
+}
+
+/// A single step of a [MerkleProof].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MerkleProofStep {
+    /// The hex-encoded hash of the sibling node.
+    pub sibling_hex: String,
+
+    /// Whether the sibling is the left child of the parent node.
+    pub sibling_is_left: bool,
+}
+
+/// A proof that a leaf is included in a [MerkleTree].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MerkleProof {
+    /// The sibling hashes, from the leaf level up to the root.
+    pub steps: Vec<MerkleProofStep>,
+}
+
+impl MerkleProof {
+    /// Compute the root implied by this proof for the given leaf hash.
+    pub fn compute_root(&self, leaf: &MerkleHash) -> Result<MerkleHash, Error> {
+        self.steps.iter().try_fold(*leaf, |node, step| {
+            let sibling_bytes = hex::decode(&step.sibling_hex)?;
+            let sibling = MerkleHash::try_from(&sibling_bytes[..])
+                .map_err(|_| Error::InvalidLength(32, sibling_bytes.len()))?;
+            Ok(if step.sibling_is_left {
+                hash_node(&sibling, &node)
+            } else {
+                hash_node(&node, &sibling)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(num_leaves: u8) -> Vec<MerkleHash> {
+        (0..num_leaves).map(|i| hash_leaf(&[i])).collect()
+    }
+
+    #[test]
+    fn proofs_verify_for_all_tree_sizes() {
+        for num_leaves in 1..=9 {
+            let leaves = leaves(num_leaves);
+            let tree = MerkleTree::new(leaves.clone());
+            assert_eq!(tree.num_leaves(), leaves.len());
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert_eq!(proof.compute_root(leaf).unwrap(), tree.root());
+            }
+            assert!(tree.proof(leaves.len()).is_none());
+        }
+    }
+
+    #[test]
+    fn proofs_do_not_verify_other_leaves() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(leaves.clone());
+
+        let proof = tree.proof(1).unwrap();
+        assert_ne!(proof.compute_root(&leaves[2]).unwrap(), tree.root());
+
+        // A tampered sibling changes the root.
+        let mut tampered_proof = proof;
+        tampered_proof.steps[0].sibling_hex = hex::encode(leaves[3]);
+        assert_ne!(
+            tampered_proof.compute_root(&leaves[1]).unwrap(),
+            tree.root()
+        );
+    }
+
+    #[test]
+    fn single_and_empty_trees() {
+        let leaf = hash_leaf(b"hello");
+        let tree = MerkleTree::new(vec![leaf]);
+        assert_eq!(tree.root(), leaf);
+        assert!(tree.proof(0).unwrap().steps.is_empty());
+
+        let empty_tree = MerkleTree::new(vec![]);
+        assert_eq!(empty_tree.num_leaves(), 0);
+        assert_eq!(empty_tree.root(), empty_root());
+
+        // An empty tree has a different root from a tree over an empty leaf.
+        let empty_leaf_tree = MerkleTree::new(vec![hash_leaf(&[])]);
+        assert_ne!(empty_tree.root(), empty_leaf_tree.root());
+    }
+}
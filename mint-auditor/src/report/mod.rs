@@ -0,0 +1,693 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Proof-of-reserves reports.
+//!
+//! A report summarizes, for each token, how much was minted and burned up to
+//! a given block, how much of it was matched with reserve deposits and
+//! withdrawals, and any discrepancies the auditor knows about. The audited
+//! mint and burn records are committed to with a Merkle root, so that a
+//! [RecordInclusionProof] can later show that a given record was part of the
+//! report without having to publish all of them.
+//!
+//! A report for a given block only takes into account the reserve deposits and
+//! withdrawals the auditor had synced when that block was audited (see
+//! [BlockReserveCutoff]), so generating it again later yields the same report.
+//!
+//! Record hashes and report signatures are computed over the protobuf
+//! encoding of the records and reports, which verifiers can reproduce without
+//! depending on how the JSON representation is laid out.
+
+mod merkle;
+
+pub use self::merkle::{hash_leaf, MerkleHash, MerkleProof, MerkleProofStep, MerkleTree};
+
+use crate::{
+    db::{
+        AuditedBurn, AuditedMint, BlockBalance, BlockReserveCutoff, BurnTxOut, Conn, MintTx,
+        ReserveDeposit, ReserveWithdrawal,
+    },
+    Error,
+};
+use mc_blockchain_types::{BlockID, BlockIndex};
+use mc_crypto_keys::{Ed25519Pair, Ed25519Public, Ed25519Signature, Signer, Verifier};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Whether a record is a mint or a burn.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportRecordType {
+    /// A MintTx matched with a reserve deposit.
+    Mint,
+
+    /// A burn TxOut matched with a reserve withdrawal.
+    Burn,
+}
+
+/// An audited mint or burn, together with the reserve deposit or withdrawal
+/// it was matched with. These are the leaves of the report's Merkle tree.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportRecord {
+    /// Whether this is a mint or a burn.
+    #[serde(rename = "type")]
+    pub record_type: ReportRecordType,
+
+    /// The block index at which the mint or burn appeared.
+    pub block_index: BlockIndex,
+
+    /// The token id.
+    pub token_id: u64,
+
+    /// The amount minted or burned.
+    pub amount: u64,
+
+    /// The hex-encoded MintTx nonce (for mints) or burn TxOut public key (for
+    /// burns).
+    pub mc_id_hex: String,
+
+    /// The id of the reserve.
+    pub reserve_id: String,
+
+    /// The id of the matching reserve transaction.
+    pub reserve_tx_id: String,
+
+    /// The position of the matching reserve transaction in the reserve's
+    /// history (e.g. an Ethereum block number).
+    pub reserve_position: u64,
+
+    /// The asset on the reserve side.
+    pub reserve_asset: String,
+}
+
+impl ReportRecord {
+    /// Create a record for a [MintTx] matched with a [ReserveDeposit].
+    pub fn from_mint(mint_tx: &MintTx, deposit: &ReserveDeposit) -> Self {
+        Self {
+            record_type: ReportRecordType::Mint,
+            block_index: mint_tx.block_index(),
+            token_id: *mint_tx.token_id(),
+            amount: mint_tx.amount(),
+            mc_id_hex: mint_tx.nonce_hex().to_string(),
+            reserve_id: deposit.reserve_id().to_string(),
+            reserve_tx_id: deposit.tx_id().to_string(),
+            reserve_position: deposit.position(),
+            reserve_asset: deposit.asset().to_string(),
+        }
+    }
+
+    /// Create a record for a [BurnTxOut] matched with a
+    /// [ReserveWithdrawal].
+    pub fn from_burn(burn_tx_out: &BurnTxOut, withdrawal: &ReserveWithdrawal) -> Self {
+        Self {
+            record_type: ReportRecordType::Burn,
+            block_index: burn_tx_out.block_index(),
+            token_id: *burn_tx_out.token_id(),
+            amount: burn_tx_out.amount(),
+            mc_id_hex: burn_tx_out.public_key_hex().to_string(),
+            reserve_id: withdrawal.reserve_id().to_string(),
+            reserve_tx_id: withdrawal.tx_id().to_string(),
+            reserve_position: withdrawal.position(),
+            reserve_asset: withdrawal.asset().to_string(),
+        }
+    }
+
+    /// The Merkle leaf hash of this record, computed over its canonical
+    /// encoding.
+    pub fn leaf_hash(&self) -> MerkleHash {
+        hash_leaf(&self.canonical_bytes())
+    }
+
+    /// The canonical encoding of this record: the protobuf encoding of a
+    /// [CanonicalReportRecord].
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let record = CanonicalReportRecord {
+            record_type: match self.record_type {
+                ReportRecordType::Mint => 0,
+                ReportRecordType::Burn => 1,
+            },
+            block_index: self.block_index,
+            token_id: self.token_id,
+            amount: self.amount,
+            mc_id_hex: self.mc_id_hex.clone(),
+            reserve_id: self.reserve_id.clone(),
+            reserve_tx_id: self.reserve_tx_id.clone(),
+            reserve_position: self.reserve_position,
+            reserve_asset: self.reserve_asset.clone(),
+        };
+        mc_util_serial::encode(&record)
+    }
+}
+
+/// The protobuf layout used to hash a [ReportRecord].
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct CanonicalReportRecord {
+    /// 0 for a mint, 1 for a burn.
+    #[prost(uint32, tag = "1")]
+    pub record_type: u32,
+
+    /// The block index at which the mint or burn appeared.
+    #[prost(uint64, tag = "2")]
+    pub block_index: u64,
+
+    /// The token id.
+    #[prost(uint64, tag = "3")]
+    pub token_id: u64,
+
+    /// The amount minted or burned.
+    #[prost(uint64, tag = "4")]
+    pub amount: u64,
+
+    /// The hex-encoded MintTx nonce or burn TxOut public key.
+    #[prost(string, tag = "5")]
+    pub mc_id_hex: String,
+
+    /// The id of the reserve.
+    #[prost(string, tag = "6")]
+    pub reserve_id: String,
+
+    /// The id of the matching reserve transaction.
+    #[prost(string, tag = "7")]
+    pub reserve_tx_id: String,
+
+    /// The position of the matching reserve transaction.
+    #[prost(uint64, tag = "8")]
+    pub reserve_position: u64,
+
+    /// The asset on the reserve side.
+    #[prost(string, tag = "9")]
+    pub reserve_asset: String,
+}
+
+/// The part of a report covering a single token.
+#[derive(Clone, Deserialize, Eq, Message, PartialEq, Serialize)]
+pub struct TokenReport {
+    /// The token id.
+    #[prost(uint64, tag = "1")]
+    pub token_id: u64,
+
+    /// Total amount minted up to and including the report block.
+    #[prost(uint64, tag = "2")]
+    pub total_minted: u64,
+
+    /// Total amount burned up to and including the report block.
+    #[prost(uint64, tag = "3")]
+    pub total_burned: u64,
+
+    /// Total minted minus total burned.
+    #[prost(uint64, tag = "4")]
+    pub net_minted: u64,
+
+    /// The balance the auditor tracked for the report block.
+    #[prost(uint64, tag = "5")]
+    pub ledger_balance: u64,
+
+    /// Total amount of the reserve deposits matched with mints.
+    #[prost(uint64, tag = "6")]
+    pub total_deposited: u64,
+
+    /// Total amount of the reserve withdrawals matched with burns.
+    #[prost(uint64, tag = "7")]
+    pub total_withdrawn: u64,
+
+    /// Number of mints matched with a reserve deposit.
+    #[prost(uint64, tag = "8")]
+    pub num_audited_mints: u64,
+
+    /// Number of burns matched with a reserve withdrawal.
+    #[prost(uint64, tag = "9")]
+    pub num_audited_burns: u64,
+
+    /// Human readable descriptions of any discrepancies found for this token.
+    #[prost(string, repeated, tag = "10")]
+    pub discrepancies: Vec<String>,
+}
+
+/// A proof-of-reserves report for a given block.
+#[derive(Clone, Deserialize, Eq, Message, PartialEq, Serialize)]
+pub struct ProofOfReservesReport {
+    /// The block index the report covers.
+    #[prost(uint64, tag = "1")]
+    pub block_index: BlockIndex,
+
+    /// The hex-encoded id (hash) of the block.
+    #[prost(string, tag = "2")]
+    pub block_id_hex: String,
+
+    /// Per-token summaries, ordered by token id.
+    #[prost(message, repeated, tag = "3")]
+    pub tokens: Vec<TokenReport>,
+
+    /// Discrepancies that cannot be attributed to a specific token, such as
+    /// reserve deposits and withdrawals that were not matched with anything.
+    #[prost(string, repeated, tag = "4")]
+    pub discrepancies: Vec<String>,
+
+    /// Number of records committed to by `records_merkle_root_hex`.
+    #[prost(uint64, tag = "5")]
+    pub num_records: u64,
+
+    /// The hex-encoded Merkle root over all audited mint and burn records.
+    #[prost(string, tag = "6")]
+    pub records_merkle_root_hex: String,
+}
+
+impl ProofOfReservesReport {
+    /// Generate a report for a given block, together with an inclusion proof
+    /// for each of the records it commits to.
+    pub fn generate(
+        block_index: BlockIndex,
+        block_id: &BlockID,
+        conn: &Conn,
+    ) -> Result<(Self, Vec<RecordInclusionProof>), Error> {
+        let cutoff = BlockReserveCutoff::get(block_index, conn)?;
+        let mut tokens = BTreeMap::<u64, TokenReport>::new();
+
+        for (token_id, balance) in BlockBalance::get_balances_for_block(conn, block_index)? {
+            token_report(&mut tokens, *token_id).ledger_balance = balance;
+        }
+
+        let mut records = Vec::new();
+
+        // Mints and burns are only considered matched if the matching reserve
+        // deposit or withdrawal had been synced when the block was audited.
+        let mut unmatched_mint_txs = Vec::new();
+        for (mint_tx, deposit) in
+            AuditedMint::get_audited_mints_up_to_block_index(block_index, conn)?
+        {
+            if deposit.id().unwrap_or_default() > cutoff.last_reserve_deposit_id() {
+                unmatched_mint_txs.push(mint_tx);
+                continue;
+            }
+            let token = token_report(&mut tokens, *mint_tx.token_id());
+            token.total_minted += mint_tx.amount();
+            token.total_deposited += deposit.amount();
+            token.num_audited_mints += 1;
+            records.push(ReportRecord::from_mint(&mint_tx, &deposit));
+        }
+        unmatched_mint_txs.extend(
+            MintTx::find_unaudited_mint_txs(conn)?
+                .into_iter()
+                .filter(|mint_tx| mint_tx.block_index() <= block_index),
+        );
+        unmatched_mint_txs.sort_by(|a, b| {
+            (a.block_index(), a.nonce_hex()).cmp(&(b.block_index(), b.nonce_hex()))
+        });
+        for mint_tx in unmatched_mint_txs {
+            let token = token_report(&mut tokens, *mint_tx.token_id());
+            token.total_minted += mint_tx.amount();
+            token.discrepancies.push(format!(
+                "MintTx nonce={} at block {} has no matching reserve deposit",
+                mint_tx.nonce_hex(),
+                mint_tx.block_index()
+            ));
+        }
+
+        let mut unmatched_burn_tx_outs = Vec::new();
+        for (burn_tx_out, withdrawal) in
+            AuditedBurn::get_audited_burns_up_to_block_index(block_index, conn)?
+        {
+            if withdrawal.id().unwrap_or_default() > cutoff.last_reserve_withdrawal_id() {
+                unmatched_burn_tx_outs.push(burn_tx_out);
+                continue;
+            }
+            let token = token_report(&mut tokens, *burn_tx_out.token_id());
+            token.total_burned += burn_tx_out.amount();
+            token.total_withdrawn += withdrawal.amount();
+            token.num_audited_burns += 1;
+            records.push(ReportRecord::from_burn(&burn_tx_out, &withdrawal));
+        }
+        unmatched_burn_tx_outs.extend(
+            BurnTxOut::find_unaudited_burn_tx_outs(conn)?
+                .into_iter()
+                .filter(|burn_tx_out| burn_tx_out.block_index() <= block_index),
+        );
+        unmatched_burn_tx_outs.sort_by(|a, b| {
+            (a.block_index(), a.public_key_hex()).cmp(&(b.block_index(), b.public_key_hex()))
+        });
+        for burn_tx_out in unmatched_burn_tx_outs {
+            let token = token_report(&mut tokens, *burn_tx_out.token_id());
+            token.total_burned += burn_tx_out.amount();
+            token.discrepancies.push(format!(
+                "BurnTxOut public_key={} at block {} has no matching reserve withdrawal",
+                burn_tx_out.public_key_hex(),
+                burn_tx_out.block_index()
+            ));
+        }
+
+        for token in tokens.values_mut() {
+            token.net_minted = token.total_minted.saturating_sub(token.total_burned);
+            if token.total_burned > token.total_minted {
+                token.discrepancies.push(format!(
+                    "Burned {} but only minted {}",
+                    token.total_burned, token.total_minted
+                ));
+            }
+            if token.net_minted != token.ledger_balance {
+                token.discrepancies.push(format!(
+                    "Minted minus burned ({}) does not match the ledger balance ({})",
+                    token.net_minted, token.ledger_balance
+                ));
+            }
+        }
+
+        let mut discrepancies = Vec::new();
+        for deposit in ReserveDeposit::find_unaudited_deposits_at_block(&cutoff, conn)? {
+            discrepancies.push(format!(
+                "Reserve deposit tx_id={} (reserve={} asset={} amount={}) has no matching MintTx",
+                deposit.tx_id(),
+                deposit.reserve_id(),
+                deposit.asset(),
+                deposit.amount()
+            ));
+        }
+        for withdrawal in ReserveWithdrawal::find_unaudited_withdrawals_at_block(&cutoff, conn)? {
+            discrepancies.push(format!(
+                "Reserve withdrawal tx_id={} (reserve={} asset={} amount={}) has no matching BurnTxOut",
+                withdrawal.tx_id(),
+                withdrawal.reserve_id(),
+                withdrawal.asset(),
+                withdrawal.amount()
+            ));
+        }
+
+        // Order the records so that the Merkle root does not depend on the order
+        // in which matches happened to be made.
+        records.sort_by(|a, b| {
+            (a.block_index, a.record_type, &a.mc_id_hex).cmp(&(
+                b.block_index,
+                b.record_type,
+                &b.mc_id_hex,
+            ))
+        });
+        let leaves = records.iter().map(ReportRecord::leaf_hash).collect();
+        let tree = MerkleTree::new(leaves);
+
+        let proofs = records
+            .into_iter()
+            .enumerate()
+            .map(|(index, record)| RecordInclusionProof {
+                record,
+                proof: tree
+                    .proof(index)
+                    .expect("every record has a leaf in the tree"),
+            })
+            .collect();
+
+        let report = Self {
+            block_index,
+            block_id_hex: hex::encode(block_id),
+            tokens: tokens.into_values().collect(),
+            discrepancies,
+            num_records: tree.num_leaves() as u64,
+            records_merkle_root_hex: hex::encode(tree.root()),
+        };
+
+        Ok((report, proofs))
+    }
+
+    /// Domain separation tag, prepended to the report before signing it so
+    /// that the signature can't be passed off as one over another message.
+    pub const SIGNATURE_DOMAIN_TAG: &'static [u8] = b"mc-mint-auditor-proof-of-reserves-v1";
+
+    /// The bytes covered by the report signature: the domain tag followed by
+    /// the protobuf encoding of the report.
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Self::SIGNATURE_DOMAIN_TAG.to_vec();
+        bytes.extend(mc_util_serial::encode(self));
+        bytes
+    }
+
+    /// Sign the report.
+    pub fn sign(self, signer: &Ed25519Pair) -> Result<SignedReport, Error> {
+        let signature = signer
+            .try_sign(&self.signed_bytes())
+            .map_err(|err| Error::Other(format!("failed signing report: {}", err)))?;
+
+        Ok(SignedReport {
+            report: self,
+            signer_public_key_hex: hex::encode(signer.public_key()),
+            signature_hex: hex::encode(signature),
+        })
+    }
+}
+
+/// Get the [TokenReport] for a given token id, creating it if needed.
+fn token_report(tokens: &mut BTreeMap<u64, TokenReport>, token_id: u64) -> &mut TokenReport {
+    tokens.entry(token_id).or_insert_with(|| TokenReport {
+        token_id,
+        ..Default::default()
+    })
+}
+
+/// A [ProofOfReservesReport] signed by the auditor.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedReport {
+    /// The report.
+    pub report: ProofOfReservesReport,
+
+    /// The hex-encoded public key of the signer.
+    pub signer_public_key_hex: String,
+
+    /// The hex-encoded Ed25519 signature over
+    /// [ProofOfReservesReport::signed_bytes].
+    pub signature_hex: String,
+}
+
+impl SignedReport {
+    /// Verify that the report was signed by the given key.
+    pub fn verify(&self, signer: &Ed25519Public) -> Result<(), Error> {
+        if hex::encode(signer) != self.signer_public_key_hex {
+            return Err(Error::ReportVerification(format!(
+                "report was signed by {}, not by {}",
+                self.signer_public_key_hex,
+                hex::encode(signer)
+            )));
+        }
+
+        let signature = Ed25519Signature::try_from(hex::decode(&self.signature_hex)?)
+            .map_err(|err| Error::ReportVerification(format!("invalid signature: {}", err)))?;
+        signer
+            .verify(&self.report.signed_bytes(), &signature)
+            .map_err(|err| Error::ReportVerification(format!("bad signature: {}", err)))
+    }
+}
+
+/// A proof that a record was committed to by a [ProofOfReservesReport].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecordInclusionProof {
+    /// The record.
+    pub record: ReportRecord,
+
+    /// The Merkle proof linking the record to the report's Merkle root.
+    pub proof: MerkleProof,
+}
+
+impl RecordInclusionProof {
+    /// Verify that the record is included in the given report.
+    pub fn verify(&self, report: &ProofOfReservesReport) -> Result<(), Error> {
+        let root = self.proof.compute_root(&self.record.leaf_hash())?;
+        if hex::encode(root) != report.records_merkle_root_hex {
+            return Err(Error::ReportVerification(format!(
+                "{:?} record {} is not included in the report",
+                self.record.record_type, self.record.mc_id_hex
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        test_utils::{
+            create_burn_tx_out, create_reserve_deposit, create_reserve_withdrawal,
+            insert_mint_tx_from_deposit, insert_reserve_deposit, insert_reserve_withdrawal,
+            test_gnosis_config, test_reserve_config, TestDbContext,
+        },
+        BlockAuditData,
+    };
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_crypto_keys::Ed25519Private;
+    use mc_util_from_random::FromRandom;
+
+    #[test_with_logger]
+    fn generate_sign_and_verify(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let config = test_reserve_config();
+        let token_id = test_gnosis_config().safes[0].tokens[0].token_id;
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        // Two matched mints and one unmatched mint.
+        for amount in [100, 200] {
+            let mut deposit = create_reserve_deposit(amount, &mut rng);
+            insert_reserve_deposit(&mut deposit, &conn);
+            let mint_tx = insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
+            AuditedMint::try_match_mint_with_deposit(&mint_tx, &config, &conn).unwrap();
+        }
+        let deposit = create_reserve_deposit(300, &mut rng);
+        insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
+
+        // One matched burn.
+        let tx_out = create_burn_tx_out(token_id, 50, None, &mut rng);
+        let burn_tx_out = BurnTxOut::insert_from_core_tx_out(0, &tx_out, &conn).unwrap();
+        let mut withdrawal = create_reserve_withdrawal(&tx_out, 50, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal, &conn);
+        AuditedBurn::try_match_burn_with_withdrawal(&burn_tx_out, &config, &conn).unwrap();
+
+        BlockAuditData::new(0).set(&conn).unwrap();
+        BlockReserveCutoff::record(0, &conn).unwrap();
+        BlockBalance::set_balances_for_block(&conn, 0, &[(token_id, 550)].into_iter().collect())
+            .unwrap();
+
+        let block_id = BlockID([7; 32]);
+        let (report, proofs) = ProofOfReservesReport::generate(0, &block_id, &conn).unwrap();
+
+        assert_eq!(report.block_id_hex, hex::encode([7; 32]));
+        assert_eq!(report.num_records, 3);
+        assert_eq!(proofs.len(), 3);
+        assert_eq!(report.tokens.len(), 1);
+        let token = &report.tokens[0];
+        assert_eq!(token.token_id, *token_id);
+        assert_eq!(token.total_minted, 600);
+        assert_eq!(token.total_burned, 50);
+        assert_eq!(token.net_minted, 550);
+        assert_eq!(token.ledger_balance, 550);
+        assert_eq!(token.total_deposited, 300);
+        assert_eq!(token.total_withdrawn, 50);
+        assert_eq!(token.num_audited_mints, 2);
+        assert_eq!(token.num_audited_burns, 1);
+        assert_eq!(token.discrepancies.len(), 1);
+        assert!(token.discrepancies[0].contains("no matching reserve deposit"));
+        assert!(report.discrepancies.is_empty());
+
+        for proof in &proofs {
+            proof.verify(&report).unwrap();
+        }
+
+        // Tampering with a record invalidates its proof.
+        let mut tampered_proof = proofs[0].clone();
+        tampered_proof.record.amount += 1;
+        assert!(matches!(
+            tampered_proof.verify(&report),
+            Err(Error::ReportVerification(_))
+        ));
+
+        let signer = Ed25519Pair::from(Ed25519Private::from_random(&mut rng));
+        let signed_report = report.sign(&signer).unwrap();
+        signed_report.verify(&signer.public_key()).unwrap();
+
+        // Verification fails with a different key, or when the report is modified.
+        let other_signer = Ed25519Pair::from(Ed25519Private::from_random(&mut rng));
+        assert!(signed_report.verify(&other_signer.public_key()).is_err());
+
+        let mut tampered_report = signed_report;
+        tampered_report.report.tokens[0].ledger_balance += 1;
+        assert!(tampered_report.verify(&signer.public_key()).is_err());
+    }
+
+    #[test_with_logger]
+    fn reserve_events_synced_after_the_block_are_ignored(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let config = test_reserve_config();
+        let token_id = test_gnosis_config().safes[0].tokens[0].token_id;
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        // One matched mint, one mint whose deposit is not synced yet, and one
+        // deposit without a mint.
+        let mut deposit = create_reserve_deposit(100, &mut rng);
+        insert_reserve_deposit(&mut deposit, &conn);
+        let mint_tx = insert_mint_tx_from_deposit(&deposit, &conn, &mut rng);
+        AuditedMint::try_match_mint_with_deposit(&mint_tx, &config, &conn).unwrap();
+
+        let mut late_deposit = create_reserve_deposit(200, &mut rng);
+        let late_mint_tx = insert_mint_tx_from_deposit(&late_deposit, &conn, &mut rng);
+
+        let mut unmatched_deposit = create_reserve_deposit(300, &mut rng);
+        insert_reserve_deposit(&mut unmatched_deposit, &conn);
+
+        BlockAuditData::new(0).set(&conn).unwrap();
+        BlockReserveCutoff::record(0, &conn).unwrap();
+        BlockBalance::set_balances_for_block(&conn, 0, &[(token_id, 300)].into_iter().collect())
+            .unwrap();
+
+        let block_id = BlockID([7; 32]);
+        let (report, proofs) = ProofOfReservesReport::generate(0, &block_id, &conn).unwrap();
+
+        assert_eq!(report.num_records, 1);
+        let token = &report.tokens[0];
+        assert_eq!(token.total_minted, 300);
+        assert_eq!(token.total_deposited, 100);
+        assert_eq!(token.num_audited_mints, 1);
+        assert_eq!(token.discrepancies.len(), 1);
+        assert!(token.discrepancies[0].contains(late_mint_tx.nonce_hex()));
+        assert_eq!(report.discrepancies.len(), 1);
+        assert!(report.discrepancies[0].contains(unmatched_deposit.tx_id()));
+
+        // Reserve data synced after block 0 was audited: the deposit matching
+        // the second mint, and a deposit for a future mint.
+        insert_reserve_deposit(&mut late_deposit, &conn);
+        AuditedMint::try_match_mint_with_deposit(&late_mint_tx, &config, &conn).unwrap();
+
+        let mut later_deposit = create_reserve_deposit(400, &mut rng);
+        insert_reserve_deposit(&mut later_deposit, &conn);
+
+        // Regenerating the report for block 0 yields the same report.
+        let (regenerated_report, regenerated_proofs) =
+            ProofOfReservesReport::generate(0, &block_id, &conn).unwrap();
+        assert_eq!(regenerated_report, report);
+        assert_eq!(regenerated_proofs, proofs);
+        assert_eq!(regenerated_report.signed_bytes(), report.signed_bytes());
+    }
+
+    #[test]
+    fn canonical_encoding_is_stable() {
+        let record = ReportRecord {
+            record_type: ReportRecordType::Burn,
+            block_index: 1,
+            token_id: 2,
+            amount: 3,
+            mc_id_hex: "ab".to_string(),
+            reserve_id: "r".to_string(),
+            reserve_tx_id: "t".to_string(),
+            reserve_position: 4,
+            reserve_asset: "a".to_string(),
+        };
+        assert_eq!(
+            record.canonical_bytes(),
+            [
+                0x08, 1, 0x10, 1, 0x18, 2, 0x20, 3, 0x2a, 2, b'a', b'b', 0x32, 1, b'r', 0x3a, 1,
+                b't', 0x40, 4, 0x4a, 1, b'a'
+            ]
+        );
+
+        let report = ProofOfReservesReport {
+            block_index: 5,
+            block_id_hex: "cd".to_string(),
+            tokens: vec![TokenReport {
+                token_id: 1,
+                discrepancies: vec!["x".to_string()],
+                ..Default::default()
+            }],
+            discrepancies: vec![],
+            num_records: 0,
+            records_merkle_root_hex: "ef".to_string(),
+        };
+        assert_eq!(
+            report.signed_bytes(),
+            [
+                &b"mc-mint-auditor-proof-of-reserves-v1"[..],
+                &[
+                    0x08, 5, 0x12, 2, b'c', b'd', 0x1a, 5, 0x08, 1, 0x52, 1, b'x', 0x32, 2, b'e',
+                    b'f'
+                ],
+            ]
+            .concat()
+        );
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Configuration for reserve auditing
+
+use crate::gnosis::{AuditedSafeConfig, Error, EthAddr, GnosisSafeConfig};
+use mc_transaction_core::TokenId;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// Configuration for a token held in a plain Ethereum wallet.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Erc20TokenConfig {
+    /// The MobileCoin token id.
+    pub token_id: TokenId,
+
+    /// The ERC-20 token contract address.
+    pub token_contract_addr: EthAddr,
+
+    /// The auxiliary burn contract address (this is the contract whose
+    /// `MetadataReceived` event records the matching TxOut public key for the
+    /// burn transaction on the MobileCoin blockchain).
+    pub aux_burn_contract_addr: EthAddr,
+}
+
+/// Configuration for a plain Ethereum wallet we want to audit.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Erc20ReserveConfig {
+    /// The wallet address.
+    pub wallet_addr: EthAddr,
+
+    /// The Ethereum JSON-RPC endpoint to query.
+    pub rpc_url: Url,
+
+    /// The first Ethereum block to look at.
+    #[serde(default)]
+    pub start_block: u64,
+
+    /// The number of blocks that have to be built on top of a block before we
+    /// fetch its logs, so that we don't record transfers that get reorged
+    /// away.
+    #[serde(default = "Erc20ReserveConfig::default_confirmations")]
+    pub confirmations: u64,
+
+    /// The tokens we want to audit.
+    pub tokens: Vec<Erc20TokenConfig>,
+}
+
+impl Erc20ReserveConfig {
+    /// The default number of confirmations.
+    pub const DEFAULT_CONFIRMATIONS: u64 = 12;
+
+    fn default_confirmations() -> u64 {
+        Self::DEFAULT_CONFIRMATIONS
+    }
+
+    /// Get an audited token by its contract address.
+    pub fn get_token_by_contract_addr(&self, contract_addr: &EthAddr) -> Option<&Erc20TokenConfig> {
+        self.tokens
+            .iter()
+            .find(|token| token.token_contract_addr == *contract_addr)
+    }
+}
+
+/// Configuration for an asset held in an account whose history is imported
+/// from a file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileImportTokenConfig {
+    /// The MobileCoin token id.
+    pub token_id: TokenId,
+
+    /// The asset, as it appears in the `asset` column of the imported file
+    /// (e.g. a currency code).
+    pub asset: String,
+}
+
+/// Configuration for an account (e.g. a custodial bank account) whose
+/// deposits and withdrawals are imported from a file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileImportReserveConfig {
+    /// An identifier for the account. This is what the imported deposits and
+    /// withdrawals are recorded against.
+    pub account_id: String,
+
+    /// Path to the file. CSV and JSON files are supported, and the format is
+    /// determined by the file extension.
+    pub path: PathBuf,
+
+    /// The assets we want to audit.
+    pub tokens: Vec<FileImportTokenConfig>,
+}
+
+/// Configuration for a single reserve we want to audit. The `type` field
+/// picks where its deposits and withdrawals are fetched from.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditedReserveConfig {
+    /// A Gnosis safe, synced from a safe transaction service.
+    GnosisSafe(AuditedSafeConfig),
+
+    /// A plain Ethereum wallet, synced by querying ERC-20 `Transfer` logs from
+    /// an Ethereum JSON-RPC node.
+    Erc20JsonRpc(Erc20ReserveConfig),
+
+    /// An account whose deposits and withdrawals are exported from another
+    /// system (e.g. a custodial bank ledger) into a file.
+    FileImport(FileImportReserveConfig),
+}
+
+impl AuditedReserveConfig {
+    /// The id deposits and withdrawals into this reserve are recorded against.
+    /// Ethereum addresses are lowercased, since they are case-insensitive.
+    pub fn id(&self) -> String {
+        match self {
+            Self::GnosisSafe(config) => config.safe_addr.0.to_lowercase(),
+            Self::Erc20JsonRpc(config) => config.wallet_addr.0.to_lowercase(),
+            Self::FileImport(config) => config.account_id.clone(),
+        }
+    }
+
+    /// Get the MobileCoin token id of an audited asset, as recorded in a
+    /// deposit or withdrawal.
+    pub fn token_id_for_asset(&self, asset: &str) -> Option<TokenId> {
+        match self {
+            Self::GnosisSafe(config) => config
+                .tokens
+                .iter()
+                .find(|token| token.eth_token_contract_addr.0.to_lowercase() == asset)
+                .map(|token| token.token_id),
+            Self::Erc20JsonRpc(config) => config
+                .tokens
+                .iter()
+                .find(|token| token.token_contract_addr.0.to_lowercase() == asset)
+                .map(|token| token.token_id),
+            Self::FileImport(config) => config
+                .tokens
+                .iter()
+                .find(|token| token.asset == asset)
+                .map(|token| token.token_id),
+        }
+    }
+}
+
+/// Configuration for reserve auditing. This supersedes [GnosisSafeConfig],
+/// which can still be converted into a [ReserveConfig] that only contains
+/// Gnosis safes.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReserveConfig {
+    /// The reserve(s) we want to audit.
+    pub reserves: Vec<AuditedReserveConfig>,
+}
+
+impl ReserveConfig {
+    /// Load configuration data from a toml/json file.
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        // Read configuration file.
+        let data = fs::read_to_string(path)?;
+
+        // Parse configuration file.
+        let config: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            None => Err(Error::PathExtension),
+            Some("toml") => toml::from_str(&data).map_err(Error::from),
+            Some("json") => serde_json::from_str(&data).map_err(Error::from),
+            Some(ext) => Err(Error::UnrecognizedExtension(ext.to_string())),
+        }?;
+
+        Ok(config)
+    }
+
+    /// Get an [AuditedReserveConfig] by its reserve id.
+    pub fn get_reserve(&self, reserve_id: &str) -> Option<&AuditedReserveConfig> {
+        self.reserves
+            .iter()
+            .find(|reserve| reserve.id() == reserve_id)
+    }
+}
+
+impl From<GnosisSafeConfig> for ReserveConfig {
+    fn from(src: GnosisSafeConfig) -> Self {
+        Self {
+            reserves: src
+                .safes
+                .into_iter()
+                .map(AuditedReserveConfig::GnosisSafe)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gnosis::AuditedToken;
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    static INPUT_TOML: &str = r#"
+        [[reserves]]
+        type = "gnosis_safe"
+        safe_addr = "0x90213de428E9Ce4C77dD4943755Aa69cb2F803b7"
+        api_url = "https://safe-api.example.com"
+
+        [[reserves.tokens]]
+        token_id = 1
+        eth_token_contract_addr = "0xd92e713d051c37ebb2561803a3b5fbabc4962431"
+        aux_burn_contract_addr = "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40"
+        aux_burn_function_sig = [0xc7, 0x6f, 0x06, 0x35]
+
+        [[reserves]]
+        type = "erc20_json_rpc"
+        wallet_addr = "0x1111111111111111111111111111111111111111"
+        rpc_url = "https://eth-rpc.example.com"
+        start_block = 1234
+
+        [[reserves.tokens]]
+        token_id = 1
+        token_contract_addr = "0xD92E713d051C37EbB2561803a3b5FBAbc4962431"
+        aux_burn_contract_addr = "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40"
+
+        [[reserves]]
+        type = "file_import"
+        account_id = "bank-account-1"
+        path = "/var/lib/reserves/bank.csv"
+
+        [[reserves.tokens]]
+        token_id = 2
+        asset = "USD"
+    "#;
+
+    static INPUT_JSON: &str = r#"{
+        "reserves": [
+            {
+                "type": "gnosis_safe",
+                "safe_addr": "0x90213de428E9Ce4C77dD4943755Aa69cb2F803b7",
+                "api_url": "https://safe-api.example.com",
+                "tokens": [
+                    {
+                        "token_id": 1,
+                        "eth_token_contract_addr": "0xd92e713d051c37ebb2561803a3b5fbabc4962431",
+                        "aux_burn_contract_addr": "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40",
+                        "aux_burn_function_sig": [199, 111, 6, 53]
+                    }
+                ]
+            },
+            {
+                "type": "erc20_json_rpc",
+                "wallet_addr": "0x1111111111111111111111111111111111111111",
+                "rpc_url": "https://eth-rpc.example.com",
+                "start_block": 1234,
+                "confirmations": 12,
+                "tokens": [
+                    {
+                        "token_id": 1,
+                        "token_contract_addr": "0xD92E713d051C37EbB2561803a3b5FBAbc4962431",
+                        "aux_burn_contract_addr": "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40"
+                    }
+                ]
+            },
+            {
+                "type": "file_import",
+                "account_id": "bank-account-1",
+                "path": "/var/lib/reserves/bank.csv",
+                "tokens": [
+                    {
+                        "token_id": 2,
+                        "asset": "USD"
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    fn expected_config() -> ReserveConfig {
+        ReserveConfig {
+            reserves: vec![
+                AuditedReserveConfig::GnosisSafe(AuditedSafeConfig {
+                    safe_addr: EthAddr::from_str("0x90213de428E9Ce4C77dD4943755Aa69cb2F803b7")
+                        .unwrap(),
+                    api_url: Url::parse("https://safe-api.example.com").unwrap(),
+                    tokens: vec![AuditedToken {
+                        token_id: TokenId::from(1),
+                        eth_token_contract_addr: EthAddr::from_str(
+                            "0xd92e713d051c37ebb2561803a3b5fbabc4962431",
+                        )
+                        .unwrap(),
+                        aux_burn_contract_addr: EthAddr::from_str(
+                            "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40",
+                        )
+                        .unwrap(),
+                        aux_burn_function_sig: [199, 111, 6, 53],
+                    }],
+                }),
+                AuditedReserveConfig::Erc20JsonRpc(Erc20ReserveConfig {
+                    wallet_addr: EthAddr::from_str("0x1111111111111111111111111111111111111111")
+                        .unwrap(),
+                    rpc_url: Url::parse("https://eth-rpc.example.com").unwrap(),
+                    start_block: 1234,
+                    confirmations: Erc20ReserveConfig::DEFAULT_CONFIRMATIONS,
+                    tokens: vec![Erc20TokenConfig {
+                        token_id: TokenId::from(1),
+                        token_contract_addr: EthAddr::from_str(
+                            "0xD92E713d051C37EbB2561803a3b5FBAbc4962431",
+                        )
+                        .unwrap(),
+                        aux_burn_contract_addr: EthAddr::from_str(
+                            "0x76BD419fBa96583d968b422D4f3CB2A70bf4CF40",
+                        )
+                        .unwrap(),
+                    }],
+                }),
+                AuditedReserveConfig::FileImport(FileImportReserveConfig {
+                    account_id: "bank-account-1".to_string(),
+                    path: PathBuf::from("/var/lib/reserves/bank.csv"),
+                    tokens: vec![FileImportTokenConfig {
+                        token_id: TokenId::from(2),
+                        asset: "USD".to_string(),
+                    }],
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn valid_config() {
+        let cfg1: ReserveConfig = toml::from_str(INPUT_TOML).expect("failed parsing toml");
+        let cfg2: ReserveConfig = serde_json::from_str(INPUT_JSON).expect("failed parsing json");
+
+        assert_eq!(cfg1, cfg2);
+        assert_eq!(cfg1, expected_config());
+
+        // Round-tripping through JSON preserves the reserve types.
+        let json = serde_json::to_string(&cfg1).unwrap();
+        let cfg3: ReserveConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(cfg1, cfg3);
+    }
+
+    #[test]
+    fn missing_or_unknown_type_is_rejected() {
+        let json = r#"{"reserves": [{"account_id": "a", "path": "a.csv", "tokens": []}]}"#;
+        assert!(serde_json::from_str::<ReserveConfig>(json).is_err());
+
+        let json =
+            r#"{"reserves": [{"type": "bank", "account_id": "a", "path": "a.csv", "tokens": []}]}"#;
+        assert!(serde_json::from_str::<ReserveConfig>(json).is_err());
+    }
+
+    #[test]
+    fn reserve_ids_and_assets() {
+        let cfg = expected_config();
+
+        assert_eq!(
+            cfg.reserves[0].id(),
+            "0x90213de428e9ce4c77dd4943755aa69cb2f803b7"
+        );
+        assert_eq!(
+            cfg.reserves[1].id(),
+            "0x1111111111111111111111111111111111111111"
+        );
+        assert_eq!(cfg.reserves[2].id(), "bank-account-1");
+
+        assert_eq!(
+            cfg.get_reserve("0x90213de428e9ce4c77dd4943755aa69cb2f803b7"),
+            Some(&cfg.reserves[0])
+        );
+        assert_eq!(cfg.get_reserve("bank-account-2"), None);
+
+        // Ethereum assets are matched by their lowercase contract address.
+        assert_eq!(
+            cfg.reserves[1].token_id_for_asset("0xd92e713d051c37ebb2561803a3b5fbabc4962431"),
+            Some(TokenId::from(1))
+        );
+        assert_eq!(
+            cfg.reserves[2].token_id_for_asset("USD"),
+            Some(TokenId::from(2))
+        );
+        assert_eq!(cfg.reserves[2].token_id_for_asset("EUR"), None);
+    }
+
+    #[test]
+    fn from_gnosis_safe_config() {
+        let gnosis_safe_config = GnosisSafeConfig {
+            safes: vec![match expected_config().reserves.remove(0) {
+                AuditedReserveConfig::GnosisSafe(safe) => safe,
+                _ => unreachable!(),
+            }],
+        };
+
+        let cfg = ReserveConfig::from(gnosis_safe_config);
+        assert_eq!(cfg.reserves, vec![expected_config().reserves.remove(0)]);
+    }
+
+    #[test]
+    fn configs_from_path() {
+        let dir = tempdir().unwrap();
+        let toml_file = dir.path().join("testing.toml");
+        fs::write(&toml_file, INPUT_TOML).unwrap();
+        let json_file = dir.path().join("testing.json");
+        fs::write(&json_file, INPUT_JSON).unwrap();
+        let cfg1 = ReserveConfig::load_from_path(toml_file).unwrap();
+        let cfg2 = ReserveConfig::load_from_path(json_file).unwrap();
+        assert_eq!(cfg1, cfg2);
+
+        let poml_file = dir.path().join("testing.poml");
+        fs::write(&poml_file, INPUT_TOML).unwrap();
+        assert!(matches!(
+            ReserveConfig::load_from_path(poml_file),
+            Err(Error::UnrecognizedExtension(_))
+        ));
+    }
+}
@@ -0,0 +1,681 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A [ReserveSource] for plain Ethereum wallets, backed by an Ethereum
+//! JSON-RPC node.
+//!
+//! Deposits are ERC-20 `Transfer` events into the wallet. Withdrawals are
+//! `Transfer` events out of the wallet that are accompanied, in the same
+//! Ethereum transaction, by a `MetadataReceived` event emitted by the token's
+//! auxiliary burn contract. The event metadata records the matching MobileCoin
+//! burn TxOut public key, the same way a Gnosis safe withdrawal does.
+
+use super::{Erc20ReserveConfig, ReserveCursor, ReserveEvents, ReserveSource, ReserveTransactions};
+use crate::{
+    db::{ReserveDeposit, ReserveTx, ReserveWithdrawal},
+    gnosis::{Error, EthAddr, EthTxHash},
+    MintTxNonce,
+};
+use mc_common::logger::{log, o, Logger};
+use reqwest::{blocking::Client, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{cmp::min, collections::BTreeMap, str::FromStr};
+use url::Url;
+
+/// keccak256("Transfer(address,address,uint256)")
+const TRANSFER_EVENT_TOPIC: &str =
+    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// keccak256("MetadataReceived(address,bytes)")
+const METADATA_RECEIVED_EVENT_TOPIC: &str =
+    "0xa47e9d531af412c8c4d6b01f2910fab0634dc9b5a6bf574aac39b74d06cd5feb";
+
+/// Maximum number of Ethereum blocks to query logs for in a single fetch.
+/// JSON-RPC providers commonly reject `eth_getLogs` calls spanning more.
+const MAX_BLOCKS_PER_FETCH: u64 = 10_000;
+
+/// Length of an ABI-encoded word.
+const WORD_LEN: usize = 32;
+
+/// An Ethereum log entry, as returned by `eth_getLogs`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EthLog {
+    /// The contract that emitted the log.
+    address: EthAddr,
+
+    /// Indexed event topics (hex-encoded, 32 bytes each).
+    topics: Vec<String>,
+
+    /// Non-indexed event data (hex-encoded).
+    data: String,
+
+    /// Block number (hex-encoded quantity).
+    block_number: String,
+
+    /// Transaction hash.
+    transaction_hash: EthTxHash,
+
+    /// Log index within the block (hex-encoded quantity).
+    log_index: String,
+}
+
+/// All the logs relevant to the reserve in a single Ethereum transaction. This
+/// is what gets stored in the database as the raw transaction.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Erc20Transaction {
+    /// Transaction hash
+    tx_hash: EthTxHash,
+
+    /// Ethereum block number.
+    eth_block_number: u64,
+
+    /// Logs, ordered by log index.
+    logs: Vec<EthLog>,
+}
+
+/// JSON-RPC error object.
+#[derive(Clone, Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+/// JSON-RPC response.
+#[derive(Clone, Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+/// A [ReserveSource] for plain Ethereum wallets, backed by an Ethereum
+/// JSON-RPC node.
+pub struct Erc20JsonRpcSource {
+    config: Erc20ReserveConfig,
+    reserve_id: String,
+    client: Client,
+    logger: Logger,
+}
+
+impl Erc20JsonRpcSource {
+    /// Instantiate a new [Erc20JsonRpcSource] querying the configured
+    /// JSON-RPC endpoint, starting at the configured start block.
+    pub fn new(config: Erc20ReserveConfig, logger: Logger) -> Result<Self, Error> {
+        let logger = logger.new(o!("rpc_url" => config.rpc_url.to_string()));
+
+        let client = Client::builder()
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create reqwest client: {}", e)))?;
+
+        Ok(Self {
+            reserve_id: config.wallet_addr.0.to_lowercase(),
+            config,
+            client,
+            logger,
+        })
+    }
+
+    /// Perform a JSON-RPC call.
+    fn rpc_call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+        log::trace!(self.logger, "Calling {} with {}", method, params);
+
+        let response = self
+            .client
+            .post(self.config.rpc_url.clone())
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .map_err(|err| Error::Other(format!("Failed calling {}: {}", method, err)))?;
+        if response.status() != StatusCode::OK {
+            return Err(Error::Other(format!(
+                "Failed calling {}: Expected status 200, got {}",
+                method,
+                response.status()
+            )));
+        }
+
+        let response: JsonRpcResponse<T> = response.json().map_err(|err| {
+            Error::ApiResultParse(format!("Failed parsing {} response: {}", method, err))
+        })?;
+        match (response.result, response.error) {
+            (_, Some(err)) => Err(Error::Other(format!(
+                "{} returned error {}: {}",
+                method, err.code, err.message
+            ))),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(Error::ApiResultParse(format!(
+                "{} response has neither a result nor an error",
+                method
+            ))),
+        }
+    }
+
+    /// Get logs emitted by any of the given contracts in a range of blocks.
+    fn get_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        addresses: &[EthAddr],
+        topics: Value,
+    ) -> Result<Vec<EthLog>, Error> {
+        self.rpc_call(
+            "eth_getLogs",
+            json!([{
+                "fromBlock": format!("0x{:x}", from_block),
+                "toBlock": format!("0x{:x}", to_block),
+                "address": addresses,
+                "topics": topics,
+            }]),
+        )
+    }
+
+    /// Group logs by the transaction that emitted them, dropping duplicates (a
+    /// transfer from the reserve to itself is returned by both transfer
+    /// queries).
+    fn group_logs(logs: Vec<EthLog>) -> Result<Vec<Erc20Transaction>, Error> {
+        let mut txs = BTreeMap::<(u64, EthTxHash), BTreeMap<u64, EthLog>>::new();
+        for eth_log in logs {
+            let block_number = parse_quantity(&eth_log.block_number)?;
+            let log_index = parse_quantity(&eth_log.log_index)?;
+            txs.entry((block_number, eth_log.transaction_hash))
+                .or_default()
+                .insert(log_index, eth_log);
+        }
+
+        Ok(txs
+            .into_iter()
+            .map(|((eth_block_number, tx_hash), logs)| Erc20Transaction {
+                tx_hash,
+                eth_block_number,
+                logs: logs.into_values().collect(),
+            })
+            .collect())
+    }
+}
+
+impl ReserveSource for Erc20JsonRpcSource {
+    fn fetch_since(&mut self, cursor: Option<ReserveCursor>) -> Result<ReserveTransactions, Error> {
+        // The cursor is the last block we have fetched logs for.
+        let from_block = cursor
+            .map(|block| block + 1)
+            .unwrap_or(self.config.start_block);
+        let latest_block = parse_quantity(&self.rpc_call::<String>("eth_blockNumber", json!([]))?)?;
+        let to_block = match confirmed_to_block(from_block, latest_block, self.config.confirmations)
+        {
+            Some(to_block) => to_block,
+            None => {
+                return Ok(ReserveTransactions {
+                    transactions: vec![],
+                    next_cursor: cursor,
+                })
+            }
+        };
+
+        log::debug!(
+            self.logger,
+            "Fetching logs for blocks {}-{}",
+            from_block,
+            to_block
+        );
+
+        let reserve_topic = address_to_topic(&self.config.wallet_addr)?;
+        let token_addrs = self
+            .config
+            .tokens
+            .iter()
+            .map(|token| token.token_contract_addr.clone())
+            .collect::<Vec<_>>();
+        let aux_burn_contract_addrs = self
+            .config
+            .tokens
+            .iter()
+            .map(|token| token.aux_burn_contract_addr.clone())
+            .collect::<Vec<_>>();
+
+        let mut logs = Vec::new();
+        if !token_addrs.is_empty() {
+            // Transfers out of the reserve.
+            logs.extend(self.get_logs(
+                from_block,
+                to_block,
+                &token_addrs,
+                json!([TRANSFER_EVENT_TOPIC, reserve_topic]),
+            )?);
+
+            // Transfers into the reserve.
+            logs.extend(self.get_logs(
+                from_block,
+                to_block,
+                &token_addrs,
+                json!([TRANSFER_EVENT_TOPIC, Value::Null, reserve_topic]),
+            )?);
+
+            // Burn TxOut public keys recorded by the reserve.
+            logs.extend(self.get_logs(
+                from_block,
+                to_block,
+                &aux_burn_contract_addrs,
+                json!([METADATA_RECEIVED_EVENT_TOPIC, reserve_topic]),
+            )?);
+        }
+
+        let transactions = Self::group_logs(logs)?
+            .into_iter()
+            .map(|tx| {
+                Ok(ReserveTx::new(
+                    self.reserve_id.clone(),
+                    tx.tx_hash.to_string(),
+                    serde_json::to_string(&tx)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ReserveTransactions {
+            transactions,
+            next_cursor: Some(to_block),
+        })
+    }
+
+    fn parse_transaction(&self, raw_tx: &ReserveTx) -> Result<ReserveEvents, Error> {
+        let tx: Erc20Transaction = serde_json::from_str(&raw_tx.raw_tx_json)?;
+        let reserve_addr = &self.config.wallet_addr;
+
+        // Collect the burn TxOut public keys recorded by the reserve in this
+        // transaction, keyed by the auxiliary contract that recorded them.
+        let mut recorded_pub_keys = Vec::new();
+        for eth_log in &tx.logs {
+            if eth_log.topics.get(0).map(String::as_str) != Some(METADATA_RECEIVED_EVENT_TOPIC) {
+                continue;
+            }
+            if topic_to_address(eth_log.topics.get(1))? != *reserve_addr {
+                continue;
+            }
+            let metadata = decode_abi_bytes(&eth_log.data)?;
+            if metadata.len() < 32 {
+                return Err(Error::ApiResultParse(format!(
+                    "metadata {} in eth tx {} does not contain enough bytes",
+                    eth_log.data, tx.tx_hash
+                )));
+            }
+            // The tx out pub key is the last 32 bytes.
+            recorded_pub_keys.push((
+                eth_log.address.clone(),
+                hex::encode(&metadata[metadata.len() - 32..]),
+            ));
+        }
+
+        let mut events = ReserveEvents::default();
+        for eth_log in &tx.logs {
+            if eth_log.topics.get(0).map(String::as_str) != Some(TRANSFER_EVENT_TOPIC) {
+                continue;
+            }
+            let audited_token = match self.config.get_token_by_contract_addr(&eth_log.address) {
+                Some(token) => token,
+                None => {
+                    log::warn!(
+                        self.logger,
+                        "Ignoring transfer of unknown token {} in eth tx {}",
+                        eth_log.address,
+                        tx.tx_hash
+                    );
+                    continue;
+                }
+            };
+
+            let from = topic_to_address(eth_log.topics.get(1))?;
+            let to = topic_to_address(eth_log.topics.get(2))?;
+            let value = decode_u64(&eth_log.data)?;
+
+            if to == *reserve_addr {
+                log::info!(
+                    self.logger,
+                    "Processing ERC-20 deposit of {} {} in eth tx {}",
+                    value,
+                    eth_log.address,
+                    tx.tx_hash
+                );
+                events.deposits.push(ReserveDeposit::new(
+                    None,
+                    self.reserve_id.clone(),
+                    tx.tx_hash.to_string(),
+                    tx.eth_block_number,
+                    eth_log.address.0.to_lowercase(),
+                    value,
+                    MintTxNonce::EthereumGnosisDeposit(tx.tx_hash).to_bytes(),
+                ));
+            }
+
+            if from == *reserve_addr {
+                let pub_key_index = recorded_pub_keys
+                    .iter()
+                    .position(|(aux_addr, _)| *aux_addr == audited_token.aux_burn_contract_addr);
+                match pub_key_index {
+                    Some(index) => {
+                        let (_, pub_key_hex) = recorded_pub_keys.remove(index);
+                        log::info!(
+                            self.logger,
+                            "Processing ERC-20 withdrawal of {} {} in eth tx {}",
+                            value,
+                            eth_log.address,
+                            tx.tx_hash
+                        );
+                        events.withdrawals.push(ReserveWithdrawal::new(
+                            None,
+                            self.reserve_id.clone(),
+                            tx.tx_hash.to_string(),
+                            tx.eth_block_number,
+                            eth_log.address.0.to_lowercase(),
+                            value,
+                            pub_key_hex,
+                        ));
+                    }
+                    None => {
+                        log::warn!(
+                            self.logger,
+                            "Transfer out of {} in eth tx {} has no recorded burn TxOut public key",
+                            reserve_addr,
+                            tx.tx_hash
+                        );
+                    }
+                }
+            }
+        }
+
+        // The database (and the MintTx nonce) allows for a single deposit and a
+        // single withdrawal per Ethereum transaction.
+        if events.deposits.len() > 1 || events.withdrawals.len() > 1 {
+            log::crit!(
+                self.logger,
+                "Eth tx {} contains {} deposits and {} withdrawals, only the first of each will be audited",
+                tx.tx_hash,
+                events.deposits.len(),
+                events.withdrawals.len()
+            );
+            events.deposits.truncate(1);
+            events.withdrawals.truncate(1);
+        }
+
+        Ok(events)
+    }
+}
+
+/// The last block to fetch logs for, starting at `from_block`, or None if no
+/// block from `from_block` on has enough confirmations yet.
+fn confirmed_to_block(from_block: u64, latest_block: u64, confirmations: u64) -> Option<u64> {
+    let last_confirmed_block = latest_block.checked_sub(confirmations)?;
+    if from_block > last_confirmed_block {
+        return None;
+    }
+    Some(min(
+        last_confirmed_block,
+        from_block.saturating_add(MAX_BLOCKS_PER_FETCH - 1),
+    ))
+}
+
+/// Parse a hex-encoded JSON-RPC quantity.
+fn parse_quantity(src: &str) -> Result<u64, Error> {
+    src.strip_prefix("0x")
+        .and_then(|hex_str| u64::from_str_radix(hex_str, 16).ok())
+        .ok_or_else(|| Error::ApiResultParse(format!("invalid quantity: {}", src)))
+}
+
+/// Left-pad an address into a 32 bytes topic.
+fn address_to_topic(addr: &EthAddr) -> Result<String, Error> {
+    let hex_str = addr
+        .0
+        .strip_prefix("0x")
+        .ok_or_else(|| Error::InvalidAddress(addr.to_string()))?;
+    Ok(format!("0x{:0>64}", hex_str.to_lowercase()))
+}
+
+/// Extract the address stored in the last 20 bytes of a topic.
+fn topic_to_address(topic: Option<&String>) -> Result<EthAddr, Error> {
+    let topic = topic.ok_or_else(|| Error::ApiResultParse("missing log topic".into()))?;
+    if topic.len() != 2 + 2 * WORD_LEN {
+        return Err(Error::ApiResultParse(format!("invalid topic: {}", topic)));
+    }
+    EthAddr::from_str(&format!("0x{}", &topic[topic.len() - 2 * EthAddr::LEN..]))
+}
+
+/// Decode a hex-encoded `uint256` that is expected to fit in a u64.
+fn decode_u64(data: &str) -> Result<u64, Error> {
+    let bytes = decode_hex(data)?;
+    if bytes.len() != WORD_LEN || bytes[..WORD_LEN - 8].iter().any(|b| *b != 0) {
+        return Err(Error::ApiResultParse(format!(
+            "value {} cannot be converted to u64",
+            data
+        )));
+    }
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&bytes[WORD_LEN - 8..]);
+    Ok(u64::from_be_bytes(value))
+}
+
+/// Decode hex-encoded event data that contains a single ABI-encoded `bytes`
+/// value.
+fn decode_abi_bytes(data: &str) -> Result<Vec<u8>, Error> {
+    let bytes = decode_hex(data)?;
+    let invalid = || Error::ApiResultParse(format!("invalid ABI-encoded bytes: {}", data));
+
+    let read_word = |offset: usize| -> Result<usize, Error> {
+        let end = offset.checked_add(WORD_LEN).ok_or_else(invalid)?;
+        let word = bytes.get(offset..end).ok_or_else(invalid)?;
+        if word[..WORD_LEN - 8].iter().any(|b| *b != 0) {
+            return Err(invalid());
+        }
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&word[WORD_LEN - 8..]);
+        usize::try_from(u64::from_be_bytes(value)).map_err(|_| invalid())
+    };
+
+    let offset = read_word(0)?;
+    let len = read_word(offset)?;
+    let start = offset + WORD_LEN;
+    Ok(bytes
+        .get(start..start.checked_add(len).ok_or_else(invalid)?)
+        .ok_or_else(invalid)?
+        .to_vec())
+}
+
+/// Decode a 0x-prefixed hex string.
+fn decode_hex(data: &str) -> Result<Vec<u8>, Error> {
+    data.strip_prefix("0x")
+        .and_then(|hex_str| hex::decode(hex_str).ok())
+        .ok_or_else(|| Error::ApiResultParse(format!("invalid hex data: {}", data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::{AUX_BURN_CONTRACT_ADDR, ETH_TOKEN_CONTRACT_ADDR, SAFE_ADDR},
+        reserve::Erc20TokenConfig,
+    };
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::TokenId;
+
+    const TX_HASH: &str = "0x0e781edb7739aa88ad2ffb6a69aab46ff9e32dbd0f0c87e4006a176838b075d2";
+    const OTHER_ADDR: &str = "0xdc079a637a1417020916FfB8a39fF5a2801A0F07";
+
+    fn create_source(logger: Logger) -> Erc20JsonRpcSource {
+        let config = Erc20ReserveConfig {
+            wallet_addr: EthAddr::from_str(SAFE_ADDR).unwrap(),
+            rpc_url: Url::parse("http://unused:8545").unwrap(),
+            start_block: 0,
+            confirmations: Erc20ReserveConfig::DEFAULT_CONFIRMATIONS,
+            tokens: vec![Erc20TokenConfig {
+                token_id: TokenId::from(1),
+                token_contract_addr: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+                aux_burn_contract_addr: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+            }],
+        };
+        Erc20JsonRpcSource::new(config, logger).unwrap()
+    }
+
+    fn transfer_log(from: &str, to: &str, value: u64, log_index: u64) -> EthLog {
+        EthLog {
+            address: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+            topics: vec![
+                TRANSFER_EVENT_TOPIC.to_string(),
+                address_to_topic(&EthAddr::from_str(from).unwrap()).unwrap(),
+                address_to_topic(&EthAddr::from_str(to).unwrap()).unwrap(),
+            ],
+            data: format!("0x{:064x}", value),
+            block_number: "0xa63566".to_string(),
+            transaction_hash: EthTxHash::from_str(TX_HASH).unwrap(),
+            log_index: format!("0x{:x}", log_index),
+        }
+    }
+
+    fn metadata_log(pub_key: &[u8; 32], log_index: u64) -> EthLog {
+        EthLog {
+            address: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+            topics: vec![
+                METADATA_RECEIVED_EVENT_TOPIC.to_string(),
+                address_to_topic(&EthAddr::from_str(SAFE_ADDR).unwrap()).unwrap(),
+            ],
+            data: format!("0x{:064x}{:064x}{}", 0x20, 32, hex::encode(pub_key)),
+            block_number: "0xa63566".to_string(),
+            transaction_hash: EthTxHash::from_str(TX_HASH).unwrap(),
+            log_index: format!("0x{:x}", log_index),
+        }
+    }
+
+    fn to_raw_tx(logs: Vec<EthLog>) -> ReserveTx {
+        let mut txs = Erc20JsonRpcSource::group_logs(logs).unwrap();
+        assert_eq!(txs.len(), 1);
+        let tx = txs.remove(0);
+        ReserveTx::new(
+            SAFE_ADDR.to_lowercase(),
+            tx.tx_hash.to_string(),
+            serde_json::to_string(&tx).unwrap(),
+        )
+    }
+
+    #[test_with_logger]
+    fn parse_deposit(logger: Logger) {
+        let source = create_source(logger);
+        let raw_tx = to_raw_tx(vec![transfer_log(OTHER_ADDR, SAFE_ADDR, 1000, 3)]);
+        assert_eq!(raw_tx.tx_id, TX_HASH);
+
+        let events = source.parse_transaction(&raw_tx).unwrap();
+        assert_eq!(
+            events,
+            ReserveEvents {
+                deposits: vec![ReserveDeposit::new(
+                    None,
+                    SAFE_ADDR.to_lowercase(),
+                    TX_HASH.to_string(),
+                    0xa63566,
+                    ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+                    1000,
+                    MintTxNonce::EthereumGnosisDeposit(EthTxHash::from_str(TX_HASH).unwrap())
+                        .to_bytes(),
+                )],
+                withdrawals: vec![],
+            }
+        );
+    }
+
+    #[test_with_logger]
+    fn parse_withdrawal(logger: Logger) {
+        let source = create_source(logger);
+        let pub_key = [7u8; 32];
+
+        // A transfer out without a recorded public key is not a withdrawal we can
+        // audit.
+        let raw_tx = to_raw_tx(vec![transfer_log(SAFE_ADDR, OTHER_ADDR, 500, 1)]);
+        assert_eq!(
+            source.parse_transaction(&raw_tx).unwrap(),
+            ReserveEvents::default()
+        );
+
+        // Logs are ordered by log index regardless of the order they were fetched
+        // in.
+        let raw_tx = to_raw_tx(vec![
+            metadata_log(&pub_key, 2),
+            transfer_log(SAFE_ADDR, OTHER_ADDR, 500, 1),
+        ]);
+        let events = source.parse_transaction(&raw_tx).unwrap();
+        assert_eq!(
+            events,
+            ReserveEvents {
+                deposits: vec![],
+                withdrawals: vec![ReserveWithdrawal::new(
+                    None,
+                    SAFE_ADDR.to_lowercase(),
+                    TX_HASH.to_string(),
+                    0xa63566,
+                    ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+                    500,
+                    hex::encode(pub_key),
+                )],
+            }
+        );
+    }
+
+    #[test_with_logger]
+    fn parse_ignores_unknown_tokens(logger: Logger) {
+        let source = create_source(logger);
+        let mut eth_log = transfer_log(OTHER_ADDR, SAFE_ADDR, 1000, 0);
+        eth_log.address = EthAddr::from_str(OTHER_ADDR).unwrap();
+
+        let raw_tx = to_raw_tx(vec![eth_log]);
+        assert_eq!(
+            source.parse_transaction(&raw_tx).unwrap(),
+            ReserveEvents::default()
+        );
+    }
+
+    #[test]
+    fn group_logs_drops_duplicates() {
+        // A self-transfer shows up in both the incoming and outgoing queries.
+        let eth_log = transfer_log(SAFE_ADDR, SAFE_ADDR, 1, 5);
+        let txs = Erc20JsonRpcSource::group_logs(vec![eth_log.clone(), eth_log.clone()]).unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].logs, vec![eth_log]);
+    }
+
+    #[test]
+    fn fetch_range_is_capped_at_confirmed_blocks() {
+        // Nothing is confirmed until the chain is deep enough.
+        assert_eq!(confirmed_to_block(0, 11, 12), None);
+        assert_eq!(confirmed_to_block(0, 12, 12), Some(0));
+
+        // Blocks without enough confirmations are left for the next fetch.
+        assert_eq!(confirmed_to_block(100, 111, 12), None);
+        assert_eq!(confirmed_to_block(100, 150, 12), Some(138));
+        assert_eq!(confirmed_to_block(100, 150, 0), Some(150));
+
+        // Long ranges are split across fetches.
+        assert_eq!(
+            confirmed_to_block(100, 100_000, 12),
+            Some(100 + MAX_BLOCKS_PER_FETCH - 1)
+        );
+    }
+
+    #[test]
+    fn abi_decoding() {
+        assert_eq!(decode_u64(&format!("0x{:064x}", 1234)).unwrap(), 1234);
+        assert!(decode_u64(&format!("0x1{:063x}", 1234)).is_err());
+        assert!(decode_u64("0x1234").is_err());
+
+        let data = format!(
+            "0x{:064x}{:064x}{}",
+            0x20,
+            3,
+            "abcdef".to_string() + &"0".repeat(58)
+        );
+        assert_eq!(decode_abi_bytes(&data).unwrap(), vec![0xab, 0xcd, 0xef]);
+        assert!(decode_abi_bytes(&format!("0x{:064x}{:064x}", 0x20, 3)).is_err());
+
+        let addr = EthAddr::from_str(SAFE_ADDR).unwrap();
+        let topic = address_to_topic(&addr).unwrap();
+        assert_eq!(topic.len(), 66);
+        assert_eq!(topic_to_address(Some(&topic)).unwrap(), addr);
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A [ReserveSource] that imports deposits and withdrawals from a CSV or JSON
+//! file, for reserves that are not on a blockchain we can query directly (e.g.
+//! a custodial bank ledger).
+//!
+//! The file is expected to only ever be appended to. The cursor is the number
+//! of records that were already imported.
+
+use super::{
+    FileImportReserveConfig, ReserveCursor, ReserveEvents, ReserveSource, ReserveTransactions,
+};
+use crate::{
+    db::{ReserveDeposit, ReserveTx, ReserveWithdrawal},
+    gnosis::Error,
+};
+use mc_common::logger::{log, o, Logger};
+use mc_transaction_core::mint::constants::NONCE_LENGTH;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Whether a record moves funds into or out of the reserve.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileImportRecordType {
+    /// Funds moved into the reserve.
+    Deposit,
+
+    /// Funds moved out of the reserve.
+    Withdrawal,
+}
+
+/// A single deposit or withdrawal in an imported file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileImportRecord {
+    /// Unique identifier of the record in the account's history (e.g. a bank
+    /// transaction reference).
+    pub id: String,
+
+    /// Position of the record in the account's history (e.g. a ledger entry
+    /// number).
+    pub position: u64,
+
+    /// Whether this is a deposit or a withdrawal.
+    #[serde(rename = "type")]
+    pub record_type: FileImportRecordType,
+
+    /// The asset moved, matching `asset` in the audited token configuration.
+    pub asset: String,
+
+    /// The amount deposited or withdrawn.
+    pub amount: u64,
+
+    /// The hex-encoded nonce of the MintTx that matches a deposit. Required
+    /// for deposits.
+    #[serde(default)]
+    pub mc_mint_tx_nonce: Option<String>,
+
+    /// The hex-encoded public key of the burn TxOut that matches a withdrawal.
+    /// Required for withdrawals.
+    #[serde(default)]
+    pub mc_tx_out_public_key: Option<String>,
+}
+
+impl FileImportRecord {
+    /// Get the MintTx nonce of a deposit, making sure it is valid.
+    fn mc_mint_tx_nonce(&self) -> Result<[u8; NONCE_LENGTH], Error> {
+        let nonce_hex = self.mc_mint_tx_nonce.as_ref().ok_or_else(|| {
+            Error::ApiResultParse(format!("deposit {} is missing mc_mint_tx_nonce", self.id))
+        })?;
+        hex::decode(nonce_hex)
+            .ok()
+            .and_then(|bytes| <[u8; NONCE_LENGTH]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                Error::ApiResultParse(format!(
+                    "deposit {} has an invalid mc_mint_tx_nonce: {}",
+                    self.id, nonce_hex
+                ))
+            })
+    }
+
+    /// Get the burn TxOut public key of a withdrawal, making sure it is
+    /// valid.
+    fn mc_tx_out_public_key_hex(&self) -> Result<String, Error> {
+        let pub_key_hex = self.mc_tx_out_public_key.as_ref().ok_or_else(|| {
+            Error::ApiResultParse(format!(
+                "withdrawal {} is missing mc_tx_out_public_key",
+                self.id
+            ))
+        })?;
+        match hex::decode(pub_key_hex) {
+            Ok(bytes) if bytes.len() == 32 => Ok(hex::encode(bytes)),
+            _ => Err(Error::ApiResultParse(format!(
+                "withdrawal {} has an invalid mc_tx_out_public_key: {}",
+                self.id, pub_key_hex
+            ))),
+        }
+    }
+}
+
+/// A [ReserveSource] that imports deposits and withdrawals from a CSV or JSON
+/// file.
+pub struct FileImportSource {
+    config: FileImportReserveConfig,
+    logger: Logger,
+}
+
+impl FileImportSource {
+    /// Instantiate a new [FileImportSource] reading from the configured path.
+    pub fn new(config: FileImportReserveConfig, logger: Logger) -> Self {
+        let logger = logger.new(o!("path" => config.path.display().to_string()));
+        Self { config, logger }
+    }
+
+    /// Read and validate all records in the file.
+    fn read_records(&self) -> Result<Vec<FileImportRecord>, Error> {
+        let path = &self.config.path;
+        let records: Vec<FileImportRecord> = match path.extension().and_then(|ext| ext.to_str()) {
+            None => Err(Error::PathExtension),
+            Some("csv") => csv::Reader::from_path(path)
+                .and_then(|mut reader| reader.deserialize().collect())
+                .map_err(|err| {
+                    Error::Other(format!(
+                        "Failed parsing CSV file {}: {}",
+                        path.display(),
+                        err
+                    ))
+                }),
+            Some("json") => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            Some(ext) => Err(Error::UnrecognizedExtension(ext.to_string())),
+        }?;
+
+        // Reject the whole file if a record cannot be audited, so that the
+        // problem gets noticed and fixed before anything is imported.
+        for record in &records {
+            match record.record_type {
+                FileImportRecordType::Deposit => {
+                    record.mc_mint_tx_nonce()?;
+                }
+                FileImportRecordType::Withdrawal => {
+                    record.mc_tx_out_public_key_hex()?;
+                }
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+impl ReserveSource for FileImportSource {
+    fn fetch_since(&mut self, cursor: Option<ReserveCursor>) -> Result<ReserveTransactions, Error> {
+        let records = self.read_records()?;
+
+        let num_imported = cursor.unwrap_or_default() as usize;
+        if num_imported > records.len() {
+            return Err(Error::Other(format!(
+                "File contains {} records, but {} were already imported",
+                records.len(),
+                num_imported
+            )));
+        }
+        log::debug!(
+            self.logger,
+            "Importing {} new records",
+            records.len() - num_imported
+        );
+
+        let transactions = records[num_imported..]
+            .iter()
+            .map(|record| {
+                Ok(ReserveTx::new(
+                    self.config.account_id.clone(),
+                    record.id.clone(),
+                    serde_json::to_string(record)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ReserveTransactions {
+            transactions,
+            next_cursor: Some(records.len() as u64),
+        })
+    }
+
+    fn parse_transaction(&self, raw_tx: &ReserveTx) -> Result<ReserveEvents, Error> {
+        let record: FileImportRecord = serde_json::from_str(&raw_tx.raw_tx_json)?;
+        log::info!(self.logger, "Processing imported record: {:?}", record);
+
+        Ok(match record.record_type {
+            FileImportRecordType::Deposit => ReserveEvents {
+                deposits: vec![ReserveDeposit::new(
+                    None,
+                    self.config.account_id.clone(),
+                    record.id.clone(),
+                    record.position,
+                    record.asset.clone(),
+                    record.amount,
+                    record.mc_mint_tx_nonce()?,
+                )],
+                ..Default::default()
+            },
+            FileImportRecordType::Withdrawal => ReserveEvents {
+                withdrawals: vec![ReserveWithdrawal::new(
+                    None,
+                    self.config.account_id.clone(),
+                    record.id.clone(),
+                    record.position,
+                    record.asset.clone(),
+                    record.amount,
+                    record.mc_tx_out_public_key_hex()?,
+                )],
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reserve::FileImportTokenConfig;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::TokenId;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    const ACCOUNT_ID: &str = "bank-account-1";
+    const DEPOSIT_ID: &str = "TRN-0001";
+    const WITHDRAWAL_ID: &str = "TRN-0002";
+    const PUB_KEY_HEX: &str = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+
+    fn nonce_hex() -> String {
+        hex::encode([3u8; NONCE_LENGTH])
+    }
+
+    fn test_config(path: PathBuf) -> FileImportReserveConfig {
+        FileImportReserveConfig {
+            account_id: ACCOUNT_ID.to_string(),
+            path,
+            tokens: vec![FileImportTokenConfig {
+                token_id: TokenId::from(1),
+                asset: "USD".to_string(),
+            }],
+        }
+    }
+
+    fn csv_contents() -> String {
+        format!(
+            "id,position,type,asset,amount,mc_mint_tx_nonce,mc_tx_out_public_key\n\
+             {},1,deposit,USD,1000,{},\n\
+             {},2,withdrawal,USD,500,,{}\n",
+            DEPOSIT_ID,
+            nonce_hex(),
+            WITHDRAWAL_ID,
+            PUB_KEY_HEX,
+        )
+    }
+
+    fn json_contents() -> String {
+        format!(
+            r#"[
+                {{"id": "{}", "position": 1, "type": "deposit", "asset": "USD", "amount": 1000, "mc_mint_tx_nonce": "{}"}},
+                {{"id": "{}", "position": 2, "type": "withdrawal", "asset": "USD", "amount": 500, "mc_tx_out_public_key": "{}"}}
+            ]"#,
+            DEPOSIT_ID,
+            nonce_hex(),
+            WITHDRAWAL_ID,
+            PUB_KEY_HEX,
+        )
+    }
+
+    fn expected_events() -> Vec<ReserveEvents> {
+        vec![
+            ReserveEvents {
+                deposits: vec![ReserveDeposit::new(
+                    None,
+                    ACCOUNT_ID.to_string(),
+                    DEPOSIT_ID.to_string(),
+                    1,
+                    "USD".to_string(),
+                    1000,
+                    [3u8; NONCE_LENGTH],
+                )],
+                withdrawals: vec![],
+            },
+            ReserveEvents {
+                deposits: vec![],
+                withdrawals: vec![ReserveWithdrawal::new(
+                    None,
+                    ACCOUNT_ID.to_string(),
+                    WITHDRAWAL_ID.to_string(),
+                    2,
+                    "USD".to_string(),
+                    500,
+                    PUB_KEY_HEX.to_string(),
+                )],
+            },
+        ]
+    }
+
+    fn fetch_events(source: &mut FileImportSource) -> Vec<ReserveEvents> {
+        let reserve_transactions = source.fetch_since(None).unwrap();
+        assert_eq!(reserve_transactions.next_cursor, Some(2));
+        reserve_transactions
+            .transactions
+            .iter()
+            .map(|raw_tx| source.parse_transaction(raw_tx).unwrap())
+            .collect()
+    }
+
+    #[test_with_logger]
+    fn import_csv_and_json(logger: Logger) {
+        let dir = tempdir().unwrap();
+
+        let csv_file = dir.path().join("reserve.csv");
+        fs::write(&csv_file, csv_contents()).unwrap();
+        let mut source = FileImportSource::new(test_config(csv_file), logger.clone());
+        assert_eq!(fetch_events(&mut source), expected_events());
+
+        let json_file = dir.path().join("reserve.json");
+        fs::write(&json_file, json_contents()).unwrap();
+        let mut source = FileImportSource::new(test_config(json_file), logger);
+        assert_eq!(fetch_events(&mut source), expected_events());
+    }
+
+    #[test_with_logger]
+    fn fetch_since_cursor(logger: Logger) {
+        let dir = tempdir().unwrap();
+        let csv_file = dir.path().join("reserve.csv");
+        fs::write(&csv_file, csv_contents()).unwrap();
+        let mut source = FileImportSource::new(test_config(csv_file), logger);
+
+        // Nothing new since the last import.
+        let reserve_transactions = source.fetch_since(Some(2)).unwrap();
+        assert!(reserve_transactions.transactions.is_empty());
+        assert_eq!(reserve_transactions.next_cursor, Some(2));
+
+        // Only the records after the cursor are returned.
+        let reserve_transactions = source.fetch_since(Some(1)).unwrap();
+        assert_eq!(reserve_transactions.transactions.len(), 1);
+        assert_eq!(reserve_transactions.transactions[0].reserve_id, ACCOUNT_ID);
+        assert_eq!(reserve_transactions.transactions[0].tx_id, WITHDRAWAL_ID);
+
+        // The file got truncated.
+        assert!(source.fetch_since(Some(3)).is_err());
+    }
+
+    #[test_with_logger]
+    fn invalid_records_are_rejected(logger: Logger) {
+        let dir = tempdir().unwrap();
+
+        // A withdrawal without a burn TxOut public key.
+        let csv_file = dir.path().join("withdrawal.csv");
+        fs::write(
+            &csv_file,
+            format!(
+                "id,position,type,asset,amount,mc_mint_tx_nonce,mc_tx_out_public_key\n\
+                 {},2,withdrawal,USD,500,,\n",
+                WITHDRAWAL_ID,
+            ),
+        )
+        .unwrap();
+        let mut source = FileImportSource::new(test_config(csv_file), logger.clone());
+        assert!(matches!(
+            source.fetch_since(None),
+            Err(Error::ApiResultParse(_))
+        ));
+
+        // A deposit with a truncated MintTx nonce.
+        let csv_file = dir.path().join("deposit.csv");
+        fs::write(
+            &csv_file,
+            format!(
+                "id,position,type,asset,amount,mc_mint_tx_nonce,mc_tx_out_public_key\n\
+                 {},1,deposit,USD,1000,{},\n",
+                DEPOSIT_ID,
+                &nonce_hex()[2..],
+            ),
+        )
+        .unwrap();
+        let mut source = FileImportSource::new(test_config(csv_file), logger);
+        assert!(matches!(
+            source.fetch_since(None),
+            Err(Error::ApiResultParse(_))
+        ));
+    }
+}
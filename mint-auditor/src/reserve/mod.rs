@@ -0,0 +1,88 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Reserve auditing support.
+//!
+//! A reserve holds the assets backing a minted token. Deposits into a reserve
+//! are matched with MintTxs, and withdrawals out of it are matched with burns.
+//! Reserves can live in different places (a Gnosis safe, a plain Ethereum
+//! wallet, a custodial bank account), and a [ReserveSource] abstracts fetching
+//! their deposits and withdrawals.
+
+mod config;
+mod erc20;
+mod file_import;
+mod sync;
+mod sync_thread;
+
+pub use self::{
+    config::{
+        AuditedReserveConfig, Erc20ReserveConfig, Erc20TokenConfig, FileImportReserveConfig,
+        FileImportTokenConfig, ReserveConfig,
+    },
+    erc20::Erc20JsonRpcSource,
+    file_import::{FileImportRecord, FileImportRecordType, FileImportSource},
+    sync::ReserveSync,
+    sync_thread::ReserveSyncThread,
+};
+
+use crate::{
+    db::{ReserveDeposit, ReserveTx, ReserveWithdrawal},
+    gnosis::{Error, GnosisSafeSource},
+};
+use mc_common::logger::Logger;
+
+/// A position in the history of a reserve source, used to resume fetching
+/// where the previous fetch left off. The meaning is specific to each source
+/// (e.g. an Ethereum block number, or a number of records already imported).
+pub type ReserveCursor = u64;
+
+/// Transactions returned by [ReserveSource::fetch_since].
+#[derive(Clone, Debug, Default)]
+pub struct ReserveTransactions {
+    /// The raw transactions. These get stored in the database as-is, and are
+    /// de-duplicated by their reserve and transaction ids.
+    pub transactions: Vec<ReserveTx>,
+
+    /// The cursor to pass to the next [ReserveSource::fetch_since] call.
+    pub next_cursor: Option<ReserveCursor>,
+}
+
+/// Deposits and withdrawals contained in a single reserve transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReserveEvents {
+    /// Deposits into the reserve.
+    pub deposits: Vec<ReserveDeposit>,
+
+    /// Withdrawals out of the reserve.
+    pub withdrawals: Vec<ReserveWithdrawal>,
+}
+
+/// A source of deposits into and withdrawals out of an audited reserve.
+pub trait ReserveSource: Send {
+    /// Fetch the transactions that touched the reserve since the given cursor.
+    /// A `None` cursor fetches everything the source knows about.
+    fn fetch_since(&mut self, cursor: Option<ReserveCursor>) -> Result<ReserveTransactions, Error>;
+
+    /// Extract the deposits and withdrawals contained in a transaction that was
+    /// returned by [ReserveSource::fetch_since]. Transactions that do not
+    /// move funds in or out of the reserve produce no events.
+    fn parse_transaction(&self, tx: &ReserveTx) -> Result<ReserveEvents, Error>;
+}
+
+/// Create the [ReserveSource] configured for an audited reserve.
+pub fn create_reserve_source(
+    reserve: &AuditedReserveConfig,
+    logger: Logger,
+) -> Result<Box<dyn ReserveSource>, Error> {
+    Ok(match reserve {
+        AuditedReserveConfig::GnosisSafe(config) => {
+            Box::new(GnosisSafeSource::new(config.clone(), logger)?)
+        }
+        AuditedReserveConfig::Erc20JsonRpc(config) => {
+            Box::new(Erc20JsonRpcSource::new(config.clone(), logger)?)
+        }
+        AuditedReserveConfig::FileImport(config) => {
+            Box::new(FileImportSource::new(config.clone(), logger))
+        }
+    })
+}
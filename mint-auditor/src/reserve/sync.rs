@@ -0,0 +1,554 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Code for syncing reserve transactions into the SQLite database.
+//!
+//! Each reserve's cursor is stored in the database after its transactions
+//! are processed, so a restart resumes fetching where it left off instead of
+//! re-importing everything.
+//!
+//! NOTE: Right now, if the audited reserves configuration changes, one should
+//! delete the SQLite database and re-audit. The code is not smart enough to
+//! handle adding/removing reserves/tokens for transactions that were already
+//! processed.
+
+use super::{create_reserve_source, AuditedReserveConfig, ReserveSource};
+use crate::{
+    counters,
+    db::{
+        AuditedBurn, AuditedMint, Conn, MintAuditorDb, ReserveDeposit, ReserveSyncCursor,
+        ReserveTx, ReserveWithdrawal,
+    },
+    error::Error,
+    gnosis::Error as GnosisError,
+};
+use mc_common::logger::{log, Logger};
+
+/// An object for syncing transaction data from a [ReserveSource] into the
+/// SQLite database.
+pub struct ReserveSync {
+    source: Box<dyn ReserveSource>,
+    reserve: AuditedReserveConfig,
+    reserve_id: String,
+    mint_auditor_db: MintAuditorDb,
+    logger: Logger,
+}
+
+impl ReserveSync {
+    /// Instantiate a new [ReserveSync] object, using the source configured for
+    /// the audited reserve.
+    pub fn new(
+        reserve: AuditedReserveConfig,
+        mint_auditor_db: MintAuditorDb,
+        logger: Logger,
+    ) -> Result<Self, GnosisError> {
+        let source = create_reserve_source(&reserve, logger.clone())?;
+        Ok(Self::with_source(source, reserve, mint_auditor_db, logger))
+    }
+
+    /// Instantiate a new [ReserveSync] object with a specific source.
+    pub fn with_source(
+        source: Box<dyn ReserveSource>,
+        reserve: AuditedReserveConfig,
+        mint_auditor_db: MintAuditorDb,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            source,
+            reserve_id: reserve.id(),
+            reserve,
+            mint_auditor_db,
+            logger,
+        }
+    }
+
+    /// Poll the reserve source for new transaction data, resuming from the
+    /// cursor stored in the database.
+    pub fn poll(&mut self) {
+        let conn = self
+            .mint_auditor_db
+            .get_conn()
+            .expect("failed getting connection");
+        let cursor =
+            ReserveSyncCursor::get(&self.reserve_id, &conn).expect("failed getting sync cursor");
+
+        match self.source.fetch_since(cursor) {
+            Ok(reserve_transactions) => {
+                self.process_transactions(reserve_transactions.transactions);
+                if let Some(next_cursor) = reserve_transactions.next_cursor {
+                    conn.exclusive_transaction(|| {
+                        ReserveSyncCursor::set(&self.reserve_id, next_cursor, &conn)
+                    })
+                    .expect("failed storing sync cursor");
+                }
+            }
+            Err(err) => {
+                counters::NUM_FAILED_RESERVE_SOURCE_FETCHES.inc();
+                log::error!(
+                    self.logger,
+                    "Failed to fetch reserve transactions for {}: {}",
+                    self.reserve_id,
+                    err
+                );
+            }
+        }
+    }
+
+    /// Process transactions and insert them to the database.
+    pub fn process_transactions(&self, transactions: Vec<ReserveTx>) {
+        for tx in transactions {
+            let conn = self
+                .mint_auditor_db
+                .get_conn()
+                .expect("failed getting connection");
+
+            // SQLite3 does not like concurrent writes. Since we are going to be writing to
+            // the database, ensure we are the only writers.
+            conn.exclusive_transaction(|| {
+                match tx.insert(&conn) {
+                    Ok(_) => {}
+                    Err(Error::AlreadyExists(_)) => {
+                        log::trace!(
+                            self.logger,
+                            "Skipping already-processed reserve transaction {}",
+                            tx.tx_id
+                        );
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        log::error!(self.logger, "Failed to insert ReserveTx: {}", err);
+                        return Err(err);
+                    }
+                };
+
+                let events = self.source.parse_transaction(&tx)?;
+                for deposit in events.deposits {
+                    self.process_deposit(&conn, deposit)?;
+                }
+                for withdrawal in events.withdrawals {
+                    self.process_withdrawal(&conn, withdrawal)?;
+                }
+
+                Ok(())
+            })
+            .expect("failed processing transaction");
+        }
+    }
+
+    /// Store a deposit and attempt to match it with an existing MintTx.
+    fn process_deposit(&self, conn: &Conn, mut deposit: ReserveDeposit) -> Result<(), Error> {
+        deposit.insert(conn)?;
+
+        match AuditedMint::try_match_deposit_with_mint(&deposit, &self.reserve, conn) {
+            Ok(mint_tx) => {
+                log::info!(
+                    self.logger,
+                    "Reserve deposit tx_id={} matched MintTx nonce={}",
+                    deposit.tx_id(),
+                    mint_tx.nonce_hex(),
+                )
+            }
+            Err(Error::NotFound) => {
+                log::debug!(self.logger, "Reserve deposit tx_id={} does not currently have matching MintTx, this could be fine if the ledger is not fully synced.", deposit.tx_id());
+            }
+            Err(err) => {
+                log::error!(
+                    self.logger,
+                    "Reserve deposit tx_id={} failed matching to a MintTx: {}",
+                    deposit.tx_id(),
+                    err
+                );
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Store a withdrawal and attempt to match it with an existing burn.
+    fn process_withdrawal(
+        &self,
+        conn: &Conn,
+        mut withdrawal: ReserveWithdrawal,
+    ) -> Result<(), Error> {
+        withdrawal.insert(conn)?;
+
+        match AuditedBurn::try_match_withdrawal_with_burn(&withdrawal, &self.reserve, conn) {
+            Ok(burn_tx_out) => {
+                log::info!(
+                    self.logger,
+                    "Reserve withdrawal tx_id={} matched BurnTxOut public_key={}",
+                    withdrawal.tx_id(),
+                    burn_tx_out.public_key_hex(),
+                )
+            }
+            Err(Error::NotFound) => {
+                log::debug!(self.logger, "Reserve withdrawal tx_id={} does not currently have matching BurnTxOut, this could be fine if the ledger is not fully synced.", withdrawal.tx_id());
+            }
+            Err(err) => {
+                log::error!(
+                    self.logger,
+                    "Reserve withdrawal tx_id={} failed matching to a BurnTxOut: {}",
+                    withdrawal.tx_id(),
+                    err
+                );
+            }
+        };
+
+        AuditedBurn::update_unaudited_prometheus_gauges(conn)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::{
+            schema::{reserve_deposits, reserve_withdrawals},
+            test_utils::{
+                insert_mint_tx_from_deposit, TestDbContext, AUX_BURN_CONTRACT_ADDR,
+                AUX_BURN_FUNCTION_SIG, ETH_TOKEN_CONTRACT_ADDR, SAFE_ADDR,
+            },
+            MintTx,
+        },
+        gnosis::{
+            api_data_types::{AllTransactionsResponse, RawGnosisTransaction},
+            AuditedSafeConfig, AuditedToken, EthAddr, EthTxHash,
+        },
+        reserve::{ReserveCursor, ReserveEvents, ReserveTransactions},
+        MintTxNonce,
+    };
+    use diesel::prelude::*;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_transaction_core::TokenId;
+    use mc_transaction_core_test_utils::{create_mint_config_tx_and_signers, create_mint_tx};
+    use std::{
+        collections::HashSet,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+    use url::Url;
+
+    // Generated by the following command:
+    // curl -X GET "https://safe-transaction.rinkeby.gnosis.io/api/v1/safes/0xeC018400FFe5Ad6E0B42Aa592Ee1CF6092972dEe/all-transactions/?ordering=-executionDate&offset=0&executed=true&queued=false&trusted=true" -H  "accept: application/json" -H  "X-CSRFToken: skjBYGJ68aaPzCvTmFfKh5EryjEbjHpKRjNGtcqqH8jU7zdzxOR1nGNqoAGb1NGI" | python3 -mjson.tool
+    // This is a test safe that was created on the Rinkeby network and contains some
+    // deposits and withdrawals.
+    const ALL_TRANSACTIONS_JSON: &str = include_str!("../../data/test/all-transactions.json");
+
+    // Helper to parse ALL_TRANSACTIONS_JSON into a list of ReserveTxs fetched
+    // for the given safe.
+    fn get_raw_transactions(audited_safe: &AuditedSafeConfig) -> Vec<ReserveTx> {
+        let all_transactions_response: AllTransactionsResponse =
+            serde_json::from_str(ALL_TRANSACTIONS_JSON).unwrap();
+        all_transactions_response
+            .results
+            .into_iter()
+            .map(|value| {
+                let raw_tx = RawGnosisTransaction::from(value);
+                ReserveTx::new(
+                    audited_safe.safe_addr.0.to_lowercase(),
+                    raw_tx.tx_hash().unwrap().to_string(),
+                    raw_tx.to_json_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn expected_deposit(
+        id: i32,
+        tx_hash: &str,
+        eth_block_number: u64,
+        amount: u64,
+    ) -> ReserveDeposit {
+        let tx_hash = EthTxHash::from_str(tx_hash).unwrap();
+        ReserveDeposit::new(
+            Some(id),
+            SAFE_ADDR.to_lowercase(),
+            tx_hash.to_string(),
+            eth_block_number,
+            ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+            amount,
+            MintTxNonce::EthereumGnosisDeposit(tx_hash).to_bytes(),
+        )
+    }
+
+    #[test_with_logger]
+    fn process_transactions_works(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+
+        // Must match the contents of the test JSON file.
+        let audited_safe_config = AuditedSafeConfig {
+            safe_addr: EthAddr::from_str(SAFE_ADDR).unwrap(),
+            api_url: Url::parse("http://unused:8545").unwrap(),
+            tokens: vec![AuditedToken {
+                token_id: TokenId::from(1),
+                eth_token_contract_addr: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+                aux_burn_contract_addr: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+                aux_burn_function_sig: AUX_BURN_FUNCTION_SIG,
+            }],
+        };
+
+        let raw_transactions = get_raw_transactions(&audited_safe_config);
+
+        let expected_deposits = vec![
+            expected_deposit(
+                2,
+                "0xa202a4c37f0670557ceeb33f796fba0c187f699f5dd4d8add0eba1c3154b2fa7",
+                10824613,
+                1000000,
+            ),
+            expected_deposit(
+                1,
+                "0x4f3124c61c48aa7c7892f8fe426e0c0d8afae100fc0a9aa8e290e530a7632849",
+                10824662,
+                10000000,
+            ),
+        ];
+
+        // Store the MintTxs in the database so we can see they get audited properly.
+        let mint_tx1 = insert_mint_tx_from_deposit(&expected_deposits[0], &conn, &mut rng);
+        let mint_tx2 = insert_mint_tx_from_deposit(&expected_deposits[1], &conn, &mut rng);
+
+        // Add another unrelated MintTx.
+        let token_id = TokenId::from(1);
+        let (_mint_config_tx, signers) = create_mint_config_tx_and_signers(token_id, &mut rng);
+        let mint_tx3 = MintTx::insert_from_core_mint_tx(
+            0,
+            None,
+            &create_mint_tx(token_id, &signers, 100, &mut rng),
+            &conn,
+        )
+        .unwrap();
+
+        // Initially, none are audited.
+        assert_eq!(
+            HashSet::from_iter(MintTx::find_unaudited_mint_txs(&conn).unwrap()),
+            [mint_tx1, mint_tx2, mint_tx3.clone()].into(),
+        );
+
+        // Perform a gnosis sync.
+        let sync = ReserveSync::new(
+            AuditedReserveConfig::GnosisSafe(audited_safe_config),
+            mint_auditor_db,
+            logger,
+        )
+        .unwrap();
+        sync.process_transactions(raw_transactions);
+
+        // Validate that we are seeing the expected deposits.
+        let deposits = reserve_deposits::table
+            .order_by(reserve_deposits::position)
+            .load::<ReserveDeposit>(&conn)
+            .unwrap();
+
+        assert_eq!(deposits, expected_deposits);
+
+        // Validate that we are seeing the expected withdrawals.
+        let withdrawals = reserve_withdrawals::table
+            .order_by(reserve_withdrawals::position)
+            .load::<ReserveWithdrawal>(&conn)
+            .unwrap();
+        let expected_withdrawals = vec![
+            ReserveWithdrawal::new(
+                Some(2),
+                SAFE_ADDR.to_lowercase(),
+                "0x323b145662d2a64de0a55977089b7a89ed6003e341d5a68266a200dde83639d4".to_string(),
+                10824635,
+                ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+                500000,
+                "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".to_string(),
+            ),
+            ReserveWithdrawal::new(
+                Some(1),
+                SAFE_ADDR.to_lowercase(),
+                "0x2f55d7b7620876c1dfc25419937a7fd2538489c1dd3adf6b438396a958d88e28".to_string(),
+                10824678,
+                ETH_TOKEN_CONTRACT_ADDR.to_lowercase(),
+                2000000,
+                "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".to_string(),
+            ),
+        ];
+        assert_eq!(withdrawals, expected_withdrawals);
+
+        // Only the third MintTx remains unaudited.
+        assert_eq!(
+            vec![mint_tx3],
+            MintTx::find_unaudited_mint_txs(&conn).unwrap()
+        );
+    }
+
+    #[test_with_logger]
+    fn process_transactions_ignores_unknown(logger: Logger) {
+        // Must match the contents of the test JSON file.
+        // (Except the safe address, since that is what we are testing here)
+        let unknown_safe_audited_safe_config = AuditedSafeConfig {
+            safe_addr: EthAddr::from_str("0x0000000000000000000000000000000000000000").unwrap(),
+            api_url: Url::parse("http://unused:8545").unwrap(),
+            tokens: vec![AuditedToken {
+                token_id: TokenId::from(1),
+                eth_token_contract_addr: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+                aux_burn_contract_addr: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+                aux_burn_function_sig: AUX_BURN_FUNCTION_SIG,
+            }],
+        };
+
+        // Must match the contents of the test JSON file.
+        // (Except the fields we are purposefully altering to make sure they are
+        // ignored)
+        let unknown_token_audited_safe_config = AuditedSafeConfig {
+            safe_addr: EthAddr::from_str(SAFE_ADDR).unwrap(),
+            api_url: Url::parse("http://unused:8545").unwrap(),
+            tokens: vec![
+                // Unknown token contract address
+                AuditedToken {
+                    token_id: TokenId::from(1),
+                    eth_token_contract_addr: EthAddr::from_str(
+                        "0x0000000000000000000000000000000000000000",
+                    )
+                    .unwrap(),
+                    aux_burn_contract_addr: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+                    aux_burn_function_sig: AUX_BURN_FUNCTION_SIG,
+                },
+                // Unknown aux burn contract address
+                AuditedToken {
+                    token_id: TokenId::from(1),
+                    eth_token_contract_addr: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+                    aux_burn_contract_addr: EthAddr::from_str(
+                        "0x0000000000000000000000000000000000000000",
+                    )
+                    .unwrap(),
+                    aux_burn_function_sig: AUX_BURN_FUNCTION_SIG,
+                },
+                // Unknown aux burn function sig
+                AuditedToken {
+                    token_id: TokenId::from(1),
+                    eth_token_contract_addr: EthAddr::from_str(ETH_TOKEN_CONTRACT_ADDR).unwrap(),
+                    aux_burn_contract_addr: EthAddr::from_str(AUX_BURN_CONTRACT_ADDR).unwrap(),
+                    aux_burn_function_sig: [0xc7, 0x6f, 0x06, 0xFF],
+                },
+            ],
+        };
+
+        // Transactions are stored per reserve, so each configuration gets its own
+        // database to make sure nothing is skipped as already processed.
+        for audited_safe_config in [
+            unknown_safe_audited_safe_config,
+            unknown_token_audited_safe_config,
+        ] {
+            let test_db_context = TestDbContext::default();
+            let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+            let conn = mint_auditor_db.get_conn().unwrap();
+            let raw_transactions = get_raw_transactions(&audited_safe_config);
+            let deposits_expected =
+                audited_safe_config.safe_addr == EthAddr::from_str(SAFE_ADDR).unwrap();
+
+            // Perform a gnosis sync.
+            let sync = ReserveSync::new(
+                AuditedReserveConfig::GnosisSafe(audited_safe_config),
+                mint_auditor_db,
+                logger.clone(),
+            )
+            .unwrap();
+            sync.process_transactions(raw_transactions);
+
+            // Deposits into the audited safe are recorded regardless of the token
+            // configuration (they fail matching later if the token is not
+            // audited), but nothing is recorded for a different safe.
+            let deposits = reserve_deposits::table
+                .order_by(reserve_deposits::position)
+                .load::<ReserveDeposit>(&conn)
+                .unwrap();
+            assert_eq!(deposits.len(), if deposits_expected { 2 } else { 0 });
+
+            // Validate that we are seeing no withdrawals.
+            let withdrawals = reserve_withdrawals::table
+                .order_by(reserve_withdrawals::position)
+                .load::<ReserveWithdrawal>(&conn)
+                .unwrap();
+            assert_eq!(withdrawals, vec![]);
+        }
+    }
+
+    /// A [ReserveSource] that records the cursors it was polled with, and
+    /// returns a single transaction per poll.
+    struct MockReserveSource {
+        polled_cursors: Arc<Mutex<Vec<Option<ReserveCursor>>>>,
+    }
+
+    impl ReserveSource for MockReserveSource {
+        fn fetch_since(
+            &mut self,
+            cursor: Option<ReserveCursor>,
+        ) -> Result<ReserveTransactions, GnosisError> {
+            self.polled_cursors.lock().unwrap().push(cursor);
+            let next_cursor = cursor.map(|cursor| cursor + 1).unwrap_or_default();
+            Ok(ReserveTransactions {
+                transactions: vec![ReserveTx::new(
+                    SAFE_ADDR.to_lowercase(),
+                    format!("tx-{}", next_cursor),
+                    "{}".to_string(),
+                )],
+                next_cursor: Some(next_cursor),
+            })
+        }
+
+        fn parse_transaction(&self, _tx: &ReserveTx) -> Result<ReserveEvents, GnosisError> {
+            Ok(ReserveEvents::default())
+        }
+    }
+
+    #[test_with_logger]
+    fn poll_persists_cursor(logger: Logger) {
+        let test_db_context = TestDbContext::default();
+        let mint_auditor_db = test_db_context.get_db_instance(logger.clone());
+        let conn = mint_auditor_db.get_conn().unwrap();
+        let reserve = AuditedReserveConfig::GnosisSafe(AuditedSafeConfig {
+            safe_addr: EthAddr::from_str(SAFE_ADDR).unwrap(),
+            api_url: Url::parse("http://unused:8545").unwrap(),
+            tokens: vec![],
+        });
+        let polled_cursors = Arc::new(Mutex::new(Vec::new()));
+
+        let mut sync = ReserveSync::with_source(
+            Box::new(MockReserveSource {
+                polled_cursors: polled_cursors.clone(),
+            }),
+            reserve.clone(),
+            mint_auditor_db.clone(),
+            logger.clone(),
+        );
+        sync.poll();
+        sync.poll();
+        assert_eq!(*polled_cursors.lock().unwrap(), vec![None, Some(0)]);
+        assert_eq!(
+            ReserveSyncCursor::get(&SAFE_ADDR.to_lowercase(), &conn).unwrap(),
+            Some(1)
+        );
+
+        // A new sync object (e.g. after a restart) resumes from the stored cursor.
+        let mut sync = ReserveSync::with_source(
+            Box::new(MockReserveSource {
+                polled_cursors: polled_cursors.clone(),
+            }),
+            reserve,
+            mint_auditor_db,
+            logger,
+        );
+        sync.poll();
+        assert_eq!(
+            *polled_cursors.lock().unwrap(),
+            vec![None, Some(0), Some(1)]
+        );
+        assert_eq!(
+            ReserveSyncCursor::get(&SAFE_ADDR.to_lowercase(), &conn).unwrap(),
+            Some(2)
+        );
+
+        // Cursors of other reserves are unaffected.
+        assert_eq!(
+            ReserveSyncCursor::get("bank-account-1", &conn).unwrap(),
+            None
+        );
+    }
+}
@@ -1,9 +1,9 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-//! Background thread for periodically fetching data from the Gnosis API and
+//! Background thread for periodically fetching data from a reserve source and
 //! inserting it into the database.
 
-use super::{sync::GnosisSync, AuditedSafeConfig};
+use super::{sync::ReserveSync, AuditedReserveConfig};
 use crate::{db::MintAuditorDb, error::Error};
 use mc_common::logger::{log, Logger};
 use std::{
@@ -15,18 +15,18 @@ use std::{
     time::Duration,
 };
 
-/// Background thread for periodically fetching data from the Gnosis API and
+/// Background thread for periodically fetching data from a reserve source and
 /// inserting it into the database.
-pub struct GnosisSyncThread {
+pub struct ReserveSyncThread {
     stop_requested: Arc<AtomicBool>,
     join_handle: Option<JoinHandle<()>>,
     logger: Logger,
 }
 
-impl GnosisSyncThread {
+impl ReserveSyncThread {
     /// Start the sync thread.
     pub fn start(
-        audited_safe: &AuditedSafeConfig,
+        reserve: &AuditedReserveConfig,
         mint_auditor_db: MintAuditorDb,
         poll_interval: Duration,
         logger: Logger,
@@ -35,12 +35,12 @@ impl GnosisSyncThread {
 
         let thread_stop_requested = stop_requested.clone();
         let thread_logger = logger.clone();
-        let thread_audited_safe = audited_safe.clone();
+        let thread_reserve = reserve.clone();
 
         let join_handle = Some(spawn(move || {
             thread_entry_point(
                 thread_stop_requested,
-                thread_audited_safe,
+                thread_reserve,
                 mint_auditor_db,
                 poll_interval,
                 thread_logger,
@@ -56,17 +56,17 @@ impl GnosisSyncThread {
 
     /// Stop the sync thread.
     pub fn stop(&mut self) {
-        log::info!(self.logger, "Stopping gnosis sync thread...");
+        log::info!(self.logger, "Stopping reserve sync thread...");
         self.stop_requested.store(true, Ordering::Relaxed);
         if let Some(join_nandle) = self.join_handle.take() {
             join_nandle
                 .join()
-                .expect("failed joining gnosis sync thread");
+                .expect("failed joining reserve sync thread");
         }
     }
 }
 
-impl Drop for GnosisSyncThread {
+impl Drop for ReserveSyncThread {
     fn drop(&mut self) {
         self.stop();
     }
@@ -74,18 +74,18 @@ impl Drop for GnosisSyncThread {
 
 fn thread_entry_point(
     stop_requested: Arc<AtomicBool>,
-    audited_safe: AuditedSafeConfig,
+    reserve: AuditedReserveConfig,
     mint_auditor_db: MintAuditorDb,
     poll_interval: Duration,
     logger: Logger,
 ) {
-    log::info!(logger, "GnosisFetcher thread started");
-    let sync = GnosisSync::new(audited_safe, mint_auditor_db, logger.clone())
+    log::info!(logger, "Reserve sync thread started for {}", reserve.id());
+    let mut sync = ReserveSync::new(reserve, mint_auditor_db, logger.clone())
         .expect("Failed creating sync object");
 
     loop {
         if stop_requested.load(Ordering::Relaxed) {
-            log::info!(logger, "GnosisFetcher thread stop trigger received");
+            log::info!(logger, "Reserve sync thread stop trigger received");
             break;
         }
 
@@ -3,7 +3,7 @@
 //! Mint auditor GRPC service implementation.
 
 use crate::{
-    db::{BlockAuditData, BlockBalance, Counters, MintAuditorDb},
+    db::{BlockAuditData, BlockBalance, BurnTxOut, Counters, MintAuditorDb, ReserveWithdrawal},
     Error,
 };
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, Service, UnarySink};
@@ -11,8 +11,10 @@ use mc_common::logger::Logger;
 use mc_mint_auditor_api::{
     empty::Empty,
     mint_auditor::{
-        BlockAuditData as GrpcBlockAuditData, Counters as GrpcCounters, GetBlockAuditDataRequest,
-        GetBlockAuditDataResponse, GetLastBlockAuditDataResponse,
+        BlockAuditData as GrpcBlockAuditData, BurnTxOut as GrpcBurnTxOut, Counters as GrpcCounters,
+        GetBlockAuditDataRequest, GetBlockAuditDataResponse, GetLastBlockAuditDataResponse,
+        GetUnauditedBurnTxOutsResponse, GetUnauditedReserveWithdrawalsResponse,
+        ReserveWithdrawal as GrpcReserveWithdrawal,
     },
     mint_auditor_grpc::{create_mint_auditor_api, MintAuditorApi},
 };
@@ -117,6 +119,48 @@ impl MintAuditorService {
             .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string()))
             .map(|counters| GrpcCounters::from(&counters))
     }
+
+    fn get_unaudited_burn_tx_outs_impl(&self) -> Result<GetUnauditedBurnTxOutsResponse, RpcStatus> {
+        let conn = self
+            .mint_auditor_db
+            .get_conn()
+            .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string()))?;
+
+        let burn_tx_outs = BurnTxOut::find_unaudited_burn_tx_outs(&conn)
+            .and_then(|burn_tx_outs| {
+                burn_tx_outs
+                    .iter()
+                    .map(GrpcBurnTxOut::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string()))?;
+
+        let mut resp = GetUnauditedBurnTxOutsResponse::new();
+        resp.set_burn_tx_outs(burn_tx_outs.into());
+        Ok(resp)
+    }
+
+    fn get_unaudited_reserve_withdrawals_impl(
+        &self,
+    ) -> Result<GetUnauditedReserveWithdrawalsResponse, RpcStatus> {
+        let conn = self
+            .mint_auditor_db
+            .get_conn()
+            .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string()))?;
+
+        let withdrawals = ReserveWithdrawal::find_unaudited_withdrawals(&conn)
+            .and_then(|withdrawals| {
+                withdrawals
+                    .iter()
+                    .map(GrpcReserveWithdrawal::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string()))?;
+
+        let mut resp = GetUnauditedReserveWithdrawalsResponse::new();
+        resp.set_reserve_withdrawals(withdrawals.into());
+        Ok(resp)
+    }
 }
 
 impl MintAuditorApi for MintAuditorService {
@@ -144,12 +188,39 @@ impl MintAuditorApi for MintAuditorService {
         let logger = rpc_logger(&ctx, &self.logger);
         send_result(ctx, sink, self.get_counters_impl(), &logger)
     }
+
+    fn get_unaudited_burn_tx_outs(
+        &mut self,
+        ctx: RpcContext,
+        _req: Empty,
+        sink: UnarySink<GetUnauditedBurnTxOutsResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_unaudited_burn_tx_outs_impl(), &logger)
+    }
+
+    fn get_unaudited_reserve_withdrawals(
+        &mut self,
+        ctx: RpcContext,
+        _req: Empty,
+        sink: UnarySink<GetUnauditedReserveWithdrawalsResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(
+            ctx,
+            sink,
+            self.get_unaudited_reserve_withdrawals_impl(),
+            &logger,
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::test_utils::TestDbContext;
+    use crate::db::test_utils::{
+        create_burn_tx_out, create_reserve_withdrawal, insert_reserve_withdrawal, TestDbContext,
+    };
     use grpcio::{ChannelBuilder, Environment, Server, ServerBuilder};
     use mc_account_keys::AccountKey;
     use mc_blockchain_types::{Block, BlockContents, BlockVersion};
@@ -313,4 +384,47 @@ mod tests {
             }
         );
     }
+
+    #[test_with_logger]
+    fn test_get_unaudited_burns_and_withdrawals(logger: Logger) {
+        let mut rng = mc_util_test_helper::get_seeded_rng();
+        let (mint_audit_db, _test_db_context) = get_test_db(&logger);
+        let (client, _server) = get_client_server(&mint_audit_db, &logger);
+        let conn = mint_audit_db.get_conn().unwrap();
+
+        // Initially there is nothing to report.
+        let response = client
+            .get_unaudited_burn_tx_outs(&Empty::default())
+            .unwrap();
+        assert!(response.get_burn_tx_outs().is_empty());
+        let response = client
+            .get_unaudited_reserve_withdrawals(&Empty::default())
+            .unwrap();
+        assert!(response.get_reserve_withdrawals().is_empty());
+
+        // Insert a burn and an unrelated withdrawal, neither of which have been
+        // matched.
+        let tx_out = create_burn_tx_out(TokenId::from(1), 100, None, &mut rng);
+        let burn_tx_out = BurnTxOut::insert_from_core_tx_out(2, &tx_out, &conn).unwrap();
+
+        let other_tx_out = create_burn_tx_out(TokenId::from(1), 100, None, &mut rng);
+        let mut withdrawal = create_reserve_withdrawal(&other_tx_out, 100, &mut rng);
+        insert_reserve_withdrawal(&mut withdrawal, &conn);
+
+        let response = client
+            .get_unaudited_burn_tx_outs(&Empty::default())
+            .unwrap();
+        assert_eq!(
+            response.get_burn_tx_outs(),
+            &[GrpcBurnTxOut::try_from(&burn_tx_out).unwrap()]
+        );
+
+        let response = client
+            .get_unaudited_reserve_withdrawals(&Empty::default())
+            .unwrap();
+        assert_eq!(
+            response.get_reserve_withdrawals(),
+            &[GrpcReserveWithdrawal::try_from(&withdrawal).unwrap()]
+        );
+    }
 }
@@ -12,6 +12,7 @@ use mc_common::logger::{create_app_logger, log, o};
 use mc_mobilecoind_api::{self as api, mobilecoind_api_grpc::MobilecoindApiClient, MobilecoindUri};
 use mc_mobilecoind_json::data_types::*;
 use mc_util_grpc::ConnectionUriGrpcioChannel;
+use mc_util_serial::JsonU64;
 use protobuf::RepeatedField;
 use rocket::{delete, get, post, routes, serde::json::Json};
 use std::sync::Arc;
@@ -226,12 +227,14 @@ fn monitor_status(
     Ok(Json(JsonMonitorStatusResponse::from(&resp)))
 }
 
-/// Balance check using a created monitor and subaddress index
-#[get("/monitors/<monitor_hex>/subaddresses/<subaddress_index>/balance")]
+/// Balance check using a created monitor and subaddress index, for a single
+/// token (MOB if no token id is given)
+#[get("/monitors/<monitor_hex>/subaddresses/<subaddress_index>/balance?<token_id>")]
 fn balance(
     state: &rocket::State<State>,
     monitor_hex: String,
     subaddress_index: u64,
+    token_id: Option<u64>,
 ) -> Result<Json<JsonBalanceResponse>, String> {
     let monitor_id =
         hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
@@ -239,6 +242,7 @@ fn balance(
     let mut req = api::GetBalanceRequest::new();
     req.set_monitor_id(monitor_id);
     req.set_subaddress_index(subaddress_index);
+    req.set_token_id(token_id.unwrap_or(0));
 
     let resp = state
         .mobilecoind_api_client
@@ -248,11 +252,46 @@ fn balance(
     Ok(Json(JsonBalanceResponse::from(&resp)))
 }
 
-#[get("/monitors/<monitor_hex>/subaddresses/<subaddress_index>/utxos")]
+/// Balance check using a created monitor and subaddress index, broken down
+/// for every token the network has a minimum fee for
+#[get("/monitors/<monitor_hex>/subaddresses/<subaddress_index>/balances")]
+fn balances(
+    state: &rocket::State<State>,
+    monitor_hex: String,
+    subaddress_index: u64,
+) -> Result<Json<JsonBalancesResponse>, String> {
+    let monitor_id =
+        hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
+
+    let tokens = get_tokens(state)?;
+
+    let mut balances = Vec::new();
+    for token in tokens.tokens {
+        let mut req = api::GetBalanceRequest::new();
+        req.set_monitor_id(monitor_id.clone());
+        req.set_subaddress_index(subaddress_index);
+        req.set_token_id(token.token_id.into());
+
+        let resp = state
+            .mobilecoind_api_client
+            .get_balance(&req)
+            .map_err(|err| format!("Failed getting balance: {}", err))?;
+
+        balances.push(JsonTokenBalance {
+            token_id: token.token_id,
+            balance: JsonU64(resp.balance),
+        });
+    }
+
+    Ok(Json(JsonBalancesResponse { balances }))
+}
+
+#[get("/monitors/<monitor_hex>/subaddresses/<subaddress_index>/utxos?<token_id>")]
 fn utxos(
     state: &rocket::State<State>,
     monitor_hex: String,
     subaddress_index: u64,
+    token_id: Option<u64>,
 ) -> Result<Json<JsonUtxosResponse>, String> {
     let monitor_id =
         hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
@@ -260,6 +299,7 @@ fn utxos(
     let mut req = api::GetUnspentTxOutListRequest::new();
     req.set_monitor_id(monitor_id);
     req.set_subaddress_index(subaddress_index);
+    req.set_token_id(token_id.unwrap_or(0));
 
     let resp = state
         .mobilecoind_api_client
@@ -310,6 +350,9 @@ fn create_request_code(
     if let Some(memo) = request.memo.clone() {
         req.set_memo(memo);
     }
+    if let Some(token_id) = request.token_id {
+        req.set_token_id(token_id.into());
+    }
 
     let resp = state
         .mobilecoind_api_client
@@ -406,12 +449,20 @@ fn build_and_submit(
         .map(u64::from)
         .unwrap_or(0);
 
+    // The fee is paid in the token being transferred.
+    let token_id = u64::from(transfer.request_data.token_id);
+    check_fee_token_id(token_id, transfer.fee_token_id)?;
+
     // Send the payment request
     let mut req = api::SendPaymentRequest::new();
     req.set_sender_monitor_id(monitor_id);
     req.set_sender_subaddress(subaddress_index);
     req.set_outlay_list(RepeatedField::from_vec(vec![outlay]));
     req.set_max_input_utxo_value(max_input_utxo_value);
+    req.set_token_id(token_id);
+    if let Some(fee) = transfer.fee {
+        req.set_fee(fee.into());
+    }
     if let Some(subaddress) = transfer.change_subaddress.as_ref() {
         req.set_override_change_subaddress(true);
         req.set_change_subaddress(u64::from(subaddress))
@@ -452,6 +503,10 @@ fn pay_address_code(
         .map(u64::from)
         .unwrap_or(0);
 
+    // The fee is paid in the token being transferred.
+    let token_id = transfer.token_id.as_ref().map(u64::from).unwrap_or(0);
+    check_fee_token_id(token_id, transfer.fee_token_id)?;
+
     // Send the pay address code request
     let mut req = api::PayAddressCodeRequest::new();
     req.set_sender_monitor_id(monitor_id);
@@ -459,6 +514,10 @@ fn pay_address_code(
     req.set_receiver_b58_code(transfer.receiver_b58_address_code.clone());
     req.set_amount(amount);
     req.set_max_input_utxo_value(max_input_utxo_value);
+    req.set_token_id(token_id);
+    if let Some(fee) = transfer.fee {
+        req.set_fee(fee.into());
+    }
     if let Some(subaddress) = transfer.change_subaddress.as_ref() {
         req.set_override_change_subaddress(true);
         req.set_change_subaddress(u64::from(subaddress))
@@ -506,12 +565,20 @@ fn generate_request_code_transaction(
         })
         .collect::<Result<_, String>>()?;
 
+    // The fee is paid in the token being transferred.
+    let token_id = u64::from(request.transfer.token_id);
+    check_fee_token_id(token_id, request.fee_token_id)?;
+
     // Get a tx proposal
     let mut req = api::GenerateTxRequest::new();
     req.set_sender_monitor_id(monitor_id);
     req.set_change_subaddress(subaddress_index);
     req.set_outlay_list(RepeatedField::from_vec(vec![outlay]));
     req.set_input_list(RepeatedField::from_vec(inputs));
+    req.set_token_id(token_id);
+    if let Some(fee) = request.fee {
+        req.set_fee(fee.into());
+    }
 
     let resp = state
         .mobilecoind_api_client
@@ -521,6 +588,162 @@ fn generate_request_code_transaction(
     Ok(Json(JsonCreateTxProposalResponse::from(&resp)))
 }
 
+/// Creates a transaction proposal that funds a gift code from a monitor and
+/// subaddress. Once the proposal has been submitted via submit-tx and has
+/// landed in the ledger, the gift code can be created from the returned
+/// tx_out_public_key.
+#[post(
+    "/monitors/<monitor_hex>/subaddresses/<subaddress_index>/gift-codes/fund",
+    format = "json",
+    data = "<request>"
+)]
+fn generate_gift_code_funding_tx(
+    state: &rocket::State<State>,
+    monitor_hex: String,
+    subaddress_index: u64,
+    request: Json<JsonGenerateGiftCodeFundingTxRequest>,
+) -> Result<Json<JsonGenerateGiftCodeFundingTxResponse>, String> {
+    let monitor_id =
+        hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
+    let token_id = request.token_id.as_ref().map(u64::from).unwrap_or(0);
+
+    // Fund the gift code from the utxos of this subaddress.
+    let mut utxos_req = api::GetUnspentTxOutListRequest::new();
+    utxos_req.set_monitor_id(monitor_id.clone());
+    utxos_req.set_subaddress_index(subaddress_index);
+    utxos_req.set_token_id(token_id);
+
+    let utxos_resp = state
+        .mobilecoind_api_client
+        .get_unspent_tx_out_list(&utxos_req)
+        .map_err(|err| format!("Failed getting utxos: {}", err))?;
+
+    let mut req = api::GenerateGiftCodeFundingTxRequest::new();
+    req.set_sender_monitor_id(monitor_id);
+    req.set_change_subaddress(subaddress_index);
+    req.set_input_list(utxos_resp.output_list);
+    req.set_value(request.value.into());
+    req.set_token_id(token_id);
+    if let Some(note) = request.note.clone() {
+        req.set_note(note);
+    }
+
+    let resp = state
+        .mobilecoind_api_client
+        .generate_gift_code_funding_tx(&req)
+        .map_err(|err| format!("Failed to generate gift code funding tx: {}", err))?;
+
+    Ok(Json(JsonGenerateGiftCodeFundingTxResponse::from(&resp)))
+}
+
+/// Creates the b58 gift code of a funded gift code TxOut
+#[post(
+    "/monitors/<monitor_hex>/gift-codes",
+    format = "json",
+    data = "<request>"
+)]
+fn create_gift_code(
+    state: &rocket::State<State>,
+    monitor_hex: String,
+    request: Json<JsonCreateGiftCodeRequest>,
+) -> Result<Json<JsonCreateGiftCodeResponse>, String> {
+    let monitor_id =
+        hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
+    let tx_out_public_key = hex::decode(&request.tx_out_public_key)
+        .map_err(|err| format!("Failed to decode tx out public key hex: {}", err))?;
+
+    let mut req = api::CreateGiftCodeRequest::new();
+    req.set_monitor_id(monitor_id);
+    req.mut_tx_out_public_key().set_data(tx_out_public_key);
+
+    let resp = state
+        .mobilecoind_api_client
+        .create_gift_code(&req)
+        .map_err(|err| format!("Failed creating gift code: {}", err))?;
+
+    Ok(Json(JsonCreateGiftCodeResponse::from(&resp)))
+}
+
+/// Creates a transaction proposal that claims a gift code into a monitor and
+/// subaddress.
+#[post(
+    "/monitors/<monitor_hex>/subaddresses/<subaddress_index>/gift-codes/claim",
+    format = "json",
+    data = "<request>"
+)]
+fn generate_gift_code_claim_tx(
+    state: &rocket::State<State>,
+    monitor_hex: String,
+    subaddress_index: u64,
+    request: Json<JsonGenerateGiftCodeClaimTxRequest>,
+) -> Result<Json<JsonGiftCodeTxProposalResponse>, String> {
+    let monitor_id =
+        hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
+
+    let mut req = api::GenerateGiftCodeClaimTxRequest::new();
+    req.set_monitor_id(monitor_id);
+    req.set_subaddress(subaddress_index);
+    req.set_b58_code(request.b58_code.clone());
+    if let Some(note) = request.note.clone() {
+        req.set_note(note);
+    }
+
+    let resp = state
+        .mobilecoind_api_client
+        .generate_gift_code_claim_tx(&req)
+        .map_err(|err| format!("Failed to generate gift code claim tx: {}", err))?;
+
+    Ok(Json(JsonGiftCodeTxProposalResponse::from(&resp)))
+}
+
+/// Creates a transaction proposal that returns an unclaimed gift code to the
+/// monitor that funded it.
+#[post(
+    "/monitors/<monitor_hex>/subaddresses/<subaddress_index>/gift-codes/cancel",
+    format = "json",
+    data = "<request>"
+)]
+fn generate_gift_code_cancellation_tx(
+    state: &rocket::State<State>,
+    monitor_hex: String,
+    subaddress_index: u64,
+    request: Json<JsonGenerateGiftCodeCancellationTxRequest>,
+) -> Result<Json<JsonGiftCodeTxProposalResponse>, String> {
+    let monitor_id =
+        hex::decode(monitor_hex).map_err(|err| format!("Failed to decode monitor hex: {}", err))?;
+    let tx_out_public_key = hex::decode(&request.tx_out_public_key)
+        .map_err(|err| format!("Failed to decode tx out public key hex: {}", err))?;
+
+    let mut req = api::GenerateGiftCodeCancellationTxRequest::new();
+    req.set_monitor_id(monitor_id);
+    req.set_change_subaddress(subaddress_index);
+    req.mut_tx_out_public_key().set_data(tx_out_public_key);
+
+    let resp = state
+        .mobilecoind_api_client
+        .generate_gift_code_cancellation_tx(&req)
+        .map_err(|err| format!("Failed to generate gift code cancellation tx: {}", err))?;
+
+    Ok(Json(JsonGiftCodeTxProposalResponse::from(&resp)))
+}
+
+/// Checks whether a gift code has been claimed or cancelled
+#[get("/gift-codes/<b58_code>/status")]
+fn gift_code_status(
+    state: &rocket::State<State>,
+    b58_code: String,
+) -> Result<Json<JsonGiftCodeStatusResponse>, String> {
+    let mut req = api::GetGiftCodeStatusRequest::new();
+    req.set_b58_code(b58_code);
+
+    let resp = state
+        .mobilecoind_api_client
+        .get_gift_code_status(&req)
+        .map_err(|err| format!("Failed getting gift code status: {}", err))?;
+
+    Ok(Json(JsonGiftCodeStatusResponse::from(&resp)))
+}
+
 /// Submit a prepared TxProposal
 #[post("/submit-tx", format = "json", data = "<proposal>")]
 fn submit_tx(
@@ -614,6 +837,12 @@ fn ledger_info(state: &rocket::State<State>) -> Result<Json<JsonLedgerInfoRespon
     Ok(Json(JsonLedgerInfoResponse::from(&resp)))
 }
 
+/// Lists the tokens the network accepts, with their minimum fees
+#[get("/ledger/tokens")]
+fn ledger_tokens(state: &rocket::State<State>) -> Result<Json<JsonTokensResponse>, String> {
+    Ok(Json(get_tokens(state)?))
+}
+
 /// Retrieves the data in a request code
 #[get("/ledger/blocks/<block_num>/header")]
 fn block_info(
@@ -776,6 +1005,30 @@ fn get_mixins(
     Ok(Json(response))
 }
 
+/// Get the tokens known to the network from the last block info reported by
+/// consensus.
+fn get_tokens(state: &rocket::State<State>) -> Result<JsonTokensResponse, String> {
+    let resp = state
+        .mobilecoind_api_client
+        .get_network_status(&api::Empty::new())
+        .map_err(|err| format!("Failed getting network status: {}", err))?;
+
+    Ok(JsonTokensResponse::from(&resp))
+}
+
+/// Transactions built by mobilecoind pay their fee in the token being
+/// transferred, so an explicit fee token must match it.
+fn check_fee_token_id(token_id: u64, fee_token_id: Option<JsonU64>) -> Result<(), String> {
+    match fee_token_id {
+        Some(fee_token_id) if u64::from(fee_token_id) != token_id => Err(format!(
+            "Fee token id {} must match the token id {} being transferred",
+            u64::from(fee_token_id),
+            token_id
+        )),
+        _ => Ok(()),
+    }
+}
+
 #[rocket::main]
 async fn main() -> Result<(), rocket::Error> {
     mc_common::setup_panic_handler();
@@ -821,6 +1074,7 @@ async fn main() -> Result<(), rocket::Error> {
                 monitors,
                 monitor_status,
                 balance,
+                balances,
                 utxos,
                 public_address,
                 create_request_code,
@@ -830,10 +1084,16 @@ async fn main() -> Result<(), rocket::Error> {
                 build_and_submit,
                 pay_address_code,
                 generate_request_code_transaction,
+                generate_gift_code_funding_tx,
+                create_gift_code,
+                generate_gift_code_claim_tx,
+                generate_gift_code_cancellation_tx,
+                gift_code_status,
                 submit_tx,
                 check_transfer_status,
                 check_receiver_transfer_status,
                 ledger_info,
+                ledger_tokens,
                 block_info,
                 block_details,
                 processed_block,
@@ -144,12 +144,25 @@ impl From<&api::GetBalanceResponse> for JsonBalanceResponse {
     }
 }
 
+#[derive(Serialize, Default, Debug)]
+pub struct JsonTokenBalance {
+    pub token_id: JsonU64,
+    pub balance: JsonU64,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct JsonBalancesResponse {
+    pub balances: Vec<JsonTokenBalance>,
+}
+
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct JsonUnspentTxOut {
     pub tx_out: JsonTxOut,
     pub subaddress_index: u64,
     pub key_image: String,
     pub value: JsonU64,
+    #[serde(default)]
+    pub token_id: JsonU64,
     pub attempted_spend_height: u64,
     pub attempted_spend_tombstone: u64,
     pub monitor_id: String,
@@ -162,6 +175,7 @@ impl From<&api::UnspentTxOut> for JsonUnspentTxOut {
             subaddress_index: src.get_subaddress_index(),
             key_image: hex::encode(&src.get_key_image().get_data()),
             value: JsonU64(src.value),
+            token_id: JsonU64(src.token_id),
             attempted_spend_height: src.get_attempted_spend_height(),
             attempted_spend_tombstone: src.get_attempted_spend_tombstone(),
             monitor_id: hex::encode(&src.get_monitor_id()),
@@ -189,6 +203,7 @@ impl TryFrom<&JsonUnspentTxOut> for api::UnspentTxOut {
         utxo.set_subaddress_index(src.subaddress_index);
         utxo.set_key_image(key_image);
         utxo.set_value(src.value.into());
+        utxo.set_token_id(src.token_id.into());
         utxo.set_attempted_spend_height(src.attempted_spend_height);
         utxo.set_attempted_spend_tombstone(src.attempted_spend_tombstone);
         utxo.set_monitor_id(
@@ -222,6 +237,7 @@ pub struct JsonCreateRequestCodeRequest {
     pub receiver: JsonPublicAddress,
     pub value: Option<JsonU64>,
     pub memo: Option<String>,
+    pub token_id: Option<JsonU64>,
 }
 
 #[derive(Serialize, Default, Debug)]
@@ -339,6 +355,8 @@ pub struct JsonParseRequestCodeResponse {
     pub receiver: JsonPublicAddress,
     pub value: JsonU64,
     pub memo: String,
+    #[serde(default)]
+    pub token_id: JsonU64,
 }
 
 impl From<&api::ParseRequestCodeResponse> for JsonParseRequestCodeResponse {
@@ -347,6 +365,7 @@ impl From<&api::ParseRequestCodeResponse> for JsonParseRequestCodeResponse {
             receiver: JsonPublicAddress::from(src.get_receiver()),
             value: JsonU64(src.get_value()),
             memo: src.get_memo().to_string(),
+            token_id: JsonU64(src.get_token_id()),
         }
     }
 }
@@ -427,6 +446,8 @@ pub struct JsonSendPaymentRequest {
     pub request_data: JsonParseRequestCodeResponse,
     pub max_input_utxo_value: Option<JsonU64>,
     pub change_subaddress: Option<JsonU64>,
+    pub fee: Option<JsonU64>,
+    pub fee_token_id: Option<JsonU64>,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug)]
@@ -454,6 +475,9 @@ pub struct JsonPayAddressCodeRequest {
     pub value: JsonU64,
     pub max_input_utxo_value: Option<JsonU64>,
     pub change_subaddress: Option<JsonU64>,
+    pub token_id: Option<JsonU64>,
+    pub fee: Option<JsonU64>,
+    pub fee_token_id: Option<JsonU64>,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
@@ -784,6 +808,8 @@ pub struct JsonTxPrefix {
     pub outputs: Vec<JsonTxOut>,
     pub fee: JsonU64,
     tombstone_block: JsonU64,
+    #[serde(default)]
+    pub fee_token_id: JsonU64,
 }
 
 impl From<&TxPrefix> for JsonTxPrefix {
@@ -793,6 +819,7 @@ impl From<&TxPrefix> for JsonTxPrefix {
             outputs: src.get_outputs().iter().map(JsonTxOut::from).collect(),
             fee: JsonU64(src.get_fee()),
             tombstone_block: JsonU64(src.get_tombstone_block()),
+            fee_token_id: JsonU64(src.get_fee_token_id()),
         }
     }
 }
@@ -820,6 +847,7 @@ impl TryFrom<&JsonTxPrefix> for TxPrefix {
         prefix.set_outputs(RepeatedField::from_vec(outputs));
         prefix.set_fee(src.fee.into());
         prefix.set_tombstone_block(src.tombstone_block.into());
+        prefix.set_fee_token_id(src.fee_token_id.into());
 
         Ok(prefix)
     }
@@ -1065,6 +1093,8 @@ impl TryFrom<&JsonTxProposal> for api::TxProposal {
 pub struct JsonCreateTxProposalRequest {
     pub input_list: Vec<JsonUnspentTxOut>,
     pub transfer: JsonParseRequestCodeResponse,
+    pub fee: Option<JsonU64>,
+    pub fee_token_id: Option<JsonU64>,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug)]
@@ -1210,6 +1240,140 @@ impl From<&api::GetTxStatusAsReceiverResponse> for JsonStatusResponse {
     }
 }
 
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonGenerateGiftCodeFundingTxRequest {
+    pub value: JsonU64,
+    pub token_id: Option<JsonU64>,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonGenerateGiftCodeFundingTxResponse {
+    pub tx_proposal: JsonTxProposal,
+    pub tx_out_public_key: String,
+}
+
+impl From<&api::GenerateGiftCodeFundingTxResponse> for JsonGenerateGiftCodeFundingTxResponse {
+    fn from(src: &api::GenerateGiftCodeFundingTxResponse) -> Self {
+        Self {
+            tx_proposal: src.get_tx_proposal().into(),
+            tx_out_public_key: hex::encode(src.get_tx_out_public_key().get_data()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonCreateGiftCodeRequest {
+    pub tx_out_public_key: String,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct JsonCreateGiftCodeResponse {
+    pub b58_code: String,
+    pub global_index: JsonU64,
+    pub value: JsonU64,
+    pub token_id: JsonU64,
+}
+
+impl From<&api::CreateGiftCodeResponse> for JsonCreateGiftCodeResponse {
+    fn from(src: &api::CreateGiftCodeResponse) -> Self {
+        Self {
+            b58_code: src.get_b58_code().to_string(),
+            global_index: JsonU64(src.get_global_index()),
+            value: JsonU64(src.get_value()),
+            token_id: JsonU64(src.get_token_id()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonGenerateGiftCodeClaimTxRequest {
+    pub b58_code: String,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonGenerateGiftCodeCancellationTxRequest {
+    pub tx_out_public_key: String,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct JsonGiftCodeTxProposalResponse {
+    pub tx_proposal: JsonTxProposal,
+}
+
+impl From<&api::GenerateGiftCodeClaimTxResponse> for JsonGiftCodeTxProposalResponse {
+    fn from(src: &api::GenerateGiftCodeClaimTxResponse) -> Self {
+        Self {
+            tx_proposal: src.get_tx_proposal().into(),
+        }
+    }
+}
+
+impl From<&api::GenerateGiftCodeCancellationTxResponse> for JsonGiftCodeTxProposalResponse {
+    fn from(src: &api::GenerateGiftCodeCancellationTxResponse) -> Self {
+        Self {
+            tx_proposal: src.get_tx_proposal().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct JsonGiftCodeStatusResponse {
+    pub status: String,
+    pub value: JsonU64,
+    pub token_id: JsonU64,
+    pub spent_block_index: Option<JsonU64>,
+}
+
+impl From<&api::GetGiftCodeStatusResponse> for JsonGiftCodeStatusResponse {
+    fn from(src: &api::GetGiftCodeStatusResponse) -> Self {
+        let status_str = match src.get_status() {
+            api::GiftCodeStatus::GiftCodeUnspent => "unspent",
+            api::GiftCodeStatus::GiftCodeClaimed => "claimed",
+            api::GiftCodeStatus::GiftCodeCancelled => "cancelled",
+        };
+
+        Self {
+            status: String::from(status_str),
+            value: JsonU64(src.get_value()),
+            token_id: JsonU64(src.get_token_id()),
+            spent_block_index: match src.get_status() {
+                api::GiftCodeStatus::GiftCodeUnspent => None,
+                _ => Some(JsonU64(src.get_spent_block_index())),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct JsonTokenInfo {
+    pub token_id: JsonU64,
+    pub minimum_fee: JsonU64,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct JsonTokensResponse {
+    pub tokens: Vec<JsonTokenInfo>,
+}
+
+impl From<&api::GetNetworkStatusResponse> for JsonTokensResponse {
+    fn from(src: &api::GetNetworkStatusResponse) -> Self {
+        let mut tokens: Vec<JsonTokenInfo> = src
+            .get_last_block_info()
+            .get_minimum_fees()
+            .iter()
+            .map(|(token_id, minimum_fee)| JsonTokenInfo {
+                token_id: JsonU64(*token_id),
+                minimum_fee: JsonU64(*minimum_fee),
+            })
+            .collect();
+        tokens.sort_by_key(|token| token.token_id.0);
+
+        Self { tokens }
+    }
+}
+
 #[derive(Serialize, Default, Debug)]
 pub struct JsonLedgerInfoResponse {
     pub block_count: JsonU64,
@@ -1280,6 +1444,7 @@ pub struct JsonProcessedTxOut {
     pub public_key: String,
     pub key_image: String,
     pub value: JsonU64,
+    pub token_id: JsonU64,
     pub direction: String,
 }
 
@@ -1297,6 +1462,7 @@ impl From<&api::ProcessedTxOut> for JsonProcessedTxOut {
             public_key: hex::encode(&src.get_public_key().get_data()),
             key_image: hex::encode(&src.get_key_image().get_data()),
             value: JsonU64(src.value),
+            token_id: JsonU64(src.token_id),
             direction: direction_str.to_owned(),
         }
     }
@@ -1406,6 +1572,7 @@ mod test {
             let value = 789;
             let attempted_spend_height = 1000;
             let attempted_spend_tombstone = 1234;
+            let token_id = 2;
 
             // make proto UnspentTxOut
             let mut unspent = api::UnspentTxOut::new();
@@ -1415,6 +1582,7 @@ mod test {
             unspent.set_value(value);
             unspent.set_attempted_spend_height(attempted_spend_height);
             unspent.set_attempted_spend_tombstone(attempted_spend_tombstone);
+            unspent.set_token_id(token_id);
             unspent
         };
 
@@ -7,9 +7,12 @@ use clap::Parser;
 use mc_attest_verifier::{MrSignerVerifier, Verifier, DEBUG_ENCLAVE};
 use mc_common::logger::{create_app_logger, log, o, Logger};
 use mc_ledger_db::{Ledger, LedgerDB};
-use mc_ledger_sync::{LedgerSyncServiceThread, PollingNetworkState, ReqwestTransactionsFetcher};
+use mc_ledger_sync::{
+    LedgerSyncServiceThread, ObjectStoreTransactionsFetcher, PollingNetworkState,
+};
 use mc_mobilecoind::{
-    config::Config, database::Database, payments::TransactionsManager, service::Service,
+    config::Config, database::Database, memo_decoder::MemoDecoder, payments::TransactionsManager,
+    service::Service,
 };
 use mc_util_telemetry::setup_default_tracer;
 use mc_watcher::{watcher::WatcherSyncThread, watcher_db::create_or_open_rw_watcher_db};
@@ -50,11 +53,11 @@ fn main() {
         logger.clone(),
     )));
 
-    let transactions_fetcher = ReqwestTransactionsFetcher::new(
+    let transactions_fetcher = ObjectStoreTransactionsFetcher::new(
         config.tx_source_urls.clone().unwrap_or_default(),
         logger.clone(),
     )
-    .expect("Failed creating ReqwestTransactionsFetcher");
+    .expect("Failed creating ObjectStoreTransactionsFetcher");
 
     // Create the ledger_db.
     let ledger_db = create_or_open_ledger_db(&config, &logger, &transactions_fetcher);
@@ -133,6 +136,7 @@ fn main() {
                 network_state,
                 listen_uri,
                 config.num_workers,
+                MemoDecoder::new(config.contacts.clone()),
                 logger,
             );
 
@@ -159,7 +163,7 @@ fn main() {
 fn create_or_open_ledger_db(
     config: &Config,
     logger: &Logger,
-    transactions_fetcher: &ReqwestTransactionsFetcher,
+    transactions_fetcher: &ObjectStoreTransactionsFetcher,
 ) -> LedgerDB {
     let ledger_db_file = Path::new(&config.ledger_db).join("data.mdb");
 
@@ -5,6 +5,8 @@
 
 use clap::Parser;
 use displaydoc::Display;
+use mc_account_keys::PublicAddress;
+use mc_api::printable::PrintableWrapper;
 use mc_attest_verifier::{MrSignerVerifier, Verifier, DEBUG_ENCLAVE};
 use mc_common::{logger::Logger, ResponderId};
 use mc_connection::{ConnectionManager, HardcodedCredentialsProvider, ThickClient};
@@ -60,6 +62,9 @@ pub struct Config {
     /// URLs to use for transaction data.
     ///
     /// For example: https://s3-us-west-1.amazonaws.com/mobilecoin.chain/node1.test.mobilecoin.com/
+    ///
+    /// Local (file:///path/to/blocks/) and S3 (s3://bucket/prefix/) snapshots
+    /// are also supported.
     #[clap(
         long = "tx-source-url",
         required_unless_present = "offline",
@@ -103,6 +108,26 @@ pub struct Config {
     /// An authorization token for the ipinfo.io service, if available
     #[clap(long, env = "MC_IP_INFO_TOKEN", default_value = "")]
     pub ip_info_token: String,
+
+    /// B58-encoded public addresses of known contacts. Authenticated sender
+    /// memos from these addresses are validated and attributed to them.
+    #[clap(
+        long = "contact",
+        use_value_delimiter = true,
+        parse(try_from_str = parse_public_address),
+        env = "MC_CONTACTS"
+    )]
+    pub contacts: Vec<PublicAddress>,
+}
+
+fn parse_public_address(src: &str) -> Result<PublicAddress, String> {
+    let wrapper = PrintableWrapper::b58_decode(src.to_string())
+        .map_err(|err| format!("Error decoding public address {}: {}", src, err))?;
+    if !wrapper.has_public_address() {
+        return Err(format!("{} is not a public address", src));
+    }
+    PublicAddress::try_from(wrapper.get_public_address())
+        .map_err(|err| format!("Error decoding public address {}: {}", src, err))
 }
 
 fn parse_quorum_set_from_json(src: &str) -> Result<QuorumSet<ResponderId>, String> {
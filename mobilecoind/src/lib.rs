@@ -6,6 +6,8 @@ extern crate alloc;
 
 pub mod config;
 pub mod database;
+pub mod memo_builder;
+pub mod memo_decoder;
 pub mod payments;
 pub mod service;
 
@@ -0,0 +1,173 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Construction of memo builders from their API configuration.
+//! * Sender credentials for authenticated memos are derived from the account
+//!   that is sending the transaction.
+
+use crate::error::Error;
+use mc_account_keys::AccountKey;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_mobilecoind_api::{self as api, MemoBuilderConfig_oneof_memo_builder as MemoBuilderKind};
+use mc_transaction_std::{
+    BurnRedemptionMemo, BurnRedemptionMemoBuilder, GiftCodeCancellationMemoBuilder,
+    GiftCodeFundingMemoBuilder, GiftCodeSenderMemoBuilder, InvoiceSettlementMemoBuilder,
+    MemoBuilder, RTHMemoBuilder, RefundMemoBuilder, SenderMemoCredential,
+};
+
+/// A memo builder which can be handed to the transactions manager.
+pub type BoxedMemoBuilder = Box<dyn MemoBuilder + 'static + Send + Sync>;
+
+/// Create the memo builder selected by a memo builder configuration.
+///
+/// # Arguments
+/// * `config` - The memo builder configuration from the request.
+/// * `account_key` - The account sending the transaction. Authenticated memos
+///   are signed with the credential of its default subaddress.
+///
+/// Returns None if no memo builder was selected, in which case the default
+/// (empty) memo builder should be used.
+pub fn memo_builder_from_config(
+    config: &api::MemoBuilderConfig,
+    account_key: &AccountKey,
+) -> Result<Option<BoxedMemoBuilder>, Error> {
+    let memo_builder: BoxedMemoBuilder = match config.memo_builder.as_ref() {
+        None => return Ok(None),
+        Some(MemoBuilderKind::rth(rth_config)) => {
+            let mut memo_builder = RTHMemoBuilder::default();
+            if rth_config.enable_sender_memo {
+                memo_builder.set_sender_credential(SenderMemoCredential::from(account_key));
+                if rth_config.include_payment_request_id {
+                    memo_builder.set_payment_request_id(rth_config.payment_request_id);
+                }
+            }
+            if rth_config.enable_destination_memo {
+                memo_builder.enable_destination_memo();
+            }
+            Box::new(memo_builder)
+        }
+        Some(MemoBuilderKind::burn_redemption(burn_config)) => {
+            let mut memo_data = burn_config.get_redemption_memo().to_vec();
+            if memo_data.is_empty() {
+                memo_data.resize(BurnRedemptionMemo::MEMO_DATA_LEN, 0);
+            }
+            let memo_data_array = memo_data.try_into().map_err(|_err| {
+                Error::InvalidArgument(
+                    "redemption_memo".to_string(),
+                    format!("Must be {} bytes long", BurnRedemptionMemo::MEMO_DATA_LEN),
+                )
+            })?;
+            let mut memo_builder = BurnRedemptionMemoBuilder::new(memo_data_array);
+            if burn_config.enable_destination_memo {
+                memo_builder.enable_destination_memo();
+            }
+            Box::new(memo_builder)
+        }
+        Some(MemoBuilderKind::gift_code_funding(funding_config)) => Box::new(
+            GiftCodeFundingMemoBuilder::new(funding_config.get_note())
+                .map_err(|err| Error::InvalidArgument("note".to_string(), err.to_string()))?,
+        ),
+        Some(MemoBuilderKind::gift_code_sender(sender_config)) => Box::new(
+            GiftCodeSenderMemoBuilder::new(sender_config.get_note())
+                .map_err(|err| Error::InvalidArgument("note".to_string(), err.to_string()))?,
+        ),
+        Some(MemoBuilderKind::gift_code_cancellation(cancellation_config)) => Box::new(
+            GiftCodeCancellationMemoBuilder::new(cancellation_config.gift_code_tx_out_global_index),
+        ),
+        Some(MemoBuilderKind::refund(refund_config)) => {
+            let refunded_tx_out_public_key = CompressedRistrettoPublic::try_from(
+                refund_config.get_refunded_tx_out_public_key(),
+            )?;
+            let mut memo_builder = RefundMemoBuilder::new(
+                SenderMemoCredential::from(account_key),
+                refunded_tx_out_public_key,
+            );
+            if refund_config.enable_destination_memo {
+                memo_builder.enable_destination_memo();
+            }
+            Box::new(memo_builder)
+        }
+        Some(MemoBuilderKind::invoice_settlement(invoice_config)) => {
+            let invoice_id = invoice_config.get_invoice_id().try_into().map_err(|_err| {
+                Error::InvalidArgument(
+                    "invoice_id".to_string(),
+                    "Must be 16 bytes long".to_string(),
+                )
+            })?;
+            let mut memo_builder = InvoiceSettlementMemoBuilder::new(
+                SenderMemoCredential::from(account_key),
+                invoice_id,
+            );
+            if invoice_config.enable_destination_memo {
+                memo_builder.enable_destination_memo();
+            }
+            Box::new(memo_builder)
+        }
+    };
+
+    Ok(Some(memo_builder))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_memo_builder_from_config() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let account_key = AccountKey::random(&mut rng);
+
+        // No memo builder selected.
+        assert!(
+            memo_builder_from_config(&api::MemoBuilderConfig::new(), &account_key)
+                .unwrap()
+                .is_none()
+        );
+
+        let mut config = api::MemoBuilderConfig::new();
+        config.mut_rth().set_enable_sender_memo(true);
+        assert!(memo_builder_from_config(&config, &account_key)
+            .unwrap()
+            .is_some());
+
+        // Burn redemption memo data must be empty or exactly 64 bytes.
+        let mut config = api::MemoBuilderConfig::new();
+        config.mut_burn_redemption();
+        assert!(memo_builder_from_config(&config, &account_key).is_ok());
+        config
+            .mut_burn_redemption()
+            .set_redemption_memo(vec![1u8; BurnRedemptionMemo::MEMO_DATA_LEN]);
+        assert!(memo_builder_from_config(&config, &account_key).is_ok());
+        config
+            .mut_burn_redemption()
+            .set_redemption_memo(vec![1u8; BurnRedemptionMemo::MEMO_DATA_LEN - 1]);
+        assert!(memo_builder_from_config(&config, &account_key).is_err());
+
+        // Gift code notes must fit in the memo.
+        let mut config = api::MemoBuilderConfig::new();
+        config.mut_gift_code_sender().set_note("hello".to_string());
+        assert!(memo_builder_from_config(&config, &account_key).is_ok());
+        config.mut_gift_code_sender().set_note("x".repeat(100));
+        assert!(memo_builder_from_config(&config, &account_key).is_err());
+
+        // Invoice ids must be 16 bytes.
+        let mut config = api::MemoBuilderConfig::new();
+        config
+            .mut_invoice_settlement()
+            .set_invoice_id(vec![3u8; 16]);
+        assert!(memo_builder_from_config(&config, &account_key).is_ok());
+        config
+            .mut_invoice_settlement()
+            .set_invoice_id(vec![3u8; 15]);
+        assert!(memo_builder_from_config(&config, &account_key).is_err());
+
+        // The refunded TxOut public key must be 32 bytes.
+        let mut config = api::MemoBuilderConfig::new();
+        config.mut_refund();
+        assert!(memo_builder_from_config(&config, &account_key).is_err());
+        config.mut_refund().set_refunded_tx_out_public_key(
+            account_key.default_subaddress().view_public_key().into(),
+        );
+        assert!(memo_builder_from_config(&config, &account_key).is_ok());
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Decryption and classification of the memos of TxOuts owned by a monitor.
+//! * Authenticated sender, refund and invoice settlement memos are validated
+//!   against a list of contacts, so that incoming payments can be attributed to
+//!   a known sender.
+
+use crate::error::Error;
+use mc_account_keys::{AccountKey, PublicAddress, ShortAddressHash, CHANGE_SUBADDRESS_INDEX};
+use mc_common::HashMap;
+use mc_crypto_keys::RistrettoPublic;
+use mc_mobilecoind_api as api;
+use mc_transaction_core::{get_tx_out_shared_secret, tx::TxOut};
+use mc_transaction_std::MemoType;
+
+/// Decrypts memos with a monitor's keys, and validates authenticated memos
+/// against the public addresses of known contacts.
+#[derive(Clone, Debug, Default)]
+pub struct MemoDecoder {
+    /// Contacts, by their short address hash.
+    contacts: HashMap<ShortAddressHash, PublicAddress>,
+}
+
+impl MemoDecoder {
+    /// Create a memo decoder which recognizes the given contacts.
+    pub fn new(contacts: impl IntoIterator<Item = PublicAddress>) -> Self {
+        Self {
+            contacts: contacts
+                .into_iter()
+                .map(|address| (ShortAddressHash::from(&address), address))
+                .collect(),
+        }
+    }
+
+    /// Decrypt and classify the memo of a TxOut which was sent to the given
+    /// subaddress of an account.
+    ///
+    /// TxOuts without a memo are reported as having an unused (0x0000) memo.
+    /// Destination memos are only decoded on the change subaddress.
+    pub fn decode(
+        &self,
+        account_key: &AccountKey,
+        subaddress_index: u64,
+        tx_out: &TxOut,
+    ) -> Result<api::DecodedMemo, Error> {
+        let tx_public_key = RistrettoPublic::try_from(&tx_out.public_key)?;
+        let shared_secret =
+            get_tx_out_shared_secret(account_key.view_private_key(), &tx_public_key);
+        let memo_payload = tx_out.decrypt_memo(&shared_secret);
+
+        let mut decoded = api::DecodedMemo::new();
+        decoded.set_memo_type(memo_payload.get_memo_type().to_vec());
+
+        let view_private_key = account_key.subaddress_view_private(subaddress_index);
+
+        // Unknown memo types are only reported by their type bytes.
+        match MemoType::try_from(&memo_payload) {
+            Ok(MemoType::AuthenticatedSender(memo)) => {
+                let sender_hash = memo.sender_address_hash();
+                let sender = self.validated_sender(&sender_hash, |sender| {
+                    bool::from(memo.validate(sender, &view_private_key, &tx_out.public_key))
+                });
+                decoded.set_authenticated_sender(authenticated_sender_memo(
+                    &sender_hash,
+                    0,
+                    sender,
+                ));
+            }
+            Ok(MemoType::AuthenticatedSenderWithPaymentRequestId(memo)) => {
+                let sender_hash = memo.sender_address_hash();
+                let sender = self.validated_sender(&sender_hash, |sender| {
+                    bool::from(memo.validate(sender, &view_private_key, &tx_out.public_key))
+                });
+                decoded.set_authenticated_sender(authenticated_sender_memo(
+                    &sender_hash,
+                    memo.payment_request_id(),
+                    sender,
+                ));
+            }
+            Ok(MemoType::AuthenticatedRefund(memo)) => {
+                let sender_hash = memo.sender_address_hash();
+                let sender = self.validated_sender(&sender_hash, |sender| {
+                    bool::from(memo.validate(sender, &view_private_key, &tx_out.public_key))
+                });
+                let mut refund = api::AuthenticatedRefundMemo::new();
+                refund.set_sender_hash(sender_hash.as_ref().to_vec());
+                refund.set_refunded_tx_out_public_key((&memo.refunded_tx_out_public_key()).into());
+                if let Some(sender) = sender {
+                    refund.set_validated(true);
+                    refund.set_sender(sender.into());
+                }
+                decoded.set_authenticated_refund(refund);
+            }
+            Ok(MemoType::AuthenticatedInvoiceSettlement(memo)) => {
+                let sender_hash = memo.sender_address_hash();
+                let sender = self.validated_sender(&sender_hash, |sender| {
+                    bool::from(memo.validate(sender, &view_private_key, &tx_out.public_key))
+                });
+                let mut settlement = api::AuthenticatedInvoiceSettlementMemo::new();
+                settlement.set_sender_hash(sender_hash.as_ref().to_vec());
+                settlement.set_invoice_id(memo.invoice_id().to_vec());
+                if let Some(sender) = sender {
+                    settlement.set_validated(true);
+                    settlement.set_sender(sender.into());
+                }
+                decoded.set_authenticated_invoice_settlement(settlement);
+            }
+            // Destination memos are only meaningful on change outputs, since
+            // anyone can put one on a TxOut they send us.
+            Ok(MemoType::Destination(memo)) if subaddress_index == CHANGE_SUBADDRESS_INDEX => {
+                let mut destination = api::DestinationMemo::new();
+                destination.set_recipient_hash(memo.get_address_hash().as_ref().to_vec());
+                destination.set_num_recipients(memo.get_num_recipients() as u32);
+                destination.set_fee(memo.get_fee());
+                destination.set_total_outlay(memo.get_total_outlay());
+                decoded.set_destination(destination);
+            }
+            Ok(_) | Err(_) => {}
+        }
+
+        Ok(decoded)
+    }
+
+    // The contact with this short address hash, if the memo validates against
+    // their address
+    fn validated_sender(
+        &self,
+        sender_hash: &ShortAddressHash,
+        validate: impl Fn(&PublicAddress) -> bool,
+    ) -> Option<&PublicAddress> {
+        self.contacts
+            .get(sender_hash)
+            .filter(|sender| validate(sender))
+    }
+}
+
+fn authenticated_sender_memo(
+    sender_hash: &ShortAddressHash,
+    payment_request_id: u64,
+    validated_sender: Option<&PublicAddress>,
+) -> api::AuthenticatedSenderMemo {
+    let mut memo = api::AuthenticatedSenderMemo::new();
+    memo.set_sender_hash(sender_hash.as_ref().to_vec());
+    memo.set_payment_request_id(payment_request_id);
+    if let Some(sender) = validated_sender {
+        memo.set_validated(true);
+        memo.set_sender(sender.into());
+    }
+    memo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
+    use mc_transaction_core::{
+        encrypted_fog_hint::EncryptedFogHint, tokens::Mob, Amount, BlockVersion, MemoPayload, Token,
+    };
+    use mc_transaction_std::{
+        AuthenticatedInvoiceSettlementMemo, AuthenticatedRefundMemo, AuthenticatedSenderMemo,
+        AuthenticatedSenderWithPaymentRequestIdMemo, DestinationMemo, SenderMemoCredential,
+    };
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn tx_out_with_memo(
+        recipient: &PublicAddress,
+        memo_fn: impl FnOnce(&RistrettoPublic) -> MemoPayload,
+        rng: &mut StdRng,
+    ) -> TxOut {
+        TxOut::new_with_memo(
+            BlockVersion::MAX,
+            Amount::new(10, Mob::ID),
+            recipient,
+            &RistrettoPrivate::from_random(rng),
+            EncryptedFogHint::fake_onetime_hint(rng),
+            |memo_context| Ok(memo_fn(memo_context.tx_public_key)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_authenticated_sender_memos() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+        let bob_addr = bob.subaddress(3);
+
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                AuthenticatedSenderWithPaymentRequestIdMemo::new(
+                    &SenderMemoCredential::from(&alice),
+                    bob_addr.view_public_key(),
+                    &tx_public_key.into(),
+                    17,
+                )
+                .into()
+            },
+            &mut rng,
+        );
+
+        // Without contacts, the sender hash is reported but not validated.
+        let decoded = MemoDecoder::default().decode(&bob, 3, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x01, 0x01]);
+        let memo = decoded.get_authenticated_sender();
+        assert_eq!(
+            memo.get_sender_hash(),
+            ShortAddressHash::from(&alice.default_subaddress()).as_ref()
+        );
+        assert_eq!(memo.get_payment_request_id(), 17);
+        assert!(!memo.get_validated());
+        assert!(!memo.has_sender());
+
+        // With alice as a contact, the memo validates.
+        let decoder = MemoDecoder::new(vec![alice.default_subaddress()]);
+        let decoded = decoder.decode(&bob, 3, &tx_out).unwrap();
+        let memo = decoded.get_authenticated_sender();
+        assert!(memo.get_validated());
+        assert_eq!(
+            PublicAddress::try_from(memo.get_sender()).unwrap(),
+            alice.default_subaddress()
+        );
+
+        // Decoding with the wrong subaddress index fails validation.
+        let decoded = decoder.decode(&bob, 4, &tx_out).unwrap();
+        assert!(!decoded.get_authenticated_sender().get_validated());
+
+        // A memo from someone else who has the same hash as a contact doesn't
+        // validate.
+        let mallory = AccountKey::random(&mut rng);
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                let cred = SenderMemoCredential {
+                    address_hash: ShortAddressHash::from(&alice.default_subaddress()),
+                    subaddress_spend_private_key: mallory.default_subaddress_spend_private(),
+                };
+                AuthenticatedSenderMemo::new(
+                    &cred,
+                    bob_addr.view_public_key(),
+                    &tx_public_key.into(),
+                )
+                .into()
+            },
+            &mut rng,
+        );
+        let decoded = decoder.decode(&bob, 3, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x01, 0x00]);
+        assert_eq!(
+            decoded.get_authenticated_sender().get_payment_request_id(),
+            0
+        );
+        assert!(!decoded.get_authenticated_sender().get_validated());
+    }
+
+    #[test]
+    fn test_decode_refund_and_invoice_settlement_memos() {
+        let mut rng: StdRng = SeedableRng::from_seed([3u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+        let bob_addr = bob.default_subaddress();
+        let decoder = MemoDecoder::new(vec![alice.default_subaddress()]);
+
+        let refunded = CompressedRistrettoPublic::from_random(&mut rng);
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                AuthenticatedRefundMemo::new(
+                    &SenderMemoCredential::from(&alice),
+                    bob_addr.view_public_key(),
+                    &tx_public_key.into(),
+                    &refunded,
+                )
+                .into()
+            },
+            &mut rng,
+        );
+
+        let decoded = MemoDecoder::default().decode(&bob, 0, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x01, 0x02]);
+        let memo = decoded.get_authenticated_refund();
+        assert_eq!(
+            memo.get_sender_hash(),
+            ShortAddressHash::from(&alice.default_subaddress()).as_ref()
+        );
+        assert_eq!(
+            CompressedRistrettoPublic::try_from(memo.get_refunded_tx_out_public_key()).unwrap(),
+            refunded
+        );
+        assert!(!memo.get_validated());
+        assert!(!memo.has_sender());
+
+        let decoded = decoder.decode(&bob, 0, &tx_out).unwrap();
+        let memo = decoded.get_authenticated_refund();
+        assert!(memo.get_validated());
+        assert_eq!(
+            PublicAddress::try_from(memo.get_sender()).unwrap(),
+            alice.default_subaddress()
+        );
+        assert!(!decoder
+            .decode(&bob, 1, &tx_out)
+            .unwrap()
+            .get_authenticated_refund()
+            .get_validated());
+
+        let invoice_id = [9u8; 16];
+        let tx_out = tx_out_with_memo(
+            &bob_addr,
+            |tx_public_key| {
+                AuthenticatedInvoiceSettlementMemo::new(
+                    &SenderMemoCredential::from(&alice),
+                    bob_addr.view_public_key(),
+                    &tx_public_key.into(),
+                    invoice_id,
+                )
+                .into()
+            },
+            &mut rng,
+        );
+
+        let decoded = MemoDecoder::default().decode(&bob, 0, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x01, 0x03]);
+        let memo = decoded.get_authenticated_invoice_settlement();
+        assert_eq!(
+            memo.get_sender_hash(),
+            ShortAddressHash::from(&alice.default_subaddress()).as_ref()
+        );
+        assert_eq!(memo.get_invoice_id(), &invoice_id);
+        assert!(!memo.get_validated());
+
+        let decoded = decoder.decode(&bob, 0, &tx_out).unwrap();
+        let memo = decoded.get_authenticated_invoice_settlement();
+        assert!(memo.get_validated());
+        assert_eq!(
+            PublicAddress::try_from(memo.get_sender()).unwrap(),
+            alice.default_subaddress()
+        );
+        assert!(!decoded.has_authenticated_sender());
+        assert!(!decoded.has_authenticated_refund());
+    }
+
+    #[test]
+    fn test_decode_destination_and_unknown_memos() {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+
+        let recipient_hash = ShortAddressHash::from(&bob.default_subaddress());
+        let tx_out = tx_out_with_memo(
+            &alice.change_subaddress(),
+            |_| {
+                let mut memo = DestinationMemo::new(recipient_hash.clone(), 110, 10).unwrap();
+                memo.set_num_recipients(2);
+                memo.into()
+            },
+            &mut rng,
+        );
+        let decoded = MemoDecoder::default()
+            .decode(&alice, CHANGE_SUBADDRESS_INDEX, &tx_out)
+            .unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x02, 0x00]);
+        let memo = decoded.get_destination();
+        assert_eq!(memo.get_recipient_hash(), recipient_hash.as_ref());
+        assert_eq!(memo.get_num_recipients(), 2);
+        assert_eq!(memo.get_fee(), 10);
+        assert_eq!(memo.get_total_outlay(), 110);
+
+        // A destination memo sent to a subaddress other than change is not
+        // reported.
+        let tx_out = tx_out_with_memo(
+            &alice.default_subaddress(),
+            |_| {
+                DestinationMemo::new(recipient_hash.clone(), 110, 10)
+                    .unwrap()
+                    .into()
+            },
+            &mut rng,
+        );
+        let decoded = MemoDecoder::default().decode(&alice, 0, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x02, 0x00]);
+        assert!(!decoded.has_destination());
+
+        let tx_out = tx_out_with_memo(
+            &alice.default_subaddress(),
+            |_| MemoPayload::new([0x7f, 0x01], [3u8; 64]),
+            &mut rng,
+        );
+        let decoded = MemoDecoder::default().decode(&alice, 0, &tx_out).unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x7f, 0x01]);
+        assert!(!decoded.has_authenticated_sender());
+        assert!(!decoded.has_destination());
+    }
+}
@@ -3,7 +3,7 @@
 //! Construct and submit transactions to the validator network.
 
 use crate::{database::Database, error::Error, monitor_store::MonitorId, utxo_store::UnspentTxOut};
-use mc_account_keys::{AccountKey, PublicAddress};
+use mc_account_keys::{AccountKey, PublicAddress, GIFT_CODE_SUBADDRESS_INDEX};
 use mc_blockchain_types::{BlockIndex, BlockVersion};
 use mc_common::{
     logger::{log, o, Logger},
@@ -12,17 +12,18 @@ use mc_common::{
 use mc_connection::{
     BlockInfo, BlockchainConnection, ConnectionManager, RetryableUserTxConnection, UserTxConnection,
 };
-use mc_crypto_keys::RistrettoPublic;
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
 use mc_crypto_rand::{CryptoRng, RngCore};
-use mc_crypto_ring_signature_signer::NoKeysRingSigner;
+use mc_crypto_ring_signature_signer::{InputSecret, NoKeysRingSigner};
 use mc_fog_report_validation::FogPubkeyResolver;
 use mc_ledger_db::{Error as LedgerError, Ledger, LedgerDB};
 use mc_transaction_core::{
     constants::{MAX_INPUTS, MILLIMOB_TO_PICOMOB, RING_SIZE},
+    get_tx_out_shared_secret,
     onetime_keys::recover_onetime_private_key,
     ring_signature::KeyImage,
     tx::{Tx, TxOut, TxOutConfirmationNumber, TxOutMembershipProof},
-    Amount, TokenId,
+    Amount, TokenId, TxOutGiftCode,
 };
 use mc_transaction_std::{
     EmptyMemoBuilder, InputCredentials, MemoBuilder, ReservedSubaddresses, TransactionBuilder,
@@ -472,6 +473,8 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
     ///   determining fees. This should normally come from polling_network_state
     /// * `opt_fee` - Transaction fee. If zero, defaults to the highest fee set
     ///   by configured consensus nodes, or the hard-coded FALLBACK_FEE.
+    /// * `opt_memo_builder` - Optional memo builder to use instead of the
+    ///   default one (EmptyMemoBuilder).
     pub fn generate_tx_from_tx_list(
         &self,
         account_key: &AccountKey,
@@ -480,6 +483,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         receiver: &PublicAddress,
         last_block_infos: &[BlockInfo],
         opt_fee: u64,
+        opt_memo_builder: Option<Box<dyn MemoBuilder + 'static + Send + Sync>>,
     ) -> Result<TxProposal, Error> {
         let logger = self.logger.new(o!("receiver" => receiver.to_string()));
         log::trace!(logger, "Generating txo list transaction...");
@@ -554,7 +558,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             &outlays,
             tombstone_block,
             &self.fog_resolver_factory,
-            None,
+            opt_memo_builder,
             &mut rng,
             &self.logger,
         )?;
@@ -567,6 +571,213 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         Ok(tx_proposal)
     }
 
+    /// Get the gift code for a TxOut that was sent to the gift code
+    /// subaddress of an account, i.e. by a transaction funding a gift code.
+    ///
+    /// # Arguments
+    /// * `account_key` - Account key that funded the gift code.
+    /// * `tx_out_public_key` - Public key of the gift code TxOut.
+    pub fn get_gift_code(
+        &self,
+        account_key: &AccountKey,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Result<TxOutGiftCode, Error> {
+        let global_index = self
+            .ledger_db
+            .get_tx_out_index_by_public_key(tx_out_public_key)?;
+        let tx_out = self.ledger_db.get_tx_out_by_index(global_index)?;
+
+        let tx_public_key = RistrettoPublic::try_from(&tx_out.public_key)?;
+        let shared_secret =
+            get_tx_out_shared_secret(account_key.view_private_key(), &tx_public_key);
+        let onetime_private_key = recover_onetime_private_key(
+            &tx_public_key,
+            account_key.view_private_key(),
+            &account_key.gift_code_subaddress_spend_private(),
+        );
+
+        // Only TxOuts at our gift code subaddress are gift codes we can hand out.
+        if CompressedRistrettoPublic::from(&RistrettoPublic::from(&onetime_private_key))
+            != tx_out.target_key
+        {
+            return Err(Error::InvalidArgument(
+                "tx_out_public_key".to_string(),
+                "TxOut was not sent to the gift code subaddress".to_string(),
+            ));
+        }
+
+        Ok(TxOutGiftCode::new(
+            global_index,
+            onetime_private_key,
+            shared_secret,
+        ))
+    }
+
+    /// Create a TxProposal that spends a gift code, sending its entire value
+    /// minus the fee to a change output of an account.
+    ///
+    /// This is used both to claim a gift code and to cancel one. The memo
+    /// builder determines which gift code memo is written to the change
+    /// output.
+    ///
+    /// # Arguments
+    /// * `gift_code` - The gift code to spend.
+    /// * `account_key` - Account receiving the value of the gift code.
+    /// * `change_subaddress` - Subaddress receiving the value of the gift code.
+    /// * `last_block_infos` - Last block info responses from the network, for
+    ///   determining fees. This should normally come from polling_network_state
+    /// * `opt_fee` - Transaction fee. If zero, defaults to the highest fee set
+    ///   by configured consensus nodes, or the hard-coded FALLBACK_FEE.
+    /// * `opt_tombstone` - Tombstone block. If zero, sets to default.
+    /// * `memo_builder` - Memo builder for the change output.
+    pub fn generate_gift_code_spend_tx(
+        &self,
+        gift_code: &TxOutGiftCode,
+        account_key: &AccountKey,
+        change_subaddress: u64,
+        last_block_infos: &[BlockInfo],
+        opt_fee: u64,
+        opt_tombstone: u64,
+        memo_builder: Box<dyn MemoBuilder + 'static + Send + Sync>,
+    ) -> Result<TxProposal, Error> {
+        let logger = self
+            .logger
+            .new(o!("gift_code_global_index" => gift_code.global_index));
+        log::trace!(logger, "Generating gift code spend transaction...");
+
+        // Find the gift code TxOut, and check that the gift code can spend it.
+        let tx_out = self.ledger_db.get_tx_out_by_index(gift_code.global_index)?;
+        if CompressedRistrettoPublic::from(&RistrettoPublic::from(&gift_code.onetime_private_key))
+            != tx_out.target_key
+        {
+            return Err(Error::InvalidArgument(
+                "gift_code".to_string(),
+                "onetime_private_key does not match the gift code TxOut".to_string(),
+            ));
+        }
+        let (amount, blinding) = tx_out
+            .masked_amount
+            .get_value(&gift_code.shared_secret)
+            .map_err(|err| Error::InvalidArgument("gift_code".to_string(), err.to_string()))?;
+        if self
+            .ledger_db
+            .contains_key_image(&KeyImage::from(&gift_code.onetime_private_key))?
+        {
+            return Err(Error::InvalidArgument(
+                "gift_code".to_string(),
+                "The gift code has already been spent".to_string(),
+            ));
+        }
+
+        // Figure out the block_version and fee (involves network round-trips to
+        // consensus, unless fee arg is non-zero)
+        let (fee, block_version) =
+            self.get_network_fee_and_block_version(amount.token_id, opt_fee, last_block_infos)?;
+
+        // Make sure we understand this block version
+        let block_version =
+            BlockVersion::try_from(block_version).map_err(|err| Error::TxBuild(err.to_string()))?;
+
+        if amount.value < fee {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // Get a ring of mixins, and add the gift code TxOut to it.
+        let proof = self
+            .get_membership_proofs(core::slice::from_ref(&tx_out))?
+            .pop()
+            .ok_or_else(|| Error::TxBuild("missing membership proof".to_string()))?;
+        let mut ring_and_proofs = self
+            .get_rings(DEFAULT_RING_SIZE - 1, 1, &[proof.index])?
+            .pop()
+            .unwrap_or_default();
+        ring_and_proofs.push((tx_out.clone(), proof));
+        ring_and_proofs
+            .sort_by(|(tx_out_a, _), (tx_out_b, _)| tx_out_a.public_key.cmp(&tx_out_b.public_key));
+        let real_index = ring_and_proofs
+            .iter()
+            .position(|(ring_tx_out, _)| *ring_tx_out == tx_out)
+            .expect("Ring must contain the gift code TxOut");
+        let (ring, membership_proofs): (Vec<TxOut>, Vec<TxOutMembershipProof>) =
+            ring_and_proofs.into_iter().unzip();
+        log::trace!(logger, "Got ring");
+
+        // Come up with tombstone block.
+        let tombstone_block = if opt_tombstone > 0 {
+            opt_tombstone
+        } else {
+            self.ledger_db.num_blocks()? + DEFAULT_NEW_TX_BLOCK_ATTEMPTS
+        };
+        log::trace!(logger, "Tombstone block set to {}", tombstone_block);
+
+        let change_dest =
+            ReservedSubaddresses::from_subaddress_index(account_key, Some(change_subaddress), None);
+        let fog_resolver = {
+            let fog_uris = extract_fog_uri(&change_dest.primary_address)?
+                .into_iter()
+                .collect::<Vec<_>>();
+            (self.fog_resolver_factory)(&fog_uris).map_err(Error::Fog)?
+        };
+
+        let mut tx_builder = TransactionBuilder::new_with_box(
+            block_version,
+            Amount::new(fee, amount.token_id),
+            fog_resolver,
+            memo_builder,
+        )
+        .map_err(|err| Error::TxBuild(format!("Error creating transaction builder: {}", err)))?;
+
+        tx_builder.add_input(InputCredentials {
+            ring,
+            membership_proofs,
+            real_index,
+            input_secret: InputSecret {
+                onetime_key_derive_data: gift_code.onetime_private_key.into(),
+                amount,
+                blinding,
+            },
+        });
+
+        let mut rng = rand::thread_rng();
+        tx_builder
+            .add_change_output(
+                Amount::new(amount.value - fee, amount.token_id),
+                &change_dest,
+                &mut rng,
+            )
+            .map_err(|err| Error::TxBuild(format!("failed adding output (change): {}", err)))?;
+        tx_builder.set_tombstone_block(tombstone_block);
+
+        let tx = tx_builder
+            .build(&NoKeysRingSigner {}, &mut rng)
+            .map_err(|err| Error::TxBuild(format!("build tx failed: {}", err)))?;
+        log::trace!(
+            logger,
+            "Gift code spend tx constructed, hash={}",
+            tx.tx_hash()
+        );
+
+        // The gift code TxOut always belongs to the gift code subaddress of
+        // the account that funded it.
+        let utxo = UnspentTxOut {
+            tx_out,
+            subaddress_index: GIFT_CODE_SUBADDRESS_INDEX,
+            key_image: KeyImage::from(&gift_code.onetime_private_key),
+            value: amount.value,
+            token_id: *amount.token_id,
+            attempted_spend_height: 0,
+            attempted_spend_tombstone: 0,
+        };
+
+        Ok(TxProposal {
+            utxos: vec![utxo],
+            outlays: vec![],
+            tx,
+            outlay_index_to_tx_out_index: HashMap::default(),
+            outlay_confirmation_numbers: vec![],
+        })
+    }
+
     /// Submit a previously built tx proposal to the network.
     pub fn submit_tx_proposal(&self, tx_proposal: &TxProposal) -> Result<u64, Error> {
         // Pick a peer to submit to.
@@ -877,8 +1088,11 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             fog_resolver_factory(&fog_uris).map_err(Error::Fog)?
         };
 
+        // Memo builders may write to the change output (e.g. destination memos),
+        // so one is always written when a memo builder is provided.
+        let always_write_change = opt_memo_builder.is_some();
+
         // Create tx_builder.
-        // TODO (GH #1522): Use RTH memo builder, optionally?
         let memo_builder: Box<dyn MemoBuilder + Send + Sync> =
             opt_memo_builder.unwrap_or_else(|| Box::new(EmptyMemoBuilder::default()));
 
@@ -966,6 +1180,13 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             );
         }
 
+        // Our change and gift code subaddresses.
+        let reserved_subaddresses = ReservedSubaddresses::from_subaddress_index(
+            from_account_key,
+            Some(change_subaddress),
+            None,
+        );
+
         // Add outputs to our destinations.
         let mut total_value = 0;
         let mut tx_out_to_outlay_index = HashMap::default();
@@ -977,8 +1198,13 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 value: outlay.value,
                 token_id,
             };
-            let (tx_out, confirmation_number) = tx_builder
-                .add_output(amount, &outlay.receiver, rng)
+            // Outlays to our own gift code subaddress fund gift codes.
+            let (tx_out, confirmation_number) =
+                if outlay.receiver == reserved_subaddresses.gift_code_subaddress {
+                    tx_builder.add_gift_code_output(amount, &reserved_subaddresses, rng)
+                } else {
+                    tx_builder.add_output(amount, &outlay.receiver, rng)
+                }
                 .map_err(|err| Error::TxBuild(format!("failed adding output: {}", err)))?;
 
             tx_out_to_outlay_index.insert(tx_out, i);
@@ -997,9 +1223,9 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         let change = input_value - total_value - tx_builder.get_fee();
 
         // If we do have nonzero change, add an output for that as well.
-        // TODO (GH #1522): Should the exchange write destination memos?
-        // If so then we must always write a change output, even if the change is zero
-        if change > 0 {
+        // Write a change output, unless there is no change and no memo builder
+        // that might need it.
+        if change > 0 || always_write_change {
             // TODO: If you want to support mixed transactions, use outlay-specific token id
             // here
             let change_amount = Amount {
@@ -1007,14 +1233,8 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 token_id,
             };
 
-            let change_dest = ReservedSubaddresses::from_subaddress_index(
-                from_account_key,
-                Some(change_subaddress),
-                None,
-            );
-
             tx_builder
-                .add_change_output(change_amount, &change_dest, rng)
+                .add_change_output(change_amount, &reserved_subaddresses, rng)
                 .map_err(|err| Error::TxBuild(format!("failed adding output (change): {}", err)))?;
         }
 
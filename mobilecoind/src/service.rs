@@ -9,8 +9,11 @@
 use crate::{
     database::Database,
     error::Error,
+    memo_builder::memo_builder_from_config,
+    memo_decoder::MemoDecoder,
     monitor_store::{MonitorData, MonitorId},
     payments::{Outlay, TransactionsManager, TxProposal},
+    processed_block_store::ProcessedTxOutDirection,
     sync::SyncThread,
     utxo_store::{UnspentTxOut, UtxoId},
 };
@@ -39,9 +42,12 @@ use mc_transaction_core::{
     onetime_keys::recover_onetime_private_key,
     ring_signature::KeyImage,
     tx::{TxOut, TxOutConfirmationNumber, TxOutMembershipProof},
-    TokenId,
+    Amount, TokenId, TxOutGiftCode,
+};
+use mc_transaction_std::{
+    BurnRedemptionMemo, BurnRedemptionMemoBuilder, GiftCodeCancellationMemoBuilder,
+    GiftCodeSenderMemoBuilder, MemoType,
 };
-use mc_transaction_std::{BurnRedemptionMemo, BurnRedemptionMemoBuilder};
 use mc_util_from_random::FromRandom;
 use mc_util_grpc::{
     rpc_internal_error, rpc_invalid_arg_error, rpc_logger, send_result, AdminService,
@@ -71,6 +77,7 @@ impl Service {
         network_state: Arc<RwLock<PollingNetworkState<T>>>,
         listen_uri: &MobilecoindUri,
         num_workers: Option<usize>,
+        memo_decoder: MemoDecoder,
         logger: Logger,
     ) -> Self {
         let sync_thread = if mobilecoind_db.is_db_encrypted() {
@@ -111,6 +118,7 @@ impl Service {
             watcher_db,
             network_state,
             start_sync_thread,
+            memo_decoder,
             logger.clone(),
         );
 
@@ -168,6 +176,7 @@ pub struct ServiceApi<
     watcher_db: Option<WatcherDB>,
     network_state: Arc<RwLock<PollingNetworkState<T>>>,
     start_sync_thread: Arc<dyn Fn() + Send + Sync>,
+    memo_decoder: MemoDecoder,
     logger: Logger,
 }
 
@@ -182,6 +191,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             watcher_db: self.watcher_db.clone(),
             network_state: self.network_state.clone(),
             start_sync_thread: self.start_sync_thread.clone(),
+            memo_decoder: self.memo_decoder.clone(),
             logger: self.logger.clone(),
         }
     }
@@ -197,6 +207,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         watcher_db: Option<WatcherDB>,
         network_state: Arc<RwLock<PollingNetworkState<T>>>,
         start_sync_thread: Arc<dyn Fn() + Send + Sync>,
+        memo_decoder: MemoDecoder,
         logger: Logger,
     ) -> Self {
         Self {
@@ -206,6 +217,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             watcher_db,
             network_state,
             start_sync_thread,
+            memo_decoder,
             logger,
         }
     }
@@ -351,8 +363,28 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             .filter(|utxo| utxo.token_id == request.token_id)
             .collect();
 
+        // We will use the AccountKey to decrypt the memos
+        let account_key = self
+            .mobilecoind_db
+            .get_monitor_data(&monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?
+            .account_key;
+
         // Convert to protos.
-        let proto_utxos: Vec<api::UnspentTxOut> = utxos.iter().map(|utxo| utxo.into()).collect();
+        let proto_utxos = utxos
+            .iter()
+            .map(|utxo| {
+                let mut proto_utxo = api::UnspentTxOut::from(utxo);
+                let memo = self
+                    .memo_decoder
+                    .decode(&account_key, utxo.subaddress_index, &utxo.tx_out)
+                    .map_err(|err| rpc_internal_error("memo_decoder.decode", err, &self.logger))?;
+                proto_utxo.set_memo(memo);
+                Ok(proto_utxo)
+            })
+            .collect::<Result<Vec<_>, RpcStatus>>()?;
 
         // Returrn response.
         let mut response = api::GetUnspentTxOutListResponse::new();
@@ -893,6 +925,11 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             })
             .collect::<Result<Vec<Outlay>, RpcStatus>>()?;
 
+        // Create the requested memo builder, if any.
+        let opt_memo_builder =
+            memo_builder_from_config(request.get_memo_builder(), &sender_monitor_data.account_key)
+                .map_err(|err| rpc_invalid_arg_error("memo_builder", err, &self.logger))?;
+
         // Attempt to construct a transaction.
         let tx_proposal = self
             .transactions_manager
@@ -905,7 +942,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 &self.get_last_block_infos(),
                 request.fee,
                 request.tombstone,
-                None,
+                opt_memo_builder,
             )
             .map_err(|err| {
                 rpc_internal_error("transactions_manager.build_transaction", err, &self.logger)
@@ -987,6 +1024,10 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         let receiver = PublicAddress::try_from(request.get_receiver())
             .map_err(|err| rpc_internal_error("PublicAddress.try_from", err, &self.logger))?;
 
+        let opt_memo_builder =
+            memo_builder_from_config(request.get_memo_builder(), &account_key)
+                .map_err(|err| rpc_invalid_arg_error("memo_builder", err, &self.logger))?;
+
         let tx_proposal = self
             .transactions_manager
             .generate_tx_from_tx_list(
@@ -996,6 +1037,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 &receiver,
                 &self.get_last_block_infos(),
                 request.fee,
+                opt_memo_builder,
             )
             .map_err(|err| {
                 rpc_internal_error(
@@ -1156,6 +1198,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         generate_tx_request.set_fee(request.fee);
         generate_tx_request.set_tombstone(request.tombstone);
         generate_tx_request.set_token_id(request.token_id);
+        generate_tx_request.set_memo_builder(request.get_memo_builder().clone());
 
         let mut generate_tx_response = self.generate_tx_impl(generate_tx_request)?;
         let tx_proposal = generate_tx_response.take_tx_proposal();
@@ -1226,6 +1269,356 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         Ok(response)
     }
 
+    fn generate_gift_code_funding_tx_impl(
+        &mut self,
+        request: api::GenerateGiftCodeFundingTxRequest,
+    ) -> Result<api::GenerateGiftCodeFundingTxResponse, RpcStatus> {
+        // Get sender monitor id from request.
+        let sender_monitor_id = MonitorId::try_from(&request.sender_monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let sender_monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&sender_monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        // The gift code is funded by paying our own gift code subaddress.
+        let outlay = Outlay {
+            receiver: sender_monitor_data.account_key.gift_code_subaddress(),
+            value: request.value,
+        };
+
+        // Generate transaction.
+        let mut generate_tx_request = api::GenerateTxRequest::new();
+        generate_tx_request.set_sender_monitor_id(request.get_sender_monitor_id().to_vec());
+        generate_tx_request.set_change_subaddress(request.change_subaddress);
+        generate_tx_request.set_input_list(RepeatedField::from_vec(request.input_list.to_vec()));
+        generate_tx_request.set_outlay_list(RepeatedField::from_vec(vec![(&outlay).into()]));
+        generate_tx_request.set_fee(request.fee);
+        generate_tx_request.set_tombstone(request.tombstone);
+        generate_tx_request.set_token_id(request.token_id);
+        generate_tx_request
+            .mut_memo_builder()
+            .mut_gift_code_funding()
+            .set_note(request.get_note().to_string());
+
+        let mut generate_tx_response = self.generate_tx_impl(generate_tx_request)?;
+        let tx_proposal = generate_tx_response.take_tx_proposal();
+
+        // Grab the public key of the gift code TxOut, which is our single outlay.
+        let tx_out_public_key = tx_proposal
+            .get_outlay_index_to_tx_out_index()
+            .get(&0)
+            .and_then(|tx_out_index| {
+                tx_proposal
+                    .get_tx()
+                    .get_prefix()
+                    .get_outputs()
+                    .get(*tx_out_index as usize)
+            })
+            .map(|tx_out| tx_out.get_public_key().clone())
+            .ok_or_else(|| {
+                RpcStatus::with_message(
+                    RpcStatusCode::INTERNAL,
+                    "gift code TxOut not found".to_owned(),
+                )
+            })?;
+
+        let mut response = api::GenerateGiftCodeFundingTxResponse::new();
+        response.set_tx_proposal(tx_proposal);
+        response.set_tx_out_public_key(tx_out_public_key);
+        Ok(response)
+    }
+
+    fn create_gift_code_impl(
+        &mut self,
+        request: api::CreateGiftCodeRequest,
+    ) -> Result<api::CreateGiftCodeResponse, RpcStatus> {
+        // Get monitor id from request.
+        let monitor_id = MonitorId::try_from(&request.monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        let tx_out_public_key =
+            CompressedRistrettoPublic::try_from(request.get_tx_out_public_key()).map_err(
+                |err| rpc_internal_error("compressed_ristretto_public.try_from", err, &self.logger),
+            )?;
+
+        let gift_code = self
+            .transactions_manager
+            .get_gift_code(&monitor_data.account_key, &tx_out_public_key)
+            .map_err(|err| {
+                rpc_internal_error("transactions_manager.get_gift_code", err, &self.logger)
+            })?;
+        let amount = self.get_gift_code_amount(&gift_code)?;
+
+        let mut wrapper = api::printable::PrintableWrapper::new();
+        wrapper.set_tx_out_gift_code((&gift_code).into());
+
+        let b58_code = wrapper
+            .b58_encode()
+            .map_err(|err| rpc_internal_error("b58_encode", err, &self.logger))?;
+
+        let mut response = api::CreateGiftCodeResponse::new();
+        response.set_b58_code(b58_code);
+        response.set_global_index(gift_code.global_index);
+        response.set_value(amount.value);
+        response.set_token_id(*amount.token_id);
+        Ok(response)
+    }
+
+    fn generate_gift_code_claim_tx_impl(
+        &mut self,
+        request: api::GenerateGiftCodeClaimTxRequest,
+    ) -> Result<api::GenerateGiftCodeClaimTxResponse, RpcStatus> {
+        // Get monitor id from request.
+        let monitor_id = MonitorId::try_from(&request.monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        // Check that subaddress is covered by this monitor.
+        if !monitor_data
+            .subaddress_indexes()
+            .contains(&request.subaddress)
+        {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "subaddress".into(),
+            ));
+        }
+
+        let gift_code = self.decode_gift_code(request.get_b58_code())?;
+
+        let memo_builder = GiftCodeSenderMemoBuilder::new(request.get_note())
+            .map_err(|err| rpc_invalid_arg_error("note", err, &self.logger))?;
+
+        let tx_proposal = self
+            .transactions_manager
+            .generate_gift_code_spend_tx(
+                &gift_code,
+                &monitor_data.account_key,
+                request.subaddress,
+                &self.get_last_block_infos(),
+                request.fee,
+                request.tombstone,
+                Box::new(memo_builder),
+            )
+            .map_err(|err| {
+                rpc_internal_error(
+                    "transactions_manager.generate_gift_code_spend_tx",
+                    err,
+                    &self.logger,
+                )
+            })?;
+
+        let mut response = api::GenerateGiftCodeClaimTxResponse::new();
+        response.set_tx_proposal((&tx_proposal).into());
+        Ok(response)
+    }
+
+    fn generate_gift_code_cancellation_tx_impl(
+        &mut self,
+        request: api::GenerateGiftCodeCancellationTxRequest,
+    ) -> Result<api::GenerateGiftCodeCancellationTxResponse, RpcStatus> {
+        // Get monitor id from request.
+        let monitor_id = MonitorId::try_from(&request.monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        // Check that change_subaddress is covered by this monitor.
+        if !monitor_data
+            .subaddress_indexes()
+            .contains(&request.change_subaddress)
+        {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "change_subaddress".into(),
+            ));
+        }
+
+        let tx_out_public_key =
+            CompressedRistrettoPublic::try_from(request.get_tx_out_public_key()).map_err(
+                |err| rpc_internal_error("compressed_ristretto_public.try_from", err, &self.logger),
+            )?;
+
+        let gift_code = self
+            .transactions_manager
+            .get_gift_code(&monitor_data.account_key, &tx_out_public_key)
+            .map_err(|err| {
+                rpc_internal_error("transactions_manager.get_gift_code", err, &self.logger)
+            })?;
+
+        let memo_builder = GiftCodeCancellationMemoBuilder::new(gift_code.global_index);
+
+        let tx_proposal = self
+            .transactions_manager
+            .generate_gift_code_spend_tx(
+                &gift_code,
+                &monitor_data.account_key,
+                request.change_subaddress,
+                &self.get_last_block_infos(),
+                request.fee,
+                request.tombstone,
+                Box::new(memo_builder),
+            )
+            .map_err(|err| {
+                rpc_internal_error(
+                    "transactions_manager.generate_gift_code_spend_tx",
+                    err,
+                    &self.logger,
+                )
+            })?;
+
+        let mut response = api::GenerateGiftCodeCancellationTxResponse::new();
+        response.set_tx_proposal((&tx_proposal).into());
+        Ok(response)
+    }
+
+    fn get_gift_code_status_impl(
+        &mut self,
+        request: api::GetGiftCodeStatusRequest,
+    ) -> Result<api::GetGiftCodeStatusResponse, RpcStatus> {
+        let gift_code = self.decode_gift_code(request.get_b58_code())?;
+        let amount = self.get_gift_code_amount(&gift_code)?;
+
+        let mut response = api::GetGiftCodeStatusResponse::new();
+        response.set_value(amount.value);
+        response.set_token_id(*amount.token_id);
+
+        // The gift code has been claimed or cancelled once its key image is in the
+        // ledger.
+        let key_image = KeyImage::from(&gift_code.onetime_private_key);
+        if let Some(block_index) = self
+            .ledger_db
+            .check_key_image(&key_image)
+            .map_err(|err| rpc_internal_error("ledger_db.check_key_image", err, &self.logger))?
+        {
+            response.set_spent_block_index(block_index);
+            if self.gift_code_cancelled_in_block(gift_code.global_index, block_index)? {
+                response.set_status(api::GiftCodeStatus::GiftCodeCancelled);
+            } else {
+                response.set_status(api::GiftCodeStatus::GiftCodeClaimed);
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Decode a b58-encoded gift code.
+    fn decode_gift_code(&self, b58_code: &str) -> Result<TxOutGiftCode, RpcStatus> {
+        let wrapper = api::printable::PrintableWrapper::b58_decode(b58_code.to_string())
+            .map_err(|err| rpc_internal_error("PrintableWrapper.b58_decode", err, &self.logger))?;
+
+        if !wrapper.has_tx_out_gift_code() {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "has_tx_out_gift_code".into(),
+            ));
+        }
+
+        TxOutGiftCode::try_from(wrapper.get_tx_out_gift_code())
+            .map_err(|err| rpc_internal_error("tx_out_gift_code.try_from", err, &self.logger))
+    }
+
+    /// Un-blind the amount of a gift code TxOut.
+    fn get_gift_code_amount(&self, gift_code: &TxOutGiftCode) -> Result<Amount, RpcStatus> {
+        let tx_out = self
+            .ledger_db
+            .get_tx_out_by_index(gift_code.global_index)
+            .map_err(|err| {
+                rpc_internal_error("ledger_db.get_tx_out_by_index", err, &self.logger)
+            })?;
+
+        gift_code
+            .unblind_amount(tx_out.masked_amount)
+            .map_err(|err| rpc_internal_error("gift_code.unblind_amount", err, &self.logger))
+    }
+
+    /// Check whether one of our monitors received a gift code cancellation
+    /// memo for the given gift code in the given block.
+    fn gift_code_cancelled_in_block(
+        &self,
+        gift_code_global_index: u64,
+        block_index: u64,
+    ) -> Result<bool, RpcStatus> {
+        let monitor_map = self.mobilecoind_db.get_monitor_map().map_err(|err| {
+            rpc_internal_error("mobilecoind_db.get_monitor_map", err, &self.logger)
+        })?;
+
+        for (monitor_id, monitor_data) in monitor_map {
+            let processed_tx_outs = match self
+                .mobilecoind_db
+                .get_processed_block(&monitor_id, block_index)
+            {
+                Ok(processed_tx_outs) => processed_tx_outs,
+                // Monitors that did not process this block could not have
+                // received the cancellation.
+                Err(Error::BlockIndexTooSmall(_, _)) | Err(Error::BlockNotYetProcessed(_, _)) => {
+                    continue;
+                }
+                Err(err) => {
+                    return Err(rpc_internal_error(
+                        "mobilecoind_db.get_processed_block",
+                        err,
+                        &self.logger,
+                    ));
+                }
+            };
+
+            for processed_tx_out in processed_tx_outs
+                .iter()
+                .filter(|src| src.direction == ProcessedTxOutDirection::Received as i32)
+            {
+                let tx_out = self
+                    .ledger_db
+                    .get_tx_out_index_by_public_key(&processed_tx_out.public_key)
+                    .and_then(|index| self.ledger_db.get_tx_out_by_index(index))
+                    .map_err(|err| rpc_internal_error("ledger_db.get_tx_out", err, &self.logger))?;
+                let tx_public_key =
+                    RistrettoPublic::try_from(&tx_out.public_key).map_err(|err| {
+                        rpc_internal_error("ristretto_public.try_from", err, &self.logger)
+                    })?;
+                let shared_secret = get_tx_out_shared_secret(
+                    monitor_data.account_key.view_private_key(),
+                    &tx_public_key,
+                );
+                if let Ok(MemoType::GiftCodeCancellation(memo)) =
+                    MemoType::try_from(&tx_out.decrypt_memo(&shared_secret))
+                {
+                    if memo.cancelled_gift_code_index() == gift_code_global_index {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
     fn submit_tx_impl(
         &mut self,
         request: api::SubmitTxRequest,
@@ -1701,7 +2094,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         let monitor_id = MonitorId::try_from(&request.monitor_id)
             .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
 
-        // We will use the AccountKey to compute the Address Code
+        // We will use the AccountKey to compute the Address Code and decrypt memos
         let account_key = self
             .mobilecoind_db
             .get_monitor_data(&monitor_id)
@@ -1738,6 +2131,25 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                     .map_err(|err| rpc_internal_error("wrapper.b58_encode", err, &self.logger))?;
                 dst.set_address_code(encoded);
                 dst.set_token_id(src.token_id);
+
+                // The memo is only meaningful to the recipient, so it is only decoded for
+                // received TxOuts. Those are in the ledger, since we found them there.
+                if src.direction == ProcessedTxOutDirection::Received as i32 {
+                    let tx_out = self
+                        .ledger_db
+                        .get_tx_out_index_by_public_key(&src.public_key)
+                        .and_then(|index| self.ledger_db.get_tx_out_by_index(index))
+                        .map_err(|err| {
+                            rpc_internal_error("ledger_db.get_tx_out", err, &self.logger)
+                        })?;
+                    let memo = self
+                        .memo_decoder
+                        .decode(&account_key, src.subaddress_index, &tx_out)
+                        .map_err(|err| {
+                            rpc_internal_error("memo_decoder.decode", err, &self.logger)
+                        })?;
+                    dst.set_memo(memo);
+                }
                 Ok(dst)
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -1833,6 +2245,14 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         let sender_monitor_id = MonitorId::try_from(&request.sender_monitor_id)
             .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
 
+        // Get monitor data for this monitor.
+        let sender_monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&sender_monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
         // Get all utxos for this monitor id.
         let mut utxos = self
             .mobilecoind_db
@@ -1866,6 +2286,11 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             request.sender_subaddress
         };
 
+        // Create the requested memo builder, if any.
+        let opt_memo_builder =
+            memo_builder_from_config(request.get_memo_builder(), &sender_monitor_data.account_key)
+                .map_err(|err| rpc_invalid_arg_error("memo_builder", err, &self.logger))?;
+
         // Attempt to construct a transaction.
         let tx_proposal = self
             .transactions_manager
@@ -1878,7 +2303,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 &self.get_last_block_infos(),
                 request.fee,
                 request.tombstone,
-                None,
+                opt_memo_builder,
             )
             .map_err(|err| {
                 rpc_internal_error("transactions_manager.build_transaction", err, &self.logger)
@@ -1932,6 +2357,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         send_payment_request.set_override_change_subaddress(request.override_change_subaddress);
         send_payment_request.set_change_subaddress(request.change_subaddress);
         send_payment_request.set_token_id(request.token_id);
+        send_payment_request.set_memo_builder(request.get_memo_builder().clone());
 
         self.send_payment_impl(send_payment_request)
     }
@@ -2084,6 +2510,13 @@ build_api! {
     generate_burn_redemption_tx GenerateBurnRedemptionTxRequest GenerateBurnRedemptionTxResponse generate_burn_redemption_tx_impl,
     submit_tx SubmitTxRequest SubmitTxResponse submit_tx_impl,
 
+    // Gift codes
+    generate_gift_code_funding_tx GenerateGiftCodeFundingTxRequest GenerateGiftCodeFundingTxResponse generate_gift_code_funding_tx_impl,
+    create_gift_code CreateGiftCodeRequest CreateGiftCodeResponse create_gift_code_impl,
+    generate_gift_code_claim_tx GenerateGiftCodeClaimTxRequest GenerateGiftCodeClaimTxResponse generate_gift_code_claim_tx_impl,
+    generate_gift_code_cancellation_tx GenerateGiftCodeCancellationTxRequest GenerateGiftCodeCancellationTxResponse generate_gift_code_cancellation_tx_impl,
+    get_gift_code_status GetGiftCodeStatusRequest GetGiftCodeStatusResponse get_gift_code_status_impl,
+
     // Databases
     get_ledger_info Empty GetLedgerInfoResponse get_ledger_info_impl,
     get_block_info GetBlockInfoRequest GetBlockInfoResponse get_block_info_impl,
@@ -2433,6 +2866,12 @@ mod test {
             })
             .collect();
 
+        // The test ledger doesn't write memos, so they all decode as unused.
+        for proto_utxo in response.output_list.iter() {
+            assert!(proto_utxo.has_memo());
+            assert_eq!(proto_utxo.get_memo().get_memo_type(), &[0, 0]);
+        }
+
         // Verify the data we got matches what we expected. This assumes knowledge about
         // how the test ledger is constructed by the test utils.
         let num_blocks = ledger_db.num_blocks().unwrap();
@@ -3205,6 +3644,7 @@ mod test {
                 tx_out.get_direction(),
                 api::ProcessedTxOutDirection::Received,
             );
+            assert_eq!(tx_out.get_memo().get_memo_type(), &[0, 0]);
 
             // test address code
             let mut request = api::GetPublicAddressRequest::new();
@@ -4059,6 +4499,116 @@ mod test {
         }
     }
 
+    #[test_with_logger]
+    fn test_generate_tx_with_rth_memos(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([23u8; 32]);
+
+        let sender = AccountKey::random(&mut rng);
+        let data = MonitorData::new(
+            sender.clone(),
+            0,  // first_subaddress
+            20, // num_subaddresses
+            0,  // first_block
+            "", // name
+        )
+        .unwrap();
+
+        // 1 known recipient, 3 random recipients and no monitors.
+        let (ledger_db, mobilecoind_db, client, _server, _server_conn_manager) =
+            get_testing_environment(
+                BLOCK_VERSION,
+                3,
+                &[sender.default_subaddress()],
+                &[],
+                logger.clone(),
+                &mut rng,
+            );
+
+        // Insert into database.
+        let monitor_id = mobilecoind_db.add_monitor(&data).unwrap();
+
+        // Allow the new monitor to process the ledger.
+        wait_for_monitors(&mobilecoind_db, &ledger_db, &logger);
+
+        // Get list of unspent tx outs
+        let utxos = mobilecoind_db
+            .get_utxos_for_subaddress(&monitor_id, 0)
+            .unwrap()
+            .iter()
+            .map(Into::into)
+            .collect::<RepeatedField<_>>();
+        assert!(!utxos.is_empty());
+
+        let receiver = AccountKey::random(&mut rng);
+        let outlay = Outlay {
+            value: 123,
+            receiver: receiver.default_subaddress(),
+        };
+
+        // Prepare a request with sender, payment request id and destination memos.
+        let mut request = api::GenerateTxRequest::new();
+        request.set_sender_monitor_id(monitor_id.to_vec());
+        request.set_change_subaddress(0);
+        request.set_input_list(utxos);
+        request.set_outlay_list(RepeatedField::from_vec(vec![(&outlay).into()]));
+        request.set_fee(Mob::MINIMUM_FEE);
+        {
+            let rth = request.mut_memo_builder().mut_rth();
+            rth.set_enable_sender_memo(true);
+            rth.set_include_payment_request_id(true);
+            rth.set_payment_request_id(42);
+            rth.set_enable_destination_memo(true);
+        }
+
+        let response = client.generate_tx(&request).unwrap();
+        let tx = Tx::try_from(response.get_tx_proposal().get_tx()).unwrap();
+
+        // Two outputs - change and outlay
+        assert_eq!(tx.prefix.outputs.len(), 2);
+
+        // The receiver can attribute the outlay to the sender.
+        let receiver_tx_out = tx
+            .prefix
+            .outputs
+            .iter()
+            .find(|tx_out| tx_out.view_key_match(receiver.view_private_key()).is_ok())
+            .expect("Didn't find receiver's output");
+        let decoded = MemoDecoder::new(vec![sender.default_subaddress()])
+            .decode(&receiver, DEFAULT_SUBADDRESS_INDEX, receiver_tx_out)
+            .unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x01, 0x01]);
+        let memo = decoded.get_authenticated_sender();
+        assert!(memo.get_validated());
+        assert_eq!(memo.get_payment_request_id(), 42);
+
+        // The change output records the destination.
+        let change_tx_out = tx
+            .prefix
+            .outputs
+            .iter()
+            .find(|tx_out| tx_out.view_key_match(sender.view_private_key()).is_ok())
+            .expect("Didn't find sender's change output");
+        let decoded = MemoDecoder::default()
+            .decode(&sender, 0, change_tx_out)
+            .unwrap();
+        assert_eq!(decoded.get_memo_type(), &[0x02, 0x00]);
+        let memo = decoded.get_destination();
+        assert_eq!(
+            memo.get_recipient_hash(),
+            ShortAddressHash::from(&receiver.default_subaddress()).as_ref()
+        );
+        assert_eq!(memo.get_num_recipients(), 1);
+        assert_eq!(memo.get_fee(), Mob::MINIMUM_FEE);
+        assert_eq!(memo.get_total_outlay(), 123 + Mob::MINIMUM_FEE);
+
+        // An invalid memo builder configuration results in an error.
+        request
+            .mut_memo_builder()
+            .mut_invoice_settlement()
+            .set_invoice_id(vec![1u8; 3]);
+        assert!(client.generate_tx(&request).is_err());
+    }
+
     #[test_with_logger]
     fn test_get_block_index_by_tx_pub_key(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([23u8; 32]);
@@ -4192,6 +4742,180 @@ mod test {
         }
     }
 
+    #[test_with_logger]
+    fn test_gift_code_lifecycle(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([24u8; 32]);
+
+        let sender = AccountKey::random(&mut rng);
+        let receiver = AccountKey::random(&mut rng);
+
+        // 1 known recipient, 3 random recipients and no monitors.
+        let (mut ledger_db, mobilecoind_db, client, _server, _server_conn_manager) =
+            get_testing_environment(
+                BLOCK_VERSION,
+                3,
+                &[sender.default_subaddress()],
+                &[],
+                logger.clone(),
+                &mut rng,
+            );
+
+        // Insert into database.
+        let sender_monitor_id = mobilecoind_db
+            .add_monitor(&MonitorData::new(sender.clone(), 0, 20, 0, "").unwrap())
+            .unwrap();
+        let receiver_monitor_id = mobilecoind_db
+            .add_monitor(&MonitorData::new(receiver.clone(), 0, 20, 0, "").unwrap())
+            .unwrap();
+
+        // Allow the new monitors to process the ledger.
+        wait_for_monitors(&mobilecoind_db, &ledger_db, &logger);
+
+        // Appends a generated transaction to the ledger, returning its block index.
+        fn append_tx(ledger_db: &mut LedgerDB, tx_proposal: &api::TxProposal) -> u64 {
+            let tx = Tx::try_from(tx_proposal.get_tx()).unwrap();
+            let block_contents = BlockContents {
+                key_images: tx.key_images(),
+                outputs: tx.prefix.outputs,
+                ..Default::default()
+            };
+            let num_blocks = ledger_db.num_blocks().unwrap();
+            let parent = ledger_db.get_block(num_blocks - 1).unwrap();
+            let new_block = Block::new_with_parent(
+                BLOCK_VERSION,
+                &parent,
+                &Default::default(),
+                &block_contents,
+            );
+            ledger_db
+                .append_block(&new_block, &block_contents, None)
+                .unwrap();
+            num_blocks
+        }
+
+        // Fund two gift codes, each from a different utxo.
+        let utxos = mobilecoind_db
+            .get_utxos_for_subaddress(&sender_monitor_id, 0)
+            .unwrap();
+        assert!(utxos.len() >= 2);
+        let value = DEFAULT_PER_RECIPIENT_AMOUNT / 2;
+        let tx_out_public_keys = utxos[0..2]
+            .iter()
+            .map(|utxo| {
+                let mut request = api::GenerateGiftCodeFundingTxRequest::new();
+                request.set_sender_monitor_id(sender_monitor_id.to_vec());
+                request.set_change_subaddress(0);
+                request.set_input_list(RepeatedField::from_vec(vec![utxo.into()]));
+                request.set_value(value);
+                request.set_fee(Mob::MINIMUM_FEE);
+                request.set_note("happy birthday".to_string());
+
+                let response = client.generate_gift_code_funding_tx(&request).unwrap();
+
+                // Two outputs - change and the gift code
+                let tx = Tx::try_from(response.get_tx_proposal().get_tx()).unwrap();
+                assert_eq!(tx.prefix.outputs.len(), 2);
+
+                append_tx(&mut ledger_db, response.get_tx_proposal());
+                response.get_tx_out_public_key().clone()
+            })
+            .collect::<Vec<_>>();
+
+        // Create the b58 codes of the gift codes.
+        let b58_codes = tx_out_public_keys
+            .iter()
+            .map(|tx_out_public_key| {
+                let mut request = api::CreateGiftCodeRequest::new();
+                request.set_monitor_id(sender_monitor_id.to_vec());
+                request.set_tx_out_public_key(tx_out_public_key.clone());
+                let response = client.create_gift_code(&request).unwrap();
+                assert_eq!(response.get_value(), value);
+                assert_eq!(response.get_token_id(), *Mob::ID);
+
+                // The gift code can only be created by its funder.
+                request.set_monitor_id(receiver_monitor_id.to_vec());
+                assert!(client.create_gift_code(&request).is_err());
+
+                response.get_b58_code().to_string()
+            })
+            .collect::<Vec<_>>();
+
+        let get_status = |b58_code: &str| {
+            let mut request = api::GetGiftCodeStatusRequest::new();
+            request.set_b58_code(b58_code.to_string());
+            client.get_gift_code_status(&request).unwrap()
+        };
+
+        for b58_code in b58_codes.iter() {
+            let response = get_status(b58_code);
+            assert_eq!(response.get_status(), api::GiftCodeStatus::GiftCodeUnspent);
+            assert_eq!(response.get_value(), value);
+        }
+
+        // The receiver claims the first gift code.
+        let mut request = api::GenerateGiftCodeClaimTxRequest::new();
+        request.set_monitor_id(receiver_monitor_id.to_vec());
+        request.set_subaddress(0);
+        request.set_b58_code(b58_codes[0].clone());
+        request.set_fee(Mob::MINIMUM_FEE);
+        request.set_note("from sender".to_string());
+        let response = client.generate_gift_code_claim_tx(&request).unwrap();
+
+        let tx = Tx::try_from(response.get_tx_proposal().get_tx()).unwrap();
+        assert_eq!(tx.prefix.outputs.len(), 1);
+        let tx_out = &tx.prefix.outputs[0];
+        let (amount, _) = tx_out.view_key_match(receiver.view_private_key()).unwrap();
+        assert_eq!(amount.value, value - Mob::MINIMUM_FEE);
+        let shared_secret = get_tx_out_shared_secret(
+            receiver.view_private_key(),
+            &RistrettoPublic::try_from(&tx_out.public_key).unwrap(),
+        );
+        assert_matches!(
+            MemoType::try_from(&tx_out.decrypt_memo(&shared_secret)),
+            Ok(MemoType::GiftCodeSender(memo)) if memo.sender_note().unwrap() == "from sender"
+        );
+
+        let claim_block_index = append_tx(&mut ledger_db, response.get_tx_proposal());
+        wait_for_monitors(&mobilecoind_db, &ledger_db, &logger);
+
+        let response = get_status(&b58_codes[0]);
+        assert_eq!(response.get_status(), api::GiftCodeStatus::GiftCodeClaimed);
+        assert_eq!(response.get_spent_block_index(), claim_block_index);
+
+        // A claimed gift code cannot be claimed again, or cancelled.
+        assert!(client.generate_gift_code_claim_tx(&request).is_err());
+        let mut request = api::GenerateGiftCodeCancellationTxRequest::new();
+        request.set_monitor_id(sender_monitor_id.to_vec());
+        request.set_change_subaddress(0);
+        request.set_tx_out_public_key(tx_out_public_keys[0].clone());
+        request.set_fee(Mob::MINIMUM_FEE);
+        assert!(client.generate_gift_code_cancellation_tx(&request).is_err());
+
+        // The sender cancels the second gift code.
+        request.set_tx_out_public_key(tx_out_public_keys[1].clone());
+        let response = client.generate_gift_code_cancellation_tx(&request).unwrap();
+
+        let tx = Tx::try_from(response.get_tx_proposal().get_tx()).unwrap();
+        assert_eq!(tx.prefix.outputs.len(), 1);
+        let tx_out = &tx.prefix.outputs[0];
+        let (amount, _) = tx_out.view_key_match(sender.view_private_key()).unwrap();
+        assert_eq!(amount.value, value - Mob::MINIMUM_FEE);
+
+        let cancel_block_index = append_tx(&mut ledger_db, response.get_tx_proposal());
+        wait_for_monitors(&mobilecoind_db, &ledger_db, &logger);
+
+        let response = get_status(&b58_codes[1]);
+        assert_eq!(
+            response.get_status(),
+            api::GiftCodeStatus::GiftCodeCancelled
+        );
+        assert_eq!(response.get_spent_block_index(), cancel_block_index);
+
+        // The claimed gift code is still reported as claimed.
+        let response = get_status(&b58_codes[0]);
+        assert_eq!(response.get_status(), api::GiftCodeStatus::GiftCodeClaimed);
+    }
+
     #[test_with_logger]
     fn test_generate_optimization_tx(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([23u8; 32]);
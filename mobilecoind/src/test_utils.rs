@@ -4,6 +4,7 @@
 
 use crate::{
     database::Database,
+    memo_decoder::MemoDecoder,
     monitor_store::{MonitorData, MonitorId},
     payments::TransactionsManager,
     service::Service,
@@ -286,6 +287,7 @@ pub fn setup_server<FPR: FogPubkeyResolver + Default + Send + Sync + 'static>(
         network_state,
         uri,
         None,
+        MemoDecoder::default(),
         logger,
     );
 
@@ -1,5 +1,5 @@
 use bip39::{Language, Mnemonic};
-use mc_account_keys::{AccountKey, RootIdentity};
+use mc_account_keys::{AccountKey, RootIdentity, ViewAccountKey};
 use mc_account_keys_slip10::Slip10KeyGenerator;
 use mc_test_vectors_definitions::account_keys::*;
 use mc_util_test_vector::write_jsonl;
@@ -196,4 +196,24 @@ fn main() {
             .collect::<Vec<_>>()
     })
     .expect("Unable to write test vectors");
+
+    write_jsonl("../vectors", || {
+        (0..10)
+            .map(|n| {
+                let account_key = AccountKey::from(&RootIdentity::from(&[n; 32]));
+                let view_account_key = ViewAccountKey::from(&account_key);
+                // Use indices spread over the whole range, not just small ones
+                let subaddress_index = (n as u64).wrapping_mul(0x1d33_7a45_c0de_0001);
+                let subaddress = account_key.subaddress(subaddress_index);
+                SubaddrKeysFromViewAcctKeys {
+                    view_private_key: view_account_key.view_private_key().to_bytes(),
+                    spend_public_key: view_account_key.spend_public_key().to_bytes(),
+                    subaddress_index,
+                    subaddress_view_public_key: subaddress.view_public_key().to_bytes(),
+                    subaddress_spend_public_key: subaddress.spend_public_key().to_bytes(),
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .expect("Unable to write test vectors");
 }
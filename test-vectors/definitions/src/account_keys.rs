@@ -57,3 +57,17 @@ impl TestVector for SubaddrKeysFromAcctPrivKeys {
     const FILE_NAME: &'static str = "subaddr_keys_from_acct_priv_keys";
     const MODULE_SUBDIR: &'static str = "account_keys";
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubaddrKeysFromViewAcctKeys {
+    pub view_private_key: [u8; 32],
+    pub spend_public_key: [u8; 32],
+    pub subaddress_index: u64,
+    pub subaddress_view_public_key: [u8; 32],
+    pub subaddress_spend_public_key: [u8; 32],
+}
+
+impl TestVector for SubaddrKeysFromViewAcctKeys {
+    const FILE_NAME: &'static str = "subaddr_keys_from_view_acct_keys";
+    const MODULE_SUBDIR: &'static str = "account_keys";
+}
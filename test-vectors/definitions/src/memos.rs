@@ -152,3 +152,117 @@ impl TestVector for IncorrectEncryptedSenderWithPaymentRequestIdMemoData {
     const FILE_NAME: &'static str = "incorrect_encrypted_sender_with_payment_request_id_memos";
     const MODULE_SUBDIR: &'static str = "memos";
 }
+
+/// Contains the "correct" data associated with an encrypted refund memo.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorrectEncryptedRefundMemoData {
+    /// The refund sender's public address proto bytes encoded as hex. This
+    /// user wrote the refund memo.
+    pub sender_public_address_hex_proto_bytes: String,
+
+    /// The refund recipient's view public key raw bytes encoded as hex.
+    /// This user received the transaction with the refund memo.
+    pub recipient_view_public_key_hex_raw_bytes: String,
+
+    /// The public key of the TxOut the refund memo is attached to, raw bytes
+    /// encoded as hex.
+    pub tx_out_public_key_hex_raw_bytes: String,
+
+    /// The public key of the refunded TxOut included in the memo, raw bytes
+    /// encoded as hex.
+    pub refunded_tx_out_public_key_hex_raw_bytes: String,
+
+    /// The encrypted refund memo raw bytes encoded as hex.
+    pub encrypted_refund_memo_hex_raw_bytes: String,
+}
+
+impl TestVector for CorrectEncryptedRefundMemoData {
+    const FILE_NAME: &'static str = "correct_encrypted_refund_memos";
+    const MODULE_SUBDIR: &'static str = "memos";
+}
+
+/// Contains data associated with an encrypted refund memo that has incorrect
+/// sender and recipient data.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncorrectEncryptedRefundMemoData {
+    /// A public address's proto bytes encoded as hex. This address did not
+    /// write the refund memo.
+    pub incorrect_sender_public_address_hex_proto_bytes: String,
+
+    /// A view public key's raw bytes encoded as hex. This key is not the key
+    /// that received the transaction with the refund memo.
+    pub incorrect_recipient_view_public_key_hex_raw_bytes: String,
+
+    /// The public key of the TxOut the refund memo is attached to, raw bytes
+    /// encoded as hex.
+    pub tx_out_public_key_hex_raw_bytes: String,
+
+    /// The public key of the refunded TxOut included in the memo, raw bytes
+    /// encoded as hex.
+    pub refunded_tx_out_public_key_hex_raw_bytes: String,
+
+    /// The encrypted refund memo raw bytes encoded as hex.
+    pub encrypted_refund_memo_hex_raw_bytes: String,
+}
+
+impl TestVector for IncorrectEncryptedRefundMemoData {
+    const FILE_NAME: &'static str = "incorrect_encrypted_refund_memos";
+    const MODULE_SUBDIR: &'static str = "memos";
+}
+
+/// Contains the "correct" data associated with an encrypted invoice settlement
+/// memo.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorrectEncryptedInvoiceSettlementMemoData {
+    /// The transaction sender's public address proto bytes encoded as hex. This
+    /// user wrote the invoice settlement memo.
+    pub sender_public_address_hex_proto_bytes: String,
+
+    /// The transaction recipient's view public key raw bytes encoded as hex.
+    /// This user issued the invoice, and received the transaction with the
+    /// invoice settlement memo.
+    pub recipient_view_public_key_hex_raw_bytes: String,
+
+    /// The public key of the TxOut the invoice settlement memo is attached to,
+    /// raw bytes encoded as hex.
+    pub tx_out_public_key_hex_raw_bytes: String,
+
+    /// The 16 byte invoice ID included in the memo, encoded as hex.
+    pub invoice_id_hex_raw_bytes: String,
+
+    /// The encrypted invoice settlement memo raw bytes encoded as hex.
+    pub encrypted_invoice_settlement_memo_hex_raw_bytes: String,
+}
+
+impl TestVector for CorrectEncryptedInvoiceSettlementMemoData {
+    const FILE_NAME: &'static str = "correct_encrypted_invoice_settlement_memos";
+    const MODULE_SUBDIR: &'static str = "memos";
+}
+
+/// Contains data associated with an encrypted invoice settlement memo that has
+/// incorrect sender and recipient data.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncorrectEncryptedInvoiceSettlementMemoData {
+    /// A public address's proto bytes encoded as hex. This address did not
+    /// write the invoice settlement memo.
+    pub incorrect_sender_public_address_hex_proto_bytes: String,
+
+    /// A view public key's raw bytes encoded as hex. This key is not the key
+    /// that received the transaction with the invoice settlement memo.
+    pub incorrect_recipient_view_public_key_hex_raw_bytes: String,
+
+    /// The public key of the TxOut the invoice settlement memo is attached to,
+    /// raw bytes encoded as hex.
+    pub tx_out_public_key_hex_raw_bytes: String,
+
+    /// The 16 byte invoice ID included in the memo, encoded as hex.
+    pub invoice_id_hex_raw_bytes: String,
+
+    /// The encrypted invoice settlement memo raw bytes encoded as hex.
+    pub encrypted_invoice_settlement_memo_hex_raw_bytes: String,
+}
+
+impl TestVector for IncorrectEncryptedInvoiceSettlementMemoData {
+    const FILE_NAME: &'static str = "incorrect_encrypted_invoice_settlement_memos";
+    const MODULE_SUBDIR: &'static str = "memos";
+}
@@ -1,18 +1,20 @@
 use mc_account_keys::{AccountKey, ShortAddressHash};
 use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
 use mc_test_vectors_definitions::memos::{
-    CorrectEncryptedDestinationMemoData, CorrectEncryptedSenderMemoData,
-    CorrectEncryptedSenderWithPaymentRequestIdMemoData, IncorrectEncryptedSenderMemoData,
-    IncorrectEncryptedSenderWithPaymentRequestIdMemoData,
+    CorrectEncryptedDestinationMemoData, CorrectEncryptedInvoiceSettlementMemoData,
+    CorrectEncryptedRefundMemoData, CorrectEncryptedSenderMemoData,
+    CorrectEncryptedSenderWithPaymentRequestIdMemoData,
+    IncorrectEncryptedInvoiceSettlementMemoData, IncorrectEncryptedRefundMemoData,
+    IncorrectEncryptedSenderMemoData, IncorrectEncryptedSenderWithPaymentRequestIdMemoData,
 };
 use mc_transaction_std::{
-    AuthenticatedSenderMemo, AuthenticatedSenderWithPaymentRequestIdMemo, DestinationMemo,
-    SenderMemoCredential,
+    AuthenticatedInvoiceSettlementMemo, AuthenticatedRefundMemo, AuthenticatedSenderMemo,
+    AuthenticatedSenderWithPaymentRequestIdMemo, DestinationMemo, SenderMemoCredential,
 };
 use mc_util_from_random::FromRandom;
 use mc_util_test_vector::write_jsonl;
 
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 
 fn main() {
     write_correct_encrypted_sender_memos();
@@ -23,6 +25,12 @@ fn main() {
 
     write_correct_encrypted_sender_with_payment_request_id_memos();
     write_incorrect_encrypted_sender_with_payment_request_id_memos();
+
+    write_correct_encrypted_refund_memos();
+    write_incorrect_encrypted_refund_memos();
+
+    write_correct_encrypted_invoice_settlement_memos();
+    write_incorrect_encrypted_invoice_settlement_memos();
 }
 
 fn write_correct_encrypted_sender_memos() {
@@ -325,3 +333,210 @@ fn write_incorrect_encrypted_sender_with_payment_request_id_memos() {
     })
     .expect("Unable to write test vectors");
 }
+
+fn write_correct_encrypted_refund_memos() {
+    write_jsonl("../vectors", || {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let mut encrypted_refund_memos: Vec<CorrectEncryptedRefundMemoData> = Vec::new();
+        for _ in 0..10 {
+            let sender_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let sender_credential = SenderMemoCredential::from(&sender_account_key);
+            let sender_public_address = sender_account_key.default_subaddress();
+
+            let recipient_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let recipient_public_address = recipient_account_key.default_subaddress();
+
+            let tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+            let refunded_tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+
+            let encrypted_refund_memo = AuthenticatedRefundMemo::new(
+                &sender_credential,
+                recipient_public_address.view_public_key(),
+                &tx_public_key,
+                &refunded_tx_public_key,
+            );
+            let refund_memo_bytes: [u8; 64] = encrypted_refund_memo.into();
+
+            let encrypted_refund_memo_data = CorrectEncryptedRefundMemoData {
+                sender_public_address_hex_proto_bytes: hex::encode(mc_util_serial::encode(
+                    &sender_public_address,
+                )),
+                recipient_view_public_key_hex_raw_bytes: hex::encode(
+                    recipient_public_address.view_public_key().to_bytes(),
+                ),
+                tx_out_public_key_hex_raw_bytes: hex::encode(tx_public_key),
+                refunded_tx_out_public_key_hex_raw_bytes: hex::encode(refunded_tx_public_key),
+                encrypted_refund_memo_hex_raw_bytes: hex::encode(refund_memo_bytes),
+            };
+            encrypted_refund_memos.push(encrypted_refund_memo_data);
+        }
+
+        encrypted_refund_memos
+    })
+    .expect("Unable to write test vectors");
+}
+
+/// Writes refund memos but records the sender as the receiver, so that
+/// verifying the memo necessarily fails.
+fn write_incorrect_encrypted_refund_memos() {
+    write_jsonl("../vectors", || {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let mut encrypted_refund_memos: Vec<IncorrectEncryptedRefundMemoData> = Vec::new();
+        for _ in 0..10 {
+            let sender_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let sender_credential = SenderMemoCredential::from(&sender_account_key);
+            let sender_public_address = sender_account_key.default_subaddress();
+
+            let recipient_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let recipient_public_address = recipient_account_key.default_subaddress();
+
+            let tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+            let refunded_tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+
+            let encrypted_refund_memo = AuthenticatedRefundMemo::new(
+                &sender_credential,
+                recipient_public_address.view_public_key(),
+                &tx_public_key,
+                &refunded_tx_public_key,
+            );
+            let refund_memo_bytes: [u8; 64] = encrypted_refund_memo.into();
+
+            let encrypted_refund_memo_data = IncorrectEncryptedRefundMemoData {
+                incorrect_sender_public_address_hex_proto_bytes: hex::encode(
+                    mc_util_serial::encode(&recipient_public_address),
+                ),
+                incorrect_recipient_view_public_key_hex_raw_bytes: hex::encode(
+                    sender_public_address.view_public_key().to_bytes(),
+                ),
+                tx_out_public_key_hex_raw_bytes: hex::encode(tx_public_key),
+                refunded_tx_out_public_key_hex_raw_bytes: hex::encode(refunded_tx_public_key),
+                encrypted_refund_memo_hex_raw_bytes: hex::encode(refund_memo_bytes),
+            };
+            encrypted_refund_memos.push(encrypted_refund_memo_data);
+        }
+
+        encrypted_refund_memos
+    })
+    .expect("Unable to write test vectors");
+}
+
+fn write_correct_encrypted_invoice_settlement_memos() {
+    write_jsonl("../vectors", || {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let mut encrypted_invoice_settlement_memos: Vec<CorrectEncryptedInvoiceSettlementMemoData> =
+            Vec::new();
+        for _ in 0..10 {
+            let sender_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let sender_credential = SenderMemoCredential::from(&sender_account_key);
+            let sender_public_address = sender_account_key.default_subaddress();
+
+            let recipient_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let recipient_public_address = recipient_account_key.default_subaddress();
+
+            let tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+            let mut invoice_id = [0u8; 16];
+            rng.fill_bytes(&mut invoice_id);
+
+            let encrypted_invoice_settlement_memo = AuthenticatedInvoiceSettlementMemo::new(
+                &sender_credential,
+                recipient_public_address.view_public_key(),
+                &tx_public_key,
+                invoice_id,
+            );
+            let invoice_settlement_memo_bytes: [u8; 64] = encrypted_invoice_settlement_memo.into();
+
+            let encrypted_invoice_settlement_memo_data =
+                CorrectEncryptedInvoiceSettlementMemoData {
+                    sender_public_address_hex_proto_bytes: hex::encode(mc_util_serial::encode(
+                        &sender_public_address,
+                    )),
+                    recipient_view_public_key_hex_raw_bytes: hex::encode(
+                        recipient_public_address.view_public_key().to_bytes(),
+                    ),
+                    tx_out_public_key_hex_raw_bytes: hex::encode(tx_public_key),
+                    invoice_id_hex_raw_bytes: hex::encode(invoice_id),
+                    encrypted_invoice_settlement_memo_hex_raw_bytes: hex::encode(
+                        invoice_settlement_memo_bytes,
+                    ),
+                };
+            encrypted_invoice_settlement_memos.push(encrypted_invoice_settlement_memo_data);
+        }
+
+        encrypted_invoice_settlement_memos
+    })
+    .expect("Unable to write test vectors");
+}
+
+/// Writes invoice settlement memos but records the sender as the receiver, so
+/// that verifying the memo necessarily fails.
+fn write_incorrect_encrypted_invoice_settlement_memos() {
+    write_jsonl("../vectors", || {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let mut encrypted_invoice_settlement_memos: Vec<
+            IncorrectEncryptedInvoiceSettlementMemoData,
+        > = Vec::new();
+        for _ in 0..10 {
+            let sender_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let sender_credential = SenderMemoCredential::from(&sender_account_key);
+            let sender_public_address = sender_account_key.default_subaddress();
+
+            let recipient_account_key = AccountKey::new(
+                &RistrettoPrivate::from_random(&mut rng),
+                &RistrettoPrivate::from_random(&mut rng),
+            );
+            let recipient_public_address = recipient_account_key.default_subaddress();
+
+            let tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+            let mut invoice_id = [0u8; 16];
+            rng.fill_bytes(&mut invoice_id);
+
+            let encrypted_invoice_settlement_memo = AuthenticatedInvoiceSettlementMemo::new(
+                &sender_credential,
+                recipient_public_address.view_public_key(),
+                &tx_public_key,
+                invoice_id,
+            );
+            let invoice_settlement_memo_bytes: [u8; 64] = encrypted_invoice_settlement_memo.into();
+
+            let encrypted_invoice_settlement_memo_data =
+                IncorrectEncryptedInvoiceSettlementMemoData {
+                    incorrect_sender_public_address_hex_proto_bytes: hex::encode(
+                        mc_util_serial::encode(&recipient_public_address),
+                    ),
+                    incorrect_recipient_view_public_key_hex_raw_bytes: hex::encode(
+                        sender_public_address.view_public_key().to_bytes(),
+                    ),
+                    tx_out_public_key_hex_raw_bytes: hex::encode(tx_public_key),
+                    invoice_id_hex_raw_bytes: hex::encode(invoice_id),
+                    encrypted_invoice_settlement_memo_hex_raw_bytes: hex::encode(
+                        invoice_settlement_memo_bytes,
+                    ),
+                };
+            encrypted_invoice_settlement_memos.push(encrypted_invoice_settlement_memo_data);
+        }
+
+        encrypted_invoice_settlement_memos
+    })
+    .expect("Unable to write test vectors");
+}
@@ -46,3 +46,6 @@ pub const MINT_CONFIG_TX_PREFIX_DOMAIN_TAG: &str = "mc_mint_config_tx_prefix";
 
 /// Domain separator for hashing MintTxPrefixs
 pub const MINT_TX_PREFIX_DOMAIN_TAG: &str = "mc_mint_tx_prefix";
+
+/// Domain separator for hashing and signing InvoicePrefixes
+pub const INVOICE_PREFIX_DOMAIN_TAG: &str = "mc_invoice_prefix";
@@ -0,0 +1,296 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Signed invoices, which tell a payer where to send a payment, which amounts
+//! are acceptable, and until when.
+//!
+//! An invoice is signed by the payee with the view private key of the
+//! subaddress it asks to be paid at, so that a wallet can check that it was
+//! not tampered with before paying it.
+//!
+//! The signature only proves that the invoice was made by whoever owns the
+//! address it names: anyone can sign an invoice asking to be paid at their own
+//! address. Wallets must also check that address against one they trust for
+//! the payee, e.g. with [Invoice::check_payment_to].
+
+use crate::{domain_separators::INVOICE_PREFIX_DOMAIN_TAG, Amount};
+use alloc::{string::String, vec::Vec};
+use displaydoc::Display;
+use mc_account_keys::{AccountKey, PublicAddress};
+use mc_crypto_digestible::{Digestible, MerlinTranscript};
+use mc_crypto_keys::RistrettoSignature;
+
+/// The signed contents of an invoice
+#[derive(Clone, Debug, Digestible, Eq, PartialEq)]
+pub struct InvoicePrefix {
+    /// The address to pay
+    pub public_address: PublicAddress,
+
+    /// The amounts the payee accepts. Paying any one of them settles the
+    /// invoice.
+    pub amounts: Vec<Amount>,
+
+    /// The block index at which the invoice expires, or 0 if it doesn't
+    pub expiry_block: u64,
+
+    /// The time at which the invoice expires, in seconds since the Unix epoch,
+    /// or 0 if it doesn't
+    pub expiry_timestamp: u64,
+
+    /// The payment request id which the payer should put in an
+    /// `AuthenticatedSenderWithPaymentRequestIdMemo`, so that the payee can
+    /// match the payment to the invoice
+    pub payment_request_id: u64,
+
+    /// Any additional text explaining the invoice
+    pub memo: String,
+
+    /// A url the payer's wallet may notify once the invoice is paid, or empty
+    pub callback_url: String,
+}
+
+impl InvoicePrefix {
+    /// Digestible-crate hash of `self` using Merlin
+    pub fn hash(&self) -> [u8; 32] {
+        self.digest32::<MerlinTranscript>(INVOICE_PREFIX_DOMAIN_TAG.as_bytes())
+    }
+}
+
+/// An invoice, signed by the payee
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    /// The invoice contents
+    pub prefix: InvoicePrefix,
+
+    /// A Schnorrkel signature over the prefix hash, made with the view private
+    /// key of the subaddress in the prefix
+    pub signature: Vec<u8>,
+}
+
+impl Invoice {
+    /// Sign an invoice asking to be paid at the given subaddress of an account.
+    ///
+    /// The `public_address` of the prefix is replaced with that subaddress.
+    pub fn sign(
+        mut prefix: InvoicePrefix,
+        account_key: &AccountKey,
+        subaddress_index: u64,
+    ) -> Self {
+        prefix.public_address = account_key.subaddress(subaddress_index);
+        let signature = account_key
+            .subaddress_view_private(subaddress_index)
+            .sign_schnorrkel(INVOICE_PREFIX_DOMAIN_TAG.as_bytes(), &prefix.hash());
+        let signature_bytes: &[u8] = signature.as_ref();
+        Self {
+            prefix,
+            signature: signature_bytes.to_vec(),
+        }
+    }
+
+    /// Check that the invoice was signed by the owner of its public address.
+    ///
+    /// This does not say who that owner is; use
+    /// [Invoice::verify_signature_from] to also check the address.
+    pub fn verify_signature(&self) -> Result<(), InvoiceError> {
+        let signature = RistrettoSignature::try_from(self.signature.as_slice())
+            .map_err(|_| InvoiceError::InvalidSignature)?;
+        self.prefix
+            .public_address
+            .view_public_key()
+            .verify_schnorrkel(
+                INVOICE_PREFIX_DOMAIN_TAG.as_bytes(),
+                &self.prefix.hash(),
+                &signature,
+            )
+            .map_err(|_| InvoiceError::InvalidSignature)
+    }
+
+    /// Check that the invoice asks to be paid at the expected payee address,
+    /// and was signed by its owner
+    pub fn verify_signature_from(&self, payee: &PublicAddress) -> Result<(), InvoiceError> {
+        if &self.prefix.public_address != payee {
+            return Err(InvoiceError::UnexpectedPayee);
+        }
+        self.verify_signature()
+    }
+
+    /// Whether the invoice has expired, given the index of the latest block in
+    /// the ledger and the current time in seconds since the Unix epoch
+    pub fn is_expired(&self, latest_block_index: u64, now: u64) -> bool {
+        (self.prefix.expiry_block != 0 && latest_block_index >= self.prefix.expiry_block)
+            || (self.prefix.expiry_timestamp != 0 && now >= self.prefix.expiry_timestamp)
+    }
+
+    /// Check that the invoice can be paid with an amount: it is correctly
+    /// signed, has not expired, and accepts the amount.
+    ///
+    /// This does not check who the invoice pays; see
+    /// [Invoice::check_payment_to].
+    pub fn check_payment(
+        &self,
+        amount: &Amount,
+        latest_block_index: u64,
+        now: u64,
+    ) -> Result<(), InvoiceError> {
+        self.verify_signature()?;
+        self.check_terms(amount, latest_block_index, now)
+    }
+
+    /// Check that the invoice can be paid with an amount, and pays the
+    /// expected payee: it names the payee's address, is correctly signed, has
+    /// not expired, and accepts the amount.
+    ///
+    /// Wallets should call this before paying an invoice, with an address for
+    /// the payee obtained independently of the invoice, and then set the
+    /// invoice's payment request id on the memo builder.
+    pub fn check_payment_to(
+        &self,
+        payee: &PublicAddress,
+        amount: &Amount,
+        latest_block_index: u64,
+        now: u64,
+    ) -> Result<(), InvoiceError> {
+        self.verify_signature_from(payee)?;
+        self.check_terms(amount, latest_block_index, now)
+    }
+
+    // Check the expiry and the accepted amounts
+    fn check_terms(
+        &self,
+        amount: &Amount,
+        latest_block_index: u64,
+        now: u64,
+    ) -> Result<(), InvoiceError> {
+        if self.is_expired(latest_block_index, now) {
+            return Err(InvoiceError::Expired);
+        }
+        if !self.prefix.amounts.contains(amount) {
+            return Err(InvoiceError::AmountNotAccepted(
+                amount.value,
+                *amount.token_id,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// An error which can occur when checking an invoice
+#[derive(Clone, Debug, Display, Eq, PartialEq)]
+pub enum InvoiceError {
+    /// The invoice signature is invalid
+    InvalidSignature,
+    /// The invoice does not pay the expected payee
+    UnexpectedPayee,
+    /// The invoice has expired
+    Expired,
+    /// The invoice does not accept {0} of token id {1}
+    AmountNotAccepted(u64, u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenId;
+    use mc_util_test_helper::get_seeded_rng;
+
+    fn test_prefix() -> InvoicePrefix {
+        InvoicePrefix {
+            public_address: Default::default(),
+            amounts: vec![
+                Amount::new(1000, TokenId::from(0)),
+                Amount::new(20, TokenId::from(1)),
+            ],
+            expiry_block: 500,
+            expiry_timestamp: 1_700_000_000,
+            payment_request_id: 42,
+            memo: "Order #42".into(),
+            callback_url: "https://example.com/paid/42".into(),
+        }
+    }
+
+    #[test]
+    fn signed_invoice_verifies() {
+        let mut rng = get_seeded_rng();
+        let account_key = AccountKey::random(&mut rng);
+        let invoice = Invoice::sign(test_prefix(), &account_key, 7);
+        assert_eq!(invoice.prefix.public_address, account_key.subaddress(7));
+        invoice.verify_signature().unwrap();
+
+        invoice
+            .check_payment(&Amount::new(20, TokenId::from(1)), 499, 1_600_000_000)
+            .unwrap();
+        assert_eq!(
+            invoice.check_payment(&Amount::new(20, TokenId::from(0)), 499, 1_600_000_000),
+            Err(InvoiceError::AmountNotAccepted(20, 0))
+        );
+        assert_eq!(
+            invoice.check_payment(&Amount::new(1000, TokenId::from(0)), 500, 1_600_000_000),
+            Err(InvoiceError::Expired)
+        );
+        assert_eq!(
+            invoice.check_payment(&Amount::new(1000, TokenId::from(0)), 10, 1_700_000_000),
+            Err(InvoiceError::Expired)
+        );
+    }
+
+    #[test]
+    fn tampered_invoice_does_not_verify() {
+        let mut rng = get_seeded_rng();
+        let account_key = AccountKey::random(&mut rng);
+        let invoice = Invoice::sign(test_prefix(), &account_key, 0);
+
+        let mut tampered = invoice.clone();
+        tampered.prefix.amounts[0].value += 1;
+        assert_eq!(
+            tampered.verify_signature(),
+            Err(InvoiceError::InvalidSignature)
+        );
+
+        // Redirecting the payment to another address is detected
+        let mut tampered = invoice.clone();
+        tampered.prefix.public_address = AccountKey::random(&mut rng).default_subaddress();
+        assert_eq!(
+            tampered.verify_signature(),
+            Err(InvoiceError::InvalidSignature)
+        );
+
+        let mut tampered = invoice;
+        tampered.signature.truncate(10);
+        assert_eq!(
+            tampered.verify_signature(),
+            Err(InvoiceError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn invoice_from_another_payee_is_refused() {
+        let mut rng = get_seeded_rng();
+        let payee = AccountKey::random(&mut rng);
+        let invoice = Invoice::sign(test_prefix(), &payee, 3);
+        let amount = Amount::new(1000, TokenId::from(0));
+
+        invoice.verify_signature_from(&payee.subaddress(3)).unwrap();
+        invoice
+            .check_payment_to(&payee.subaddress(3), &amount, 10, 1_600_000_000)
+            .unwrap();
+
+        // An attacker's invoice for their own address is correctly signed, but
+        // doesn't pay the payee
+        let attacker = AccountKey::random(&mut rng);
+        let forged = Invoice::sign(test_prefix(), &attacker, 3);
+        forged.verify_signature().unwrap();
+        assert_eq!(
+            forged.verify_signature_from(&payee.subaddress(3)),
+            Err(InvoiceError::UnexpectedPayee)
+        );
+        assert_eq!(
+            forged.check_payment_to(&payee.subaddress(3), &amount, 10, 1_600_000_000),
+            Err(InvoiceError::UnexpectedPayee)
+        );
+
+        // Nor does the payee's invoice for a different subaddress
+        assert_eq!(
+            invoice.verify_signature_from(&payee.subaddress(4)),
+            Err(InvoiceError::UnexpectedPayee)
+        );
+    }
+}
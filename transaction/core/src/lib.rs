@@ -18,8 +18,10 @@ extern crate lazy_static;
 mod amount;
 mod domain_separators;
 mod input_rules;
+mod invoice;
 mod memo;
 mod signed_contingent_input;
+mod subaddress_scanner;
 mod token;
 mod tx_error;
 mod tx_out_gift_code;
@@ -39,10 +41,12 @@ pub mod proptest_fixtures;
 
 pub use amount::{AmountError, MaskedAmount};
 pub use input_rules::{InputRuleError, InputRules};
+pub use invoice::{Invoice, InvoiceError, InvoicePrefix};
 pub use memo::{EncryptedMemo, MemoError, MemoPayload};
 pub use signed_contingent_input::{
     SignedContingentInput, SignedContingentInputError, UnmaskedAmount,
 };
+pub use subaddress_scanner::{OwnedTxOutInfo, SubaddressScanner, DEFAULT_GAP_LIMIT};
 pub use token::{tokens, Token};
 pub use tx::MemoContext;
 pub use tx_error::{NewMemoError, NewTxError, ViewKeyMatchError};
@@ -82,8 +86,7 @@ pub fn get_tx_out_shared_secret(
 /// TxOut
 ///
 /// This is not the most efficient way to check when you have many subaddresses,
-/// for that you should create a table and use
-/// recover_public_subaddress_spend_key directly.
+/// for that you should use a [SubaddressScanner].
 ///
 /// However some clients are only using one or two subaddresses.
 /// Validating that a TxOut is owned by the change subaddress is a frequently
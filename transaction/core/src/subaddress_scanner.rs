@@ -0,0 +1,297 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Recognizing TxOuts sent to any subaddress of an account, without knowing
+//! the subaddress index in advance.
+//!
+//! The spend public key `D_i` of the subaddress a TxOut was sent to can be
+//! recovered with the view private key, so ownership can be checked with one
+//! lookup in a table of `D_i -> i`. The table covers every subaddress up to
+//! `gap_limit` past the highest one seen so far, and grows whenever a TxOut
+//! sent to one of the last `gap_limit` subaddresses is found. This is the same
+//! idea as the BIP-44 gap limit: a wallet which hands out subaddresses in order
+//! finds all of its TxOuts as long as it never leaves a gap of more than
+//! `gap_limit` unused subaddresses.
+
+use crate::{onetime_keys::recover_public_subaddress_spend_key, tx::TxOut, ViewKeyMatchError};
+use core::convert::TryFrom;
+use mc_account_keys::{ViewAccountKey, CHANGE_SUBADDRESS_INDEX, GIFT_CODE_SUBADDRESS_INDEX};
+use mc_common::HashMap;
+use mc_crypto_keys::RistrettoPublic;
+use mc_transaction_types::Amount;
+
+/// The default number of unused subaddresses to look ahead by
+pub const DEFAULT_GAP_LIMIT: u64 = 1000;
+
+/// A TxOut which was found to belong to the account
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnedTxOutInfo {
+    /// The subaddress the TxOut was sent to
+    pub subaddress_index: u64,
+
+    /// The unmasked amount of the TxOut
+    pub amount: Amount,
+
+    /// The TxOut shared secret
+    pub shared_secret: RistrettoPublic,
+}
+
+/// Finds which subaddress of an account, if any, a TxOut was sent to, looking
+/// ahead of the highest subaddress seen so far.
+///
+/// The change and gift code subaddresses are always checked.
+#[derive(Clone)]
+pub struct SubaddressScanner {
+    view_account_key: ViewAccountKey,
+    /// Subaddress spend public keys `D_i`, to their index `i`
+    spend_public_keys: HashMap<RistrettoPublic, u64>,
+    /// The first subaddress index in the table
+    first_index: u64,
+    /// One past the last subaddress index in the table, not counting the
+    /// reserved subaddresses
+    end_index: u64,
+    /// The highest subaddress index a TxOut was found for
+    highest_used_index: Option<u64>,
+    /// The number of unused subaddresses to keep in the table
+    gap_limit: u64,
+}
+
+impl SubaddressScanner {
+    /// Create a scanner for the subaddresses of an account, starting at
+    /// `first_index` and looking ahead by `gap_limit` subaddresses.
+    pub fn new(view_account_key: ViewAccountKey, first_index: u64, gap_limit: u64) -> Self {
+        let mut scanner = Self {
+            view_account_key,
+            spend_public_keys: Default::default(),
+            first_index,
+            end_index: first_index,
+            highest_used_index: None,
+            gap_limit,
+        };
+        for index in [CHANGE_SUBADDRESS_INDEX, GIFT_CODE_SUBADDRESS_INDEX] {
+            scanner.insert(index);
+        }
+        scanner.extend_to(first_index.saturating_add(gap_limit));
+        scanner
+    }
+
+    /// The view account key of the account
+    pub fn view_account_key(&self) -> &ViewAccountKey {
+        &self.view_account_key
+    }
+
+    /// The number of unused subaddresses kept in the table
+    pub fn gap_limit(&self) -> u64 {
+        self.gap_limit
+    }
+
+    /// The range of (non-reserved) subaddress indices currently checked
+    pub fn index_range(&self) -> core::ops::Range<u64> {
+        self.first_index..self.end_index
+    }
+
+    /// The highest subaddress index a TxOut has been found for, or marked as
+    /// used
+    pub fn highest_used_index(&self) -> Option<u64> {
+        self.highest_used_index
+    }
+
+    /// The subaddress index with this spend public key, if it is in the table
+    pub fn subaddress_index(&self, subaddress_spend_public_key: &RistrettoPublic) -> Option<u64> {
+        self.spend_public_keys
+            .get(subaddress_spend_public_key)
+            .copied()
+    }
+
+    /// Record that a subaddress is in use, e.g. because it was handed out or
+    /// because an earlier scan found a TxOut for it, growing the table so that
+    /// it covers `gap_limit` subaddresses past it.
+    ///
+    /// Subaddresses more than `gap_limit` past the end of the table are
+    /// refused, so that one call can't make the table arbitrarily large; mark
+    /// lower subaddresses used first to reach them. Returns false if the index
+    /// was refused, in which case nothing changes.
+    pub fn mark_used(&mut self, index: u64) -> bool {
+        if index < self.first_index
+            || index == CHANGE_SUBADDRESS_INDEX
+            || index == GIFT_CODE_SUBADDRESS_INDEX
+        {
+            return true;
+        }
+        if index >= self.end_index.saturating_add(self.gap_limit) {
+            return false;
+        }
+        if self
+            .highest_used_index
+            .map_or(true, |highest| index > highest)
+        {
+            self.highest_used_index = Some(index);
+        }
+        self.extend_to(index.saturating_add(1).saturating_add(self.gap_limit));
+        true
+    }
+
+    /// Check whether a TxOut belongs to one of the account's subaddresses.
+    ///
+    /// Returns the owning subaddress, the amount and the shared secret if it
+    /// does, and None if it doesn't. Finding a TxOut marks its subaddress as
+    /// used.
+    pub fn check_tx_out(
+        &mut self,
+        tx_out: &TxOut,
+    ) -> Result<Option<OwnedTxOutInfo>, ViewKeyMatchError> {
+        let subaddress_spend_public_key = recover_public_subaddress_spend_key(
+            self.view_account_key.view_private_key(),
+            &RistrettoPublic::try_from(&tx_out.target_key)?,
+            &RistrettoPublic::try_from(&tx_out.public_key)?,
+        );
+        let subaddress_index = match self.subaddress_index(&subaddress_spend_public_key) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+
+        let (amount, shared_secret) =
+            tx_out.view_key_match(self.view_account_key.view_private_key())?;
+        // Always accepted, since the index is already in the table
+        self.mark_used(subaddress_index);
+
+        Ok(Some(OwnedTxOutInfo {
+            subaddress_index,
+            amount,
+            shared_secret,
+        }))
+    }
+
+    // Add subaddresses to the table until it ends at `end_index`
+    fn extend_to(&mut self, end_index: u64) {
+        // Never run into the reserved subaddresses
+        let end_index = end_index.min(GIFT_CODE_SUBADDRESS_INDEX);
+        while self.end_index < end_index {
+            self.insert(self.end_index);
+            self.end_index += 1;
+        }
+    }
+
+    fn insert(&mut self, index: u64) {
+        self.spend_public_keys
+            .insert(self.view_account_key.subaddress_spend_public(index), index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encrypted_fog_hint::EncryptedFogHint, BlockVersion, TokenId};
+    use mc_account_keys::AccountKey;
+    use mc_crypto_keys::RistrettoPrivate;
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::{get_seeded_rng, RngType};
+
+    fn tx_out_to(account: &AccountKey, index: u64, value: u64, rng: &mut RngType) -> TxOut {
+        TxOut::new(
+            BlockVersion::MAX,
+            Amount::new(value, TokenId::from(1)),
+            &account.subaddress(index),
+            &RistrettoPrivate::from_random(rng),
+            EncryptedFogHint::fake_onetime_hint(rng),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_tx_outs_within_the_lookahead() {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let mut scanner = SubaddressScanner::new(ViewAccountKey::from(&account), 0, 10);
+        assert_eq!(scanner.index_range(), 0..10);
+
+        let tx_out = tx_out_to(&account, 7, 100, &mut rng);
+        let info = scanner.check_tx_out(&tx_out).unwrap().unwrap();
+        assert_eq!(info.subaddress_index, 7);
+        assert_eq!(info.amount, Amount::new(100, TokenId::from(1)));
+        assert_eq!(
+            info.shared_secret,
+            tx_out.view_key_match(account.view_private_key()).unwrap().1
+        );
+
+        // The table grew to cover 10 subaddresses past 7
+        assert_eq!(scanner.highest_used_index(), Some(7));
+        assert_eq!(scanner.index_range(), 0..18);
+
+        // So subaddress 17 is found now, and that grows the table again
+        let tx_out = tx_out_to(&account, 17, 5, &mut rng);
+        let info = scanner.check_tx_out(&tx_out).unwrap().unwrap();
+        assert_eq!(info.subaddress_index, 17);
+        assert_eq!(scanner.index_range(), 0..28);
+    }
+
+    #[test]
+    fn does_not_find_tx_outs_past_the_lookahead_or_for_others() {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let mut scanner = SubaddressScanner::new(ViewAccountKey::from(&account), 0, 10);
+
+        let tx_out = tx_out_to(&account, 10, 100, &mut rng);
+        assert_eq!(scanner.check_tx_out(&tx_out).unwrap(), None);
+
+        let other = AccountKey::random(&mut rng);
+        let tx_out = tx_out_to(&other, 3, 100, &mut rng);
+        assert_eq!(scanner.check_tx_out(&tx_out).unwrap(), None);
+        assert_eq!(scanner.highest_used_index(), None);
+        assert_eq!(scanner.index_range(), 0..10);
+    }
+
+    #[test]
+    fn finds_reserved_subaddresses() {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let mut scanner = SubaddressScanner::new(ViewAccountKey::from(&account), 0, 10);
+
+        for index in [CHANGE_SUBADDRESS_INDEX, GIFT_CODE_SUBADDRESS_INDEX] {
+            let tx_out = tx_out_to(&account, index, 1, &mut rng);
+            let info = scanner.check_tx_out(&tx_out).unwrap().unwrap();
+            assert_eq!(info.subaddress_index, index);
+        }
+
+        // Reserved subaddresses don't grow the table
+        assert_eq!(scanner.highest_used_index(), None);
+        assert_eq!(scanner.index_range(), 0..10);
+    }
+
+    #[test]
+    fn mark_used_grows_the_table() {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let mut scanner = SubaddressScanner::new(ViewAccountKey::from(&account), 5, 4);
+        assert_eq!(scanner.index_range(), 5..9);
+
+        assert!(scanner.mark_used(12));
+        assert_eq!(scanner.index_range(), 5..17);
+        assert_eq!(
+            scanner.subaddress_index(account.subaddress(16).spend_public_key()),
+            Some(16)
+        );
+
+        // Marking a lower subaddress changes nothing
+        assert!(scanner.mark_used(8));
+        assert_eq!(scanner.highest_used_index(), Some(12));
+        assert_eq!(scanner.index_range(), 5..17);
+    }
+
+    #[test]
+    fn mark_used_refuses_indices_far_past_the_table() {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let mut scanner = SubaddressScanner::new(ViewAccountKey::from(&account), 0, 10);
+
+        for index in [20, 1_000_000, GIFT_CODE_SUBADDRESS_INDEX - 1, u64::MAX] {
+            assert!(!scanner.mark_used(index));
+        }
+        assert_eq!(scanner.highest_used_index(), None);
+        assert_eq!(scanner.index_range(), 0..10);
+
+        // The last index within reach is accepted
+        assert!(scanner.mark_used(19));
+        assert_eq!(scanner.highest_used_index(), Some(19));
+        assert_eq!(scanner.index_range(), 0..30);
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! The shared secret which clients present to the signing daemon
+
+use crate::Error;
+use rand_core::{CryptoRng, RngCore};
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// A 32-byte secret shared between the signing daemon and its clients.
+///
+/// The socket itself is only accessible to its owner, so the token guards
+/// against other processes running as the same user which were not given the
+/// token file.
+#[derive(Clone, Zeroize)]
+#[zeroize(drop)]
+pub struct AuthToken([u8; 32]);
+
+impl AuthToken {
+    /// Generate a new random token
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Parse a hex-encoded token
+    pub fn from_hex(src: &str) -> Result<Self, Error> {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(src.trim(), &mut bytes)
+            .map_err(|err| Error::InvalidAuthToken(err.to_string()))?;
+        Ok(Self(bytes))
+    }
+
+    /// Read a hex-encoded token from a file
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut contents = fs::read_to_string(path)?;
+        let result = Self::from_hex(&contents);
+        contents.zeroize();
+        result
+    }
+
+    /// Write the token to a new file, hex-encoded, which only the current user
+    /// can read.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(hex::encode(self.0).as_bytes())?;
+        Ok(())
+    }
+
+    /// The raw bytes of the token, as sent in sign requests
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Check, in constant time, whether the given bytes match this token
+    pub fn matches(&self, presented: &[u8]) -> bool {
+        presented.len() == self.0.len() && bool::from(self.0[..].ct_eq(presented))
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AuthToken(<redacted>)")
+    }
+}
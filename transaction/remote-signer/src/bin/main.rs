@@ -0,0 +1,116 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A daemon which holds an account's spend keys and signs input rings for
+//! RemoteRingSigner clients on a local unix socket.
+
+#![deny(missing_docs)]
+
+use clap::Parser;
+use mc_common::logger::{create_app_logger, log, o};
+use mc_crypto_rand::McRng;
+use mc_transaction_remote_signer::{
+    AuthToken, PolicySet, SigningDaemon, SigningPolicy, TokenLimitPolicy,
+};
+use mc_transaction_types::TokenId;
+use mc_util_parse::parse_duration_in_seconds;
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+/// Command line configuration for the signing daemon.
+#[derive(Clone, Debug, Parser)]
+#[clap(
+    name = "mc-ring-signer-daemon",
+    about = "Signs input rings for remote clients, keeping spend keys out of their process."
+)]
+pub struct Config {
+    /// The account key file to sign with.
+    #[clap(long, parse(from_os_str), env = "MC_KEYFILE")]
+    pub keyfile: PathBuf,
+
+    /// The path of the unix socket to listen on. Only the current user can
+    /// connect to it.
+    #[clap(long, parse(from_os_str), env = "MC_SOCKET")]
+    pub socket: PathBuf,
+
+    /// A file holding the hex-encoded auth token clients must present.
+    #[clap(long, parse(from_os_str), env = "MC_AUTH_TOKEN_FILE")]
+    pub auth_token_file: PathBuf,
+
+    /// Generate a new auth token and write it to the auth token file, which
+    /// must not exist yet.
+    #[clap(long, env = "MC_GENERATE_AUTH_TOKEN")]
+    pub generate_auth_token: bool,
+
+    /// Limit the total value of a token which may be spent per limit period,
+    /// as TOKEN_ID=AMOUNT. May be repeated. When any limit is given, tokens
+    /// without a limit may not be spent.
+    #[clap(
+        long = "limit",
+        parse(try_from_str = parse_limit),
+        env = "MC_LIMITS",
+        use_value_delimiter = true
+    )]
+    pub limits: Vec<(TokenId, u64)>,
+
+    /// The rolling period over which limits apply, in seconds.
+    #[clap(
+        long,
+        default_value = "86400",
+        parse(try_from_str = parse_duration_in_seconds),
+        env = "MC_LIMIT_PERIOD"
+    )]
+    pub limit_period: Duration,
+}
+
+fn parse_limit(src: &str) -> Result<(TokenId, u64), String> {
+    let (token_id, amount) = src
+        .split_once('=')
+        .ok_or_else(|| format!("expected TOKEN_ID=AMOUNT, got '{}'", src))?;
+    let token_id = token_id
+        .trim()
+        .parse::<u64>()
+        .map_err(|err| format!("invalid token id '{}': {}", token_id, err))?;
+    let amount = amount
+        .trim()
+        .parse::<u64>()
+        .map_err(|err| format!("invalid amount '{}': {}", amount, err))?;
+    Ok((TokenId::from(token_id), amount))
+}
+
+fn main() {
+    let config = Config::parse();
+    let (logger, _global_logger_guard) = create_app_logger(o!());
+    log::info!(logger, "Read configuration: {:?}", config);
+
+    let account_key =
+        mc_util_keyfile::read_keyfile(&config.keyfile).expect("Could not read keyfile");
+
+    if config.generate_auth_token {
+        AuthToken::random(&mut McRng::default())
+            .write_to_file(&config.auth_token_file)
+            .expect("Could not write auth token file");
+        log::info!(
+            logger,
+            "Wrote a new auth token to {:?}",
+            config.auth_token_file
+        );
+    }
+    let auth_token =
+        AuthToken::read_from_file(&config.auth_token_file).expect("Could not read auth token");
+
+    let mut policies: Vec<Box<dyn SigningPolicy>> = Vec::new();
+    if !config.limits.is_empty() {
+        let limits = config.limits.iter().cloned().collect::<HashMap<_, _>>();
+        policies.push(Box::new(TokenLimitPolicy::new(limits, config.limit_period)));
+    }
+
+    let listener = SigningDaemon::bind(&config.socket).expect("Could not bind socket");
+    log::info!(logger, "Listening on {:?}", config.socket);
+
+    let daemon = Arc::new(SigningDaemon::new(
+        &account_key,
+        auth_token,
+        Box::new(PolicySet(policies)),
+        logger.clone(),
+    ));
+    daemon.serve(listener).expect("Signing daemon failed");
+}
@@ -0,0 +1,263 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A RingSigner which asks a signing daemon to sign
+
+use crate::{
+    wire::{read_message, write_message},
+    AuthToken, SignRequest, SignResponse,
+};
+use mc_crypto_ring_signature::{CryptoRngCore, RingMLSAG, Scalar};
+use mc_crypto_ring_signature_signer::{
+    Error, NoKeysRingSigner, OneTimeKeyDeriveData, RingSigner, SignableInputRing,
+};
+use std::{
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// A RingSigner which holds no keys, and sends inputs owned by a subaddress to
+/// a signing daemon over a unix socket.
+///
+/// Inputs whose one-time private key is already known, e.g. gift codes, are
+/// signed locally, since the daemon's keys are not needed for them.
+#[derive(Clone, Debug)]
+pub struct RemoteRingSigner {
+    socket_path: PathBuf,
+    auth_token: AuthToken,
+    timeout: Duration,
+}
+
+impl RemoteRingSigner {
+    /// The default time to wait for the daemon to respond
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Create a signer for the daemon listening at the given socket
+    pub fn new(socket_path: impl AsRef<Path>, auth_token: AuthToken) -> Self {
+        Self {
+            socket_path: socket_path.as_ref().to_path_buf(),
+            auth_token,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set how long to wait for the daemon to respond
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn request(&self, request: &SignRequest) -> Result<SignResponse, crate::Error> {
+        let mut stream = UnixStream::connect(&self.socket_path)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        write_message(&mut stream, request)?;
+        read_message(&mut stream)?.ok_or_else(|| {
+            crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "signing daemon closed the connection",
+            ))
+        })
+    }
+}
+
+impl RingSigner for RemoteRingSigner {
+    fn sign(
+        &self,
+        message: &[u8],
+        signable_ring: &SignableInputRing,
+        output_blinding: Scalar,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<RingMLSAG, Error> {
+        if let OneTimeKeyDeriveData::OneTimeKey(_) =
+            signable_ring.input_secret.onetime_key_derive_data
+        {
+            return NoKeysRingSigner {}.sign(message, signable_ring, output_blinding, rng);
+        }
+
+        let request = SignRequest::new(
+            self.auth_token.as_bytes(),
+            message,
+            signable_ring,
+            output_blinding,
+        )?;
+        self.request(&request)
+            .map_err(|err| Error::ConnectionFailed(err.to_string()))?
+            .into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AllowAllPolicy, SigningDaemon, SigningPolicy, TokenLimitPolicy};
+    use mc_account_keys::AccountKey;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
+    use mc_crypto_ring_signature::{
+        generators,
+        onetime_keys::{create_tx_out_public_key, create_tx_out_target_key},
+        Commitment, CompressedCommitment, ReducedTxOut,
+    };
+    use mc_crypto_ring_signature_signer::InputSecret;
+    use mc_transaction_types::{Amount, TokenId};
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::{get_seeded_rng, RngCore, RngType};
+    use std::{collections::HashMap, sync::Arc, thread};
+    use tempdir::TempDir;
+
+    const SUBADDRESS_INDEX: u64 = 3;
+
+    // Build a ring whose real input is owned by the given account
+    fn owned_ring(account: &AccountKey, amount: Amount, rng: &mut RngType) -> SignableInputRing {
+        let mut members: Vec<ReducedTxOut> = (0..11)
+            .map(|_| ReducedTxOut {
+                public_key: CompressedRistrettoPublic::from_random(rng),
+                target_key: CompressedRistrettoPublic::from_random(rng),
+                commitment: CompressedCommitment::from(&Commitment::new(
+                    rng.next_u64(),
+                    Scalar::random(rng),
+                    &generators(*amount.token_id),
+                )),
+            })
+            .collect();
+
+        let recipient = account.subaddress(SUBADDRESS_INDEX);
+        let tx_private_key = RistrettoPrivate::from_random(rng);
+        let blinding = Scalar::random(rng);
+        let real_input_index = 4;
+        members[real_input_index] = ReducedTxOut {
+            public_key: (&create_tx_out_public_key(&tx_private_key, recipient.spend_public_key()))
+                .into(),
+            target_key: (&create_tx_out_target_key(&tx_private_key, &recipient)).into(),
+            commitment: CompressedCommitment::from(&Commitment::new(
+                amount.value,
+                blinding,
+                &generators(*amount.token_id),
+            )),
+        };
+
+        SignableInputRing {
+            members,
+            real_input_index,
+            input_secret: InputSecret {
+                onetime_key_derive_data: OneTimeKeyDeriveData::SubaddressIndex(SUBADDRESS_INDEX),
+                amount,
+                blinding,
+            },
+        }
+    }
+
+    // Start a daemon on a socket in a temporary directory
+    fn start_daemon(
+        account: &AccountKey,
+        auth_token: AuthToken,
+        policy: Box<dyn SigningPolicy>,
+        dir: &TempDir,
+        logger: Logger,
+    ) -> PathBuf {
+        let socket_path = dir.path().join("signer.sock");
+        let listener = SigningDaemon::bind(&socket_path).unwrap();
+        let daemon = Arc::new(SigningDaemon::new(account, auth_token, policy, logger));
+        thread::spawn(move || daemon.serve(listener));
+        socket_path
+    }
+
+    #[test_with_logger]
+    fn remote_signature_verifies(logger: Logger) {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let auth_token = AuthToken::random(&mut rng);
+        let dir = TempDir::new("remote-signer").unwrap();
+        let socket_path = start_daemon(
+            &account,
+            auth_token.clone(),
+            Box::new(AllowAllPolicy),
+            &dir,
+            logger,
+        );
+
+        let amount = Amount::new(1000, TokenId::from(1));
+        let ring = owned_ring(&account, amount, &mut rng);
+        let message = [7u8; 32];
+        let pseudo_output_blinding = Scalar::random(&mut rng);
+
+        let signer = RemoteRingSigner::new(&socket_path, auth_token);
+        let signature = signer
+            .sign(&message, &ring, pseudo_output_blinding, &mut rng)
+            .unwrap();
+
+        let output_commitment = CompressedCommitment::from(&Commitment::new(
+            amount.value,
+            pseudo_output_blinding,
+            &generators(*amount.token_id),
+        ));
+        signature
+            .verify(&message, &ring.members, &output_commitment)
+            .unwrap();
+
+        // Rings owned by someone else are refused
+        let other = AccountKey::random(&mut rng);
+        let ring = owned_ring(&other, amount, &mut rng);
+        assert_eq!(
+            signer.sign(&message, &ring, pseudo_output_blinding, &mut rng),
+            Err(Error::TrueInputNotOwned)
+        );
+    }
+
+    #[test_with_logger]
+    fn bad_auth_token_is_refused(logger: Logger) {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let dir = TempDir::new("remote-signer").unwrap();
+        let socket_path = start_daemon(
+            &account,
+            AuthToken::random(&mut rng),
+            Box::new(AllowAllPolicy),
+            &dir,
+            logger,
+        );
+
+        let ring = owned_ring(&account, Amount::new(10, TokenId::from(0)), &mut rng);
+        let signer = RemoteRingSigner::new(&socket_path, AuthToken::random(&mut rng));
+        assert!(matches!(
+            signer.sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng),
+            Err(Error::ConnectionFailed(_))
+        ));
+    }
+
+    #[test_with_logger]
+    fn policy_rejections_are_returned(logger: Logger) {
+        let mut rng = get_seeded_rng();
+        let account = AccountKey::random(&mut rng);
+        let auth_token = AuthToken::random(&mut rng);
+        let dir = TempDir::new("remote-signer").unwrap();
+        let socket_path = start_daemon(
+            &account,
+            auth_token.clone(),
+            Box::new(TokenLimitPolicy::new(
+                HashMap::from([(TokenId::from(0), 10)]),
+                Duration::from_secs(3600),
+            )),
+            &dir,
+            logger,
+        );
+
+        let ring = owned_ring(&account, Amount::new(10, TokenId::from(0)), &mut rng);
+        let signer = RemoteRingSigner::new(&socket_path, auth_token);
+        signer
+            .sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng)
+            .unwrap();
+
+        // The limit for token 0 is used up, and token 1 has no limit.
+        assert!(matches!(
+            signer.sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng),
+            Err(Error::Rejected(_))
+        ));
+        let ring = owned_ring(&account, Amount::new(1, TokenId::from(1)), &mut rng);
+        assert!(matches!(
+            signer.sign(&[0u8; 32], &ring, Scalar::random(&mut rng), &mut rng),
+            Err(Error::Rejected(_))
+        ));
+    }
+}
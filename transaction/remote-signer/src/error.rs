@@ -0,0 +1,36 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Errors which can occur in the remote signer client and daemon
+
+use displaydoc::Display;
+use prost::DecodeError;
+use std::{io, path::PathBuf};
+
+/// An error which can occur when talking to, or running, a signing daemon
+#[derive(Debug, Display)]
+pub enum Error {
+    /// IO error: {0}
+    Io(io::Error),
+    /// Could not decode message: {0}
+    Decode(DecodeError),
+    /// Message of {0} bytes exceeds the maximum frame length
+    FrameTooLarge(usize),
+    /// Invalid sign request: {0}
+    InvalidRequest(String),
+    /// Invalid auth token: {0}
+    InvalidAuthToken(String),
+    /// Refusing to replace {0:?}, which is not a socket
+    NotASocket(PathBuf),
+}
+
+impl From<io::Error> for Error {
+    fn from(src: io::Error) -> Self {
+        Self::Io(src)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(src: DecodeError) -> Self {
+        Self::Decode(src)
+    }
+}
@@ -0,0 +1,33 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A RingSigner which keeps spend keys in a separate process.
+//!
+//! The [SigningDaemon] holds an account key and listens on a local unix
+//! socket. Services that must never hold spend keys use a [RemoteRingSigner],
+//! which implements [RingSigner](mc_crypto_ring_signature_signer::RingSigner)
+//! by sending each input ring to the daemon and returning the [RingMLSAG] it
+//! produces, so that it can be passed to `TransactionBuilder::build` like any
+//! other signer.
+//!
+//! The daemon consults a [SigningPolicy] before signing anything. Since it
+//! only sees a digest of the transaction, policies can only be about the
+//! input being spent.
+//!
+//! [RingMLSAG]: mc_crypto_ring_signature::RingMLSAG
+
+#![deny(missing_docs)]
+
+mod auth;
+mod client;
+mod error;
+mod messages;
+mod policy;
+mod server;
+mod wire;
+
+pub use auth::AuthToken;
+pub use client::RemoteRingSigner;
+pub use error::Error;
+pub use messages::{RingMember, SignErrorCode, SignRequest, SignResponse};
+pub use policy::{AllowAllPolicy, PolicySet, SigningPolicy, SigningRequestInfo, TokenLimitPolicy};
+pub use server::SigningDaemon;
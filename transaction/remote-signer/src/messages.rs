@@ -0,0 +1,209 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Messages exchanged between a RemoteRingSigner and the signing daemon
+
+use crate::Error;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_crypto_ring_signature::{
+    CompressedCommitment, CurveScalar, ReducedTxOut, RingMLSAG, Scalar,
+};
+use mc_crypto_ring_signature_signer::{
+    Error as SignerError, InputSecret, OneTimeKeyDeriveData, SignableInputRing,
+};
+use mc_transaction_types::{Amount, TokenId};
+use prost::{Enumeration, Message};
+
+/// A request to sign one input ring
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct SignRequest {
+    /// The auth token shared with the daemon
+    #[prost(bytes, tag = "1")]
+    pub auth_token: Vec<u8>,
+
+    /// The digest of transaction context to sign
+    #[prost(bytes, tag = "2")]
+    pub message: Vec<u8>,
+
+    /// The members of the ring
+    #[prost(message, repeated, tag = "3")]
+    pub members: Vec<RingMember>,
+
+    /// The index of the real input among the ring members
+    #[prost(uint64, tag = "4")]
+    pub real_input_index: u64,
+
+    /// The subaddress index which owns the real input
+    #[prost(uint64, tag = "5")]
+    pub subaddress_index: u64,
+
+    /// The value of the real input
+    #[prost(uint64, tag = "6")]
+    pub value: u64,
+
+    /// The token id of the real input
+    #[prost(fixed64, tag = "7")]
+    pub token_id: u64,
+
+    /// The blinding factor of the real input's commitment
+    #[prost(message, required, tag = "8")]
+    pub blinding: CurveScalar,
+
+    /// The desired blinding factor of the pseudo-output
+    #[prost(message, required, tag = "9")]
+    pub pseudo_output_blinding: CurveScalar,
+}
+
+/// A reduced TxOut in the ring
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct RingMember {
+    /// The tx_out.public_key field
+    #[prost(message, required, tag = "1")]
+    pub public_key: CompressedRistrettoPublic,
+
+    /// The tx_out.target_key field
+    #[prost(message, required, tag = "2")]
+    pub target_key: CompressedRistrettoPublic,
+
+    /// The tx_out.masked_amount.commitment field
+    #[prost(message, required, tag = "3")]
+    pub commitment: CompressedCommitment,
+}
+
+/// Why a sign request failed
+#[derive(Clone, Copy, Debug, Enumeration, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum SignErrorCode {
+    /// The request succeeded
+    Ok = 0,
+    /// The auth token was missing or wrong
+    Unauthorized = 1,
+    /// The signing policy rejected the request
+    Rejected = 2,
+    /// The real input is not owned by the daemon's account
+    TrueInputNotOwned = 3,
+    /// The request was malformed
+    InvalidRequest = 4,
+    /// Signing failed for some other reason
+    SigningFailed = 5,
+}
+
+/// The response to a sign request
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct SignResponse {
+    /// The signature, if signing succeeded
+    #[prost(message, optional, tag = "1")]
+    pub signature: Option<RingMLSAG>,
+
+    /// Why signing failed, if it did
+    #[prost(enumeration = "SignErrorCode", tag = "2")]
+    pub error_code: i32,
+
+    /// A description of the failure
+    #[prost(string, tag = "3")]
+    pub error_message: String,
+}
+
+impl SignRequest {
+    /// Build a request to sign a ring.
+    ///
+    /// Only rings whose input secret is a subaddress index can be signed
+    /// remotely; others fail with `NoPathToSpendKey`.
+    pub fn new(
+        auth_token: &[u8],
+        message: &[u8],
+        ring: &SignableInputRing,
+        pseudo_output_blinding: Scalar,
+    ) -> Result<Self, SignerError> {
+        let subaddress_index = match ring.input_secret.onetime_key_derive_data {
+            OneTimeKeyDeriveData::SubaddressIndex(index) => index,
+            OneTimeKeyDeriveData::OneTimeKey(_) => return Err(SignerError::NoPathToSpendKey),
+        };
+        Ok(Self {
+            auth_token: auth_token.to_vec(),
+            message: message.to_vec(),
+            members: ring.members.iter().map(RingMember::from).collect(),
+            real_input_index: ring.real_input_index as u64,
+            subaddress_index,
+            value: ring.input_secret.amount.value,
+            token_id: *ring.input_secret.amount.token_id,
+            blinding: ring.input_secret.blinding.into(),
+            pseudo_output_blinding: pseudo_output_blinding.into(),
+        })
+    }
+
+    /// The ring to sign, with its input secret
+    pub fn signable_ring(&self) -> Result<SignableInputRing, Error> {
+        let real_input_index = usize::try_from(self.real_input_index)
+            .map_err(|_| Error::InvalidRequest("real input index overflows".to_owned()))?;
+        Ok(SignableInputRing {
+            members: self.members.iter().map(ReducedTxOut::from).collect(),
+            real_input_index,
+            input_secret: InputSecret {
+                onetime_key_derive_data: OneTimeKeyDeriveData::SubaddressIndex(
+                    self.subaddress_index,
+                ),
+                amount: Amount::new(self.value, TokenId::from(self.token_id)),
+                blinding: self.blinding.scalar,
+            },
+        })
+    }
+}
+
+impl SignResponse {
+    /// A successful response
+    pub fn success(signature: RingMLSAG) -> Self {
+        Self {
+            signature: Some(signature),
+            error_code: SignErrorCode::Ok as i32,
+            error_message: Default::default(),
+        }
+    }
+
+    /// A failed response
+    pub fn failure(code: SignErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            signature: None,
+            error_code: code as i32,
+            error_message: message.into(),
+        }
+    }
+
+    /// Convert the response into the result a RingSigner returns
+    pub fn into_result(self) -> Result<RingMLSAG, SignerError> {
+        match SignErrorCode::from_i32(self.error_code) {
+            Some(SignErrorCode::Ok) => self.signature.ok_or_else(|| {
+                SignerError::ConnectionFailed("signing daemon returned no signature".to_owned())
+            }),
+            Some(SignErrorCode::TrueInputNotOwned) => Err(SignerError::TrueInputNotOwned),
+            Some(SignErrorCode::Rejected) => Err(SignerError::Rejected(self.error_message)),
+            Some(code) => Err(SignerError::ConnectionFailed(format!(
+                "{:?}: {}",
+                code, self.error_message
+            ))),
+            None => Err(SignerError::ConnectionFailed(format!(
+                "unknown error code {}: {}",
+                self.error_code, self.error_message
+            ))),
+        }
+    }
+}
+
+impl From<&ReducedTxOut> for RingMember {
+    fn from(src: &ReducedTxOut) -> Self {
+        Self {
+            public_key: src.public_key,
+            target_key: src.target_key,
+            commitment: src.commitment,
+        }
+    }
+}
+
+impl From<&RingMember> for ReducedTxOut {
+    fn from(src: &RingMember) -> Self {
+        Self {
+            public_key: src.public_key,
+            target_key: src.target_key,
+            commitment: src.commitment,
+        }
+    }
+}
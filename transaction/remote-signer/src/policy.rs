@@ -0,0 +1,154 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Policies the signing daemon applies before signing an input.
+//!
+//! The daemon only sees a digest of the transaction it is signing, so only
+//! policies about the input being spent are supported. Anything the client
+//! could say about the rest of the transaction, such as its outputs, would
+//! not be bound to the signature and could not be trusted. The amount limits
+//! are enforced on the value of the inputs spent, which bounds what any
+//! transaction built from them can send.
+
+use mc_transaction_types::TokenId;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// What a policy knows about a sign request
+#[derive(Clone, Debug)]
+pub struct SigningRequestInfo {
+    /// The token id of the input being spent
+    pub token_id: TokenId,
+    /// The value of the input being spent
+    pub value: u64,
+    /// The subaddress which owns the input
+    pub subaddress_index: u64,
+}
+
+/// A hook which decides whether the daemon may sign a request
+pub trait SigningPolicy: Send + Sync {
+    /// Approve or reject a request. Rejections carry a reason, which is
+    /// returned to the client.
+    ///
+    /// Approving a request may record it, e.g. against a spending limit.
+    fn approve(&self, request: &SigningRequestInfo) -> Result<(), String>;
+}
+
+/// A policy which approves everything
+#[derive(Clone, Debug, Default)]
+pub struct AllowAllPolicy;
+
+impl SigningPolicy for AllowAllPolicy {
+    fn approve(&self, _request: &SigningRequestInfo) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Limits the total value of each token which may be spent within a rolling
+/// period. Tokens without a limit may not be spent at all.
+pub struct TokenLimitPolicy {
+    /// The limit for each token id
+    limits: HashMap<TokenId, u64>,
+    /// The length of the rolling period
+    period: Duration,
+    /// When each approved input was signed, and its value, by token id
+    spent: Mutex<HashMap<TokenId, VecDeque<(Instant, u64)>>>,
+}
+
+impl TokenLimitPolicy {
+    /// Create a new limit policy
+    pub fn new(limits: HashMap<TokenId, u64>, period: Duration) -> Self {
+        Self {
+            limits,
+            period,
+            spent: Default::default(),
+        }
+    }
+}
+
+impl SigningPolicy for TokenLimitPolicy {
+    fn approve(&self, request: &SigningRequestInfo) -> Result<(), String> {
+        let limit = self
+            .limits
+            .get(&request.token_id)
+            .ok_or_else(|| format!("token id {} may not be spent", request.token_id))?;
+
+        let now = Instant::now();
+        let mut spent = self.spent.lock().expect("mutex poisoned");
+        let history = spent.entry(request.token_id).or_default();
+        while let Some((signed_at, _)) = history.front() {
+            if now.duration_since(*signed_at) < self.period {
+                break;
+            }
+            history.pop_front();
+        }
+
+        let total = history
+            .iter()
+            .map(|(_, value)| *value as u128)
+            .sum::<u128>()
+            + request.value as u128;
+        if total > *limit as u128 {
+            return Err(format!(
+                "spending {} of token id {} would exceed the limit of {} per {:?}",
+                request.value, request.token_id, limit, self.period
+            ));
+        }
+        history.push_back((now, request.value));
+        Ok(())
+    }
+}
+
+/// A list of policies which must all approve a request. They are consulted
+/// in order, stopping at the first rejection, so policies which record
+/// approvals should come last.
+#[derive(Default)]
+pub struct PolicySet(pub Vec<Box<dyn SigningPolicy>>);
+
+impl SigningPolicy for PolicySet {
+    fn approve(&self, request: &SigningRequestInfo) -> Result<(), String> {
+        self.0.iter().try_for_each(|policy| policy.approve(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(token_id: u64, value: u64) -> SigningRequestInfo {
+        SigningRequestInfo {
+            token_id: TokenId::from(token_id),
+            value,
+            subaddress_index: 0,
+        }
+    }
+
+    #[test]
+    fn token_limits_are_enforced() {
+        let policy = TokenLimitPolicy::new(
+            HashMap::from([(TokenId::from(0), 100)]),
+            Duration::from_secs(3600),
+        );
+
+        policy.approve(&request(0, 60)).unwrap();
+        policy.approve(&request(0, 40)).unwrap();
+        assert!(policy.approve(&request(0, 1)).is_err());
+        // Tokens without a limit are rejected
+        assert!(policy.approve(&request(1, 1)).is_err());
+    }
+
+    #[test]
+    fn token_limits_roll_over() {
+        let policy = TokenLimitPolicy::new(
+            HashMap::from([(TokenId::from(0), 100)]),
+            Duration::from_millis(10),
+        );
+
+        policy.approve(&request(0, 100)).unwrap();
+        assert!(policy.approve(&request(0, 1)).is_err());
+        std::thread::sleep(Duration::from_millis(20));
+        policy.approve(&request(0, 100)).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! The signing daemon, which holds the spend keys
+
+use crate::{
+    wire::{read_message, write_message},
+    AuthToken, Error, SignErrorCode, SignRequest, SignResponse, SigningPolicy, SigningRequestInfo,
+};
+use mc_account_keys::AccountKey;
+use mc_common::logger::{log, Logger};
+use mc_crypto_rand::McRng;
+use mc_crypto_ring_signature_signer::{Error as SignerError, LocalRingSigner, RingSigner};
+use mc_transaction_types::TokenId;
+use std::{
+    fs::{self, DirBuilder, Permissions},
+    io::{self, ErrorKind},
+    os::unix::{
+        fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::Path,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+/// Signs input rings on behalf of RemoteRingSigner clients, subject to a
+/// signing policy.
+pub struct SigningDaemon {
+    signer: LocalRingSigner,
+    auth_token: AuthToken,
+    policy: Box<dyn SigningPolicy>,
+    logger: Logger,
+}
+
+impl SigningDaemon {
+    /// How long a connection may take to send a request, or to accept a
+    /// response, before it is dropped
+    pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Create a new daemon
+    pub fn new(
+        account_key: &AccountKey,
+        auth_token: AuthToken,
+        policy: Box<dyn SigningPolicy>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            signer: LocalRingSigner::from(account_key),
+            auth_token,
+            policy,
+            logger,
+        }
+    }
+
+    /// Bind a unix socket at the given path which only the current user can
+    /// connect to. A stale socket left at the path is removed, but any other
+    /// file there is left alone and makes this fail.
+    ///
+    /// The socket is first bound in a new directory which only the current
+    /// user can enter, and only moved to the given path once its permissions
+    /// are restricted, so that nobody else can connect to it in between.
+    pub fn bind(path: impl AsRef<Path>) -> Result<UnixListener, Error> {
+        let path = path.as_ref();
+        match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path)?,
+            Ok(_) => return Err(Error::NotASocket(path.to_path_buf())),
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let file_name = path.file_name().ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidInput, "socket path has no file name")
+        })?;
+        let mut private_dir_name = file_name.to_os_string();
+        private_dir_name.push(format!(".{}.tmp", std::process::id()));
+        let private_dir = path.with_file_name(private_dir_name);
+        DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+        let private_path = private_dir.join("socket");
+        let result = UnixListener::bind(&private_path)
+            .and_then(|listener| {
+                fs::set_permissions(&private_path, Permissions::from_mode(0o600))?;
+                fs::rename(&private_path, path)?;
+                Ok(listener)
+            })
+            .map_err(Error::from);
+        let _ = fs::remove_file(&private_path);
+        let _ = fs::remove_dir(&private_dir);
+        result
+    }
+
+    /// Accept connections until the listener fails, handling each on its own
+    /// thread.
+    pub fn serve(self: Arc<Self>, listener: UnixListener) -> Result<(), Error> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let daemon = self.clone();
+            thread::Builder::new()
+                .name("ring-signer-conn".to_owned())
+                .spawn(move || {
+                    if let Err(err) = daemon.handle_connection(stream) {
+                        log::warn!(daemon.logger, "Signing connection failed: {}", err);
+                    }
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Answer sign requests on a connection until the client closes it, or
+    /// stalls for longer than [Self::CONNECTION_TIMEOUT]
+    pub fn handle_connection(&self, mut stream: UnixStream) -> Result<(), Error> {
+        stream.set_read_timeout(Some(Self::CONNECTION_TIMEOUT))?;
+        stream.set_write_timeout(Some(Self::CONNECTION_TIMEOUT))?;
+        while let Some(request) = read_message::<SignRequest>(&mut stream)? {
+            let response = self.handle_request(&request);
+            write_message(&mut stream, &response)?;
+        }
+        Ok(())
+    }
+
+    /// Authenticate, check and sign a single request
+    pub fn handle_request(&self, request: &SignRequest) -> SignResponse {
+        if !self.auth_token.matches(&request.auth_token) {
+            log::warn!(self.logger, "Rejected sign request with a bad auth token");
+            return SignResponse::failure(SignErrorCode::Unauthorized, "bad auth token");
+        }
+
+        let ring = match request.signable_ring() {
+            Ok(ring) => ring,
+            Err(err) => {
+                return SignResponse::failure(SignErrorCode::InvalidRequest, err.to_string())
+            }
+        };
+
+        let info = SigningRequestInfo {
+            token_id: TokenId::from(request.token_id),
+            value: request.value,
+            subaddress_index: request.subaddress_index,
+        };
+        if let Err(reason) = self.policy.approve(&info) {
+            log::info!(self.logger, "Signing policy rejected request: {}", reason);
+            return SignResponse::failure(SignErrorCode::Rejected, reason);
+        }
+
+        match self.signer.sign(
+            &request.message,
+            &ring,
+            request.pseudo_output_blinding.scalar,
+            &mut McRng::default(),
+        ) {
+            Ok(signature) => {
+                log::debug!(
+                    self.logger,
+                    "Signed input of {} token id {} from subaddress {}",
+                    info.value,
+                    info.token_id,
+                    info.subaddress_index
+                );
+                SignResponse::success(signature)
+            }
+            Err(SignerError::TrueInputNotOwned) => SignResponse::failure(
+                SignErrorCode::TrueInputNotOwned,
+                SignerError::TrueInputNotOwned.to_string(),
+            ),
+            Err(err) => SignResponse::failure(SignErrorCode::SigningFailed, err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn bind_restricts_socket_permissions() {
+        let dir = TempDir::new("remote-signer").unwrap();
+        let socket_path = dir.path().join("signer.sock");
+
+        let _listener = SigningDaemon::bind(&socket_path).unwrap();
+        let metadata = fs::symlink_metadata(&socket_path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+        // Nothing but the socket is left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+        UnixStream::connect(&socket_path).unwrap();
+    }
+
+    #[test]
+    fn bind_replaces_stale_socket() {
+        let dir = TempDir::new("remote-signer").unwrap();
+        let socket_path = dir.path().join("signer.sock");
+
+        drop(SigningDaemon::bind(&socket_path).unwrap());
+        let _listener = SigningDaemon::bind(&socket_path).unwrap();
+        UnixStream::connect(&socket_path).unwrap();
+    }
+
+    #[test]
+    fn bind_refuses_to_replace_other_files() {
+        let dir = TempDir::new("remote-signer").unwrap();
+        let file_path = dir.path().join("signer.sock");
+        fs::write(&file_path, b"not a socket").unwrap();
+
+        match SigningDaemon::bind(&file_path) {
+            Err(Error::NotASocket(path)) => assert_eq!(path, file_path),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+        assert_eq!(fs::read(&file_path).unwrap(), b"not a socket");
+
+        let dir_path = dir.path().join("signer-dir");
+        fs::create_dir(&dir_path).unwrap();
+        assert!(matches!(
+            SigningDaemon::bind(&dir_path),
+            Err(Error::NotASocket(_))
+        ));
+        assert!(dir_path.is_dir());
+    }
+}
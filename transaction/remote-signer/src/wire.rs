@@ -0,0 +1,46 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Framing of protobuf messages on the signing socket.
+//!
+//! Each message is sent as a 4-byte big-endian length, followed by that many
+//! bytes of protobuf.
+
+use crate::Error;
+use prost::Message;
+use std::io::{ErrorKind, Read, Write};
+
+/// The largest frame either side will accept. A sign request for an 11-member
+/// ring is well under 2KB.
+pub const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Write a message as a single frame
+pub fn write_message<M: Message>(stream: &mut impl Write, message: &M) -> Result<(), Error> {
+    let bytes = mc_util_serial::encode(message);
+    if bytes.len() > MAX_FRAME_LEN {
+        return Err(Error::FrameTooLarge(bytes.len()));
+    }
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Read a single frame and decode it.
+///
+/// Returns `Ok(None)` if the stream was closed cleanly before the frame
+/// started.
+pub fn read_message<M: Message + Default>(stream: &mut impl Read) -> Result<Option<M>, Error> {
+    let mut len_bytes = [0u8; 4];
+    match stream.read_exact(&mut len_bytes) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(Error::FrameTooLarge(len));
+    }
+    let mut bytes = vec![0u8; len];
+    stream.read_exact(&mut bytes)?;
+    Ok(Some(mc_util_serial::decode(&bytes)?))
+}
@@ -43,7 +43,7 @@ pub fn compute_category1_hmac(
     result
 }
 
-/// Shared code for validation of 0x0100 and 0x0101 memos
+/// Shared code for validation of 0x0100, 0x0101, 0x0102 and 0x0103 memos
 pub fn validate_authenticated_sender(
     sender_address: &PublicAddress,
     receiving_subaddress_view_private_key: &RistrettoPrivate,
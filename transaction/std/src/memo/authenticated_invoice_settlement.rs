@@ -0,0 +1,146 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Object for 0x0103 Authenticated Invoice Settlement memo type
+//!
+//! This follows the authentication scheme of the 0x0100 Authenticated Sender
+//! memo proposed in mobilecoinfoundation/mcips/pull/4
+
+use super::{
+    authenticated_common::{compute_category1_hmac, validate_authenticated_sender},
+    credential::SenderMemoCredential,
+    RegisteredMemoType,
+};
+use crate::impl_memo_type_conversions;
+use mc_account_keys::{PublicAddress, ShortAddressHash};
+use mc_crypto_keys::{
+    CompressedRistrettoPublic, KexReusablePrivate, RistrettoPrivate, RistrettoPublic,
+};
+use subtle::Choice;
+
+/// A memo that the sender writes when paying an invoice, conveying their
+/// identity in an authenticated but deniable way, and including the 16-byte id
+/// of the invoice being settled under the MAC.
+///
+/// See MCIP document for a discussion of the deniability property.
+///
+/// The recipient of this memo type should:
+/// * First, use sender_address_hash to look up the address of the sender, from
+///   among their contacts. If the sender isn't known then we can't validate.
+/// * Then, call validate to check the mac and confirm authenticity.
+/// * We can extract the invoice id to mark the invoice as paid.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AuthenticatedInvoiceSettlementMemo {
+    /// The memo data
+    memo_data: [u8; 64],
+}
+
+impl RegisteredMemoType for AuthenticatedInvoiceSettlementMemo {
+    const MEMO_TYPE_BYTES: [u8; 2] = [0x01, 0x03];
+}
+
+impl AuthenticatedInvoiceSettlementMemo {
+    /// Create a new AuthenticatedInvoiceSettlementMemo given credential,
+    /// recipient public key, tx out public key, and invoice id
+    ///
+    /// # Arguments:
+    /// * cred: A sender memo credential tied to the address we wish to identify
+    ///   ourselves as
+    /// * receiving_subaddress_view_public_key: This is the view public key from
+    ///   the public address of recipient
+    /// * tx_out_public_key: The public_key of the TxOut to which we will attach
+    ///   this memo
+    /// * invoice_id: The id of the invoice which this TxOut pays
+    pub fn new(
+        cred: &SenderMemoCredential,
+        receiving_subaddress_view_public_key: &RistrettoPublic,
+        tx_out_public_key: &CompressedRistrettoPublic,
+        invoice_id: [u8; 16],
+    ) -> Self {
+        // The layout of the memo is:
+        // [0-16) address hash
+        // [16-32) invoice id
+        // [32-48) unused
+        // [48-64) HMAC
+
+        let mut memo_data = [0u8; 64];
+        memo_data[..16].copy_from_slice(cred.address_hash.as_ref());
+        memo_data[16..32].copy_from_slice(&invoice_id);
+
+        let shared_secret = cred
+            .subaddress_spend_private_key
+            .key_exchange(receiving_subaddress_view_public_key);
+
+        let hmac_value = compute_category1_hmac(
+            shared_secret.as_ref(),
+            tx_out_public_key,
+            Self::MEMO_TYPE_BYTES,
+            &memo_data,
+        );
+        memo_data[48..].copy_from_slice(&hmac_value);
+
+        Self { memo_data }
+    }
+
+    /// Get the sender address hash from the memo
+    pub fn sender_address_hash(&self) -> ShortAddressHash {
+        let bytes: [u8; 16] = self.memo_data[0..16].try_into().unwrap();
+        ShortAddressHash::from(bytes)
+    }
+
+    /// Get the invoice id from the memo
+    pub fn invoice_id(&self) -> [u8; 16] {
+        self.memo_data[16..32].try_into().unwrap()
+    }
+
+    /// Validate an AuthenticatedInvoiceSettlementMemo
+    ///
+    /// This works exactly like validating an AuthenticatedSenderMemo: look up
+    /// the sender's Public Address from their hash, then validate the memo
+    /// against it and the view private key of the subaddress which received
+    /// this TxOut.
+    ///
+    /// Arguments:
+    /// * sender_address: The public address of the sender. This can be looked
+    ///   up by the ShortAddressHash provided.
+    /// * receiving_subaddress_view_private_key: This is usually our
+    ///   default_subaddress_view_private_key, but should correspond to whatever
+    ///   subaddress recieved this TxOut.
+    /// * tx_out_public_key: The public key of the TxOut to which this memo is
+    ///   attached.
+    ///
+    /// Returns:
+    /// * subtle::Choice(1u8) if validation passed, subtle::Choice(0u8) if hmac
+    ///   comparison failed.
+    ///
+    /// This function is constant-time.
+    pub fn validate(
+        &self,
+        sender_address: &PublicAddress,
+        receiving_subaddress_view_private_key: &RistrettoPrivate,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Choice {
+        validate_authenticated_sender(
+            sender_address,
+            receiving_subaddress_view_private_key,
+            tx_out_public_key,
+            Self::MEMO_TYPE_BYTES,
+            &self.memo_data,
+        )
+    }
+}
+
+impl From<&[u8; 64]> for AuthenticatedInvoiceSettlementMemo {
+    fn from(src: &[u8; 64]) -> Self {
+        let mut memo_data = [0u8; 64];
+        memo_data.copy_from_slice(src);
+        Self { memo_data }
+    }
+}
+
+impl From<AuthenticatedInvoiceSettlementMemo> for [u8; 64] {
+    fn from(src: AuthenticatedInvoiceSettlementMemo) -> [u8; 64] {
+        src.memo_data
+    }
+}
+
+impl_memo_type_conversions! { AuthenticatedInvoiceSettlementMemo }
@@ -0,0 +1,148 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Object for 0x0102 Authenticated Refund memo type
+//!
+//! This follows the authentication scheme of the 0x0100 Authenticated Sender
+//! memo proposed in mobilecoinfoundation/mcips/pull/4
+
+use super::{
+    authenticated_common::{compute_category1_hmac, validate_authenticated_sender},
+    credential::SenderMemoCredential,
+    RegisteredMemoType,
+};
+use crate::impl_memo_type_conversions;
+use mc_account_keys::{PublicAddress, ShortAddressHash};
+use mc_crypto_keys::{
+    CompressedRistrettoPublic, KexReusablePrivate, RistrettoPrivate, RistrettoPublic,
+};
+use subtle::Choice;
+
+/// A memo that the sender writes when returning funds which they received,
+/// conveying their identity in an authenticated but deniable way, and
+/// including the public key of the TxOut being refunded under the MAC.
+///
+/// See MCIP document for a discussion of the deniability property.
+///
+/// The recipient of this memo type should:
+/// * First, use sender_address_hash to look up the address of the sender, from
+///   among their contacts. If the sender isn't known then we can't validate.
+/// * Then, call validate to check the mac and confirm authenticity.
+/// * We can extract the refunded TxOut public key to find the payment which was
+///   refunded.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AuthenticatedRefundMemo {
+    /// The memo data
+    memo_data: [u8; 64],
+}
+
+impl RegisteredMemoType for AuthenticatedRefundMemo {
+    const MEMO_TYPE_BYTES: [u8; 2] = [0x01, 0x02];
+}
+
+impl AuthenticatedRefundMemo {
+    /// Create a new AuthenticatedRefundMemo given credential, recipient public
+    /// key, tx out public key, and the public key of the refunded TxOut
+    ///
+    /// # Arguments:
+    /// * cred: A sender memo credential tied to the address we wish to identify
+    ///   ourselves as
+    /// * receiving_subaddress_view_public_key: This is the view public key from
+    ///   the public address of recipient
+    /// * tx_out_public_key: The public_key of the TxOut to which we will attach
+    ///   this memo
+    /// * refunded_tx_out_public_key: The public_key of the TxOut, previously
+    ///   received from the recipient, which this TxOut refunds
+    pub fn new(
+        cred: &SenderMemoCredential,
+        receiving_subaddress_view_public_key: &RistrettoPublic,
+        tx_out_public_key: &CompressedRistrettoPublic,
+        refunded_tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Self {
+        // The layout of the memo is:
+        // [0-16) address hash
+        // [16-48) refunded tx out public key
+        // [48-64) HMAC
+
+        let mut memo_data = [0u8; 64];
+        memo_data[..16].copy_from_slice(cred.address_hash.as_ref());
+        memo_data[16..48].copy_from_slice(refunded_tx_out_public_key.as_ref());
+
+        let shared_secret = cred
+            .subaddress_spend_private_key
+            .key_exchange(receiving_subaddress_view_public_key);
+
+        let hmac_value = compute_category1_hmac(
+            shared_secret.as_ref(),
+            tx_out_public_key,
+            Self::MEMO_TYPE_BYTES,
+            &memo_data,
+        );
+        memo_data[48..].copy_from_slice(&hmac_value);
+
+        Self { memo_data }
+    }
+
+    /// Get the sender address hash from the memo
+    pub fn sender_address_hash(&self) -> ShortAddressHash {
+        let bytes: [u8; 16] = self.memo_data[0..16].try_into().unwrap();
+        ShortAddressHash::from(bytes)
+    }
+
+    /// Get the public key of the refunded TxOut from the memo
+    pub fn refunded_tx_out_public_key(&self) -> CompressedRistrettoPublic {
+        let bytes: [u8; 32] = self.memo_data[16..48].try_into().unwrap();
+        CompressedRistrettoPublic::from(&bytes)
+    }
+
+    /// Validate an AuthenticatedRefundMemo
+    ///
+    /// This works exactly like validating an AuthenticatedSenderMemo: look up
+    /// the sender's Public Address from their hash, then validate the memo
+    /// against it and the view private key of the subaddress which received
+    /// this TxOut.
+    ///
+    /// Arguments:
+    /// * sender_address: The public address of the sender. This can be looked
+    ///   up by the ShortAddressHash provided.
+    /// * receiving_subaddress_view_private_key: This is usually our
+    ///   default_subaddress_view_private_key, but should correspond to whatever
+    ///   subaddress recieved this TxOut.
+    /// * tx_out_public_key: The public key of the TxOut to which this memo is
+    ///   attached.
+    ///
+    /// Returns:
+    /// * subtle::Choice(1u8) if validation passed, subtle::Choice(0u8) if hmac
+    ///   comparison failed.
+    ///
+    /// This function is constant-time.
+    pub fn validate(
+        &self,
+        sender_address: &PublicAddress,
+        receiving_subaddress_view_private_key: &RistrettoPrivate,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Choice {
+        validate_authenticated_sender(
+            sender_address,
+            receiving_subaddress_view_private_key,
+            tx_out_public_key,
+            Self::MEMO_TYPE_BYTES,
+            &self.memo_data,
+        )
+    }
+}
+
+impl From<&[u8; 64]> for AuthenticatedRefundMemo {
+    fn from(src: &[u8; 64]) -> Self {
+        let mut memo_data = [0u8; 64];
+        memo_data.copy_from_slice(src);
+        Self { memo_data }
+    }
+}
+
+impl From<AuthenticatedRefundMemo> for [u8; 64] {
+    fn from(src: AuthenticatedRefundMemo) -> [u8; 64] {
+        src.memo_data
+    }
+}
+
+impl_memo_type_conversions! { AuthenticatedRefundMemo }
@@ -39,12 +39,16 @@
 //! | 0x0002          | Gift Code Sender Memo                             |
 //! | 0x0100          | Authenticated Sender Memo                         |
 //! | 0x0101          | Authenticated Sender With Payment Request Id Memo |
+//! | 0x0102          | Authenticated Refund Memo                         |
+//! | 0x0103          | Authenticated Invoice Settlement Memo             |
 //! | 0x0200          | Destination Memo                                  |
 //! | 0x0201          | Gift Code Funding Memo                            |
 //! | 0x0202          | Gift Code Cancellation Memo                       |
 
 pub use self::{
     authenticated_common::compute_category1_hmac,
+    authenticated_invoice_settlement::AuthenticatedInvoiceSettlementMemo,
+    authenticated_refund::AuthenticatedRefundMemo,
     authenticated_sender::AuthenticatedSenderMemo,
     authenticated_sender_with_payment_request_id::AuthenticatedSenderWithPaymentRequestIdMemo,
     burn_redemption::BurnRedemptionMemo,
@@ -57,6 +61,8 @@ pub use self::{
 };
 
 mod authenticated_common;
+mod authenticated_invoice_settlement;
+mod authenticated_refund;
 mod authenticated_sender;
 mod authenticated_sender_with_payment_request_id;
 mod burn_redemption;
@@ -94,6 +100,8 @@ pub enum MemoDecodingError {
 }
 
 impl_memo_enum! { MemoType,
+    AuthenticatedInvoiceSettlement(AuthenticatedInvoiceSettlementMemo),
+    AuthenticatedRefund(AuthenticatedRefundMemo),
     AuthenticatedSender(AuthenticatedSenderMemo),
     AuthenticatedSenderWithPaymentRequestId(AuthenticatedSenderWithPaymentRequestIdMemo),
     BurnRedemption(BurnRedemptionMemo),
@@ -196,6 +204,114 @@ mod tests {
                 panic!("unexpected deserialization");
             }
         }
+
+        let memo7 = AuthenticatedRefundMemo::new(
+            &alice_cred,
+            bob_addr.view_public_key(),
+            &tx_public_key,
+            &CompressedRistrettoPublic::from_random(&mut rng),
+        );
+        match MemoType::try_from(&MemoPayload::from(memo7.clone())).unwrap() {
+            MemoType::AuthenticatedRefund(memo) => {
+                assert_eq!(memo7, memo);
+            }
+            _ => {
+                panic!("unexpected deserialization");
+            }
+        }
+
+        let memo8 = AuthenticatedInvoiceSettlementMemo::new(
+            &alice_cred,
+            bob_addr.view_public_key(),
+            &tx_public_key,
+            [9u8; 16],
+        );
+        match MemoType::try_from(&MemoPayload::from(memo8.clone())).unwrap() {
+            MemoType::AuthenticatedInvoiceSettlement(memo) => {
+                assert_eq!(memo8, memo);
+            }
+            _ => {
+                panic!("unexpected deserialization");
+            }
+        }
+    }
+
+    #[test]
+    fn test_refund_and_invoice_settlement_memo_authentication() {
+        let mut rng: StdRng = SeedableRng::from_seed([3u8; 32]);
+
+        let alice = AccountKey::new(
+            &RistrettoPrivate::from_random(&mut rng),
+            &RistrettoPrivate::from_random(&mut rng),
+        );
+        let alice_cred = SenderMemoCredential::from(&alice);
+        let alice_addr = alice.default_subaddress();
+
+        let bob = AccountKey::new(
+            &RistrettoPrivate::from_random(&mut rng),
+            &RistrettoPrivate::from_random(&mut rng),
+        );
+        let bob_addr = bob.default_subaddress();
+
+        let tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+        let tx_public_key2 = CompressedRistrettoPublic::from_random(&mut rng);
+        let refunded_tx_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+
+        let refund_memo = AuthenticatedRefundMemo::new(
+            &alice_cred,
+            bob_addr.view_public_key(),
+            &tx_public_key,
+            &refunded_tx_public_key,
+        );
+        assert_eq!(
+            refund_memo.sender_address_hash(),
+            ShortAddressHash::from(&alice_addr)
+        );
+        assert_eq!(
+            refund_memo.refunded_tx_out_public_key(),
+            refunded_tx_public_key
+        );
+
+        let invoice_id = [0xabu8; 16];
+        let settlement_memo = AuthenticatedInvoiceSettlementMemo::new(
+            &alice_cred,
+            bob_addr.view_public_key(),
+            &tx_public_key,
+            invoice_id,
+        );
+        assert_eq!(
+            settlement_memo.sender_address_hash(),
+            ShortAddressHash::from(&alice_addr)
+        );
+        assert_eq!(settlement_memo.invoice_id(), invoice_id);
+
+        let bob_view = bob.default_subaddress_view_private();
+        let alice_view = alice.default_subaddress_view_private();
+        for (sender, view_private, tx_public_key, expected) in [
+            (&alice_addr, &bob_view, &tx_public_key, true),
+            (&bob_addr, &bob_view, &tx_public_key, false),
+            (&alice_addr, &alice_view, &tx_public_key, false),
+            (&alice_addr, &bob_view, &tx_public_key2, false),
+        ] {
+            assert_eq!(
+                bool::from(refund_memo.validate(sender, view_private, tx_public_key)),
+                expected
+            );
+            assert_eq!(
+                bool::from(settlement_memo.validate(sender, view_private, tx_public_key)),
+                expected
+            );
+        }
+
+        // A memo of one type doesn't validate as the other, since the memo type
+        // bytes are under the mac
+        let bytes: [u8; 64] = settlement_memo.into();
+        let as_refund = AuthenticatedRefundMemo::from(&bytes);
+        assert!(!bool::from(as_refund.validate(
+            &alice_addr,
+            &bob_view,
+            &tx_public_key
+        )));
     }
 
     #[test]
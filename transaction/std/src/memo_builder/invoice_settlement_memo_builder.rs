@@ -0,0 +1,153 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Defines the InvoiceSettlementMemoBuilder.
+//! This MemoBuilder policy attaches 0x0103 Authenticated Invoice Settlement
+//! Memos to normal outputs, for paying an invoice.
+
+use super::{
+    memo::{AuthenticatedInvoiceSettlementMemo, SenderMemoCredential},
+    MemoBuilder, RTHMemoBuilder,
+};
+use crate::ReservedSubaddresses;
+use mc_account_keys::PublicAddress;
+use mc_transaction_core::{Amount, MemoContext, MemoPayload, NewMemoError};
+
+/// This memo builder attaches 0x0103 Authenticated Invoice Settlement Memos to
+/// normal outputs, and optionally 0x0200 Destination Memos to change outputs.
+///
+/// Usage:
+///
+///   let mut mb = InvoiceSettlementMemoBuilder::new(
+///       SenderMemoCredential::from(&account_key),
+///       invoice_id,
+///   );
+///   mb.enable_destination_memo();
+///
+/// Change outputs are handled exactly as by the [RTHMemoBuilder].
+#[derive(Clone, Debug)]
+pub struct InvoiceSettlementMemoBuilder {
+    // The credential used to form 0x0103 memos
+    sender_cred: SenderMemoCredential,
+    // The id of the invoice being paid
+    invoice_id: [u8; 16],
+    // Tracks the outlay, and builds the destination memo
+    rth_memo_builder: RTHMemoBuilder,
+}
+
+impl InvoiceSettlementMemoBuilder {
+    /// Create an invoice settlement memo builder, given the sender credential
+    /// and the id of the invoice being paid.
+    pub fn new(sender_cred: SenderMemoCredential, invoice_id: [u8; 16]) -> Self {
+        Self {
+            sender_cred,
+            invoice_id,
+            rth_memo_builder: Default::default(),
+        }
+    }
+
+    /// Enable destination memos
+    pub fn enable_destination_memo(&mut self) {
+        self.rth_memo_builder.enable_destination_memo();
+    }
+
+    /// Disable destination memos
+    pub fn disable_destination_memo(&mut self) {
+        self.rth_memo_builder.disable_destination_memo();
+    }
+}
+
+impl MemoBuilder for InvoiceSettlementMemoBuilder {
+    /// Set the fee
+    fn set_fee(&mut self, fee: Amount) -> Result<(), NewMemoError> {
+        self.rth_memo_builder.set_fee(fee)
+    }
+
+    /// Build a memo for a normal output (to another party).
+    fn make_memo_for_output(
+        &mut self,
+        amount: Amount,
+        recipient: &PublicAddress,
+        memo_context: MemoContext,
+    ) -> Result<MemoPayload, NewMemoError> {
+        let tx_public_key = memo_context.tx_public_key;
+        // The RTH memo builder has no sender credential, so this only records
+        // the outlay for the destination memo.
+        self.rth_memo_builder
+            .make_memo_for_output(amount, recipient, memo_context)?;
+        Ok(AuthenticatedInvoiceSettlementMemo::new(
+            &self.sender_cred,
+            recipient.view_public_key(),
+            &tx_public_key.into(),
+            self.invoice_id,
+        )
+        .into())
+    }
+
+    /// Build a memo for a change output (to ourselves).
+    fn make_memo_for_change_output(
+        &mut self,
+        amount: Amount,
+        change_destination: &ReservedSubaddresses,
+        memo_context: MemoContext,
+    ) -> Result<MemoPayload, NewMemoError> {
+        self.rth_memo_builder
+            .make_memo_for_change_output(amount, change_destination, memo_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::build_change_memo_with_amount, DestinationMemo, MemoType};
+    use mc_account_keys::AccountKey;
+    use mc_crypto_keys::RistrettoPublic;
+    use mc_transaction_core::{tokens::Mob, Token};
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_invoice_settlement_memo_builder() {
+        let mut rng: StdRng = SeedableRng::from_seed([5u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+        let bob_addr = bob.default_subaddress();
+        let invoice_id = [7u8; 16];
+
+        let mut builder =
+            InvoiceSettlementMemoBuilder::new(SenderMemoCredential::from(&alice), invoice_id);
+        builder.enable_destination_memo();
+
+        let tx_public_key = RistrettoPublic::from_random(&mut rng);
+        let memo_payload = builder
+            .make_memo_for_output(
+                Amount::new(1000, 0.into()),
+                &bob_addr,
+                MemoContext {
+                    tx_public_key: &tx_public_key,
+                },
+            )
+            .unwrap();
+        match MemoType::try_from(&memo_payload).unwrap() {
+            MemoType::AuthenticatedInvoiceSettlement(memo) => {
+                assert_eq!(memo.invoice_id(), invoice_id);
+                assert!(bool::from(memo.validate(
+                    &alice.default_subaddress(),
+                    &bob.default_subaddress_view_private(),
+                    &tx_public_key.into(),
+                )));
+            }
+            _ => panic!("unexpected memo type"),
+        }
+
+        // Mixing token ids is an error, as with the RTH memo builder
+        assert_eq!(
+            build_change_memo_with_amount(&mut builder, Amount::new(10, 1.into())),
+            Err(NewMemoError::MixedTokenIds)
+        );
+
+        let memo_payload =
+            build_change_memo_with_amount(&mut builder, Amount::new(10, 0.into())).unwrap();
+        let memo = DestinationMemo::from(memo_payload.get_memo_data());
+        assert_eq!(memo.get_total_outlay(), 1000 + Mob::MINIMUM_FEE);
+    }
+}
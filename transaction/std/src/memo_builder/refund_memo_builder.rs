@@ -0,0 +1,173 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Defines the RefundMemoBuilder.
+//! This MemoBuilder policy attaches 0x0102 Authenticated Refund Memos to
+//! normal outputs, for returning funds to whoever sent them.
+
+use super::{
+    memo::{AuthenticatedRefundMemo, SenderMemoCredential},
+    MemoBuilder, RTHMemoBuilder,
+};
+use crate::ReservedSubaddresses;
+use mc_account_keys::PublicAddress;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_transaction_core::{Amount, MemoContext, MemoPayload, NewMemoError};
+
+/// This memo builder attaches 0x0102 Authenticated Refund Memos to normal
+/// outputs, and optionally 0x0200 Destination Memos to change outputs.
+///
+/// Usage:
+///
+///   let mut mb = RefundMemoBuilder::new(
+///       SenderMemoCredential::from(&account_key),
+///       refunded_tx_out.public_key,
+///   );
+///   mb.enable_destination_memo();
+///
+/// The refund should be sent back to the address which sent the refunded
+/// TxOut, so that the recipient can validate the memo and match it to their
+/// payment.
+///
+/// Change outputs are handled exactly as by the [RTHMemoBuilder].
+#[derive(Clone, Debug)]
+pub struct RefundMemoBuilder {
+    // The credential used to form 0x0102 memos
+    sender_cred: SenderMemoCredential,
+    // The public key of the TxOut being refunded
+    refunded_tx_out_public_key: CompressedRistrettoPublic,
+    // Tracks the outlay, and builds the destination memo
+    rth_memo_builder: RTHMemoBuilder,
+}
+
+impl RefundMemoBuilder {
+    /// Create a refund memo builder, given the credential of the address we
+    /// received the refunded TxOut at, and the public key of that TxOut.
+    pub fn new(
+        sender_cred: SenderMemoCredential,
+        refunded_tx_out_public_key: CompressedRistrettoPublic,
+    ) -> Self {
+        Self {
+            sender_cred,
+            refunded_tx_out_public_key,
+            rth_memo_builder: Default::default(),
+        }
+    }
+
+    /// Enable destination memos
+    pub fn enable_destination_memo(&mut self) {
+        self.rth_memo_builder.enable_destination_memo();
+    }
+
+    /// Disable destination memos
+    pub fn disable_destination_memo(&mut self) {
+        self.rth_memo_builder.disable_destination_memo();
+    }
+}
+
+impl MemoBuilder for RefundMemoBuilder {
+    /// Set the fee
+    fn set_fee(&mut self, fee: Amount) -> Result<(), NewMemoError> {
+        self.rth_memo_builder.set_fee(fee)
+    }
+
+    /// Build a memo for a normal output (to another party).
+    fn make_memo_for_output(
+        &mut self,
+        amount: Amount,
+        recipient: &PublicAddress,
+        memo_context: MemoContext,
+    ) -> Result<MemoPayload, NewMemoError> {
+        let tx_public_key = memo_context.tx_public_key;
+        // The RTH memo builder has no sender credential, so this only records
+        // the outlay for the destination memo.
+        self.rth_memo_builder
+            .make_memo_for_output(amount, recipient, memo_context)?;
+        Ok(AuthenticatedRefundMemo::new(
+            &self.sender_cred,
+            recipient.view_public_key(),
+            &tx_public_key.into(),
+            &self.refunded_tx_out_public_key,
+        )
+        .into())
+    }
+
+    /// Build a memo for a change output (to ourselves).
+    fn make_memo_for_change_output(
+        &mut self,
+        amount: Amount,
+        change_destination: &ReservedSubaddresses,
+        memo_context: MemoContext,
+    ) -> Result<MemoPayload, NewMemoError> {
+        self.rth_memo_builder
+            .make_memo_for_change_output(amount, change_destination, memo_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::build_change_memo_with_amount, DestinationMemo, MemoType};
+    use mc_account_keys::AccountKey;
+    use mc_crypto_keys::RistrettoPublic;
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_refund_memo_builder() {
+        let mut rng: StdRng = SeedableRng::from_seed([4u8; 32]);
+        let alice = AccountKey::random(&mut rng);
+        let bob = AccountKey::random(&mut rng);
+        let bob_addr = bob.default_subaddress();
+        let refunded_tx_out_public_key = CompressedRistrettoPublic::from_random(&mut rng);
+
+        let mut builder = RefundMemoBuilder::new(
+            SenderMemoCredential::from(&alice),
+            refunded_tx_out_public_key,
+        );
+        builder.enable_destination_memo();
+        builder.set_fee(Amount::new(5, 0.into())).unwrap();
+
+        let tx_public_key = RistrettoPublic::from_random(&mut rng);
+        let memo_payload = builder
+            .make_memo_for_output(
+                Amount::new(100, 0.into()),
+                &bob_addr,
+                MemoContext {
+                    tx_public_key: &tx_public_key,
+                },
+            )
+            .unwrap();
+        match MemoType::try_from(&memo_payload).unwrap() {
+            MemoType::AuthenticatedRefund(memo) => {
+                assert_eq!(
+                    memo.refunded_tx_out_public_key(),
+                    refunded_tx_out_public_key
+                );
+                assert!(bool::from(memo.validate(
+                    &alice.default_subaddress(),
+                    &bob.default_subaddress_view_private(),
+                    &tx_public_key.into(),
+                )));
+            }
+            _ => panic!("unexpected memo type"),
+        }
+
+        let memo_payload =
+            build_change_memo_with_amount(&mut builder, Amount::new(10, 0.into())).unwrap();
+        let memo = DestinationMemo::from(memo_payload.get_memo_data());
+        assert_eq!(memo.get_total_outlay(), 105);
+        assert_eq!(memo.get_fee(), 5);
+
+        // Outputs can't be created after the change output
+        assert_eq!(
+            builder.make_memo_for_output(
+                Amount::new(100, 0.into()),
+                &bob_addr,
+                MemoContext {
+                    tx_public_key: &tx_public_key,
+                },
+            ),
+            Err(NewMemoError::OutputsAfterChange)
+        );
+    }
+}
@@ -7,6 +7,7 @@
 use displaydoc::Display;
 use mc_watcher::{
     config::WatcherConfig,
+    service::WatcherService,
     verification_reports_collector::VerificationReportsCollector,
     watcher::{SyncResult, Watcher},
     watcher_db::create_or_open_rw_watcher_db,
@@ -39,12 +40,14 @@ fn main() {
         logger.clone(),
     )
     .expect("Could not create or open watcher db");
+    let watcher_service = WatcherService::new(watcher_db.clone(), logger.clone()).into_service();
     let watcher = Watcher::new(watcher_db.clone(), config.store_block_data, logger.clone())
         .expect("Failed creating watcher");
 
     let _verification_reports_collector = <VerificationReportsCollector>::new(
         watcher_db,
         sources_config.sources().to_vec(),
+        config.enclave_policy(),
         config.poll_interval,
         logger.clone(),
     );
@@ -67,6 +70,7 @@ fn main() {
 
     let server_builder = ServerBuilder::new(env)
         .register_service(health_service)
+        .register_service(watcher_service)
         .bind_using_uri(&config.client_listen_uri, logger.clone());
 
     let mut server = server_builder.build().unwrap();
@@ -3,6 +3,7 @@
 
 //! Configuration parameters for the watcher test utility.
 
+use crate::enclave_policy::{EnclavePoliciesConfig, EnclavePolicy};
 use clap::Parser;
 use mc_util_parse::parse_duration_in_seconds;
 use mc_util_uri::{ConsensusClientUri, WatcherUri};
@@ -49,6 +50,25 @@ pub struct WatcherConfig {
         env = "MC_CLIENT_LISTEN_URI"
     )]
     pub client_listen_uri: WatcherUri,
+
+    /// (Optional) The location of the enclave policy file. This file lists the
+    /// enclave measurements and IAS advisories allowed on each network.
+    /// Verification reports collected from nodes are checked against it.
+    #[clap(
+        long,
+        parse(from_os_str),
+        requires = "enclave_policy_network",
+        env = "MC_ENCLAVE_POLICY_PATH"
+    )]
+    pub enclave_policy_path: Option<PathBuf>,
+
+    /// The network whose enclave policy to enforce (e.g. "main" or "test").
+    #[clap(
+        long,
+        requires = "enclave_policy_path",
+        env = "MC_ENCLAVE_POLICY_NETWORK"
+    )]
+    pub enclave_policy_network: Option<String>,
 }
 
 impl WatcherConfig {
@@ -62,6 +82,30 @@ impl WatcherConfig {
         toml::from_str(&data)
             .unwrap_or_else(|err| panic!("failed TOML parsing {:?}: {:?}", self.sources_path, err))
     }
+
+    /// Load the enclave policy of the configured network, if an enclave policy
+    /// file was provided.
+    pub fn enclave_policy(&self) -> Option<EnclavePolicy> {
+        let path = self.enclave_policy_path.as_ref()?;
+        let network = self
+            .enclave_policy_network
+            .as_ref()
+            .expect("enclave policy network is required with an enclave policy file");
+
+        // Read configuration file.
+        let data = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("failed reading {:?}: {:?}", path, err));
+
+        // Parse configuration file.
+        let policies: EnclavePoliciesConfig = toml::from_str(&data)
+            .unwrap_or_else(|err| panic!("failed TOML parsing {:?}: {:?}", path, err));
+
+        Some(
+            policies
+                .network_policy(network)
+                .unwrap_or_else(|err| panic!("invalid enclave policy in {:?}: {}", path, err)),
+        )
+    }
 }
 
 /// A single watched source configuration.
@@ -96,15 +140,19 @@ impl SourceConfig {
     }
 
     /// Get the tx_source_url and ensure it has a trailing slash.
-    /// This is compatible with the behavior inside ReqwestTransactionsFetcher
-    /// and ensures everywhere we use URLs we always have "slash-terminated"
-    /// URLs
+    /// This is compatible with the behavior inside
+    /// ObjectStoreTransactionsFetcher and ensures everywhere we use URLs we
+    /// always have "slash-terminated" URLs
     pub fn tx_source_url(&self) -> Url {
-        let mut url = self.tx_source_url.clone();
-        if !url.ends_with('/') {
-            url.push('/');
+        let mut url = Url::from_str(&self.tx_source_url)
+            .unwrap_or_else(|err| panic!("invalid url {}: {}", self.tx_source_url, err));
+        // The slash goes at the end of the path, so that query parameters (e.g. the
+        // region of an s3:// URL) are kept intact.
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
         }
-        Url::from_str(&url).unwrap_or_else(|err| panic!("invalid url {}: {}", url, err))
+        url
     }
 
     /// Get consensus client URL, if available.
@@ -178,4 +226,19 @@ mod tests {
 
         assert_eq!(config, expected_config);
     }
+
+    #[test]
+    fn tx_source_url_has_trailing_slash() {
+        let source = SourceConfig::new("https://www.source.com/node1".to_owned(), None, None);
+        assert_eq!(
+            source.tx_source_url().as_str(),
+            "https://www.source.com/node1/"
+        );
+
+        let source = SourceConfig::new("s3://bucket/node1?region=us-east-1".to_owned(), None, None);
+        assert_eq!(
+            source.tx_source_url().as_str(),
+            "s3://bucket/node1/?region=us-east-1"
+        );
+    }
 }
@@ -0,0 +1,445 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Enclave policy checks for the verification reports collected from consensus
+//! nodes.
+//!
+//! A policy lists the enclave measurements (MRENCLAVE values, or MRSIGNER /
+//! product id / minimum security version tuples) and the IAS advisories that
+//! are acceptable on a given network. Every collected verification report is
+//! verified against the policy, and the outcome is persisted per node so that
+//! unapproved enclave upgrades and degraded IAS statuses get noticed.
+
+use crate::error::EnclavePolicyError;
+use hex::FromHex;
+use mc_attest_core::{
+    MrEnclave, MrSigner, ProductId, ReportBody, SecurityVersion, VerificationReport,
+    VerificationReportData,
+};
+use mc_attest_verifier::{Error as VerifierError, MrEnclaveVerifier, MrSignerVerifier, Verifier};
+use mc_crypto_keys::Ed25519Public;
+use prost::{Enumeration, Message};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Enclave policies for all networks, as read from the policy file.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EnclavePoliciesConfig {
+    /// Policies by network name.
+    pub networks: BTreeMap<String, EnclavePolicy>,
+}
+
+/// The enclaves that are acceptable on a network.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EnclavePolicy {
+    /// Allowed MRENCLAVE values.
+    #[serde(default)]
+    pub allowed_enclaves: Vec<AllowedEnclave>,
+
+    /// Allowed MRSIGNER values.
+    #[serde(default)]
+    pub allowed_signers: Vec<AllowedSigner>,
+
+    /// Whether debug enclaves are acceptable.
+    #[serde(default)]
+    pub allow_debug: bool,
+}
+
+/// An allowed MRENCLAVE value.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AllowedEnclave {
+    /// Hex-encoded MRENCLAVE.
+    pub mr_enclave: String,
+
+    /// IAS advisories that only require BIOS configuration changes and are
+    /// acceptable for this enclave.
+    #[serde(default)]
+    pub config_advisories: Vec<String>,
+
+    /// IAS advisories this enclave is hardened against.
+    #[serde(default)]
+    pub hardening_advisories: Vec<String>,
+}
+
+/// An allowed MRSIGNER value.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AllowedSigner {
+    /// Hex-encoded MRSIGNER.
+    pub mr_signer: String,
+
+    /// The expected product id.
+    pub product_id: ProductId,
+
+    /// The minimum acceptable enclave security version.
+    pub minimum_svn: SecurityVersion,
+
+    /// IAS advisories that only require BIOS configuration changes and are
+    /// acceptable for this enclave.
+    #[serde(default)]
+    pub config_advisories: Vec<String>,
+
+    /// IAS advisories this enclave is hardened against.
+    #[serde(default)]
+    pub hardening_advisories: Vec<String>,
+}
+
+/// The outcome of checking a verification report against an
+/// [EnclavePolicy].
+#[derive(Clone, Copy, Debug, Enumeration, Eq, Hash, PartialEq)]
+pub enum EnclavePolicyStatus {
+    /// The report satisfies the policy.
+    Compliant = 1,
+
+    /// The report could not be verified (bad signature, malformed contents).
+    InvalidReport = 2,
+
+    /// The enclave measurement is not allowed by the policy.
+    MeasurementNotAllowed = 3,
+
+    /// The enclave measurement is allowed, but the IAS quote status (or the
+    /// enclave debug mode) is worse than the policy allows.
+    StatusNotAllowed = 4,
+}
+
+/// The result of checking the verification report of a node against an
+/// [EnclavePolicy].
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct EnclavePolicyCheck {
+    /// The outcome of the check (an [EnclavePolicyStatus]).
+    #[prost(enumeration = "EnclavePolicyStatus", tag = "1")]
+    pub status: i32,
+
+    /// The block signer the report vouches for.
+    #[prost(message, required, tag = "2")]
+    pub block_signer: Ed25519Public,
+
+    /// The MRENCLAVE found in the report (empty if the report could not be
+    /// parsed).
+    #[prost(bytes, tag = "3")]
+    pub mr_enclave: Vec<u8>,
+
+    /// The MRSIGNER found in the report (empty if the report could not be
+    /// parsed).
+    #[prost(bytes, tag = "4")]
+    pub mr_signer: Vec<u8>,
+
+    /// Human readable description of the outcome.
+    #[prost(string, tag = "5")]
+    pub description: String,
+}
+
+impl EnclavePolicyCheck {
+    /// Get the outcome of the check.
+    pub fn status(&self) -> Option<EnclavePolicyStatus> {
+        EnclavePolicyStatus::from_i32(self.status)
+    }
+
+    /// Whether the report satisfied the policy.
+    pub fn is_compliant(&self) -> bool {
+        self.status() == Some(EnclavePolicyStatus::Compliant)
+    }
+}
+
+impl EnclavePoliciesConfig {
+    /// Get the policy of a given network, making sure it is valid.
+    pub fn network_policy(&self, network: &str) -> Result<EnclavePolicy, EnclavePolicyError> {
+        let policy = self
+            .networks
+            .get(network)
+            .cloned()
+            .ok_or_else(|| EnclavePolicyError::UnknownNetwork(network.to_string()))?;
+        policy.verifier()?;
+        Ok(policy)
+    }
+}
+
+impl EnclavePolicy {
+    /// Construct an attestation verifier that only accepts reports satisfying
+    /// this policy.
+    pub fn verifier(&self) -> Result<Verifier, EnclavePolicyError> {
+        if self.allowed_enclaves.is_empty() && self.allowed_signers.is_empty() {
+            return Err(EnclavePolicyError::NoAllowedMeasurements);
+        }
+
+        let mut verifier = Verifier::default();
+        verifier.debug(self.allow_debug);
+
+        for allowed_enclave in &self.allowed_enclaves {
+            let mut mr_enclave_verifier =
+                MrEnclaveVerifier::new(parse_mr_enclave(&allowed_enclave.mr_enclave)?);
+            mr_enclave_verifier
+                .allow_config_advisories(&as_strs(&allowed_enclave.config_advisories))
+                .allow_hardening_advisories(&as_strs(&allowed_enclave.hardening_advisories));
+            verifier.mr_enclave(mr_enclave_verifier);
+        }
+
+        for allowed_signer in &self.allowed_signers {
+            let mut mr_signer_verifier = MrSignerVerifier::new(
+                parse_mr_signer(&allowed_signer.mr_signer)?,
+                allowed_signer.product_id,
+                allowed_signer.minimum_svn,
+            );
+            mr_signer_verifier
+                .allow_config_advisories(&as_strs(&allowed_signer.config_advisories))
+                .allow_hardening_advisories(&as_strs(&allowed_signer.hardening_advisories));
+            verifier.mr_signer(mr_signer_verifier);
+        }
+
+        Ok(verifier)
+    }
+
+    /// Check whether an enclave measurement is allowed by this policy,
+    /// regardless of its IAS quote status.
+    pub fn allows_measurement(
+        &self,
+        mr_enclave: &MrEnclave,
+        mr_signer: &MrSigner,
+        product_id: ProductId,
+        security_version: SecurityVersion,
+    ) -> Result<bool, EnclavePolicyError> {
+        for allowed_enclave in &self.allowed_enclaves {
+            if &parse_mr_enclave(&allowed_enclave.mr_enclave)? == mr_enclave {
+                return Ok(true);
+            }
+        }
+
+        for allowed_signer in &self.allowed_signers {
+            if &parse_mr_signer(&allowed_signer.mr_signer)? == mr_signer
+                && allowed_signer.product_id == product_id
+                && allowed_signer.minimum_svn <= security_version
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Check the verification report of a node against this policy.
+    pub fn check(
+        &self,
+        block_signer: &Ed25519Public,
+        verification_report: &VerificationReport,
+    ) -> EnclavePolicyCheck {
+        let mut check = EnclavePolicyCheck {
+            block_signer: *block_signer,
+            ..Default::default()
+        };
+
+        let (status, description) = match self
+            .verifier()
+            .map(|verifier| verifier.verify(verification_report))
+        {
+            Err(err) => (EnclavePolicyStatus::InvalidReport, err.to_string()),
+
+            Ok(Ok(report_data)) => {
+                check.set_measurements(&report_data);
+                (
+                    EnclavePolicyStatus::Compliant,
+                    "Report satisfies the enclave policy".to_string(),
+                )
+            }
+
+            Ok(Err(VerifierError::Verification(report_data))) => {
+                let measurements = check.set_measurements(&report_data);
+                match measurements.map(|report_body| {
+                    self.allows_measurement(
+                        &report_body.mr_enclave(),
+                        &report_body.mr_signer(),
+                        report_body.product_id(),
+                        report_body.security_version(),
+                    )
+                }) {
+                    Some(Ok(true)) => (
+                        EnclavePolicyStatus::StatusNotAllowed,
+                        match &report_data.quote_status {
+                            Ok(_) => "Enclave debug mode is not allowed".to_string(),
+                            Err(err) => format!("IAS quote status is not allowed: {}", err),
+                        },
+                    ),
+                    Some(Ok(false)) => (
+                        EnclavePolicyStatus::MeasurementNotAllowed,
+                        format!(
+                            "Enclave measurement is not allowed: MRENCLAVE {} MRSIGNER {}",
+                            hex::encode(&check.mr_enclave),
+                            hex::encode(&check.mr_signer)
+                        ),
+                    ),
+                    Some(Err(err)) => (EnclavePolicyStatus::InvalidReport, err.to_string()),
+                    None => (
+                        EnclavePolicyStatus::InvalidReport,
+                        "Report does not contain a valid quote".to_string(),
+                    ),
+                }
+            }
+
+            Ok(Err(err)) => (
+                EnclavePolicyStatus::InvalidReport,
+                format!("Report verification failed: {}", err),
+            ),
+        };
+
+        check.status = status as i32;
+        check.description = description;
+        check
+    }
+}
+
+impl EnclavePolicyCheck {
+    /// Record the measurements found in the report, if its quote can be
+    /// parsed.
+    fn set_measurements(&mut self, report_data: &VerificationReportData) -> Option<ReportBody> {
+        let report_body = report_data.quote.report_body().ok()?;
+        self.mr_enclave = report_body.mr_enclave().as_ref().to_vec();
+        self.mr_signer = report_body.mr_signer().as_ref().to_vec();
+        Some(report_body)
+    }
+}
+
+fn parse_mr_enclave(src: &str) -> Result<MrEnclave, EnclavePolicyError> {
+    <[u8; 32]>::from_hex(src)
+        .map(MrEnclave::from)
+        .map_err(|_| EnclavePolicyError::InvalidMeasurement(src.to_string()))
+}
+
+fn parse_mr_signer(src: &str) -> Result<MrSigner, EnclavePolicyError> {
+    <[u8; 32]>::from_hex(src)
+        .map(MrSigner::from)
+        .map_err(|_| EnclavePolicyError::InvalidMeasurement(src.to_string()))
+}
+
+fn as_strs(strings: &[String]) -> Vec<&str> {
+    strings.iter().map(String::as_str).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_attest_core::VerificationSignature;
+    use mc_crypto_keys::Ed25519Pair;
+    use mc_util_from_random::FromRandom;
+    use rand_core::SeedableRng;
+    use rand_hc::Hc128Rng;
+
+    const MR_ENCLAVE_HEX: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const MR_SIGNER_HEX: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn test_policy() -> EnclavePolicy {
+        EnclavePolicy {
+            allowed_enclaves: vec![AllowedEnclave {
+                mr_enclave: MR_ENCLAVE_HEX.to_string(),
+                config_advisories: vec!["INTEL-SA-00239".to_string()],
+                hardening_advisories: vec!["INTEL-SA-00334".to_string()],
+            }],
+            allowed_signers: vec![AllowedSigner {
+                mr_signer: MR_SIGNER_HEX.to_string(),
+                product_id: 1,
+                minimum_svn: 3,
+                ..Default::default()
+            }],
+            allow_debug: false,
+        }
+    }
+
+    #[test]
+    fn policies_config_toml() {
+        let input_toml = format!(
+            r#"
+            [networks.main]
+            [[networks.main.allowed_enclaves]]
+            mr_enclave = "{}"
+            config_advisories = ["INTEL-SA-00239"]
+            hardening_advisories = ["INTEL-SA-00334"]
+
+            [[networks.main.allowed_signers]]
+            mr_signer = "{}"
+            product_id = 1
+            minimum_svn = 3
+
+            [networks.test]
+            allow_debug = true
+        "#,
+            MR_ENCLAVE_HEX, MR_SIGNER_HEX
+        );
+        let config: EnclavePoliciesConfig =
+            toml::from_str(&input_toml).expect("failed parsing toml");
+
+        assert_eq!(config.network_policy("main").unwrap(), test_policy());
+
+        // The test network has no allowed measurements.
+        assert_eq!(
+            config.network_policy("test"),
+            Err(EnclavePolicyError::NoAllowedMeasurements)
+        );
+        assert_eq!(
+            config.network_policy("other"),
+            Err(EnclavePolicyError::UnknownNetwork("other".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_measurements_are_rejected() {
+        let mut policy = test_policy();
+        policy.allowed_enclaves[0].mr_enclave = "0102".to_string();
+        assert_eq!(
+            policy.verifier().map(|_| ()),
+            Err(EnclavePolicyError::InvalidMeasurement("0102".to_string()))
+        );
+    }
+
+    #[test]
+    fn allows_measurement() {
+        let policy = test_policy();
+        let mr_enclave = parse_mr_enclave(MR_ENCLAVE_HEX).unwrap();
+        let mr_signer = parse_mr_signer(MR_SIGNER_HEX).unwrap();
+        let other_mr_enclave = MrEnclave::from([3u8; 32]);
+        let other_mr_signer = MrSigner::from([4u8; 32]);
+
+        // An allowed MRENCLAVE is accepted regardless of its signer.
+        assert!(policy
+            .allows_measurement(&mr_enclave, &other_mr_signer, 7, 0)
+            .unwrap());
+
+        // An allowed MRSIGNER is accepted if the product id and version match.
+        assert!(policy
+            .allows_measurement(&other_mr_enclave, &mr_signer, 1, 3)
+            .unwrap());
+        assert!(policy
+            .allows_measurement(&other_mr_enclave, &mr_signer, 1, 4)
+            .unwrap());
+        assert!(!policy
+            .allows_measurement(&other_mr_enclave, &mr_signer, 1, 2)
+            .unwrap());
+        assert!(!policy
+            .allows_measurement(&other_mr_enclave, &mr_signer, 2, 3)
+            .unwrap());
+
+        assert!(!policy
+            .allows_measurement(&other_mr_enclave, &other_mr_signer, 1, 3)
+            .unwrap());
+    }
+
+    #[test]
+    fn unverifiable_reports_are_flagged() {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let block_signer = Ed25519Pair::from_random(&mut rng).public_key();
+        let verification_report = VerificationReport {
+            sig: VerificationSignature::from(vec![1; 32]),
+            chain: vec![vec![2; 16], vec![3; 32]],
+            http_body: "test body".to_owned(),
+        };
+
+        let check = test_policy().check(&block_signer, &verification_report);
+        assert_eq!(check.status(), Some(EnclavePolicyStatus::InvalidReport));
+        assert!(!check.is_compliant());
+        assert_eq!(check.block_signer, block_signer);
+        assert!(check.mr_enclave.is_empty());
+        assert!(check.mr_signer.is_empty());
+
+        // The check survives a round trip through the database encoding.
+        let bytes = mc_util_serial::encode(&check);
+        assert_eq!(
+            mc_util_serial::decode::<EnclavePolicyCheck>(&bytes).unwrap(),
+            check
+        );
+    }
+}
@@ -5,7 +5,7 @@
 use displaydoc::Display;
 use mc_connection::Error as ConnectionError;
 use mc_crypto_keys::KeyError;
-use mc_ledger_sync::ReqwestTransactionsFetcherError;
+use mc_ledger_sync::ObjectStoreTransactionsFetcherError;
 use mc_util_lmdb::MetadataStoreError;
 use std::string::FromUtf8Error;
 
@@ -19,7 +19,7 @@ pub enum WatcherError {
     DB(WatcherDBError),
 
     /// Block fetching failed
-    BlockFetch(ReqwestTransactionsFetcherError),
+    BlockFetch(ObjectStoreTransactionsFetcherError),
 
     /// Connection: {0}
     Connection(ConnectionError),
@@ -40,8 +40,8 @@ impl From<WatcherDBError> for WatcherError {
     }
 }
 
-impl From<ReqwestTransactionsFetcherError> for WatcherError {
-    fn from(src: ReqwestTransactionsFetcherError) -> Self {
+impl From<ObjectStoreTransactionsFetcherError> for WatcherError {
+    fn from(src: ObjectStoreTransactionsFetcherError) -> Self {
         Self::BlockFetch(src)
     }
 }
@@ -149,3 +149,16 @@ impl From<KeyError> for WatcherDBError {
         Self::CryptoKey(src)
     }
 }
+
+/// Enclave policy Errors
+#[derive(Clone, Debug, Display, Eq, PartialEq)]
+pub enum EnclavePolicyError {
+    /// Unknown network: {0}
+    UnknownNetwork(String),
+
+    /// The policy does not allow any enclave measurement
+    NoAllowedMeasurements,
+
+    /// Invalid measurement: {0}
+    InvalidMeasurement(String),
+}
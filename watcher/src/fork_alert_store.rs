@@ -0,0 +1,588 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A store object for detecting forks and equivocation across the watched
+//! sources, and for keeping the evidence when that happens.
+//!
+//! Every block fetched from a source is compared with the blocks other sources
+//! served at the same height, and with the parent block the same source served
+//! before it. Any inconsistency is recorded as a [ForkAlert] that holds both
+//! blocks and their signatures.
+
+use crate::{error::WatcherDBError, watcher_db::BlockSignatureData};
+use lmdb::{Cursor, Database, DatabaseFlags, Environment, RwTransaction, Transaction, WriteFlags};
+use mc_blockchain_types::{Block, BlockData, BlockIndex, BlockSignature};
+use mc_common::logger::{log, Logger};
+use mc_crypto_digestible::{Digestible, MerlinTranscript};
+use mc_util_serial::{decode, encode};
+use prost::{Enumeration, Message};
+use std::{str, sync::Arc};
+use url::Url;
+
+/// Blocks by (block index, tx source url) database name.
+pub const BLOCKS_BY_SOURCE_DB_NAME: &str = "watcher_db:fork_alerts:blocks_by_source";
+
+/// Fork alerts database name.
+pub const FORK_ALERTS_DB_NAME: &str = "watcher_db:fork_alerts:alerts";
+
+/// The kind of inconsistency a [ForkAlert] reports.
+#[derive(Clone, Copy, Debug, Enumeration, Eq, Hash, PartialEq)]
+pub enum ForkAlertKind {
+    /// Two signers produced valid signatures over different blocks at the same
+    /// height.
+    ConflictingBlockSignatures = 1,
+
+    /// Two sources served different blocks at the same height.
+    ConflictingBlocks = 2,
+
+    /// A source served a block that does not chain to the parent block it
+    /// previously served.
+    BrokenChain = 3,
+
+    /// A source served a block whose id or contents hash does not match its
+    /// contents.
+    InvalidBlock = 4,
+}
+
+/// Evidence of a fork or equivocation.
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct ForkAlert {
+    /// The kind of inconsistency (a [ForkAlertKind]).
+    #[prost(enumeration = "ForkAlertKind", tag = "1")]
+    pub kind: i32,
+
+    /// The block index the alert was raised for.
+    #[prost(uint64, tag = "2")]
+    pub block_index: BlockIndex,
+
+    /// The source that served `block`.
+    #[prost(string, tag = "3")]
+    pub src_url: String,
+
+    /// The block that triggered the alert.
+    #[prost(message, required, tag = "4")]
+    pub block: Block,
+
+    /// The signature that came with `block`, if any.
+    #[prost(message, optional, tag = "5")]
+    pub block_signature: Option<BlockSignature>,
+
+    /// The source that served `conflicting_block` (empty if there is none).
+    #[prost(string, tag = "6")]
+    pub conflicting_src_url: String,
+
+    /// The block `block` conflicts with: a different block at the same
+    /// height, or the parent block it does not chain to.
+    #[prost(message, optional, tag = "7")]
+    pub conflicting_block: Option<Block>,
+
+    /// The signature that came with `conflicting_block`, if any.
+    #[prost(message, optional, tag = "8")]
+    pub conflicting_block_signature: Option<BlockSignature>,
+
+    /// Human readable description of the inconsistency.
+    #[prost(string, tag = "9")]
+    pub description: String,
+}
+
+impl ForkAlert {
+    /// Get the kind of the alert.
+    pub fn kind(&self) -> Option<ForkAlertKind> {
+        ForkAlertKind::from_i32(self.kind)
+    }
+}
+
+/// Object for detecting forks and storing [ForkAlert]s.
+#[derive(Clone)]
+pub struct ForkAlertStore {
+    /// Blocks database. Indexed by (block index, tx_src_url) and maps into the
+    /// Block served by that source.
+    blocks_by_source: Database,
+
+    /// Fork alerts database. Indexed by (block index, alert hash) and maps into
+    /// a ForkAlert.
+    fork_alerts: Database,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl ForkAlertStore {
+    /// Create a new ForkAlertStore instance.
+    pub fn new(env: Arc<Environment>, logger: Logger) -> Result<Self, WatcherDBError> {
+        let blocks_by_source = env.open_db(Some(BLOCKS_BY_SOURCE_DB_NAME))?;
+        let fork_alerts = env.open_db(Some(FORK_ALERTS_DB_NAME))?;
+        Ok(Self {
+            blocks_by_source,
+            fork_alerts,
+            logger,
+        })
+    }
+
+    /// Setup the required databases in the LMDB file.
+    pub fn create(env: Arc<Environment>) -> Result<(), WatcherDBError> {
+        env.create_db(Some(BLOCKS_BY_SOURCE_DB_NAME), DatabaseFlags::empty())?;
+        env.create_db(Some(FORK_ALERTS_DB_NAME), DatabaseFlags::empty())?;
+        Ok(())
+    }
+
+    /// Compare a block fetched from `src_url` with what other sources (and the
+    /// same source, for the parent block) served, and remember it for future
+    /// comparisons.
+    ///
+    /// `block_signatures` are the signatures already collected for the same
+    /// block index, and are used as evidence when two sources disagree.
+    ///
+    /// Returns the newly raised alerts, which are also stored in the database.
+    pub fn check_and_add_block<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        src_url: &Url,
+        block_data: &BlockData,
+        block_signatures: &[BlockSignatureData],
+    ) -> Result<Vec<ForkAlert>, WatcherDBError> {
+        let block = block_data.block();
+        let signature = block_data.signature();
+        let mut alerts = Vec::new();
+
+        // The block needs to be consistent with its contents.
+        if !block.is_block_id_valid() || block.contents_hash != block_data.contents().hash() {
+            alerts.push(ForkAlert {
+                kind: ForkAlertKind::InvalidBlock as i32,
+                block_index: block.index,
+                src_url: src_url.to_string(),
+                block: block.clone(),
+                block_signature: signature.cloned(),
+                description: format!(
+                    "Block {} from {} has an id or contents hash that does not match its contents",
+                    block.index, src_url
+                ),
+                ..Default::default()
+            });
+        }
+
+        // The block needs to chain to the parent served by the same source.
+        if block.index > 0 {
+            if let Some(parent) = self.get_block(db_txn, src_url, block.index - 1)? {
+                if parent.id != block.parent_id {
+                    alerts.push(ForkAlert {
+                        kind: ForkAlertKind::BrokenChain as i32,
+                        block_index: block.index,
+                        src_url: src_url.to_string(),
+                        block: block.clone(),
+                        block_signature: signature.cloned(),
+                        conflicting_src_url: src_url.to_string(),
+                        description: format!(
+                            "Block {} from {} has parent id {} but the previous block is {}",
+                            block.index, src_url, block.parent_id, parent.id
+                        ),
+                        conflicting_block: Some(parent),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        // All sources need to agree on the block at this height.
+        for (other_src_url, other_block) in self.get_blocks_by_index(db_txn, block.index)? {
+            if other_block.id == block.id {
+                continue;
+            }
+
+            let other_signature = block_signatures
+                .iter()
+                .find(|signature_data| signature_data.src_url == other_src_url.as_str())
+                .map(|signature_data| signature_data.block_signature.clone());
+
+            // If both blocks carry a valid signature, two signers signed different blocks
+            // at the same height.
+            let both_signed = signature.map_or(false, |sig| sig.verify(block).is_ok())
+                && other_signature
+                    .as_ref()
+                    .map_or(false, |sig| sig.verify(&other_block).is_ok());
+            let (kind, description) = if both_signed {
+                (
+                    ForkAlertKind::ConflictingBlockSignatures,
+                    format!(
+                        "Block {}: {} signed {} but {} signed {}",
+                        block.index, src_url, block.id, other_src_url, other_block.id
+                    ),
+                )
+            } else {
+                (
+                    ForkAlertKind::ConflictingBlocks,
+                    format!(
+                        "Block {}: {} served {} but {} served {}",
+                        block.index, src_url, block.id, other_src_url, other_block.id
+                    ),
+                )
+            };
+
+            alerts.push(ForkAlert {
+                kind: kind as i32,
+                block_index: block.index,
+                src_url: src_url.to_string(),
+                block: block.clone(),
+                block_signature: signature.cloned(),
+                conflicting_src_url: other_src_url.to_string(),
+                conflicting_block: Some(other_block),
+                conflicting_block_signature: other_signature,
+                description,
+            });
+        }
+
+        // Remember the block so that later blocks can be compared against it. If the
+        // source already served a different block at this height, the first one is kept
+        // (the discrepancy was reported above).
+        let mut key_bytes = block.index.to_be_bytes().to_vec();
+        key_bytes.extend(src_url.as_str().as_bytes());
+        match db_txn.put(
+            self.blocks_by_source,
+            &key_bytes,
+            &encode(block),
+            WriteFlags::NO_OVERWRITE,
+        ) {
+            Ok(()) | Err(lmdb::Error::KeyExist) => {}
+            Err(err) => Err(err)?,
+        };
+
+        // Store the alerts, skipping ones we already know about.
+        let mut new_alerts = Vec::new();
+        for alert in alerts {
+            if self.add_fork_alert(db_txn, &alert)? {
+                new_alerts.push(alert);
+            }
+        }
+        Ok(new_alerts)
+    }
+
+    /// Get fork alerts raised for blocks at or above `start_block_index`,
+    /// ordered by block index.
+    pub fn get_fork_alerts(
+        &self,
+        db_txn: &impl Transaction,
+        start_block_index: BlockIndex,
+        limit: Option<usize>,
+    ) -> Result<Vec<ForkAlert>, WatcherDBError> {
+        let mut cursor = db_txn.open_ro_cursor(self.fork_alerts)?;
+        cursor
+            .iter_from(&start_block_index.to_be_bytes())
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|result| {
+                let (_key_bytes, value_bytes) = result?;
+                Ok(decode(value_bytes)?)
+            })
+            .collect()
+    }
+
+    /// Remove all blocks associated with a given source url.
+    /// Note that this assumes blocks where added in a sequential order, and
+    /// that there are no gaps (no blocks were skipped).
+    /// Fork alerts are kept since they are evidence of what the source served.
+    pub fn remove_all_for_source_url<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        src_url: &Url,
+        last_synced_block_index: u64,
+    ) -> Result<(), WatcherDBError> {
+        let mut block_index: u64 = 0;
+        loop {
+            let mut key_bytes = block_index.to_be_bytes().to_vec();
+            key_bytes.extend(src_url.as_str().as_bytes());
+
+            match db_txn.del(self.blocks_by_source, &key_bytes, None) {
+                Ok(()) => {}
+                Err(lmdb::Error::NotFound) => {
+                    if block_index > last_synced_block_index {
+                        break;
+                    }
+                }
+                Err(err) => Err(err)?,
+            }
+
+            block_index += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Store a fork alert. Returns false if the alert was already stored.
+    fn add_fork_alert<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        alert: &ForkAlert,
+    ) -> Result<bool, WatcherDBError> {
+        let value_bytes = encode(alert);
+        let hash: [u8; 32] = value_bytes.digest32::<MerlinTranscript>(b"fork_alert");
+
+        let mut key_bytes = alert.block_index.to_be_bytes().to_vec();
+        key_bytes.extend(hash);
+
+        match db_txn.put(
+            self.fork_alerts,
+            &key_bytes,
+            &value_bytes,
+            WriteFlags::NO_OVERWRITE,
+        ) {
+            Ok(()) => {
+                log::debug!(
+                    self.logger,
+                    "Stored fork alert for block {}: {}",
+                    alert.block_index,
+                    alert.description
+                );
+                Ok(true)
+            }
+            Err(lmdb::Error::KeyExist) => Ok(false),
+            Err(err) => Err(err)?,
+        }
+    }
+
+    /// Get the block a given source served at a given block index, if any.
+    fn get_block(
+        &self,
+        db_txn: &impl Transaction,
+        src_url: &Url,
+        block_index: BlockIndex,
+    ) -> Result<Option<Block>, WatcherDBError> {
+        let mut key_bytes = block_index.to_be_bytes().to_vec();
+        key_bytes.extend(src_url.as_str().as_bytes());
+
+        match db_txn.get(self.blocks_by_source, &key_bytes) {
+            Ok(value_bytes) => Ok(Some(decode(value_bytes)?)),
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(err) => Err(err)?,
+        }
+    }
+
+    /// Get all blocks served at a given block index, together with the source
+    /// that served them.
+    fn get_blocks_by_index(
+        &self,
+        db_txn: &impl Transaction,
+        block_index: BlockIndex,
+    ) -> Result<Vec<(Url, Block)>, WatcherDBError> {
+        let mut cursor = db_txn.open_ro_cursor(self.blocks_by_source)?;
+        let first_key_bytes = block_index.to_be_bytes();
+
+        let mut results = Vec::new();
+        for (key_bytes, value_bytes) in cursor.iter_from(&first_key_bytes).filter_map(Result::ok) {
+            // Remember that the key is the block index, followed by the source url.
+            if key_bytes.len() < first_key_bytes.len() {
+                continue;
+            }
+
+            let index_bytes = &key_bytes[..first_key_bytes.len()];
+            if index_bytes != first_key_bytes {
+                // Moved to the next index, we're done.
+                break;
+            }
+
+            let tx_source_url_bytes = &key_bytes[first_key_bytes.len()..];
+            let tx_source_url = Url::parse(str::from_utf8(tx_source_url_bytes)?)?;
+
+            results.push((tx_source_url, decode(value_bytes)?));
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher_db::tests::{setup_blocks, setup_watcher_db};
+    use mc_blockchain_types::{compute_block_id, BlockContents};
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_crypto_keys::Ed25519Pair;
+    use mc_util_from_random::FromRandom;
+    use rand_core::SeedableRng;
+    use rand_hc::Hc128Rng;
+
+    fn signed_block_data(
+        block: &Block,
+        contents: &BlockContents,
+        signer: &Ed25519Pair,
+    ) -> BlockData {
+        BlockData::new(
+            block.clone(),
+            contents.clone(),
+            Some(BlockSignature::from_block_and_keypair(block, signer).unwrap()),
+            None,
+        )
+    }
+
+    #[test_with_logger]
+    fn consistent_sources_raise_no_alerts(logger: Logger) {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger);
+        let blocks = setup_blocks();
+        let signer1 = Ed25519Pair::from_random(&mut rng);
+        let signer2 = Ed25519Pair::from_random(&mut rng);
+
+        for (block, contents) in &blocks {
+            for (url, signer) in [(&url1, &signer1), (&url2, &signer2)] {
+                let block_data = signed_block_data(block, contents, signer);
+                assert_eq!(
+                    watcher_db.check_block_for_forks(url, &block_data).unwrap(),
+                    vec![]
+                );
+                watcher_db
+                    .add_block_signature(
+                        url,
+                        block.index,
+                        block_data.signature().unwrap().clone(),
+                        "filename".to_string(),
+                    )
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(watcher_db.get_fork_alerts(0, None).unwrap(), vec![]);
+    }
+
+    #[test_with_logger]
+    fn conflicting_signatures_are_detected(logger: Logger) {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger);
+        let blocks = setup_blocks();
+        let signer1 = Ed25519Pair::from_random(&mut rng);
+        let signer2 = Ed25519Pair::from_random(&mut rng);
+
+        // Source 1 serves block 1, source 2 serves a different block at the same
+        // height.
+        let (block1, contents1) = &blocks[1];
+        let (other_block, other_contents) = &blocks[2];
+        let mut forked_block = other_block.clone();
+        forked_block.index = block1.index;
+
+        let block_data1 = signed_block_data(block1, contents1, &signer1);
+        assert_eq!(
+            watcher_db
+                .check_block_for_forks(&url1, &block_data1)
+                .unwrap(),
+            vec![]
+        );
+        watcher_db
+            .add_block_signature(
+                &url1,
+                1,
+                block_data1.signature().unwrap().clone(),
+                "filename".to_string(),
+            )
+            .unwrap();
+
+        let block_data2 = signed_block_data(&forked_block, other_contents, &signer2);
+        let alerts = watcher_db
+            .check_block_for_forks(&url2, &block_data2)
+            .unwrap();
+
+        // The forked block also has an id that does not match its contents, since we
+        // changed its index.
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].kind(), Some(ForkAlertKind::InvalidBlock));
+
+        let alert = &alerts[1];
+        assert_eq!(
+            alert.kind(),
+            Some(ForkAlertKind::ConflictingBlockSignatures)
+        );
+        assert_eq!(alert.block_index, 1);
+        assert_eq!(alert.src_url, url2.to_string());
+        assert_eq!(alert.block, forked_block);
+        assert_eq!(alert.block_signature.as_ref(), block_data2.signature());
+        assert_eq!(alert.conflicting_src_url, url1.to_string());
+        assert_eq!(alert.conflicting_block.as_ref(), Some(block1));
+        assert_eq!(
+            alert.conflicting_block_signature.as_ref(),
+            block_data1.signature()
+        );
+
+        assert_eq!(watcher_db.get_fork_alerts(0, None).unwrap(), alerts);
+        assert_eq!(watcher_db.get_fork_alerts(2, None).unwrap(), vec![]);
+        assert_eq!(watcher_db.get_fork_alerts(0, Some(1)).unwrap().len(), 1);
+
+        // Checking the same block again does not raise the alerts again.
+        assert_eq!(
+            watcher_db
+                .check_block_for_forks(&url2, &block_data2)
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(watcher_db.get_fork_alerts(0, None).unwrap().len(), 2);
+    }
+
+    #[test_with_logger]
+    fn unsigned_conflicting_blocks_are_detected(logger: Logger) {
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger);
+        let blocks = setup_blocks();
+
+        let (block1, contents1) = &blocks[1];
+        let mut forked_block = block1.clone();
+        forked_block.cumulative_txo_count += 1;
+
+        let block_data1 = BlockData::new(block1.clone(), contents1.clone(), None, None);
+        let block_data2 = BlockData::new(forked_block, contents1.clone(), None, None);
+        watcher_db
+            .check_block_for_forks(&url1, &block_data1)
+            .unwrap();
+        let alerts = watcher_db
+            .check_block_for_forks(&url2, &block_data2)
+            .unwrap();
+
+        // The forked block's id no longer matches its contents either.
+        assert_eq!(
+            alerts.iter().map(ForkAlert::kind).collect::<Vec<_>>(),
+            vec![
+                Some(ForkAlertKind::InvalidBlock),
+                Some(ForkAlertKind::ConflictingBlocks)
+            ]
+        );
+    }
+
+    #[test_with_logger]
+    fn broken_chain_is_detected(logger: Logger) {
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone()], logger);
+        let blocks = setup_blocks();
+
+        let (block1, contents1) = &blocks[1];
+        let (block3, contents3) = &blocks[3];
+
+        // Block 3 claims to be at height 2, following block 1, but its parent is block
+        // 2.
+        let mut block2 = block3.clone();
+        block2.index = 2;
+        block2.id = compute_block_id(
+            block2.version,
+            &block2.parent_id,
+            block2.index,
+            block2.cumulative_txo_count,
+            &block2.root_element,
+            &block2.contents_hash,
+        );
+
+        watcher_db
+            .check_block_for_forks(
+                &url1,
+                &BlockData::new(block1.clone(), contents1.clone(), None, None),
+            )
+            .unwrap();
+        let alerts = watcher_db
+            .check_block_for_forks(
+                &url1,
+                &BlockData::new(block2.clone(), contents3.clone(), None, None),
+            )
+            .unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind(), Some(ForkAlertKind::BrokenChain));
+        assert_eq!(alerts[0].block, block2);
+        assert_eq!(alerts[0].conflicting_block.as_ref(), Some(block1));
+    }
+}
@@ -6,8 +6,11 @@
 
 pub mod block_data_store;
 pub mod config;
+pub mod enclave_policy;
 pub mod error;
+pub mod fork_alert_store;
 pub mod metrics;
+pub mod service;
 pub mod verification_reports_collector;
 pub mod watcher;
 pub mod watcher_db;
@@ -2,8 +2,9 @@
 
 //! Watcher metrics comparing ledger height and block height
 
+use crate::enclave_policy::EnclavePolicyCheck;
 use mc_common::HashMap;
-use mc_util_metrics::{IntGauge, OpMetrics};
+use mc_util_metrics::{IntCounter, IntGauge, OpMetrics};
 use url::Url;
 
 lazy_static::lazy_static! {
@@ -15,6 +16,9 @@ lazy_static::lazy_static! {
 pub struct WatcherMetrics {
     /// Number of blocks in the ledger
     ledger_block_height: IntGauge,
+
+    /// Number of fork alerts raised
+    fork_alerts: IntCounter,
 }
 
 impl Default for WatcherMetrics {
@@ -27,8 +31,10 @@ impl WatcherMetrics {
     /// Initialize new metrics object
     pub fn new() -> Self {
         let ledger_block_height = COLLECTOR.gauge("ledger_block_height");
+        let fork_alerts = COLLECTOR.counter("fork_alerts");
         Self {
             ledger_block_height,
+            fork_alerts,
         }
     }
 
@@ -37,6 +43,11 @@ impl WatcherMetrics {
         self.ledger_block_height.set(ledger_height);
     }
 
+    /// Record newly raised fork alerts
+    pub fn inc_fork_alerts(&self, num_alerts: usize) {
+        self.fork_alerts.inc_by(num_alerts as u64);
+    }
+
     /// Measure blocks synced so far for each peer
     pub fn collect_peer_blocks_synced(&self, peer_sync_states: HashMap<Url, Option<u64>>) {
         peer_sync_states.iter().for_each(|(url, num_blocks)| {
@@ -45,4 +56,14 @@ impl WatcherMetrics {
                 .set(num_blocks.unwrap_or(0) as i64);
         });
     }
+
+    /// Record the outcome of the latest enclave policy check for a peer
+    pub fn set_enclave_policy_check(&self, url: &Url, check: &EnclavePolicyCheck) {
+        COLLECTOR
+            .peer_gauge("enclave_policy_status", url.as_str())
+            .set(check.status as i64);
+        COLLECTOR
+            .peer_gauge("enclave_policy_violation", url.as_str())
+            .set(if check.is_compliant() { 0 } else { 1 });
+    }
 }
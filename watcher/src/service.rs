@@ -0,0 +1,632 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Watcher GRPC service implementation.
+
+use crate::{
+    error::WatcherDBError,
+    fork_alert_store::{ForkAlert, ForkAlertKind},
+    watcher_db::{BlockSignatureData, WatcherDB},
+};
+use grpcio::{RpcContext, RpcStatus, RpcStatusCode, Service, UnarySink};
+use mc_common::logger::Logger;
+use mc_crypto_keys::Ed25519Public;
+use mc_util_grpc::{rpc_logger, send_result};
+use mc_watcher_grpc_api::{
+    empty::Empty,
+    watcher::TimestampResultCode as GrpcTimestampResultCode,
+    watcher_api::{
+        BlockSignatureData as GrpcBlockSignatureData, BlockSigner, ForkAlert as GrpcForkAlert,
+        ForkAlertKind as GrpcForkAlertKind, GetBlockSignaturesRequest, GetBlockSignaturesResponse,
+        GetBlockSignersRequest, GetBlockSignersResponse, GetBlockTimestampRequest,
+        GetBlockTimestampResponse, GetForkAlertsRequest, GetForkAlertsResponse,
+        GetHighestCommonBlockResponse, GetSyncStatusResponse,
+        GetVerificationReportsForSignerRequest, GetVerificationReportsForSignerResponse,
+        SignerVerificationReport, SourceSyncStatus,
+    },
+    watcher_api_grpc::{create_watcher_api, WatcherApi},
+};
+
+/// Watcher GRPC service implementation.
+#[derive(Clone)]
+pub struct WatcherService {
+    /// Watcher database.
+    watcher_db: WatcherDB,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl WatcherService {
+    /// Create a new watcher service.
+    pub fn new(watcher_db: WatcherDB, logger: Logger) -> Self {
+        Self { watcher_db, logger }
+    }
+
+    /// Convert into a grpc service
+    pub fn into_service(self) -> Service {
+        create_watcher_api(self)
+    }
+
+    fn get_fork_alerts_impl(
+        &self,
+        req: &GetForkAlertsRequest,
+    ) -> Result<GetForkAlertsResponse, RpcStatus> {
+        let limit = match req.limit {
+            0 => None,
+            limit => Some(limit as usize),
+        };
+
+        let alerts = self
+            .watcher_db
+            .get_fork_alerts(req.start_block_index, limit)
+            .map_err(internal_error)?;
+
+        let mut resp = GetForkAlertsResponse::new();
+        resp.set_alerts(alerts.iter().map(GrpcForkAlert::from).collect());
+        Ok(resp)
+    }
+
+    fn get_block_signatures_impl(
+        &self,
+        req: &GetBlockSignaturesRequest,
+    ) -> Result<GetBlockSignaturesResponse, RpcStatus> {
+        let signatures = self
+            .watcher_db
+            .get_block_signatures(req.block_index)
+            .map_err(internal_error)?;
+
+        let mut resp = GetBlockSignaturesResponse::new();
+        resp.set_signatures(
+            signatures
+                .iter()
+                .map(GrpcBlockSignatureData::from)
+                .collect(),
+        );
+        Ok(resp)
+    }
+
+    fn get_block_signers_impl(
+        &self,
+        req: &GetBlockSignersRequest,
+    ) -> Result<GetBlockSignersResponse, RpcStatus> {
+        let signatures = self
+            .watcher_db
+            .get_block_signatures(req.block_index)
+            .map_err(internal_error)?;
+
+        let mut resp = GetBlockSignersResponse::new();
+        resp.set_signers(
+            signatures
+                .iter()
+                .map(|signature_data| {
+                    let mut signer = BlockSigner::new();
+                    signer.set_src_url(signature_data.src_url.clone());
+                    signer.set_signer(signature_data.block_signature.signer().into());
+                    signer.set_signed_at(signature_data.block_signature.signed_at());
+                    signer
+                })
+                .collect(),
+        );
+        Ok(resp)
+    }
+
+    fn get_block_timestamp_impl(
+        &self,
+        req: &GetBlockTimestampRequest,
+    ) -> Result<GetBlockTimestampResponse, RpcStatus> {
+        let (timestamp, timestamp_result_code) = self
+            .watcher_db
+            .get_block_timestamp(req.block_index)
+            .map_err(internal_error)?;
+
+        let mut resp = GetBlockTimestampResponse::new();
+        resp.set_timestamp(timestamp);
+        resp.set_timestamp_result_code(GrpcTimestampResultCode::from(&timestamp_result_code));
+        Ok(resp)
+    }
+
+    fn get_highest_common_block_impl(&self) -> Result<GetHighestCommonBlockResponse, RpcStatus> {
+        let block_index = self
+            .watcher_db
+            .highest_common_block()
+            .map_err(internal_error)?;
+
+        let mut resp = GetHighestCommonBlockResponse::new();
+        resp.set_block_index(block_index);
+        Ok(resp)
+    }
+
+    fn get_verification_reports_for_signer_impl(
+        &self,
+        req: &GetVerificationReportsForSignerRequest,
+    ) -> Result<GetVerificationReportsForSignerResponse, RpcStatus> {
+        let signer = Ed25519Public::try_from(req.get_signer()).map_err(|err| {
+            RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                format!("Invalid signer: {}", err),
+            )
+        })?;
+
+        let mut reports_by_url = self
+            .watcher_db
+            .get_verification_reports_for_signer(&signer)
+            .map_err(internal_error)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        reports_by_url.sort_by(|(url1, _), (url2, _)| url1.cmp(url2));
+
+        let mut resp = GetVerificationReportsForSignerResponse::new();
+        resp.set_reports(
+            reports_by_url
+                .into_iter()
+                .flat_map(|(src_url, reports)| {
+                    reports.into_iter().map(move |opt_report| {
+                        let mut report = SignerVerificationReport::new();
+                        report.set_src_url(src_url.to_string());
+                        if let Some(verification_report) = opt_report.as_ref() {
+                            report.set_report(verification_report.into());
+                        }
+                        report
+                    })
+                })
+                .collect(),
+        );
+        Ok(resp)
+    }
+
+    fn get_sync_status_impl(&self) -> Result<GetSyncStatusResponse, RpcStatus> {
+        let mut last_synced = self
+            .watcher_db
+            .last_synced_blocks()
+            .map_err(internal_error)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        last_synced.sort_by(|(url1, _), (url2, _)| url1.cmp(url2));
+
+        let highest_common_block = self
+            .watcher_db
+            .highest_common_block()
+            .map_err(internal_error)?;
+
+        let mut resp = GetSyncStatusResponse::new();
+        resp.set_sources(
+            last_synced
+                .into_iter()
+                .map(|(src_url, opt_block_index)| {
+                    let mut status = SourceSyncStatus::new();
+                    status.set_src_url(src_url.to_string());
+                    status.set_has_synced(opt_block_index.is_some());
+                    status.set_last_synced_block_index(opt_block_index.unwrap_or_default());
+                    status
+                })
+                .collect(),
+        );
+        resp.set_highest_common_block(highest_common_block);
+        Ok(resp)
+    }
+}
+
+/// Map a database error into an INTERNAL grpc error.
+fn internal_error(err: WatcherDBError) -> RpcStatus {
+    RpcStatus::with_message(RpcStatusCode::INTERNAL, err.to_string())
+}
+
+impl WatcherApi for WatcherService {
+    fn get_fork_alerts(
+        &mut self,
+        ctx: RpcContext,
+        req: GetForkAlertsRequest,
+        sink: UnarySink<GetForkAlertsResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_fork_alerts_impl(&req), &logger)
+    }
+
+    fn get_block_signatures(
+        &mut self,
+        ctx: RpcContext,
+        req: GetBlockSignaturesRequest,
+        sink: UnarySink<GetBlockSignaturesResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_block_signatures_impl(&req), &logger)
+    }
+
+    fn get_block_signers(
+        &mut self,
+        ctx: RpcContext,
+        req: GetBlockSignersRequest,
+        sink: UnarySink<GetBlockSignersResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_block_signers_impl(&req), &logger)
+    }
+
+    fn get_block_timestamp(
+        &mut self,
+        ctx: RpcContext,
+        req: GetBlockTimestampRequest,
+        sink: UnarySink<GetBlockTimestampResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_block_timestamp_impl(&req), &logger)
+    }
+
+    fn get_highest_common_block(
+        &mut self,
+        ctx: RpcContext,
+        _req: Empty,
+        sink: UnarySink<GetHighestCommonBlockResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_highest_common_block_impl(), &logger)
+    }
+
+    fn get_verification_reports_for_signer(
+        &mut self,
+        ctx: RpcContext,
+        req: GetVerificationReportsForSignerRequest,
+        sink: UnarySink<GetVerificationReportsForSignerResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(
+            ctx,
+            sink,
+            self.get_verification_reports_for_signer_impl(&req),
+            &logger,
+        )
+    }
+
+    fn get_sync_status(
+        &mut self,
+        ctx: RpcContext,
+        _req: Empty,
+        sink: UnarySink<GetSyncStatusResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_sync_status_impl(), &logger)
+    }
+}
+
+impl From<&BlockSignatureData> for GrpcBlockSignatureData {
+    fn from(src: &BlockSignatureData) -> Self {
+        let mut dst = GrpcBlockSignatureData::new();
+        dst.set_src_url(src.src_url.clone());
+        dst.set_archive_filename(src.archive_filename.clone());
+        dst.set_block_signature((&src.block_signature).into());
+        dst
+    }
+}
+
+impl From<&ForkAlert> for GrpcForkAlert {
+    fn from(src: &ForkAlert) -> Self {
+        let mut dst = GrpcForkAlert::new();
+        dst.set_kind(match src.kind() {
+            Some(ForkAlertKind::ConflictingBlockSignatures) => {
+                GrpcForkAlertKind::ConflictingBlockSignatures
+            }
+            Some(ForkAlertKind::ConflictingBlocks) => GrpcForkAlertKind::ConflictingBlocks,
+            Some(ForkAlertKind::BrokenChain) => GrpcForkAlertKind::BrokenChain,
+            Some(ForkAlertKind::InvalidBlock) => GrpcForkAlertKind::InvalidBlock,
+            None => GrpcForkAlertKind::UnusedForkAlertKind,
+        });
+        dst.set_block_index(src.block_index);
+        dst.set_src_url(src.src_url.clone());
+        dst.set_block((&src.block).into());
+        if let Some(block_signature) = src.block_signature.as_ref() {
+            dst.set_block_signature(block_signature.into());
+        }
+        dst.set_conflicting_src_url(src.conflicting_src_url.clone());
+        if let Some(conflicting_block) = src.conflicting_block.as_ref() {
+            dst.set_conflicting_block(conflicting_block.into());
+        }
+        if let Some(conflicting_block_signature) = src.conflicting_block_signature.as_ref() {
+            dst.set_conflicting_block_signature(conflicting_block_signature.into());
+        }
+        dst.set_description(src.description.clone());
+        dst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher_db::tests::{setup_blocks, setup_watcher_db};
+    use grpcio::{ChannelBuilder, Environment, Server, ServerBuilder};
+    use mc_attest_core::{VerificationReport, VerificationSignature};
+    use mc_blockchain_types::{BlockData, BlockSignature};
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_crypto_keys::Ed25519Pair;
+    use mc_util_from_random::FromRandom;
+    use mc_watcher_grpc_api::watcher_api_grpc::WatcherApiClient;
+    use rand_core::SeedableRng;
+    use rand_hc::Hc128Rng;
+    use std::sync::Arc;
+    use url::Url;
+
+    /// Starts the service on localhost and connects a client to it.
+    fn get_client_server(watcher_db: &WatcherDB, logger: &Logger) -> (WatcherApiClient, Server) {
+        let service = WatcherService::new(watcher_db.clone(), logger.clone()).into_service();
+        let env = Arc::new(Environment::new(1));
+        let mut server = ServerBuilder::new(env.clone())
+            .register_service(service)
+            .bind("127.0.0.1", 0)
+            .build()
+            .unwrap();
+        server.start();
+        let (_, port) = server.bind_addrs().next().unwrap();
+        let ch = ChannelBuilder::new(env).connect(&format!("127.0.0.1:{}", port));
+        let client = WatcherApiClient::new(ch);
+        (client, server)
+    }
+
+    #[test_with_logger]
+    fn test_get_fork_alerts(logger: Logger) {
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger.clone());
+        let (client, _server) = get_client_server(&watcher_db, &logger);
+
+        // No alerts initially.
+        let response = client
+            .get_fork_alerts(&GetForkAlertsRequest::default())
+            .unwrap();
+        assert!(response.get_alerts().is_empty());
+
+        // Two sources serving different blocks at the same height raise an alert.
+        let blocks = setup_blocks();
+        let (block, contents) = &blocks[1];
+        let mut forked_block = block.clone();
+        forked_block.cumulative_txo_count += 1;
+        watcher_db
+            .check_block_for_forks(
+                &url1,
+                &BlockData::new(block.clone(), contents.clone(), None, None),
+            )
+            .unwrap();
+        let alerts = watcher_db
+            .check_block_for_forks(
+                &url2,
+                &BlockData::new(forked_block, contents.clone(), None, None),
+            )
+            .unwrap();
+        assert_eq!(alerts.len(), 2);
+
+        let response = client
+            .get_fork_alerts(&GetForkAlertsRequest::default())
+            .unwrap();
+        assert_eq!(
+            response.get_alerts(),
+            alerts.iter().map(GrpcForkAlert::from).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            response.get_alerts()[1].get_kind(),
+            GrpcForkAlertKind::ConflictingBlocks
+        );
+        assert_eq!(
+            response.get_alerts()[1].get_conflicting_block(),
+            &mc_api::blockchain::Block::from(block)
+        );
+
+        // Limit and start block index are honored.
+        let mut request = GetForkAlertsRequest::new();
+        request.set_limit(1);
+        let response = client.get_fork_alerts(&request).unwrap();
+        assert_eq!(response.get_alerts().len(), 1);
+
+        let mut request = GetForkAlertsRequest::new();
+        request.set_start_block_index(2);
+        let response = client.get_fork_alerts(&request).unwrap();
+        assert!(response.get_alerts().is_empty());
+    }
+
+    #[test_with_logger]
+    fn test_get_block_signatures_and_signers(logger: Logger) {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger.clone());
+        let (client, _server) = get_client_server(&watcher_db, &logger);
+
+        let blocks = setup_blocks();
+        let signing_key_a = Ed25519Pair::from_random(&mut rng);
+        let signing_key_b = Ed25519Pair::from_random(&mut rng);
+        let signature_a =
+            BlockSignature::from_block_and_keypair(&blocks[1].0, &signing_key_a).unwrap();
+        let signature_b =
+            BlockSignature::from_block_and_keypair(&blocks[1].0, &signing_key_b).unwrap();
+        watcher_db
+            .add_block_signature(&url1, 1, signature_a.clone(), "block1".to_string())
+            .unwrap();
+        watcher_db
+            .add_block_signature(&url2, 1, signature_b.clone(), "block1".to_string())
+            .unwrap();
+
+        let mut request = GetBlockSignaturesRequest::new();
+        request.set_block_index(1);
+        let response = client.get_block_signatures(&request).unwrap();
+        assert_eq!(
+            response.get_signatures(),
+            watcher_db
+                .get_block_signatures(1)
+                .unwrap()
+                .iter()
+                .map(GrpcBlockSignatureData::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(response.get_signatures().len(), 2);
+
+        let mut request = GetBlockSignersRequest::new();
+        request.set_block_index(1);
+        let response = client.get_block_signers(&request).unwrap();
+        let mut signers = response
+            .get_signers()
+            .iter()
+            .map(|signer| {
+                (
+                    signer.get_src_url().to_string(),
+                    Ed25519Public::try_from(signer.get_signer()).unwrap(),
+                    signer.get_signed_at(),
+                )
+            })
+            .collect::<Vec<_>>();
+        signers.sort();
+        assert_eq!(
+            signers,
+            vec![
+                (
+                    url1.to_string(),
+                    signing_key_a.public_key(),
+                    signature_a.signed_at()
+                ),
+                (
+                    url2.to_string(),
+                    signing_key_b.public_key(),
+                    signature_b.signed_at()
+                ),
+            ]
+        );
+
+        // Nothing was signed at block 2.
+        let mut request = GetBlockSignersRequest::new();
+        request.set_block_index(2);
+        let response = client.get_block_signers(&request).unwrap();
+        assert!(response.get_signers().is_empty());
+    }
+
+    #[test_with_logger]
+    fn test_get_block_timestamp_and_sync_status(logger: Logger) {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger.clone());
+        let (client, _server) = get_client_server(&watcher_db, &logger);
+
+        // Nothing synced yet.
+        let response = client.get_sync_status(&Empty::default()).unwrap();
+        assert_eq!(response.get_highest_common_block(), 0);
+        assert_eq!(
+            response
+                .get_sources()
+                .iter()
+                .map(|status| (status.get_src_url(), status.get_has_synced()))
+                .collect::<Vec<_>>(),
+            vec![(url1.as_str(), false), (url2.as_str(), false)]
+        );
+
+        let blocks = setup_blocks();
+        let signing_key = Ed25519Pair::from_random(&mut rng);
+        for (block, _contents) in &blocks[1..4] {
+            let signature = BlockSignature::from_block_and_keypair(block, &signing_key).unwrap();
+            watcher_db
+                .add_block_signature(&url1, block.index, signature, "filename".to_string())
+                .unwrap();
+        }
+        watcher_db.update_last_synced(&url2, 2).unwrap();
+
+        let response = client.get_sync_status(&Empty::default()).unwrap();
+        assert_eq!(response.get_highest_common_block(), 2);
+        let sources = response.get_sources();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].get_src_url(), url1.as_str());
+        assert!(sources[0].get_has_synced());
+        assert_eq!(sources[0].get_last_synced_block_index(), 3);
+        assert_eq!(sources[1].get_src_url(), url2.as_str());
+        assert!(sources[1].get_has_synced());
+        assert_eq!(sources[1].get_last_synced_block_index(), 2);
+
+        let response = client.get_highest_common_block(&Empty::default()).unwrap();
+        assert_eq!(response.get_block_index(), 2);
+
+        let mut request = GetBlockTimestampRequest::new();
+        request.set_block_index(1);
+        let response = client.get_block_timestamp(&request).unwrap();
+        assert_eq!(
+            response.get_timestamp_result_code(),
+            GrpcTimestampResultCode::TimestampFound
+        );
+        assert_eq!(
+            response.get_timestamp(),
+            watcher_db.get_block_timestamp(1).unwrap().0
+        );
+
+        // Block 4 was not synced by all sources yet.
+        request.set_block_index(4);
+        let response = client.get_block_timestamp(&request).unwrap();
+        assert_eq!(
+            response.get_timestamp_result_code(),
+            GrpcTimestampResultCode::WatcherBehind
+        );
+        assert_eq!(response.get_timestamp(), u64::MAX);
+    }
+
+    #[test_with_logger]
+    fn test_get_verification_reports_for_signer(logger: Logger) {
+        let mut rng: Hc128Rng = Hc128Rng::from_seed([8u8; 32]);
+        let url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let url2 = Url::parse("http://www.my_url2.com").unwrap();
+        let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger.clone());
+        let (client, _server) = get_client_server(&watcher_db, &logger);
+
+        let signing_key_a = Ed25519Pair::from_random(&mut rng).public_key();
+        let signing_key_b = Ed25519Pair::from_random(&mut rng).public_key();
+        let verification_report_a = VerificationReport {
+            sig: VerificationSignature::from(vec![1; 32]),
+            chain: vec![vec![2; 16], vec![3; 32]],
+            http_body: "test body a".to_owned(),
+        };
+        let verification_report_b = VerificationReport {
+            sig: VerificationSignature::from(vec![10; 32]),
+            chain: vec![vec![20; 16], vec![30; 32]],
+            http_body: "test body b".to_owned(),
+        };
+
+        // url1 has a report for signer a, url2 has a report for signer b and no report
+        // for signer a.
+        watcher_db
+            .add_verification_report(&url1, &signing_key_a, &verification_report_a, &[])
+            .unwrap();
+        watcher_db
+            .add_verification_report(
+                &url2,
+                &signing_key_b,
+                &verification_report_b,
+                &[signing_key_a],
+            )
+            .unwrap();
+
+        let mut request = GetVerificationReportsForSignerRequest::new();
+        request.set_signer((&signing_key_a).into());
+        let response = client
+            .get_verification_reports_for_signer(&request)
+            .unwrap();
+        let reports = response.get_reports();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].get_src_url(), url1.as_str());
+        assert_eq!(
+            VerificationReport::from(reports[0].get_report()),
+            verification_report_a
+        );
+        assert_eq!(reports[1].get_src_url(), url2.as_str());
+        assert!(!reports[1].has_report());
+
+        request.set_signer((&signing_key_b).into());
+        let response = client
+            .get_verification_reports_for_signer(&request)
+            .unwrap();
+        let reports = response.get_reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].get_src_url(), url2.as_str());
+        assert_eq!(
+            VerificationReport::from(reports[0].get_report()),
+            verification_report_b
+        );
+
+        // An invalid signer is rejected.
+        let mut request = GetVerificationReportsForSignerRequest::new();
+        request.mut_signer().set_data(vec![1; 3]);
+        assert!(client
+            .get_verification_reports_for_signer(&request)
+            .is_err());
+    }
+}
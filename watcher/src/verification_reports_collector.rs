@@ -2,7 +2,10 @@
 
 //! Worker thread for collecting verification reports from nodes.
 
-use crate::{config::SourceConfig, watcher_db::WatcherDB};
+use crate::{
+    config::SourceConfig, enclave_policy::EnclavePolicy, metrics::WatcherMetrics,
+    watcher_db::WatcherDB,
+};
 use grpcio::Environment;
 use mc_attest_core::{VerificationReport, VerificationReportData};
 use mc_attest_verifier::Verifier;
@@ -132,9 +135,12 @@ pub struct VerificationReportsCollector<NC: NodeClient = ConsensusNodeClient> {
 
 impl<NC: NodeClient> VerificationReportsCollector<NC> {
     /// Create a new verification reports collector thread.
+    /// When an enclave policy is provided, every collected report is checked
+    /// against it and the result is persisted and exported as metrics.
     pub fn new(
         watcher_db: WatcherDB,
         sources: Vec<SourceConfig>,
+        enclave_policy: Option<EnclavePolicy>,
         poll_interval: Duration,
         logger: Logger,
     ) -> Self {
@@ -148,6 +154,7 @@ impl<NC: NodeClient> VerificationReportsCollector<NC> {
                     let thread = VerificationReportsCollectorThread::<NC>::new(
                         watcher_db,
                         sources,
+                        enclave_policy,
                         poll_interval,
                         logger,
                         thread_stop_requested,
@@ -183,10 +190,12 @@ impl<NC: NodeClient> Drop for VerificationReportsCollector<NC> {
 struct VerificationReportsCollectorThread<NC: NodeClient> {
     watcher_db: WatcherDB,
     sources: Vec<SourceConfig>,
+    enclave_policy: Option<EnclavePolicy>,
     poll_interval: Duration,
     logger: Logger,
     stop_requested: Arc<AtomicBool>,
     grpcio_env: Arc<Environment>,
+    metrics: WatcherMetrics,
     _nc: PhantomData<NC>,
 }
 
@@ -194,6 +203,7 @@ impl<NC: NodeClient> VerificationReportsCollectorThread<NC> {
     pub fn new(
         watcher_db: WatcherDB,
         sources: Vec<SourceConfig>,
+        enclave_policy: Option<EnclavePolicy>,
         poll_interval: Duration,
         logger: Logger,
         stop_requested: Arc<AtomicBool>,
@@ -207,16 +217,34 @@ impl<NC: NodeClient> VerificationReportsCollectorThread<NC> {
         Self {
             watcher_db,
             sources,
+            enclave_policy,
             poll_interval,
             logger,
             stop_requested,
             grpcio_env,
+            metrics: WatcherMetrics::new(),
             _nc: Default::default(),
         }
     }
 
     pub fn entrypoint(self) {
         log::info!(self.logger, "VerificationReportsCollectorThread starting");
+
+        // Export the policy checks persisted by previous runs, so that known
+        // violations remain visible until a newer report is collected.
+        if self.enclave_policy.is_some() {
+            match self.watcher_db.get_enclave_policy_checks() {
+                Ok(checks) => {
+                    for (src_url, check) in checks {
+                        self.metrics.set_enclave_policy_check(&src_url, &check);
+                    }
+                }
+                Err(err) => {
+                    log::error!(self.logger, "Failed getting enclave policy checks: {}", err);
+                }
+            }
+        }
+
         loop {
             if self.stop_requested.load(Ordering::SeqCst) {
                 log::debug!(
@@ -346,6 +374,11 @@ impl<NC: NodeClient> VerificationReportsCollectorThread<NC> {
                     tx_src_url,
                     hex::encode(verification_report_block_signer.to_bytes())
                 );
+                self.check_enclave_policy(
+                    tx_src_url,
+                    &verification_report_block_signer,
+                    verification_report,
+                );
             }
             Err(err) => {
                 log::error!(
@@ -359,12 +392,54 @@ impl<NC: NodeClient> VerificationReportsCollectorThread<NC> {
             }
         }
     }
+
+    fn check_enclave_policy(
+        &self,
+        tx_src_url: &Url,
+        block_signer: &Ed25519Public,
+        verification_report: &VerificationReport,
+    ) {
+        let enclave_policy = match self.enclave_policy.as_ref() {
+            Some(enclave_policy) => enclave_policy,
+            None => return,
+        };
+
+        let check = enclave_policy.check(block_signer, verification_report);
+        if check.is_compliant() {
+            log::info!(
+                self.logger,
+                "Verification report for {} complies with the enclave policy",
+                tx_src_url
+            );
+        } else {
+            log::crit!(
+                self.logger,
+                "Verification report for {} violates the enclave policy: {:?}: {}",
+                tx_src_url,
+                check.status(),
+                check.description
+            );
+        }
+
+        if let Err(err) = self.watcher_db.set_enclave_policy_check(tx_src_url, &check) {
+            log::error!(
+                self.logger,
+                "Failed writing enclave policy check for {} to database: {}",
+                tx_src_url,
+                err
+            );
+        }
+        self.metrics.set_enclave_policy_check(tx_src_url, &check);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::watcher_db::tests::{setup_blocks, setup_watcher_db};
+    use crate::{
+        enclave_policy::{AllowedEnclave, EnclavePolicyStatus},
+        watcher_db::tests::{setup_blocks, setup_watcher_db},
+    };
     use mc_attest_core::VerificationSignature;
     use mc_blockchain_types::BlockSignature;
     use mc_common::logger::{test_with_logger, Logger};
@@ -462,6 +537,7 @@ mod tests {
         let _verification_reports_collector = VerificationReportsCollector::<TestNodeClient>::new(
             watcher_db.clone(),
             sources,
+            None,
             Duration::from_millis(100),
             logger,
         );
@@ -665,4 +741,61 @@ mod tests {
             sleep(Duration::from_millis(100));
         }
     }
+
+    #[test_with_logger]
+    #[serial]
+    fn test_reports_are_checked_against_enclave_policy(logger: Logger) {
+        TestNodeClient::reset();
+
+        let tx_src_url1 = Url::parse("http://www.my_url1.com").unwrap();
+        let watcher_db = setup_watcher_db(&[tx_src_url1.clone()], logger.clone());
+        let blocks = setup_blocks();
+
+        let node1_url = ConsensusClientUri::from_str("mc://node1.test.com:443/").unwrap();
+        let sources = vec![SourceConfig::new(
+            tx_src_url1.to_string(),
+            Some(node1_url.clone()),
+            None,
+        )];
+        let enclave_policy = EnclavePolicy {
+            allowed_enclaves: vec![AllowedEnclave {
+                mr_enclave: hex::encode([1u8; 32]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let _verification_reports_collector = VerificationReportsCollector::<TestNodeClient>::new(
+            watcher_db.clone(),
+            sources,
+            Some(enclave_policy),
+            Duration::from_millis(100),
+            logger,
+        );
+
+        let signer1 = TestNodeClient::current_signer(&node1_url);
+        let signed_block_a1 =
+            BlockSignature::from_block_and_keypair(&blocks[0].0, &signer1).unwrap();
+        watcher_db
+            .add_block_signature(&tx_src_url1, 1, signed_block_a1, "00/00".to_owned())
+            .unwrap();
+
+        // The test reports are not signed by IAS, so they should get flagged.
+        let mut tries = 30;
+        loop {
+            let checks = watcher_db.get_enclave_policy_checks().unwrap();
+            if let Some(check) = checks.get(&tx_src_url1) {
+                assert_eq!(check.status(), Some(EnclavePolicyStatus::InvalidReport));
+                assert_eq!(check.block_signer, signer1.public_key());
+                assert!(!check.is_compliant());
+                break;
+            }
+
+            if tries == 0 {
+                panic!("enclave policy check not recorded");
+            }
+            tries -= 1;
+            sleep(Duration::from_millis(100));
+        }
+    }
 }
@@ -11,7 +11,7 @@ use mc_api::block_num_to_s3block_path;
 use mc_blockchain_types::{BlockData, BlockIndex};
 use mc_common::logger::{log, Logger};
 use mc_ledger_db::Ledger;
-use mc_ledger_sync::ReqwestTransactionsFetcher;
+use mc_ledger_sync::ObjectStoreTransactionsFetcher;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::{
     collections::HashMap,
@@ -28,14 +28,14 @@ use url::Url;
 pub struct Watcher {
     /// A transaction fetcher per watched URL.
     // The reason we keep a transaction fetcher per url has to do with the pre-fetching and caching
-    // mechanism implemented in ReqwestTransactionsFetcher: ReqwestTransactionsFetcher will try and
-    // fetch the large merged-blocks and then try to hand out results from its cache. However, it
-    // is oblivious as to which URL got used to cache the blocks. This behavior is suitable for
-    // LedgerSyncService but in the watcher we want to ensure the blocks are fetched from a
-    // specific URL. If we want to use the cache mechanism (which we do, since it cuts down sync
-    // time significantlly) then the workaround is to have a ReqwestTransactionsFetcher that only
-    // has a single source URL.
-    transactions_fetcher_by_url: Arc<HashMap<Url, ReqwestTransactionsFetcher>>,
+    // mechanism implemented in ObjectStoreTransactionsFetcher: ObjectStoreTransactionsFetcher will
+    // try and fetch the large merged-blocks and then try to hand out results from its cache.
+    // However, it is oblivious as to which URL got used to cache the blocks. This behavior is
+    // suitable for LedgerSyncService but in the watcher we want to ensure the blocks are fetched
+    // from a specific URL. If we want to use the cache mechanism (which we do, since it cuts down
+    // sync time significantlly) then the workaround is to have an ObjectStoreTransactionsFetcher
+    // that only has a single source URL.
+    transactions_fetcher_by_url: Arc<HashMap<Url, ObjectStoreTransactionsFetcher>>,
     watcher_db: WatcherDB,
     store_block_data: bool,
     logger: Logger,
@@ -77,7 +77,7 @@ impl Watcher {
                 .map(|source_url| {
                     Ok((
                         source_url.clone(),
-                        ReqwestTransactionsFetcher::new(
+                        ObjectStoreTransactionsFetcher::new(
                             vec![source_url.to_string()],
                             logger.clone(),
                         )?,
@@ -193,6 +193,19 @@ impl Watcher {
                             src_url,
                             block_index
                         );
+                        let fork_alerts =
+                            self.watcher_db.check_block_for_forks(src_url, block_data)?;
+                        for alert in &fork_alerts {
+                            log::crit!(
+                                self.logger,
+                                "Fork alert ({:?}) for block {}: {}",
+                                alert.kind(),
+                                alert.block_index,
+                                alert.description
+                            );
+                        }
+                        self.metrics.inc_fork_alerts(fork_alerts.len());
+
                         if self.store_block_data {
                             match self.watcher_db.add_block_data(src_url, block_data) {
                                 Ok(()) => {}
@@ -266,7 +279,7 @@ impl Watcher {
 /// return the result.
 fn parallel_fetch_blocks(
     url_to_block_index: HashMap<Url, BlockIndex>,
-    transactions_fetcher_by_url: Arc<HashMap<Url, ReqwestTransactionsFetcher>>,
+    transactions_fetcher_by_url: Arc<HashMap<Url, ObjectStoreTransactionsFetcher>>,
 ) -> Result<HashMap<Url, (u64, Result<BlockData, WatcherError>)>, WatcherError> {
     Ok(url_to_block_index
         .into_par_iter()
@@ -2,7 +2,12 @@
 
 //! The watcher database
 
-use crate::{block_data_store::BlockDataStore, error::WatcherDBError};
+use crate::{
+    block_data_store::BlockDataStore,
+    enclave_policy::EnclavePolicyCheck,
+    error::WatcherDBError,
+    fork_alert_store::{ForkAlert, ForkAlertStore},
+};
 
 use mc_attest_core::VerificationReport;
 use mc_blockchain_types::{BlockData, BlockIndex, BlockSignature};
@@ -42,7 +47,7 @@ impl MetadataStoreSettings for WatcherDbMetadataStoreSettings {
     // db opening for any incompatibilities, and either refuse to open or
     // perform a migration.
     #[allow(clippy::unreadable_literal)]
-    const LATEST_VERSION: u64 = 20210127;
+    const LATEST_VERSION: u64 = 20221020;
 
     /// The current crate version that manages the database.
     const CRATE_VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -65,6 +70,9 @@ pub const VERIFICATION_REPORTS_POLL_QUEUE_DB_NAME: &str =
 /// Verification reports by report hash database name.
 pub const VERIFICATION_REPORTS_BY_HASH_DB_NAME: &str = "watcher_db:verification_reports_by_hash";
 
+/// Enclave policy checks database name.
+pub const ENCLAVE_POLICY_CHECKS_DB_NAME: &str = "watcher_db:enclave_policy_checks";
+
 /// Last synced archive blocks database name.
 pub const LAST_SYNCED_DB_NAME: &str = "watcher_db:last_synced";
 
@@ -116,6 +124,9 @@ pub struct WatcherDB {
     /// BlockData store.
     block_data_store: BlockDataStore,
 
+    /// Fork alert store.
+    fork_alert_store: ForkAlertStore,
+
     /// Signature store.
     block_signatures: Database,
 
@@ -142,6 +153,12 @@ pub struct WatcherDB {
     /// removed from the queue.
     verification_reports_poll_queue: Database,
 
+    /// Enclave policy checks database.
+    /// This database holds a map of tx source url -> the result of checking
+    /// the latest verification report collected for that url against the
+    /// configured enclave policy.
+    enclave_policy_checks: Database,
+
     /// Last synced archive block.
     last_synced: Database,
 
@@ -162,7 +179,7 @@ impl WatcherDB {
     pub fn open_ro(path: &Path, logger: Logger) -> Result<Self, WatcherDBError> {
         let env = Arc::new(
             Environment::new()
-                .set_max_dbs(10)
+                .set_max_dbs(20)
                 .set_map_size(MAX_LMDB_FILE_SIZE)
                 // TODO - needed because currently our test cloud machines have slow disks.
                 .set_flags(EnvironmentFlags::NO_SYNC)
@@ -185,18 +202,22 @@ impl WatcherDB {
             env.open_db(Some(VERIFICATION_REPORTS_BY_HASH_DB_NAME))?;
         let verification_reports_poll_queue =
             env.open_db(Some(VERIFICATION_REPORTS_POLL_QUEUE_DB_NAME))?;
+        let enclave_policy_checks = env.open_db(Some(ENCLAVE_POLICY_CHECKS_DB_NAME))?;
         let last_synced = env.open_db(Some(LAST_SYNCED_DB_NAME))?;
         let config = env.open_db(Some(CONFIG_DB_NAME))?;
 
         let block_data_store = BlockDataStore::new(env.clone(), logger.clone())?;
+        let fork_alert_store = ForkAlertStore::new(env.clone(), logger.clone())?;
 
         Ok(WatcherDB {
             env,
             block_data_store,
+            fork_alert_store,
             block_signatures,
             verification_reports_by_signer,
             verification_reports_by_hash,
             verification_reports_poll_queue,
+            enclave_policy_checks,
             last_synced,
             config,
             write_allowed: false,
@@ -220,7 +241,7 @@ impl WatcherDB {
     pub fn create(path: &Path) -> Result<(), WatcherDBError> {
         let env = Arc::new(
             Environment::new()
-                .set_max_dbs(10)
+                .set_max_dbs(20)
                 .set_map_size(MAX_LMDB_FILE_SIZE)
                 .open(path)?,
         );
@@ -240,10 +261,12 @@ impl WatcherDB {
             Some(VERIFICATION_REPORTS_POLL_QUEUE_DB_NAME),
             DatabaseFlags::DUP_SORT,
         )?;
+        env.create_db(Some(ENCLAVE_POLICY_CHECKS_DB_NAME), DatabaseFlags::empty())?;
         env.create_db(Some(LAST_SYNCED_DB_NAME), DatabaseFlags::empty())?;
         env.create_db(Some(CONFIG_DB_NAME), DatabaseFlags::DUP_SORT)?;
 
-        BlockDataStore::create(env)?;
+        BlockDataStore::create(env.clone())?;
+        ForkAlertStore::create(env)?;
 
         Ok(())
     }
@@ -634,6 +657,42 @@ impl WatcherDB {
             .get_block_data_map(&db_txn, block_index)
     }
 
+    /// Compare a block fetched from a given tx source url with the blocks
+    /// served by all sources so far, and record it for future comparisons.
+    /// Returns any newly raised fork alerts.
+    pub fn check_block_for_forks(
+        &self,
+        src_url: &Url,
+        block_data: &BlockData,
+    ) -> Result<Vec<ForkAlert>, WatcherDBError> {
+        if !self.write_allowed {
+            return Err(WatcherDBError::ReadOnly);
+        }
+
+        let mut db_txn = self.env.begin_rw_txn()?;
+        let block_signatures = self.get_block_signatures_impl(&db_txn, block_data.block().index)?;
+        let alerts = self.fork_alert_store.check_and_add_block(
+            &mut db_txn,
+            src_url,
+            block_data,
+            &block_signatures,
+        )?;
+        db_txn.commit()?;
+        Ok(alerts)
+    }
+
+    /// Get fork alerts raised for blocks at or above a given block index,
+    /// ordered by block index.
+    pub fn get_fork_alerts(
+        &self,
+        start_block_index: BlockIndex,
+        limit: Option<usize>,
+    ) -> Result<Vec<ForkAlert>, WatcherDBError> {
+        let db_txn = self.env.begin_ro_txn()?;
+        self.fork_alert_store
+            .get_fork_alerts(&db_txn, start_block_index, limit)
+    }
+
     /// Record a verification report for a given source URL, that is associated
     /// with a specific block signer.
     /// Additionally, record no report for an optional list of expected block
@@ -988,6 +1047,46 @@ impl WatcherDB {
         }
     }
 
+    /// Record the result of checking the latest verification report of a given
+    /// source URL against the enclave policy. This replaces any previously
+    /// recorded result for the same URL.
+    pub fn set_enclave_policy_check(
+        &self,
+        src_url: &Url,
+        check: &EnclavePolicyCheck,
+    ) -> Result<(), WatcherDBError> {
+        if !self.write_allowed {
+            return Err(WatcherDBError::ReadOnly);
+        }
+
+        let mut db_txn = self.env.begin_rw_txn()?;
+        db_txn.put(
+            self.enclave_policy_checks,
+            &src_url.as_str().as_bytes(),
+            &encode(check),
+            WriteFlags::empty(),
+        )?;
+        db_txn.commit()?;
+        Ok(())
+    }
+
+    /// Get the latest enclave policy check result of every source URL that had
+    /// one recorded.
+    pub fn get_enclave_policy_checks(
+        &self,
+    ) -> Result<HashMap<Url, EnclavePolicyCheck>, WatcherDBError> {
+        let db_txn = self.env.begin_ro_txn()?;
+        let mut cursor = db_txn.open_ro_cursor(self.enclave_policy_checks)?;
+
+        cursor
+            .iter_start()
+            .map(|result| {
+                let (key_bytes, value_bytes) = result?;
+                Ok((bytes_to_url(key_bytes)?, decode(value_bytes)?))
+            })
+            .collect()
+    }
+
     /// Remove all the data associated with a given source url.
     pub fn remove_all_for_source_url(&self, src_url: &Url) -> Result<(), WatcherDBError> {
         if !self.write_allowed {
@@ -1007,6 +1106,13 @@ impl WatcherDB {
             last_synced_block_index,
         )?;
 
+        // Remove the blocks used for fork detection.
+        self.fork_alert_store.remove_all_for_source_url(
+            &mut db_txn,
+            src_url,
+            last_synced_block_index,
+        )?;
+
         // Remove any block signatures associated with this source URL.
         for block_index in 0..=last_synced_block_index {
             let block_signatures = self.get_block_signatures_impl(&db_txn, block_index)?;
@@ -1025,6 +1131,16 @@ impl WatcherDB {
             Err(err) => Err(err)?,
         };
 
+        // Remove enclave policy check.
+        match db_txn.del(
+            self.enclave_policy_checks,
+            &src_url.as_str().as_bytes(),
+            None,
+        ) {
+            Ok(()) | Err(lmdb::Error::NotFound) => {}
+            Err(err) => Err(err)?,
+        };
+
         // Remove verification reports.
         let signer_key_size = <Ed25519Public as ReprBytes>::Size::USIZE;
         let mut cursor = db_txn.open_rw_cursor(self.verification_reports_by_signer)?;
@@ -1087,6 +1203,7 @@ fn bytes_to_url(bytes: &[u8]) -> Result<Url, WatcherDBError> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::enclave_policy::EnclavePolicyStatus;
     use mc_account_keys::AccountKey;
     use mc_attest_core::VerificationSignature;
     use mc_blockchain_types::{Block, BlockContents, BlockVersion};
@@ -2011,4 +2128,67 @@ pub mod tests {
             );
         })
     }
+
+    // Enclave policy checks should be stored per url, and replaced by newer ones.
+    #[test_with_logger]
+    fn test_enclave_policy_checks(logger: Logger) {
+        run_with_one_seed(|mut rng| {
+            let url1 = Url::parse("http://www.my_url1.com").unwrap();
+            let url2 = Url::parse("http://www.my_url2.com").unwrap();
+            let watcher_db = setup_watcher_db(&[url1.clone(), url2.clone()], logger.clone());
+
+            assert_eq!(
+                watcher_db.get_enclave_policy_checks().unwrap(),
+                HashMap::default()
+            );
+
+            let check_a = EnclavePolicyCheck {
+                status: EnclavePolicyStatus::Compliant as i32,
+                block_signer: Ed25519Pair::from_random(&mut rng).public_key(),
+                mr_enclave: vec![1; 32],
+                mr_signer: vec![2; 32],
+                description: "a".to_owned(),
+            };
+            let check_b = EnclavePolicyCheck {
+                status: EnclavePolicyStatus::MeasurementNotAllowed as i32,
+                block_signer: Ed25519Pair::from_random(&mut rng).public_key(),
+                mr_enclave: vec![3; 32],
+                mr_signer: vec![2; 32],
+                description: "b".to_owned(),
+            };
+
+            watcher_db
+                .set_enclave_policy_check(&url1, &check_a)
+                .unwrap();
+            watcher_db
+                .set_enclave_policy_check(&url2, &check_a)
+                .unwrap();
+            assert_eq!(
+                watcher_db.get_enclave_policy_checks().unwrap(),
+                HashMap::from_iter(vec![
+                    (url1.clone(), check_a.clone()),
+                    (url2.clone(), check_a.clone()),
+                ])
+            );
+
+            // A newer check replaces the previous one.
+            watcher_db
+                .set_enclave_policy_check(&url1, &check_b)
+                .unwrap();
+            assert_eq!(
+                watcher_db.get_enclave_policy_checks().unwrap(),
+                HashMap::from_iter(vec![
+                    (url1.clone(), check_b),
+                    (url2.clone(), check_a.clone()),
+                ])
+            );
+
+            // Removing a url removes its check.
+            watcher_db.remove_all_for_source_url(&url1).unwrap();
+            assert_eq!(
+                watcher_db.get_enclave_policy_checks().unwrap(),
+                HashMap::from_iter(vec![(url2, check_a)])
+            );
+        })
+    }
 }